@@ -1,4 +1,4 @@
-/**
+/*!
  * CLIアプリケーション
  * 
  * MCPサーバーとして動作するか、直接CLIコマンドとして実行できる
@@ -10,7 +10,16 @@
  * - summarize: 要約を生成
  * - wiki: Wikiサイトを生成
  * - slides: スライドを生成
+ * - poster: アーキテクチャを1枚にまとめた印刷用のポスター（HTML、`--pdf` でPDFも）を生成
  * - publish: GitHub Pagesに公開
+ * - search: ソースまたは生成済みドキュメントを検索
+ * - ask: インデックスに対して質問し、引用付きの回答を表示（質問を省略すると対話モード）
+ * - notion-export: 生成済みWikiをNotionにエクスポート
+ * - export-graph: モジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONのファイルに出力（yEd・Gephi・ブラウザでの探索用）
+ * - wiki・slidesの `--dry-run`: 書き込まずに生成予定のファイルツリーを表示
+ * - preview: インデックス化・Wikiまたはスライドの生成・ローカルサーバーでの配信をまとめて行い、ブラウザで開く（`--skip-index` で保存済みのインデックスを再利用、previewモジュール）
+ * - doctor: 外部ツール（mdbook, marp, chromium）の有無を確認（ない場合はMarkdownのみを生成し、スキップした成果物を表示）
+ * - index・wiki・slides・poster・ask・build-allの最後に、解析・要約・図・ビルド中の警告（スキップしたファイルなど）をまとめて表示
  * 
  * 制限事項:
  * - MCPモードでは標準入出力でJSON-RPC通信
@@ -19,16 +28,35 @@
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
 use tracing::{info, Level};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 use config::Config;
+use diagrammer::Diagrammer;
 use mcp_server::McpServer;
-use analyzer_core::Analyzer;
+use analyzer_core::{search_docs, Analyzer, Diagnostics, Executor, Index, Redaction, SearchFilter, SEARCH_KINDS};
 use site_mdbook::MdBookBuilder;
 use slides::SlideBuilder;
-use publisher_ghpages::Publisher;
+use poster::PosterBuilder;
+use summarizer::{check_tools, PlannedFile, SkippedArtifact, Summarizer};
+use publisher_ghpages::{BuildSummary, Notifier, OptimizeReport, PublishStatus, Publisher};
+use publisher_notion::NotionPublisher;
+
+mod preview;
+
+/// Wikiの目次セクション
+const WIKI_SECTIONS: &[&str] = &["overview", "architecture", "metrics", "hotspots", "timeline", "tests", "unused", "debt", "modules", "flows", "deploy", "faq"];
+/// build-allで生成するスライドのセクション
+const SLIDE_SECTIONS: &[&str] = &["overview", "architecture", "modules", "flows", "deploy"];
+
+/// `--no-touch-repo` の指定（設定ファイルの `security.read-only-repo` より優先して有効にする）
+static NO_TOUCH_REPO: AtomicBool = AtomicBool::new(false);
+/// `--lang` の指定（設定ファイルの `project.language`・`slides.language` より優先する）
+static LANG: OnceLock<String> = OnceLock::new();
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -62,35 +90,55 @@ async fn run_mcp_server() -> Result<()> {
 /// CLIコマンドを実行
 async fn run_cli() -> Result<()> {
     let cli = Cli::parse();
+    NO_TOUCH_REPO.store(cli.no_touch_repo, Ordering::Relaxed);
+    if let Some(lang) = cli.lang {
+        let _ = LANG.set(lang);
+    }
 
     match cli.command {
-        Commands::Index { repo, config } => {
-            cmd_index(&repo, config.as_deref()).await?;
+        Commands::Index { repo, out, config } => {
+            cmd_index(&repo, out.as_deref(), config.as_deref()).await?;
         }
         Commands::Summarize { scope, target, style } => {
             cmd_summarize(&scope, &target, &style).await?;
         }
-        Commands::Wiki { out, config } => {
-            cmd_wiki(out.as_deref(), config.as_deref()).await?;
+        Commands::Wiki { out, path, config, dry_run, resume } => {
+            cmd_wiki(out.as_deref(), path.as_deref(), config.as_deref(), dry_run, resume).await?;
         }
         Commands::Slides {
             flavor,
             out,
             sections,
             export,
+            from_wiki,
+            preset,
+            path,
+            language,
             config,
+            dry_run,
         } => {
             let sections_vec: Vec<String> = sections.split(',').map(|s| s.trim().to_string()).collect();
             let export_vec: Vec<String> = export.split(',').map(|s| s.trim().to_string()).collect();
+            let language_vec: Vec<String> = language
+                .map(|l| l.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+                .unwrap_or_default();
             cmd_slides(
                 &flavor,
                 out.as_deref(),
                 &sections_vec,
                 &export_vec,
+                from_wiki.as_deref(),
+                &preset,
+                path.as_deref(),
+                &language_vec,
                 config.as_deref(),
+                dry_run,
             )
             .await?;
         }
+        Commands::Poster { out, pdf, path, config } => {
+            cmd_poster(out.as_deref(), pdf, path.as_deref(), config.as_deref()).await?;
+        }
         Commands::Publish {
             mode,
             site_dir,
@@ -110,23 +158,70 @@ async fn run_cli() -> Result<()> {
         Commands::BuildAll { config } => {
             cmd_build_all(config.as_deref()).await?;
         }
+        Commands::Search { q, k, search_in, kind, semantic, language, path_glob, module, offset, config } => {
+            let filter = SearchFilter { language, path_glob, module, offset };
+            cmd_search(&q, k, &search_in, &kind, semantic, &filter, config.as_deref()).await?;
+        }
+        Commands::Ask { question, index_file, config } => {
+            cmd_ask(question.as_deref(), index_file.as_deref(), config.as_deref()).await?;
+        }
+        Commands::Diff { old, new, out, config } => {
+            cmd_diff(&old, new.as_deref(), out.as_deref(), config.as_deref()).await?;
+        }
+        Commands::NotionExport { site_dir, config } => {
+            cmd_notion_export(site_dir.as_deref(), config.as_deref()).await?;
+        }
+        Commands::ExportGraph { out, path, config } => {
+            cmd_export_graph(out.as_deref(), path.as_deref(), config.as_deref()).await?;
+        }
+        Commands::Preview { slides, wiki: _, skip_index, index_file, port, no_open, config } => {
+            cmd_preview(slides, skip_index, &index_file, port, no_open, config.as_deref()).await?;
+        }
+        Commands::Doctor => {
+            cmd_doctor();
+        }
     }
 
     Ok(())
 }
 
+/// 設定ファイルを読み込み、`--no-touch-repo`・`--lang` の指定を反映する
+fn load_config(config_path: Option<&str>) -> Result<Config> {
+    let mut config = Config::load(config_path)?;
+    if let Some(lang) = LANG.get() {
+        config.override_language(lang)?;
+    }
+    if NO_TOUCH_REPO.load(Ordering::Relaxed) {
+        config.security.read_only_repo = true;
+        config.validate()?;
+    }
+    Ok(config)
+}
+
 /// indexコマンドを実行
-async fn cmd_index(repo: &str, config_path: Option<&str>) -> Result<()> {
+async fn cmd_index(repo: &str, out: Option<&str>, config_path: Option<&str>) -> Result<()> {
     info!("リポジトリをインデックス化: {}", repo);
 
-    let config = Config::load(config_path)?;
-    let analyzer = Analyzer::new(config.clone());
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+    let analyzer = Analyzer::new(config.clone()).with_diagnostics(diagnostics.clone());
     let index = analyzer.analyze_repo(repo, &config).await?;
 
     println!("インデックス化完了:");
     println!("  ファイル数: {}", index.stats.files);
     println!("  言語数: {}", index.stats.languages.len());
     println!("  モジュール数: {}", index.stats.modules);
+    if !index.stats.skipped.is_empty() {
+        println!("  除外したファイル数: {}（バイナリ・読み込めないファイル）", index.stats.skipped.len());
+    }
+
+    if let Some(out) = out {
+        let out = config.security.output_path(Path::new(repo), Path::new(out));
+        index.save_with(&out, &config.index.content)?;
+        println!("インデックスを保存しました: {}", out.display());
+    }
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
 
     Ok(())
 }
@@ -143,39 +238,186 @@ async fn cmd_summarize(scope: &str, target: &str, style: &str) -> Result<()> {
 }
 
 /// wikiコマンドを実行
-async fn cmd_wiki(out: Option<&str>, config_path: Option<&str>) -> Result<()> {
+async fn cmd_wiki(out: Option<&str>, path: Option<&str>, config_path: Option<&str>, dry_run: bool, resume: bool) -> Result<()> {
     let out_dir = out.unwrap_or("./out/wiki");
-    info!("Wiki生成: out_dir={}", out_dir);
+    info!("Wiki生成: out_dir={}, path={:?}", out_dir, path);
 
-    let _config = Config::load(config_path)?;
-    
-    // インデックスを読み込む（簡易実装）
-    // 実際の実装では、インデックスを保存・読み込む機能が必要
-    eprintln!("Wiki生成機能は実装中です（インデックスが必要です）");
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+    let index = analyze_scope(&config, path, &diagnostics).await?;
+    let toc: Vec<String> = WIKI_SECTIONS.iter().map(|s| s.to_string()).collect();
+    let plan_root = config.output_path(out_dir);
+    let builder = MdBookBuilder::new(config).with_diagnostics(diagnostics.clone()).with_resume(resume);
+
+    if dry_run {
+        let files = builder.plan_wiki(&index, true, &toc).await?;
+        print!("{}", render_plan(&plan_root.to_string_lossy(), &files));
+        return Ok(());
+    }
+
+    let result = builder
+        .build_wiki(&index, out_dir, true, &toc)
+        .await
+        .map_err(|e| e.context("Wikiの生成が中断しました（生成済みのページは `wiki --resume` で再利用できます）"))?;
+    match (result.resumed, result.resumed_modules) {
+        (0, 0) => println!("Wiki生成完了: {}ページ", result.pages),
+        (pages, modules) => println!(
+            "Wiki生成完了: {}ページ（前回の途中経過から{}ページ・{}モジュールの解説を再利用）",
+            result.pages, pages, modules
+        ),
+    }
+    for badge in &result.badges {
+        println!("  バッジ: {}", badge.display());
+    }
+    print_skipped(&result.skipped);
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
 
     Ok(())
 }
 
 /// slidesコマンドを実行
+#[allow(clippy::too_many_arguments)]
 async fn cmd_slides(
     flavor: &str,
     out: Option<&str>,
-    _sections: &[String],
-    _export: &[String],
+    sections: &[String],
+    export: &[String],
+    from_wiki: Option<&str>,
+    preset: &str,
+    path: Option<&str>,
+    languages: &[String],
     config_path: Option<&str>,
+    dry_run: bool,
 ) -> Result<()> {
     let out_dir = out.unwrap_or("./out/slides");
     info!("スライド生成: flavor={}, out_dir={}", flavor, out_dir);
 
-    let _config = Config::load(config_path)?;
-    
-    // インデックスを読み込む（簡易実装）
-    // 実際の実装では、インデックスを保存・読み込む機能が必要
-    eprintln!("スライド生成機能は実装中です（インデックスが必要です）");
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+    let decks = language_decks(out_dir, languages, &config);
+
+    if dry_run {
+        let slide_builder = SlideBuilder::new(config.clone());
+        let files = match from_wiki {
+            Some(wiki_dir) => slide_builder.plan_slides_from_wiki(wiki_dir, preset, flavor, export)?,
+            None => {
+                let index = analyze_scope(&config, path, &diagnostics).await?;
+                for (language, deck_dir) in &decks {
+                    let files = slide_builder.plan_slides(&index, flavor, sections, export, language).await?;
+                    print!("{}", render_plan(&config.output_path(deck_dir).to_string_lossy(), &files));
+                }
+                return Ok(());
+            }
+        };
+        print!("{}", render_plan(&config.output_path(out_dir).to_string_lossy(), &files));
+        return Ok(());
+    }
+
+    if let Some(wiki_dir) = from_wiki {
+        let slide_builder = SlideBuilder::new(config).with_diagnostics(diagnostics.clone());
+        let result = slide_builder
+            .build_slides_from_wiki(wiki_dir, preset, flavor, out_dir, export)
+            .await?;
+        println!("スライド生成完了: {}ファイル", result.files.len());
+        print_skipped(&result.skipped);
+        print_diagnostics(&diagnostics);
+        return Ok(());
+    }
+
+    // 同じインデックスから言語ごとのスライドを生成する
+    let index = analyze_scope(&config, path, &diagnostics).await?;
+    let slide_builder = SlideBuilder::new(config).with_diagnostics(diagnostics.clone());
+    for (language, deck_dir) in &decks {
+        let result = slide_builder.build_slides(&index, flavor, deck_dir, sections, export, language).await?;
+        println!("スライド生成完了（{}）: {}ファイル", language, result.files.len());
+        print_skipped(&result.skipped);
+    }
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
 
     Ok(())
 }
 
+/// posterコマンドを実行
+async fn cmd_poster(out: Option<&str>, pdf: bool, path: Option<&str>, config_path: Option<&str>) -> Result<()> {
+    let config = load_config(config_path)?;
+    let out_dir = out.map(PathBuf::from).unwrap_or_else(|| config.poster.out_dir.clone());
+    info!("ポスター生成: out_dir={:?}, pdf={}", out_dir, pdf);
+
+    let diagnostics = Diagnostics::new();
+    let index = analyze_scope(&config, path, &diagnostics).await?;
+    let result = PosterBuilder::new(config).with_diagnostics(diagnostics.clone()).build(&index, &out_dir, pdf)?;
+    println!("ポスター生成完了: {}ファイル", result.files.len());
+    for file in &result.files {
+        println!("  {}", file.display());
+    }
+    print_skipped(&result.skipped);
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
+
+    Ok(())
+}
+
+/// export-graphコマンドを実行
+async fn cmd_export_graph(out: Option<&str>, path: Option<&str>, config_path: Option<&str>) -> Result<()> {
+    let config = load_config(config_path)?;
+    let out_dir = config.output_path(out.unwrap_or(diagrammer::DEFAULT_EXPORT_DIR));
+    info!("グラフのエクスポート: out_dir={:?}", out_dir);
+
+    let diagnostics = Diagnostics::new();
+    let index = analyze_scope(&config, path, &diagnostics).await?;
+    let files = Diagrammer::new(config).write_graph_exports(&index, &out_dir)?;
+    println!("グラフのエクスポート完了: {}ファイル", files.len());
+    for file in &files {
+        println!("  {}", file.display());
+    }
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
+
+    Ok(())
+}
+
+/// スライドの言語ごとの出力先
+///
+/// # 引数
+/// * `out_dir` - 出力ディレクトリ
+/// * `languages` - `--language` で指定した言語（空の場合は `slides.language`、なければ `project.language`）
+/// * `config` - 設定
+///
+/// # 戻り値
+/// * `Vec<(String, String)>` - 言語と出力ディレクトリ（複数の言語を指定した場合は `<out_dir>/<言語>`）
+fn language_decks(out_dir: &str, languages: &[String], config: &Config) -> Vec<(String, String)> {
+    match languages {
+        [] => vec![(config.slides_language().to_string(), out_dir.to_string())],
+        [language] => vec![(language.clone(), out_dir.to_string())],
+        _ => languages
+            .iter()
+            .map(|language| (language.clone(), Path::new(out_dir).join(language).to_string_lossy().into_owned()))
+            .collect(),
+    }
+}
+
+/// リポジトリを解析し、パスの指定があればその配下に絞り込む
+///
+/// # 引数
+/// * `config` - 設定
+/// * `path` - 絞り込むディレクトリ（リポジトリルートからの相対パス、Noneの場合はリポジトリ全体）
+/// * `diagnostics` - 解析中の警告の記録先
+///
+/// # 戻り値
+/// * `Result<Index>` - インデックス、またはエラー
+async fn analyze_scope(config: &Config, path: Option<&str>, diagnostics: &Diagnostics) -> Result<Index> {
+    let index = Analyzer::new(config.clone())
+        .with_diagnostics(diagnostics.clone())
+        .analyze_repo(&config.project.repo_path, config)
+        .await?;
+    match path {
+        Some(path) => index.subset(Path::new(path)),
+        None => Ok(index),
+    }
+}
+
 /// publishコマンドを実行
 async fn cmd_publish(
     mode: &str,
@@ -186,74 +428,332 @@ async fn cmd_publish(
 ) -> Result<()> {
     info!("GitHub Pages公開: mode={}", mode);
 
-    let config = Config::load::<PathBuf>(None)?;
-    let publisher = Publisher::new(config);
+    let config = load_config(None)?;
+    let publisher = Publisher::new(config.clone());
     let result = publisher
         .publish(mode, site_dir, slides_dir, repo_root, branch)
         .await?;
 
-    println!("公開完了: {}", result.hint);
+    match result.status {
+        PublishStatus::Published => println!("公開完了: {}", result.hint),
+        PublishStatus::Unchanged => println!("{}", result.hint),
+    }
+    if !result.page_changes.is_empty() {
+        let count = |kind: &str| result.page_changes.iter().filter(|c| c.kind == kind).count();
+        println!(
+            "今回の更新内容: 追加{}・更新{}・削除{}ページ（whats-new.html）",
+            count("added"),
+            count("modified"),
+            count("removed")
+        );
+    }
+    print_optimization(result.optimization.as_ref());
+
+    Notifier::new(config.clone())
+        .notify(&BuildSummary {
+            event: "publish".to_string(),
+            project: config.project.name.clone(),
+            ok: result.ok,
+            url: result.url.clone(),
+            message: result.hint.clone(),
+            ..Default::default()
+        })
+        .await;
+
+    Ok(())
+}
+
+/// notion-exportコマンドを実行
+async fn cmd_notion_export(site_dir: Option<&str>, config_path: Option<&str>) -> Result<()> {
+    let config = load_config(config_path)?;
+    let site_dir = config
+        .output_path(site_dir.map(PathBuf::from).unwrap_or_else(|| config.site.out_dir.clone()))
+        .to_string_lossy()
+        .to_string();
+    info!("Notionエクスポート: site_dir={}", site_dir);
+
+    let publisher = NotionPublisher::new(config);
+    let result = publisher.export_wiki(&site_dir).await?;
+
+    println!("Notionエクスポート完了: {}ページ", result.pages);
+    if let Some(url) = result.url {
+        println!("URL: {}", url);
+    }
+
+    Ok(())
+}
+
+/// searchコマンドを実行
+async fn cmd_search(
+    q: &str,
+    k: usize,
+    search_in: &str,
+    kind: &str,
+    semantic: bool,
+    filter: &SearchFilter,
+    config_path: Option<&str>,
+) -> Result<()> {
+    info!("検索: q={}, in={}, kind={}, semantic={}", q, search_in, kind, semantic);
+
+    let config = load_config(config_path)?;
+    let mut hits = Vec::new();
+
+    match search_in {
+        "source" | "docs" | "all" => {}
+        _ => return Err(anyhow::anyhow!("不明な検索対象: {}（source|docs|all）", search_in)),
+    }
+    if semantic && search_in != "source" {
+        return Err(anyhow::anyhow!("--semanticはソースの検索（--in source）のみ対応しています"));
+    }
+    if !SEARCH_KINDS.contains(&kind) {
+        return Err(anyhow::anyhow!("不明な検索の種類: {}（{}）", kind, SEARCH_KINDS.join("|")));
+    }
+    if kind != "text" && (semantic || search_in == "docs") {
+        return Err(anyhow::anyhow!("--kind {}はソースの検索（--in source|all、--semanticなし）のみ対応しています", kind));
+    }
+    if (filter.language.is_some() || filter.path_glob.is_some() || filter.module.is_some()) && (semantic || search_in != "source") {
+        return Err(anyhow::anyhow!("--language・--path-glob・--moduleはソースの検索（--in source、--semanticなし）のみ対応しています"));
+    }
+
+    // ソース・ドキュメントの結果を統合してから読み飛ばすため、それぞれ先頭からoffset + k件を取得する
+    let fetch = filter.offset + k;
+    if search_in == "source" || search_in == "all" {
+        let analyzer = Analyzer::new(config.clone());
+        let index = analyzer.analyze_repo(&config.project.repo_path, &config).await?;
+        if semantic {
+            hits.extend(index.semantic_search(q, fetch).await?);
+        } else {
+            hits.extend(index.search(q, fetch, kind, &SearchFilter { offset: 0, ..filter.clone() }).await?);
+        }
+    }
+
+    if (search_in == "docs" || search_in == "all") && kind == "text" {
+        let doc_dirs = vec![config.site.out_dir.clone(), config.slides.out_dir.clone()];
+        hits.extend(search_docs(&doc_dirs, &config.site.layout.build_dir, q, fetch).await?);
+    }
+
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    hits.drain(..filter.offset.min(hits.len()));
+    hits.truncate(k);
+
+    if hits.is_empty() {
+        println!("一致する結果はありませんでした");
+    }
+    for hit in &hits {
+        match (hit.start_line, hit.end_line, hit.matches.first()) {
+            (Some(start), Some(end), _) => println!("{}:{}-{} (score: {:.2})", hit.path, start, end, hit.score),
+            (_, _, Some(m)) => println!("{}:{}:{} (score: {:.2})", hit.path, m.line, m.column, hit.score),
+            _ => println!("{} (score: {:.2})", hit.path, hit.score),
+        }
+        if let Some(module) = &hit.module {
+            println!("  モジュール: {}", module);
+        }
+        if !hit.matches.is_empty() {
+            let positions: Vec<String> = hit.matches.iter().map(|m| format!("{}:{}", m.line, m.column)).collect();
+            println!("  一致: {}", positions.join(", "));
+        }
+        println!("  {}", hit.excerpt.replace('\n', " "));
+    }
+
+    Ok(())
+}
+
+/// askコマンドを実行
+/// 
+/// 質問を指定した場合は1回だけ回答し、省略した場合は標準入力から質問を読む対話モードになる。
+/// 
+/// # 引数
+/// * `question` - 質問（省略時は対話モード）
+/// * `index_file` - 保存済みのインデックス（省略時は設定のリポジトリを解析）
+/// * `config_path` - 設定ファイルパス
+async fn cmd_ask(question: Option<&str>, index_file: Option<&str>, config_path: Option<&str>) -> Result<()> {
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+    let index = match index_file {
+        Some(path) => analyzer_core::Index::load(std::path::Path::new(path))?,
+        None => {
+            Analyzer::new(config.clone())
+                .with_diagnostics(diagnostics.clone())
+                .analyze_repo(&config.project.repo_path, &config)
+                .await?
+        }
+    };
+    let summarizer = Summarizer::new(config).with_diagnostics(diagnostics.clone());
+
+    if let Some(question) = question {
+        let result = summarizer.ask(&index, question).await?;
+        println!("{}", result.answer_md.trim_end());
+        print_diagnostics(&diagnostics);
+        return Ok(());
+    }
+
+    println!("質問を入力してください（:history で履歴、!番号 で再実行、:quit で終了）");
+    let stdin = std::io::stdin();
+    let mut history: Vec<String> = Vec::new();
+    loop {
+        print!("> ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            break;
+        }
+        match parse_repl_input(&line, &history) {
+            ReplInput::Empty => {}
+            ReplInput::Quit => break,
+            ReplInput::History => {
+                for (i, question) in history.iter().enumerate() {
+                    println!("{:>3}  {}", i + 1, question);
+                }
+            }
+            ReplInput::Invalid(message) => eprintln!("{}", message),
+            ReplInput::Question(question) => {
+                // 1件の失敗でセッションを終えず、エラーを表示して次の質問を待つ（!番号 で再実行できる）
+                match summarizer.ask(&index, &question).await {
+                    Ok(result) => println!("{}\n", result.answer_md.trim_end()),
+                    Err(e) => eprintln!("回答できませんでした: {:#}\n", e),
+                }
+                history.push(question);
+            }
+        }
+    }
+    print_diagnostics(&diagnostics);
+
+    Ok(())
+}
+
+/// diffコマンドを実行
+/// 
+/// # 引数
+/// * `old` - 以前の版の保存済みのインデックス
+/// * `new` - 新しい版の保存済みのインデックス（省略時は設定のリポジトリを解析）
+/// * `out` - 出力先のMarkdownファイル（省略時は標準出力）
+/// * `config_path` - 設定ファイルパス
+async fn cmd_diff(old: &str, new: Option<&str>, out: Option<&str>, config_path: Option<&str>) -> Result<()> {
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+    let old_index = Index::load(Path::new(old))?;
+    let new_index = match new {
+        Some(path) => Index::load(Path::new(path))?,
+        None => {
+            Analyzer::new(config.clone())
+                .with_diagnostics(diagnostics.clone())
+                .analyze_repo(&config.project.repo_path, &config)
+                .await?
+        }
+    };
+    let result = Summarizer::new(config.clone()).with_diagnostics(diagnostics.clone()).summarize_diff(&old_index, &new_index);
+
+    match out {
+        Some(out) => {
+            let out = config.output_path(out);
+            if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&out, &result.content_md)?;
+            println!("変更履歴を出力しました: {}", out.display());
+        }
+        None => println!("{}", result.content_md.trim_end()),
+    }
+    print_diagnostics(&diagnostics);
 
     Ok(())
 }
 
+/// 対話モードの入力
+#[derive(Debug, PartialEq)]
+enum ReplInput {
+    Empty,
+    Quit,
+    History,
+    Question(String),
+    Invalid(String),
+}
+
+/// 対話モードの入力を解釈（`!番号` は履歴の質問に置き換える）
+fn parse_repl_input(line: &str, history: &[String]) -> ReplInput {
+    let line = line.trim();
+    match line {
+        "" => ReplInput::Empty,
+        ":quit" | ":q" | "exit" => ReplInput::Quit,
+        ":history" | ":h" => ReplInput::History,
+        _ => match line.strip_prefix('!') {
+            Some(number) => match number.parse::<usize>().ok().and_then(|n| history.get(n.checked_sub(1)?)) {
+                Some(question) => ReplInput::Question(question.clone()),
+                None => ReplInput::Invalid(format!("履歴にありません: {}", line)),
+            },
+            None => ReplInput::Question(line.to_string()),
+        },
+    }
+}
+
 /// build-allコマンドを実行（全機能を一度に実行）
 async fn cmd_build_all(config_path: Option<&str>) -> Result<()> {
     info!("全機能をビルド中...");
 
-    let config = Config::load(config_path)?;
+    let config = load_config(config_path)?;
     
     // 1. インデックス化
     info!("1. リポジトリをインデックス化中...");
-    let analyzer = Analyzer::new(config.clone());
+    let diagnostics = Diagnostics::new();
+    let analyzer = Analyzer::new(config.clone()).with_diagnostics(diagnostics.clone());
     let index = analyzer.analyze_repo(&config.project.repo_path, &config).await?;
     
     println!("インデックス化完了: {}ファイル, {}モジュール", index.stats.files, index.stats.modules);
 
-    // 2. Wiki生成
-    info!("2. Wikiを生成中...");
-    let wiki_builder = MdBookBuilder::new(config.clone());
-    let wiki_result = wiki_builder
-        .build_wiki(
+    // 2. Wiki・スライド生成（同じワーカープールで並行に生成し、同時実行数の上限を共有する）
+    info!("2. Wiki・スライドを生成中...");
+    let executor = Executor::new(config.summarization.workers);
+    let wiki_builder = MdBookBuilder::new(config.clone())
+        .with_diagnostics(diagnostics.clone())
+        .with_executor(executor.clone());
+    let slide_builder = SlideBuilder::new(config.clone())
+        .with_diagnostics(diagnostics.clone())
+        .with_executor(executor);
+    let wiki_sections = WIKI_SECTIONS.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let slide_sections = slide_sections(&index);
+    let slide_exports = ["html", "pdf", "pptx"].iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let wiki_out_dir = config.site.out_dir.to_string_lossy();
+    let slides_out_dir = config.slides.out_dir.to_string_lossy();
+    let (wiki_result, slide_result) = tokio::join!(
+        wiki_builder.build_wiki(&index, &wiki_out_dir, true, &wiki_sections),
+        slide_builder.build_slides(
             &index,
-            &config.site.out_dir.to_string_lossy(),
-            true,
-            &vec!["overview", "architecture", "modules", "flows", "deploy", "faq"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-        )
-        .await?;
+            &config.slides.flavor,
+            &slides_out_dir,
+            &slide_sections,
+            &slide_exports,
+            config.slides_language(),
+        ),
+    );
+    let wiki_result = wiki_result?;
+    let slide_result = slide_result?;
     
     println!("Wiki生成完了: {}ページ", wiki_result.pages);
-
-    // 3. スライド生成
-    info!("3. スライドを生成中...");
-    let slide_builder = SlideBuilder::new(config.clone());
-    let slide_result = slide_builder
-        .build_slides(
-            &index,
-            &config.slides.flavor,
-            &config.slides.out_dir.to_string_lossy(),
-            &vec!["overview", "architecture", "modules", "flows", "deploy"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-            &vec!["html", "pdf", "pptx"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>(),
-        )
-        .await?;
+    print_skipped(&wiki_result.skipped);
+    for page in &wiki_result.quality {
+        println!(
+            "  品質スコア {}: {:.1}（情報密度 {:.2}、定型文率 {:.2}、図 {}、引用 {}）",
+            page.section,
+            page.score,
+            page.information_density,
+            page.boilerplate_ratio,
+            page.diagrams,
+            page.citations
+        );
+    }
     
     println!("スライド生成完了: {}ファイル", slide_result.files.len());
     for file in &slide_result.files {
         println!("  - {}: {}", file.format, file.path.display());
     }
+    print_skipped(&slide_result.skipped);
 
-    // 4. GitHub Pages公開（オプション）
-    if config.publish.mode == "docs" {
-        info!("4. GitHub Pagesに公開中...");
+    // 3. GitHub Pages公開（オプション）
+    let mut published_url = None;
+    if config.publish.mode == "docs" && !wiki_result.skipped.is_empty() {
+        println!("mdBookのビルド結果がないため、公開をスキップしました");
+    } else if config.publish.mode == "docs" {
+        info!("3. GitHub Pagesに公開中...");
         let publisher = Publisher::new(config.clone());
         let slides_out_dir = config.slides.out_dir.to_string_lossy().to_string();
         let publish_result = publisher
@@ -266,14 +766,282 @@ async fn cmd_build_all(config_path: Option<&str>) -> Result<()> {
             )
             .await?;
         
-        println!("公開完了: {}", publish_result.hint);
+        match publish_result.status {
+            PublishStatus::Published => println!("公開完了: {}", publish_result.hint),
+            PublishStatus::Unchanged => println!("{}", publish_result.hint),
+        }
+        print_optimization(publish_result.optimization.as_ref());
+        published_url = publish_result.url;
     }
 
     println!("全機能のビルドが完了しました！");
+    print_redactions(&index);
+    print_diagnostics(&diagnostics);
+
+    // 4. Webhook通知
+    Notifier::new(config.clone())
+        .notify(&BuildSummary {
+            event: "build-all".to_string(),
+            project: config.project.name.clone(),
+            ok: true,
+            files: index.stats.files,
+            modules: index.stats.modules,
+            wiki_pages: wiki_result.pages,
+            slide_files: slide_result.files.len(),
+            url: published_url,
+            message: wiki_result
+                .skipped
+                .iter()
+                .chain(&slide_result.skipped)
+                .map(|a| format!("スキップ: {}（{}）", a.path.display(), a.reason))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        })
+        .await;
 
     Ok(())
 }
 
+/// previewコマンドを実行
+///
+/// # 引数
+/// * `slides` - trueの場合はスライド、falseの場合はWikiをプレビュー
+/// * `skip_index` - インデックスのファイルがあれば解析せずに再利用するか
+/// * `index_file` - インデックスの保存先
+/// * `port` - 待ち受けるポート
+/// * `no_open` - ブラウザを開かないか
+/// * `config_path` - 設定ファイルパス
+///
+/// # 戻り値
+/// * `Result<()>` - Ctrl-Cで終了するまで戻らない、またはエラー
+async fn cmd_preview(
+    slides: bool,
+    skip_index: bool,
+    index_file: &str,
+    port: u16,
+    no_open: bool,
+    config_path: Option<&str>,
+) -> Result<()> {
+    let config = load_config(config_path)?;
+    let diagnostics = Diagnostics::new();
+
+    // 1. インデックス化（--skip-indexで保存済みのものがあれば再利用）
+    let index_path = config.output_path(index_file);
+    let index = if skip_index && index_path.exists() {
+        println!("保存済みのインデックスを使用します: {}", index_path.display());
+        Index::load(&index_path)?
+    } else {
+        if skip_index {
+            println!("インデックスのファイルがないため解析します: {}", index_path.display());
+        }
+        let index = Analyzer::new(config.clone())
+            .with_diagnostics(diagnostics.clone())
+            .analyze_repo(&config.project.repo_path, &config)
+            .await?;
+        if let Some(parent) = index_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        index.save_with(&index_path, &config.index.content)?;
+        index
+    };
+    println!("インデックス: {}ファイル, {}モジュール", index.stats.files, index.stats.modules);
+
+    // 2. 生成（配信するディレクトリと開くページを決める）
+    let (root, page) = if slides {
+        let exports = vec!["html".to_string()];
+        let result = SlideBuilder::new(config.clone())
+            .with_diagnostics(diagnostics.clone())
+            .build_slides(
+                &index,
+                &config.slides.flavor,
+                &config.slides.out_dir.to_string_lossy(),
+                &slide_sections(&index),
+                &exports,
+                config.slides_language(),
+            )
+            .await?;
+        print_skipped(&result.skipped);
+        let html = result
+            .files
+            .iter()
+            .find(|f| f.format == "html")
+            .ok_or_else(|| anyhow::anyhow!("HTMLのスライドを生成できませんでした（doctorコマンドでmdbook・marpを確認してください）"))?;
+        let root = html.path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let page = html.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        (root, page)
+    } else {
+        let toc: Vec<String> = WIKI_SECTIONS.iter().map(|s| s.to_string()).collect();
+        let result = MdBookBuilder::new(config.clone())
+            .with_diagnostics(diagnostics.clone())
+            .build_wiki(&index, &config.site.out_dir.to_string_lossy(), true, &toc)
+            .await?;
+        print_skipped(&result.skipped);
+        if !result.site_dir.join("index.html").exists() {
+            return Err(anyhow::anyhow!(
+                "Wikiのビルド結果がありません: {}（doctorコマンドでmdbookを確認してください）",
+                result.site_dir.display()
+            ));
+        }
+        (result.site_dir, String::new())
+    };
+    print_diagnostics(&diagnostics);
+
+    // 3. ローカルサーバーで配信し、ブラウザで開く
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| anyhow::anyhow!("ポート{}で待ち受けできません（--portで変更してください）: {}", port, e))?;
+    let url = format!("http://{}/{}", listener.local_addr()?, page);
+    println!("プレビュー: {}（Ctrl-Cで終了）", url);
+    if !no_open {
+        if let Err(e) = opener::open_browser(&url) {
+            eprintln!("ブラウザを開けませんでした（URLを開いてください）: {}", e);
+        }
+    }
+
+    tokio::select! {
+        result = preview::serve_dir(listener, root) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+/// build-allで生成するスライドのセクション（APIエンドポイントがあればモジュールの次にAPIを追加）
+fn slide_sections(index: &Index) -> Vec<String> {
+    let mut sections: Vec<String> = SLIDE_SECTIONS.iter().map(|s| s.to_string()).collect();
+    if !index.endpoints.is_empty() {
+        let position = sections.iter().position(|s| s == "modules").map_or(sections.len(), |i| i + 1);
+        sections.insert(position, "api".to_string());
+    }
+    sections
+}
+
+/// doctorコマンドを実行（外部ツールの有無を表示）
+fn cmd_doctor() {
+    for tool in check_tools() {
+        if tool.available {
+            println!("✓ {}: {}", tool.name, tool.version.as_deref().unwrap_or("バージョン不明"));
+        } else {
+            println!("✗ {}: 見つかりません（インストール: {}）", tool.name, tool.install_hint);
+        }
+    }
+    println!("見つからないツールで作る成果物（HTMLサイト・スライドのエクスポート・ポスターのPDF）はスキップし、Markdownのみを生成します");
+}
+
+/// 外部ツールがないためにスキップした成果物を表示
+fn print_skipped(skipped: &[SkippedArtifact]) {
+    for artifact in skipped {
+        println!("  スキップ: {}（{}）", artifact.path.display(), artifact.reason);
+    }
+}
+
+/// 公開前の最適化によるサイズの削減を表示（`publish.optimize = false` の場合は何もしない）
+fn print_optimization(report: Option<&OptimizeReport>) {
+    let Some(report) = report else {
+        return;
+    };
+    println!(
+        "最適化: {:.1} KB → {:.1} KB（{:.0}%削減）",
+        report.before_bytes as f64 / 1024.0,
+        report.after_bytes as f64 / 1024.0,
+        report.saved_ratio() * 100.0
+    );
+    for kind in &report.kinds {
+        println!(
+            "  {}: {}ファイル {:.1} KB → {:.1} KB",
+            kind.kind,
+            kind.files,
+            kind.before_bytes as f64 / 1024.0,
+            kind.after_bytes as f64 / 1024.0
+        );
+    }
+    if !report.removed.is_empty() {
+        println!("  参照されないテーマのファイルを削除: {}件", report.removed.len());
+    }
+}
+
+/// 機密情報・個人情報と独自の規則で置き換えた件数を表示（置き換えがない場合は何もしない）
+fn print_redactions(index: &Index) {
+    let counts = index.redaction_counts();
+    if counts.is_empty() {
+        return;
+    }
+    let kinds: Vec<String> = counts.iter().map(|(kind, count)| format!("{} {}件", Redaction::label(kind), count)).collect();
+    println!("マスク: {}件（{}）", counts.values().sum::<usize>(), kinds.join("、"));
+}
+
+/// 解析・要約・図・ビルド中に記録した警告をまとめて表示
+fn print_diagnostics(diagnostics: &Diagnostics) {
+    if diagnostics.is_empty() {
+        return;
+    }
+    println!("警告（{}件）:", diagnostics.len());
+    for diagnostic in diagnostics.entries() {
+        println!("  - {}", diagnostic.label());
+    }
+}
+
+/// 生成予定のファイルをツリー形式で表示する文字列にする
+/// 
+/// # 引数
+/// * `root` - 出力ディレクトリ
+/// * `files` - 生成予定のファイル（出力ディレクトリからの相対パス）
+/// 
+/// # 戻り値
+/// * `String` - ツリーと合計（推定サイズ・生成方式付き）
+fn render_plan(root: &str, files: &[PlannedFile]) -> String {
+    /// ツリーのノード（ディレクトリは子を持ち、ファイルは計画を持つ）
+    #[derive(Default)]
+    struct Node<'a> {
+        children: BTreeMap<String, Node<'a>>,
+        file: Option<&'a PlannedFile>,
+    }
+
+    fn render(node: &Node, prefix: &str, out: &mut String) {
+        let count = node.children.len();
+        for (i, (name, child)) in node.children.iter().enumerate() {
+            let last = i + 1 == count;
+            let is_dir = !child.children.is_empty() || child.file.is_some_and(|f| f.path.to_string_lossy().ends_with('/'));
+            let label = if is_dir { format!("{}/", name) } else { name.clone() };
+            let detail = child
+                .file
+                .map(|f| format!("  ({}, {})", format_size(f.estimated_bytes), f.generator))
+                .unwrap_or_default();
+            out.push_str(&format!("{}{}{}{}\n", prefix, if last { "└── " } else { "├── " }, label, detail));
+            render(child, &format!("{}{}", prefix, if last { "    " } else { "│   " }), out);
+        }
+    }
+
+    let mut tree = Node::default();
+    for file in files {
+        let mut node = &mut tree;
+        for part in file.path.iter() {
+            node = node.children.entry(part.to_string_lossy().to_string()).or_default();
+        }
+        node.file = Some(file);
+    }
+
+    let mut out = format!("{}/（ドライラン: 書き込みは行っていません）\n", root.trim_end_matches('/'));
+    render(&tree, "", &mut out);
+
+    let known: usize = files.iter().filter_map(|f| f.estimated_bytes).sum();
+    let unknown = files.iter().filter(|f| f.estimated_bytes.is_none()).count();
+    out.push_str(&format!("合計: {}ファイル、推定 {}", files.len(), format_size(Some(known))));
+    if unknown > 0 {
+        out.push_str(&format!("（外部コマンドの出力{}件はサイズ不明）", unknown));
+    }
+    out.push('\n');
+    out
+}
+
+/// 推定サイズを表示用にする
+fn format_size(bytes: Option<usize>) -> String {
+    match bytes {
+        None => "サイズ不明".to_string(),
+        Some(b) if b < 1024 => format!("{} B", b),
+        Some(b) if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        Some(b) => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
 /// CLI引数定義
 #[derive(Parser)]
 #[command(name = "deeprepo-slides-mcp")]
@@ -281,6 +1049,14 @@ async fn cmd_build_all(config_path: Option<&str>) -> Result<()> {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// 解析対象のリポジトリ内に書き込まない（`security.read-only-repo`、出力・公開は作業ディレクトリに置き換える）
+    #[arg(long, global = true)]
+    no_touch_repo: bool,
+
+    /// 今回の実行だけ出力言語を上書きする（ja|en、要約・Wiki・スライドに適用、deeprepo.tomlは変更しない）
+    #[arg(long, global = true, value_parser = ["ja", "en"])]
+    lang: Option<String>,
 }
 
 /// コマンド定義
@@ -292,6 +1068,10 @@ enum Commands {
         #[arg(long)]
         repo: String,
 
+        /// インデックスの保存先（zstd圧縮のコンパクト形式）
+        #[arg(long)]
+        out: Option<String>,
+
         /// 設定ファイルパス
         #[arg(short, long)]
         config: Option<String>,
@@ -299,7 +1079,7 @@ enum Commands {
 
     /// 要約を生成
     Summarize {
-        /// スコープ（repo|package|module|file）
+        /// スコープ（repo|package|module|file|readme）
         #[arg(long)]
         scope: String,
 
@@ -307,7 +1087,7 @@ enum Commands {
         #[arg(long)]
         target: String,
 
-        /// スタイル（concise-ja|detailed-ja）
+        /// スタイル（concise-ja|detailed-ja|concise-en|detailed-en）
         #[arg(long, default_value = "concise-ja")]
         style: String,
     },
@@ -318,9 +1098,21 @@ enum Commands {
         #[arg(short, long)]
         out: Option<String>,
 
+        /// 生成対象をこのディレクトリ配下に絞る（例: crates/summarizer）
+        #[arg(long)]
+        path: Option<String>,
+
         /// 設定ファイルパス
         #[arg(short, long)]
         config: Option<String>,
+
+        /// 書き込まずに生成予定のファイルツリー・推定サイズ・要約方式を表示
+        #[arg(long)]
+        dry_run: bool,
+
+        /// 中断したビルドを続きから再開する（入力が前回と同じなら生成済みのページ・モジュールの解説を再利用）
+        #[arg(long)]
+        resume: bool,
     },
 
     /// スライドを生成
@@ -341,6 +1133,45 @@ enum Commands {
         #[arg(long, default_value = "html")]
         export: String,
 
+        /// 生成済みWikiのディレクトリ（指定時はWikiを凝縮してスライド化）
+        #[arg(long)]
+        from_wiki: Option<String>,
+
+        /// プリセット（summary|detailed、--from-wiki指定時のみ）
+        #[arg(long, default_value = "summary")]
+        preset: String,
+
+        /// 生成対象をこのディレクトリ配下に絞る（例: crates/summarizer、--from-wiki指定時は無視）
+        #[arg(long)]
+        path: Option<String>,
+
+        /// スライドの言語（ja|en、カンマ区切りで複数指定すると <out>/<言語> に出力、省略時は --lang・slides.language・project.language、--from-wiki指定時は無視）
+        #[arg(long)]
+        language: Option<String>,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// 書き込まずに生成予定のファイルツリー・推定サイズ・要約方式を表示
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// アーキテクチャを1枚にまとめた印刷用のポスター（HTML・PDF）を生成
+    Poster {
+        /// 出力ディレクトリ（省略時は poster.out-dir）
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// PDFも出力（ヘッドレスのChromiumが必要）
+        #[arg(long)]
+        pdf: bool,
+
+        /// 対象をこのディレクトリ配下に絞る（例: crates/summarizer）
+        #[arg(long)]
+        path: Option<String>,
+
         /// 設定ファイルパス
         #[arg(short, long)]
         config: Option<String>,
@@ -375,6 +1206,140 @@ enum Commands {
         #[arg(short, long)]
         config: Option<String>,
     },
+
+    /// ソースコードまたは生成済みドキュメントを検索
+    Search {
+        /// 検索クエリ
+        q: String,
+
+        /// 返す結果の最大数
+        #[arg(short, default_value_t = 20)]
+        k: usize,
+
+        /// 検索対象（source|docs|all）
+        #[arg(long = "in", default_value = "source")]
+        search_in: String,
+
+        /// 検索の種類（text: 本文、symbol: 関数・型の宣言名（前方一致・あいまい一致）と定義位置・シグネチャ、path: ファイルパス、symbol・pathはソースのみ）
+        #[arg(long, default_value = "text")]
+        kind: String,
+
+        /// 埋め込みベクトルで意味の近いコードを検索（ソースのみ、`index.embeddings` で方法を選択）
+        #[arg(long)]
+        semantic: bool,
+
+        /// 言語で絞り込む（例: py、ソースのみ、--semanticなし）
+        #[arg(long)]
+        language: Option<String>,
+
+        /// リポジトリルートからの相対パスのglobで絞り込む（例: "services/**"、ソースのみ、--semanticなし）
+        #[arg(long)]
+        path_glob: Option<String>,
+
+        /// モジュールID、またはパッケージ名・ディレクトリ（例: web、web:src/api）で絞り込む（ソースのみ、--semanticなし）
+        #[arg(long)]
+        module: Option<String>,
+
+        /// 読み飛ばす結果の数（ページ送り）
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// インデックスに対して質問し、引用付きの回答を表示
+    Ask {
+        /// 質問（省略時は対話モード）
+        question: Option<String>,
+
+        /// 保存済みのインデックス（省略時は設定のリポジトリを解析）
+        #[arg(long)]
+        index_file: Option<String>,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// 2つのインデックスの差分を変更履歴のMarkdownにする
+    Diff {
+        /// 以前の版の保存済みのインデックス（`index --out` で保存したもの）
+        old: String,
+
+        /// 新しい版の保存済みのインデックス（省略時は設定のリポジトリを解析）
+        #[arg(long)]
+        new: Option<String>,
+
+        /// 出力先のMarkdownファイル（省略時は標準出力）
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// 生成済みWikiをNotionにエクスポート
+    NotionExport {
+        /// Wikiディレクトリ（省略時は設定のsite.out-dir）
+        #[arg(long)]
+        site_dir: Option<String>,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// モジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONで出力
+    ExportGraph {
+        /// 出力ディレクトリ（省略時は ./out/graphs）
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// 対象をこのディレクトリ配下に絞る（例: crates/summarizer）
+        #[arg(long)]
+        path: Option<String>,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// インデックス化・生成・ローカルサーバーでの配信をまとめて行い、ブラウザで開く
+    Preview {
+        /// スライドをプレビュー（slides.flavor・slides.languageで生成）
+        #[arg(long, conflicts_with = "wiki")]
+        slides: bool,
+
+        /// Wikiをプレビュー（デフォルト）
+        #[arg(long)]
+        wiki: bool,
+
+        /// インデックスのファイルがあれば解析せずに再利用（ファイル以降の変更は反映されない）
+        #[arg(long)]
+        skip_index: bool,
+
+        /// インデックスの保存先（解析した場合は保存し、--skip-indexで再利用）
+        #[arg(long, default_value = "./out/index.idx")]
+        index_file: String,
+
+        /// 待ち受けるポート（0の場合は空いているポート）
+        #[arg(long, default_value_t = 3000)]
+        port: u16,
+
+        /// ブラウザを開かない
+        #[arg(long)]
+        no_open: bool,
+
+        /// 設定ファイルパス
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+
+    /// 外部ツール（mdbook, marp）の有無を確認
+    Doctor,
 }
 
 #[cfg(test)]
@@ -384,7 +1349,7 @@ mod tests {
     #[tokio::test]
     async fn test_cli_parse() {
         // CLI引数のパーステスト
-        let cli = Cli::parse_from(&["deeprepo-slides-mcp", "index", "--repo", "."]);
+        let cli = Cli::parse_from(["deeprepo-slides-mcp", "index", "--repo", "."]);
         match cli.command {
             Commands::Index { repo, .. } => {
                 assert_eq!(repo, ".");
@@ -392,5 +1357,81 @@ mod tests {
             _ => panic!("予期しないコマンド"),
         }
     }
+
+    #[test]
+    fn test_parse_ask_and_repl_input() {
+        let cli = Cli::parse_from(["deeprepo-slides-mcp", "ask", "認証はどこ？", "--index-file", "idx.json"]);
+        match cli.command {
+            Commands::Ask { question, index_file, .. } => {
+                assert_eq!(question.as_deref(), Some("認証はどこ？"));
+                assert_eq!(index_file.as_deref(), Some("idx.json"));
+            }
+            _ => panic!("予期しないコマンド"),
+        }
+
+        let history = vec!["認証はどこ？".to_string()];
+        assert_eq!(parse_repl_input("  \n", &history), ReplInput::Empty);
+        assert_eq!(parse_repl_input(":quit\n", &history), ReplInput::Quit);
+        assert_eq!(parse_repl_input("!1\n", &history), ReplInput::Question("認証はどこ？".to_string()));
+        assert!(matches!(parse_repl_input("!2", &history), ReplInput::Invalid(_)));
+        assert_eq!(parse_repl_input("設定は？", &history), ReplInput::Question("設定は？".to_string()));
+    }
+
+    #[test]
+    fn test_parse_preview() {
+        let cli = Cli::parse_from(["deeprepo-slides-mcp", "preview", "--slides", "--skip-index", "--port", "0"]);
+        match cli.command {
+            Commands::Preview { slides, skip_index, index_file, port, no_open, .. } => {
+                assert!(slides && skip_index && !no_open);
+                assert_eq!(index_file, "./out/index.idx");
+                assert_eq!(port, 0);
+            }
+            _ => panic!("予期しないコマンド"),
+        }
+        assert!(Cli::try_parse_from(["deeprepo-slides-mcp", "preview", "--slides", "--wiki"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_poster() {
+        let cli = Cli::parse_from(["deeprepo-slides-mcp", "poster", "--pdf", "--out", "./wall"]);
+        match cli.command {
+            Commands::Poster { out, pdf, path, .. } => {
+                assert!(pdf);
+                assert_eq!(out.as_deref(), Some("./wall"));
+                assert_eq!(path, None);
+            }
+            _ => panic!("予期しないコマンド"),
+        }
+    }
+
+    #[test]
+    fn test_parse_export_graph() {
+        let cli = Cli::parse_from(["deeprepo-slides-mcp", "export-graph", "--out", "./graphs", "--path", "crates/core"]);
+        match cli.command {
+            Commands::ExportGraph { out, path, .. } => {
+                assert_eq!(out.as_deref(), Some("./graphs"));
+                assert_eq!(path.as_deref(), Some("crates/core"));
+            }
+            _ => panic!("予期しないコマンド"),
+        }
+    }
+
+    #[test]
+    fn test_render_plan_tree() {
+        let files = vec![
+            PlannedFile::new("book.toml", Some(300), "static"),
+            PlannedFile::new("src/SUMMARY.md", Some(2048), "static"),
+            PlannedFile::new("src/modules.md", Some(100), "heuristic"),
+            PlannedFile::new("book/", None, "mdbook"),
+        ];
+        let text = render_plan("./out/wiki", &files);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "├── book/  (サイズ不明, mdbook)");
+        assert_eq!(lines[2], "├── book.toml  (300 B, static)");
+        assert_eq!(lines[3], "└── src/");
+        assert_eq!(lines[4], "    ├── SUMMARY.md  (2.0 KB, static)");
+        assert_eq!(lines[5], "    └── modules.md  (100 B, heuristic)");
+        assert!(lines[6].starts_with("合計: 4ファイル"));
+    }
 }
 
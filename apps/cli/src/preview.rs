@@ -0,0 +1,151 @@
+/*!
+ * previewコマンドのローカルサーバー
+ *
+ * 生成したWiki（mdBookのビルド結果）・HTMLスライドをブラウザで確認できるよう、
+ * 出力ディレクトリを静的ファイルとしてHTTPで配信する
+ *
+ * 主な仕様:
+ * - `GET` のみに応答し、ディレクトリへのリクエストは `index.html` を返す
+ * - パーセントエンコードされたパス（日本語のファイル名など）を復元してから解決する
+ * - `..` で配信するディレクトリの外に出るリクエストは404にする
+ *
+ * 制限事項:
+ * - ローカルでの確認用の最小限の実装（Range・キャッシュ・ファイルの変更の監視には対応しない）
+ */
+
+use std::path::{Component, Path, PathBuf};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tracing::debug;
+
+/// ディレクトリを静的ファイルとして配信し続ける
+///
+/// # 引数
+/// * `listener` - 待ち受け中のソケット
+/// * `root` - 配信するディレクトリ
+///
+/// # 戻り値
+/// * `anyhow::Result<()>` - 待ち受けのエラー（正常時は戻らない）
+pub(crate) async fn serve_dir(listener: TcpListener, root: PathBuf) -> anyhow::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let root = root.clone();
+        tokio::spawn(async move {
+            let (reader, mut writer) = stream.into_split();
+            let mut request_line = String::new();
+            if BufReader::new(reader).read_line(&mut request_line).await.is_err() {
+                return;
+            }
+            debug!("プレビューのリクエスト: {} {}", peer, request_line.trim());
+
+            let file = match request_line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["GET", target, ..] => resolve_request_path(&root, target),
+                _ => None,
+            };
+            let (status, content_type, body) = match file {
+                Some(path) => match tokio::fs::read(&path).await {
+                    Ok(body) => ("200 OK", content_type(&path), body),
+                    Err(_) => ("404 Not Found", "text/plain; charset=utf-8", b"not found\n".to_vec()),
+                },
+                None => ("404 Not Found", "text/plain; charset=utf-8", b"not found\n".to_vec()),
+            };
+            let header = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                content_type,
+                body.len()
+            );
+            let _ = writer.write_all(header.as_bytes()).await;
+            let _ = writer.write_all(&body).await;
+            let _ = writer.shutdown().await;
+        });
+    }
+}
+
+/// リクエストのパスを配信するファイルに解決
+///
+/// # 引数
+/// * `root` - 配信するディレクトリ
+/// * `target` - リクエストのパス（クエリ・フラグメントを含んでもよい）
+///
+/// # 戻り値
+/// * `Option<PathBuf>` - ファイルのパス（ディレクトリの外・存在しない場合はNone）
+fn resolve_request_path(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let decoded = percent_decode(path)?;
+    let relative = Path::new(decoded.trim_start_matches('/'));
+    if relative.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return None;
+    }
+
+    let mut file = root.join(relative);
+    if file.is_dir() {
+        file = file.join("index.html");
+    }
+    file.is_file().then_some(file)
+}
+
+/// パーセントエンコードを復元（UTF-8として不正な場合はNone）
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// 拡張子からContent-Typeを決める
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("html") | Some("htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("pdf") => "application/pdf",
+        Some("md") | Some("txt") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_request_path() {
+        let root = std::env::temp_dir().join(format!("deeprepo-preview-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("モジュール")).unwrap();
+        std::fs::write(root.join("index.html"), "<html></html>").unwrap();
+        std::fs::write(root.join("モジュール/index.html"), "<html></html>").unwrap();
+
+        assert_eq!(resolve_request_path(&root, "/"), Some(root.join("index.html")));
+        assert_eq!(resolve_request_path(&root, "/index.html?theme=dark#top"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve_request_path(&root, "/%E3%83%A2%E3%82%B8%E3%83%A5%E3%83%BC%E3%83%AB/"),
+            Some(root.join("モジュール/index.html"))
+        );
+        assert_eq!(resolve_request_path(&root, "/../etc/passwd"), None);
+        assert_eq!(resolve_request_path(&root, "/missing.html"), None);
+        assert_eq!(resolve_request_path(&root, "/%E3%83"), None);
+        assert_eq!(content_type(Path::new("book/index.HTML")), "text/html; charset=utf-8");
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,384 @@
+/*!
+ * アーキテクチャの推定
+ *
+ * モジュールを層（UI・API/ハンドラー・ドメイン/サービス・永続化・ユーティリティ）に分類し、層の間の依存をまとめる
+ * - Wikiの「アーキテクチャ」章の層ごとの一覧と層構成図（`layers`）に使う
+ * - 静的に分類できなかったモジュールは、ローカルLLMがある場合に要約側で分類し直す（`ArchitectureModel::reclassify`）
+ *
+ * 主な仕様:
+ * - 分類の根拠は次の順に使う
+ *   1. APIエンドポイントを定義している（`api`）
+ *   2. ファイル名・ディレクトリ名（`handlers`・`services`・`repository`・`utils` など、ファイルに近い名前を優先）
+ *   3. 外部ライブラリのimport（sqlx・SQLAlchemy・Prisma → 永続化、axum・Express・FastAPI → API、React・Vue → UI）
+ *   4. 内部依存（API層からimportされる・永続化層をimportする → ドメイン、他をimportせず複数から使われる → ユーティリティ）
+ * - どれにも当てはまらないモジュールは `other`
+ * - 層の逆転は、下位の層（ドメイン・永続化・ユーティリティ）からAPI・UI層へのimportと、ユーティリティから他の層へのimport
+ *
+ * 制限事項:
+ * - 名前とimportによる推定のため、実際の設計上の層と一致しない場合がある（Djangoの `views` などは分類しない）
+ * - 層の間の依存は内部依存グラフで解決できたimportのみ
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Index;
+
+/// 層（上位から順、`other` は含まない）
+pub const LAYERS: &[&str] = &["ui", "api", "domain", "persistence", "utils"];
+
+/// ファイル名・ディレクトリ名の語と層
+const NAME_KEYWORDS: &[(&str, &[&str])] = &[
+    ("ui", &["ui", "components", "component", "pages", "screens", "widgets", "layouts", "frontend"]),
+    ("api", &["api", "handlers", "handler", "routes", "route", "router", "routers", "controllers", "controller", "endpoints", "resolvers", "grpc"]),
+    ("persistence", &["db", "database", "repository", "repositories", "dao", "storage", "persistence", "migrations", "migration", "store", "orm"]),
+    ("domain", &["domain", "services", "service", "usecases", "usecase", "business", "models", "model", "entities", "entity", "core"]),
+    ("utils", &["util", "utils", "helpers", "helper", "common", "shared", "support", "misc"]),
+];
+
+/// UIのファイルの拡張子
+const UI_EXTENSIONS: &[&str] = &["tsx", "jsx", "vue", "svelte"];
+
+/// 外部ライブラリのimportと層（import文字列の先頭が一致するもの）
+const LIBRARY_LAYERS: &[(&str, &[&str])] = &[
+    ("persistence", &[
+        "sqlx", "diesel", "sea_orm", "rusqlite", "redis", "mongodb", "sqlalchemy", "psycopg", "psycopg2", "pymongo",
+        "django.db", "prisma", "@prisma/client", "mongoose", "typeorm", "sequelize", "knex", "pg", "mysql2",
+        "gorm.io/gorm", "database/sql", "java.sql", "javax.persistence", "jakarta.persistence",
+    ]),
+    ("api", &[
+        "axum", "actix_web", "warp", "rocket", "express", "fastify", "koa", "@nestjs/common", "fastapi", "flask",
+        "net/http", "github.com/gin-gonic/gin", "github.com/labstack/echo", "org.springframework.web",
+    ]),
+    ("ui", &["react", "react-dom", "vue", "svelte", "@angular/core", "solid-js", "preact"]),
+];
+
+/// 分類の根拠
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LayerBasis {
+    /// APIエンドポイントを定義している
+    Endpoint,
+    /// ファイル名・ディレクトリ名
+    Name,
+    /// 外部ライブラリのimport
+    Library,
+    /// 内部依存（importする・されるモジュールの層）
+    Dependencies,
+    /// ローカルLLMによる分類
+    Llm,
+    /// 分類できなかった
+    None,
+}
+
+/// モジュールの層
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentLayer {
+    pub path: PathBuf,
+    /// モジュールID
+    pub id: String,
+    /// 層（`LAYERS` のいずれか、または `other`）
+    pub layer: String,
+    pub basis: LayerBasis,
+    /// 根拠となった名前・ライブラリ（内部依存・LLMの場合は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub evidence: String,
+}
+
+/// 層の間の依存
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayerDependency {
+    pub from: String,
+    pub to: String,
+    /// importの数（ファイルの組の数）
+    pub imports: usize,
+    /// 層の逆転（下位の層から上位の層へのimport）か
+    pub violation: bool,
+}
+
+/// モジュールの層と層の間の依存
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchitectureModel {
+    /// モジュールの層（`Index::modules` の順）
+    pub components: Vec<ComponentLayer>,
+    /// 層の間の依存（同じ層の中のimportは含まない）
+    pub dependencies: Vec<LayerDependency>,
+    /// 層の逆転となるimport（importするファイル、importされるファイル）
+    pub violations: Vec<(PathBuf, PathBuf)>,
+}
+
+impl ArchitectureModel {
+    /// 層に属するモジュール
+    ///
+    /// # 引数
+    /// * `layer` - 層（`LAYERS` のいずれか、または `other`）
+    ///
+    /// # 戻り値
+    /// * `Vec<&ComponentLayer>` - モジュール（`Index::modules` の順）
+    pub fn layer(&self, layer: &str) -> Vec<&ComponentLayer> {
+        self.components.iter().filter(|c| c.layer == layer).collect()
+    }
+
+    /// モジュールの層
+    pub fn layer_of(&self, path: &Path) -> Option<&str> {
+        self.components.iter().find(|c| c.path == path).map(|c| c.layer.as_str())
+    }
+
+    /// モジュールを分類し直し、層の間の依存を計算し直す
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `changes` - モジュールのパスと新しい層（`LAYERS` にも `other` にもない層は無視）
+    /// * `basis` - 分類の根拠
+    pub fn reclassify(&mut self, index: &Index, changes: &[(PathBuf, String)], basis: LayerBasis) {
+        for (path, layer) in changes {
+            if !LAYERS.contains(&layer.as_str()) && layer != "other" {
+                continue;
+            }
+            if let Some(component) = self.components.iter_mut().find(|c| &c.path == path) {
+                component.layer = layer.clone();
+                component.basis = basis;
+                component.evidence.clear();
+            }
+        }
+        self.link(index);
+    }
+
+    /// 内部依存グラフから層の間の依存と層の逆転を計算
+    fn link(&mut self, index: &Index) {
+        let layers: HashMap<&Path, &str> = self.components.iter().map(|c| (c.path.as_path(), c.layer.as_str())).collect();
+        let mut counts: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        let mut violations = Vec::new();
+        for (from, targets) in &index.internal_dependencies {
+            let Some(from_layer) = layers.get(from.as_path()) else {
+                continue;
+            };
+            for to in targets {
+                let Some(to_layer) = layers.get(to.as_path()) else {
+                    continue;
+                };
+                if from_layer == to_layer {
+                    continue;
+                }
+                *counts.entry((rank(from_layer), rank(to_layer))).or_default() += 1;
+                if is_violation(from_layer, to_layer) {
+                    violations.push((from.clone(), to.clone()));
+                }
+            }
+        }
+        let name = |rank: usize| LAYERS.get(rank).copied().unwrap_or("other").to_string();
+        self.dependencies = counts
+            .into_iter()
+            .map(|((from, to), imports)| {
+                let (from, to) = (name(from), name(to));
+                let violation = is_violation(&from, &to);
+                LayerDependency { from, to, imports, violation }
+            })
+            .collect();
+        self.violations = violations;
+    }
+}
+
+/// インデックスのモジュールを層に分類
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `ArchitectureModel` - モジュールの層と層の間の依存
+pub(crate) fn infer_architecture(index: &Index) -> ArchitectureModel {
+    let endpoint_files: HashSet<&Path> = index.endpoints.iter().map(|e| e.file.as_path()).collect();
+    let mut components: Vec<ComponentLayer> = index
+        .modules
+        .iter()
+        .map(|module| {
+            let relative = module.path.strip_prefix(&index.repo_path).unwrap_or(&module.path);
+            let imports = index.files.iter().find(|f| f.path == module.path).map(|f| f.dependencies.as_slice()).unwrap_or_default();
+            let (layer, basis, evidence) = if endpoint_files.contains(module.path.as_path()) {
+                ("api", LayerBasis::Endpoint, String::new())
+            } else if let Some((layer, name)) = layer_by_name(relative) {
+                (layer, LayerBasis::Name, name)
+            } else if let Some((layer, library)) = layer_by_library(imports) {
+                (layer, LayerBasis::Library, library.to_string())
+            } else {
+                ("other", LayerBasis::None, String::new())
+            };
+            ComponentLayer { path: module.path.clone(), id: module.id.clone(), layer: layer.to_string(), basis, evidence }
+        })
+        .collect();
+
+    // 名前・importで分類できなかったモジュールを、分類済みのモジュールとの依存から推定
+    let layers: HashMap<PathBuf, String> = components.iter().map(|c| (c.path.clone(), c.layer.clone())).collect();
+    let mut importers: HashMap<&Path, Vec<&Path>> = HashMap::new();
+    for (from, targets) in &index.internal_dependencies {
+        for to in targets {
+            importers.entry(to.as_path()).or_default().push(from.as_path());
+        }
+    }
+    for component in components.iter_mut().filter(|c| c.basis == LayerBasis::None) {
+        let imports: Vec<&str> = index
+            .internal_dependencies
+            .get(&component.path)
+            .into_iter()
+            .flatten()
+            .filter_map(|to| layers.get(to).map(String::as_str))
+            .collect();
+        let importer_layers: Vec<&str> = importers
+            .get(component.path.as_path())
+            .into_iter()
+            .flatten()
+            .filter_map(|from| layers.get(*from).map(String::as_str))
+            .collect();
+        let layer = if importer_layers.contains(&"api") || imports.contains(&"persistence") {
+            "domain"
+        } else if imports.is_empty() && importer_layers.len() >= 2 {
+            "utils"
+        } else {
+            continue;
+        };
+        component.layer = layer.to_string();
+        component.basis = LayerBasis::Dependencies;
+    }
+
+    let mut model = ArchitectureModel { components, ..Default::default() };
+    model.link(index);
+    model
+}
+
+/// ファイル名・ディレクトリ名から層を推定（ファイルに近い名前を優先）
+fn layer_by_name(relative: &Path) -> Option<(&'static str, String)> {
+    if relative.extension().and_then(|e| e.to_str()).is_some_and(|e| UI_EXTENSIONS.contains(&e)) {
+        return Some(("ui", relative.extension()?.to_string_lossy().into_owned()));
+    }
+    let segments: Vec<String> = relative
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    for segment in segments.iter().rev() {
+        for word in segment.split(['_', '-', '.']) {
+            if let Some((layer, _)) = NAME_KEYWORDS.iter().find(|(_, words)| words.contains(&word)) {
+                return Some((layer, word.to_string()));
+            }
+        }
+    }
+    None
+}
+
+/// 外部ライブラリのimportから層を推定（`LIBRARY_LAYERS` の順に優先）
+fn layer_by_library(imports: &[String]) -> Option<(&'static str, &'static str)> {
+    LIBRARY_LAYERS.iter().find_map(|(layer, libraries)| {
+        libraries
+            .iter()
+            .find(|library| {
+                imports.iter().any(|import| {
+                    let rest = import.strip_prefix(**library);
+                    rest.is_some_and(|rest| rest.is_empty() || rest.starts_with("::") || rest.starts_with(['/', '.']))
+                })
+            })
+            .map(|library| (*layer, *library))
+    })
+}
+
+/// 層の順位（`other` は最後）
+fn rank(layer: &str) -> usize {
+    LAYERS.iter().position(|l| *l == layer).unwrap_or(LAYERS.len())
+}
+
+/// 層の逆転か（下位の層からAPI・UI層へのimport、ユーティリティから他の層へのimport）
+fn is_violation(from: &str, to: &str) -> bool {
+    if from == "other" || to == "other" || from == to {
+        return false;
+    }
+    (matches!(to, "ui" | "api") && rank(from) > rank(to)) || from == "utils"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApiEndpoint, FileInfo, ModuleInfo};
+
+    fn index(files: &[(&str, &[&str])], dependencies: &[(&str, &str)]) -> Index {
+        let repo = PathBuf::from("/repo");
+        Index {
+            repo_path: repo.clone(),
+            files: files
+                .iter()
+                .map(|(path, imports)| FileInfo {
+                    path: repo.join(path),
+                    dependencies: imports.iter().map(|i| i.to_string()).collect(),
+                    ..Default::default()
+                })
+                .collect(),
+            modules: files
+                .iter()
+                .map(|(path, _)| ModuleInfo { path: repo.join(path), id: path.to_string(), ..Default::default() })
+                .collect(),
+            internal_dependencies: dependencies.iter().fold(BTreeMap::new(), |mut map, (from, to)| {
+                map.entry(repo.join(from)).or_insert_with(Vec::new).push(repo.join(to));
+                map
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_infer_layers() {
+        let mut index = index(
+            &[
+                ("src/server.rs", &["axum::Router"]),
+                ("src/handlers/users.rs", &[]),
+                ("src/billing.rs", &[]),
+                ("src/repo_impl.rs", &["sqlx::PgPool"]),
+                ("src/text.rs", &[]),
+                ("web/App.tsx", &[]),
+                ("src/main.rs", &[]),
+            ],
+            &[
+                ("src/handlers/users.rs", "src/billing.rs"),
+                ("src/billing.rs", "src/repo_impl.rs"),
+                ("src/billing.rs", "src/text.rs"),
+                ("src/repo_impl.rs", "src/text.rs"),
+                ("src/repo_impl.rs", "src/handlers/users.rs"),
+            ],
+        );
+        index.endpoints.push(ApiEndpoint { file: PathBuf::from("/repo/src/server.rs"), ..Default::default() });
+
+        let model = infer_architecture(&index);
+        let layers: Vec<(&str, &str, LayerBasis)> =
+            model.components.iter().map(|c| (c.id.as_str(), c.layer.as_str(), c.basis)).collect();
+        assert_eq!(
+            layers,
+            vec![
+                ("src/server.rs", "api", LayerBasis::Endpoint),
+                ("src/handlers/users.rs", "api", LayerBasis::Name),
+                ("src/billing.rs", "domain", LayerBasis::Dependencies),
+                ("src/repo_impl.rs", "persistence", LayerBasis::Library),
+                ("src/text.rs", "utils", LayerBasis::Dependencies),
+                ("web/App.tsx", "ui", LayerBasis::Name),
+                ("src/main.rs", "other", LayerBasis::None),
+            ]
+        );
+        assert_eq!(model.components[3].evidence, "sqlx");
+        assert_eq!(model.violations, vec![(PathBuf::from("/repo/src/repo_impl.rs"), PathBuf::from("/repo/src/handlers/users.rs"))]);
+        let dependencies: Vec<(&str, &str, usize, bool)> =
+            model.dependencies.iter().map(|d| (d.from.as_str(), d.to.as_str(), d.imports, d.violation)).collect();
+        assert_eq!(
+            dependencies,
+            vec![
+                ("api", "domain", 1, false),
+                ("domain", "persistence", 1, false),
+                ("domain", "utils", 1, false),
+                ("persistence", "api", 1, true),
+                ("persistence", "utils", 1, false),
+            ]
+        );
+
+        let mut model = model;
+        model.reclassify(&index, &[(PathBuf::from("/repo/src/main.rs"), "api".to_string()), (PathBuf::from("/repo/src/text.rs"), "bogus".to_string())], LayerBasis::Llm);
+        assert_eq!(model.layer("api").len(), 3);
+        assert_eq!(model.layer_of(Path::new("/repo/src/text.rs")), Some("utils"));
+    }
+}
@@ -0,0 +1,196 @@
+/*!
+ * ビルドツール検出
+ *
+ * リポジトリ直下のビルドファイルからビルド・テスト・実行コマンドを推定する
+ * - Cargo（Cargo.toml）
+ * - npm/yarn/pnpm（package.jsonのscripts）
+ * - Make（Makefileのターゲット）
+ * - Gradle/Maven（build.gradle(.kts), pom.xml）
+ * - Go（go.mod）、Python（pyproject.toml, requirements.txt）
+ *
+ * 主な仕様:
+ * - 実際に存在するファイル・スクリプト・ターゲットに基づくコマンドのみを返す
+ * - FAQの「どうやってビルドしますか？」「テストはどう実行しますか？」に使用
+ *
+ * 制限事項:
+ * - リポジトリ直下のみを確認（サブパッケージのビルドファイルは対象外）
+ */
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// ビルド・テスト・実行コマンド
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildCommand {
+    /// ツール名（cargo, npm, make, gradle, maven, go, python）
+    pub tool: String,
+    /// コマンドの種類（build|test|run）
+    pub kind: String,
+    /// 実行するコマンド
+    pub command: String,
+    /// 根拠となったファイル（リポジトリルートからの相対パス）
+    pub source: String,
+}
+
+impl BuildCommand {
+    fn new(tool: &str, kind: &str, command: &str, source: &str) -> Self {
+        Self {
+            tool: tool.to_string(),
+            kind: kind.to_string(),
+            command: command.to_string(),
+            source: source.to_string(),
+        }
+    }
+}
+
+/// ビルドファイルからコマンドを検出
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+///
+/// # 戻り値
+/// * `Vec<BuildCommand>` - 検出されたコマンドのリスト
+pub fn detect_build_commands(repo_path: &Path) -> Vec<BuildCommand> {
+    let mut commands = Vec::new();
+
+    if repo_path.join("Cargo.toml").exists() {
+        detect_cargo(repo_path, &mut commands);
+    }
+    if repo_path.join("package.json").exists() {
+        detect_npm(repo_path, &mut commands);
+    }
+    if repo_path.join("Makefile").exists() {
+        detect_make(repo_path, &mut commands);
+    }
+    for gradle_file in ["build.gradle", "build.gradle.kts"] {
+        if repo_path.join(gradle_file).exists() {
+            let gradle = if repo_path.join("gradlew").exists() { "./gradlew" } else { "gradle" };
+            commands.push(BuildCommand::new("gradle", "build", &format!("{} build", gradle), gradle_file));
+            commands.push(BuildCommand::new("gradle", "test", &format!("{} test", gradle), gradle_file));
+            break;
+        }
+    }
+    if repo_path.join("pom.xml").exists() {
+        commands.push(BuildCommand::new("maven", "build", "mvn package", "pom.xml"));
+        commands.push(BuildCommand::new("maven", "test", "mvn test", "pom.xml"));
+    }
+    if repo_path.join("go.mod").exists() {
+        commands.push(BuildCommand::new("go", "build", "go build ./...", "go.mod"));
+        commands.push(BuildCommand::new("go", "test", "go test ./...", "go.mod"));
+    }
+    for py_file in ["pyproject.toml", "requirements.txt"] {
+        if repo_path.join(py_file).exists() {
+            let install = if py_file == "pyproject.toml" { "pip install -e ." } else { "pip install -r requirements.txt" };
+            commands.push(BuildCommand::new("python", "build", install, py_file));
+            commands.push(BuildCommand::new("python", "test", "pytest", py_file));
+            break;
+        }
+    }
+
+    commands
+}
+
+/// Cargoのコマンドを検出
+fn detect_cargo(repo_path: &Path, commands: &mut Vec<BuildCommand>) {
+    let manifest = std::fs::read_to_string(repo_path.join("Cargo.toml")).unwrap_or_default();
+    let is_workspace = manifest.contains("[workspace]");
+
+    commands.push(BuildCommand::new("cargo", "build", "cargo build --release", "Cargo.toml"));
+    let test = if is_workspace { "cargo test --workspace" } else { "cargo test" };
+    commands.push(BuildCommand::new("cargo", "test", test, "Cargo.toml"));
+
+    if repo_path.join("src").join("main.rs").exists() || manifest.contains("[[bin]]") {
+        commands.push(BuildCommand::new("cargo", "run", "cargo run", "Cargo.toml"));
+    }
+}
+
+/// npm系のコマンドを検出（lockfileからyarn/pnpmを判定）
+fn detect_npm(repo_path: &Path, commands: &mut Vec<BuildCommand>) {
+    let content = std::fs::read_to_string(repo_path.join("package.json")).unwrap_or_default();
+    let json: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    let runner = if repo_path.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if repo_path.join("yarn.lock").exists() {
+        "yarn"
+    } else {
+        "npm"
+    };
+
+    let scripts = match json.get("scripts").and_then(|s| s.as_object()) {
+        Some(s) => s,
+        None => return,
+    };
+
+    commands.push(BuildCommand::new(runner, "build", &format!("{} install", runner), "package.json"));
+    for (script, kind) in [("build", "build"), ("test", "test"), ("start", "run"), ("dev", "run")] {
+        if scripts.contains_key(script) {
+            let command = match (runner, script) {
+                ("npm", "test") | ("npm", "start") => format!("npm {}", script),
+                ("npm", _) => format!("npm run {}", script),
+                _ => format!("{} {}", runner, script),
+            };
+            commands.push(BuildCommand::new(runner, kind, &command, "package.json"));
+        }
+    }
+}
+
+/// Makefileのターゲットを検出
+fn detect_make(repo_path: &Path, commands: &mut Vec<BuildCommand>) {
+    let content = std::fs::read_to_string(repo_path.join("Makefile")).unwrap_or_default();
+    let target_re = Regex::new(r"(?m)^([A-Za-z0-9_.-]+)\s*:([^=]|$)").unwrap();
+    let targets: Vec<&str> = target_re
+        .captures_iter(&content)
+        .filter_map(|c| c.get(1))
+        .map(|m| m.as_str())
+        .collect();
+
+    if targets.contains(&"build") {
+        commands.push(BuildCommand::new("make", "build", "make build", "Makefile"));
+    } else if !targets.is_empty() {
+        commands.push(BuildCommand::new("make", "build", "make", "Makefile"));
+    }
+    if targets.contains(&"test") {
+        commands.push(BuildCommand::new("make", "test", "make test", "Makefile"));
+    } else if targets.contains(&"check") {
+        commands.push(BuildCommand::new("make", "test", "make check", "Makefile"));
+    }
+    if targets.contains(&"run") {
+        commands.push(BuildCommand::new("make", "run", "make run", "Makefile"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_npm_and_make_commands() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-build-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"scripts":{"build":"tsc","test":"jest"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("Makefile"), "build:\n\tgo build\n\ntest:\n\tgo test\n").unwrap();
+
+        let commands = detect_build_commands(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let find = |kind: &str, tool: &str| {
+            commands
+                .iter()
+                .find(|c| c.kind == kind && c.tool == tool)
+                .map(|c| c.command.clone())
+        };
+        assert_eq!(find("build", "make"), Some("make build".to_string()));
+        assert_eq!(find("test", "make"), Some("make test".to_string()));
+        assert_eq!(find("test", "npm"), Some("npm test".to_string()));
+        assert!(commands.iter().any(|c| c.command == "npm run build"));
+    }
+}
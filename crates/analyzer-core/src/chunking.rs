@@ -0,0 +1,144 @@
+/*!
+ * ソースコードのチャンク分割とトークン化
+ *
+ * 全文検索インデックス（searchモジュール）とベクトルインデックス（embeddingsモジュール）で共有する
+ *
+ * 主な仕様:
+ * - チャンクは行単位で、`index.chunk-tokens` トークンごとに `index.chunk-overlap` トークン重ねて分割
+ * - トークン: 英数字は単語単位（小文字化）、日本語（かな・漢字）は文字bigram
+ *
+ * 制限事項:
+ * - 1行が `chunk-tokens` を超える場合も行の途中では分割しない
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// チャンク分割の設定
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Chunking {
+    pub(crate) chunk_tokens: usize,
+    pub(crate) chunk_overlap: usize,
+}
+
+impl Chunking {
+    /// 設定値からチャンク分割の設定を作成（重なりは `chunk_tokens` 未満に丸める）
+    pub(crate) fn new(chunk_tokens: usize, chunk_overlap: usize) -> Self {
+        Self {
+            chunk_tokens: chunk_tokens.max(1),
+            chunk_overlap: chunk_overlap.min(chunk_tokens.saturating_sub(1)),
+        }
+    }
+}
+
+/// ファイルのチャンク
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Chunk {
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+    pub(crate) text: String,
+}
+
+/// トークン（元のテキスト内のバイト範囲と正規化した文字列）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TokenSpan {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) text: String,
+}
+
+/// 内容を行単位のチャンクに分割
+pub(crate) fn chunk_content(content: &str, chunking: Chunking) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let counts: Vec<usize> = lines.iter().map(|line| tokenize(line).len()).collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < lines.len() && (tokens < chunking.chunk_tokens || end == start) {
+            tokens += counts[end];
+            end += 1;
+        }
+        chunks.push(Chunk {
+            start_line: start + 1,
+            end_line: end,
+            text: lines[start..end].join("\n"),
+        });
+        if end >= lines.len() {
+            break;
+        }
+
+        // 末尾の行を重ねて次のチャンクを開始（必ず1行以上進める）
+        let mut next = end;
+        let mut overlap = 0;
+        while next > start + 1 && overlap < chunking.chunk_overlap {
+            next -= 1;
+            overlap += counts[next];
+        }
+        start = next;
+    }
+
+    chunks
+}
+
+/// 日本語の文字（かな・漢字）かを判定
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{ff66}'..='\u{ff9f}')
+}
+
+/// テキストをトークンに分割（英数字は単語、日本語は文字bigram）
+pub(crate) fn tokenize(text: &str) -> Vec<TokenSpan> {
+    let mut tokens = Vec::new();
+    let mut push = |from: usize, to: usize, text: String| tokens.push(TokenSpan { from, to, text });
+
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let end_of = |i: usize| chars.get(i + 1).map(|(o, _)| *o).unwrap_or(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let (offset, c) = chars[i];
+        if c.is_alphanumeric() && !is_cjk(c) {
+            let mut j = i;
+            while j + 1 < chars.len() && (chars[j + 1].1.is_alphanumeric() || chars[j + 1].1 == '_') && !is_cjk(chars[j + 1].1) {
+                j += 1;
+            }
+            let to = end_of(j);
+            push(offset, to, text[offset..to].to_lowercase());
+            i = j + 1;
+        } else if is_cjk(c) {
+            let mut j = i;
+            while j + 1 < chars.len() && is_cjk(chars[j + 1].1) {
+                j += 1;
+            }
+            if i == j {
+                push(offset, end_of(i), c.to_string());
+            } else {
+                for (k, &(from, _)) in chars.iter().enumerate().take(j).skip(i) {
+                    let to = end_of(k + 1);
+                    push(from, to, text[from..to].to_string());
+                }
+            }
+            i = j + 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_content_overlaps() {
+        let content = "a b c\nd e f\ng h i\nj k l\n";
+        let chunks = chunk_content(content, Chunking { chunk_tokens: 6, chunk_overlap: 3 });
+        let ranges: Vec<(usize, usize)> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(1, 2), (2, 3), (3, 4)]);
+
+        let words: Vec<String> = tokenize("build_graph 依存グラフ").into_iter().map(|t| t.text).collect();
+        assert_eq!(words, vec!["build_graph", "依存", "存グ", "グラ", "ラフ"]);
+    }
+}
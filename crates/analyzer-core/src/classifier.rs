@@ -0,0 +1,79 @@
+/*!
+ * 独自のファイル分類
+ *
+ * 埋め込み先のアプリケーションが独自の形式のファイル（社内のDSLなど）を解析対象に加えるための拡張点
+ * - `Analyzer::register_classifier` で分類関数を登録する
+ * - 分類されたファイルは指定した言語のラベルで、要約・Wiki・スライド・検索などすべての出力に現れる
+ *
+ * 主な仕様:
+ * - 分類関数はファイルのパスと先頭 `HEAD_BYTES` バイト（UTF-8として不正なバイトは置換）を受け取る
+ * - 登録順に呼び出し、最初に `Some` を返した分類を使う（すべて `None` なら組み込みの判定）
+ * - 設定の `language-overrides` に一致するファイルは分類関数を呼ばない（リポジトリごとの指定を優先）
+ *
+ * 制限事項:
+ * - 独自の言語にはtree-sitterの文法がないため、importとシンボルは抽出されない（行数などのメトリクスのみ）
+ * - 分類関数を登録すると、走査したすべてのファイルの先頭を読むため解析が遅くなる
+ */
+
+use std::io::Read;
+use std::path::Path;
+
+/// 分類関数に渡すファイルの先頭のバイト数
+pub const HEAD_BYTES: usize = 1024;
+
+/// ファイルの分類
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    /// 言語のラベル（`FileInfo::language` などにそのまま使われる）
+    pub language: String,
+    /// モジュールとしてWiki・スライドに掲載するか
+    pub is_module: bool,
+}
+
+/// ファイルの分類関数（ファイルのパスと先頭の内容を受け取り、分類しない場合は `None`）
+pub type Classifier = fn(&Path, &str) -> Option<Classification>;
+
+/// 登録された分類関数でファイルを分類
+///
+/// # 引数
+/// * `classifiers` - 分類関数（登録順）
+/// * `path` - ファイルパス
+///
+/// # 戻り値
+/// * `Option<Classification>` - 最初に分類できた分類関数の結果（読み込めない場合・どれも分類しない場合はNone）
+pub(crate) fn classify(classifiers: &[Classifier], path: &Path) -> Option<Classification> {
+    if classifiers.is_empty() {
+        return None;
+    }
+    let mut head = Vec::with_capacity(HEAD_BYTES);
+    std::fs::File::open(path).ok()?.take(HEAD_BYTES as u64).read_to_end(&mut head).ok()?;
+    let head = String::from_utf8_lossy(&head);
+    classifiers.iter().find_map(|classifier| classifier(path, &head))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow_dsl(path: &Path, head: &str) -> Option<Classification> {
+        (path.extension().is_some_and(|e| e == "flow") && head.starts_with("workflow "))
+            .then(|| Classification { language: "flow".to_string(), is_module: true })
+    }
+
+    #[test]
+    fn test_classify_uses_first_match() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-classifier-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("order.flow"), "workflow order {\n}\n").unwrap();
+        std::fs::write(dir.join("notes.flow"), "# memo\n").unwrap();
+
+        let fallback: Classifier = |_, _| Some(Classification { language: "text".to_string(), is_module: false });
+        let classifiers: Vec<Classifier> = vec![workflow_dsl, fallback];
+        assert_eq!(classify(&classifiers, &dir.join("order.flow")).unwrap().language, "flow");
+        assert_eq!(classify(&classifiers, &dir.join("notes.flow")).unwrap().language, "text");
+        assert_eq!(classify(&classifiers[..1], &dir.join("notes.flow")), None);
+        assert_eq!(classify(&[], &dir.join("order.flow")), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
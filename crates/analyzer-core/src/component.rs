@@ -0,0 +1,195 @@
+/*!
+ * コンポーネントの種類の推定
+ *
+ * モジュールをファイル名・ディレクトリ名から種類（API・DB・UI・ワーカー・インフラなど）に分類し、
+ * 種類ごとのアイコンと色（`COMPONENT_STYLES`）を図・スライドで共通に使う
+ * - 分類は `ModuleInfo::component` に保持し、要約の役割の推定（`Summarizer::infer_role`）も同じ分類を使う
+ *
+ * 主な仕様:
+ * - ファイル名を語（`_`・`-`・`.`・キャメルケースの区切り）に分け、種類の語に一致するものを探す
+ * - ファイル名で決まらない場合は拡張子（`.tsx` → UI、`.tf` → インフラ、`.sql` → DB）、次に近い親ディレクトリ（2階層まで）の名前
+ * - ファイル名の語は `COMPONENT_KEYWORDS` の順に照合する（`user_service_config` は設定）
+ *
+ * 制限事項:
+ * - 名前による推定のため、語を含まないモジュールは `other`
+ * - 層（`ArchitectureModel`）とは独立した分類で、importや内部依存は見ない
+ */
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// コンポーネントの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ComponentKind {
+    Config,
+    Api,
+    Util,
+    Model,
+    Service,
+    Db,
+    Ui,
+    Worker,
+    Infra,
+    #[default]
+    Other,
+}
+
+/// 種類ごとの語（照合する順）
+const COMPONENT_KEYWORDS: &[(ComponentKind, &[&str])] = &[
+    (ComponentKind::Config, &["config", "configs", "configuration", "settings", "setting", "env"]),
+    (ComponentKind::Api, &["api", "apis", "route", "routes", "router", "routers", "handler", "handlers", "controller", "controllers", "endpoint", "endpoints", "resolver", "resolvers"]),
+    (ComponentKind::Util, &["util", "utils", "helper", "helpers", "common", "shared"]),
+    (ComponentKind::Model, &["model", "models", "schema", "schemas", "entity", "entities", "types"]),
+    (ComponentKind::Service, &["service", "services", "business", "usecase", "usecases", "domain"]),
+    (ComponentKind::Db, &["db", "database", "repository", "repositories", "dao", "migration", "migrations", "store", "storage", "sql", "orm"]),
+    (ComponentKind::Ui, &["ui", "view", "views", "page", "pages", "component", "components", "screen", "screens", "widget", "widgets", "layout", "frontend"]),
+    (ComponentKind::Worker, &["worker", "workers", "job", "jobs", "task", "tasks", "queue", "consumer", "consumers", "cron", "scheduler"]),
+    (ComponentKind::Infra, &["infra", "deploy", "deployment", "docker", "dockerfile", "terraform", "k8s", "kubernetes", "helm", "ci"]),
+];
+
+/// 拡張子で決まる種類
+const EXTENSION_KINDS: &[(ComponentKind, &[&str])] = &[
+    (ComponentKind::Ui, &["tsx", "jsx", "vue", "svelte"]),
+    (ComponentKind::Infra, &["tf", "hcl"]),
+    (ComponentKind::Db, &["sql", "prisma"]),
+];
+
+/// 種類ごとのアイコンと色（図のノードの塗り・スライドの見出し）
+pub const COMPONENT_STYLES: &[(ComponentKind, &str, &str)] = &[
+    (ComponentKind::Config, "🔧", "#fff8e1"),
+    (ComponentKind::Api, "🔌", "#e3f2fd"),
+    (ComponentKind::Util, "🧰", "#f5f5f5"),
+    (ComponentKind::Model, "📐", "#fce4ec"),
+    (ComponentKind::Service, "🧩", "#e0f7fa"),
+    (ComponentKind::Db, "🗄️", "#fff3e0"),
+    (ComponentKind::Ui, "🖥️", "#f3e5f5"),
+    (ComponentKind::Worker, "⚙️", "#e8f5e9"),
+    (ComponentKind::Infra, "☁️", "#eceff1"),
+    (ComponentKind::Other, "📄", "#ffffff"),
+];
+
+impl ComponentKind {
+    /// ファイルのパスから種類を推定
+    ///
+    /// # 引数
+    /// * `path` - ファイルのパス（絶対パス・相対パスのどちらでもよい）
+    ///
+    /// # 戻り値
+    /// * `ComponentKind` - 推定した種類（語が見つからない場合は `Other`）
+    pub fn classify(path: &Path) -> Self {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        if let Some(kind) = keyword_kind(&name_words(stem)) {
+            return kind;
+        }
+        let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase();
+        if let Some((kind, _)) = EXTENSION_KINDS.iter().find(|(_, extensions)| extensions.contains(&extension.as_str())) {
+            return *kind;
+        }
+        path.parent()
+            .into_iter()
+            .flat_map(|dir| dir.components().rev().take(2))
+            .find_map(|dir| keyword_kind(&name_words(&dir.as_os_str().to_string_lossy())))
+            .unwrap_or_default()
+    }
+
+    /// 種類の名前（`api`・`db` など、図のクラス名にも使う）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Api => "api",
+            Self::Util => "util",
+            Self::Model => "model",
+            Self::Service => "service",
+            Self::Db => "db",
+            Self::Ui => "ui",
+            Self::Worker => "worker",
+            Self::Infra => "infra",
+            Self::Other => "other",
+        }
+    }
+
+    /// アイコン（絵文字）
+    pub fn icon(self) -> &'static str {
+        self.style().0
+    }
+
+    /// 図のノードの塗りの色（`#rrggbb`）
+    pub fn color(self) -> &'static str {
+        self.style().1
+    }
+
+    fn style(self) -> (&'static str, &'static str) {
+        COMPONENT_STYLES
+            .iter()
+            .find(|(kind, _, _)| *kind == self)
+            .map(|(_, icon, color)| (*icon, *color))
+            .unwrap_or(("📄", "#ffffff"))
+    }
+}
+
+/// 語のうち最初に一致した種類（`COMPONENT_KEYWORDS` の順）
+fn keyword_kind(words: &[String]) -> Option<ComponentKind> {
+    COMPONENT_KEYWORDS
+        .iter()
+        .find(|(_, keywords)| words.iter().any(|w| keywords.contains(&w.as_str())))
+        .map(|(kind, _)| *kind)
+}
+
+/// 名前を小文字の語に分ける（`UserRepository` → `user`・`repository`、`api_routes` → `api`・`routes`）
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            previous_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && previous_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    words.extend((!word.is_empty()).then_some(word));
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_by_name_extension_and_directory() {
+        let cases = [
+            ("src/config.ts", ComponentKind::Config),
+            ("src/UserRepository.java", ComponentKind::Db),
+            ("app/api_routes.py", ComponentKind::Api),
+            ("app/email_worker.py", ComponentKind::Worker),
+            ("web/src/Button.tsx", ComponentKind::Ui),
+            ("deploy/main.tf", ComponentKind::Infra),
+            ("services/billing/invoice.go", ComponentKind::Service),
+            ("src/handlers/order.rs", ComponentKind::Api),
+            ("src/user_service_config.rs", ComponentKind::Config),
+            ("/home/dev/infra/repo/src/main.rs", ComponentKind::Other),
+            ("src/restore.rs", ComponentKind::Other),
+        ];
+        for (path, kind) in cases {
+            assert_eq!(ComponentKind::classify(Path::new(path)), kind, "{}", path);
+        }
+        assert_eq!(name_words("HTTPServer2Api"), vec!["httpserver2", "api"]);
+    }
+
+    #[test]
+    fn test_every_kind_has_style() {
+        for (kind, _) in COMPONENT_KEYWORDS {
+            assert!(COMPONENT_STYLES.iter().any(|(k, _, _)| k == kind), "{:?}", kind);
+        }
+        assert_eq!(ComponentKind::Db.icon(), "🗄️");
+        assert_eq!(ComponentKind::Other.color(), "#ffffff");
+        assert_eq!(serde_json::to_string(&ComponentKind::Worker).unwrap(), "\"worker\"");
+    }
+}
@@ -0,0 +1,361 @@
+/*!
+ * ビルド条件の検出
+ *
+ * Cargoのフィーチャー・`cfg`、Cのプリプロセッサの条件、JavaScriptの環境変数による分岐を検出し、
+ * 条件付きでコンパイル・読み込みされるファイルに条件を記録する（`FileInfo::build_conditions`）
+ * - Wikiのアーキテクチャの章で「この機能はfeature X有効時のみ」のように、無条件の構成と区別して示す
+ *
+ * 主な仕様:
+ * - Rust: `#[cfg(...)]` を付けた `mod name;` の宣言は子モジュールのファイルに、ファイル先頭の `#![cfg(...)]` はそのファイルに記録
+ *   - `feature = "..."` を含む条件は `cargo-feature`、それ以外（`unix`・`target_os` など）は `cfg`
+ *   - `cfg(test)` はテストのため記録しない
+ * - C/C++: `#ifdef`・`#ifndef`・`#if`（`#elif`・`#else` を含む）の中の `#include "..."` はインクルードしたファイルに、
+ *   ファイルのコード全体を囲む条件はそのファイルに記録（インクルードガードは除く）
+ * - JavaScript/TypeScript: `process.env` を含む `if` のブロック（`else` を含む）・同じ行の三項演算子・`&&` の中の
+ *   `require(...)`・`import(...)` を読み込んだファイルに記録
+ * - 入れ子の条件は ` && ` で結合し、条件を書いたファイルと行を併記する
+ *
+ * 制限事項:
+ * - 行単位の検出のため、複数行にわたる `#[cfg(...)]`・`if` の条件は検出しない
+ * - 条件付きのモジュールの子モジュールには条件を引き継がない
+ * - 文字列・コメント中の波括弧も数える（JavaScriptのブロックの範囲がずれることがある）
+ */
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// ビルド・読み込みの条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildCondition {
+    /// 種類（cargo-feature|cfg|preprocessor|env）
+    pub kind: String,
+    /// 条件式（`feature = "search"`・`defined(USE_SSL)`・`process.env.NODE_ENV === 'production'` など）
+    pub expr: String,
+    /// 条件を書いたファイル（リポジトリルートからの相対パス）
+    pub declared_in: PathBuf,
+    /// 条件を書いた行（1始まり）
+    pub line: usize,
+}
+
+impl BuildCondition {
+    /// Cargoのフィーチャー1つだけの条件の場合、そのフィーチャー名
+    pub fn feature(&self) -> Option<&str> {
+        FEATURE_ONLY.captures(&self.expr).and_then(|c| c.get(1)).map(|m| m.as_str())
+    }
+
+    /// 条件の説明（「feature `search` 有効時のみ」など）
+    pub fn label(&self) -> String {
+        if let Some(feature) = self.feature() {
+            return format!("feature `{}` 有効時のみ", feature);
+        }
+        match self.kind.as_str() {
+            "cargo-feature" | "cfg" => format!("`cfg({})` の場合のみ", self.expr),
+            "preprocessor" => match DEFINED_ONLY.captures(&self.expr) {
+                Some(c) if c.get(1).is_some() => format!("マクロ `{}` 未定義時のみ", &c[2]),
+                Some(c) => format!("マクロ `{}` 定義時のみ", &c[2]),
+                None => format!("`#if {}` の場合のみ", self.expr),
+            },
+            "env" => format!("環境変数の条件 `{}` を満たす場合のみ", self.expr),
+            _ => format!("`{}` の場合のみ", self.expr),
+        }
+    }
+}
+
+/// 条件付きのimport（解決前）
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ConditionalImport {
+    /// import文字列（Rustの `mod` 宣言は `self::name`）
+    pub import: String,
+    pub condition: BuildCondition,
+}
+
+/// 1ファイルから検出した条件
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct DetectedConditions {
+    /// ファイル自身の条件
+    pub own: Vec<BuildCondition>,
+    /// 条件付きのimport
+    pub imports: Vec<ConditionalImport>,
+}
+
+/// `feature = "x"` だけの条件
+static FEATURE_ONLY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^feature\s*=\s*"([^"]+)"$"#).unwrap());
+/// `defined(X)`・`!defined(X)` だけの条件
+static DEFINED_ONLY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(!)?defined\((\w+)\)$").unwrap());
+/// `#[cfg(...)]`・`#![cfg(...)]`（1行）
+static RUST_CFG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^#(!)?\[cfg\((.*)\)\]$").unwrap());
+/// `mod name;`（可視性付きを含む）
+static RUST_MOD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_][A-Za-z0-9_]*)\s*;").unwrap());
+/// Cのプリプロセッサの指令
+static C_DIRECTIVE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^#\s*(\w+)\s*(.*?)\s*(?://.*|/\*.*)?$").unwrap());
+/// `#include "..."`
+static C_INCLUDE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^"([^"]+)""#).unwrap());
+/// `process.env` を含む `if (...)`
+static JS_ENV_IF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bif\s*\((.*process\.env\b.*)\)").unwrap());
+/// `process.env.X`（比較を含む）
+static JS_ENV_EXPR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"!?process\.env(?:\.\w+|\[['"]\w+['"]\])(?:\s*(?:===|!==|==|!=)\s*(?:'[^']*'|"[^"]*"|\w+))?"#).unwrap()
+});
+/// `require('...')`・`import('...')`
+static JS_LOAD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\b(?:require|import)\(\s*['"]([^'"]+)['"]\s*\)"#).unwrap());
+
+/// ファイルのビルド条件を検出
+///
+/// # 引数
+/// * `relative` - リポジトリルートからの相対パス
+/// * `language` - 言語
+/// * `content` - ファイル内容
+///
+/// # 戻り値
+/// * `DetectedConditions` - ファイル自身の条件と条件付きのimport（対応しない言語は空）
+pub(crate) fn detect_conditions(relative: &Path, language: &str, content: &str) -> DetectedConditions {
+    match language {
+        "rs" => detect_rust(relative, content),
+        "c" | "cpp" => detect_c(relative, content),
+        "ts" | "js" => detect_js(relative, content),
+        _ => DetectedConditions::default(),
+    }
+}
+
+fn condition(kind: &str, expr: String, relative: &Path, line: usize) -> BuildCondition {
+    BuildCondition { kind: kind.to_string(), expr, declared_in: relative.to_path_buf(), line }
+}
+
+fn detect_rust(relative: &Path, content: &str) -> DetectedConditions {
+    let mut detected = DetectedConditions::default();
+    let cfg = |expr: &str, line: usize| {
+        let kind = if expr.contains("feature") { "cargo-feature" } else { "cfg" };
+        condition(kind, expr.trim().to_string(), relative, line)
+    };
+    let mut pending: Vec<BuildCondition> = Vec::new();
+    let mut seen_item = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if let Some(captures) = RUST_CFG.captures(trimmed) {
+            let expr = &captures[2];
+            if expr.trim() == "test" {
+                continue;
+            }
+            match captures.get(1) {
+                Some(_) if !seen_item => detected.own.push(cfg(expr, i + 1)),
+                Some(_) => {}
+                None => pending.push(cfg(expr, i + 1)),
+            }
+            continue;
+        }
+        // 属性・コメントは宣言との間にあってもよい
+        if trimmed.is_empty() || trimmed.starts_with("#") || trimmed.starts_with("//") {
+            continue;
+        }
+        seen_item = true;
+        if let Some(captures) = RUST_MOD.captures(trimmed) {
+            for condition in pending.drain(..) {
+                detected.imports.push(ConditionalImport { import: format!("self::{}", &captures[1]), condition });
+            }
+        }
+        pending.clear();
+    }
+    detected
+}
+
+fn detect_c(relative: &Path, content: &str) -> DetectedConditions {
+    let mut detected = DetectedConditions::default();
+    // 条件のスタック（インクルードガードはNone）
+    let mut stack: Vec<Option<BuildCondition>> = Vec::new();
+    // ファイル全体を囲む条件の候補（コードの行がすべて同じ最も外側の条件の中にあるか）
+    let mut outermost: Option<BuildCondition> = None;
+    let mut whole_file = true;
+    let mut has_code = false;
+    let lines: Vec<&str> = content.lines().collect();
+    let active = |stack: &[Option<BuildCondition>]| -> Vec<BuildCondition> { stack.iter().flatten().cloned().collect() };
+
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        let Some(captures) = C_DIRECTIVE.captures(trimmed) else {
+            let is_comment = trimmed.starts_with("//") || trimmed.starts_with("/*") || trimmed.starts_with('*');
+            if !trimmed.is_empty() && !is_comment {
+                has_code = true;
+                let conditions = active(&stack);
+                match (conditions.first(), &outermost) {
+                    (None, _) => whole_file = false,
+                    (Some(first), Some(outer)) if first != outer => whole_file = false,
+                    (Some(first), None) => outermost = Some(first.clone()),
+                    _ => {}
+                }
+            }
+            continue;
+        };
+        let (directive, argument) = (&captures[1], captures[2].to_string());
+        let preprocessor = |expr: String| Some(condition("preprocessor", expr, relative, i + 1));
+        match directive {
+            "ifndef" => {
+                // `#ifndef X` の直後の `#define X` はインクルードガード
+                let next = lines[i + 1..].iter().map(|l| l.trim()).find(|l| !l.is_empty());
+                let guard = next.is_some_and(|l| {
+                    C_DIRECTIVE.captures(l).is_some_and(|c| &c[1] == "define" && c[2].split_whitespace().next() == Some(argument.as_str()))
+                });
+                stack.push(if guard { None } else { preprocessor(format!("!defined({})", argument)) });
+            }
+            "ifdef" => stack.push(preprocessor(format!("defined({})", argument))),
+            "if" => stack.push(preprocessor(argument)),
+            "elif" => {
+                if let Some(top) = stack.last_mut() {
+                    *top = preprocessor(argument);
+                }
+            }
+            "else" => {
+                if let Some(Some(top)) = stack.last_mut() {
+                    top.expr = negate(&top.expr);
+                }
+            }
+            "endif" => {
+                stack.pop();
+            }
+            "include" => {
+                let conditions = active(&stack);
+                if let (Some(include), false) = (C_INCLUDE.captures(&argument), conditions.is_empty()) {
+                    detected.imports.push(ConditionalImport { import: include[1].to_string(), condition: combine(conditions) });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let (true, true, Some(outer)) = (whole_file, has_code, outermost) {
+        detected.own.push(outer);
+    }
+    detected
+}
+
+fn detect_js(relative: &Path, content: &str) -> DetectedConditions {
+    let mut detected = DetectedConditions::default();
+    // ブロックの条件と、ブロックを開いた時点の深さ
+    let mut stack: Vec<(BuildCondition, usize)> = Vec::new();
+    let mut depth = 0usize;
+    for (i, line) in content.lines().enumerate() {
+        let env_if = JS_ENV_IF.captures(line).map(|c| c[1].trim().to_string());
+
+        // 同じ行の条件（`if` の本体・三項演算子・`&&`）
+        for load in JS_LOAD.captures_iter(line) {
+            let position = load.get(0).map_or(0, |m| m.start());
+            let mut conditions: Vec<BuildCondition> = stack.iter().map(|(c, _)| c.clone()).collect();
+            let inline = env_if.clone().or_else(|| {
+                JS_ENV_EXPR.find(line).filter(|m| m.start() < position).map(|m| m.as_str().to_string())
+            });
+            if let Some(expr) = inline {
+                // 三項演算子の `:` より後は条件を満たさない場合
+                let question = line.find('?').filter(|q| *q < position);
+                let negated = question.is_some_and(|q| line[q..position].contains(':'));
+                let expr = if negated { negate(&expr) } else { expr };
+                conditions.push(condition("env", expr, relative, i + 1));
+            }
+            if !conditions.is_empty() {
+                detected.imports.push(ConditionalImport { import: load[1].to_string(), condition: combine(conditions) });
+            }
+        }
+
+        // ブロックの開始・終了
+        let has_else = line.contains("else") && !line.contains("else if");
+        let mut pending = env_if;
+        let mut closed: Option<BuildCondition> = None;
+        for c in line.chars() {
+            match c {
+                '{' => {
+                    if let Some(expr) = pending.take() {
+                        stack.push((condition("env", expr, relative, i + 1), depth));
+                    } else if let (true, Some(mut previous)) = (has_else, closed.take()) {
+                        previous.expr = negate(&previous.expr);
+                        previous.line = i + 1;
+                        stack.push((previous, depth));
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if stack.last().is_some_and(|(_, open)| *open == depth) {
+                        closed = stack.pop().map(|(c, _)| c);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    detected
+}
+
+/// 条件を否定する
+fn negate(expr: &str) -> String {
+    match expr.strip_prefix("!(").and_then(|e| e.strip_suffix(')')) {
+        Some(inner) => inner.to_string(),
+        None if expr.starts_with("!defined(") => expr[1..].to_string(),
+        None if DEFINED_ONLY.is_match(expr) => format!("!{}", expr),
+        None => format!("!({})", expr),
+    }
+}
+
+/// 入れ子の条件を ` && ` で結合（最も内側の条件の位置を使う）
+fn combine(conditions: Vec<BuildCondition>) -> BuildCondition {
+    let mut iter = conditions.into_iter();
+    let mut combined = iter.next().expect("条件が空です");
+    for inner in iter {
+        combined.expr = format!("{} && {}", combined.expr, inner.expr);
+        combined.line = inner.line;
+        if inner.kind != combined.kind {
+            combined.kind = inner.kind;
+        }
+    }
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imports(detected: &DetectedConditions) -> Vec<(&str, &str, usize)> {
+        detected.imports.iter().map(|i| (i.import.as_str(), i.condition.expr.as_str(), i.condition.line)).collect()
+    }
+
+    #[test]
+    fn test_detect_rust_cfg_modules() {
+        let content = "//! crate\n#![cfg(unix)]\n\nmod always;\n#[cfg(feature = \"search\")]\n/// 全文検索\npub mod search;\n#[cfg(test)]\nmod tests;\n#[cfg(feature = \"x\")]\nfn not_a_module() {}\nmod plain;\n";
+        let detected = detect_conditions(Path::new("src/lib.rs"), "rs", content);
+        assert_eq!(detected.own.len(), 1);
+        assert_eq!(detected.own[0].label(), "`cfg(unix)` の場合のみ");
+        assert_eq!(imports(&detected), vec![("self::search", "feature = \"search\"", 5)]);
+        assert_eq!(detected.imports[0].condition.kind, "cargo-feature");
+        assert_eq!(detected.imports[0].condition.label(), "feature `search` 有効時のみ");
+    }
+
+    #[test]
+    fn test_detect_c_guards() {
+        let header = "#ifndef NET_H\n#define NET_H\n#ifdef USE_SSL\n#include \"tls.h\"\n#else\n#include \"plain.h\"\n#endif\n#include \"common.h\"\n#endif\n";
+        let detected = detect_conditions(Path::new("net.h"), "c", header);
+        assert!(detected.own.is_empty());
+        assert_eq!(imports(&detected), vec![("tls.h", "defined(USE_SSL)", 3), ("plain.h", "!defined(USE_SSL)", 3)]);
+
+        let source = "/* Windows only */\n#if defined(_WIN32)\n#include <windows.h>\nint run(void) { return 0; }\n#endif\n";
+        let detected = detect_conditions(Path::new("win.c"), "c", source);
+        assert_eq!(detected.own[0].label(), "マクロ `_WIN32` 定義時のみ");
+        assert!(detect_conditions(Path::new("a.c"), "c", "#ifdef X\nint a;\n#endif\nint b;\n").own.is_empty());
+    }
+
+    #[test]
+    fn test_detect_js_env_branches() {
+        let content = "const base = require('./base');\nif (process.env.NODE_ENV === 'production') {\n  module.exports = require('./prod');\n} else {\n  module.exports = require('./dev');\n}\nconst mock = process.env.USE_MOCK ? require('./mock') : require('./real');\nprocess.env.DEBUG && import('./debug');\n";
+        let detected = detect_conditions(Path::new("src/config.js"), "js", content);
+        assert_eq!(
+            imports(&detected),
+            vec![
+                ("./prod", "process.env.NODE_ENV === 'production'", 2),
+                ("./dev", "!(process.env.NODE_ENV === 'production')", 4),
+                ("./mock", "process.env.USE_MOCK", 7),
+                ("./real", "!(process.env.USE_MOCK)", 7),
+                ("./debug", "process.env.DEBUG", 8),
+            ]
+        );
+        assert_eq!(detected.imports[0].condition.label(), "環境変数の条件 `process.env.NODE_ENV === 'production'` を満たす場合のみ");
+    }
+}
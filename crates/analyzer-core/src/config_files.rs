@@ -0,0 +1,487 @@
+/*!
+ * 解析対象のリポジトリ自身の設定ファイルの解析
+ *
+ * ソースコードとして解析しない設定ファイルを読み、何を制御しているかを抽出する
+ * - TypeScript（tsconfig.json・tsconfig.*.json・jsconfig.json）のコンパイラーオプション
+ * - Cargo（Cargo.toml）のフィーチャーとビルドプロファイル
+ * - 環境変数の例（.env.example・.env.sample・.env.template・.env.dist）
+ * - webpack（webpack.config.*・webpack.*.js）のエントリー・出力先・ローダー・プラグイン
+ *
+ * 主な仕様:
+ * - 設定項目ごとに値と説明を保持する（説明は直前のコメント、なければよく使う項目の組み込みの説明）
+ * - tsconfigのコメント・末尾のカンマは取り除いてから読む
+ * - 環境変数の例のうち、秘密の値（`*_SECRET`・`*_TOKEN`・`*PASSWORD` など）は値を伏せる
+ * - 設定項目のないCargo.toml（フィーチャー・プロファイルを定義しないもの）は対象外
+ *
+ * 制限事項:
+ * - 実際の `.env` は秘密の値を含むため読まない
+ * - webpackの設定はJavaScriptを実行せず正規表現で抽出する（関数で組み立てる設定は読めない）
+ * - tsconfigの `extends` の継承元は読まない
+ */
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::Diagnostics;
+
+/// 環境変数の例として読むファイル名
+pub(crate) const ENV_EXAMPLE_FILES: &[&str] = &[".env.example", ".env.sample", ".env.template", ".env.dist", "example.env"];
+
+/// よく使うtsconfigのコンパイラーオプションの説明
+const TS_OPTIONS: &[(&str, &str)] = &[
+    ("target", "出力するJavaScriptのバージョン"),
+    ("module", "出力するモジュール形式"),
+    ("moduleResolution", "importの解決方法"),
+    ("lib", "型定義を読み込む標準ライブラリ"),
+    ("strict", "厳格な型チェックをまとめて有効にする"),
+    ("noImplicitAny", "型を推論できない `any` をエラーにする"),
+    ("strictNullChecks", "`null`・`undefined` を別の型として扱う"),
+    ("outDir", "コンパイル結果の出力先"),
+    ("rootDir", "ソースのルートディレクトリ"),
+    ("baseUrl", "非相対パスのimportの基準ディレクトリ"),
+    ("paths", "importのパスの別名"),
+    ("jsx", "JSXの変換方法"),
+    ("declaration", "型定義ファイル（`.d.ts`）を出力する"),
+    ("sourceMap", "ソースマップを出力する"),
+    ("noEmit", "型チェックのみ行い、ファイルを出力しない"),
+    ("allowJs", "JavaScriptのファイルもコンパイルする"),
+    ("esModuleInterop", "CommonJSのモジュールをES Modulesとしてimportできるようにする"),
+    ("skipLibCheck", "型定義ファイルの型チェックを省略する"),
+    ("resolveJsonModule", "JSONファイルをimportできるようにする"),
+    ("isolatedModules", "ファイル単位で変換できるコードに制限する"),
+    ("types", "自動で読み込む `@types` パッケージ"),
+    ("incremental", "前回のビルド情報を使って差分だけコンパイルする"),
+    ("composite", "プロジェクト参照の参照先にできるようにする"),
+];
+
+/// Cargoのビルドプロファイルの項目の説明
+const CARGO_PROFILE_KEYS: &[(&str, &str)] = &[
+    ("opt-level", "最適化レベル"),
+    ("debug", "デバッグ情報の出力"),
+    ("lto", "リンク時最適化"),
+    ("codegen-units", "並列にコード生成する単位の数（少ないほど最適化が効く）"),
+    ("panic", "パニック時の動作（unwind|abort）"),
+    ("strip", "バイナリからシンボル・デバッグ情報を取り除く"),
+    ("incremental", "インクリメンタルコンパイル"),
+    ("overflow-checks", "整数のオーバーフローの検査"),
+];
+
+/// 設定ファイル
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFile {
+    /// 設定ファイルのパス（リポジトリルートからの相対パス）
+    pub path: PathBuf,
+    /// 種類（tsconfig|cargo|env|webpack）
+    pub kind: String,
+    /// 設定ファイルが制御するもの
+    pub summary: String,
+    pub settings: Vec<ConfigSetting>,
+}
+
+/// 設定項目
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSetting {
+    /// 項目名（`compilerOptions.strict`・`features.default`・環境変数名など）
+    pub key: String,
+    /// 値（秘密の値は伏せる）
+    pub value: String,
+    /// 説明（直前のコメントまたは組み込みの説明、不明な場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl ConfigSetting {
+    fn new(key: impl Into<String>, value: impl Into<String>, description: Option<String>) -> Self {
+        Self { key: key.into(), value: value.into(), description }
+    }
+}
+
+/// 設定ファイルとして読むファイルか
+///
+/// # 引数
+/// * `file_name` - ファイル名
+pub(crate) fn is_config_file(file_name: &str) -> bool {
+    config_kind(file_name).is_some()
+}
+
+/// ファイル名から設定ファイルの種類を判定
+fn config_kind(file_name: &str) -> Option<&'static str> {
+    let is_tsconfig = file_name == "jsconfig.json"
+        || (file_name.starts_with("tsconfig") && file_name.ends_with(".json"));
+    let is_webpack = file_name.starts_with("webpack.")
+        && [".js", ".ts", ".mjs", ".cjs"].iter().any(|ext| file_name.ends_with(ext));
+    match file_name {
+        _ if is_tsconfig => Some("tsconfig"),
+        "Cargo.toml" => Some("cargo"),
+        _ if ENV_EXAMPLE_FILES.contains(&file_name) => Some("env"),
+        _ if is_webpack => Some("webpack"),
+        _ => None,
+    }
+}
+
+/// 設定ファイルを解析
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `paths` - 設定ファイルのパス（`is_config_file` で選んだもの）
+/// * `diagnostics` - 解析できなかった設定ファイルの記録先
+///
+/// # 戻り値
+/// * `Vec<ConfigFile>` - 設定項目のある設定ファイル（パス順）
+pub(crate) fn parse_config_files(repo_path: &Path, paths: &[PathBuf], diagnostics: &Diagnostics) -> Vec<ConfigFile> {
+    let mut files: Vec<ConfigFile> = paths
+        .iter()
+        .filter_map(|path| {
+            let kind = config_kind(path.file_name()?.to_str()?)?;
+            let content = std::fs::read_to_string(path).ok()?;
+            let settings = match kind {
+                "tsconfig" => parse_tsconfig(&content),
+                "cargo" => parse_cargo(&content),
+                "env" => Some(parse_env(&content)),
+                _ => Some(parse_webpack(&content)),
+            };
+            if settings.is_none() {
+                diagnostics.warn_at("analyzer", path, "設定ファイルを解析できませんでした");
+            }
+            let settings = settings.filter(|s| !s.is_empty())?;
+            Some(ConfigFile {
+                path: path.strip_prefix(repo_path).unwrap_or(path).to_path_buf(),
+                kind: kind.to_string(),
+                summary: summary(kind).to_string(),
+                settings,
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// 種類ごとの設定ファイルが制御するもの
+fn summary(kind: &str) -> &'static str {
+    match kind {
+        "tsconfig" => "TypeScriptのコンパイラーの設定です。型チェックの厳しさ、出力するJavaScriptの形式と出力先、importの解決方法を制御します。",
+        "cargo" => "Cargoのフィーチャーとビルドプロファイルです。フィーチャーはコンパイルする機能とオプションの依存を、プロファイルは最適化とデバッグ情報を制御します。",
+        "env" => "アプリケーションが読み込む環境変数の例です。接続先・認証情報・動作モードなど、環境ごとに変える値を制御します。",
+        _ => "webpackのバンドルの設定です。エントリーポイント、出力先、ファイルの変換（ローダー）、ビルド時の処理（プラグイン）を制御します。",
+    }
+}
+
+/// tsconfig.jsonを解析
+fn parse_tsconfig(content: &str) -> Option<Vec<ConfigSetting>> {
+    let json: serde_json::Value = serde_json::from_str(&strip_json_comments(content)).ok()?;
+    let mut settings = Vec::new();
+    if let Some(extends) = json.get("extends") {
+        settings.push(ConfigSetting::new("extends", compact(extends), Some("設定を継承する元のtsconfig".to_string())));
+    }
+    if let Some(options) = json.get("compilerOptions").and_then(|o| o.as_object()) {
+        for (name, value) in options {
+            let description = TS_OPTIONS.iter().find(|(key, _)| key == name).map(|(_, d)| d.to_string());
+            settings.push(ConfigSetting::new(format!("compilerOptions.{}", name), compact(value), description));
+        }
+    }
+    let fields = [
+        ("files", "コンパイルするファイル"),
+        ("include", "コンパイル対象に含めるパターン"),
+        ("exclude", "コンパイル対象から除くパターン"),
+        ("references", "参照するプロジェクト（プロジェクト参照）"),
+    ];
+    for (name, description) in fields {
+        if let Some(value) = json.get(name) {
+            settings.push(ConfigSetting::new(name, compact(value), Some(description.to_string())));
+        }
+    }
+    Some(settings)
+}
+
+/// JSONの値を1行で表す（文字列は引用符を外す）
+fn compact(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// JSONC（tsconfig）のコメントと末尾のカンマを取り除く
+fn strip_json_comments(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            result.push(c);
+            match c {
+                '\\' => result.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                result.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        result.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    let trailing_comma = Regex::new(r",(\s*[}\]])").expect("固定の正規表現");
+    trailing_comma.replace_all(&result, "$1").into_owned()
+}
+
+/// Cargo.tomlのフィーチャーとプロファイルを解析
+fn parse_cargo(content: &str) -> Option<Vec<ConfigSetting>> {
+    let table: toml::Table = content.parse().ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut settings = Vec::new();
+
+    if let Some(features) = table.get("features").and_then(|f| f.as_table()) {
+        for (name, enables) in features {
+            let items: Vec<String> = enables
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_str()).map(str::to_string).collect())
+                .unwrap_or_default();
+            let description = key_comment(&lines, "features", name, "#").or_else(|| match name.as_str() {
+                "default" => Some("既定で有効になるフィーチャー".to_string()),
+                _ => {
+                    let deps: Vec<&str> = items.iter().filter_map(|i| i.strip_prefix("dep:")).collect();
+                    (!deps.is_empty()).then(|| format!("オプションの依存 {} を有効にする", deps.join("・")))
+                }
+            });
+            settings.push(ConfigSetting::new(format!("features.{}", name), format!("[{}]", items.join(", ")), description));
+        }
+    }
+
+    if let Some(profiles) = table.get("profile").and_then(|p| p.as_table()) {
+        for (profile, keys) in profiles {
+            let Some(keys) = keys.as_table() else {
+                continue;
+            };
+            for (key, value) in keys {
+                let description = CARGO_PROFILE_KEYS.iter().find(|(k, _)| k == key).map(|(_, d)| d.to_string());
+                let value = match value {
+                    toml::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                settings.push(ConfigSetting::new(format!("profile.{}.{}", profile, key), value, description));
+            }
+        }
+    }
+    Some(settings)
+}
+
+/// TOMLのテーブル内のキーの直前のコメント
+///
+/// # 引数
+/// * `lines` - ファイルの行
+/// * `section` - テーブル名（`[section]`）
+/// * `key` - キー
+/// * `prefix` - コメントの開始記号
+fn key_comment(lines: &[&str], section: &str, key: &str, prefix: &str) -> Option<String> {
+    let header = format!("[{}]", section);
+    let start = lines.iter().position(|l| l.trim() == header)?;
+    let offset = lines[start + 1..]
+        .iter()
+        .take_while(|l| !l.trim_start().starts_with('['))
+        .position(|l| l.split('=').next().is_some_and(|k| k.trim().trim_matches('"') == key))?;
+    preceding_comment(lines, start + 1 + offset, prefix)
+}
+
+/// 指定した行の直前に続くコメント行（複数行はつなげる）
+fn preceding_comment(lines: &[&str], line: usize, prefix: &str) -> Option<String> {
+    let comments: Vec<&str> = lines[..line]
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .take_while(|l| l.starts_with(prefix))
+        .map(|l| l.trim_start_matches(prefix).trim())
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!comments.is_empty()).then(|| comments.into_iter().rev().collect::<Vec<_>>().join(" "))
+}
+
+/// 環境変数の例（`KEY=value`）を解析
+fn parse_env(content: &str) -> Vec<ConfigSetting> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return None;
+            }
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            let value = match is_secret(key) && !value.is_empty() {
+                true => "（秘密の値）".to_string(),
+                false => value.to_string(),
+            };
+            let description = preceding_comment(&lines, i, "#").or_else(|| env_description(key));
+            Some(ConfigSetting::new(key, value, description))
+        })
+        .collect()
+}
+
+/// 秘密の値を表す環境変数名か
+fn is_secret(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase();
+    ["SECRET", "TOKEN", "PASSWORD", "PASSWD", "PRIVATE_KEY", "API_KEY", "CREDENTIAL"]
+        .iter()
+        .any(|word| upper.contains(word))
+}
+
+/// よく使う環境変数名からの説明
+fn env_description(key: &str) -> Option<String> {
+    let upper = key.to_ascii_uppercase();
+    let description = match upper.as_str() {
+        _ if is_secret(key) => "認証情報（秘密の値）",
+        "PORT" | "HOST" => "待ち受けるホスト・ポート",
+        "NODE_ENV" | "APP_ENV" | "ENV" | "ENVIRONMENT" => "実行環境（development・productionなど）",
+        "RUST_LOG" | "LOG_LEVEL" => "ログの出力レベル",
+        "DATABASE_URL" => "データベースの接続先",
+        _ if upper.ends_with("_URL") || upper.ends_with("_ENDPOINT") || upper.ends_with("_HOST") => "接続先のURL・ホスト",
+        _ if upper.ends_with("_PORT") => "接続先・待ち受けのポート",
+        _ => return None,
+    };
+    Some(description.to_string())
+}
+
+/// webpackの設定を解析（正規表現で代表的な項目を抽出）
+fn parse_webpack(content: &str) -> Vec<ConfigSetting> {
+    let capture = |pattern: &str| {
+        Regex::new(pattern)
+            .ok()
+            .and_then(|re| re.captures(content).map(|c| c[1].trim().to_string()))
+    };
+    let all = |pattern: &str| {
+        let mut found: Vec<String> = Regex::new(pattern)
+            .map(|re| re.captures_iter(content).map(|c| c[1].to_string()).collect())
+            .unwrap_or_default();
+        found.sort();
+        found.dedup();
+        found
+    };
+
+    let mut settings = Vec::new();
+    let mut push = |key: &str, value: Option<String>, description: &str| {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            settings.push(ConfigSetting::new(key, value, Some(description.to_string())));
+        }
+    };
+    push("mode", capture(r#"mode\s*:\s*['"](\w+)['"]"#), "ビルドのモード（developmentは開発用、productionは最適化）");
+    push(
+        "entry",
+        capture(r#"entry\s*:\s*(\{[^}]*\}|\[[^\]]*\]|['"][^'"]+['"])"#).map(|e| e.split_whitespace().collect::<Vec<_>>().join(" ")),
+        "バンドルの起点となるファイル",
+    );
+    push("output.path", capture(r#"output\s*:\s*\{[^}]*?path\s*:\s*([\w.]+\([^)]*\)|['"][^'"]+['"]|[\w.]+)"#), "バンドルの出力先");
+    push("output.filename", capture(r#"filename\s*:\s*['"]([^'"]+)['"]"#), "出力するファイル名");
+    push("devtool", capture(r#"devtool\s*:\s*['"]([^'"]+)['"]"#), "ソースマップの形式");
+    push("target", capture(r#"target\s*:\s*['"]([^'"]+)['"]"#), "出力先の実行環境");
+    let loaders = all(r#"['"]([\w@/.-]+-loader)['"]"#);
+    push("module.rules", (!loaders.is_empty()).then(|| loaders.join(", ")), "ファイルを変換するローダー");
+    let plugins = all(r"new\s+(\w*Plugin)\s*\(");
+    push("plugins", (!plugins.is_empty()).then(|| plugins.join(", ")), "ビルド時に実行するプラグイン");
+    push(
+        "devServer.port",
+        content.contains("devServer").then(|| capture(r"port\s*:\s*(\d+)")).flatten(),
+        "開発サーバーのポート",
+    );
+    push("resolve.extensions", capture(r"extensions\s*:\s*(\[[^\]]*\])"), "拡張子を省略してimportできるファイル");
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_files() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-config-files-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("web")).unwrap();
+        std::fs::write(
+            dir.join("web/tsconfig.json"),
+            "{\n  // 共通の設定\n  \"extends\": \"./tsconfig.base.json\",\n  \"compilerOptions\": {\n    \"strict\": true, /* 厳格 */\n    \"outDir\": \"dist\",\n  },\n  \"include\": [\"src\"],\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[features]\ndefault = [\"search\"]\n# 全文検索\nsearch = [\"dep:tantivy\"]\ncloud = [\"dep:aws-sdk\"]\n\n[profile.release]\nlto = true\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join(".env.example"), "# 決済APIのキー\nSTRIPE_SECRET_KEY=sk_test_xxx\nPORT=8080\nFEATURE_X=\n").unwrap();
+        std::fs::write(
+            dir.join("web/webpack.config.js"),
+            "module.exports = {\n  mode: 'production',\n  entry: './src/index.ts',\n  output: { path: path.resolve(__dirname, 'dist'), filename: '[name].js' },\n  module: { rules: [{ test: /\\.ts$/, use: 'ts-loader' }, { test: /\\.css$/, use: ['style-loader', 'css-loader'] }] },\n  plugins: [new HtmlWebpackPlugin()],\n};\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("lib.toml"), "").unwrap();
+
+        let paths: Vec<PathBuf> = ["Cargo.toml", ".env.example", "web/tsconfig.json", "web/webpack.config.js", "lib.toml"]
+            .iter()
+            .map(|p| dir.join(p))
+            .filter(|p| is_config_file(p.file_name().unwrap().to_str().unwrap()))
+            .collect();
+        assert_eq!(paths.len(), 4);
+        let files = parse_config_files(&dir, &paths, &Diagnostics::new());
+        let kinds: Vec<(&Path, &str)> = files.iter().map(|f| (f.path.as_path(), f.kind.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Path::new(".env.example"), "env"),
+                (Path::new("Cargo.toml"), "cargo"),
+                (Path::new("web/tsconfig.json"), "tsconfig"),
+                (Path::new("web/webpack.config.js"), "webpack"),
+            ]
+        );
+        let setting = |file: usize, key: &str| files[file].settings.iter().find(|s| s.key == key).cloned().unwrap();
+
+        assert_eq!(setting(0, "STRIPE_SECRET_KEY").value, "（秘密の値）");
+        assert_eq!(setting(0, "STRIPE_SECRET_KEY").description.as_deref(), Some("決済APIのキー"));
+        assert_eq!(setting(0, "PORT").description.as_deref(), Some("待ち受けるホスト・ポート"));
+        assert_eq!(setting(0, "FEATURE_X").description, None);
+
+        assert_eq!(setting(1, "features.default").value, "[search]");
+        assert_eq!(setting(1, "features.search").description.as_deref(), Some("全文検索"));
+        assert_eq!(setting(1, "features.cloud").description.as_deref(), Some("オプションの依存 aws-sdk を有効にする"));
+        assert_eq!(setting(1, "profile.release.lto").description.as_deref(), Some("リンク時最適化"));
+
+        assert_eq!(setting(2, "extends").value, "./tsconfig.base.json");
+        assert_eq!(setting(2, "compilerOptions.strict").value, "true");
+        assert_eq!(setting(2, "compilerOptions.outDir").description.as_deref(), Some("コンパイル結果の出力先"));
+        assert_eq!(setting(2, "include").value, "[\"src\"]");
+
+        assert_eq!(setting(3, "mode").value, "production");
+        assert_eq!(setting(3, "entry").value, "'./src/index.ts'");
+        assert_eq!(setting(3, "output.path").value, "path.resolve(__dirname, 'dist')");
+        assert_eq!(setting(3, "module.rules").value, "css-loader, style-loader, ts-loader");
+        assert_eq!(setting(3, "plugins").value, "HtmlWebpackPlugin");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,538 @@
+/*!
+ * 内部依存グラフ
+ *
+ * import・use・requireの文字列をリポジトリ内のファイルに解決し、ファイル間の依存を隣接リストにする
+ * - モジュールグラフの辺・重要度の被依存数・要約の結合度の説明に使う
+ *
+ * 主な仕様:
+ * - TypeScript/JavaScript: `./` `../` の相対パス（拡張子の補完、`index.*`、`.js` → `.ts` の読み替え）
+ * - Python: ドット区切りのモジュール（`a/b.py`、`a/b/__init__.py`）、先頭の `.` による相対import
+ *   - 絶対importはimportしたファイルのディレクトリからリポジトリルートまでの各階層を起点に探す
+ * - Rust: `crate::` `self::` `super::` のパス、ワークスペース内のクレート名（`my_crate::x`）
+ *   - 末尾のセグメントから順に型・関数名とみなして除き、最初に見つかったモジュールのファイルに解決
+ * - Go: importパスの末尾に一致するディレクトリ（パッケージ）のファイル（`_test.go` を除く）
+ * - Java/Kotlin: パッケージ名のパスに一致するファイル（`com.example.Foo` → `com/example/Foo.java`）
+ * - C/C++・Ruby・PHP: importしたファイルからの相対パス、一致しない場合はパスの末尾が一致するファイル
+ * - 解決できないimportは外部依存として扱う
+ * - 条件付きのimport（`conditions`）も同じ規則で解決し、読み込んだファイルに条件を記録する
+ * - 循環依存は互いに到達できるファイルの集まり（強連結成分）として検出し、最短の循環の経路を例として添える
+ *
+ * 制限事項:
+ * - tsconfigの `paths`・Pythonの `sys.path` の変更・Goの `replace` は考慮しない
+ * - Rustの `mod` 宣言とマクロで生成されるパスは解決しない
+ * - Rustのクレート名は `src/lib.rs` の親ディレクトリ名から推定（Cargo.tomlの `name` は読まない）
+ * - 末尾が一致するファイルが複数ある場合はパス順で最初のものを使う
+ */
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditions::ConditionalImport;
+use crate::{BuildCondition, FileInfo};
+
+/// TypeScript/JavaScriptで補完する拡張子
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+
+/// import文字列をリポジトリ内のファイルに解決する
+struct ImportResolver<'a> {
+    repo_path: &'a Path,
+    /// リポジトリルートからの相対パス → ファイルのパス
+    by_relative: HashMap<PathBuf, &'a Path>,
+    /// ファイル名 → 相対パス（パス順）
+    by_name: HashMap<String, Vec<PathBuf>>,
+    /// Goのパッケージ（`/` 区切りの相対ディレクトリ） → ファイル
+    go_packages: BTreeMap<String, Vec<&'a Path>>,
+    /// Rustのクレート名（`-` は `_`） → `src` ディレクトリの相対パス
+    crates: HashMap<String, PathBuf>,
+}
+
+impl<'a> ImportResolver<'a> {
+    fn new(repo_path: &'a Path, files: &'a [FileInfo]) -> Self {
+        let mut resolver = Self {
+            repo_path,
+            by_relative: HashMap::new(),
+            by_name: HashMap::new(),
+            go_packages: BTreeMap::new(),
+            crates: HashMap::new(),
+        };
+        for file in files {
+            let relative = file.path.strip_prefix(repo_path).unwrap_or(&file.path).to_path_buf();
+            if let Some(name) = relative.file_name().and_then(|n| n.to_str()) {
+                resolver.by_name.entry(name.to_string()).or_default().push(relative.clone());
+            }
+            if file.language == "go" && !file.path.to_string_lossy().ends_with("_test.go") {
+                let dir = relative.parent().map(slash_path).unwrap_or_default();
+                resolver.go_packages.entry(dir).or_default().push(&file.path);
+            }
+            // `<crate>/src/lib.rs` のディレクトリ名をクレート名とみなす
+            if relative.ends_with("src/lib.rs") {
+                let src = relative.parent().unwrap_or(Path::new("")).to_path_buf();
+                let name = src
+                    .parent()
+                    .and_then(|c| c.file_name())
+                    .or_else(|| repo_path.file_name())
+                    .map(|n| n.to_string_lossy().replace('-', "_"));
+                if let Some(name) = name {
+                    resolver.crates.insert(name, src);
+                }
+            }
+            resolver.by_relative.insert(relative, &file.path);
+        }
+        for paths in resolver.by_name.values_mut() {
+            paths.sort();
+        }
+        resolver
+    }
+
+    /// importを解決
+    ///
+    /// # 引数
+    /// * `file` - importしたファイル
+    /// * `import` - import・use・requireの対象
+    ///
+    /// # 戻り値
+    /// * `Vec<&Path>` - 解決したファイル（外部依存の場合は空）
+    fn resolve(&self, file: &FileInfo, import: &str) -> Vec<&'a Path> {
+        let relative = file.path.strip_prefix(self.repo_path).unwrap_or(&file.path);
+        let dir = relative.parent().unwrap_or(Path::new(""));
+        let found = match file.language.as_str() {
+            "ts" | "js" => self.resolve_js(dir, import),
+            "py" => self.resolve_python(dir, import),
+            "rs" => self.resolve_rust(relative, import),
+            "go" => return self.resolve_go(import),
+            "java" => self.resolve_suffix(&format!("{}.java", import.replace('.', "/"))),
+            "kt" => self
+                .resolve_suffix(&format!("{}.kt", import.replace('.', "/")))
+                .or_else(|| self.resolve_suffix(&format!("{}.java", import.replace('.', "/")))),
+            "c" | "cpp" => self.find(&dir.join(import)).or_else(|| self.resolve_suffix(import)),
+            "rb" => {
+                let import = if import.ends_with(".rb") { import.to_string() } else { format!("{}.rb", import) };
+                self.find(&dir.join(&import))
+                    .or_else(|| self.find(&Path::new("lib").join(&import)))
+            }
+            "php" => {
+                if import.ends_with(".php") {
+                    self.find(&dir.join(import)).or_else(|| self.resolve_suffix(import))
+                } else {
+                    // PSR-4: 先頭の名前空間（`App` など）はディレクトリ名と異なることが多いため除いて照合
+                    let path = import.replace('\\', "/");
+                    self.resolve_suffix(&format!("{}.php", path)).or_else(|| {
+                        let (_, rest) = path.split_once('/')?;
+                        self.resolve_suffix(&format!("{}.php", rest))
+                    })
+                }
+            }
+            _ => None,
+        };
+        found.into_iter().filter(|p| *p != file.path.as_path()).collect()
+    }
+
+    fn resolve_js(&self, dir: &Path, import: &str) -> Option<&'a Path> {
+        if !import.starts_with("./") && !import.starts_with("../") && import != "." && import != ".." {
+            return None;
+        }
+        let base = dir.join(import);
+        if let Some(found) = self.find(&base) {
+            return Some(found);
+        }
+        // ESMのTypeScriptは `./x.js` で `x.ts` を参照する
+        let stem = match base.extension().and_then(|e| e.to_str()) {
+            Some(ext) if JS_EXTENSIONS.contains(&ext) => base.with_extension(""),
+            _ => base.clone(),
+        };
+        JS_EXTENSIONS
+            .iter()
+            .find_map(|ext| self.find(&append_extension(&stem, ext)))
+            .or_else(|| JS_EXTENSIONS.iter().find_map(|ext| self.find(&base.join(format!("index.{}", ext)))))
+    }
+
+    fn resolve_python(&self, dir: &Path, import: &str) -> Option<&'a Path> {
+        let module = import.trim_start_matches('.');
+        let dots = import.len() - module.len();
+        let segments = module.split('.').filter(|s| !s.is_empty()).collect::<Vec<_>>().join("/");
+        let candidates = |base: &Path| {
+            let path = base.join(&segments);
+            [append_extension(&path, "py"), path.join("__init__.py")]
+        };
+
+        if dots > 0 {
+            let mut base = dir.to_path_buf();
+            for _ in 1..dots {
+                base.pop();
+            }
+            return candidates(&base).iter().find_map(|c| self.find(c));
+        }
+        dir.ancestors()
+            .flat_map(candidates)
+            .find_map(|c| self.find(&c))
+    }
+
+    fn resolve_rust(&self, relative: &Path, import: &str) -> Option<&'a Path> {
+        let mut segments: Vec<&str> = import.split("::").collect();
+        let first = segments.remove(0);
+        let is_mod_root = relative
+            .file_name()
+            .is_some_and(|n| n == "mod.rs" || n == "lib.rs" || n == "main.rs");
+        let dir = relative.parent().unwrap_or(Path::new(""));
+        // 子モジュールを置くディレクトリ（`a.rs` は `a/`、`mod.rs`・`lib.rs` は同じディレクトリ）
+        let own_dir = if is_mod_root {
+            dir.to_path_buf()
+        } else {
+            relative.with_extension("")
+        };
+
+        let base = match first {
+            "crate" => relative.ancestors().find(|a| a.ends_with("src"))?.to_path_buf(),
+            "self" => own_dir,
+            "super" => {
+                let mut base = own_dir;
+                base.pop();
+                while segments.first() == Some(&"super") {
+                    segments.remove(0);
+                    base.pop();
+                }
+                base
+            }
+            name => self.crates.get(name)?.clone(),
+        };
+
+        for len in (1..=segments.len()).rev() {
+            let path = segments[..len].iter().fold(base.clone(), |p, s| p.join(s));
+            for candidate in [append_extension(&path, "rs"), path.join("mod.rs")] {
+                if let Some(found) = self.find(&candidate) {
+                    return Some(found);
+                }
+            }
+        }
+        // クレートまたは親モジュール直下の型・関数
+        ["lib.rs", "main.rs", "mod.rs"]
+            .iter()
+            .find_map(|root| self.find(&base.join(root)))
+            .or_else(|| self.find(&append_extension(&base, "rs")))
+    }
+
+    fn resolve_go(&self, import: &str) -> Vec<&'a Path> {
+        self.go_packages
+            .iter()
+            .filter(|(dir, _)| !dir.is_empty() && (import == dir.as_str() || import.ends_with(&format!("/{}", dir))))
+            .max_by_key(|(dir, _)| dir.len())
+            .map(|(_, files)| files.clone())
+            .unwrap_or_default()
+    }
+
+    /// パスの末尾（`/` 区切り）が一致するファイルを探す
+    fn resolve_suffix(&self, suffix: &str) -> Option<&'a Path> {
+        let suffix = Path::new(suffix);
+        let name = suffix.file_name()?.to_str()?;
+        self.by_name
+            .get(name)?
+            .iter()
+            .find(|relative| relative.ends_with(suffix))
+            .and_then(|relative| self.by_relative.get(relative).copied())
+    }
+
+    /// 相対パス（`..` を含む）に一致するファイルを探す
+    fn find(&self, relative: &Path) -> Option<&'a Path> {
+        let mut normalized = PathBuf::new();
+        for component in relative.components() {
+            match component {
+                Component::Normal(part) => normalized.push(part),
+                Component::ParentDir => {
+                    if !normalized.pop() {
+                        return None;
+                    }
+                }
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        self.by_relative.get(&normalized).copied()
+    }
+}
+
+/// ファイル間の依存グラフを作成
+///
+/// # 引数
+/// * `repo_path` - リポジトリのルート
+/// * `files` - 解析したファイル
+/// * `dependencies` - import文字列 → 解決したファイルの相対パス（外部依存は空のまま）
+///
+/// # 戻り値
+/// * `BTreeMap<PathBuf, Vec<PathBuf>>` - ファイル → importしているリポジトリ内のファイル
+pub(crate) fn build_internal_graph(
+    repo_path: &Path,
+    files: &[FileInfo],
+    dependencies: &mut HashMap<String, Vec<String>>,
+) -> BTreeMap<PathBuf, Vec<PathBuf>> {
+    let resolver = ImportResolver::new(repo_path, files);
+    let mut graph = BTreeMap::new();
+    for file in files {
+        let mut targets: Vec<PathBuf> = Vec::new();
+        for import in &file.dependencies {
+            let resolved = resolver.resolve(file, import);
+            let entry = dependencies.entry(import.clone()).or_default();
+            for path in &resolved {
+                let relative = slash_path(path.strip_prefix(repo_path).unwrap_or(path));
+                if !entry.contains(&relative) {
+                    entry.push(relative);
+                }
+            }
+            targets.extend(resolved.into_iter().map(Path::to_path_buf));
+        }
+        targets.sort();
+        targets.dedup();
+        if !targets.is_empty() {
+            graph.insert(file.path.clone(), targets);
+        }
+    }
+    for paths in dependencies.values_mut() {
+        paths.sort();
+    }
+    graph
+}
+
+/// 条件付きのimportを読み込んだファイルに解決
+///
+/// # 引数
+/// * `repo_path` - リポジトリのルート
+/// * `files` - 解析したファイル
+/// * `imports` - importしたファイルと条件付きのimport
+///
+/// # 戻り値
+/// * `Vec<(PathBuf, BuildCondition)>` - 読み込んだファイルとその条件（解決できないimportは除く）
+pub(crate) fn resolve_conditional_imports(
+    repo_path: &Path,
+    files: &[FileInfo],
+    imports: &[(PathBuf, ConditionalImport)],
+) -> Vec<(PathBuf, BuildCondition)> {
+    if imports.is_empty() {
+        return Vec::new();
+    }
+    let resolver = ImportResolver::new(repo_path, files);
+    let mut resolved = Vec::new();
+    for (path, conditional) in imports {
+        let Some(file) = files.iter().find(|f| &f.path == path) else {
+            continue;
+        };
+        for target in resolver.resolve(file, &conditional.import) {
+            resolved.push((target.to_path_buf(), conditional.condition.clone()));
+        }
+    }
+    resolved
+}
+
+/// 循環依存（互いにimportをたどって到達できるファイルの集まり）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DependencyCycle {
+    /// 循環に含まれるファイル（パス順）
+    pub files: Vec<PathBuf>,
+    /// 循環の例（先頭のファイルから最短でたどって戻る経路、先頭と末尾は同じファイル）
+    pub path: Vec<PathBuf>,
+}
+
+/// 内部依存グラフの循環依存を検出（自身へのimportは除く）
+///
+/// # 引数
+/// * `graph` - ファイル → importしているリポジトリ内のファイル
+///
+/// # 戻り値
+/// * `Vec<DependencyCycle>` - 循環依存（ファイル数の多い順、同数は先頭のファイルのパス順）
+pub(crate) fn find_cycles(graph: &BTreeMap<PathBuf, Vec<PathBuf>>) -> Vec<DependencyCycle> {
+    let mut cycles: Vec<DependencyCycle> = strongly_connected_components(graph)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|mut files| {
+            files.sort();
+            let members: HashSet<&Path> = files.iter().map(PathBuf::as_path).collect();
+            let path = shortest_cycle(graph, &files[0], &members);
+            DependencyCycle { files, path }
+        })
+        .collect();
+    cycles.sort_by(|a, b| b.files.len().cmp(&a.files.len()).then_with(|| a.files.cmp(&b.files)));
+    cycles
+}
+
+/// Tarjanのアルゴリズムで強連結成分を求める（深いグラフでもスタックを溢れさせないよう反復で実装）
+fn strongly_connected_components(graph: &BTreeMap<PathBuf, Vec<PathBuf>>) -> Vec<Vec<PathBuf>> {
+    let nodes: Vec<&Path> = graph
+        .iter()
+        .flat_map(|(from, targets)| std::iter::once(from).chain(targets))
+        .map(PathBuf::as_path)
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let ids: HashMap<&Path, usize> = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+    let edges: Vec<Vec<usize>> = nodes
+        .iter()
+        .map(|n| graph.get(*n).map(|t| t.iter().filter_map(|p| ids.get(p.as_path()).copied()).collect()).unwrap_or_default())
+        .collect();
+
+    let mut index = vec![usize::MAX; nodes.len()];
+    let mut low = vec![0; nodes.len()];
+    let mut on_stack = vec![false; nodes.len()];
+    let mut stack = Vec::new();
+    let mut counter = 0;
+    let mut components = Vec::new();
+    for root in 0..nodes.len() {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // (ノード, 次に調べる辺の位置)
+        let mut work = vec![(root, 0)];
+        while let Some(&mut (node, ref mut next)) = work.last_mut() {
+            if *next == 0 && index[node] == usize::MAX {
+                index[node] = counter;
+                low[node] = counter;
+                counter += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+            if let Some(&target) = edges[node].get(*next) {
+                *next += 1;
+                if index[target] == usize::MAX {
+                    work.push((target, 0));
+                } else if on_stack[target] {
+                    low[node] = low[node].min(index[target]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+            if low[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(nodes[member].to_path_buf());
+                    if member == node {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// 成分内で `start` から戻る最短の経路（幅優先探索）
+fn shortest_cycle(graph: &BTreeMap<PathBuf, Vec<PathBuf>>, start: &Path, members: &HashSet<&Path>) -> Vec<PathBuf> {
+    let mut previous: HashMap<&Path, &Path> = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        for target in graph.get(node).into_iter().flatten().map(PathBuf::as_path) {
+            if !members.contains(target) {
+                continue;
+            }
+            if target == start {
+                let mut path = vec![start.to_path_buf()];
+                let mut current = node;
+                while current != start {
+                    path.push(current.to_path_buf());
+                    current = previous[current];
+                }
+                path.push(start.to_path_buf());
+                path.reverse();
+                return path;
+            }
+            if !previous.contains_key(target) {
+                previous.insert(target, node);
+                queue.push_back(target);
+            }
+        }
+    }
+    Vec::new()
+}
+
+/// `/` 区切りのパス文字列
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 拡張子を追加（`a.test` → `a.test.ts`）
+fn append_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(".");
+    path.push(ext);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_internal_graph() {
+        let repo = Path::new("/repo");
+        let file = |path: &str, language: &str, deps: &[&str]| FileInfo {
+            path: repo.join(path),
+            language: language.to_string(),
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let files = vec![
+            file("web/src/app.ts", "ts", &["./api.js", "../lib", "react"]),
+            file("web/src/api.ts", "ts", &[]),
+            file("web/lib/index.ts", "ts", &[]),
+            file("py/pkg/cli.py", "py", &["pkg.core", ".util", "os"]),
+            file("py/pkg/core/__init__.py", "py", &[]),
+            file("py/pkg/util.py", "py", &[]),
+            file("crates/store/src/lib.rs", "rs", &["crate::blob::Blob", "self::blob", "std::fs"]),
+            file("crates/store/src/blob.rs", "rs", &["super::Store"]),
+            file("crates/cli/src/main.rs", "rs", &["store::blob::Blob"]),
+            file("go/cmd/main.go", "go", &["github.com/acme/app/go/server", "fmt"]),
+            file("go/server/server.go", "go", &[]),
+            file("go/server/server_test.go", "go", &[]),
+        ];
+        let mut dependencies = HashMap::new();
+        let graph = build_internal_graph(repo, &files, &mut dependencies);
+
+        let targets = |path: &str| -> Vec<String> {
+            graph
+                .get(&repo.join(path))
+                .map(|t| t.iter().map(|p| slash_path(p.strip_prefix(repo).unwrap())).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(targets("web/src/app.ts"), vec!["web/lib/index.ts", "web/src/api.ts"]);
+        assert_eq!(targets("py/pkg/cli.py"), vec!["py/pkg/core/__init__.py", "py/pkg/util.py"]);
+        assert_eq!(targets("crates/store/src/lib.rs"), vec!["crates/store/src/blob.rs"]);
+        assert_eq!(targets("crates/store/src/blob.rs"), vec!["crates/store/src/lib.rs"]);
+        assert_eq!(targets("crates/cli/src/main.rs"), vec!["crates/store/src/blob.rs"]);
+        assert_eq!(targets("go/cmd/main.go"), vec!["go/server/server.go"]);
+
+        // 外部依存は空のまま記録される
+        assert_eq!(dependencies["react"], Vec::<String>::new());
+        assert_eq!(dependencies["./api.js"], vec!["web/src/api.ts"]);
+    }
+
+    #[test]
+    fn test_find_cycles() {
+        let graph: BTreeMap<PathBuf, Vec<PathBuf>> = [
+            ("a.ts", vec!["b.ts"]),
+            ("b.ts", vec!["c.ts", "d.ts"]),
+            ("c.ts", vec!["a.ts"]),
+            ("d.ts", vec!["b.ts", "d.ts"]),
+            ("e.ts", vec!["e.ts", "a.ts"]),
+            ("x.ts", vec!["y.ts"]),
+            ("y.ts", vec!["x.ts"]),
+        ]
+        .into_iter()
+        .map(|(from, to)| (PathBuf::from(from), to.into_iter().map(PathBuf::from).collect()))
+        .collect();
+
+        let cycles = find_cycles(&graph);
+        let paths = |items: &[PathBuf]| items.iter().map(|p| p.to_string_lossy().into_owned()).collect::<Vec<_>>();
+        assert_eq!(cycles.len(), 2);
+        assert_eq!(paths(&cycles[0].files), vec!["a.ts", "b.ts", "c.ts", "d.ts"]);
+        assert_eq!(paths(&cycles[0].path), vec!["a.ts", "b.ts", "c.ts", "a.ts"]);
+        assert_eq!(paths(&cycles[1].files), vec!["x.ts", "y.ts"]);
+        assert_eq!(paths(&cycles[1].path), vec!["x.ts", "y.ts", "x.ts"]);
+        assert!(find_cycles(&BTreeMap::new()).is_empty());
+    }
+}
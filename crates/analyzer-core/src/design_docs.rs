@@ -0,0 +1,312 @@
+/*!
+ * 設計ドキュメントの検出と実装との比較
+ *
+ * ARCHITECTURE.mdとADR（Architecture Decision Record）のディレクトリを読み、書かれた構成と解析したコードを比べる
+ * - Wikiの「アーキテクチャ」章の「ドキュメントと実装の差分」に使う
+ *
+ * 主な仕様:
+ * - ARCHITECTURE.mdはリポジトリ直下、`docs/`、`.github/` の順に探す（大文字・小文字は区別しない）
+ * - ADRは `docs/adr`・`docs/adrs`・`docs/decisions`・`docs/architecture/decisions`・`doc/adr`・`adr` 直下のMarkdown（名前順、最大 `MAX_ADR_FILES` 件）
+ * - ドキュメント中の言及は、コードブロックの外のインラインコード（`` `src/auth/` ``・`` `OrderService` `` など）のうち
+ *   - パスの形のもの（`/` を含む、または拡張子がある）は、リポジトリにそのファイル・ディレクトリがあるかで判定
+ *   - 識別子の形のもの（`snake_case`・`kebab-case`・`PascalCase`・`a::b`）は、型・関数・ファイル・ディレクトリ・パッケージ・外部依存の名前と照合
+ * - コード側の構成要素は、ワークスペースのパッケージ（2つ以上の場合）、なければ最上位のディレクトリ（`src/` などの下は1つ下の階層）
+ *   - ドキュメントのどこにも名前が現れない構成要素を「ドキュメントにない」とする
+ *
+ * 制限事項:
+ * - 名前の一致だけで判定するため、言い換え（「認証モジュール」と `auth` など）は対応付けない
+ * - 単語1つの小文字の名前（`` `cargo` `` など）はコマンドや一般の語と区別できないため言及として扱わない
+ * - ADRの状態（Accepted・Superseded など）は区別しない
+ */
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Index;
+
+/// 読み込むADRの最大数
+const MAX_ADR_FILES: usize = 100;
+
+/// ADRを置くディレクトリ（リポジトリルートからの相対パス）
+const ADR_DIRS: &[&str] = &["docs/adr", "docs/adrs", "docs/decisions", "docs/architecture/decisions", "doc/adr", "adr"];
+
+/// 1つ下の階層を構成要素とするディレクトリ
+const CONTAINER_DIRS: &[&str] = &["src", "lib", "app", "apps", "pkg", "internal", "crates", "packages", "libs", "services", "modules"];
+
+/// 設計ドキュメント（ARCHITECTURE.md・ADR）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DesignDoc {
+    /// リポジトリルートからの相対パス
+    pub path: PathBuf,
+    /// 種類（architecture|adr）
+    pub kind: String,
+    /// 最初の見出し（ない場合はファイル名）
+    pub title: String,
+    /// インラインコードで書かれた構成要素の言及（記載順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mentions: Vec<DocMention>,
+    /// 本文に現れる語（小文字、`/`・空白・記号で区切ったもの）
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub words: BTreeSet<String>,
+}
+
+/// ドキュメント中の構成要素の言及
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocMention {
+    /// 言及した名前（末尾の `/`・`()` を除いたもの）
+    pub name: String,
+    /// 行番号（1始まり）
+    pub line: usize,
+    /// パスの形の言及か
+    #[serde(default)]
+    pub is_path: bool,
+    /// パスの形の言及で、リポジトリにそのファイル・ディレクトリがあるか
+    #[serde(default)]
+    pub exists: bool,
+}
+
+/// 設計ドキュメントと実装の差分
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesignDrift {
+    /// ドキュメントにあってコードにない言及（ドキュメントのパス、言及）
+    pub missing_in_code: Vec<(PathBuf, DocMention)>,
+    /// コードにあってドキュメントにない構成要素（リポジトリルートからの相対パス）
+    pub missing_in_docs: Vec<String>,
+}
+
+/// 設計ドキュメントを検出して言及を読み取る
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+///
+/// # 戻り値
+/// * `Vec<DesignDoc>` - ARCHITECTURE.md、ADRの順（見つからない場合は空）
+pub(crate) fn detect_design_docs(repo_path: &Path) -> Vec<DesignDoc> {
+    let mut paths: Vec<(PathBuf, &str)> = Vec::new();
+    let architecture = ["", "docs", ".github"].iter().find_map(|dir| {
+        markdown_files(repo_path, Path::new(dir)).into_iter().find(|path| {
+            path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.eq_ignore_ascii_case("ARCHITECTURE"))
+        })
+    });
+    paths.extend(architecture.map(|path| (path, "architecture")));
+    if let Some(dir) = ADR_DIRS.iter().find(|dir| repo_path.join(dir).is_dir()) {
+        paths.extend(markdown_files(repo_path, Path::new(dir)).into_iter().take(MAX_ADR_FILES).map(|path| (path, "adr")));
+    }
+
+    paths
+        .into_iter()
+        .filter_map(|(path, kind)| {
+            let content = std::fs::read_to_string(repo_path.join(&path)).ok()?;
+            Some(parse_design_doc(repo_path, path, kind, &content))
+        })
+        .collect()
+}
+
+/// ディレクトリ直下のMarkdownファイル（リポジトリルートからの相対パス、名前順）
+fn markdown_files(repo_path: &Path, dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(repo_path.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| dir.join(e.file_name()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 設計ドキュメントの見出し・言及・語を読み取る
+fn parse_design_doc(repo_path: &Path, path: PathBuf, kind: &str, content: &str) -> DesignDoc {
+    let mut title = None;
+    let mut mentions = Vec::new();
+    let mut words = BTreeSet::new();
+    let mut in_code_block = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        words.extend(
+            line.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .filter(|w| w.chars().count() >= 2)
+                .map(|w| w.to_lowercase()),
+        );
+        if in_code_block {
+            continue;
+        }
+        if title.is_none() {
+            title = trimmed.strip_prefix("# ").map(|t| t.trim().to_string());
+        }
+        for span in line.split('`').skip(1).step_by(2) {
+            if let Some((name, is_path)) = mention_name(span) {
+                let exists = is_path && repo_path.join(&name).exists();
+                mentions.push(DocMention { name, line: i + 1, is_path, exists });
+            }
+        }
+    }
+    DesignDoc {
+        title: title.unwrap_or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()),
+        path,
+        kind: kind.to_string(),
+        mentions,
+        words,
+    }
+}
+
+/// インラインコードが構成要素の言及なら、名前とパスの形か（言及でない場合はNone）
+fn mention_name(span: &str) -> Option<(String, bool)> {
+    let span = span.trim();
+    if span.chars().any(|c| c.is_whitespace()) || span.starts_with(['-', '.', '$', '<']) && !span.starts_with("./") {
+        return None;
+    }
+    let name = span.trim_start_matches("./").trim_end_matches("()").trim_end_matches('/');
+    if name.chars().count() < 3 || name.contains("://") {
+        return None;
+    }
+    let path_chars = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/');
+    if name.contains('/') || Path::new(name).extension().is_some_and(|ext| ext.len() <= 5 && ext.to_string_lossy().chars().all(|c| c.is_ascii_alphabetic())) {
+        return (name.chars().all(path_chars) && !name.starts_with('/')).then(|| (name.to_string(), true));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | ':')) || !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let compound = name.contains(['_', '-', ':']) || name.chars().skip(1).any(|c| c.is_ascii_uppercase());
+    compound.then(|| (name.rsplit("::").next().unwrap_or(name).to_string(), false)).filter(|(n, _)| n.len() >= 3)
+}
+
+/// 設計ドキュメントと実装を比べる
+///
+/// # 引数
+/// * `index` - インデックス（`design_docs` が空の場合は差分なし）
+///
+/// # 戻り値
+/// * `DesignDrift` - ドキュメントにあってコードにない言及と、コードにあってドキュメントにない構成要素
+pub(crate) fn compare(index: &Index) -> DesignDrift {
+    if index.design_docs.is_empty() {
+        return DesignDrift::default();
+    }
+    let key = |name: &str| name.to_lowercase().replace('-', "_");
+
+    // コード側の名前（型・関数・ファイル・ディレクトリ・モジュール・パッケージ・外部依存）
+    let mut names: BTreeSet<String> = BTreeSet::new();
+    for file in &index.files {
+        let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+        names.extend(relative.iter().map(|c| key(&c.to_string_lossy())));
+        names.insert(key(&file.name));
+        names.extend(file.symbols.iter().map(|s| key(&s.name)));
+    }
+    names.extend(index.modules.iter().map(|m| key(&m.id)));
+    names.extend(index.packages.iter().map(|p| key(&p.name)));
+    names.extend(index.external_dependencies.iter().map(|d| key(&d.name)));
+
+    let mut seen = BTreeSet::new();
+    let missing_in_code = index
+        .design_docs
+        .iter()
+        .flat_map(|doc| doc.mentions.iter().map(move |m| (doc, m)))
+        .filter(|(_, m)| if m.is_path { !m.exists } else { !names.contains(&key(&m.name)) })
+        .filter(|(_, m)| seen.insert(m.name.clone()))
+        .map(|(doc, m)| (doc.path.clone(), m.clone()))
+        .collect();
+
+    let words: BTreeSet<String> = index.design_docs.iter().flat_map(|doc| doc.words.iter().map(|w| key(w))).collect();
+    let missing_in_docs = components(index)
+        .into_iter()
+        .filter(|component| {
+            let name = component.rsplit('/').next().unwrap_or(component);
+            !words.contains(&key(name))
+        })
+        .collect();
+
+    DesignDrift { missing_in_code, missing_in_docs }
+}
+
+/// コード側の構成要素（ワークスペースのパッケージ、なければ最上位のディレクトリ）
+fn components(index: &Index) -> Vec<String> {
+    if index.packages.len() > 1 {
+        return index.packages.iter().filter(|p| !p.path.as_os_str().is_empty()).map(|p| p.path.to_string_lossy().replace('\\', "/")).collect();
+    }
+    let mut dirs = BTreeSet::new();
+    for file in index.files.iter().filter(|f| !f.is_test) {
+        let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+        let parts: Vec<String> = relative.parent().into_iter().flat_map(|p| p.iter()).map(|c| c.to_string_lossy().to_string()).collect();
+        match parts.as_slice() {
+            [] => {}
+            [container, child, ..] if CONTAINER_DIRS.contains(&container.as_str()) => {
+                dirs.insert(format!("{}/{}", container, child));
+            }
+            [container] if CONTAINER_DIRS.contains(&container.as_str()) => {}
+            [top, ..] => {
+                dirs.insert(top.clone());
+            }
+        }
+    }
+    dirs.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileInfo, Symbol};
+
+    #[test]
+    fn test_mention_name() {
+        assert_eq!(mention_name("src/auth/"), Some(("src/auth".to_string(), true)));
+        assert_eq!(mention_name("Cargo.toml"), Some(("Cargo.toml".to_string(), true)));
+        assert_eq!(mention_name("OrderService"), Some(("OrderService".to_string(), false)));
+        assert_eq!(mention_name("crate::billing::create_invoice()"), Some(("create_invoice".to_string(), false)));
+        assert_eq!(mention_name("cargo"), None);
+        assert_eq!(mention_name("cargo build"), None);
+        assert_eq!(mention_name("--release"), None);
+        assert_eq!(mention_name("https://example.com/a"), None);
+    }
+
+    #[test]
+    fn test_compare_docs_with_code() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-design-docs-{}", uuid::Uuid::new_v4()));
+        let root = dir.as_path();
+        std::fs::create_dir_all(root.join("src/orders")).unwrap();
+        std::fs::create_dir_all(root.join("src/payments")).unwrap();
+        std::fs::create_dir_all(root.join("docs/adr")).unwrap();
+        std::fs::write(
+            root.join("ARCHITECTURE.md"),
+            "# Architecture\n\n`src/orders/` holds `OrderService`.\n`src/legacy/` wraps `LegacyGateway`.\n\n```\n`src/ignored/`\n```\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("docs/adr/0001-use-postgres.md"), "# Use Postgres\n\nStore orders via `order_repository`.\n").unwrap();
+
+        let docs = detect_design_docs(root);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].title, "Architecture");
+        assert_eq!(docs[1].kind, "adr");
+        assert_eq!(docs[1].path, PathBuf::from("docs/adr/0001-use-postgres.md"));
+        let names: Vec<&str> = docs[0].mentions.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["src/orders", "OrderService", "src/legacy", "LegacyGateway"]);
+
+        let file = |path: &str, symbols: &[&str]| FileInfo {
+            path: root.join(path),
+            name: Path::new(path).file_stem().unwrap().to_string_lossy().to_string(),
+            symbols: symbols.iter().map(|s| Symbol { kind: "struct".to_string(), name: s.to_string(), ..Default::default() }).collect(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: root.to_path_buf(),
+            files: vec![file("src/orders/service.rs", &["OrderService"]), file("src/payments/card.rs", &[]), file("src/main.rs", &[])],
+            design_docs: docs,
+            ..Default::default()
+        };
+        let drift = compare(&index);
+        let missing: Vec<&str> = drift.missing_in_code.iter().map(|(_, m)| m.name.as_str()).collect();
+        assert_eq!(missing, ["src/legacy", "LegacyGateway", "order_repository"]);
+        assert_eq!(drift.missing_in_code[0].0, PathBuf::from("ARCHITECTURE.md"));
+        assert_eq!(drift.missing_in_code[0].1.line, 4);
+        assert_eq!(drift.missing_in_docs, ["src/payments"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
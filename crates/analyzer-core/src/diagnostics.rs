@@ -0,0 +1,125 @@
+/*!
+ * 警告の収集
+ *
+ * 解析・要約・図の生成・Wiki/スライドのビルド中に発生した、処理を止めない問題を集める
+ * - スキップしたファイル（バイナリ・読み込めない・大きすぎる）
+ * - 生成できなかった図
+ * - 省略・切り詰めた要約や一覧
+ * - 見つからない外部ツール
+ *
+ * 主な仕様:
+ * - `Diagnostics` は複製しても同じ記録先を共有する（並列に生成するセクション・スレッドプールからも記録可能）
+ * - 記録と同時に `tracing` の警告ログにも出力する
+ * - 各結果（`Index`・`SummarizeResult`・`WikiResult`・`SlideResult`）には記録済みの警告を記録順に含める
+ *
+ * 制限事項:
+ * - 同じ内容の警告も重複を除かずにすべて記録する
+ */
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// 1件の警告
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// 発生元（analyzer・summarizer・diagrammer・wiki・slides）
+    pub source: String,
+    /// 対象のファイル（特定のファイルに関係しない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+/// 警告の収集先（複製したものは同じ記録先を共有する）
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    entries: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl Diagnostics {
+    /// 空の収集先を作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 警告を記録
+    ///
+    /// # 引数
+    /// * `source` - 発生元
+    /// * `message` - 内容
+    pub fn warn(&self, source: &str, message: impl Into<String>) {
+        self.record(source, None, message.into());
+    }
+
+    /// ファイルに関する警告を記録
+    ///
+    /// # 引数
+    /// * `source` - 発生元
+    /// * `path` - 対象のファイル
+    /// * `message` - 内容
+    pub fn warn_at(&self, source: &str, path: &Path, message: impl Into<String>) {
+        self.record(source, Some(path.to_path_buf()), message.into());
+    }
+
+    /// 記録済みの警告（記録順）
+    pub fn entries(&self) -> Vec<Diagnostic> {
+        self.lock().clone()
+    }
+
+    /// 記録済みの警告の数
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// 警告が記録されていないか
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn record(&self, source: &str, path: Option<PathBuf>, message: String) {
+        match &path {
+            Some(path) => warn!("[{}] {:?}: {}", source, path, message),
+            None => warn!("[{}] {}", source, message),
+        }
+        self.lock().push(Diagnostic { source: source.to_string(), path, message });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Diagnostic>> {
+        // 記録中のパニックで汚染されても、それまでの警告は読めるようにする
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Diagnostic {
+    /// レポート用の1行（`[発生元] パス: 内容`）
+    pub fn label(&self) -> String {
+        match &self.path {
+            Some(path) => format!("[{}] {}: {}", self.source, path.display(), self.message),
+            None => format!("[{}] {}", self.source, self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostics_shared_between_clones() {
+        let diagnostics = Diagnostics::new();
+        let clone = diagnostics.clone();
+        std::thread::spawn(move || clone.warn_at("analyzer", Path::new("logo.png"), "バイナリファイルのためスキップ"))
+            .join()
+            .unwrap();
+        diagnostics.warn("wiki", "mdbookが見つかりません");
+
+        let labels: Vec<String> = diagnostics.entries().iter().map(Diagnostic::label).collect();
+        assert_eq!(
+            labels,
+            vec!["[analyzer] logo.png: バイナリファイルのためスキップ", "[wiki] mdbookが見つかりません"]
+        );
+        assert_eq!(diagnostics.len(), 2);
+    }
+}
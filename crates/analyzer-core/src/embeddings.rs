@@ -0,0 +1,489 @@
+/*!
+ * 埋め込みベクトルによる意味検索（ベクトルインデックス）
+ *
+ * ソースコードをチャンクに分割して埋め込みベクトルを計算し、クエリとのコサイン類似度で検索する
+ * - `index.embeddings` が `none` 以外のとき `analyze_repo` で構築
+ * - `Index::save` でインデックスファイルの隣（`<path>.vectors`）に保存し、`Index::load` で読み込む
+ *
+ * 主な仕様:
+ * - チャンク分割は全文検索と共通（`index.chunk-tokens`・`index.chunk-overlap`）、先頭にファイルの相対パスを付けて埋め込む
+ * - 埋め込みの計算方法（`index.embeddings`）
+ *   - hash: 組み込みの特徴ハッシュ（単語・識別子の構成語・日本語の文字bigramを `HASH_DIMS` 次元に射影、モデル不要）
+ *   - local: Ollamaの `POST /api/embed`（`index.embeddings-url`、未指定時は `summarization.ollama-url`）
+ *   - remote: OpenAI互換の `POST /embeddings`（APIキーは `index.embeddings-api-key-env` の環境変数から読む）
+ * - ベクトルはL2正規化して保存し、内積（コサイン類似度）で順位付けする
+ * - クエリは構築時と同じ方法・モデルで埋め込む（保存したインデックスを別の設定で読んでも結果が変わらないように）
+ * - 保存するのはベクトルと位置のみで、抜粋は `Index::file_content` から読む（`index.content` によらず保存する）
+ *
+ * 制限事項:
+ * - candle・fastembedなどの推論ランタイムは同梱しない（ローカルのモデルはOllama経由で使う）
+ * - hashは語の一致に基づくため、同義語・言い換えは近いと判定できない
+ * - 全チャンクとの総当たりで検索する（近似最近傍探索は行わない）
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use anyhow::{Context, Result};
+
+use config::Config;
+
+use crate::chunking::{self, chunk_content, Chunking};
+use crate::{Index, SearchHit};
+
+/// 特徴ハッシュの次元数
+const HASH_DIMS: usize = 512;
+/// 1回のAPI呼び出しで埋め込むチャンク数
+const BATCH_SIZE: usize = 32;
+/// localの既定モデル
+const DEFAULT_LOCAL_MODEL: &str = "nomic-embed-text";
+/// remoteの既定モデル
+const DEFAULT_REMOTE_MODEL: &str = "text-embedding-3-small";
+/// remoteの既定URL
+const DEFAULT_REMOTE_URL: &str = "https://api.openai.com/v1";
+/// 抜粋の最大文字数
+const EXCERPT_MAX_CHARS: usize = 160;
+/// zstdの圧縮レベル
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// 埋め込みの計算方法（構築時の設定をベクトルと一緒に保存する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Embedder {
+    /// hash|local|remote
+    provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    /// remoteのAPIキーを読む環境変数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_key_env: Option<String>,
+    timeout_secs: u64,
+}
+
+/// ベクトル化したチャンク
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VectorChunk {
+    path: PathBuf,
+    start_line: usize,
+    end_line: usize,
+    vector: Vec<f32>,
+}
+
+/// ベクトルインデックス
+#[derive(Clone, Serialize, Deserialize)]
+pub struct VectorIndex {
+    embedder: Embedder,
+    chunking: Chunking,
+    chunks: Vec<VectorChunk>,
+}
+
+impl std::fmt::Debug for VectorIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VectorIndex")
+            .field("embedder", &self.embedder)
+            .field("chunking", &self.chunking)
+            .field("chunks", &self.chunks.len())
+            .finish()
+    }
+}
+
+/// Ollamaの `/api/embed` のリクエスト
+#[derive(Serialize)]
+struct OllamaEmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// Ollamaの `/api/embed` のレスポンス
+#[derive(Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// OpenAI互換の `/embeddings` のレスポンス
+#[derive(Deserialize)]
+struct RemoteEmbedResponse {
+    data: Vec<RemoteEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct RemoteEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl VectorIndex {
+    /// インデックスのファイル内容からベクトルインデックスを構築
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `config` - 設定（`index.*` と、localの既定URLに `summarization.ollama-url`）
+    ///
+    /// # 戻り値
+    /// * `Result<Option<VectorIndex>>` - ベクトルインデックス（`index.embeddings = "none"` の場合はNone）、またはエラー
+    pub(crate) async fn build(index: &Index, config: &Config) -> Result<Option<Self>> {
+        let Some(embedder) = Embedder::from_config(config) else {
+            return Ok(None);
+        };
+        let chunking = Chunking::new(config.index.chunk_tokens, config.index.chunk_overlap);
+
+        let mut chunks = Vec::new();
+        let mut texts = Vec::new();
+        for file in &index.files {
+            let Some(content) = index.file_content(file) else {
+                continue;
+            };
+            let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+            for chunk in chunk_content(&content, chunking) {
+                texts.push(format!("{}\n{}", relative.to_string_lossy(), chunk.text));
+                chunks.push(VectorChunk {
+                    path: file.path.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    vector: Vec::new(),
+                });
+            }
+        }
+
+        let vectors = embedder.embed(&texts).await?;
+        for (chunk, vector) in chunks.iter_mut().zip(vectors) {
+            chunk.vector = vector;
+        }
+
+        Ok(Some(Self { embedder, chunking, chunks }))
+    }
+
+    /// 保存済みのベクトルインデックスを読み込む
+    ///
+    /// # 引数
+    /// * `path` - ベクトルインデックスのファイル（`vectors_path` の戻り値）
+    ///
+    /// # 戻り値
+    /// * `Result<VectorIndex>` - ベクトルインデックス、またはエラー
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let compressed = std::fs::read(path).with_context(|| format!("ベクトルインデックスを読み込めませんでした: {:?}", path))?;
+        let json = zstd::decode_all(compressed.as_slice()).context("ベクトルインデックスの展開に失敗しました")?;
+        serde_json::from_slice(&json).context("ベクトルインデックスの解析に失敗しました")
+    }
+
+    /// ベクトルインデックスを保存
+    ///
+    /// # 引数
+    /// * `path` - 保存先（`vectors_path` の戻り値）
+    ///
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec(self)?;
+        let compressed = zstd::encode_all(json.as_slice(), COMPRESSION_LEVEL)?;
+        std::fs::write(path, compressed).with_context(|| format!("ベクトルインデックスを保存できませんでした: {:?}", path))
+    }
+
+    /// 指定したファイルのチャンクだけに絞り込む
+    ///
+    /// # 引数
+    /// * `contains` - 残すファイルか
+    ///
+    /// # 戻り値
+    /// * `VectorIndex` - 絞り込んだベクトルインデックス
+    pub(crate) fn filtered(&self, contains: impl Fn(&Path) -> bool) -> Self {
+        Self {
+            embedder: self.embedder.clone(),
+            chunking: self.chunking,
+            chunks: self.chunks.iter().filter(|c| contains(&c.path)).cloned().collect(),
+        }
+    }
+
+    /// クエリと意味の近いチャンクを検索
+    ///
+    /// # 引数
+    /// * `index` - インデックス（抜粋の読み込み元）
+    /// * `query` - 検索クエリ（自然文）
+    /// * `k` - 返す結果の最大数
+    ///
+    /// # 戻り値
+    /// * `Result<Vec<SearchHit>>` - 類似度順のチャンク、またはエラー
+    pub(crate) async fn search(&self, index: &Index, query: &str, k: usize) -> Result<Vec<SearchHit>> {
+        let query_vector = self
+            .embedder
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .context("クエリの埋め込みを取得できませんでした")?;
+
+        let mut scored: Vec<(f32, &VectorChunk)> = self
+            .chunks
+            .iter()
+            .filter(|c| c.vector.len() == query_vector.len())
+            .map(|c| (dot(&c.vector, &query_vector), c))
+            .filter(|(score, _)| *score > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(k);
+
+        Ok(scored
+            .into_iter()
+            .map(|(score, chunk)| SearchHit {
+                path: chunk.path.to_string_lossy().to_string(),
+                score: score as f64,
+                excerpt: chunk_excerpt(index, chunk),
+                start_line: Some(chunk.start_line),
+                end_line: Some(chunk.end_line),
+                module: None,
+                matches: Vec::new(),
+                signature: None,
+            })
+            .collect())
+    }
+}
+
+impl Embedder {
+    /// 設定から埋め込みの計算方法を決める（`none` の場合はNone）
+    fn from_config(config: &Config) -> Option<Self> {
+        let index = &config.index;
+        let timeout_secs = config.summarization.timeout_secs;
+        let embedder = match index.embeddings.as_str() {
+            "hash" => Self {
+                provider: "hash".to_string(),
+                model: None,
+                url: None,
+                api_key_env: None,
+                timeout_secs,
+            },
+            "local" => Self {
+                provider: "local".to_string(),
+                model: Some(index.embeddings_model.clone().unwrap_or_else(|| DEFAULT_LOCAL_MODEL.to_string())),
+                url: Some(index.embeddings_url.clone().unwrap_or_else(|| config.summarization.ollama_url.clone())),
+                api_key_env: None,
+                timeout_secs,
+            },
+            "remote" => Self {
+                provider: "remote".to_string(),
+                model: Some(index.embeddings_model.clone().unwrap_or_else(|| DEFAULT_REMOTE_MODEL.to_string())),
+                url: Some(index.embeddings_url.clone().unwrap_or_else(|| DEFAULT_REMOTE_URL.to_string())),
+                api_key_env: Some(index.embeddings_api_key_env.clone()),
+                timeout_secs,
+            },
+            _ => return None,
+        };
+        Some(embedder)
+    }
+
+    /// テキストを埋め込み、L2正規化したベクトルを返す
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = match self.provider.as_str() {
+            "hash" => texts.iter().map(|t| hash_embedding(t)).collect(),
+            _ => {
+                let client = reqwest::Client::builder()
+                    .timeout(Duration::from_secs(self.timeout_secs))
+                    .build()
+                    .unwrap_or_default();
+                let mut vectors = Vec::with_capacity(texts.len());
+                for batch in texts.chunks(BATCH_SIZE) {
+                    vectors.extend(self.embed_remote(&client, batch).await?);
+                }
+                vectors
+            }
+        };
+        for vector in &mut vectors {
+            normalize(vector);
+        }
+        Ok(vectors)
+    }
+
+    /// Ollama・OpenAI互換APIで埋め込む
+    async fn embed_remote(&self, client: &reqwest::Client, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let base_url = self.url.as_deref().unwrap_or_default().trim_end_matches('/');
+        let model = self.model.as_deref().unwrap_or_default();
+        let request = OllamaEmbedRequest { model, input: texts };
+
+        let (url, builder) = match self.provider.as_str() {
+            "local" => {
+                let url = format!("{}/api/embed", base_url);
+                (url.clone(), client.post(url))
+            }
+            _ => {
+                let env = self.api_key_env.as_deref().unwrap_or_default();
+                let key = std::env::var(env).with_context(|| format!("埋め込みAPIのキーが環境変数 {} に設定されていません", env))?;
+                let url = format!("{}/embeddings", base_url);
+                (url.clone(), client.post(url).bearer_auth(key))
+            }
+        };
+
+        let response = builder
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("埋め込みAPIに接続できませんでした: {}", url))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("埋め込みAPIがエラーを返しました（{}）: {}", status, body));
+        }
+
+        let vectors = match self.provider.as_str() {
+            "local" => {
+                let body: OllamaEmbedResponse = response.json().await.context("埋め込みAPIのレスポンスの解析に失敗しました")?;
+                body.embeddings
+            }
+            _ => {
+                let mut body: RemoteEmbedResponse =
+                    response.json().await.context("埋め込みAPIのレスポンスの解析に失敗しました")?;
+                body.data.sort_by_key(|d| d.index);
+                body.data.into_iter().map(|d| d.embedding).collect()
+            }
+        };
+        if vectors.len() != texts.len() {
+            return Err(anyhow::anyhow!(
+                "埋め込みAPIが返したベクトルの数が一致しません（{}件中{}件）",
+                texts.len(),
+                vectors.len()
+            ));
+        }
+        Ok(vectors)
+    }
+}
+
+/// ベクトルインデックスの保存先（インデックスファイルのパス + `.vectors`）
+///
+/// # 引数
+/// * `index_path` - インデックスファイルのパス
+///
+/// # 戻り値
+/// * `PathBuf` - ベクトルインデックスのファイル
+pub(crate) fn vectors_path(index_path: &Path) -> PathBuf {
+    let mut path = index_path.as_os_str().to_owned();
+    path.push(".vectors");
+    PathBuf::from(path)
+}
+
+/// 特徴ハッシュで埋め込む
+///
+/// トークン（単語・日本語の文字bigram）に加え、`snake_case`・`camelCase` の識別子は構成語も特徴にする。
+/// 頻出する語に引きずられないよう、出現回数は `1 + ln(回数)` で重み付けする
+fn hash_embedding(text: &str) -> Vec<f32> {
+    let mut counts: HashMap<String, f32> = HashMap::new();
+    for token in chunking::tokenize(text) {
+        let words = identifier_words(&text[token.from..token.to]);
+        if words.len() > 1 {
+            for word in words {
+                *counts.entry(word).or_default() += 0.5;
+            }
+        }
+        *counts.entry(token.text).or_default() += 1.0;
+    }
+
+    let mut vector = vec![0.0f32; HASH_DIMS];
+    for (feature, count) in counts {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % HASH_DIMS as u64) as usize] += sign * (1.0 + count.max(1.0).ln());
+    }
+    vector
+}
+
+/// 識別子を構成語に分割（`build_graph` → build, graph、`renderPage` → render, page）
+fn identifier_words(identifier: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in identifier.chars() {
+        if (c == '_' || (c.is_uppercase() && previous_lower)) && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if c != '_' {
+            current.extend(c.to_lowercase());
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// FNV-1aハッシュ（実行ごとに変わらないハッシュ）
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ *b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// L2正規化（零ベクトルはそのまま）
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// チャンクの先頭からの抜粋（空白を詰めて `EXCERPT_MAX_CHARS` 文字まで）
+fn chunk_excerpt(index: &Index, chunk: &VectorChunk) -> String {
+    let Some(content) = index.files.iter().find(|f| f.path == chunk.path).and_then(|f| index.file_content(f)) else {
+        return String::new();
+    };
+    let text: Vec<&str> = content
+        .lines()
+        .skip(chunk.start_line.saturating_sub(1))
+        .take(chunk.end_line + 1 - chunk.start_line.max(1))
+        .flat_map(str::split_whitespace)
+        .collect();
+    let excerpt = text.join(" ");
+    match excerpt.char_indices().nth(EXCERPT_MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &excerpt[..end]),
+        None => excerpt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+
+    #[tokio::test]
+    async fn test_hash_embeddings_rank_related_chunks() {
+        let file = |path: &str, content: &str| FileInfo {
+            path: PathBuf::from("/repo").join(path),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![
+                file("src/graph.rs", "// モジュールの依存グラフを生成する\nfn build_dependency_graph() {}\n"),
+                file("src/config.rs", "// 設定ファイルを読み込む\nfn load_config(path: &str) {}\n"),
+                file("src/publish.rs", "// gh-pagesブランチに公開する\nfn publish_pages() {}\n"),
+            ],
+            ..Default::default()
+        };
+
+        let vectors = VectorIndex::build(&index, &Config::default()).await.unwrap().unwrap();
+        assert_eq!(vectors.chunks.len(), 3);
+        let hits = vectors.search(&index, "依存グラフを作る処理", 2).await.unwrap();
+        assert_eq!(hits[0].path, "/repo/src/graph.rs");
+        assert_eq!((hits[0].start_line, hits[0].end_line), (Some(1), Some(2)));
+        assert!(hits[0].excerpt.starts_with("// モジュールの依存グラフを生成する fn build_dependency_graph()"));
+
+        // 識別子の構成語でも一致する
+        let hits = vectors.search(&index, "load config", 1).await.unwrap();
+        assert_eq!(hits[0].path, "/repo/src/config.rs");
+
+        let dir = std::env::temp_dir().join(format!("deeprepo-vectors-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = vectors_path(&dir.join("index.bin"));
+        vectors.save(&path).unwrap();
+        let loaded = VectorIndex::load(&path).unwrap();
+        assert_eq!(loaded.embedder, vectors.embedder);
+        assert_eq!(loaded.filtered(|p| p.ends_with("src/publish.rs")).chunks.len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut config = Config::default();
+        config.index.embeddings = "none".to_string();
+        assert!(VectorIndex::build(&index, &config).await.unwrap().is_none());
+        assert_eq!(identifier_words("renderPage_v2"), vec!["render", "page", "v2"]);
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+ * ファイル内容の文字コード判定
+ *
+ * 読み込んだバイト列をバイナリとテキストに分け、テキストはUTF-8に変換する
+ * - バイナリ: 先頭 `SNIFF_BYTES` バイトにNULを含むファイル（Gitの判定と同じ）
+ * - UTF-8（BOM付きを含む）・UTF-16（BOM付き）
+ * - 日本語の旧来の文字コード: Shift_JIS（Windows-31J）・EUC-JP（encoding_rsで変換）
+ *
+ * 主な仕様:
+ * - UTF-8として正しいバイト列はそのまま使う
+ * - Shift_JIS・EUC-JPの両方で変換できる場合は、ひらがな・カタカナ・漢字の割合が高い方を選ぶ
+ *   （EUC-JPのかなはShift_JISの半角カタカナとしても解釈できるため）
+ * - どの文字コードでも変換できない場合は、不正なバイトを置換文字にしたUTF-8とする
+ *
+ * 制限事項:
+ * - 日本語以外の旧来の文字コード（Latin-1・GBKなど）は判定しない
+ * - BOMのないUTF-16はバイナリと判定される
+ */
+
+use encoding_rs::{Encoding, EUC_JP, SHIFT_JIS};
+
+/// バイナリ判定で調べる先頭のバイト数
+const SNIFF_BYTES: usize = 8000;
+
+/// デコードしたファイル内容
+#[derive(Debug, PartialEq)]
+pub(crate) enum Decoded {
+    /// テキスト（UTF-8に変換した内容と、元の文字コード名）
+    Text { content: String, encoding: &'static str },
+    /// バイナリファイル
+    Binary,
+}
+
+/// バイト列をデコード
+///
+/// # 引数
+/// * `bytes` - ファイルの内容
+///
+/// # 戻り値
+/// * `Decoded` - UTF-8に変換したテキスト、またはバイナリ
+pub(crate) fn decode(bytes: &[u8]) -> Decoded {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (content, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Decoded::Text { content: content.into_owned(), encoding: encoding.name() };
+    }
+    if bytes[..bytes.len().min(SNIFF_BYTES)].contains(&0) {
+        return Decoded::Binary;
+    }
+    if let Ok(content) = std::str::from_utf8(bytes) {
+        return Decoded::Text { content: content.to_string(), encoding: "UTF-8" };
+    }
+
+    let candidates = [SHIFT_JIS, EUC_JP]
+        .into_iter()
+        .filter_map(|encoding| {
+            let content = encoding.decode_without_bom_handling_and_without_replacement(bytes)?;
+            Some((japanese_ratio(&content), encoding.name(), content.into_owned()))
+        });
+    match candidates.max_by(|a, b| a.0.total_cmp(&b.0)) {
+        Some((_, encoding, content)) => Decoded::Text { content, encoding },
+        None => Decoded::Text {
+            content: String::from_utf8_lossy(bytes).into_owned(),
+            encoding: "UTF-8",
+        },
+    }
+}
+
+/// テキストとして読める内容を取得（バイナリは不正なバイトを置換文字にしたUTF-8）
+pub(crate) fn decode_lossy(bytes: &[u8]) -> String {
+    match decode(bytes) {
+        Decoded::Text { content, .. } => content,
+        Decoded::Binary => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// ASCII以外の文字のうち、ひらがな・全角カタカナ・漢字・全角記号の割合
+fn japanese_ratio(content: &str) -> f64 {
+    let (mut japanese, mut non_ascii) = (0usize, 0usize);
+    for c in content.chars().filter(|c| !c.is_ascii()) {
+        non_ascii += 1;
+        if matches!(c, '\u{3000}'..='\u{30FF}' | '\u{4E00}'..='\u{9FFF}' | '\u{FF01}'..='\u{FF5E}') {
+            japanese += 1;
+        }
+    }
+    if non_ascii == 0 {
+        return 0.0;
+    }
+    japanese as f64 / non_ascii as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(encoding: &'static Encoding, text: &str) -> Vec<u8> {
+        encoding.encode(text).0.into_owned()
+    }
+
+    #[test]
+    fn test_decode_japanese_legacy_encodings() {
+        let text = "// 設定を読み込む\nfn load() {}\n";
+        assert_eq!(decode(text.as_bytes()), Decoded::Text { content: text.to_string(), encoding: "UTF-8" });
+        assert_eq!(
+            decode(&encode(SHIFT_JIS, text)),
+            Decoded::Text { content: text.to_string(), encoding: "Shift_JIS" }
+        );
+        // かなだけのEUC-JPはShift_JISの半角カタカナとしても読めるが、EUC-JPを選ぶ
+        let kana = "# かなのコメント\n";
+        assert_eq!(decode(&encode(EUC_JP, kana)), Decoded::Text { content: kana.to_string(), encoding: "EUC-JP" });
+
+        let mut bom = vec![0xEF, 0xBB, 0xBF];
+        bom.extend_from_slice(b"x = 1\n");
+        assert_eq!(decode(&bom), Decoded::Text { content: "x = 1\n".to_string(), encoding: "UTF-8" });
+    }
+
+    #[test]
+    fn test_decode_detects_binary() {
+        assert_eq!(decode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Decoded::Binary);
+        assert_eq!(decode_lossy(b"ab\0c"), "ab\0c");
+    }
+}
@@ -0,0 +1,193 @@
+/*!
+ * エントリーポイントの検出
+ *
+ * ファイル名の規約（`main.rs`・`index.ts` など）に加えて、Webフレームワークのアプリ定義と
+ * Dockerfileの起動コマンドから、システムがどのように起動するかを検出する
+ *
+ * 主な仕様:
+ * - フレームワーク: FastAPI・Flaskのアプリオブジェクト、Expressなどの `app.listen`、NestJSの `NestFactory.create`、
+ *   axum・actix-webのサーバー起動、Springの `@SpringBootApplication`、Goの `http.ListenAndServe`
+ * - Dockerfile（`Dockerfile`・`Dockerfile.*`・`*.Dockerfile`）の `ENTRYPOINT`・`CMD`（exec形式は空白区切りに変換）
+ * - 各エントリーポイントに種類（`Entrypoint::kind`）と、検出した行・コマンドを付ける
+ * - ファイル名の規約とフレームワークの両方に一致したファイルはフレームワークの種類とする
+ *
+ * 制限事項:
+ * - フレームワークの検出は行単位のパターンの一致による（別のファイルで作ったアプリを起動する構成は、起動する行のあるファイルのみ）
+ * - Dockerfileの `ARG`・環境変数の展開、マルチステージビルドのステージの区別は行わない
+ */
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// エントリーポイント
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Entrypoint {
+    pub path: PathBuf,
+    /// 種類（main|config|fastapi|flask|express|nestjs|axum|actix-web|spring-boot|go-http|docker）
+    pub kind: String,
+    /// 検出した行（1始まり、ファイル名の規約・設定によるものはNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// 検出した行の内容、またはDockerの起動コマンド
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+impl Entrypoint {
+    /// 種類の説明（「FastAPIアプリ」など）
+    pub fn kind_label(&self) -> &str {
+        match self.kind.as_str() {
+            "main" => "実行ファイルのエントリーポイント",
+            "config" => "設定で指定したエントリーポイント",
+            "fastapi" => "FastAPIアプリ",
+            "flask" => "Flaskアプリ",
+            "express" => "Node.jsのHTTPサーバー（Expressなど）",
+            "nestjs" => "NestJSアプリ",
+            "axum" => "axumのHTTPサーバー",
+            "actix-web" => "actix-webのHTTPサーバー",
+            "spring-boot" => "Spring Bootアプリ",
+            "go-http" => "GoのHTTPサーバー",
+            "docker" => "コンテナの起動コマンド",
+            other => other,
+        }
+    }
+
+    /// 起動方法の1行の説明（Markdown、パスはリポジトリルートからの相対パス）
+    ///
+    /// # 引数
+    /// * `repo_path` - リポジトリのパス
+    ///
+    /// # 戻り値
+    /// * `String` - 例: `` `app/main.py`（12行目）: FastAPIアプリ — `app = FastAPI()` ``
+    pub fn describe(&self, repo_path: &Path) -> String {
+        let path = self.path.strip_prefix(repo_path).unwrap_or(&self.path).to_string_lossy().replace('\\', "/");
+        let location = match self.line {
+            Some(line) => format!("`{}`（{}行目）", path, line),
+            None => format!("`{}`", path),
+        };
+        if self.detail.is_empty() {
+            format!("{}: {}", location, self.kind_label())
+        } else {
+            format!("{}: {} — `{}`", location, self.kind_label(), self.detail)
+        }
+    }
+}
+
+/// ソースコードからフレームワークのエントリーポイントを検出
+///
+/// # 引数
+/// * `path` - ファイルパス
+/// * `language` - 言語
+/// * `content` - ファイル内容
+///
+/// # 戻り値
+/// * `Option<Entrypoint>` - 最初に一致した行のエントリーポイント（ない場合はNone）
+pub(crate) fn detect_in_source(path: &Path, language: &str, content: &str) -> Option<Entrypoint> {
+    let patterns: &[(&str, &str)] = match language {
+        "py" => &[("fastapi", "= FastAPI("), ("flask", "= Flask(")],
+        "ts" | "js" => &[("nestjs", "NestFactory.create"), ("express", "app.listen("), ("express", "server.listen(")],
+        "rs" => &[("axum", "axum::serve("), ("axum", "axum::Server::bind"), ("actix-web", "HttpServer::new(")],
+        "java" | "kt" => &[("spring-boot", "@SpringBootApplication")],
+        "go" => &[("go-http", "http.ListenAndServe")],
+        _ => return None,
+    };
+    content.lines().enumerate().find_map(|(i, line)| {
+        let trimmed = line.trim();
+        if trimmed.starts_with("//") || trimmed.starts_with('#') {
+            return None;
+        }
+        let (kind, _) = patterns.iter().find(|(_, pattern)| trimmed.contains(pattern))?;
+        Some(Entrypoint {
+            path: path.to_path_buf(),
+            kind: kind.to_string(),
+            line: Some(i + 1),
+            detail: trimmed.to_string(),
+        })
+    })
+}
+
+/// Dockerfileか
+pub(crate) fn is_dockerfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name == "Dockerfile" || name.starts_with("Dockerfile.") || name.ends_with(".Dockerfile"))
+}
+
+/// Dockerfileの `ENTRYPOINT`・`CMD` を取得
+///
+/// # 引数
+/// * `path` - Dockerfileのパス
+/// * `content` - Dockerfileの内容
+///
+/// # 戻り値
+/// * `Vec<Entrypoint>` - 最後の `ENTRYPOINT` と最後の `CMD`（この順、ない場合は含めない）
+pub(crate) fn parse_dockerfile(path: &Path, content: &str) -> Vec<Entrypoint> {
+    let mut entrypoint = None;
+    let mut cmd = None;
+    let mut lines = content.lines().enumerate();
+    while let Some((i, line)) = lines.next() {
+        // 行末の `\` による継続行を1行にまとめる
+        let mut instruction = line.trim().to_string();
+        while instruction.ends_with('\\') {
+            instruction.pop();
+            match lines.next() {
+                Some((_, next)) => instruction.push_str(next.trim()),
+                None => break,
+            }
+        }
+        let Some((keyword, args)) = instruction.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let target = match keyword.to_uppercase().as_str() {
+            "ENTRYPOINT" => &mut entrypoint,
+            "CMD" => &mut cmd,
+            _ => continue,
+        };
+        *target = Some(Entrypoint {
+            path: path.to_path_buf(),
+            kind: "docker".to_string(),
+            line: Some(i + 1),
+            detail: docker_command(args.trim()),
+        });
+    }
+    entrypoint.into_iter().chain(cmd).collect()
+}
+
+/// exec形式（`["node", "server.js"]`）のコマンドを空白区切りにする
+fn docker_command(args: &str) -> String {
+    match serde_json::from_str::<Vec<String>>(args) {
+        Ok(parts) => parts.join(" "),
+        Err(_) => args.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_framework_entrypoints() {
+        let fastapi = detect_in_source(Path::new("/repo/app/api.py"), "py", "from fastapi import FastAPI\n\napp = FastAPI()\n").unwrap();
+        assert_eq!((fastapi.kind.as_str(), fastapi.line), ("fastapi", Some(3)));
+        assert_eq!(fastapi.describe(Path::new("/repo")), "`app/api.py`（3行目）: FastAPIアプリ — `app = FastAPI()`");
+
+        let express = "const app = express();\n// app.listen(1)\napp.listen(3000, () => {});\n";
+        assert_eq!(detect_in_source(Path::new("server.js"), "js", express).unwrap().line, Some(3));
+        let axum = "#[tokio::main]\nasync fn main() {\n    axum::serve(listener, app).await.unwrap();\n}\n";
+        assert_eq!(detect_in_source(Path::new("main.rs"), "rs", axum).unwrap().kind, "axum");
+        assert_eq!(detect_in_source(Path::new("lib.rs"), "rs", "pub fn add() {}\n"), None);
+    }
+
+    #[test]
+    fn test_parse_dockerfile_commands() {
+        assert!(is_dockerfile(Path::new("deploy/api.Dockerfile")));
+        assert!(is_dockerfile(Path::new("Dockerfile.prod")));
+        assert!(!is_dockerfile(Path::new("docker/README.md")));
+
+        let content = "FROM node:20\nCMD [\"npm\", \"start\"]\nENTRYPOINT [\"docker-entrypoint.sh\"]\nCMD node \\\n  server.js\n";
+        let entrypoints = parse_dockerfile(Path::new("Dockerfile"), content);
+        let details: Vec<(&str, Option<usize>)> = entrypoints.iter().map(|e| (e.detail.as_str(), e.line)).collect();
+        assert_eq!(details, vec![("docker-entrypoint.sh", Some(3)), ("node server.js", Some(4))]);
+    }
+}
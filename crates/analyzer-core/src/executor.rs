@@ -0,0 +1,218 @@
+/*!
+ * 優先度付きのワーカープール
+ *
+ * Wiki・スライドの生成タスクの同時実行数を1つの上限で管理する
+ * - build-allなどでWikiとスライドを続けて生成しても、LLMの呼び出しとCPUの使用が上限を超えない
+ * - ビルダー間で共有する場合は、同じ `Executor` を `with_executor` で渡す（クローンは同じプールを指す）
+ *
+ * 主な仕様:
+ * - 同時に実行するタスク数は `summarization.workers`（0の場合はCPU数）
+ * - 空きを待つタスクは優先度の高い順（セクション → モジュール → 付録）、同じ優先度では登録順に実行する
+ * - 実行中のタスクは中断しない（優先度は空きができたときの順番にのみ使う）
+ *
+ * 制限事項:
+ * - 実行枠を持つタスクの中で、同じプールのタスクの完了を待ってはいけない（枠が足りない場合にデッドロックする）
+ *   - 他のタスクを待つだけのタスクは `tokio::spawn` で起動する
+ */
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// タスクの優先度（先に並べたものほど先に実行する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// 利用者が最初に読むセクション（概要・アーキテクチャなど）
+    Section,
+    /// モジュールごとの解説
+    Module,
+    /// 付録（ライセンス・サードパーティ・未使用コードなど）
+    Appendix,
+}
+
+/// 優先度付きのワーカープール
+#[derive(Clone)]
+pub struct Executor {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    workers: usize,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    running: usize,
+    /// 登録順の通し番号（同じ優先度を登録順に並べる）
+    sequence: u64,
+    waiting: BinaryHeap<Waiter>,
+}
+
+/// 空きを待つタスク
+struct Waiter {
+    priority: Priority,
+    sequence: u64,
+    wake: oneshot::Sender<Permit>,
+}
+
+/// 実行枠（破棄すると待っているタスクに譲る）
+struct Permit {
+    inner: Option<Arc<Inner>>,
+}
+
+impl Executor {
+    /// ワーカープールを作成
+    ///
+    /// # 引数
+    /// * `workers` - 同時に実行するタスク数（0の場合はCPU数）
+    ///
+    /// # 戻り値
+    /// * `Self` - ワーカープール
+    pub fn new(workers: usize) -> Self {
+        let workers = match workers {
+            0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+            n => n,
+        };
+        Self {
+            inner: Arc::new(Inner {
+                workers,
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// 同時に実行するタスク数
+    pub fn workers(&self) -> usize {
+        self.inner.workers
+    }
+
+    /// 実行枠の空きを待ってタスクを実行
+    ///
+    /// # 引数
+    /// * `priority` - 優先度
+    /// * `task` - タスク
+    ///
+    /// # 戻り値
+    /// * `JoinHandle<T>` - タスクの結果
+    pub fn spawn<F>(&self, priority: Priority, task: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let inner = self.inner.clone();
+        tokio::spawn(async move {
+            let _permit = Inner::acquire(&inner, priority).await;
+            task.await
+        })
+    }
+}
+
+impl Inner {
+    /// 実行枠を取得（空きがなければ優先度順に待つ）
+    async fn acquire(inner: &Arc<Inner>, priority: Priority) -> Permit {
+        let receiver = {
+            let mut state = inner.state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.running < inner.workers && state.waiting.is_empty() {
+                state.running += 1;
+                return Permit { inner: Some(inner.clone()) };
+            }
+            let (wake, receiver) = oneshot::channel();
+            state.sequence += 1;
+            let sequence = state.sequence;
+            state.waiting.push(Waiter { priority, sequence, wake });
+            receiver
+        };
+        // 送信側はプールの破棄時のみ閉じる（その場合は枠なしで実行する）
+        receiver.await.unwrap_or(Permit { inner: None })
+    }
+
+    /// 実行枠を返す（待っているタスクがあれば、優先度の最も高いタスクに直接譲る）
+    fn release(inner: Arc<Inner>) {
+        let mut state = inner.state.lock().unwrap_or_else(|e| e.into_inner());
+        while let Some(waiter) = state.waiting.pop() {
+            match waiter.wake.send(Permit { inner: Some(inner.clone()) }) {
+                Ok(()) => return,
+                // 待っていたタスクが中断された場合は次のタスクに譲る
+                Err(mut permit) => {
+                    permit.inner = None;
+                }
+            }
+        }
+        state.running -= 1;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            Inner::release(inner);
+        }
+    }
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    /// BinaryHeapは最大のものから取り出すため、優先度が高く登録の早いものを大きくする
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority).then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_executor_runs_by_priority() {
+        let executor = Executor::new(1);
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        // 最初のタスクが枠を持っている間に、優先度の異なるタスクを登録する
+        let (release, blocked) = oneshot::channel::<()>();
+        let first = executor.spawn(Priority::Module, async move {
+            let _ = blocked.await;
+        });
+        tokio::task::yield_now().await;
+
+        let mut handles = Vec::new();
+        for (name, priority) in [
+            ("license", Priority::Appendix),
+            ("module-a", Priority::Module),
+            ("overview", Priority::Section),
+            ("module-b", Priority::Module),
+            ("architecture", Priority::Section),
+        ] {
+            let order = order.clone();
+            handles.push(executor.spawn(priority, async move {
+                order.lock().unwrap().push(name);
+            }));
+            tokio::task::yield_now().await;
+        }
+
+        release.send(()).unwrap();
+        first.await.unwrap();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["overview", "architecture", "module-a", "module-b", "license"]);
+        assert_eq!(executor.inner.state.lock().unwrap().running, 0);
+    }
+}
@@ -0,0 +1,228 @@
+/*!
+ * 用語の抽出
+ *
+ * 型・関数・メソッド・ファイルの名前を単語に分け、コードベース全体で繰り返し現れるドメインの用語を挙げる
+ * - Wikiの「用語集」の章と、モジュールのページの用語へのリンクに使う
+ *
+ * 主な仕様:
+ * - 名前は `snake_case`・`camelCase`・`PascalCase`・`kebab-case` の区切りで単語に分ける（`HTTPServer` → `http`・`server`）
+ * - 大文字・小文字、単数・複数（`orders` → `order`、`categories` → `category`）の違いは同じ用語にまとめ、
+ *   その語を含む名前（`OrderItem`・`create_order` など）を関連する識別子として添える
+ * - 3文字以上の英字の単語のうち、プログラミング一般の語（`get`・`config`・`handler` など）を除いたもの
+ * - テスト以外の `MIN_TERM_FILES` 個以上のファイルに現れる語を、現れるファイル数・名前の数の多い順に `MAX_TERMS` 件まで選ぶ
+ * - 用語と同じ名前の型があれば、その型の宣言の位置とドキュメントコメントを定義の手がかりとして添える
+ *
+ * 制限事項:
+ * - コメント・文字列中の語は数えない
+ * - 複数の単語からなる用語（`line item` など）は単語ごとに扱う
+ * - 単数・複数の変換は英語の規則的な変化のみ
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Index;
+
+/// 用語集に載せる用語の最大数
+const MAX_TERMS: usize = 40;
+/// 用語とみなすのに必要な、語が現れるファイルの数
+const MIN_TERM_FILES: usize = 2;
+/// 用語ごとに添える関連する識別子の最大数
+const MAX_IDENTIFIERS: usize = 8;
+
+/// 用語から除くプログラミング一般の語
+const STOP_WORDS: &[&str] = &[
+    "abstract", "add", "all", "any", "api", "app", "arg", "args", "assert", "async", "await", "base", "begin", "bool",
+    "buf", "buffer", "build", "builder", "byte", "bytes", "cache", "call", "callback", "can", "char", "check", "class",
+    "client", "clone", "close", "cmd", "code", "common", "config", "const", "content", "context", "core", "count",
+    "create", "ctx", "data", "debug", "default", "delete", "dir", "does", "entry", "enum", "equal", "err", "error",
+    "event", "expect", "factory", "fetch", "field", "file", "find", "first", "fmt", "for", "format", "from", "func",
+    "function", "generate", "get", "handle", "handler", "has", "helper", "http", "https", "impl", "index", "info",
+    "init", "input", "int", "interface", "into", "is", "item", "iter", "json", "key", "last", "len", "lib", "line",
+    "list", "load", "log", "main", "make", "manager", "map", "max", "message", "method", "min", "mock", "mod",
+    "module", "name", "new", "next", "node", "not", "num", "number", "object", "old", "open", "option", "output",
+    "param", "parse", "path", "prev", "print", "process", "props", "read", "remove", "render", "req", "request",
+    "res", "reset", "response", "result", "return", "run", "save", "self", "send", "server", "service", "set",
+    "setup", "should", "size", "spec", "src", "start", "state", "str", "string", "struct", "test", "the", "this",
+    "tmp", "to", "trait", "type", "update", "uri", "url", "use", "util", "utils", "val", "validate", "value", "vec",
+    "with", "write",
+];
+
+/// コードベースに繰り返し現れる用語
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    /// 表記（同じ名前の型があればその型名、なければ小文字の単数形）
+    pub term: String,
+    /// その語を含む名前（多い順、最大 `MAX_IDENTIFIERS` 件）
+    pub identifiers: Vec<String>,
+    /// その語を含む名前の数（重複を含む）
+    pub occurrences: usize,
+    /// 語が現れるファイル（パス順）
+    pub files: Vec<PathBuf>,
+    /// 用語と同じ名前の型を宣言したファイルと行
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defined_in: Option<(PathBuf, usize)>,
+    /// 用語と同じ名前の型のドキュメントコメント（ない場合は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub documentation: String,
+}
+
+/// 用語ごとの集計
+#[derive(Default)]
+struct Tally {
+    identifiers: BTreeMap<String, usize>,
+    occurrences: usize,
+    files: BTreeSet<PathBuf>,
+}
+
+/// インデックスから用語を抽出
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `Vec<GlossaryTerm>` - 用語（表記の大文字・小文字を区別しない辞書順）
+pub(crate) fn extract_terms(index: &Index) -> Vec<GlossaryTerm> {
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+    for file in index.files.iter().filter(|f| !f.is_test) {
+        let names = file.symbols.iter().filter(|s| s.kind != "impl").map(|s| s.name.as_str()).chain(std::iter::once(file.name.as_str()));
+        for name in names {
+            let words: BTreeSet<String> = split_words(name).iter().filter_map(|w| normalize(w)).collect();
+            for word in words {
+                let tally = tallies.entry(word).or_default();
+                *tally.identifiers.entry(name.to_string()).or_default() += 1;
+                tally.occurrences += 1;
+                tally.files.insert(file.path.clone());
+            }
+        }
+    }
+
+    let mut ranked: Vec<(String, Tally)> = tallies.into_iter().filter(|(_, t)| t.files.len() >= MIN_TERM_FILES).collect();
+    ranked.sort_by(|(a, ta), (b, tb)| {
+        tb.files.len().cmp(&ta.files.len()).then(tb.occurrences.cmp(&ta.occurrences)).then_with(|| a.cmp(b))
+    });
+    ranked.truncate(MAX_TERMS);
+
+    let mut terms: Vec<GlossaryTerm> = ranked
+        .into_iter()
+        .map(|(word, tally)| {
+            // 用語と同じ名前（単数・複数を含む）の型
+            let declaration = index.files.iter().filter(|f| !f.is_test).find_map(|file| {
+                file.symbols
+                    .iter()
+                    .find(|s| s.is_type() && normalize(&s.name).as_deref() == Some(word.as_str()) && split_words(&s.name).len() == 1)
+                    .map(|s| (file, s))
+            });
+            let mut identifiers: Vec<(String, usize)> = tally.identifiers.into_iter().collect();
+            identifiers.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            GlossaryTerm {
+                term: declaration.map_or_else(|| word.clone(), |(_, s)| s.name.clone()),
+                identifiers: identifiers.into_iter().take(MAX_IDENTIFIERS).map(|(name, _)| name).collect(),
+                occurrences: tally.occurrences,
+                files: tally.files.into_iter().collect(),
+                defined_in: declaration.map(|(file, s)| (file.path.clone(), s.start_line)),
+                documentation: declaration.map(|(_, s)| s.documentation.trim().to_string()).unwrap_or_default(),
+            }
+        })
+        .collect();
+    terms.sort_by_key(|t| t.term.to_lowercase());
+    terms
+}
+
+/// 名前を単語に分ける（`HTTPServer` → `HTTP`・`Server`、`create_order` → `create`・`order`）
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for part in name.split(|c: char| !c.is_ascii_alphanumeric()).filter(|p| !p.is_empty()) {
+        let chars: Vec<char> = part.chars().collect();
+        let mut current = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            let boundary = i > 0
+                && c.is_ascii_uppercase()
+                && (chars[i - 1].is_ascii_lowercase()
+                    || chars[i - 1].is_ascii_digit()
+                    || (chars[i - 1].is_ascii_uppercase() && chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase())));
+            if boundary && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        if !current.is_empty() {
+            words.push(current);
+        }
+    }
+    words
+}
+
+/// 単語を用語のキー（小文字の単数形）にする（用語にしない語はNone）
+fn normalize(word: &str) -> Option<String> {
+    if word.len() < 3 || !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let word = word.to_ascii_lowercase();
+    let singular = if let Some(stem) = word.strip_suffix("ies").filter(|s| s.len() >= 3) {
+        format!("{}y", stem)
+    } else if let Some(stem) = word.strip_suffix("ses").filter(|s| s.ends_with('s') || s.ends_with("as")) {
+        format!("{}s", stem)
+    } else if word.ends_with('s') && !["ss", "us", "is"].iter().any(|s| word.ends_with(s)) && word.len() > 3 {
+        word[..word.len() - 1].to_string()
+    } else {
+        word
+    };
+    (!STOP_WORDS.contains(&singular.as_str())).then_some(singular)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileInfo, Symbol};
+
+    fn symbol(kind: &str, name: &str, documentation: &str) -> Symbol {
+        Symbol { kind: kind.to_string(), name: name.to_string(), start_line: 3, documentation: documentation.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_split_and_normalize_words() {
+        assert_eq!(split_words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(split_words("create_order_items"), ["create", "order", "items"]);
+        assert_eq!(split_words("parseV2Response"), ["parse", "V2", "Response"]);
+        assert_eq!(normalize("Categories").as_deref(), Some("category"));
+        assert_eq!(normalize("Orders").as_deref(), Some("order"));
+        assert_eq!(normalize("addresses").as_deref(), Some("address"));
+        assert_eq!(normalize("status").as_deref(), Some("status"));
+        assert_eq!(normalize("handlers"), None);
+        assert_eq!(normalize("V2"), None);
+    }
+
+    #[test]
+    fn test_extract_terms_across_files() {
+        let file = |path: &str, symbols: Vec<Symbol>| FileInfo {
+            path: PathBuf::from(path),
+            name: PathBuf::from(path).file_stem().unwrap().to_string_lossy().to_string(),
+            symbols,
+            ..Default::default()
+        };
+        let index = Index {
+            files: vec![
+                file("src/order.rs", vec![symbol("struct", "Order", "A customer's purchase."), symbol("function", "create_order", "")]),
+                file("src/api.rs", vec![symbol("function", "list_orders", ""), symbol("function", "get_invoice", "")]),
+                file("src/billing.rs", vec![symbol("struct", "InvoiceLine", ""), symbol("function", "bill_order", "")]),
+                FileInfo { is_test: true, ..file("tests/invoice_test.rs", vec![symbol("function", "invoice_total", "")]) },
+            ],
+            ..Default::default()
+        };
+
+        let terms = extract_terms(&index);
+        let names: Vec<&str> = terms.iter().map(|t| t.term.as_str()).collect();
+        assert_eq!(names, ["invoice", "Order"]);
+        let order = &terms[1];
+        assert_eq!(order.files.len(), 3);
+        assert_eq!(order.identifiers[0], "Order");
+        assert!(order.identifiers.contains(&"list_orders".to_string()));
+        assert_eq!(order.defined_in, Some((PathBuf::from("src/order.rs"), 3)));
+        assert_eq!(order.documentation, "A customer's purchase.");
+        assert_eq!(terms[0].files, [PathBuf::from("src/api.rs"), PathBuf::from("src/billing.rs")]);
+        assert_eq!(terms[0].defined_in, None);
+    }
+}
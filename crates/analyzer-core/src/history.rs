@@ -0,0 +1,461 @@
+/*!
+ * Git履歴の解析
+ *
+ * コミット履歴を走査し、ファイルごとの変更回数（churn）・作成者・最終更新日を集計する
+ * - 重要度スコアの変更頻度と、Wikiのホットスポット（変更が多く複雑なファイル）に使う
+ *
+ * 主な仕様:
+ * - HEADから親を辿り、各コミットと最初の親の差分で変更されたファイルを数える
+ * - 作成者はコミットのauthor名で集計し、コミット数の多い順に `MAX_AUTHORS` 人まで保持
+ * - 最終更新日は最も新しいコミットのauthor日時
+ * - 月ごとの変更回数は、走査した中で最も新しいコミットの月までの `HISTORY_MONTHS` か月分（古い順）
+ * - Gitリポジトリでない場合・`analysis.git-history = false` の場合は空
+ * - 作業メモのコメント（todosモジュール）の行は、`git blame` 相当でその行を最後に変更した作成者を調べる
+ * - プロジェクトの歩み（`TimelineEvent`）は、タグ（リリース）・大きなコミット（大規模な変更）・ドキュメントの出力先のみを変更したコミット（ドキュメントの再生成）を古い順に並べる
+ *
+ * 制限事項:
+ * - 直近 `MAX_HISTORY_COMMITS` 件のコミットのみを走査
+ * - リネームは追跡しない（リネーム前の履歴は別のパスとして数える）
+ * - マージコミットは最初の親との差分のみを数える（プロジェクトの歩みの大規模な変更・ドキュメントの再生成には含めない）
+ * - プロジェクトの歩みは種類ごとに新しい順に `MAX_TIMELINE_EVENTS` 件まで、gh-pagesなどHEADから辿れないブランチのコミットは含まない
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+/// 走査するコミット数の上限
+const MAX_HISTORY_COMMITS: usize = 1000;
+/// ファイルごとに保持する作成者の最大数
+const MAX_AUTHORS: usize = 3;
+/// 月ごとの変更回数を保持する月数
+const HISTORY_MONTHS: usize = 12;
+/// スパークラインの文字（低い順）
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+/// 大規模な変更とみなすコミットの変更ファイル数
+const LARGE_COMMIT_FILES: usize = 20;
+/// 大規模な変更とみなすコミットの変更行数（追加 + 削除）
+const LARGE_COMMIT_LINES: usize = 1000;
+/// プロジェクトの歩みに種類ごとに含める出来事の最大数
+const MAX_TIMELINE_EVENTS: usize = 20;
+/// 生成したドキュメントの出力先とみなすディレクトリ
+const DOC_OUTPUT_DIRS: &[&str] = &["docs", "doc", "book", "site", "wiki"];
+
+/// ファイルの変更履歴
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileHistory {
+    /// 変更したコミット数（churn）
+    pub commits: usize,
+    /// コミット数の多い作成者（多い順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<AuthorCommits>,
+    /// 最後に変更したコミットの日時（UNIX時刻、秒）
+    pub last_modified: i64,
+    /// 月ごとの変更回数（リポジトリの最新のコミットの月まで、古い順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub monthly: Vec<usize>,
+}
+
+/// 作成者ごとのコミット数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthorCommits {
+    pub name: String,
+    pub commits: usize,
+}
+
+impl FileHistory {
+    /// 最終更新日（`YYYY-MM-DD`、UTC）
+    pub fn last_modified_date(&self) -> String {
+        chrono::DateTime::from_timestamp(self.last_modified, 0)
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+
+    /// 月ごとの変更回数のスパークライン（`▁▃█` のような1か月1文字、最も多い月を `█` とする）
+    pub fn sparkline(&self) -> String {
+        let max = self.monthly.iter().copied().max().unwrap_or(0).max(1);
+        self.monthly
+            .iter()
+            .map(|&count| SPARK_BLOCKS[(count * (SPARK_BLOCKS.len() - 1)).div_ceil(max)])
+            .collect()
+    }
+}
+
+/// プロジェクトの歩みの出来事の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimelineKind {
+    /// タグを付けたリリース
+    Release,
+    /// 多くのファイル・行を変更した大規模な変更
+    Refactor,
+    /// ドキュメントの出力先のみを変更した再生成
+    Docs,
+}
+
+impl TimelineKind {
+    /// 種類の名前（`release`・`refactor`・`docs`）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimelineKind::Release => "release",
+            TimelineKind::Refactor => "refactor",
+            TimelineKind::Docs => "docs",
+        }
+    }
+}
+
+/// プロジェクトの歩みの出来事
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// コミットの日時（UNIX時刻、秒）
+    pub time: i64,
+    pub kind: TimelineKind,
+    /// タグの名前、またはコミットメッセージの1行目
+    pub title: String,
+    /// コミットの短いSHA
+    pub commit: String,
+    /// 変更したファイル数（リリースは0）
+    #[serde(default)]
+    pub files: usize,
+}
+
+impl TimelineEvent {
+    /// 日付（`YYYY-MM-DD`、UTC）
+    pub fn date(&self) -> String {
+        chrono::DateTime::from_timestamp(self.time, 0)
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// UNIX時刻の月の通し番号（西暦年 × 12 + 月）
+fn month_number(time: i64) -> i64 {
+    use chrono::Datelike;
+    chrono::DateTime::from_timestamp(time, 0).map_or(0, |t| t.year() as i64 * 12 + t.month0() as i64)
+}
+
+/// 解析対象を含むGitリポジトリを開く
+///
+/// # 引数
+/// * `repo_path` - 解析対象のパス（Gitリポジトリ内のサブディレクトリでもよい）
+///
+/// # 戻り値
+/// * `Option<(git2::Repository, PathBuf)>` - リポジトリと、作業ツリーから `repo_path` への相対パス（Gitリポジトリでない場合はNone）
+fn open_repo(repo_path: &Path) -> Option<(git2::Repository, PathBuf)> {
+    let repo = match git2::Repository::discover(repo_path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            debug!("Gitリポジトリではないため履歴を解析しません: {}", e);
+            return None;
+        }
+    };
+    let workdir = repo.workdir().and_then(|w| w.canonicalize().ok())?;
+    let root = repo_path.canonicalize().ok()?;
+    let prefix = root.strip_prefix(&workdir).unwrap_or(Path::new("")).to_path_buf();
+    Some((repo, prefix))
+}
+
+/// ファイルごとの変更履歴を収集
+///
+/// # 引数
+/// * `repo_path` - 解析対象のパス（Gitリポジトリ内のサブディレクトリでもよい）
+///
+/// # 戻り値
+/// * `HashMap<PathBuf, FileHistory>` - `repo_path` からの相対パス → 変更履歴
+pub(crate) fn collect_history(repo_path: &Path) -> HashMap<PathBuf, FileHistory> {
+    let mut history: HashMap<PathBuf, FileHistory> = HashMap::new();
+    let mut authors: HashMap<PathBuf, HashMap<String, usize>> = HashMap::new();
+    let mut times: HashMap<PathBuf, Vec<i64>> = HashMap::new();
+    let mut newest = i64::MIN;
+
+    let Some((repo, prefix)) = open_repo(repo_path) else {
+        return history;
+    };
+
+    let mut revwalk = match repo.revwalk() {
+        Ok(r) => r,
+        Err(_) => return history,
+    };
+    if revwalk.push_head().is_err() {
+        return history;
+    }
+
+    for oid in revwalk.take(MAX_HISTORY_COMMITS).flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let tree = match commit.tree() {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+        let diff = match repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) {
+            Ok(d) => d,
+            Err(_) => continue,
+        };
+        let author = commit.author().name().unwrap_or("unknown").to_string();
+        let time = commit.author().when().seconds();
+        newest = newest.max(time);
+
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path() {
+                if let Ok(relative) = path.strip_prefix(&prefix) {
+                    let entry = history.entry(relative.to_path_buf()).or_default();
+                    entry.commits += 1;
+                    entry.last_modified = entry.last_modified.max(time);
+                    *authors.entry(relative.to_path_buf()).or_default().entry(author.clone()).or_insert(0) += 1;
+                    times.entry(relative.to_path_buf()).or_default().push(time);
+                }
+            }
+        }
+    }
+
+    for (path, counts) in authors {
+        let mut counts: Vec<AuthorCommits> = counts
+            .into_iter()
+            .map(|(name, commits)| AuthorCommits { name, commits })
+            .collect();
+        counts.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+        counts.truncate(MAX_AUTHORS);
+        if let Some(entry) = history.get_mut(&path) {
+            entry.authors = counts;
+        }
+    }
+
+    let last_month = month_number(newest);
+    for (path, times) in times {
+        let mut monthly = vec![0; HISTORY_MONTHS];
+        for time in times {
+            let age = last_month - month_number(time);
+            if (0..HISTORY_MONTHS as i64).contains(&age) {
+                monthly[HISTORY_MONTHS - 1 - age as usize] += 1;
+            }
+        }
+        if let Some(entry) = history.get_mut(&path) {
+            entry.monthly = monthly;
+        }
+    }
+
+    history
+}
+
+/// プロジェクトの歩み（リリース・大規模な変更・ドキュメントの再生成）を収集
+///
+/// # 引数
+/// * `repo_path` - 解析対象のパス（Gitリポジトリ内のサブディレクトリでもよい）
+///
+/// # 戻り値
+/// * `Vec<TimelineEvent>` - 出来事（古い順、Gitリポジトリでない場合は空）
+pub(crate) fn collect_timeline(repo_path: &Path) -> Vec<TimelineEvent> {
+    let Some((repo, prefix)) = open_repo(repo_path) else {
+        return Vec::new();
+    };
+    let short_id = |commit: &git2::Commit| commit.id().to_string().chars().take(7).collect::<String>();
+    let mut events = Vec::new();
+
+    let mut releases = Vec::new();
+    for name in repo.tag_names(None).iter().flat_map(|names| names.iter().flatten().map(str::to_string).collect::<Vec<_>>()) {
+        let Ok(commit) = repo.revparse_single(&format!("refs/tags/{}", name)).and_then(|o| o.peel_to_commit()) else {
+            continue;
+        };
+        releases.push(TimelineEvent {
+            time: commit.time().seconds(),
+            kind: TimelineKind::Release,
+            title: name,
+            commit: short_id(&commit),
+            files: 0,
+        });
+    }
+    releases.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.title.cmp(&b.title)));
+    events.extend(releases.into_iter().take(MAX_TIMELINE_EVENTS));
+
+    let mut revwalk = match repo.revwalk() {
+        Ok(r) => r,
+        Err(_) => return events,
+    };
+    if revwalk.push_head().is_ok() {
+        let (mut refactors, mut docs) = (0, 0);
+        for oid in revwalk.take(MAX_HISTORY_COMMITS).flatten() {
+            if refactors >= MAX_TIMELINE_EVENTS && docs >= MAX_TIMELINE_EVENTS {
+                break;
+            }
+            let Ok(commit) = repo.find_commit(oid) else {
+                continue;
+            };
+            // マージコミットは取り込んだ変更の合計になるため除外
+            if commit.parent_count() > 1 {
+                continue;
+            }
+            let Ok(tree) = commit.tree() else {
+                continue;
+            };
+            let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+            let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) else {
+                continue;
+            };
+            let paths: Vec<PathBuf> = diff
+                .deltas()
+                .filter_map(|d| d.new_file().path().or(d.old_file().path()))
+                .filter_map(|p| p.strip_prefix(&prefix).ok().map(Path::to_path_buf))
+                .collect();
+            if paths.is_empty() {
+                continue;
+            }
+            let is_docs = paths.iter().all(|p| {
+                p.components()
+                    .next()
+                    .is_some_and(|c| DOC_OUTPUT_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()))
+            });
+            let kind = if is_docs {
+                TimelineKind::Docs
+            } else if paths.len() >= LARGE_COMMIT_FILES
+                || diff.stats().is_ok_and(|s| s.insertions() + s.deletions() >= LARGE_COMMIT_LINES)
+            {
+                TimelineKind::Refactor
+            } else {
+                continue;
+            };
+            let count = if kind == TimelineKind::Docs { &mut docs } else { &mut refactors };
+            if *count >= MAX_TIMELINE_EVENTS {
+                continue;
+            }
+            *count += 1;
+            events.push(TimelineEvent {
+                time: commit.time().seconds(),
+                kind,
+                title: commit.summary().unwrap_or_default().to_string(),
+                commit: short_id(&commit),
+                files: paths.len(),
+            });
+        }
+    }
+
+    events.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.kind.cmp(&b.kind)).then_with(|| a.title.cmp(&b.title)));
+    events
+}
+
+/// 行を最後に変更したコミットの作成者を調べる（`git blame` 相当）
+///
+/// # 引数
+/// * `repo_path` - 解析対象のパス（Gitリポジトリ内のサブディレクトリでもよい）
+/// * `lines` - `repo_path` からの相対パス → 調べる行番号（1始まり）
+///
+/// # 戻り値
+/// * `HashMap<(PathBuf, usize), String>` - (相対パス, 行番号) → 作成者（コミットされていないファイル・行は含まない）
+pub(crate) fn blame_lines(repo_path: &Path, lines: &HashMap<PathBuf, Vec<usize>>) -> HashMap<(PathBuf, usize), String> {
+    let mut authors = HashMap::new();
+    let Some((repo, prefix)) = open_repo(repo_path) else {
+        return authors;
+    };
+    for (path, numbers) in lines {
+        let blame = match repo.blame_file(&prefix.join(path), None) {
+            Ok(blame) => blame,
+            Err(e) => {
+                debug!("行の作成者を調べられません: {:?} - {}", path, e);
+                continue;
+            }
+        };
+        for &line in numbers {
+            if let Some(name) = blame.get_line(line).and_then(|hunk| hunk.final_signature().name().map(str::to_string)) {
+                authors.insert((path.clone(), line), name);
+            }
+        }
+    }
+    authors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_history_from_commits() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-history-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = git2::Repository::init(&dir).unwrap();
+
+        let commit = |name: &str, time: i64, files: &[(&str, &str)]| {
+            for (path, content) in files {
+                std::fs::write(dir.join(path), content).unwrap();
+            }
+            let mut index = repo.index().unwrap();
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::new(name, "dev@example.com", &git2::Time::new(time, 0)).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, "update", &tree, &parents).unwrap();
+        };
+        commit("alice", 1_700_000_000, &[("a.rs", "1"), ("b.rs", "1")]);
+        commit("bob", 1_700_100_000, &[("a.rs", "2")]);
+        commit("alice", 1_700_200_000, &[("a.rs", "3")]);
+
+        let history = collect_history(&dir);
+        let lines: HashMap<PathBuf, Vec<usize>> = [(PathBuf::from("a.rs"), vec![1, 2]), (PathBuf::from("new.rs"), vec![1])].into_iter().collect();
+        let authors = blame_lines(&dir, &lines);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(authors.len(), 1);
+        assert_eq!(authors[&(PathBuf::from("a.rs"), 1)], "alice");
+
+        let a = &history[Path::new("a.rs")];
+        assert_eq!(a.commits, 3);
+        assert_eq!(a.authors[0], AuthorCommits { name: "alice".to_string(), commits: 2 });
+        assert_eq!(a.last_modified_date(), "2023-11-17");
+        assert_eq!(history[Path::new("b.rs")].commits, 1);
+        // 3回とも2023年11月（最新のコミットの月）
+        assert_eq!(a.monthly[11], 3);
+        assert_eq!(a.sparkline(), "▁▁▁▁▁▁▁▁▁▁▁█");
+
+        let partial = FileHistory { monthly: vec![0, 1, 4], ..Default::default() };
+        assert_eq!(partial.sparkline(), "▁▃█");
+    }
+
+    #[test]
+    fn test_collect_timeline_from_tags_and_large_commits() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-timeline-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("docs")).unwrap();
+        let repo = git2::Repository::init(&dir).unwrap();
+
+        let commit = |message: &str, time: i64, files: &[(String, String)]| {
+            for (path, content) in files {
+                std::fs::write(dir.join(path), content).unwrap();
+            }
+            let mut index = repo.index().unwrap();
+            index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let signature = git2::Signature::new("dev", "dev@example.com", &git2::Time::new(time, 0)).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+        };
+        let file = |path: &str, content: &str| (path.to_string(), content.to_string());
+        let first = commit("Initial commit", 1_700_000_000, &[file("main.rs", "fn main() {}")]);
+        repo.tag_lightweight("v0.1.0", &repo.find_object(first, None).unwrap(), false).unwrap();
+        let many: Vec<(String, String)> = (0..LARGE_COMMIT_FILES).map(|i| (format!("m{}.rs", i), "// split".to_string())).collect();
+        commit("Split into modules", 1_700_100_000, &many);
+        commit("Fix typo", 1_700_200_000, &[file("main.rs", "fn main() { }")]);
+        commit("Regenerate docs\n\nbody", 1_700_300_000, &[file("docs/index.html", "<html></html>")]);
+
+        let timeline = collect_timeline(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let summary: Vec<(TimelineKind, &str, usize)> = timeline.iter().map(|e| (e.kind, e.title.as_str(), e.files)).collect();
+        assert_eq!(
+            summary,
+            [
+                (TimelineKind::Release, "v0.1.0", 0),
+                (TimelineKind::Refactor, "Split into modules", LARGE_COMMIT_FILES),
+                (TimelineKind::Docs, "Regenerate docs", 1),
+            ]
+        );
+        assert_eq!(timeline[0].date(), "2023-11-14");
+        assert_eq!(timeline[0].commit.len(), 7);
+        assert!(collect_timeline(&std::env::temp_dir().join("deeprepo-not-a-repo")).is_empty());
+    }
+}
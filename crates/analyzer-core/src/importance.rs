@@ -0,0 +1,135 @@
+/*!
+ * モジュール重要度スコアリング
+ *
+ * Wiki/スライドで詳細に扱うモジュールを選択・並べ替えるためのスコアを計算する
+ * - 被依存数（fan-in）: 他のファイルからimportされている回数
+ * - 行数（LOC）: 対数スケールで評価
+ * - エントリーポイントへの近さ: 同一ファイル/同一ディレクトリ/同一トップレベル
+ * - Git変更頻度（churn）: 直近のコミットで変更された回数
+ *
+ * 主な仕様:
+ * - 各指標をモジュール間の最大値で正規化し、重み付き和を0.0〜1.0で返す
+ * - Gitリポジトリでない場合はchurnを0として扱う
+ *
+ * 制限事項:
+ * - fan-inは内部依存グラフ（depgraph）でimportしているファイル数
+ *   - グラフがない場合はimport文字列とモジュールIDのパスの一致で判定（簡易、`module_id::matches_import`）
+ * - churnは直近のコミットのみ（historyモジュールの走査範囲）
+ */
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::Index;
+
+const WEIGHT_FAN_IN: f64 = 0.35;
+const WEIGHT_LOC: f64 = 0.25;
+const WEIGHT_ENTRYPOINT: f64 = 0.2;
+const WEIGHT_CHURN: f64 = 0.2;
+
+/// モジュールごとの重要度スコアを計算
+///
+/// # 引数
+/// * `index` - インデックス（モジュール・ファイル・エントリーポイントを参照）
+/// * `churn` - `repo_path` からの相対パス → 変更コミット数
+///
+/// # 戻り値
+/// * `Vec<f64>` - `index.modules` と同じ順序のスコア（0.0〜1.0）
+pub(crate) fn compute_importance(index: &Index, churn: &HashMap<PathBuf, usize>) -> Vec<f64> {
+    let fan_in: Vec<f64> = index
+        .modules
+        .iter()
+        .map(|module| {
+            if !index.internal_dependencies.is_empty() {
+                return index.dependents_of(&module.path).len() as f64;
+            }
+            index
+                .files
+                .iter()
+                .filter(|f| f.path != module.path)
+                .flat_map(|f| f.dependencies.iter())
+                .filter(|dep| module.matches_import(dep))
+                .count() as f64
+        })
+        .collect();
+
+    let loc: Vec<f64> = index
+        .modules
+        .iter()
+        .map(|module| {
+            let lines = index
+                .files
+                .iter()
+                .find(|f| f.path == module.path)
+                .and_then(|f| index.file_content(f))
+                .map(|c| c.lines().count())
+                .unwrap_or(0);
+            (lines as f64 + 1.0).ln()
+        })
+        .collect();
+
+    let proximity: Vec<f64> = index
+        .modules
+        .iter()
+        .map(|module| entrypoint_proximity(&index.repo_path, &module.path, &index.entrypoints))
+        .collect();
+
+    let churn_scores: Vec<f64> = index
+        .modules
+        .iter()
+        .map(|module| {
+            let relative = module.path.strip_prefix(&index.repo_path).unwrap_or(&module.path);
+            let count = churn.get(relative).copied().unwrap_or(0);
+            (count as f64 + 1.0).ln()
+        })
+        .collect();
+
+    let fan_in = normalize(&fan_in);
+    let loc = normalize(&loc);
+    let churn_scores = normalize(&churn_scores);
+
+    (0..index.modules.len())
+        .map(|i| {
+            WEIGHT_FAN_IN * fan_in[i]
+                + WEIGHT_LOC * loc[i]
+                + WEIGHT_ENTRYPOINT * proximity[i]
+                + WEIGHT_CHURN * churn_scores[i]
+        })
+        .collect()
+}
+
+/// エントリーポイントへの近さを評価
+fn entrypoint_proximity(repo_path: &Path, path: &Path, entrypoints: &[PathBuf]) -> f64 {
+    let top_level = |p: &Path| {
+        p.strip_prefix(repo_path)
+            .unwrap_or(p)
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_os_string())
+    };
+
+    let mut best: f64 = 0.0;
+    for ep in entrypoints {
+        let score = if ep == path {
+            1.0
+        } else if ep.parent() == path.parent() {
+            0.5
+        } else if top_level(ep) == top_level(path) {
+            // 同じトップレベルディレクトリを共有
+            0.25
+        } else {
+            0.0
+        };
+        best = best.max(score);
+    }
+    best
+}
+
+/// 最大値で正規化（すべて0の場合は0のまま）
+fn normalize(values: &[f64]) -> Vec<f64> {
+    let max = values.iter().cloned().fold(0.0, f64::max);
+    if max <= 0.0 {
+        return vec![0.0; values.len()];
+    }
+    values.iter().map(|v| v / max).collect()
+}
@@ -1,36 +1,139 @@
-/**
+/*!
  * アナライザーコア実装
  * 
  * 多言語対応のコード解析を行う
- * - tree-sitterによる構文解析
- * - 依存関係の抽出
- * - エントリーポイントの推定
+ * - tree-sitterによる構文解析（syntaxモジュール）
+ * - 依存関係の抽出と、リポジトリ内のファイルへの解決、循環依存の検出（depgraphモジュール）
+ * - `project.include`・`project.exclude` のglobパターンによる対象の絞り込み（path_filterモジュール）
+ * - 行数・コメント率・関数数・サイクロマティック複雑度の計算（metricsモジュール）
+ * - コミット履歴からファイルごとの変更回数・作成者・最終更新日を集計（historyモジュール）
+ * - Cargo.toml・package.json・pyproject.toml・go.modに宣言された依存の取得（manifestsモジュール）
+ * - 外部依存のlockfileで解決したバージョンとライセンスの検出、コピーレフトの判定（licensesモジュール）
+ * - tsconfig・Cargo.tomlのフィーチャー・.env.example・webpackの設定ファイルの項目と説明の抽出（config_filesモジュール）
+ * - README・LICENSE・CONTRIBUTING・CHANGELOGの検出とプロジェクトのライセンスの判定（project_docsモジュール）
+ * - ARCHITECTURE.md・ADRに書かれた構成と実装の差分の検出（design_docsモジュール）
+ * - Cargo・npm/pnpmワークスペース、Goのマルチモジュールのパッケージの検出（workspaceモジュール）
+ * - 本体のコードとテストファイルの分類、テスト関数の数の推定（testingモジュール）
+ * - 埋め込み先のアプリケーションが登録する独自のファイル分類（社内DSLなど、classifierモジュール）
+ * - エントリーポイントの推定（ファイル名の規約、Webフレームワークのアプリ定義、DockerfileのCMD・ENTRYPOINT、entrypointsモジュール）
+ * - クライアントライブラリのimportからの外部サービス（クラウド・SaaS・データベース・メッセージキュー）の推定（servicesモジュール）
+ * - REST（Express・axum・actix-web・FastAPI・Flask・Spring）とGraphQLのAPIエンドポイントの抽出（routesモジュール）
+ * - バイナリファイルの除外と、Shift_JIS・EUC-JPのソースのUTF-8への変換（encodingモジュール）
+ * - どこからもimportされないモジュール・参照されない公開関数の検出（unusedモジュール）
+ * - TODO・FIXME・HACK・XXXのコメントと、その行を最後に変更した作成者の抽出（todosモジュール）
+ * - モジュールの層（UI・API・ドメイン・永続化・ユーティリティ）と層の間の依存の推定（architectureモジュール）
+ * - スキップしたファイルなど処理を止めない警告の収集（diagnosticsモジュール、要約・図・Wiki・スライドと共有）
+ * - Wiki・スライドの生成タスクの同時実行数を共有する優先度付きのワーカープール（executorモジュール）
+ * - tantivyによる全文検索（searchモジュール、`tantivy-search` フィーチャー）と、一致した箇所の行・列の特定（text_matchモジュール）
+ * - 埋め込みベクトルによる意味検索（embeddingsモジュール、`index.embeddings`）
+ * - シンボルの定義・参照・呼び出し関係（xrefモジュール）と型の実装・継承関係（relationsモジュール）
+ * - ファイル内容の行・窓ごとの逐次読み込み（streamingモジュール）
  * - モジュール構造の解析
  * 
  * 主な仕様:
- * - TypeScript/JavaScript, Python, Go, Rust, Javaに対応
+ * - TypeScript/JavaScript, Python, Go, Rust, Java, C/C++, C#, Ruby, PHP, Kotlinに対応
  * - 言語ごとの特性に応じた解析ロジック
  * - インデックス形式でのデータ保存
+ * - 検索は本文・シンボル名・ファイルパス（`SEARCH_KINDS`）と、言語・パス・モジュールによる絞り込みに対応
  * 
  * 制限事項:
- * - tree-sitterのバインディングは外部で提供されることを想定
+ * - tree-sitterの文法は上記の言語のみ同梱（ほかの言語は構文を解析しない）
  * - 大規模ファイルはスキップ（設定で制御可能）
  */
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use anyhow::{Context, Result};
 use tracing::{info, warn};
 use walkdir::WalkDir;
-use regex::Regex;
 
 use config::Config;
 
+use path_filter::PathFilter;
+
+mod architecture;
+mod build_tools;
+mod chunking;
+mod classifier;
+mod component;
+mod conditions;
+mod config_files;
+mod depgraph;
+mod design_docs;
+mod diagnostics;
+mod embeddings;
+mod encoding;
+mod entrypoints;
+mod executor;
+mod glossary;
+mod history;
+mod importance;
+mod licenses;
+mod manifests;
+mod metrics;
+mod module_id;
+mod path_filter;
+mod project_docs;
+mod redact;
+mod relations;
+mod routes;
+#[cfg(feature = "tantivy-search")]
+mod search;
+mod services;
+mod store;
+mod streaming;
+mod syntax;
+mod testing;
+mod text_match;
+mod todos;
+mod unused;
+mod vendored;
+mod workspace;
+mod xref;
+
+pub use architecture::{ArchitectureModel, ComponentLayer, LayerBasis, LayerDependency, LAYERS};
+pub use build_tools::{detect_build_commands, BuildCommand};
+pub use classifier::{Classification, Classifier};
+pub use component::{ComponentKind, COMPONENT_STYLES};
+pub use conditions::BuildCondition;
+pub use config_files::{ConfigFile, ConfigSetting};
+pub use depgraph::DependencyCycle;
+pub use design_docs::{DesignDoc, DesignDrift, DocMention};
+pub use diagnostics::{Diagnostic, Diagnostics};
+pub use embeddings::VectorIndex;
+pub use entrypoints::Entrypoint;
+pub use executor::{Executor, Priority};
+pub use glossary::GlossaryTerm;
+pub use history::{AuthorCommits, FileHistory, TimelineEvent, TimelineKind};
+pub use licenses::{is_copyleft, ExternalDependency};
+pub use manifests::{DeclaredDependency, Manifest};
+pub use metrics::{FileMetrics, FunctionMetrics};
+pub use project_docs::ProjectDocs;
+pub use redact::{redact, Redaction, RedactionRules};
+pub use routes::ApiEndpoint;
+pub use services::ExternalService;
+pub use todos::{marker_label, TodoComment, TODO_MARKERS};
+#[cfg(feature = "tantivy-search")]
+pub use search::SearchIndex;
+pub use store::ContentStore;
+pub use streaming::{ContentLines, ContentReader, ContentWindow, ContentWindows};
+pub use syntax::{parse_source, Parameter, ParsedSource, Symbol, TypeField, TypeRelation};
+pub use text_match::SearchMatch;
+pub use unused::{UnreferencedSymbol, UnusedCode};
+pub use vendored::VendoredDir;
+pub use workspace::PackageInfo;
+pub use xref::{SymbolExplanation, SymbolReference};
+
 /// アナライザー
 pub struct Analyzer {
     #[allow(dead_code)]
     config: Config,
+    /// スキップしたファイルなどの警告の記録先
+    diagnostics: Diagnostics,
+    /// 独自のファイルの分類関数（登録順）
+    classifiers: Vec<Classifier>,
 }
 
 impl Analyzer {
@@ -42,11 +145,41 @@ impl Analyzer {
     /// # 戻り値
     /// * `Self` - アナライザーインスタンス
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self { config, diagnostics: Diagnostics::new(), classifiers: Vec::new() }
+    }
+
+    /// 独自のファイルの分類関数を登録
+    /// 
+    /// 組み込みで判定できない形式（社内のDSLなど）のファイルを、指定した言語のラベルで解析対象に加える。
+    /// 分類関数は登録順に呼ばれ、設定の `language-overrides` に一致しないファイルでは組み込みの判定より優先する。
+    /// 
+    /// # 引数
+    /// * `classifier` - ファイルのパスと先頭の内容から分類を返す関数（分類しない場合は `None`）
+    /// 
+    /// # 戻り値
+    /// * `&mut Self` - 分類関数を追加したアナライザー
+    pub fn register_classifier(&mut self, classifier: Classifier) -> &mut Self {
+        self.classifiers.push(classifier);
+        self
+    }
+
+    /// 警告の記録先を指定（要約・Wiki・スライドと共有して最後にまとめて表示する場合）
+    /// 
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    /// 
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたアナライザー
+    pub fn with_diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = diagnostics;
+        self
     }
 
     /// リポジトリを解析してインデックスを作成
     /// 
+    /// ファイルの読み込み・構文解析はスレッドプールで `analysis.concurrency` 件ずつ並列に行い、
+    /// 内容はインデックスに保持しない（`Index::file_content` で作業ツリーから読み直す）。
+    /// 
     /// # 引数
     /// * `repo_path` - リポジトリのパス
     /// * `config` - 設定（上書き用）
@@ -61,56 +194,192 @@ impl Analyzer {
         let repo_path = repo_path.as_ref();
         info!("リポジトリ解析開始: {:?}", repo_path);
 
-        let mut files = Vec::new();
         let mut modules = Vec::new();
         let mut dependencies = HashMap::new();
-        let mut languages = std::collections::HashSet::new();
+        let mut languages = std::collections::BTreeSet::new();
+        let mut readmes = Vec::new();
+        let redaction = std::sync::Arc::new(RedactionRules::new(config)?);
 
-        // ファイルを走査
-        for entry in WalkDir::new(repo_path) {
-            let entry = entry?;
-            let path = entry.path();
+        // 走査はブロッキングのためスレッドプールで実行（対象のパスのみを収集）
+        let mut walked = {
+            let repo_path = repo_path.to_path_buf();
+            let filter = PathFilter::new(&config.project.include, &config.project.exclude)?;
+            let detect_vendored = config.analysis.detect_vendored;
+            let max_file_kb = config.analysis.max_file_kb as u64;
+            tokio::task::spawn_blocking(move || walk_repo(&repo_path, &filter, detect_vendored, max_file_kb))
+                .await
+                .context("ファイル走査タスクが異常終了しました")??
+        };
 
-            if !entry.file_type().is_file() {
-                continue;
+        // パッケージのREADME
+        for path in &walked.readmes {
+            match tokio::fs::read_to_string(path).await {
+                Ok(content) => readmes.push(PackageReadme {
+                    dir: path.parent().unwrap_or(repo_path).to_path_buf(),
+                    content: redaction.redact_text(&content),
+                }),
+                Err(e) => self.diagnostics.warn_at("analyzer", path, format!("READMEを読み込めませんでした: {}", e)),
             }
+        }
+
+        // マニフェストに宣言された依存
+        let manifests = {
+            let repo_path = repo_path.to_path_buf();
+            let paths = std::mem::take(&mut walked.manifests);
+            let diagnostics = self.diagnostics.clone();
+            tokio::task::spawn_blocking(move || manifests::parse_manifests(&repo_path, &paths, &diagnostics))
+                .await
+                .context("マニフェストの解析タスクが異常終了しました")?
+        };
 
-            // 除外パターンのチェック
-            if self.should_exclude(path, &config.project.exclude) {
+        // 外部依存のバージョンとライセンス（lockfile・パッケージのキャッシュから解決）
+        let external_dependencies = {
+            let repo_path = repo_path.to_path_buf();
+            let manifests = manifests.clone();
+            let lockfiles = std::mem::take(&mut walked.lockfiles);
+            let diagnostics = self.diagnostics.clone();
+            tokio::task::spawn_blocking(move || {
+                let sources = licenses::LicenseSources::from_env();
+                licenses::resolve_dependencies(&repo_path, &manifests, &lockfiles, &sources, &diagnostics)
+            })
+            .await
+            .context("依存ライセンスの解決タスクが異常終了しました")?
+        };
+
+        // 解析対象のリポジトリ自身の設定ファイル
+        let config_files = {
+            let repo_path = repo_path.to_path_buf();
+            let paths = std::mem::take(&mut walked.config_files);
+            let diagnostics = self.diagnostics.clone();
+            tokio::task::spawn_blocking(move || config_files::parse_config_files(&repo_path, &paths, &diagnostics))
+                .await
+                .context("設定ファイルの解析タスクが異常終了しました")?
+        };
+
+        let dockerfiles: Vec<PathBuf> = walked.files.iter().filter(|p| entrypoints::is_dockerfile(p)).cloned().collect();
+        let graphql_schemas: Vec<PathBuf> = walked.files.iter().filter(|p| routes::is_graphql_schema(p)).cloned().collect();
+
+        // ファイルを並列に解析（同時に保持する内容は `analysis.concurrency` 件まで）
+        let concurrency = match config.analysis.concurrency {
+            0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4),
+            n => n,
+        };
+        let mut targets = walked
+            .files
+            .into_iter()
+            .filter_map(|path| {
+                let (lang, is_module) = self.classify(&path, config)?;
+                // リポジトリより上のディレクトリ名（`/home/me/test/repo` など）では判定しない
+                let is_test = testing::is_test_path(path.strip_prefix(repo_path).unwrap_or(&path));
+                Some((path, lang, is_module, is_test))
+            })
+            .enumerate();
+        let mut tasks = tokio::task::JoinSet::new();
+        let mut analyzed = Vec::new();
+        let mut detected_entrypoints = Vec::new();
+        let mut endpoints = Vec::new();
+        let mut conditional_imports = Vec::new();
+        let mut skipped = Vec::new();
+        // 解析対象の言語のファイルのみ記録（ビルド成果物などは対象外のため警告しない）
+        for path in std::mem::take(&mut walked.too_large) {
+            if self.classify(&path, config).is_none() {
                 continue;
             }
+            self.diagnostics.warn_at(
+                "analyzer",
+                &path,
+                format!("{}KBを超えるためスキップしました", config.analysis.max_file_kb),
+            );
+            skipped.push((usize::MAX, SkippedFile { path, reason: "too-large".to_string() }));
+        }
+        loop {
+            while tasks.len() < concurrency {
+                let Some((order, (path, lang, is_module, is_test))) = targets.next() else {
+                    break;
+                };
+                languages.insert(lang.clone());
+                let redaction = redaction.clone();
+                let relative = path.strip_prefix(repo_path).unwrap_or(&path).to_path_buf();
+                tasks.spawn_blocking(move || {
+                    let result = analyze_file(&path, &relative, &lang, is_module, is_test, &redaction);
+                    (order, path, result)
+                });
+            }
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            let (order, path, result) = joined.context("ファイル解析タスクが異常終了しました")?;
+            match result {
+                Ok(Some(file)) => {
+                    if !file.info.redactions.is_empty() {
+                        self.diagnostics.warn_at("analyzer", &path, describe_redactions(&file.info.redactions));
+                    }
+                    detected_entrypoints.extend(file.entrypoint.map(|e| (order, e)));
+                    endpoints.extend(file.endpoints.into_iter().map(|e| (order, e)));
+                    conditional_imports.extend(file.conditional_imports.into_iter().map(|i| (order, path.clone(), i)));
+                    analyzed.push((order, file.info));
+                }
+                Ok(None) => {
+                    self.diagnostics.warn_at("analyzer", &path, "バイナリファイルのためスキップしました");
+                    skipped.push((order, SkippedFile { path, reason: "binary".to_string() }));
+                }
+                Err(e) => {
+                    self.diagnostics.warn_at("analyzer", &path, format!("解析できませんでした: {}", e));
+                    skipped.push((order, SkippedFile { path, reason: format!("unreadable: {}", e) }));
+                }
+            }
+        }
 
-            // ファイルサイズチェック
-            let metadata = std::fs::metadata(path)?;
-            let size_kb = metadata.len() / 1024;
-            if size_kb > config.analysis.max_file_kb as u64 {
-                warn!("ファイルが大きすぎるためスキップ: {:?} ({}KB)", path, size_kb);
-                continue;
+        // 走査順に並べ直す（結果は完了順に届く）
+        analyzed.sort_by_key(|(order, _)| *order);
+        detected_entrypoints.sort_by_key(|(order, _)| *order);
+        endpoints.sort_by_key(|(order, _)| *order);
+        skipped.sort_by_key(|(order, _)| *order);
+        conditional_imports.sort_by_key(|(order, _, _)| *order);
+        let mut endpoints: Vec<ApiEndpoint> = endpoints.into_iter().map(|(_, e)| e).collect();
+        for path in &graphql_schemas {
+            match tokio::fs::read_to_string(path).await {
+                Ok(content) => endpoints.extend(routes::extract_endpoints(path, "graphql", &content)),
+                Err(e) => self.diagnostics.warn_at("analyzer", path, format!("GraphQLスキーマを読み込めませんでした: {}", e)),
             }
+        }
+        let mut detected_entrypoints: Vec<Entrypoint> = detected_entrypoints.into_iter().map(|(_, e)| e).collect();
+        for path in &dockerfiles {
+            match tokio::fs::read_to_string(path).await {
+                Ok(content) => detected_entrypoints.extend(entrypoints::parse_dockerfile(path, &content)),
+                Err(e) => self.diagnostics.warn_at("analyzer", path, format!("Dockerfileを読み込めませんでした: {}", e)),
+            }
+        }
+        let mut files: Vec<FileInfo> = analyzed.into_iter().map(|(_, file_info)| file_info).collect();
+        for file_info in &files {
+            if file_info.is_module {
+                modules.push(ModuleInfo {
+                    path: file_info.path.clone(),
+                    id: String::new(),
+                    name: file_info.name.clone(),
+                    language: file_info.language.clone(),
+                    dependencies: file_info.dependencies.clone(),
+                    importance: 0.0,
+                    component: ComponentKind::classify(file_info.path.strip_prefix(repo_path).unwrap_or(&file_info.path)),
+                });
+            }
+            // 依存関係をマップに追加
+            for dep in &file_info.dependencies {
+                dependencies.entry(dep.clone()).or_insert_with(Vec::new);
+            }
+        }
 
-            // 言語検出
-            if let Some(lang) = self.detect_language(path) {
-                languages.insert(lang.clone());
+        // Goのインターフェースの実装はメソッドの一致から推定
+        relations::infer_go_implementations(&mut files);
 
-                match self.analyze_file(path, &lang).await {
-                    Ok(file_info) => {
-                        files.push(file_info.clone());
-                        if file_info.is_module {
-                            modules.push(ModuleInfo {
-                                path: path.to_path_buf(),
-                                name: file_info.name.clone(),
-                                language: lang.clone(),
-                                dependencies: file_info.dependencies.clone(),
-                            });
-                        }
-                        // 依存関係をマップに追加
-                        for dep in &file_info.dependencies {
-                            dependencies.entry(dep.clone()).or_insert_with(Vec::new);
-                        }
-                    }
-                    Err(e) => {
-                        warn!("ファイル解析エラー: {:?} - {}", path, e);
-                    }
+        // importをリポジトリ内のファイルに解決
+        let internal_dependencies = depgraph::build_internal_graph(repo_path, &files, &mut dependencies);
+        let conditional_imports: Vec<(PathBuf, conditions::ConditionalImport)> =
+            conditional_imports.into_iter().map(|(_, path, import)| (path, import)).collect();
+        for (path, condition) in depgraph::resolve_conditional_imports(repo_path, &files, &conditional_imports) {
+            if let Some(file) = files.iter_mut().find(|f| f.path == path) {
+                if !file.build_conditions.contains(&condition) {
+                    file.build_conditions.push(condition);
                 }
             }
         }
@@ -122,194 +391,231 @@ impl Analyzer {
             modules.len()
         );
 
+        let content_paths: Vec<PathBuf> = files.iter().map(|f| f.path.clone()).collect();
         let stats = IndexStats {
             files: files.len(),
             languages: languages.iter().cloned().collect(),
             modules: modules.len(),
+            skipped: skipped.into_iter().map(|(_, s)| s).collect(),
         };
 
-        Ok(Index {
+        let entrypoint_details = self.infer_entrypoints(repo_path, config, detected_entrypoints)?;
+        let mut index = Index {
             id: uuid::Uuid::new_v4().to_string(),
             repo_path: repo_path.to_path_buf(),
+            commit: head_commit(repo_path),
             files,
             modules,
             languages: languages.into_iter().collect(),
             dependencies,
-            entrypoints: self.infer_entrypoints(repo_path, &config)?,
+            internal_dependencies,
+            entrypoints: entrypoint_details.iter().filter(|e| e.kind != "docker").map(|e| e.path.clone()).collect(),
+            entrypoint_details,
+            endpoints,
+            build_commands: detect_build_commands(repo_path),
+            vendored: walked.vendored,
+            readmes,
+            project_docs: {
+                let mut docs = project_docs::detect_project_docs(repo_path);
+                docs.readme_intro = docs.readme_intro.map(|intro| redaction.redact_text(&intro));
+                docs
+            },
+            design_docs: design_docs::detect_design_docs(repo_path),
+            packages: workspace::detect_packages(&manifests),
+            manifests,
+            external_dependencies,
+            config_files,
+            timeline: Vec::new(),
+            redaction: (*redaction).clone(),
             stats,
-        })
+            diagnostics: Vec::new(),
+            store: Some(std::sync::Arc::new(ContentStore::working_tree(content_paths))),
+            #[cfg(feature = "tantivy-search")]
+            search_index: None,
+            vectors: None,
+        };
+        index.assign_module_ids();
+
+        // コミット履歴を集計（変更回数は重要度にも使用）
+        let history = if config.analysis.git_history {
+            let repo_path = repo_path.to_path_buf();
+            tokio::task::spawn_blocking(move || history::collect_history(&repo_path))
+                .await
+                .context("履歴の集計タスクが異常終了しました")?
+        } else {
+            HashMap::new()
+        };
+        for file in &mut index.files {
+            let relative = file.path.strip_prefix(repo_path).unwrap_or(&file.path);
+            file.history = history.get(relative).cloned();
+        }
+        if config.analysis.git_history {
+            let repo_path = repo_path.to_path_buf();
+            index.timeline = tokio::task::spawn_blocking(move || history::collect_timeline(&repo_path))
+                .await
+                .context("プロジェクトの歩みの集計タスクが異常終了しました")?;
+        }
+
+        // 作業メモのコメントの行を最後に変更した作成者（blameは重いため、作業メモの多いファイルから上限まで）
+        if config.analysis.git_history {
+            let mut files_with_todos: Vec<&FileInfo> = index.files.iter().filter(|f| !f.todos.is_empty()).collect();
+            files_with_todos.sort_by(|a, b| b.todos.len().cmp(&a.todos.len()).then_with(|| a.path.cmp(&b.path)));
+            let lines: HashMap<PathBuf, Vec<usize>> = files_with_todos
+                .iter()
+                .take(MAX_BLAME_FILES)
+                .map(|f| (f.path.strip_prefix(repo_path).unwrap_or(&f.path).to_path_buf(), f.todos.iter().map(|t| t.line).collect()))
+                .collect();
+            if !lines.is_empty() {
+                let root = repo_path.to_path_buf();
+                let authors = tokio::task::spawn_blocking(move || history::blame_lines(&root, &lines))
+                    .await
+                    .context("作業メモの作成者の集計タスクが異常終了しました")?;
+                for file in &mut index.files {
+                    let relative = file.path.strip_prefix(repo_path).unwrap_or(&file.path).to_path_buf();
+                    for todo in &mut file.todos {
+                        todo.author = authors.get(&(relative.clone(), todo.line)).cloned();
+                    }
+                }
+            }
+        }
+
+        // モジュールの重要度を計算（Wiki/スライドでの選択・並び順に使用）
+        let churn: HashMap<PathBuf, usize> = history.into_iter().map(|(path, h)| (path, h.commits)).collect();
+        let scores = importance::compute_importance(&index, &churn);
+        for (module, score) in index.modules.iter_mut().zip(scores) {
+            module.importance = score;
+        }
+
+        #[cfg(feature = "tantivy-search")]
+        if config.index.provider == "tantivy" {
+            let search_index = SearchIndex::build(&index, config.index.chunk_tokens, config.index.chunk_overlap, None)?;
+            index.search_index = Some(std::sync::Arc::new(search_index));
+        }
+
+        // 埋め込みAPIに接続できない場合も解析は続け、意味検索だけを使えなくする
+        match VectorIndex::build(&index, config).await {
+            Ok(vectors) => index.vectors = vectors.map(std::sync::Arc::new),
+            Err(e) => self.diagnostics.warn("analyzer", format!("埋め込みベクトルを作成できませんでした: {:#}", e)),
+        }
+
+        index.diagnostics = self.diagnostics.entries();
+        Ok(index)
     }
 
-    /// ファイルを解析
+    /// ファイルの言語とモジュールかどうかを判定
+    /// 
+    /// 設定の `language-overrides` に一致しない場合は、登録された分類関数を組み込みの判定より先に使う。
     /// 
     /// # 引数
     /// * `path` - ファイルパス
-    /// * `language` - 言語識別子
+    /// * `config` - 設定
     /// 
     /// # 戻り値
-    /// * `Result<FileInfo>` - ファイル情報、またはエラー
-    async fn analyze_file(&self, path: &Path, language: &str) -> Result<FileInfo> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("ファイル読み込みエラー: {:?}", path))?;
-
-        let name = path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string();
-
-        let dependencies = match language {
-            "ts" | "js" | "tsx" | "jsx" => self.extract_js_dependencies(&content),
-            "py" => self.extract_py_dependencies(&content),
-            "go" => self.extract_go_dependencies(&content),
-            "rs" => self.extract_rust_dependencies(&content),
-            _ => Vec::new(),
-        };
-
-        let is_module = self.is_module_file(path, language);
-
-        Ok(FileInfo {
-            path: path.to_path_buf(),
-            name,
-            language: language.to_string(),
-            size: content.len(),
-            dependencies,
-            is_module,
-            content: Some(content),
-        })
+    /// * `Option<(String, bool)>` - (言語識別子, モジュールか)（解析対象外の場合はNone）
+    fn classify(&self, path: &Path, config: &Config) -> Option<(String, bool)> {
+        if language_override(path, config).is_none() {
+            if let Some(classification) = classifier::classify(&self.classifiers, path) {
+                return Some((classification.language, classification.is_module));
+            }
+        }
+        let language = self.detect_language(path, config)?;
+        let is_module = self.is_module_file(path, &language);
+        Some((language, is_module))
     }
 
     /// 言語を検出
     /// 
+    /// 判定順序:
+    /// 1. 設定の `language-overrides`（ファイル名、次に拡張子で照合）
+    /// 2. 拡張子による組み込みの判定
+    /// 3. 先頭行のshebang（`#!/usr/bin/env python` など）
+    /// 
     /// # 引数
     /// * `path` - ファイルパス
+    /// * `config` - 設定
     /// 
     /// # 戻り値
     /// * `Option<String>` - 言語識別子（対応していない場合はNone）
-    fn detect_language(&self, path: &Path) -> Option<String> {
-        let ext = path.extension()?.to_str()?;
+    fn detect_language(&self, path: &Path, config: &Config) -> Option<String> {
+        let file_name = path.file_name().and_then(|n| n.to_str());
+        let ext = path.extension().and_then(|e| e.to_str());
+
+        if let Some(lang) = language_override(path, config) {
+            return Some(lang);
+        }
 
-        match ext {
-            "ts" | "tsx" => Some("ts".to_string()),
+        // 拡張子のないRubyのビルドファイル
+        if matches!(file_name, Some("Gemfile" | "Rakefile")) {
+            return Some("rb".to_string());
+        }
+
+        let by_ext = ext.and_then(|ext| match ext {
+            "ts" | "tsx" | "mts" | "cts" => Some("ts".to_string()),
             "js" | "jsx" | "mjs" | "cjs" => Some("js".to_string()),
+            // Vue/SvelteのSFCはscriptブロックのimportを解析する
+            "vue" | "svelte" => Some("js".to_string()),
             "py" => Some("py".to_string()),
             "go" => Some("go".to_string()),
             "rs" => Some("rs".to_string()),
             "java" => Some("java".to_string()),
+            // `.h` はCとして解析する（C++の構文を含む場合はC++文法で再解析される）
+            "c" | "h" => Some("c".to_string()),
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Some("cpp".to_string()),
+            "cs" => Some("cs".to_string()),
+            "rb" | "rake" => Some("rb".to_string()),
+            "php" => Some("php".to_string()),
+            "kt" | "kts" => Some("kt".to_string()),
             _ => None,
-        }
-    }
-
-    /// JavaScript/TypeScriptの依存関係を抽出
-    /// 
-    /// # 引数
-    /// * `content` - ファイル内容
-    /// 
-    /// # 戻り値
-    /// * `Vec<String>` - 依存関係のリスト
-    fn extract_js_dependencies(&self, content: &str) -> Vec<String> {
-        let mut deps = Vec::new();
-
-        // import文の抽出
-        let import_re = Regex::new(r#"(?:import|export).*from\s+['"]([^'"]+)['"]"#).unwrap();
-        for cap in import_re.captures_iter(content) {
-            if let Some(dep) = cap.get(1) {
-                deps.push(dep.as_str().to_string());
-            }
+        });
+        if by_ext.is_some() {
+            return by_ext;
         }
 
-        // require文の抽出
-        let require_re = Regex::new(r#"require\s*\(\s*['"]([^'"]+)['"]"#).unwrap();
-        for cap in require_re.captures_iter(content) {
-            if let Some(dep) = cap.get(1) {
-                deps.push(dep.as_str().to_string());
-            }
+        // 拡張子がない場合のみshebangを確認する
+        if ext.is_none() {
+            return self.detect_language_from_shebang(path);
         }
 
-        deps
+        None
     }
 
-    /// Pythonの依存関係を抽出
+    /// shebangから言語を検出
     /// 
     /// # 引数
-    /// * `content` - ファイル内容
+    /// * `path` - ファイルパス
     /// 
     /// # 戻り値
-    /// * `Vec<String>` - 依存関係のリスト
-    fn extract_py_dependencies(&self, content: &str) -> Vec<String> {
-        let mut deps = Vec::new();
-
-        // import文の抽出
-        let import_re = Regex::new(r#"^(?:import|from)\s+([^\s]+)"#).unwrap();
-        for line in content.lines() {
-            if let Some(cap) = import_re.captures(line) {
-                if let Some(dep) = cap.get(1) {
-                    deps.push(dep.as_str().to_string());
-                }
-            }
-        }
+    /// * `Option<String>` - 言語識別子（shebangがない、または対応していない場合はNone）
+    fn detect_language_from_shebang(&self, path: &Path) -> Option<String> {
+        use std::io::Read;
 
-        deps
-    }
+        let mut head = [0u8; 128];
+        let n = std::fs::File::open(path).ok()?.read(&mut head).ok()?;
+        let head = String::from_utf8_lossy(&head[..n]);
+        let first_line = head.lines().next()?;
+        let interpreter_line = first_line.strip_prefix("#!")?.trim();
 
-    /// Goの依存関係を抽出
-    /// 
-    /// # 引数
-    /// * `content` - ファイル内容
-    /// 
-    /// # 戻り値
-    /// * `Vec<String>` - 依存関係のリスト
-    fn extract_go_dependencies(&self, content: &str) -> Vec<String> {
-        let mut deps = Vec::new();
-
-        // import文の抽出
-        let import_re = Regex::new(r#"import\s+(?:\(([^)]+)\)|["']([^"']+)["'])"#).unwrap();
-        for cap in import_re.captures_iter(content) {
-            if let Some(dep) = cap.get(2) {
-                deps.push(dep.as_str().to_string());
-            } else if let Some(block) = cap.get(1) {
-                // 複数行import
-                for line in block.as_str().lines() {
-                    let line_re = Regex::new(r#"["']([^"']+)["']"#).unwrap();
-                    for line_cap in line_re.captures_iter(line) {
-                        if let Some(dep) = line_cap.get(1) {
-                            deps.push(dep.as_str().to_string());
-                        }
-                    }
-                }
-            }
+        // `/usr/bin/env -S python3 -u` のような形式にも対応
+        let mut parts = interpreter_line.split_whitespace();
+        let mut program = parts.next()?;
+        if program.ends_with("/env") {
+            program = parts.find(|p| !p.starts_with('-'))?;
         }
+        let program = program.rsplit('/').next().unwrap_or(program);
 
-        deps
-    }
-
-    /// Rustの依存関係を抽出
-    /// 
-    /// # 引数
-    /// * `content` - ファイル内容
-    /// 
-    /// # 戻り値
-    /// * `Vec<String>` - 依存関係のリスト
-    fn extract_rust_dependencies(&self, content: &str) -> Vec<String> {
-        let mut deps = Vec::new();
-
-        // use文の抽出
-        let use_re = Regex::new(r#"use\s+([^;]+);"#).unwrap();
-        for cap in use_re.captures_iter(content) {
-            if let Some(use_stmt) = cap.get(1) {
-                let path = use_stmt.as_str().trim();
-                // エイリアスや特定のアイテムを除外
-                if !path.contains("::") && !path.contains("{") {
-                    continue;
-                }
-                let parts: Vec<&str> = path.split("::").collect();
-                if let Some(first) = parts.first() {
-                    deps.push(first.trim().to_string());
-                }
-            }
+        if program.starts_with("python") {
+            Some("py".to_string())
+        } else if program == "node" || program == "nodejs" {
+            Some("js".to_string())
+        } else if program == "deno" || program == "ts-node" || program == "tsx" || program == "bun" {
+            Some("ts".to_string())
+        } else if program.starts_with("ruby") {
+            Some("rb".to_string())
+        } else if program.starts_with("php") {
+            Some("php".to_string())
+        } else {
+            None
         }
-
-        deps
     }
 
     /// モジュールファイルかどうかを判定
@@ -345,6 +651,17 @@ impl Analyzer {
                 // lib.rs または特定のディレクトリ構造
                 file_name == "lib.rs" || parent == Some("src")
             }
+            "c" | "cpp" => {
+                // ヘッダーファイル、または特定のディレクトリ構造
+                let is_header = matches!(
+                    path.extension().and_then(|e| e.to_str()),
+                    Some("h" | "hpp" | "hh" | "hxx")
+                );
+                is_header || parent == Some("include") || parent == Some("src")
+            }
+            "cs" => parent == Some("src"),
+            "rb" | "php" => parent == Some("lib") || parent == Some("src") || parent == Some("app"),
+            "kt" => parent == Some("kotlin") || parent == Some("src"),
             _ => false,
         }
     }
@@ -354,10 +671,11 @@ impl Analyzer {
     /// # 引数
     /// * `repo_path` - リポジトリパス
     /// * `config` - 設定
+    /// * `detected` - ソースコード・Dockerfileから検出したエントリーポイント
     /// 
     /// # 戻り値
-    /// * `Result<Vec<PathBuf>>` - エントリーポイントのリスト、またはエラー
-    fn infer_entrypoints(&self, repo_path: &Path, config: &Config) -> Result<Vec<PathBuf>> {
+    /// * `Result<Vec<Entrypoint>>` - エントリーポイントのリスト（設定・ファイル名の規約・フレームワーク・Dockerの順）、またはエラー
+    fn infer_entrypoints(&self, repo_path: &Path, config: &Config, detected: Vec<Entrypoint>) -> Result<Vec<Entrypoint>> {
         let mut entrypoints = Vec::new();
 
         // 設定で指定されたエントリーポイント
@@ -374,9 +692,15 @@ impl Analyzer {
             "main.py", "__main__.py",
             "main.go",
             "main.rs",
+            "main.c", "main.cpp", "main.cc", "src/main.c", "src/main.cpp", "src/main.cc",
+            "Program.cs", "src/Program.cs",
+            "config.ru", "bin/rails", "index.php", "public/index.php", "artisan",
+            "Main.kt", "src/main/kotlin/Main.kt",
             "cmd/**/main.go",
             "apps/**/src/main.ts",
             "apps/**/src/index.ts",
+            "**/Program.cs",
+            "**/MainActivity.kt",
         ];
 
         for pattern in patterns {
@@ -386,11 +710,9 @@ impl Analyzer {
                     let entry = entry?;
                     if entry.file_type().is_file() {
                         let path = entry.path();
-                        if path.file_name()
-                            .and_then(|n| n.to_str())
-                            .map(|n| n == "main.ts" || n == "index.ts")
-                            .unwrap_or(false)
-                        {
+                        // パターン末尾のファイル名で照合する
+                        let target = pattern.rsplit('/').next().unwrap_or(pattern);
+                        if path.file_name().and_then(|n| n.to_str()) == Some(target) {
                             entrypoints.push(path.to_path_buf());
                         }
                     }
@@ -403,50 +725,314 @@ impl Analyzer {
             }
         }
 
+        // 複数のパターンに一致したものは最初の1件のみ残す
+        let mut seen = std::collections::HashSet::new();
+        entrypoints.retain(|path| seen.insert(path.clone()));
+
+        let configured = config.analysis.infer_entrypoints.len();
+        let mut entrypoints: Vec<Entrypoint> = entrypoints
+            .into_iter()
+            .enumerate()
+            .map(|(i, path)| Entrypoint {
+                path,
+                kind: if i < configured { "config" } else { "main" }.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        // フレームワークの検出はファイル名の規約より具体的な種類として上書き
+        for entrypoint in detected {
+            match entrypoints.iter_mut().find(|e| e.path == entrypoint.path && e.kind == "main") {
+                Some(existing) => *existing = entrypoint,
+                None => entrypoints.push(entrypoint),
+            }
+        }
+
         Ok(entrypoints)
     }
+}
 
-    /// ファイルを除外すべきかチェック
-    /// 
-    /// # 引数
-    /// * `path` - ファイルパス
-    /// * `exclude_patterns` - 除外パターンのリスト
-    /// 
-    /// # 戻り値
-    /// * `bool` - 除外すべき場合true
-    fn should_exclude(&self, path: &Path, exclude_patterns: &[String]) -> bool {
-        let path_str = path.to_string_lossy();
-        for pattern in exclude_patterns {
-            // 簡易的なglobマッチング（**と*をサポート）
-            let regex_pattern = pattern
-                .replace("**", ".*")
-                .replace("*", "[^/]*")
-                .replace(".", "\\.");
-            if let Ok(re) = Regex::new(&format!("^{}$", regex_pattern)) {
-                if re.is_match(&path_str) {
-                    return true;
-                }
+/// 走査結果（解析対象のパスのみ。内容は解析時に読み込む）
+struct WalkedRepo {
+    files: Vec<PathBuf>,
+    readmes: Vec<PathBuf>,
+    manifests: Vec<PathBuf>,
+    lockfiles: Vec<PathBuf>,
+    /// 設定ファイル（ソースコードやマニフェストとしても扱うものを含む）
+    config_files: Vec<PathBuf>,
+    vendored: Vec<VendoredDir>,
+    /// `analysis.max_file_kb` を超えるためスキップしたファイル
+    too_large: Vec<PathBuf>,
+}
+
+/// リポジトリを走査して解析対象のファイルを収集
+/// 
+/// # 引数
+/// * `repo_path` - リポジトリのパス
+/// * `filter` - include・excludeパターン
+/// * `detect_vendored` - ベンダリングディレクトリを除外するか
+/// * `max_file_kb` - 解析するファイルサイズの上限
+/// 
+/// # 戻り値
+/// * `Result<WalkedRepo>` - ファイル・パッケージのREADME・マニフェスト・lockfile・設定ファイル・ベンダリングディレクトリ（走査順）
+fn walk_repo(repo_path: &Path, filter: &PathFilter, detect_vendored: bool, max_file_kb: u64) -> Result<WalkedRepo> {
+    let mut walked = WalkedRepo {
+        files: Vec::new(),
+        readmes: Vec::new(),
+        manifests: Vec::new(),
+        lockfiles: Vec::new(),
+        config_files: Vec::new(),
+        vendored: Vec::new(),
+        too_large: Vec::new(),
+    };
+
+    // ベンダリングディレクトリは配下を走査せずに記録
+    let vendored = &mut walked.vendored;
+    let walker = WalkDir::new(repo_path).into_iter().filter_entry(|e| {
+        if detect_vendored && e.depth() > 0 && e.file_type().is_dir() {
+            if let Some(reason) = vendored::vendored_reason(e.file_name()) {
+                vendored.push(vendored::describe(repo_path, e.path(), reason));
+                return false;
             }
         }
-        false
+        true
+    });
+    for entry in walker {
+        let entry = entry?;
+        let path = entry.path();
+
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        // include・excludeパターンのチェック
+        if !filter.is_included(path.strip_prefix(repo_path).unwrap_or(path)) {
+            continue;
+        }
+
+        // lockfile（大きくなりやすいためサイズの上限は適用しない）
+        if entry.file_name().to_str().is_some_and(|name| licenses::LOCK_FILES.contains(&name)) {
+            walked.lockfiles.push(path.to_path_buf());
+            continue;
+        }
+
+        // ファイルサイズチェック
+        let size_kb = entry.metadata()?.len() / 1024;
+        if size_kb > max_file_kb {
+            walked.too_large.push(path.to_path_buf());
+            continue;
+        }
+
+        // 設定ファイル（Cargo.toml・webpack.config.jsはマニフェスト・ソースコードとしても扱う）
+        if entry.file_name().to_str().is_some_and(config_files::is_config_file) {
+            walked.config_files.push(path.to_path_buf());
+        }
+
+        // パッケージのREADME（リポジトリ直下は概要用のため対象外）
+        if entry.depth() > 1 && entry.file_name().eq_ignore_ascii_case("readme.md") {
+            walked.readmes.push(path.to_path_buf());
+            continue;
+        }
+
+        // パッケージマニフェスト
+        if entry.file_name().to_str().is_some_and(|name| manifests::MANIFEST_FILES.contains(&name)) {
+            walked.manifests.push(path.to_path_buf());
+            continue;
+        }
+
+        walked.files.push(path.to_path_buf());
+    }
+
+    Ok(walked)
+}
+
+/// ファイルを解析
+/// 
+/// ファイル内容は解析の間だけ保持し、`FileInfo` には含めない（`Index::file_content` で読み直す）。
+/// 
+/// # 引数
+/// * `path` - ファイルパス
+/// * `language` - 言語識別子
+/// * `is_module` - モジュールとして扱うか
+/// * `is_test` - テストファイルか（リポジトリルートからの相対パスで判定したもの）
+/// * `relative` - リポジトリルートからの相対パス（`security.redaction.paths` の照合に使う）
+/// * `redaction` - 機密情報・個人情報と独自の規則に一致した値を置き換える規則
+/// 
+/// # 戻り値
+/// * `Result<Option<AnalyzedFile>>` - ファイル情報・フレームワークのエントリーポイント・APIエンドポイント（バイナリファイルの場合はNone）、またはエラー
+fn analyze_file(
+    path: &Path,
+    relative: &Path,
+    language: &str,
+    is_module: bool,
+    is_test: bool,
+    redaction: &RedactionRules,
+) -> Result<Option<AnalyzedFile>> {
+    let bytes = std::fs::read(path).with_context(|| format!("ファイル読み込みエラー: {:?}", path))?;
+    let (content, encoding) = match encoding::decode(&bytes) {
+        encoding::Decoded::Text { content, encoding } => (content, encoding),
+        encoding::Decoded::Binary => return Ok(None),
+    };
+    // ドキュメントコメントなどに機密情報が残らないよう、置き換えた内容から抽出する
+    let (content, redactions) = redaction.redact(relative, &content);
+
+    let name = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // tree-sitterでimportとシンボルを抽出し、同じ構文木から行数・複雑度を計算
+    let tree = syntax::parse_tree(&content, language);
+    let parsed = tree.as_ref().map(|t| syntax::collect(t, &content, language)).unwrap_or_default();
+    let metrics = metrics::measure(&content, tree.as_ref(), &parsed.symbols);
+    let tests = testing::count_tests(&content, language, is_test);
+    let entrypoint = if is_test { None } else { entrypoints::detect_in_source(path, language, &content) };
+    let endpoints = if is_test { Vec::new() } else { routes::extract_endpoints(path, language, &content) };
+    let todos = todos::extract_todos(&content);
+    let conditions = conditions::detect_conditions(relative, language, &content);
+
+    let file_info = FileInfo {
+        path: path.to_path_buf(),
+        name,
+        language: language.to_string(),
+        size: content.len(),
+        dependencies: parsed.imports,
+        is_module,
+        symbols: parsed.symbols,
+        relations: parsed.relations,
+        metrics,
+        is_test,
+        tests,
+        history: None,
+        content: None,
+        encoding: (encoding != "UTF-8").then(|| encoding.to_string()),
+        redactions,
+        todos,
+        build_conditions: conditions.own,
+    };
+    Ok(Some(AnalyzedFile { info: file_info, entrypoint, endpoints, conditional_imports: conditions.imports }))
+}
+
+/// 作業メモの行の作成者を調べるファイルの最大数
+const MAX_BLAME_FILES: usize = 300;
+
+/// レポートに載せる置き換えた位置の最大数（1ファイルあたり）
+const MAX_REPORTED_REDACTIONS: usize = 5;
+
+/// 置き換えた位置の警告の文言（`12行目（メールアドレス）` を並べたもの）
+fn describe_redactions(redactions: &[Redaction]) -> String {
+    let mut locations: Vec<String> = redactions
+        .iter()
+        .take(MAX_REPORTED_REDACTIONS)
+        .map(|r| format!("{}行目（{}）", r.line, r.kind_label()))
+        .collect();
+    if redactions.len() > MAX_REPORTED_REDACTIONS {
+        locations.push(format!("ほか{}件", redactions.len() - MAX_REPORTED_REDACTIONS));
     }
+    format!("機密情報・個人情報をマスクしました: {}", locations.join("、"))
+}
+
+/// 設定の `language-overrides` に一致する言語（ファイル名、次に拡張子で照合）
+fn language_override(path: &Path, config: &Config) -> Option<String> {
+    let overrides = &config.analysis.language_overrides;
+    let file_name = path.file_name().and_then(|n| n.to_str());
+    let ext = path.extension().and_then(|e| e.to_str());
+    file_name.and_then(|n| overrides.get(n)).or_else(|| ext.and_then(|e| overrides.get(e))).cloned()
+}
+
+/// 1ファイルの解析結果
+struct AnalyzedFile {
+    info: FileInfo,
+    /// フレームワークのエントリーポイント
+    entrypoint: Option<Entrypoint>,
+    /// ルーティングの定義から抽出したAPIエンドポイント
+    endpoints: Vec<ApiEndpoint>,
+    /// 条件付きのimport（読み込んだファイルに条件を記録する）
+    conditional_imports: Vec<conditions::ConditionalImport>,
+}
+
+/// HEADのコミットSHAを取得
+fn head_commit(repo_path: &Path) -> Option<String> {
+    let repo = git2::Repository::discover(repo_path).ok()?;
+    let commit = repo.head().ok()?.peel_to_commit().ok()?;
+    Some(commit.id().to_string())
 }
 
 /// インデックス
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Index {
     pub id: String,
     pub repo_path: PathBuf,
+    /// 解析時のHEADのコミットSHA（gitリポジトリでない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
     pub files: Vec<FileInfo>,
     pub modules: Vec<ModuleInfo>,
     pub languages: Vec<String>,
+    /// import文字列 → 解決したリポジトリ内のファイル（`/` 区切りの相対パス、外部依存は空）
     pub dependencies: HashMap<String, Vec<String>>,
+    /// ファイル → importしているリポジトリ内のファイル
+    #[serde(default)]
+    pub internal_dependencies: BTreeMap<PathBuf, Vec<PathBuf>>,
     pub entrypoints: Vec<PathBuf>,
+    /// エントリーポイントの種類と起動方法（Dockerfileの起動コマンドを含む）
+    #[serde(default)]
+    pub entrypoint_details: Vec<Entrypoint>,
+    /// REST・GraphQLのAPIエンドポイント（定義したファイルの走査順）
+    #[serde(default)]
+    pub endpoints: Vec<ApiEndpoint>,
+    /// ビルドファイルから検出したビルド・テスト・実行コマンド
+    #[serde(default)]
+    pub build_commands: Vec<BuildCommand>,
+    /// 解析対象から除外したベンダリング・サードパーティディレクトリ
+    #[serde(default)]
+    pub vendored: Vec<VendoredDir>,
+    /// パッケージディレクトリごとのREADME.md
+    #[serde(default)]
+    pub readmes: Vec<PackageReadme>,
+    /// リポジトリ直下のREADME・LICENSE・CONTRIBUTING・CHANGELOGとライセンスの種類
+    #[serde(default)]
+    pub project_docs: ProjectDocs,
+    /// ARCHITECTURE.md・ADRと、そこに書かれた構成要素の言及
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub design_docs: Vec<DesignDoc>,
+    /// パッケージマニフェスト（Cargo.toml・package.json・pyproject.toml・requirements.txt・go.mod）
+    #[serde(default)]
+    pub manifests: Vec<Manifest>,
+    /// マニフェストに宣言された外部依存（lockfileで解決したバージョン・ライセンス付き）
+    #[serde(default)]
+    pub external_dependencies: Vec<ExternalDependency>,
+    /// 解析対象のリポジトリ自身の設定ファイル（tsconfig・Cargo.tomlのフィーチャー・.env.example・webpack）
+    #[serde(default)]
+    pub config_files: Vec<ConfigFile>,
+    /// ワークスペースのパッケージ（モノレポでない場合は空）
+    #[serde(default)]
+    pub packages: Vec<PackageInfo>,
+    /// プロジェクトの歩み（リリース・大規模な変更・ドキュメントの再生成、古い順、`analysis.git-history = false` の場合は空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub timeline: Vec<TimelineEvent>,
+    /// ファイル内容・要約に適用する置き換えの規則（`Index::file_content` は同じ規則で置き換えて返す）
+    #[serde(default)]
+    pub redaction: RedactionRules,
     pub stats: IndexStats,
+    /// 解析中に記録した警告（スキップしたファイルなど）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    /// ファイル内容の読み込み元（`analyze_repo` の結果は作業ツリー、`Index::load` の結果は保存したブロブ）
+    #[serde(skip)]
+    pub store: Option<std::sync::Arc<ContentStore>>,
+    /// 全文検索インデックス（`index.provider = "tantivy"` の場合のみ）
+    #[cfg(feature = "tantivy-search")]
+    #[serde(skip)]
+    pub search_index: Option<std::sync::Arc<SearchIndex>>,
+    /// 意味検索のベクトルインデックス（`index.embeddings = "none"` 以外の場合のみ）
+    #[serde(skip)]
+    pub vectors: Option<std::sync::Arc<VectorIndex>>,
 }
 
 /// ファイル情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FileInfo {
     pub path: PathBuf,
     pub name: String,
@@ -454,128 +1040,1494 @@ pub struct FileInfo {
     pub size: usize,
     pub dependencies: Vec<String>,
     pub is_module: bool,
+    /// tree-sitterで抽出した関数・型・implブロックなど
+    #[serde(default)]
+    pub symbols: Vec<Symbol>,
+    /// 宣言した型の実装・継承関係
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relations: Vec<TypeRelation>,
+    /// 行数・関数数・サイクロマティック複雑度
+    #[serde(default)]
+    pub metrics: FileMetrics,
+    /// テストファイルか（`tests/` 配下・`*_test.go`・`*.spec.ts` など）
+    #[serde(default)]
+    pub is_test: bool,
+    /// テスト関数の数（Rustの `#[cfg(test)]` のように本体のファイルに含むテストも数える）
+    #[serde(default)]
+    pub tests: usize,
+    /// コミット履歴（Gitリポジトリでない場合・`analysis.git-history = false` の場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<FileHistory>,
+    /// ファイル内容（`analyze_repo` の結果・保存したインデックスではNone。`Index::file_content` で取得）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// 変換元の文字コード（Shift_JIS・EUC-JPなど、UTF-8の場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+    /// 機密情報・個人情報を置き換えた位置（`security.pii-redaction`、`Index::file_content` は置き換えた内容を返す）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
+    /// TODO・FIXME・HACK・XXXのコメント（行の順、Gitの履歴がある場合は行の作成者付き）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub todos: Vec<TodoComment>,
+    /// 条件付きでコンパイル・読み込みされる場合の条件（Cargoのフィーチャー・`cfg`・プリプロセッサ・環境変数、無条件の場合は空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub build_conditions: Vec<BuildCondition>,
 }
 
 /// モジュール情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModuleInfo {
     pub path: PathBuf,
+    /// パッケージとパスで一意に決まるID（例: `web:src/index.ts`、パッケージ外は `scripts/build.ts`）
+    ///
+    /// Wikiの見出し・アンカー、図のラベル、スライド、検索結果で使う
+    #[serde(default)]
+    pub id: String,
+    /// ファイル名（拡張子なし、リポジトリ内で重複しうる）
     pub name: String,
     pub language: String,
     pub dependencies: Vec<String>,
+    /// 重要度スコア（0.0〜1.0、被依存数・行数・エントリーポイントへの近さ・変更頻度から算出）
+    #[serde(default)]
+    pub importance: f64,
+    /// コンポーネントの種類（ファイル名・ディレクトリ名から推定、図・スライドのアイコンと色に使う）
+    #[serde(default)]
+    pub component: ComponentKind,
+}
+
+impl ModuleInfo {
+    /// import文字列がこのモジュールを指すか（内部依存グラフのない場合の推定）
+    /// 
+    /// # 引数
+    /// * `import` - import文字列（`./util`、`crate::store::Item`、`app.models` など）
+    pub fn matches_import(&self, import: &str) -> bool {
+        module_id::matches_import(&self.id, import)
+    }
+}
+
+/// パッケージのREADME
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageReadme {
+    /// README.mdを含むディレクトリ
+    pub dir: PathBuf,
+    pub content: String,
 }
 
 /// インデックス統計情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IndexStats {
     pub files: usize,
     pub languages: Vec<String>,
     pub modules: usize,
+    /// 解析せずに除外したファイル（バイナリ・読み込めないファイル）
+    #[serde(default)]
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// 解析から除外したファイル
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    /// 除外した理由（`binary`・`too-large`、または `unreadable: <エラー>`）
+    pub reason: String,
 }
 
 impl Index {
-    /// 検索を実行
+    /// インデックスをコンパクト形式（zstd圧縮・ハッシュ参照のブロブ）で保存
     /// 
     /// # 引数
-    /// * `query` - 検索クエリ
-    /// * `k` - 返す結果の最大数
+    /// * `path` - 保存先
     /// 
     /// # 戻り値
-    /// * `Result<Vec<SearchHit>>` - 検索結果、またはエラー
-    pub async fn search(&self, query: &str, k: usize) -> Result<Vec<SearchHit>> {
-        let mut hits = Vec::new();
-        let query_lower = query.to_lowercase();
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn save(&self, path: &Path) -> Result<()> {
+        self.save_with(path, "full")
+    }
 
-        for file in &self.files {
-            if let Some(content) = &file.content {
-                let content_lower = content.to_lowercase();
-                if content_lower.contains(&query_lower) {
-                    // 簡易的なマッチング（後でtantivyに置き換え可能）
-                    let score = self.calculate_score(&content_lower, &query_lower);
-                    let excerpt = self.extract_excerpt(content, &query_lower, 100);
-
-                    hits.push(SearchHit {
-                        path: file.path.to_string_lossy().to_string(),
-                        score,
-                        excerpt,
-                    });
-                }
-            }
+    /// 保存するファイル内容を選んでインデックスを保存
+    /// 
+    /// excerpts・hashでは全文を保存せず、読み込んだインデックスの `file_content` は作業ツリーから読み直す
+    /// （保存後に変更されたファイルは、excerptsでは先頭の抜粋、hashでは読み込みエラーになる）。
+    /// 本文を格納する全文検索インデックスは、fullの場合のみ保存する。
+    /// 
+    /// # 引数
+    /// * `path` - 保存先
+    /// * `content` - 保存する内容（full|excerpts|hash、`index.content` の値）
+    /// 
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn save_with(&self, path: &Path, content: &str) -> Result<()> {
+        store::save(self, path, content)?;
+        #[cfg(feature = "tantivy-search")]
+        if let Some(search_index) = self.search_index.as_ref().filter(|_| content == "full") {
+            search_index.save(&search::search_dir(path))?;
         }
-
-        // スコアでソート
-        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-        hits.truncate(k);
-
-        Ok(hits)
+        if let Some(vectors) = &self.vectors {
+            vectors.save(&embeddings::vectors_path(path))?;
+        }
+        Ok(())
     }
 
-    /// スコアを計算
-    fn calculate_score(&self, content: &str, query: &str) -> f64 {
-        let query_words: Vec<&str> = query.split_whitespace().collect();
-        let mut score = 0.0;
-
-        for word in &query_words {
-            let count = content.matches(word).count();
-            score += count as f64;
+    /// インデックスを読み込む
+    /// 
+    /// 構造インデックスのみを展開し、ファイル内容は `file_content` で必要な時に読み込む。
+    /// 従来のJSON形式のインデックスも読み込める。
+    /// 
+    /// # 引数
+    /// * `path` - インデックスファイル
+    /// 
+    /// # 戻り値
+    /// * `Result<Index>` - インデックス、またはエラー
+    pub fn load(path: &Path) -> Result<Index> {
+        #[allow(unused_mut)]
+        let mut index = store::load(path)?;
+        #[cfg(feature = "tantivy-search")]
+        {
+            let dir = search::search_dir(path);
+            if dir.exists() {
+                index.search_index = Some(std::sync::Arc::new(SearchIndex::open(&dir)?));
+            }
         }
-
-        score / (query_words.len() as f64 + 1.0)
+        let vectors = embeddings::vectors_path(path);
+        if vectors.exists() {
+            index.vectors = Some(std::sync::Arc::new(VectorIndex::load(&vectors)?));
+        }
+        // モジュールIDのない古いインデックス
+        if index.modules.iter().any(|m| m.id.is_empty()) {
+            index.assign_module_ids();
+        }
+        Ok(index)
     }
 
-    /// 抜粋を抽出
-    fn extract_excerpt(&self, content: &str, query: &str, max_len: usize) -> String {
-        if let Some(pos) = content.to_lowercase().find(query) {
-            let start = pos.saturating_sub(max_len / 2);
-            let end = (pos + query.len() + max_len / 2).min(content.len());
-            let excerpt = &content[start..end];
-            format!("...{}...", excerpt)
-        } else {
-            let excerpt = &content[..content.len().min(max_len)];
-            format!("{}...", excerpt)
+    /// 保存したインデックス（検索インデックスを含む）を削除
+    /// 
+    /// # 引数
+    /// * `path` - `Index::save` の保存先
+    /// 
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn remove_saved(path: &Path) -> Result<()> {
+        if path.exists() {
+            std::fs::remove_file(path).with_context(|| format!("インデックスを削除できません: {:?}", path))?;
+        }
+        #[cfg(feature = "tantivy-search")]
+        {
+            let dir = search::search_dir(path);
+            if dir.exists() {
+                std::fs::remove_dir_all(&dir).with_context(|| format!("検索インデックスを削除できません: {:?}", dir))?;
+            }
         }
+        let vectors = embeddings::vectors_path(path);
+        if vectors.exists() {
+            std::fs::remove_file(&vectors).with_context(|| format!("ベクトルインデックスを削除できません: {:?}", vectors))?;
+        }
+        Ok(())
     }
-}
 
-/// 検索ヒット
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SearchHit {
-    pub path: String,
-    pub score: f64,
-    pub excerpt: String,
-}
+    /// シンボルの定義・参照・呼び出し関係を取得
+    /// 
+    /// # 引数
+    /// * `name` - シンボル名（`Type::method` / `Type.method` 形式で型を指定可能）
+    /// 
+    /// # 戻り値
+    /// * `Option<SymbolExplanation>` - 解説（見つからない場合はNone）
+    pub fn explain_symbol(&self, name: &str) -> Option<SymbolExplanation> {
+        xref::explain_symbol(self, name)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// 内部依存グラフの循環依存を検出
+    /// 
+    /// importをたどって互いに到達できるファイルの集まりごとに、含まれるファイルと最短の循環の経路を返す。
+    /// 
+    /// # 戻り値
+    /// * `Vec<DependencyCycle>` - 循環依存（ファイル数の多い順、循環がない場合は空）
+    pub fn dependency_cycles(&self) -> Vec<DependencyCycle> {
+        depgraph::find_cycles(&self.internal_dependencies)
+    }
 
-    #[test]
-    fn test_extract_js_dependencies() {
-        let analyzer = Analyzer::new(Config::default());
-        let content = r#"
-import { foo } from './foo';
-import bar from 'bar';
-const baz = require('baz');
-"#;
-        let deps = analyzer.extract_js_dependencies(content);
-        assert!(deps.contains(&"./foo".to_string()));
-        assert!(deps.contains(&"bar".to_string()));
-        assert!(deps.contains(&"baz".to_string()));
+    /// モジュールを層に分類し、層の間の依存と層の逆転を求める
+    /// 
+    /// # 戻り値
+    /// * `ArchitectureModel` - モジュールの層と層の間の依存（名前・import・内部依存からの推定）
+    pub fn architecture(&self) -> ArchitectureModel {
+        architecture::infer_architecture(self)
     }
 
-    #[test]
-    fn test_extract_py_dependencies() {
-        let analyzer = Analyzer::new(Config::default());
+    /// TODO・FIXME・HACK・XXXのコメントを一覧にする
+    /// 
+    /// # 戻り値
+    /// * `Vec<(&FileInfo, &TodoComment)>` - ファイルと作業メモ（目印の深刻な順 `TODO_MARKERS`、同じ目印はパス・行の順）
+    pub fn todo_comments(&self) -> Vec<(&FileInfo, &TodoComment)> {
+        let severity = |marker: &str| TODO_MARKERS.iter().position(|m| *m == marker).unwrap_or(TODO_MARKERS.len());
+        let mut todos: Vec<(&FileInfo, &TodoComment)> = self.files.iter().flat_map(|f| f.todos.iter().map(move |t| (f, t))).collect();
+        todos.sort_by(|(fa, a), (fb, b)| severity(&a.marker).cmp(&severity(&b.marker)).then_with(|| fa.path.cmp(&fb.path)).then(a.line.cmp(&b.line)));
+        todos
+    }
+
+    /// 未使用コードの候補を検出
+    /// 
+    /// どのファイルからもimportされていないモジュールと、定義以外で名前が現れない公開関数・メソッドを返す。
+    /// ファイル内容をすべて読むため、呼び出しごとに計算する。
+    /// 
+    /// # 戻り値
+    /// * `UnusedCode` - 孤立したモジュールと参照されない公開関数
+    pub fn unused_code(&self) -> UnusedCode {
+        unused::find_unused_code(self)
+    }
+
+    /// コードベースに繰り返し現れるドメインの用語を抽出
+    /// 
+    /// 型・関数・ファイルの名前を単語に分け、表記の揺れ（大文字・小文字、単数・複数）をまとめて数える。
+    /// 
+    /// # 戻り値
+    /// * `Vec<GlossaryTerm>` - 用語（表記の辞書順）
+    pub fn glossary_terms(&self) -> Vec<GlossaryTerm> {
+        glossary::extract_terms(self)
+    }
+
+    /// コードが呼び出す外部サービス（クラウド・SaaS・データベース・メッセージキュー・HTTP API）を取得
+    /// 
+    /// # 戻り値
+    /// * `Vec<ExternalService>` - クライアントライブラリのimportから推定した外部サービスと、importしているファイル
+    pub fn external_services(&self) -> Vec<ExternalService> {
+        services::detect_services(self)
+    }
+
+    /// 接続先を設定する環境変数（.env.exampleの `*_URL`・`*_HOST`・`*_API_KEY` など）を取得
+    /// 
+    /// # 戻り値
+    /// * `Vec<(PathBuf, String)>` - 設定ファイルのパスと環境変数名（記載順）
+    pub fn endpoint_variables(&self) -> Vec<(PathBuf, String)> {
+        services::endpoint_variables(self)
+    }
+
+    /// ARCHITECTURE.md・ADRに書かれた構成と実装の差分を取得
+    /// 
+    /// # 戻り値
+    /// * `DesignDrift` - ドキュメントにあってコードにない言及と、コードにあってドキュメントにない構成要素（設計ドキュメントがない場合は空）
+    pub fn design_drift(&self) -> DesignDrift {
+        design_docs::compare(self)
+    }
+
+    /// すべてのファイルの型の実装・継承関係を取得
+    /// 
+    /// # 戻り値
+    /// * `Vec<(&FileInfo, &TypeRelation)>` - 関係と宣言したファイル（ファイル・行の順）
+    pub fn type_relations(&self) -> Vec<(&FileInfo, &TypeRelation)> {
+        let mut relations: Vec<(&FileInfo, &TypeRelation)> = self
+            .files
+            .iter()
+            .flat_map(|file| file.relations.iter().map(move |relation| (file, relation)))
+            .collect();
+        relations.sort_by(|a, b| a.0.path.cmp(&b.0.path).then(a.1.line.cmp(&b.1.line)));
+        relations
+    }
+
+    /// ファイルが属するワークスペースのパッケージを取得（最も深いパッケージのディレクトリ）
+    /// 
+    /// # 引数
+    /// * `path` - ファイルパス（絶対パスまたはリポジトリルートからの相対パス）
+    /// 
+    /// # 戻り値
+    /// * `Option<&PackageInfo>` - パッケージ（どのパッケージにも属さない場合はNone）
+    pub fn package_of(&self, path: &Path) -> Option<&PackageInfo> {
+        let relative = path.strip_prefix(&self.repo_path).unwrap_or(path);
+        self.packages
+            .iter()
+            .filter(|package| relative.starts_with(&package.path))
+            .max_by_key(|package| package.path.components().count())
+    }
+
+    /// モジュールIDを割り当てる（パッケージの検出後に呼ぶ）
+    pub fn assign_module_ids(&mut self) {
+        let ids: Vec<String> = self
+            .modules
+            .iter()
+            .map(|module| {
+                let relative = module.path.strip_prefix(&self.repo_path).unwrap_or(&module.path);
+                module_id::qualified_id(self.package_of(relative), relative)
+            })
+            .collect();
+        for (module, id) in self.modules.iter_mut().zip(ids) {
+            module.id = id;
+        }
+    }
+
+    /// ファイルのモジュールを取得
+    /// 
+    /// # 引数
+    /// * `path` - ファイルパス（絶対パスまたはリポジトリルートからの相対パス）
+    /// 
+    /// # 戻り値
+    /// * `Option<&ModuleInfo>` - モジュール（モジュールでないファイルの場合はNone）
+    pub fn module_at(&self, path: &Path) -> Option<&ModuleInfo> {
+        let relative = path.strip_prefix(&self.repo_path).unwrap_or(path);
+        self.modules
+            .iter()
+            .find(|m| m.path.strip_prefix(&self.repo_path).unwrap_or(&m.path) == relative)
+    }
+
+    /// パッケージに属するファイルを取得（入れ子のパッケージのファイルは除く）
+    /// 
+    /// # 引数
+    /// * `package` - パッケージ
+    /// 
+    /// # 戻り値
+    /// * `Vec<&FileInfo>` - ファイル（インデックスの順）
+    pub fn package_files(&self, package: &PackageInfo) -> Vec<&FileInfo> {
+        self.files
+            .iter()
+            .filter(|file| self.package_of(&file.path).is_some_and(|p| p.manifest == package.manifest))
+            .collect()
+    }
+
+    /// 名前またはディレクトリでパッケージを検索
+    /// 
+    /// # 引数
+    /// * `target` - パッケージ名、またはディレクトリ（絶対パスまたはリポジトリルートからの相対パス）
+    /// 
+    /// # 戻り値
+    /// * `Option<&PackageInfo>` - パッケージ（見つからない場合はNone）
+    pub fn find_package(&self, target: &str) -> Option<&PackageInfo> {
+        let path = Path::new(target);
+        let relative = path.strip_prefix(&self.repo_path).unwrap_or(path);
+        self.packages
+            .iter()
+            .find(|package| package.name == target)
+            .or_else(|| self.packages.iter().find(|package| package.path == relative))
+    }
+
+    /// マニフェストに宣言されたサードパーティの依存を取得
+    /// 
+    /// 複数のマニフェストで宣言された依存は、実行時の依存・具体的なバージョンの宣言を優先して1件にまとめる。
+    /// 
+    /// # 戻り値
+    /// * `Vec<(&Manifest, &DeclaredDependency)>` - 依存と宣言したマニフェスト（名前順）
+    pub fn external_packages(&self) -> Vec<(&Manifest, &DeclaredDependency)> {
+        let mut packages: Vec<(&Manifest, &DeclaredDependency)> = self
+            .manifests
+            .iter()
+            .flat_map(|manifest| manifest.dependencies.iter().map(move |dep| (manifest, dep)))
+            .filter(|(_, dep)| !dep.internal)
+            .collect();
+        packages.sort_by_key(|(_, dep)| (&dep.name, dep.kind != "normal", dep.version.as_deref() == Some("workspace")));
+        packages.dedup_by(|a, b| a.1.name == b.1.name);
+        packages
+    }
+
+    /// 種類付きのエントリーポイントを取得
+    /// 
+    /// 種類を記録していない古いインデックスでは、エントリーポイントのパスを `main` として返す。
+    /// 
+    /// # 戻り値
+    /// * `Vec<Entrypoint>` - エントリーポイント（Dockerfileの起動コマンドを含む）
+    pub fn all_entrypoints(&self) -> Vec<Entrypoint> {
+        if !self.entrypoint_details.is_empty() {
+            return self.entrypoint_details.clone();
+        }
+        self.entrypoints
+            .iter()
+            .map(|path| Entrypoint { path: path.clone(), kind: "main".to_string(), ..Default::default() })
+            .collect()
+    }
+
+    /// ファイルをimportしているリポジトリ内のファイルを取得
+    /// 
+    /// # 引数
+    /// * `path` - ファイルパス
+    /// 
+    /// # 戻り値
+    /// * `Vec<&Path>` - importしているファイル（パス順）
+    pub fn dependents_of(&self, path: &Path) -> Vec<&Path> {
+        self.internal_dependencies
+            .iter()
+            .filter(|(_, targets)| targets.iter().any(|t| t == path))
+            .map(|(from, _)| from.as_path())
+            .collect()
+    }
+
+    /// ファイルをテストしているテストファイルを取得
+    /// 
+    /// ファイルをimportしているテストファイルと、名前が対応するテストファイル（`foo_test.go` → `foo.go`、
+    /// `test_foo.py` → `foo.py`、`Foo.spec.ts` → `Foo.ts`）を返す。
+    /// 
+    /// # 引数
+    /// * `path` - ファイルパス
+    /// 
+    /// # 戻り値
+    /// * `Vec<&FileInfo>` - テストファイル（インデックスの順）
+    pub fn tests_of(&self, path: &Path) -> Vec<&FileInfo> {
+        let dependents = self.dependents_of(path);
+        let relative = |path: &'_ Path| path.strip_prefix(&self.repo_path).unwrap_or(path).to_path_buf();
+        self.files
+            .iter()
+            .filter(|file| file.is_test && file.path != path)
+            .filter(|file| {
+                dependents.contains(&file.path.as_path())
+                    || testing::matches_by_name(&relative(&file.path), &relative(path))
+            })
+            .collect()
+    }
+
+    /// ファイル内容を取得（遅延読み込みの場合はブロブから展開）
+    /// 
+    /// # 引数
+    /// * `file` - ファイル情報
+    /// 
+    /// # 戻り値
+    /// * `Option<Cow<str>>` - ファイル内容（保存されていない場合はNone）
+    pub fn file_content<'a>(&'a self, file: &'a FileInfo) -> Option<Cow<'a, str>> {
+        let content = match &file.content {
+            Some(content) => Cow::Borrowed(content.as_str()),
+            None => match self.store.as_ref()?.read(&file.path) {
+                Ok(content) => Cow::Owned(content?),
+                Err(e) => {
+                    warn!("ファイル内容の読み込みに失敗しました: {:?} - {}", file.path, e);
+                    return None;
+                }
+            },
+        };
+        // 作業ツリー・保存前の内容は解析後に変更されている場合があるため、常に同じ規則で置き換えて返す
+        if !self.redaction.is_active() {
+            return Some(content);
+        }
+        let relative = file.path.strip_prefix(&self.repo_path).unwrap_or(&file.path);
+        Some(Cow::Owned(self.redaction.redact(relative, &content).0))
+    }
+
+    /// 置き換えた件数（種類 → 件数）
+    /// 
+    /// 解析時にファイル内容で置き換えた位置と、READMEの抜粋・要約で置き換えた件数（`RedactionRules::redact_text`）の合計。
+    /// 
+    /// # 戻り値
+    /// * `BTreeMap<String, usize>` - 種類（`Redaction::kind`）→ 件数
+    pub fn redaction_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = self.redaction.text_counts();
+        for redaction in self.files.iter().flat_map(|f| &f.redactions) {
+            *counts.entry(redaction.kind.clone()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// ファイル内容を逐次読み込むために開く（全体をメモリに展開しない）
+    /// 
+    /// 解析時にShift_JIS・EUC-JPから変換したファイルと、置き換えの規則がある場合は、全体を読み込んで変換・置き換えてから返す。
+    /// 
+    /// # 引数
+    /// * `file` - ファイル情報
+    /// 
+    /// # 戻り値
+    /// * `Option<ContentReader>` - 読み込み元（保存されていない場合はNone）
+    pub fn open_content<'a>(&'a self, file: &'a FileInfo) -> Option<ContentReader<'a>> {
+        if let Some(content) = &file.content {
+            return Some(Box::new(std::io::Cursor::new(content.as_bytes())));
+        }
+        // 秘密鍵のブロックは複数行にわたるため、置き換える場合も全体を読み込んでから返す
+        if file.encoding.is_some() || self.redaction.is_active() {
+            let content = self.file_content(file)?.into_owned();
+            return Some(Box::new(std::io::Cursor::new(content.into_bytes())));
+        }
+        match self.store.as_ref()?.open(&file.path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                warn!("ファイル内容の読み込みに失敗しました: {:?} - {}", file.path, e);
+                None
+            }
+        }
+    }
+
+    /// ファイル内容を1行ずつ読む
+    /// 
+    /// # 引数
+    /// * `file` - ファイル情報
+    /// 
+    /// # 戻り値
+    /// * `Option<ContentLines>` - 行のイテレーター（保存されていない場合はNone）
+    pub fn content_lines<'a>(&'a self, file: &'a FileInfo) -> Option<ContentLines<'a>> {
+        self.open_content(file).map(ContentLines::new)
+    }
+
+    /// ファイル内容を行単位の窓ごとに読む
+    /// 
+    /// # 引数
+    /// * `file` - ファイル情報
+    /// * `limit` - 1つの窓の重みの上限
+    /// * `weight` - 1行の重み（バイト数なら `str::len`）
+    /// 
+    /// # 戻り値
+    /// * `Option<ContentWindows>` - 窓のイテレーター（保存されていない場合はNone）
+    pub fn content_windows<'a>(
+        &'a self,
+        file: &'a FileInfo,
+        limit: usize,
+        weight: fn(&str) -> usize,
+    ) -> Option<ContentWindows<'a>> {
+        self.open_content(file).map(|reader| ContentWindows::new(reader, limit, weight))
+    }
+
+    /// 遅延読み込み中のファイル内容をすべて展開
+    /// 
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn load_contents(&mut self) -> Result<()> {
+        let store = match &self.store {
+            Some(store) => store.clone(),
+            None => return Ok(()),
+        };
+        for file in &mut self.files {
+            if file.content.is_none() {
+                file.content = store.read(&file.path)?;
+            }
+        }
+        self.store = None;
+        Ok(())
+    }
+
+    /// ファイルが属するパッケージのREADMEを取得
+    /// 
+    /// ファイルのディレクトリからリポジトリルートの手前まで遡り、最も近いREADMEを返す。
+    /// 
+    /// # 引数
+    /// * `path` - ファイルパス
+    /// 
+    /// # 戻り値
+    /// * `Option<&PackageReadme>` - 最も近いパッケージのREADME
+    pub fn package_readme(&self, path: &Path) -> Option<&PackageReadme> {
+        path.ancestors()
+            .skip(1)
+            .take_while(|dir| *dir != self.repo_path.as_path() && dir.starts_with(&self.repo_path))
+            .find_map(|dir| self.readmes.iter().find(|r| r.dir == dir))
+    }
+
+    /// 重要度の高い順にモジュールを取得
+    /// 
+    /// # 引数
+    /// * `limit` - 返すモジュールの最大数（0の場合は無制限）
+    /// 
+    /// # 戻り値
+    /// * `Vec<&ModuleInfo>` - 重要度の降順に並んだモジュール
+    pub fn modules_by_importance(&self, limit: usize) -> Vec<&ModuleInfo> {
+        let mut modules: Vec<&ModuleInfo> = self.modules.iter().collect();
+        modules.sort_by(|a, b| {
+            b.importance
+                .partial_cmp(&a.importance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        if limit > 0 {
+            modules.truncate(limit);
+        }
+        modules
+    }
+
+    /// サブディレクトリ配下に絞り込んだインデックスを作成
+    /// 
+    /// ファイル・モジュール・依存関係・エントリーポイント・APIエンドポイント・README・マニフェスト・設定ファイル・パッケージを
+    /// 絞り込み、統計を数え直す。リポジトリのパスは変えない（Wikiのパスやソースへのリンクはリポジトリルートからの相対パスのまま）。
+    /// 重要度はリポジトリ全体で算出した値を引き継ぐ。
+    /// 
+    /// # 引数
+    /// * `path` - ディレクトリ（絶対パスまたはリポジトリルートからの相対パス）
+    /// 
+    /// # 戻り値
+    /// * `Result<Index>` - 絞り込んだインデックス、またはエラー（配下に解析したファイルがない場合）
+    pub fn subset(&self, path: &Path) -> Result<Index> {
+        let relative: PathBuf = path
+            .strip_prefix(&self.repo_path)
+            .unwrap_or(path)
+            .components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .collect();
+        let root = self.repo_path.join(&relative);
+        let contains = |path: &Path| path.starts_with(&root) || path.starts_with(&relative);
+
+        let files: Vec<FileInfo> = self.files.iter().filter(|f| contains(&f.path)).cloned().collect();
+        if files.is_empty() {
+            anyhow::bail!("指定したパスに解析したファイルがありません: {}", relative.display());
+        }
+        let modules: Vec<ModuleInfo> = self.modules.iter().filter(|m| contains(&m.path)).cloned().collect();
+        let mut languages: Vec<String> = files.iter().map(|f| f.language.clone()).collect();
+        languages.sort();
+        languages.dedup();
+        let imports: std::collections::HashSet<&String> = files.iter().flat_map(|f| &f.dependencies).collect();
+
+        Ok(Index {
+            id: self.id.clone(),
+            repo_path: self.repo_path.clone(),
+            commit: self.commit.clone(),
+            languages: languages.clone(),
+            dependencies: self
+                .dependencies
+                .iter()
+                .filter(|(import, _)| imports.contains(import))
+                .map(|(import, resolved)| (import.clone(), resolved.clone()))
+                .collect(),
+            internal_dependencies: self
+                .internal_dependencies
+                .iter()
+                .filter(|(file, _)| contains(file))
+                .map(|(file, deps)| (file.clone(), deps.iter().filter(|d| contains(d)).cloned().collect()))
+                .collect(),
+            entrypoints: self.entrypoints.iter().filter(|e| contains(e)).cloned().collect(),
+            entrypoint_details: self.entrypoint_details.iter().filter(|e| contains(&e.path)).cloned().collect(),
+            endpoints: self.endpoints.iter().filter(|e| contains(&e.file)).cloned().collect(),
+            build_commands: self.build_commands.clone(),
+            vendored: self.vendored.iter().filter(|v| contains(&v.path)).cloned().collect(),
+            readmes: self.readmes.iter().filter(|r| contains(&r.dir)).cloned().collect(),
+            project_docs: self.project_docs.clone(),
+            design_docs: self.design_docs.clone(),
+            manifests: self.manifests.iter().filter(|m| contains(&m.path)).cloned().collect(),
+            external_dependencies: self
+                .external_dependencies
+                .iter()
+                .filter_map(|d| {
+                    let manifests: Vec<PathBuf> = d.manifests.iter().filter(|m| contains(m)).cloned().collect();
+                    (!manifests.is_empty()).then(|| ExternalDependency { manifests, ..d.clone() })
+                })
+                .collect(),
+            config_files: self.config_files.iter().filter(|c| contains(&c.path)).cloned().collect(),
+            packages: self.packages.iter().filter(|p| contains(&p.path)).cloned().collect(),
+            timeline: self.timeline.clone(),
+            redaction: self.redaction.clone(),
+            stats: IndexStats {
+                files: files.len(),
+                languages,
+                modules: modules.len(),
+                skipped: self.stats.skipped.iter().filter(|s| contains(&s.path)).cloned().collect(),
+            },
+            diagnostics: self
+                .diagnostics
+                .iter()
+                .filter(|d| d.path.as_deref().is_none_or(&contains))
+                .cloned()
+                .collect(),
+            files,
+            modules,
+            store: self.store.clone(),
+            // 検索インデックスはリポジトリ全体を対象とするため引き継がない
+            #[cfg(feature = "tantivy-search")]
+            search_index: None,
+            vectors: self.vectors.as_ref().map(|v| std::sync::Arc::new(v.filtered(contains))),
+        })
+    }
+
+    /// 意味検索を実行（埋め込みベクトルのコサイン類似度）
+    /// 
+    /// キーワードが一致しなくても、自然文のクエリに意味の近いコードのチャンクを返す。
+    /// 
+    /// # 引数
+    /// * `query` - 検索クエリ（自然文）
+    /// * `k` - 返す結果の最大数
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<SearchHit>>` - 類似度順のチャンク、またはエラー（ベクトルインデックスがない場合を含む）
+    pub async fn semantic_search(&self, query: &str, k: usize) -> Result<Vec<SearchHit>> {
+        let vectors = self.vectors.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("ベクトルインデックスがありません（index.embeddingsを設定して再解析してください）")
+        })?;
+        let mut hits = vectors.search(self, query, k).await?;
+        self.annotate_modules(&mut hits);
+        Ok(hits)
+    }
+
+    /// 検索を実行
+    /// 
+    /// # 引数
+    /// * `query` - 検索クエリ
+    /// * `k` - 返す結果の最大数
+    /// * `kind` - 検索の種類（text: 本文、symbol: 宣言されたシンボル名と定義位置、path: ファイルパス）
+    /// * `filter` - 対象のファイルの絞り込みとページ送り
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<SearchHit>>` - 検索結果（`filter.offset` 件目からk件）、またはエラー（不明な種類・不正なglobの場合）
+    pub async fn search(&self, query: &str, k: usize, kind: &str, filter: &SearchFilter) -> Result<Vec<SearchHit>> {
+        // 絞り込みは検索の前に行い、上位k件に対象外のファイルが混ざって件数が減らないようにする
+        let allowed = self.filtered_paths(filter)?;
+        let allowed = allowed.as_ref();
+        let limit = filter.offset + k;
+        let mut hits = match kind {
+            "text" => self.search_text(query, limit, allowed)?,
+            "symbol" => xref::search_symbols(self, query, limit, allowed),
+            "path" => self.search_paths(query, limit, allowed),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "不明な検索の種類: {}（{}）",
+                    kind,
+                    SEARCH_KINDS.join("|")
+                ))
+            }
+        };
+        hits.drain(..filter.offset.min(hits.len()));
+        self.annotate_modules(&mut hits);
+        Ok(hits)
+    }
+
+    /// 絞り込みの条件に一致するファイルのパス（`SearchHit.path` と同じ表記）
+    ///
+    /// # 引数
+    /// * `filter` - 絞り込みの条件
+    ///
+    /// # 戻り値
+    /// * `Result<Option<HashSet<String>>>` - パスの集合（条件がない場合はNone）、または不正なglobのエラー
+    fn filtered_paths(&self, filter: &SearchFilter) -> Result<Option<HashSet<String>>> {
+        if filter.language.is_none() && filter.path_glob.is_none() && filter.module.is_none() {
+            return Ok(None);
+        }
+        let glob = filter.path_glob.as_deref().map(|g| PathFilter::glob(g, "path_glob")).transpose()?;
+        let paths = self
+            .files
+            .iter()
+            .filter(|file| filter.language.as_ref().is_none_or(|l| file.language.eq_ignore_ascii_case(l)))
+            .filter(|file| {
+                glob.as_ref().is_none_or(|glob| {
+                    glob.is_included(file.path.strip_prefix(&self.repo_path).unwrap_or(&file.path))
+                })
+            })
+            .filter(|file| {
+                filter.module.as_ref().is_none_or(|module| {
+                    self.module_at(&file.path).is_some_and(|m| module_id::is_within(&m.id, module))
+                })
+            })
+            .map(|file| file.path.to_string_lossy().to_string())
+            .collect();
+        Ok(Some(paths))
+    }
+
+    /// 本文を全文検索（検索インデックスがあればBM25、なければ部分一致）
+    fn search_text(&self, query: &str, k: usize, allowed: Option<&HashSet<String>>) -> Result<Vec<SearchHit>> {
+        #[cfg(feature = "tantivy-search")]
+        if let Some(search_index) = &self.search_index {
+            let mut hits = search_index.search(query, k, allowed)?;
+            self.locate_chunk_matches(query, &mut hits);
+            return Ok(hits);
+        }
+
+        // 検索インデックスがない場合は部分一致で走査（ファイル全体を展開せず1行ずつ読む）
+        let mut hits = Vec::new();
+        let query_lower = query.to_lowercase();
+
+        for file in &self.files {
+            if allowed.is_some_and(|allowed| !allowed.contains(file.path.to_string_lossy().as_ref())) {
+                continue;
+            }
+            let Some(mut lines) = self.content_lines(file) else {
+                continue;
+            };
+            let mut score = 0.0;
+            let mut matches = Vec::new();
+            let mut excerpt = None;
+            let mut line_number = 0;
+            loop {
+                let offset = lines.position();
+                let Some(line) = lines.next() else {
+                    break;
+                };
+                line_number += 1;
+                let line_lower = line.to_lowercase();
+                score += calculate_score(&line_lower, &query_lower);
+                if line_lower.contains(&query_lower) {
+                    let line_matches = text_match::find_matches(&line, &[query], None);
+                    if excerpt.is_none() {
+                        excerpt = Some(text_match::excerpt(&line, line_matches.first(), 100));
+                    }
+                    let remaining = text_match::MAX_MATCHES.saturating_sub(matches.len());
+                    matches.extend(line_matches.into_iter().take(remaining).map(|m| SearchMatch {
+                        line: line_number,
+                        start: m.start + offset,
+                        end: m.end + offset,
+                        ..m
+                    }));
+                }
+            }
+            if let Some(excerpt) = excerpt {
+                hits.push(SearchHit {
+                    path: file.path.to_string_lossy().to_string(),
+                    score,
+                    excerpt,
+                    start_line: None,
+                    end_line: None,
+                    module: None,
+                    matches,
+                    signature: None,
+                });
+            }
+        }
+
+        // スコアでソート
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(k);
+
+        Ok(hits)
+    }
+
+    /// ファイルパス（リポジトリルートからの相対パス）を大文字小文字を無視して部分一致で検索
+    ///
+    /// ファイル名に一致するものをディレクトリ名だけに一致するものより上位にする
+    fn search_paths(&self, query: &str, k: usize, allowed: Option<&HashSet<String>>) -> Vec<SearchHit> {
+        let query_lower = query.to_lowercase().replace('\\', "/");
+        if query_lower.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<SearchHit> = self
+            .files
+            .iter()
+            .filter(|file| allowed.is_none_or(|allowed| allowed.contains(file.path.to_string_lossy().as_ref())))
+            .filter_map(|file| {
+                let relative = file.path.strip_prefix(&self.repo_path).unwrap_or(&file.path);
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                let relative_lower = relative.to_lowercase();
+                let file_name = relative_lower.rsplit('/').next().unwrap_or_default();
+                let score = if file_name == query_lower || Path::new(file_name).file_stem().is_some_and(|s| s == query_lower.as_str()) {
+                    3.0
+                } else if file_name.contains(&query_lower) {
+                    2.0
+                } else if relative_lower.contains(&query_lower) {
+                    1.0
+                } else {
+                    return None;
+                };
+                Some(SearchHit {
+                    path: file.path.to_string_lossy().to_string(),
+                    score,
+                    excerpt: format!("{}（{}、{}行）", relative, file.language, file.metrics.lines),
+                    start_line: None,
+                    end_line: None,
+                    module: None,
+                    matches: Vec::new(),
+                    signature: None,
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.len().cmp(&b.path.len()))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        hits.truncate(k);
+        hits
+    }
+
+    /// 全文検索でヒットしたチャンクに、チャンク内で検索語に一致した箇所を付ける
+    ///
+    /// BM25はトークン単位で一致させるため、クエリを空白で区切った語ごとに探す
+    #[cfg(feature = "tantivy-search")]
+    fn locate_chunk_matches(&self, query: &str, hits: &mut [SearchHit]) {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        for hit in hits {
+            let Some(file) = self.files.iter().find(|f| f.path.to_string_lossy() == hit.path) else {
+                continue;
+            };
+            let Some(content) = self.file_content(file) else {
+                continue;
+            };
+            let lines = hit.start_line.zip(hit.end_line);
+            hit.matches = text_match::find_matches(&content, &terms, lines);
+        }
+    }
+
+    /// 検索結果にヒットしたファイルのモジュールIDを付ける
+    fn annotate_modules(&self, hits: &mut [SearchHit]) {
+        for hit in hits {
+            hit.module = self.module_at(Path::new(&hit.path)).map(|m| m.id.clone());
+        }
+    }
+}
+
+/// `Index::search` の検索の種類
+pub const SEARCH_KINDS: &[&str] = &["text", "symbol", "path"];
+
+/// `Index::search` の絞り込みとページ送り
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchFilter {
+    /// 言語（`FileInfo.language`、例: py）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// リポジトリルートからの相対パスのglob（例: `services/**`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_glob: Option<String>,
+    /// モジュールID、またはその前方（パッケージ名 `web`、ディレクトリ `web:src/api`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// 読み飛ばす結果の数
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// 生成済みドキュメント（Wikiページ・スライドのMarkdown）を検索
+/// 
+/// ソースコードではなく、生成されたMarkdownを対象にする。
+/// mdBookのビルド出力（`site.layout.build-dir`、既定は `book/`）はHTMLのため対象外。
+/// 
+/// # 引数
+/// * `doc_dirs` - 検索対象のディレクトリ（WikiやスライドのOut-dir）
+/// * `build_dir` - 各ディレクトリ内の除外するビルド出力（ディレクトリからの相対パス）
+/// * `query` - 検索クエリ
+/// * `k` - 返す結果の最大数
+/// 
+/// # 戻り値
+/// * `Result<Vec<SearchHit>>` - 検索結果、またはエラー
+pub async fn search_docs(doc_dirs: &[PathBuf], build_dir: &Path, query: &str, k: usize) -> Result<Vec<SearchHit>> {
+    let mut hits = Vec::new();
+    let query_lower = query.to_lowercase();
+
+    for dir in doc_dirs {
+        if !dir.exists() {
+            warn!("ドキュメントディレクトリが存在しません: {:?}", dir);
+            continue;
+        }
+
+        // ビルド出力はディレクトリからの相対パスで判定する（親ディレクトリの名前は問わない）
+        let build_dir = dir.join(build_dir);
+        for entry in WalkDir::new(dir).into_iter().filter_entry(|e| e.path() != build_dir) {
+            let entry = entry?;
+            let path = entry.path();
+            if !entry.file_type().is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+                continue;
+            }
+
+            let content = match std::fs::read_to_string(path) {
+                Ok(c) => c,
+                Err(e) => {
+                    warn!("ドキュメント読み込みエラー: {:?} - {}", path, e);
+                    continue;
+                }
+            };
+
+            let content_lower = content.to_lowercase();
+            if content_lower.contains(&query_lower) {
+                let matches = text_match::find_matches(&content, &[query], None);
+                hits.push(SearchHit {
+                    path: path.to_string_lossy().to_string(),
+                    score: calculate_score(&content_lower, &query_lower),
+                    excerpt: text_match::excerpt(&content, matches.first(), 100),
+                    start_line: None,
+                    end_line: None,
+                    module: None,
+                    matches,
+                    signature: None,
+                });
+            }
+        }
+    }
+
+    hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    hits.truncate(k);
+
+    Ok(hits)
+}
+
+/// スコアを計算
+fn calculate_score(content: &str, query: &str) -> f64 {
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    let mut score = 0.0;
+
+    for word in &query_words {
+        let count = content.matches(word).count();
+        score += count as f64;
+    }
+
+    score / (query_words.len() as f64 + 1.0)
+}
+
+/// 検索ヒット
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub score: f64,
+    pub excerpt: String,
+    /// ヒットしたチャンクの開始行（検索インデックス使用時のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    /// ヒットしたチャンクの終了行（検索インデックス使用時のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    /// ヒットしたファイルのモジュールID（ソースコードのモジュールのみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// 検索語に一致した箇所の行・列・バイト範囲（パスの検索・意味検索では空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<SearchMatch>,
+    /// 宣言のシグネチャ（シンボルの検索のみ）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_js_dependencies() {
+        let content = r#"
+import { foo } from './foo';
+import bar from 'bar';
+const baz = require('baz');
+"#;
+        let deps = parse_source(content, "js").unwrap().imports;
+        assert!(deps.contains(&"./foo".to_string()));
+        assert!(deps.contains(&"bar".to_string()));
+        assert!(deps.contains(&"baz".to_string()));
+    }
+
+    #[test]
+    fn test_extract_py_dependencies() {
         let content = r#"
 import os
 from pathlib import Path
 import json
 "#;
-        let deps = analyzer.extract_py_dependencies(content);
+        let deps = parse_source(content, "py").unwrap().imports;
         assert!(deps.contains(&"os".to_string()));
         assert!(deps.contains(&"pathlib".to_string()));
     }
-}
 
+    #[test]
+    fn test_subset_keeps_files_under_path() {
+        let file = |path: &str, language: &str, deps: &[&str]| FileInfo {
+            path: PathBuf::from("/repo").join(path),
+            language: language.to_string(),
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            is_module: true,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![
+                file("crates/core/src/lib.rs", "rs", &["serde"]),
+                file("crates/core/src/store.rs", "rs", &[]),
+                file("web/app.ts", "ts", &["react"]),
+            ],
+            dependencies: HashMap::from([("serde".to_string(), vec![]), ("react".to_string(), vec![])]),
+            internal_dependencies: BTreeMap::from([(
+                PathBuf::from("/repo/crates/core/src/lib.rs"),
+                vec![PathBuf::from("/repo/crates/core/src/store.rs"), PathBuf::from("/repo/web/app.ts")],
+            )]),
+            packages: vec![
+                PackageInfo { name: "core".to_string(), path: PathBuf::from("crates/core"), ..Default::default() },
+                PackageInfo { name: "web".to_string(), path: PathBuf::from("web"), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        let subset = index.subset(Path::new("./crates/core/")).unwrap();
+        assert_eq!(subset.files.len(), 2);
+        assert_eq!((subset.stats.files, subset.languages.clone()), (2, vec!["rs".to_string()]));
+        assert_eq!(subset.dependencies.keys().collect::<Vec<_>>(), vec!["serde"]);
+        assert_eq!(
+            subset.internal_dependencies[Path::new("/repo/crates/core/src/lib.rs")],
+            vec![PathBuf::from("/repo/crates/core/src/store.rs")]
+        );
+        assert_eq!(subset.packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["core"]);
+        assert_eq!(index.subset(Path::new("/repo/web")).unwrap().files.len(), 1);
+        assert!(index.subset(Path::new("docs")).is_err());
+    }
+
+    #[test]
+    fn test_modules_by_importance_prefers_fan_in() {
+        let module = |name: &str| ModuleInfo {
+            path: PathBuf::from(format!("/repo/src/{}.rs", name)),
+            name: name.to_string(),
+            language: "rs".to_string(),
+            ..Default::default()
+        };
+        let mut index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![FileInfo {
+                path: PathBuf::from("/repo/src/main.rs"),
+                dependencies: vec!["crate::core".to_string()],
+                ..Default::default()
+            }],
+            modules: vec![module("util"), module("core")],
+            ..Default::default()
+        };
+
+        let scores = importance::compute_importance(&index, &HashMap::new());
+        for (module, score) in index.modules.iter_mut().zip(scores) {
+            module.importance = score;
+        }
+
+        let ranked = index.modules_by_importance(1);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].name, "core");
+    }
+
+    #[test]
+    fn test_save_and_lazy_load_index() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-store-{}", uuid::Uuid::new_v4()));
+        let file = |name: &str, content: &str| FileInfo {
+            path: PathBuf::from(format!("/repo/src/{}.rs", name)),
+            name: name.to_string(),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("a", "fn a() {}"), file("b", "fn a() {}"), file("c", "fn c() {}")],
+            ..Default::default()
+        };
+
+        let path = dir.join("index.drsidx");
+        index.save(&path).unwrap();
+        let mut loaded = Index::load(&path).unwrap();
+
+        assert!(loaded.files.iter().all(|f| f.content.is_none()));
+        assert_eq!(loaded.file_content(&loaded.files[2]).as_deref(), Some("fn c() {}"));
+        assert_eq!(loaded.store.as_ref().unwrap().blob_count(), 2);
+
+        loaded.load_contents().unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.files[1].content.as_deref(), Some("fn a() {}"));
+    }
+
+    #[test]
+    fn test_save_excerpts_and_hash_reread_working_tree() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-store-content-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("long.rs");
+        let text: String = (1..=100).map(|i| format!("// line {}\n", i)).collect();
+        std::fs::write(&source, &text).unwrap();
+        let index = Index {
+            repo_path: dir.clone(),
+            files: vec![FileInfo { path: source.clone(), content: Some(text.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        index.save_with(&dir.join("excerpts.drsidx"), "excerpts").unwrap();
+        index.save_with(&dir.join("hash.drsidx"), "hash").unwrap();
+        let excerpts = Index::load(&dir.join("excerpts.drsidx")).unwrap();
+        let hashed = Index::load(&dir.join("hash.drsidx")).unwrap();
+        assert_eq!(hashed.store.as_ref().unwrap().blob_count(), 0);
+
+        // 作業ツリーが保存時のままなら全文を読み直す
+        assert_eq!(excerpts.file_content(&excerpts.files[0]).as_deref(), Some(text.as_str()));
+        assert_eq!(hashed.file_content(&hashed.files[0]).as_deref(), Some(text.as_str()));
+
+        // 変更後は抜粋（先頭40行）のみ、ハッシュのみの場合は読めない
+        std::fs::write(&source, "fn changed() {}\n").unwrap();
+        let excerpt = excerpts.file_content(&excerpts.files[0]).unwrap();
+        let unreadable = hashed.file_content(&hashed.files[0]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(excerpt.lines().count(), 40);
+        assert!(text.starts_with(excerpt.as_ref()));
+        assert!(unreadable.is_none());
+    }
+
+    #[test]
+    fn test_save_stores_redacted_content() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-store-redact-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("keys.ts");
+        let secret = "const api_key = \"s3cr3t-value-123\";\n";
+        std::fs::write(&source, "const name = \"app\";\n").unwrap();
+        let index = Index {
+            repo_path: dir.clone(),
+            files: vec![FileInfo { path: source.clone(), ..Default::default() }],
+            store: Some(std::sync::Arc::new(ContentStore::working_tree([source.clone()]))),
+            ..Default::default()
+        };
+
+        // 解析後に秘密の値を追加したファイルも置き換えて返す
+        std::fs::write(&source, secret).unwrap();
+        assert_eq!(index.file_content(&index.files[0]).as_deref(), Some("const api_key = \"[REDACTED:secret]\";\n"));
+
+        // ブロブにも置き換えた内容を格納する（規則を外して読んでも元の値は残っていない）
+        for content in ["full", "excerpts"] {
+            let path = dir.join(format!("{}.drsidx", content));
+            index.save_with(&path, content).unwrap();
+            std::fs::write(&source, "const name = \"changed\";\n").unwrap();
+            let mut loaded = Index::load(&path).unwrap();
+            let mut config = Config::default();
+            config.security.pii_redaction = false;
+            loaded.redaction = RedactionRules::new(&config).unwrap();
+            let stored = loaded.file_content(&loaded.files[0]).unwrap().into_owned();
+            std::fs::write(&source, secret).unwrap();
+            assert_eq!(stored, "const api_key = \"[REDACTED:secret]\";\n", "{}", content);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stream_content_without_stored_text() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-stream-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("handlers.rs");
+        let text: String = (1..=50).map(|i| format!("fn handler_{}() {{}}\r\n", i)).collect();
+        std::fs::write(&source, &text).unwrap();
+        let index = Index {
+            repo_path: dir.clone(),
+            files: vec![FileInfo { path: source.clone(), content: Some(text.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+        index.save_with(&dir.join("full.drsidx"), "full").unwrap();
+        index.save_with(&dir.join("hash.drsidx"), "hash").unwrap();
+
+        for name in ["full.drsidx", "hash.drsidx"] {
+            let loaded = Index::load(&dir.join(name)).unwrap();
+            let file = &loaded.files[0];
+            assert_eq!(loaded.content_lines(file).unwrap().count(), 50, "{}", name);
+            let windows: Vec<ContentWindow> = loaded.content_windows(file, 100, str::len).unwrap().collect();
+            assert!(windows.len() > 1);
+            assert_eq!(windows.last().unwrap().end_line, 50);
+
+            // 1行ずつ読んでも、一致箇所の行・バイト位置はファイル全体に対する位置
+            let hits = loaded.search("handler_42", 5, "text", &SearchFilter::default()).await.unwrap();
+            assert_eq!(hits.len(), 1);
+            let start = text.find("handler_42").unwrap();
+            assert_eq!((hits[0].matches[0].line, hits[0].matches[0].start), (42, start), "{}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_detect_language_overrides_and_shebang() {
+        let analyzer = Analyzer::new(Config::default());
+        let dir = std::env::temp_dir().join(format!("deeprepo-lang-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("deploy");
+        std::fs::write(&script, "#!/usr/bin/env python3\nprint('hi')\n").unwrap();
+
+        let mut config = Config::default();
+        assert_eq!(analyzer.detect_language(&script, &config), Some("py".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("App.vue"), &config), Some("js".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("util.h"), &config), Some("c".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("parser.hpp"), &config), Some("cpp".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("Program.cs"), &config), Some("cs".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("Gemfile"), &config), Some("rb".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("index.php"), &config), Some("php".to_string()));
+        assert_eq!(analyzer.detect_language(Path::new("build.gradle.kts"), &config), Some("kt".to_string()));
+
+        config.analysis.language_overrides.insert("vue".to_string(), "ts".to_string());
+        assert_eq!(analyzer.detect_language(Path::new("App.vue"), &config), Some("ts".to_string()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_search_docs_skips_book_output() {
+        // 親ディレクトリが `book` でも、除外するのは出力先の中のビルド出力のみ
+        let root = std::env::temp_dir().join(format!("deeprepo-docs-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("book/wiki");
+        for sub in ["src", "book", "site"] {
+            std::fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        std::fs::write(dir.join("src").join("architecture.md"), "# アーキテクチャ\n\nモジュールグラフの説明です。").unwrap();
+        std::fs::write(dir.join("book").join("architecture.md"), "モジュールグラフ").unwrap();
+        std::fs::write(dir.join("site").join("architecture.md"), "モジュールグラフ").unwrap();
+
+        let hits = search_docs(std::slice::from_ref(&dir), Path::new("book"), "モジュールグラフ", 10).await.unwrap();
+        let custom = search_docs(std::slice::from_ref(&dir), Path::new("site"), "モジュールグラフ", 10).await.unwrap();
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(hits.len(), 2, "{:?}", hits);
+        assert!(hits.iter().all(|h| !h.path.contains("wiki/book/")));
+        assert!(hits.iter().any(|h| h.path.ends_with("src/architecture.md") && h.excerpt.contains("モジュールグラフ")));
+        assert_eq!(custom.len(), 2);
+        assert!(custom.iter().all(|h| !h.path.contains("wiki/site/")));
+    }
+
+    #[tokio::test]
+    async fn test_search_by_path_kind() {
+        let file = |path: &str| FileInfo { path: PathBuf::from(path), language: "rs".to_string(), ..Default::default() };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("/repo/crates/wiki/src/lib.rs"), file("/repo/src/wiki.rs"), file("/repo/src/main.rs")],
+            ..Default::default()
+        };
+
+        let hits = index.search("wiki", 10, "path", &SearchFilter::default()).await.unwrap();
+        let paths: Vec<&str> = hits.iter().map(|h| h.path.as_str()).collect();
+        assert_eq!(paths, vec!["/repo/src/wiki.rs", "/repo/crates/wiki/src/lib.rs"]);
+        assert!(hits[0].excerpt.starts_with("src/wiki.rs"));
+        assert!(index.search("wiki", 10, "regex", &SearchFilter::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_search_text_reports_match_positions() {
+        let content = "/// 設定ファイルを読み込む\nfn load() {}\n\n/// 読み込んだ設定を検証する\nfn validate() {}\n";
+        let mut index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![FileInfo {
+                path: PathBuf::from("/repo/src/config.rs"),
+                language: "rs".to_string(),
+                content: Some(content.to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let hits = index.search("設定", 10, "text", &SearchFilter::default()).await.unwrap();
+        let positions: Vec<(usize, usize)> = hits[0].matches.iter().map(|m| (m.line, m.column)).collect();
+        assert_eq!(positions, vec![(1, 5), (4, 10)]);
+        assert_eq!(&content[hits[0].matches[0].start..hits[0].matches[0].end], "設定");
+        assert!(hits[0].excerpt.contains("設定"));
+
+        #[cfg(feature = "tantivy-search")]
+        {
+            index.search_index = Some(std::sync::Arc::new(SearchIndex::build(&index, 512, 0, None).unwrap()));
+            let hits = index.search("validate", 10, "text", &SearchFilter::default()).await.unwrap();
+            assert_eq!(hits[0].matches.len(), 1);
+            assert_eq!((hits[0].matches[0].line, hits[0].matches[0].column), (5, 4));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_and_offset() {
+        let file = |path: &str, language: &str| FileInfo { path: PathBuf::from(path), language: language.to_string(), ..Default::default() };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![
+                file("/repo/services/graph.py", "py"),
+                file("/repo/services/graph_util.py", "py"),
+                file("/repo/web/graph.ts", "ts"),
+            ],
+            ..Default::default()
+        };
+
+        let filter = SearchFilter { language: Some("PY".to_string()), ..Default::default() };
+        let hits = index.search("graph", 10, "path", &filter).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.path.ends_with(".py")));
+
+        let filter = SearchFilter { path_glob: Some("web/**".to_string()), ..Default::default() };
+        let hits = index.search("graph", 10, "path", &filter).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "/repo/web/graph.ts");
+
+        let all = index.search("graph", 10, "path", &SearchFilter::default()).await.unwrap();
+        let page = index.search("graph", 1, "path", &SearchFilter { offset: 1, ..Default::default() }).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].path, all[1].path);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repo_uses_registered_classifier() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-classifier-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("flows")).unwrap();
+        std::fs::write(dir.join("flows").join("order.flow"), "workflow order {\n  step pay\n}\n").unwrap();
+        std::fs::write(dir.join("flows").join("README.txt"), "notes\n").unwrap();
+
+        let config = Config::default();
+        let mut analyzer = Analyzer::new(config.clone());
+        analyzer.register_classifier(|path, head| {
+            (path.extension().is_some_and(|e| e == "flow") && head.starts_with("workflow"))
+                .then(|| Classification { language: "order-flow".to_string(), is_module: true })
+        });
+        let index = analyzer.analyze_repo(&dir, &config).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(index.files.len(), 1);
+        assert_eq!(index.files[0].language, "order-flow");
+        assert_eq!(index.languages, vec!["order-flow".to_string()]);
+        assert_eq!(index.modules.len(), 1);
+        assert_eq!(index.modules[0].language, "order-flow");
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repo_skips_vendored_dirs() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-vendored-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules").join("left-pad")).unwrap();
+        std::fs::write(dir.join("src").join("index.js"), "export const a = 1;\n").unwrap();
+        std::fs::write(dir.join("node_modules").join("left-pad").join("index.js"), "module.exports = 1;\n").unwrap();
+
+        let config = Config::default();
+        let index = Analyzer::new(config.clone()).analyze_repo(&dir, &config).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(index.files.len(), 1);
+        assert_eq!(index.vendored.len(), 1);
+        assert_eq!(index.vendored[0].path, PathBuf::from("node_modules"));
+        assert_eq!(index.vendored[0].packages, vec!["left-pad".to_string()]);
+    }
+    #[tokio::test]
+    async fn test_analyze_repo_reads_contents_lazily() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-lazy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        for i in 0..5 {
+            std::fs::write(dir.join("src").join(format!("m{}.js", i)), format!("export const v{} = {};\n", i, i)).unwrap();
+        }
+
+        let mut config = Config::default();
+        config.analysis.concurrency = 2;
+        let index = Analyzer::new(config.clone()).analyze_repo(&dir, &config).await.unwrap();
+
+        let walked = walk_repo(&dir, &PathFilter::new(&[], &[]).unwrap(), true, 512).unwrap();
+        let paths: Vec<&PathBuf> = index.files.iter().map(|f| &f.path).collect();
+        assert_eq!(paths, walked.files.iter().collect::<Vec<_>>());
+        assert!(index.files.iter().all(|f| f.content.is_none()));
+        let m3 = index.files.iter().find(|f| f.name == "m3").unwrap();
+        assert_eq!(index.file_content(m3).as_deref(), Some("export const v3 = 3;\n"));
+        assert_eq!(m3.size, 21);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repo_detects_entrypoints_and_endpoints() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-entry-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("app")).unwrap();
+        std::fs::write(dir.join("main.py"), "from app.api import app\n").unwrap();
+        std::fs::write(dir.join("app/api.py"), "from fastapi import FastAPI\n\napp = FastAPI()\n\n@app.get(\"/health\")\ndef health():\n    pass\n").unwrap();
+        std::fs::write(dir.join("Dockerfile"), "FROM python:3.12\nCMD [\"uvicorn\", \"app.api:app\"]\n").unwrap();
+        std::fs::write(dir.join("schema.graphql"), "type Query {\n  me: User\n}\n").unwrap();
+
+        let config = Config::default();
+        let index = Analyzer::new(config.clone()).analyze_repo(&dir, &config).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let kinds: Vec<(&str, &str)> =
+            index.entrypoint_details.iter().map(|e| (e.kind.as_str(), e.detail.as_str())).collect();
+        assert_eq!(kinds, vec![("main", ""), ("fastapi", "app = FastAPI()"), ("docker", "uvicorn app.api:app")]);
+        assert_eq!(index.entrypoints, vec![dir.join("main.py"), dir.join("app/api.py")]);
+
+        let endpoints: Vec<(&str, &str, Option<&str>, usize)> =
+            index.endpoints.iter().map(|e| (e.method.as_str(), e.path.as_str(), e.handler.as_deref(), e.line)).collect();
+        assert_eq!(endpoints, vec![("GET", "/health", Some("health"), 5), ("QUERY", "me", None, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repo_records_build_conditions() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-conditions-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "mod core;\n#[cfg(feature = \"search\")]\nmod search;\n").unwrap();
+        std::fs::write(dir.join("src/core.rs"), "pub fn run() {}\n").unwrap();
+        std::fs::write(dir.join("src/search.rs"), "pub fn find() {}\n").unwrap();
+
+        let config = Config::default();
+        let index = Analyzer::new(config.clone()).analyze_repo(&dir, &config).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let conditions = |name: &str| {
+            let file = index.files.iter().find(|f| f.path.ends_with(name)).unwrap();
+            file.build_conditions.iter().map(|c| (c.label(), c.declared_in.clone(), c.line)).collect::<Vec<_>>()
+        };
+        assert_eq!(conditions("search.rs"), vec![("feature `search` 有効時のみ".to_string(), PathBuf::from("src/lib.rs"), 2)]);
+        assert!(conditions("core.rs").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_analyze_repo_transcodes_legacy_encodings_and_skips_binaries() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-encoding-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = "// 受注を登録する\nexport function register() {}\n";
+        std::fs::write(dir.join("order.ts"), encoding_rs::SHIFT_JIS.encode(source).0).unwrap();
+        std::fs::write(dir.join("logo.ts"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        let config = Config::default();
+        let index = Analyzer::new(config.clone()).analyze_repo(&dir, &config).await.unwrap();
+        let content = index.files.first().and_then(|f| index.file_content(f)).map(|c| c.into_owned());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(index.files.len(), 1);
+        assert_eq!(index.files[0].encoding.as_deref(), Some("Shift_JIS"));
+        assert_eq!(content.as_deref(), Some(source));
+        let skipped: Vec<(&Path, &str)> =
+            index.stats.skipped.iter().map(|s| (s.path.as_path(), s.reason.as_str())).collect();
+        assert_eq!(skipped, vec![(dir.join("logo.ts").as_path(), "binary")]);
+        assert_eq!(index.diagnostics.len(), 1);
+    }
+}
@@ -0,0 +1,556 @@
+/*!
+ * 外部依存のバージョンとライセンスの解決
+ *
+ * マニフェストに宣言された外部依存（内部依存を除く）に、lockfileで解決したバージョンと
+ * ライセンスを付ける
+ * - バージョン: Cargo.lock・package-lock.json・poetry.lock・uv.lock（go.modは宣言がそのまま解決済みのバージョン）
+ * - ライセンス（見つかった最初のもの）:
+ *   - npm: package-lock.jsonの `license`、`node_modules/<名前>/package.json`
+ *   - Cargo: Cargoのレジストリキャッシュ（`$CARGO_HOME/registry/src`）のCargo.toml
+ *   - Python: リポジトリ内の仮想環境（`.venv`・`venv`）のMETADATA
+ *   - Go: モジュールキャッシュ（`$GOMODCACHE`）のLICENSEファイル
+ *
+ * 主な仕様:
+ * - 同じエコシステム・名前の依存は1件にまとめ、宣言したマニフェストをすべて記録する
+ * - 依存の種類は normal・build・dev の順に優先する（どこかで実行時に使われていれば normal）
+ * - lockfileに同じ名前の複数のバージョンがある場合はすべて列挙する
+ * - GPL・AGPL・LGPL・MPL・EPL・CDDL・EUPL・CC-BY-SAなどをコピーレフトとする
+ *   （`OR` で選べるライセンスは、すべての選択肢がコピーレフトの場合のみ）
+ * - LICENSEファイルは本文の定型句からSPDX識別子を推定する
+ *
+ * 制限事項:
+ * - lockfileとマニフェストの対応は見ず、エコシステムごとにすべてのlockfileをまとめて引く
+ * - yarn.lock・pnpm-lock.yamlは読まない（yarn・pnpmのプロジェクトのバージョンは未解決のまま）
+ * - 推移的な依存（マニフェストに宣言されていない依存）は対象外
+ * - ライセンス式の括弧は解釈しない
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Diagnostics, Manifest};
+
+/// バージョンの解決に読むlockfileのファイル名
+pub(crate) const LOCK_FILES: &[&str] = &["Cargo.lock", "package-lock.json", "poetry.lock", "uv.lock"];
+
+/// コピーレフトと判定するライセンス名の一部（大文字）
+const COPYLEFT_MARKERS: &[&str] = &[
+    "GPL", "GNU GENERAL PUBLIC", "GNU LESSER", "GNU AFFERO", "MPL", "MOZILLA PUBLIC", "EPL", "ECLIPSE PUBLIC",
+    "CDDL", "EUPL", "CC-BY-SA", "OSL-", "SLEEPYCAT",
+];
+
+/// 外部依存（マニフェストの宣言とlockfileの解決結果）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExternalDependency {
+    pub name: String,
+    /// エコシステム（cargo|npm|python|go）
+    pub ecosystem: String,
+    /// 要求バージョン（最初に見つかった宣言）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested: Option<String>,
+    /// lockfileで解決したバージョン（複数ある場合は `, ` 区切り、lockfileがない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// ライセンス（SPDX式またはパッケージに記載された名前、検出できない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// コピーレフトのライセンスか
+    #[serde(default)]
+    pub copyleft: bool,
+    /// 依存の種類（normal|dev|build）
+    pub kind: String,
+    /// 宣言したマニフェストのパス（リポジトリルートからの相対パス）
+    pub manifests: Vec<PathBuf>,
+}
+
+/// リポジトリの外にあるライセンスの参照先
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LicenseSources {
+    /// Cargoのレジストリの展開先（`$CARGO_HOME/registry/src`）
+    pub cargo_registry: Option<PathBuf>,
+    /// Goのモジュールキャッシュ（`$GOMODCACHE`）
+    pub go_mod_cache: Option<PathBuf>,
+}
+
+impl LicenseSources {
+    /// 環境変数とホームディレクトリから参照先を決定
+    pub fn from_env() -> Self {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let cargo_home = std::env::var_os("CARGO_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join(".cargo")));
+        let go_path = std::env::var_os("GOPATH")
+            .map(PathBuf::from)
+            .or_else(|| home.as_ref().map(|h| h.join("go")));
+        let go_mod_cache = std::env::var_os("GOMODCACHE")
+            .map(PathBuf::from)
+            .or_else(|| go_path.map(|p| p.join("pkg").join("mod")));
+        Self {
+            cargo_registry: cargo_home.map(|h| h.join("registry").join("src")),
+            go_mod_cache,
+        }
+    }
+}
+
+/// lockfileから読んだパッケージ
+#[derive(Debug, Default)]
+struct LockedPackage {
+    versions: BTreeSet<String>,
+    license: Option<String>,
+}
+
+/// エコシステムとパッケージ名ごとのlockfileの内容
+type LockedPackages = BTreeMap<(String, String), LockedPackage>;
+
+/// 外部依存のバージョンとライセンスを解決
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `manifests` - 解析済みのマニフェスト（パスはリポジトリルートからの相対パス）
+/// * `lockfiles` - lockfileのパス（`LOCK_FILES` のいずれかのファイル名）
+/// * `sources` - リポジトリの外にあるライセンスの参照先
+/// * `diagnostics` - 解析できなかったlockfileの記録先
+///
+/// # 戻り値
+/// * `Vec<ExternalDependency>` - 外部依存（エコシステム・名前順）
+pub(crate) fn resolve_dependencies(
+    repo_path: &Path,
+    manifests: &[Manifest],
+    lockfiles: &[PathBuf],
+    sources: &LicenseSources,
+    diagnostics: &Diagnostics,
+) -> Vec<ExternalDependency> {
+    let mut locked = LockedPackages::new();
+    for path in lockfiles {
+        let parsed = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| parse_lockfile(path.file_name()?.to_str()?, &content, &mut locked));
+        if parsed.is_none() {
+            diagnostics.warn_at("analyzer", path, "lockfileを解析できませんでした");
+        }
+    }
+
+    let mut dependencies: BTreeMap<(String, String), ExternalDependency> = BTreeMap::new();
+    for manifest in manifests {
+        for declared in manifest.dependencies.iter().filter(|d| !d.internal) {
+            let key = (manifest.ecosystem.clone(), package_key(&manifest.ecosystem, &declared.name));
+            let dependency = dependencies.entry(key).or_insert_with(|| ExternalDependency {
+                name: declared.name.clone(),
+                ecosystem: manifest.ecosystem.clone(),
+                requested: declared.version.clone(),
+                kind: declared.kind.clone(),
+                ..Default::default()
+            });
+            if kind_rank(&declared.kind) < kind_rank(&dependency.kind) {
+                dependency.kind = declared.kind.clone();
+            }
+            if !dependency.manifests.contains(&manifest.path) {
+                dependency.manifests.push(manifest.path.clone());
+            }
+        }
+    }
+
+    dependencies
+        .into_iter()
+        .map(|(key, mut dependency)| {
+            let lock = locked.get(&key);
+            dependency.version = match dependency.ecosystem.as_str() {
+                "go" => dependency.requested.clone(),
+                _ => lock.filter(|l| !l.versions.is_empty()).map(|l| {
+                    l.versions.iter().cloned().collect::<Vec<_>>().join(", ")
+                }),
+            };
+            let versions: Vec<&str> = lock.into_iter().flat_map(|l| l.versions.iter().map(String::as_str)).collect();
+            dependency.license = lock
+                .and_then(|l| l.license.clone())
+                .or_else(|| find_license(repo_path, &dependency, &versions, sources));
+            dependency.copyleft = dependency.license.as_deref().is_some_and(is_copyleft);
+            dependency
+        })
+        .collect()
+}
+
+/// lockfile1件を読み込み
+///
+/// # 戻り値
+/// * `Option<()>` - 形式が不正な場合はNone
+fn parse_lockfile(file_name: &str, content: &str, locked: &mut LockedPackages) -> Option<()> {
+    match file_name {
+        "Cargo.lock" => parse_toml_packages(content, "cargo", locked),
+        "poetry.lock" | "uv.lock" => parse_toml_packages(content, "python", locked),
+        "package-lock.json" => parse_package_lock(content, locked),
+        _ => None,
+    }
+}
+
+/// `[[package]]` の `name`・`version` を列挙するlockfile（Cargo.lock・poetry.lock・uv.lock）
+fn parse_toml_packages(content: &str, ecosystem: &str, locked: &mut LockedPackages) -> Option<()> {
+    let toml: toml::Table = content.parse().ok()?;
+    let packages = toml.get("package").and_then(|p| p.as_array()).into_iter().flatten();
+    for package in packages {
+        let field = |key: &str| package.get(key).and_then(|v| v.as_str());
+        let (Some(name), Some(version)) = (field("name"), field("version")) else {
+            continue;
+        };
+        lock_entry(locked, ecosystem, name).versions.insert(version.to_string());
+    }
+    Some(())
+}
+
+/// package-lock.json（lockfileVersion 2・3の `packages`、1の `dependencies`）
+fn parse_package_lock(content: &str, locked: &mut LockedPackages) -> Option<()> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    if let Some(packages) = json.get("packages").and_then(|p| p.as_object()) {
+        for (key, package) in packages {
+            // 直下の `node_modules/<名前>` のみ（入れ子の依存は対象外）
+            let Some(name) = key.strip_prefix("node_modules/").filter(|n| !n.contains("/node_modules/")) else {
+                continue;
+            };
+            let entry = lock_entry(locked, "npm", name);
+            if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+                entry.versions.insert(version.to_string());
+            }
+            if entry.license.is_none() {
+                entry.license = package.get("license").and_then(license_field);
+            }
+        }
+    } else if let Some(dependencies) = json.get("dependencies").and_then(|d| d.as_object()) {
+        for (name, package) in dependencies {
+            if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+                lock_entry(locked, "npm", name).versions.insert(version.to_string());
+            }
+        }
+    }
+    Some(())
+}
+
+fn lock_entry<'a>(locked: &'a mut LockedPackages, ecosystem: &str, name: &str) -> &'a mut LockedPackage {
+    locked.entry((ecosystem.to_string(), package_key(ecosystem, name))).or_default()
+}
+
+/// 照合用のパッケージ名（Pythonは PEP 503 の正規化: 小文字・`_` `.` を `-` に）
+fn package_key(ecosystem: &str, name: &str) -> String {
+    match ecosystem {
+        "python" => name.to_lowercase().replace(['_', '.'], "-"),
+        _ => name.to_string(),
+    }
+}
+
+/// 依存の種類の優先順位（小さいほど優先）
+fn kind_rank(kind: &str) -> u8 {
+    match kind {
+        "normal" => 0,
+        "build" => 1,
+        _ => 2,
+    }
+}
+
+/// package.jsonの `license`（文字列、または旧形式の `{ "type": ... }`）
+fn license_field(value: &serde_json::Value) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value.get("type").and_then(|t| t.as_str()))
+        .map(str::to_string)
+}
+
+/// パッケージの配布物からライセンスを探す
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `dependency` - 外部依存
+/// * `versions` - lockfileで解決したバージョン
+/// * `sources` - リポジトリの外にあるライセンスの参照先
+///
+/// # 戻り値
+/// * `Option<String>` - ライセンス（見つからない場合はNone）
+fn find_license(
+    repo_path: &Path,
+    dependency: &ExternalDependency,
+    versions: &[&str],
+    sources: &LicenseSources,
+) -> Option<String> {
+    // マニフェストのディレクトリ（npm・Pythonの依存はその配下に展開される）
+    let mut dirs: Vec<PathBuf> = dependency
+        .manifests
+        .iter()
+        .map(|m| repo_path.join(m.parent().unwrap_or(Path::new(""))))
+        .collect();
+    dirs.push(repo_path.to_path_buf());
+
+    match dependency.ecosystem.as_str() {
+        "npm" => dirs.iter().find_map(|dir| {
+            let package = std::fs::read_to_string(dir.join("node_modules").join(&dependency.name).join("package.json")).ok()?;
+            let json: serde_json::Value = serde_json::from_str(&package).ok()?;
+            json.get("license").and_then(license_field)
+        }),
+        "cargo" => {
+            let registries: Vec<PathBuf> = std::fs::read_dir(sources.cargo_registry.as_ref()?)
+                .ok()?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .collect();
+            versions.iter().find_map(|version| {
+                registries
+                    .iter()
+                    .find_map(|registry| cargo_license(&registry.join(format!("{}-{}", dependency.name, version))))
+            })
+        }
+        "python" => dirs.iter().find_map(|dir| python_license(dir, &dependency.name)),
+        "go" => {
+            let module_dir = sources
+                .go_mod_cache
+                .as_ref()?
+                .join(format!("{}@{}", escape_go_module(&dependency.name), dependency.version.as_deref()?));
+            license_file_in(&module_dir)
+        }
+        _ => None,
+    }
+}
+
+/// 展開済みのクレートのCargo.tomlの `license`（`license-file` の場合は本文から推定）
+fn cargo_license(crate_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(crate_dir.join("Cargo.toml")).ok()?;
+    let toml: toml::Table = content.parse().ok()?;
+    let package = toml.get("package")?;
+    if let Some(license) = package.get("license").and_then(|l| l.as_str()) {
+        return Some(license.to_string());
+    }
+    let file = package.get("license-file").and_then(|f| f.as_str())?;
+    classify_license_text(&std::fs::read_to_string(crate_dir.join(file)).ok()?)
+}
+
+/// 仮想環境にインストールされたパッケージのMETADATAからライセンスを取得
+fn python_license(dir: &Path, name: &str) -> Option<String> {
+    let key = package_key("python", name);
+    ["venv", ".venv"].iter().find_map(|venv| {
+        let lib = std::fs::read_dir(dir.join(venv).join("lib")).ok()?;
+        lib.filter_map(|e| e.ok()).find_map(|python| {
+            let site_packages = std::fs::read_dir(python.path().join("site-packages")).ok()?;
+            site_packages.filter_map(|e| e.ok()).find_map(|entry| {
+                let file_name = entry.file_name();
+                let dist_info = file_name.to_str()?.strip_suffix(".dist-info")?;
+                let (dist_name, _version) = dist_info.split_once('-')?;
+                if package_key("python", dist_name) != key {
+                    return None;
+                }
+                metadata_license(&std::fs::read_to_string(entry.path().join("METADATA")).ok()?)
+            })
+        })
+    })
+}
+
+/// METADATAのライセンス（`License-Expression`、分類子、`License` の順）
+fn metadata_license(metadata: &str) -> Option<String> {
+    // ヘッダーは最初の空行まで
+    let headers: Vec<(&str, &str)> = metadata
+        .lines()
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+    let header = |name: &str| headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| *v);
+
+    if let Some(expression) = header("License-Expression") {
+        return Some(expression.to_string());
+    }
+    let classifiers: Vec<&str> = headers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("Classifier"))
+        .filter_map(|(_, v)| v.strip_prefix("License ::"))
+        .map(|v| v.rsplit("::").next().unwrap_or(v).trim())
+        .collect();
+    if !classifiers.is_empty() {
+        return Some(classifiers.join(" OR "));
+    }
+    // 本文を丸ごと書いたものは対象外
+    header("License").filter(|l| !l.is_empty() && l.len() <= 64 && *l != "UNKNOWN").map(str::to_string)
+}
+
+/// Goのモジュールキャッシュのパス（大文字は `!` と小文字に置き換える）
+fn escape_go_module(module: &str) -> String {
+    let mut escaped = String::with_capacity(module.len());
+    for c in module.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// ディレクトリ直下のLICENSE・COPYINGファイルからライセンスを推定
+fn license_file_in(dir: &Path) -> Option<String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| {
+                let upper = n.to_ascii_uppercase();
+                upper.starts_with("LICENSE") || upper.starts_with("LICENCE") || upper.starts_with("COPYING")
+            })
+        })
+        .collect();
+    files.sort();
+    files
+        .iter()
+        .find_map(|path| classify_license_text(&std::fs::read_to_string(path).ok()?))
+}
+
+/// ライセンス本文の定型句からSPDX識別子を推定
+///
+/// # 引数
+/// * `text` - LICENSEファイルの内容
+///
+/// # 戻り値
+/// * `Option<String>` - SPDX識別子（判定できない場合はNone）
+pub(crate) fn classify_license_text(text: &str) -> Option<String> {
+    let upper = text.to_uppercase();
+    let version = |v2: &'static str, v3: &'static str| if upper.contains("VERSION 3") { v3 } else { v2 };
+    let license = if upper.contains("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        "AGPL-3.0"
+    } else if upper.contains("GNU LESSER GENERAL PUBLIC LICENSE") {
+        version("LGPL-2.1", "LGPL-3.0")
+    } else if upper.contains("GNU GENERAL PUBLIC LICENSE") {
+        version("GPL-2.0", "GPL-3.0")
+    } else if upper.contains("MOZILLA PUBLIC LICENSE") {
+        "MPL-2.0"
+    } else if upper.contains("APACHE LICENSE") {
+        "Apache-2.0"
+    } else if upper.contains("PERMISSION IS HEREBY GRANTED, FREE OF CHARGE") {
+        "MIT"
+    } else if upper.contains("REDISTRIBUTION AND USE IN SOURCE AND BINARY FORMS") {
+        if upper.contains("NEITHER THE NAME") { "BSD-3-Clause" } else { "BSD-2-Clause" }
+    } else if upper.contains("PERMISSION TO USE, COPY, MODIFY, AND/OR DISTRIBUTE") {
+        "ISC"
+    } else if upper.contains("THIS IS FREE AND UNENCUMBERED SOFTWARE") {
+        "Unlicense"
+    } else {
+        return None;
+    };
+    Some(license.to_string())
+}
+
+/// コピーレフトのライセンスか
+///
+/// `OR`（または旧来のCargoの `/`）で選べる場合は、すべての選択肢がコピーレフトの場合のみtrue
+///
+/// # 引数
+/// * `license` - SPDX式またはライセンス名
+pub fn is_copyleft(license: &str) -> bool {
+    let upper = license.to_uppercase().replace(['(', ')'], "");
+    let alternatives: Vec<&str> = upper.split(" OR ").flat_map(|a| a.split('/')).map(str::trim).filter(|a| !a.is_empty()).collect();
+    !alternatives.is_empty()
+        && alternatives.iter().all(|alternative| COPYLEFT_MARKERS.iter().any(|m| alternative.contains(m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeclaredDependency;
+
+    fn manifest(path: &str, ecosystem: &str, dependencies: &[(&str, &str, &str)]) -> Manifest {
+        Manifest {
+            path: PathBuf::from(path),
+            ecosystem: ecosystem.to_string(),
+            dependencies: dependencies
+                .iter()
+                .map(|(name, version, kind)| DeclaredDependency {
+                    name: name.to_string(),
+                    version: Some(version.to_string()),
+                    kind: kind.to_string(),
+                    internal: false,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_dependencies_from_lockfiles() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-licenses-{}", uuid::Uuid::new_v4()));
+        let registry = dir.join("cargo-registry");
+        let serde_dir = registry.join("index.crates.io-6f17d22bba15001f").join("serde-1.0.200");
+        std::fs::create_dir_all(&serde_dir).unwrap();
+        std::fs::create_dir_all(dir.join("web/node_modules/left-pad")).unwrap();
+        std::fs::create_dir_all(dir.join("go-cache/github.com/!burnt!sushi/toml@v1.3.2")).unwrap();
+
+        std::fs::write(
+            dir.join("Cargo.lock"),
+            "version = 3\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.200\"\n\n[[package]]\nname = \"readline\"\nversion = \"0.4.1\"\n",
+        )
+        .unwrap();
+        std::fs::write(serde_dir.join("Cargo.toml"), "[package]\nname = \"serde\"\nlicense = \"MIT OR Apache-2.0\"\n").unwrap();
+        std::fs::write(
+            dir.join("web/package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {
+                "": {"name": "web"},
+                "node_modules/left-pad": {"version": "1.3.0"},
+                "node_modules/gpl-lib": {"version": "2.0.1", "license": "GPL-3.0-only"},
+                "node_modules/gpl-lib/node_modules/left-pad": {"version": "0.0.1"}
+            }}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("web/node_modules/left-pad/package.json"), r#"{"license": "WTFPL"}"#).unwrap();
+        std::fs::write(
+            dir.join("go-cache/github.com/!burnt!sushi/toml@v1.3.2/COPYING"),
+            "The MIT License (MIT)\n\nPermission is hereby granted, free of charge, to any person",
+        )
+        .unwrap();
+
+        let manifests = vec![
+            manifest("Cargo.toml", "cargo", &[("serde", "1", "normal"), ("readline", "0.4", "dev")]),
+            manifest("tools/Cargo.toml", "cargo", &[("readline", "0.4", "build")]),
+            manifest("web/package.json", "npm", &[("left-pad", "^1.3.0", "normal"), ("gpl-lib", "^2.0.0", "dev")]),
+            manifest("go.mod", "go", &[("github.com/BurntSushi/toml", "v1.3.2", "normal")]),
+        ];
+        let sources = LicenseSources { cargo_registry: Some(registry), go_mod_cache: Some(dir.join("go-cache")) };
+        let lockfiles = vec![dir.join("Cargo.lock"), dir.join("web/package-lock.json")];
+        let diagnostics = Diagnostics::new();
+        let resolved = resolve_dependencies(&dir, &manifests, &lockfiles, &sources, &diagnostics);
+
+        let summary: Vec<String> = resolved
+            .iter()
+            .map(|d| format!("{} {:?} {:?} {} {}", d.name, d.version, d.license, d.copyleft, d.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "readline Some(\"0.4.1\") None false build",
+                "serde Some(\"1.0.200\") Some(\"MIT OR Apache-2.0\") false normal",
+                "github.com/BurntSushi/toml Some(\"v1.3.2\") Some(\"MIT\") false normal",
+                "gpl-lib Some(\"2.0.1\") Some(\"GPL-3.0-only\") true dev",
+                "left-pad Some(\"1.3.0\") Some(\"WTFPL\") false normal",
+            ]
+        );
+        assert_eq!(resolved[0].manifests, vec![PathBuf::from("Cargo.toml"), PathBuf::from("tools/Cargo.toml")]);
+        assert!(diagnostics.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_is_copyleft() {
+        assert!(is_copyleft("GPL-2.0-or-later"));
+        assert!(is_copyleft("LGPL-2.1 OR MPL-2.0"));
+        assert!(is_copyleft("GNU General Public License v3 (GPLv3)"));
+        assert!(!is_copyleft("MIT OR GPL-3.0"));
+        assert!(!is_copyleft("MIT/Apache-2.0"));
+        assert!(!is_copyleft("BSD-3-Clause"));
+        assert!(!is_copyleft(""));
+    }
+
+    #[test]
+    fn test_metadata_license() {
+        let metadata = "Metadata-Version: 2.1\nName: chardet\nLicense: LGPL\nClassifier: License :: OSI Approved :: GNU Lesser General Public License v2 or later (LGPLv2+)\n\nbody";
+        assert_eq!(
+            metadata_license(metadata).as_deref(),
+            Some("GNU Lesser General Public License v2 or later (LGPLv2+)")
+        );
+        assert_eq!(metadata_license("Name: x\nLicense-Expression: Apache-2.0\n").as_deref(), Some("Apache-2.0"));
+    }
+}
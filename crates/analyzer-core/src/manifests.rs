@@ -0,0 +1,499 @@
+/*!
+ * パッケージマニフェストの解析
+ *
+ * マニフェストに宣言された依存関係をバージョン付きで取得する
+ * - Cargo（Cargo.toml）
+ * - npm（package.json）
+ * - Python（pyproject.toml, requirements.txt）
+ * - Go（go.mod）
+ * - ワークスペースの定義（Cargoの `[workspace]`・package.jsonの `workspaces`・pnpm-workspace.yaml・go.work）
+ *
+ * 主な仕様:
+ * - 依存の種類は normal（実行時）・dev（開発・テスト）・build（ビルドスクリプト）
+ * - リポジトリ内の別のマニフェストのパッケージ（ワークスペースのメンバー）への依存は内部依存とする
+ * - パス指定（Cargoの `path`・npmの `workspace:`/`file:`・go.modのローカルへの `replace`）も内部依存とする
+ * - Cargoの `workspace = true` はワークスペースのバージョンを引き継ぐ
+ *
+ * 制限事項:
+ * - lockfileは読まない（バージョンは宣言された要求のまま、解決したバージョンはlicensesモジュールで取得）
+ * - Cargoの `[target.'cfg(...)'.dependencies]`・npmの `peerDependencies` は対象外
+ * - pyproject.tomlは `[project]` と `[tool.poetry]` のみ
+ */
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::Diagnostics;
+
+/// マニフェストとして読むファイル名
+pub(crate) const MANIFEST_FILES: &[&str] = &[
+    "Cargo.toml", "package.json", "pnpm-workspace.yaml", "pyproject.toml", "requirements.txt", "go.mod", "go.work",
+];
+
+/// パッケージマニフェスト
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// マニフェストのパス（リポジトリルートからの相対パス）
+    pub path: PathBuf,
+    /// エコシステム（cargo|npm|python|go）
+    pub ecosystem: String,
+    /// パッケージ名（ワークスペースのルート・requirements.txtの場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub dependencies: Vec<DeclaredDependency>,
+    /// ワークスペースのメンバー（マニフェストのディレクトリからの相対パス、globを含む）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspace_members: Vec<String>,
+}
+
+/// マニフェストに宣言された依存
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeclaredDependency {
+    pub name: String,
+    /// 要求バージョン（指定がない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// 依存の種類（normal|dev|build）
+    pub kind: String,
+    /// リポジトリ内のパッケージへの依存か
+    pub internal: bool,
+}
+
+impl DeclaredDependency {
+    fn new(name: &str, version: Option<String>, kind: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.filter(|v| !v.is_empty()),
+            kind: kind.to_string(),
+            internal: false,
+        }
+    }
+}
+
+/// マニフェストを解析し、内部依存を判定
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `paths` - マニフェストのパス（`MANIFEST_FILES` のいずれかのファイル名）
+/// * `diagnostics` - 解析できなかったマニフェストの記録先
+///
+/// # 戻り値
+/// * `Vec<Manifest>` - 解析できたマニフェスト（パス順）
+pub(crate) fn parse_manifests(repo_path: &Path, paths: &[PathBuf], diagnostics: &Diagnostics) -> Vec<Manifest> {
+    let mut manifests: Vec<Manifest> = paths
+        .iter()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path).ok()?;
+            let file_name = path.file_name()?.to_str()?;
+            let parsed = parse_manifest(file_name, &content);
+            if parsed.is_none() {
+                diagnostics.warn_at("analyzer", path, "マニフェストを解析できませんでした");
+            }
+            let mut manifest = parsed?;
+            manifest.path = path.strip_prefix(repo_path).unwrap_or(path).to_path_buf();
+            Some(manifest)
+        })
+        .collect();
+    manifests.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // リポジトリ内のパッケージ名（Goはモジュールパスの前方一致）
+    let members: HashSet<String> = manifests.iter().filter_map(|m| m.name.clone()).collect();
+    let go_modules: Vec<String> = manifests
+        .iter()
+        .filter(|m| m.ecosystem == "go")
+        .filter_map(|m| m.name.clone())
+        .collect();
+    for manifest in &mut manifests {
+        for dep in &mut manifest.dependencies {
+            dep.internal = dep.internal
+                || members.contains(&dep.name)
+                || go_modules.iter().any(|module| dep.name.starts_with(&format!("{}/", module)));
+        }
+    }
+
+    manifests
+}
+
+/// マニフェスト1件を解析
+///
+/// # 引数
+/// * `file_name` - ファイル名
+/// * `content` - 内容
+///
+/// # 戻り値
+/// * `Option<Manifest>` - マニフェスト（形式が不正な場合はNone）
+fn parse_manifest(file_name: &str, content: &str) -> Option<Manifest> {
+    match file_name {
+        "Cargo.toml" => parse_cargo(content),
+        "package.json" => parse_package_json(content),
+        "pnpm-workspace.yaml" => Some(parse_pnpm_workspace(content)),
+        "pyproject.toml" => parse_pyproject(content),
+        "requirements.txt" => Some(parse_requirements(content)),
+        "go.mod" => Some(parse_go_mod(content)),
+        "go.work" => Some(parse_go_work(content)),
+        _ => None,
+    }
+}
+
+/// Cargo.tomlを解析
+fn parse_cargo(content: &str) -> Option<Manifest> {
+    let toml: toml::Table = content.parse().ok()?;
+    let name = toml.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str());
+
+    // `workspace = true` で参照されるワークスペースのバージョン
+    let workspace = toml.get("workspace").and_then(|w| w.get("dependencies")).and_then(|d| d.as_table());
+    let mut dependencies = Vec::new();
+    for (section, kind) in [("dependencies", "normal"), ("dev-dependencies", "dev"), ("build-dependencies", "build")] {
+        let Some(table) = toml.get(section).and_then(|d| d.as_table()) else {
+            continue;
+        };
+        for (dep_name, spec) in table {
+            dependencies.push(cargo_dependency(dep_name, spec, kind));
+        }
+    }
+    // ワークスペースのルートでは共有の依存を宣言として扱う
+    if name.is_none() {
+        for (dep_name, spec) in workspace.into_iter().flatten() {
+            dependencies.push(cargo_dependency(dep_name, spec, "normal"));
+        }
+    }
+
+    let members = toml.get("workspace").and_then(|w| w.get("members")).and_then(|m| m.as_array());
+    Some(Manifest {
+        path: PathBuf::new(),
+        ecosystem: "cargo".to_string(),
+        name: name.map(str::to_string),
+        dependencies,
+        workspace_members: string_array(members),
+    })
+}
+
+/// Cargoの依存の指定（`"1.0"` または `{ version, path, workspace, package }`）
+fn cargo_dependency(name: &str, spec: &toml::Value, kind: &str) -> DeclaredDependency {
+    let field = |key: &str| spec.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let version = match spec {
+        toml::Value::String(version) => Some(version.clone()),
+        _ if spec.get("workspace").and_then(|w| w.as_bool()) == Some(true) => Some("workspace".to_string()),
+        _ => field("version"),
+    };
+    // `package = "..."` で名前を変えている場合は実際のクレート名
+    let name = field("package").unwrap_or_else(|| name.to_string());
+    let mut dep = DeclaredDependency::new(&name, version, kind);
+    dep.internal = spec.get("path").is_some();
+    dep
+}
+
+/// package.jsonを解析
+fn parse_package_json(content: &str) -> Option<Manifest> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let mut dependencies = Vec::new();
+    for (section, kind) in [("dependencies", "normal"), ("optionalDependencies", "normal"), ("devDependencies", "dev")] {
+        let Some(deps) = json.get(section).and_then(|d| d.as_object()) else {
+            continue;
+        };
+        for (dep_name, version) in deps {
+            let version = version.as_str().unwrap_or_default();
+            let mut dep = DeclaredDependency::new(dep_name, Some(version.to_string()), kind);
+            dep.internal = ["workspace:", "file:", "link:"].iter().any(|p| version.starts_with(p));
+            dependencies.push(dep);
+        }
+    }
+
+    // `"workspaces": [...]` または `"workspaces": { "packages": [...] }`
+    let workspaces = json.get("workspaces").map(|w| w.get("packages").unwrap_or(w));
+    let workspace_members = workspaces
+        .and_then(|w| w.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m.as_str())
+        .map(str::to_string)
+        .collect();
+    Some(Manifest {
+        path: PathBuf::new(),
+        ecosystem: "npm".to_string(),
+        name: json.get("name").and_then(|n| n.as_str()).map(str::to_string),
+        dependencies,
+        workspace_members,
+    })
+}
+
+/// pyproject.tomlを解析（PEP 621の `[project]` とPoetry）
+fn parse_pyproject(content: &str) -> Option<Manifest> {
+    let toml: toml::Table = content.parse().ok()?;
+    let project = toml.get("project");
+    let poetry = toml.get("tool").and_then(|t| t.get("poetry"));
+
+    let mut dependencies = Vec::new();
+    let requirements = project.and_then(|p| p.get("dependencies")).and_then(|d| d.as_array());
+    for requirement in requirements.into_iter().flatten().filter_map(|r| r.as_str()) {
+        dependencies.extend(parse_requirement(requirement, "normal"));
+    }
+    for (path, kind) in [(&["dependencies"][..], "normal"), (&["dev-dependencies"][..], "dev"), (&["group", "dev", "dependencies"][..], "dev")] {
+        let table = poetry
+            .and_then(|poetry| path.iter().try_fold(poetry, |value, key| value.get(key)))
+            .and_then(|t| t.as_table());
+        for (dep_name, spec) in table.into_iter().flatten() {
+            if dep_name == "python" {
+                continue;
+            }
+            let version = match spec {
+                toml::Value::String(version) => Some(version.clone()),
+                _ => spec.get("version").and_then(|v| v.as_str()).map(str::to_string),
+            };
+            let mut dep = DeclaredDependency::new(dep_name, version, kind);
+            dep.internal = spec.get("path").is_some();
+            dependencies.push(dep);
+        }
+    }
+
+    let name = project.or(poetry).and_then(|p| p.get("name")).and_then(|n| n.as_str());
+    Some(Manifest {
+        path: PathBuf::new(),
+        ecosystem: "python".to_string(),
+        name: name.map(str::to_string),
+        dependencies,
+        workspace_members: Vec::new(),
+    })
+}
+
+/// requirements.txtを解析
+fn parse_requirements(content: &str) -> Manifest {
+    let dependencies = content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .filter_map(|line| parse_requirement(line, "normal"))
+        .collect();
+    Manifest {
+        path: PathBuf::new(),
+        ecosystem: "python".to_string(),
+        name: None,
+        dependencies,
+        workspace_members: Vec::new(),
+    }
+}
+
+/// PEP 508の要求（`requests[socks]>=2.0; python_version < "3.8"`）を解析
+fn parse_requirement(requirement: &str, kind: &str) -> Option<DeclaredDependency> {
+    let re = Regex::new(r"^\s*([A-Za-z0-9][A-Za-z0-9._-]*)\s*(?:\[[^\]]*\])?\s*([^;]*)").unwrap();
+    let captures = re.captures(requirement)?;
+    let version = captures.get(2).map(|v| v.as_str().trim().replace(' ', ""));
+    Some(DeclaredDependency::new(&captures[1], version, kind))
+}
+
+/// go.modを解析
+fn parse_go_mod(content: &str) -> Manifest {
+    let mut name = None;
+    let mut requires: BTreeMap<String, (String, bool)> = BTreeMap::new();
+    let mut local_replaces = HashSet::new();
+    let mut block: Option<&str> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if block.is_some() && line == ")" {
+            block = None;
+            continue;
+        }
+        let (directive, rest) = match block {
+            Some(directive) => (directive, line),
+            None => {
+                let (directive, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let rest = rest.trim();
+                if rest == "(" {
+                    block = Some(directive);
+                    continue;
+                }
+                (directive, rest)
+            }
+        };
+        let indirect = rest.contains("// indirect");
+        let rest = rest.split("//").next().unwrap_or("").trim();
+        let fields: Vec<&str> = rest.split_whitespace().collect();
+        match (directive, fields.as_slice()) {
+            ("module", [module, ..]) => name = Some(module.to_string()),
+            ("require", [module, version, ..]) => {
+                requires.insert(module.to_string(), (version.to_string(), indirect));
+            }
+            // ローカルのディレクトリへの置き換え
+            ("replace", [module, .., "=>", target]) | ("replace", [module, .., "=>", target, _])
+                if target.starts_with('.') || target.starts_with('/') =>
+            {
+                local_replaces.insert(module.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    let dependencies = requires
+        .into_iter()
+        .filter(|(_, (_, indirect))| !indirect)
+        .map(|(module, (version, _))| {
+            let mut dep = DeclaredDependency::new(&module, Some(version), "normal");
+            dep.internal = local_replaces.contains(&module);
+            dep
+        })
+        .collect();
+    Manifest {
+        path: PathBuf::new(),
+        ecosystem: "go".to_string(),
+        name,
+        dependencies,
+        workspace_members: Vec::new(),
+    }
+}
+
+/// go.workを解析（`use` のディレクトリをワークスペースのメンバーとする）
+fn parse_go_work(content: &str) -> Manifest {
+    let mut members = Vec::new();
+    let mut in_use = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_use {
+            if line == ")" {
+                in_use = false;
+            } else if !line.is_empty() {
+                members.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            match rest.trim() {
+                "(" => in_use = true,
+                dir if !dir.is_empty() => members.push(dir.to_string()),
+                _ => {}
+            }
+        }
+    }
+    Manifest {
+        path: PathBuf::new(),
+        ecosystem: "go".to_string(),
+        name: None,
+        dependencies: Vec::new(),
+        workspace_members: members,
+    }
+}
+
+/// pnpm-workspace.yamlを解析（`packages:` の一覧のみ）
+fn parse_pnpm_workspace(content: &str) -> Manifest {
+    let mut members = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = trimmed == "packages:";
+        } else if in_packages {
+            if let Some(member) = trimmed.strip_prefix('-') {
+                let member = member.trim().trim_matches(['\'', '"']);
+                // `!` で始まる除外パターンは対象外
+                if !member.starts_with('!') {
+                    members.push(member.to_string());
+                }
+            }
+        }
+    }
+    Manifest {
+        path: PathBuf::new(),
+        ecosystem: "npm".to_string(),
+        name: None,
+        dependencies: Vec::new(),
+        workspace_members: members,
+    }
+}
+
+/// TOMLの文字列配列
+fn string_array(values: Option<&Vec<toml::Value>>) -> Vec<String> {
+    values
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifests_marks_workspace_members_internal() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-manifests-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("core")).unwrap();
+        std::fs::create_dir_all(dir.join("web")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = [\"core\"]\n\n[workspace.dependencies]\nserde = \"1.0\"\n").unwrap();
+        std::fs::write(
+            dir.join("core").join("Cargo.toml"),
+            "[package]\nname = \"core\"\n\n[dependencies]\nserde = { workspace = true }\nutil = { path = \"../util\" }\n\n[dev-dependencies]\ntempfile = \"3\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("web").join("package.json"),
+            r#"{"name":"web","dependencies":{"react":"^18.2.0","core":"*"},"devDependencies":{"vite":"^5.0.0"}}"#,
+        )
+        .unwrap();
+        let paths = vec![dir.join("Cargo.toml"), dir.join("core").join("Cargo.toml"), dir.join("web").join("package.json")];
+
+        let manifests = parse_manifests(&dir, &paths, &Diagnostics::new());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(manifests.len(), 3);
+        assert_eq!(manifests[0].path, PathBuf::from("Cargo.toml"));
+        assert_eq!(manifests[0].dependencies[0].version.as_deref(), Some("1.0"));
+
+        let core = &manifests[1];
+        assert_eq!(core.name.as_deref(), Some("core"));
+        let deps: Vec<(&str, Option<&str>, &str, bool)> = core
+            .dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.version.as_deref(), d.kind.as_str(), d.internal))
+            .collect();
+        assert_eq!(
+            deps,
+            vec![
+                ("serde", Some("workspace"), "normal", false),
+                ("util", None, "normal", true),
+                ("tempfile", Some("3"), "dev", false),
+            ]
+        );
+
+        let web = &manifests[2];
+        assert!(web.dependencies.iter().any(|d| d.name == "core" && d.internal));
+        assert!(web.dependencies.iter().any(|d| d.name == "vite" && d.kind == "dev" && !d.internal));
+    }
+
+    #[test]
+    fn test_parse_python_and_go_manifests() {
+        let requirements = parse_requirements("# web\nrequests[socks] >= 2.0 ; python_version < \"3.8\"\n-r base.txt\nflask\n");
+        let deps: Vec<(&str, Option<&str>)> =
+            requirements.dependencies.iter().map(|d| (d.name.as_str(), d.version.as_deref())).collect();
+        assert_eq!(deps, vec![("requests", Some(">=2.0")), ("flask", None)]);
+
+        let pyproject = parse_pyproject("[tool.poetry]\nname = \"app\"\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nhttpx = \"^0.27\"\n").unwrap();
+        assert_eq!(pyproject.name.as_deref(), Some("app"));
+        assert_eq!(pyproject.dependencies.len(), 1);
+        assert_eq!(pyproject.dependencies[0].version.as_deref(), Some("^0.27"));
+
+        let go = parse_go_mod(
+            "module example.com/app\n\ngo 1.22\n\nrequire (\n\tgithub.com/spf13/cobra v1.8.0\n\tgolang.org/x/sys v0.20.0 // indirect\n\texample.com/lib v0.0.0\n)\n\nreplace example.com/lib => ../lib\n",
+        );
+        assert_eq!(go.name.as_deref(), Some("example.com/app"));
+        let deps: Vec<(&str, bool)> = go.dependencies.iter().map(|d| (d.name.as_str(), d.internal)).collect();
+        assert_eq!(deps, vec![("example.com/lib", true), ("github.com/spf13/cobra", false)]);
+
+        let work = parse_go_work("go 1.22
+
+use (
+	. // root
+	./tools
+)
+use ./lib
+");
+        assert_eq!(work.workspace_members, vec![".", "./tools", "./lib"]);
+        let pnpm = parse_pnpm_workspace("packages:
+  - 'packages/*'
+  - \"!**/test/**\"
+catalog:
+  react: ^18
+");
+        assert_eq!(pnpm.workspace_members, vec!["packages/*"]);
+    }
+}
@@ -0,0 +1,254 @@
+/*!
+ * コードメトリクス
+ *
+ * ファイル・関数ごとの行数とサイクロマティック複雑度を計算する
+ * - 行数: 総行数、コード行、コメント行、空行
+ * - 関数数: tree-sitterで抽出した関数・メソッドの数
+ * - サイクロマティック複雑度: 1 + 分岐の数（if・ループ・case・catch・三項演算子・`&&`・`||`）
+ *
+ * 主な仕様:
+ * - コメント行は行の先頭（空白を除く）からコメントが始まる行（コードの後ろのコメントはコード行）
+ * - 分岐は最も内側の関数に数え、関数の外の分岐はファイルの合計にのみ含める
+ * - ファイルの複雑度は関数の複雑度の合計（関数がない場合は 1 + 分岐の数）
+ *
+ * 制限事項:
+ * - tree-sitterで解析できない言語は行数（コメント行は0）のみ
+ * - `switch` の `default`・`match` の各アームも分岐として数える
+ */
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Node, Tree};
+
+use crate::Symbol;
+
+/// 分岐として数える構文ノードの種類
+const DECISION_KINDS: &[&str] = &[
+    // 条件
+    "if_statement", "if_expression", "elif_clause", "else_if_clause", "if", "elsif", "unless",
+    "if_modifier", "unless_modifier",
+    // ループ
+    "for_statement", "for_expression", "for_in_statement", "for_range_loop", "enhanced_for_statement",
+    "foreach_statement", "for", "while_statement", "while_expression", "while", "until", "do_statement",
+    "while_modifier", "until_modifier",
+    // case・パターン
+    "match_arm", "switch_case", "case_clause", "case_statement", "switch_label", "switch_section",
+    "expression_case", "type_case", "communication_case", "when_entry", "when",
+    // 例外
+    "catch_clause", "except_clause", "rescue",
+    // 式
+    "conditional_expression", "ternary_expression", "conjunction_expression", "disjunction_expression",
+    "boolean_operator",
+];
+
+/// ファイルのメトリクス
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileMetrics {
+    /// 総行数
+    pub lines: usize,
+    pub code_lines: usize,
+    pub comment_lines: usize,
+    pub blank_lines: usize,
+    /// 関数・メソッドの数
+    pub functions: usize,
+    /// サイクロマティック複雑度の合計
+    pub complexity: usize,
+    /// 関数ごとのメトリクス（出現順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub function_metrics: Vec<FunctionMetrics>,
+}
+
+/// 関数・メソッドのメトリクス
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FunctionMetrics {
+    pub name: String,
+    /// 所属する型・クラス（メソッドの場合）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり、この行を含む）
+    pub end_line: usize,
+    /// サイクロマティック複雑度
+    pub complexity: usize,
+}
+
+impl FileMetrics {
+    /// コメント率（コメント行 / (コード行 + コメント行)、0.0〜1.0）
+    pub fn comment_ratio(&self) -> f64 {
+        let total = self.code_lines + self.comment_lines;
+        if total == 0 {
+            0.0
+        } else {
+            self.comment_lines as f64 / total as f64
+        }
+    }
+
+    /// 関数の複雑度の最大値
+    pub fn max_complexity(&self) -> usize {
+        self.function_metrics.iter().map(|f| f.complexity).max().unwrap_or(0)
+    }
+}
+
+impl FunctionMetrics {
+    /// 行数
+    pub fn lines(&self) -> usize {
+        self.end_line + 1 - self.start_line
+    }
+}
+
+/// ファイルのメトリクスを計算
+///
+/// # 引数
+/// * `content` - ファイル内容
+/// * `tree` - 構文木（未対応の言語の場合はNone）
+/// * `symbols` - 構文木から抽出したシンボル
+///
+/// # 戻り値
+/// * `FileMetrics` - メトリクス
+pub(crate) fn measure(content: &str, tree: Option<&Tree>, symbols: &[Symbol]) -> FileMetrics {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut comment_rows = vec![false; lines.len()];
+    let mut decisions = Vec::new();
+    if let Some(tree) = tree {
+        walk(tree.root_node(), content.as_bytes(), &lines, &mut comment_rows, &mut decisions);
+    }
+
+    let blank_lines = lines.iter().filter(|l| l.trim().is_empty()).count();
+    let comment_lines = lines
+        .iter()
+        .zip(&comment_rows)
+        .filter(|(line, is_comment)| **is_comment && !line.trim().is_empty())
+        .count();
+
+    let mut function_metrics: Vec<FunctionMetrics> = symbols
+        .iter()
+        .filter(|s| matches!(s.kind.as_str(), "function" | "method"))
+        .map(|s| FunctionMetrics {
+            name: s.name.clone(),
+            parent: s.parent.clone(),
+            start_line: s.start_line,
+            end_line: s.end_line,
+            complexity: 1,
+        })
+        .collect();
+    for line in &decisions {
+        let innermost = function_metrics
+            .iter_mut()
+            .filter(|f| f.start_line <= *line && *line <= f.end_line)
+            .min_by_key(|f| f.end_line - f.start_line);
+        if let Some(function) = innermost {
+            function.complexity += 1;
+        }
+    }
+
+    let complexity = if function_metrics.is_empty() {
+        if tree.is_some() { 1 + decisions.len() } else { 0 }
+    } else {
+        function_metrics.iter().map(|f| f.complexity).sum()
+    };
+
+    FileMetrics {
+        lines: lines.len(),
+        code_lines: lines.len() - blank_lines - comment_lines,
+        comment_lines,
+        blank_lines,
+        functions: function_metrics.len(),
+        complexity,
+        function_metrics,
+    }
+}
+
+/// 構文木を走査してコメント行と分岐の行（1始まり）を記録
+fn walk(node: Node, src: &[u8], lines: &[&str], comment_rows: &mut [bool], decisions: &mut Vec<usize>) {
+    if node.kind().contains("comment") {
+        let start = node.start_position();
+        let end = node.end_position();
+        // 行頭からのコメントのみ（コードの後ろのコメントは除く）
+        let leading = lines
+            .get(start.row)
+            .is_some_and(|l| l.get(..start.column).unwrap_or("").trim().is_empty());
+        let first = if leading { start.row } else { start.row + 1 };
+        for row in first..=end.row.min(comment_rows.len().saturating_sub(1)) {
+            comment_rows[row] = true;
+        }
+        return;
+    }
+
+    if node.is_named() && is_decision(node, src) {
+        decisions.push(node.start_position().row + 1);
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        walk(child, src, lines, comment_rows, decisions);
+    }
+}
+
+/// 分岐となるノードか
+fn is_decision(node: Node, src: &[u8]) -> bool {
+    if DECISION_KINDS.contains(&node.kind()) {
+        return true;
+    }
+    // 短絡評価の論理演算子（Rubyの `and`・`or` を含む）
+    if matches!(node.kind(), "binary_expression" | "binary") {
+        let operator = node
+            .child_by_field_name("operator")
+            .and_then(|op| op.utf8_text(src).ok())
+            .unwrap_or_default();
+        return matches!(operator, "&&" | "||" | "and" | "or");
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax;
+
+    fn measure_source(content: &str, language: &str) -> FileMetrics {
+        let tree = syntax::parse_tree(content, language);
+        let parsed = tree.as_ref().map(|t| syntax::collect(t, content, language)).unwrap_or_default();
+        measure(content, tree.as_ref(), &parsed.symbols)
+    }
+
+    #[test]
+    fn test_measure_rust() {
+        let content = r#"
+// 値を分類する
+fn classify(n: i32) -> &'static str {
+    if n < 0 && n != -1 {
+        "negative"
+    } else {
+        match n {
+            0 => "zero",
+            _ => "positive", // 正の値
+        }
+    }
+}
+
+fn plain() {}
+"#;
+        let metrics = measure_source(content, "rs");
+        assert_eq!((metrics.lines, metrics.blank_lines, metrics.comment_lines), (14, 2, 1));
+        assert_eq!(metrics.code_lines, 11);
+        assert_eq!(metrics.functions, 2);
+        // if + && + アーム2つ
+        assert_eq!(metrics.function_metrics[0].complexity, 5);
+        assert_eq!(metrics.function_metrics[1].complexity, 1);
+        assert_eq!((metrics.complexity, metrics.max_complexity()), (6, 5));
+        assert_eq!(metrics.function_metrics[0].lines(), 10);
+    }
+
+    #[test]
+    fn test_measure_nested_python_and_plain_text() {
+        let content = "def outer(xs):\n    \"\"\"docstring\"\"\"\n    def inner(x):\n        return x if x else 0\n    for x in xs:\n        inner(x)\n";
+        let metrics = measure_source(content, "py");
+        let complexity: Vec<(&str, usize)> =
+            metrics.function_metrics.iter().map(|f| (f.name.as_str(), f.complexity)).collect();
+        assert_eq!(complexity, vec![("outer", 2), ("inner", 2)]);
+
+        let metrics = measure_source("a\n\nb\n", "txt");
+        assert_eq!((metrics.lines, metrics.code_lines, metrics.complexity), (3, 2, 0));
+        assert_eq!(metrics.comment_ratio(), 0.0);
+    }
+}
@@ -0,0 +1,137 @@
+/*!
+ * モジュールID
+ *
+ * ファイル名（拡張子なし）だけの `ModuleInfo.name` は、別のパッケージの `index.ts` などで重複するため、
+ * パッケージとパスで一意に決まるIDを付ける
+ * - Wikiの見出し・アンカー、図のラベル、スライド、検索結果で共通に使う
+ *
+ * 主な仕様:
+ * - ワークスペースのパッケージに属する場合は `<パッケージ名>:<パッケージ内の相対パス>`（例: `web:src/index.ts`）
+ * - パッケージに属さない場合はリポジトリルートからの相対パス（例: `src/index.ts`）
+ * - 区切りは常に `/`
+ *
+ * 制限事項:
+ * - 同名のパッケージが複数ある場合は一意にならないことがある
+ */
+
+use std::path::Path;
+
+use crate::PackageInfo;
+
+/// ディレクトリ名をモジュール名とみなすファイル名（拡張子なし）
+const DIRECTORY_STEMS: &[&str] = &["index", "mod", "lib", "__init__"];
+
+/// ファイルのモジュールIDを決める
+///
+/// # 引数
+/// * `package` - ファイルが属するパッケージ（`Index::package_of`）
+/// * `relative` - リポジトリルートからの相対パス
+///
+/// # 戻り値
+/// * `String` - モジュールID
+pub(crate) fn qualified_id(package: Option<&PackageInfo>, relative: &Path) -> String {
+    match package {
+        Some(package) => {
+            let inner = relative.strip_prefix(&package.path).unwrap_or(relative);
+            format!("{}:{}", package.name, slash_path(inner))
+        }
+        None => slash_path(relative),
+    }
+}
+
+/// import文字列がモジュールIDのファイルを指すか（内部依存グラフのないインデックスでの推定用）
+///
+/// `./util`・`crate::store::Item`・`pkg.mod` のようなimportを `/` 区切りにし、拡張子を除いたパスの末尾と比べる
+///
+/// # 引数
+/// * `id` - モジュールID
+/// * `import` - import文字列
+pub(crate) fn matches_import(id: &str, import: &str) -> bool {
+    let path = Path::new(id.rsplit_once(':').map_or(id, |(_, path)| path));
+    let mut candidates = vec![slash_path(path), slash_path(&path.with_extension(""))];
+    if path.file_stem().is_some_and(|stem| DIRECTORY_STEMS.contains(&stem.to_string_lossy().as_ref())) {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            candidates.push(slash_path(dir));
+        }
+    }
+
+    let mut import = import.trim_matches(|c| c == '"' || c == '\'');
+    while let Some(rest) = ["./", "../", "crate::", "super::", "self::"].iter().find_map(|p| import.strip_prefix(p)) {
+        import = rest;
+    }
+    let import = import.trim_end_matches([':', '/', '*']);
+    if import.is_empty() {
+        return false;
+    }
+
+    let mut imports = Vec::new();
+    if import.contains("::") {
+        // Rustのuseパスはアイテム名で終わることが多いため、最後のセグメントを除いたパスも比べる
+        let import = import.replace("::", "/");
+        if let Some((module, _item)) = import.rsplit_once('/') {
+            imports.push(module.to_string());
+        }
+        imports.push(import);
+    } else {
+        imports.push(import.to_string());
+        // Pythonなどのドット区切り（`app.models.user`）
+        if !import.contains('/') {
+            imports.push(import.replace('.', "/"));
+        }
+    }
+    imports.iter().any(|import| {
+        candidates.iter().any(|c| c == import || c.ends_with(&format!("/{}", import)))
+    })
+}
+
+/// モジュールIDが指定したIDまたはその配下か（検索のモジュールによる絞り込み用）
+///
+/// # 引数
+/// * `id` - モジュールID
+/// * `scope` - モジュールID、パッケージ名（`web`）、またはディレクトリ（`web:src/api`・`scripts`）
+pub(crate) fn is_within(id: &str, scope: &str) -> bool {
+    let scope = scope.trim_end_matches(['/', ':']);
+    id == scope
+        || id.strip_prefix(scope).is_some_and(|rest| rest.starts_with(':') || rest.starts_with('/'))
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_qualified_id_and_import_matching() {
+        let package = |name: &str, path: &str| PackageInfo {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            ..Default::default()
+        };
+        let web = package("web", "packages/web");
+        let api = package("@acme/api", "packages/api");
+        assert_eq!(qualified_id(Some(&web), Path::new("packages/web/src/index.ts")), "web:src/index.ts");
+        assert_eq!(qualified_id(Some(&api), Path::new("packages/api/src/index.ts")), "@acme/api:src/index.ts");
+        assert_eq!(qualified_id(None, Path::new("scripts/build.ts")), "scripts/build.ts");
+
+        assert!(matches_import("web:src/util.ts", "./util"));
+        assert!(matches_import("web:src/store/index.ts", "../store"));
+        assert!(!matches_import("web:src/util.ts", "./utils"));
+        assert!(matches_import("core:src/store.rs", "crate::store::Store"));
+        assert!(matches_import("app/models/user.py", "app.models.user"));
+        assert!(matches_import("web:src/util.ts", "./util.ts"));
+        assert!(!matches_import("src/index.ts", "."));
+
+        assert!(is_within("web:src/api/user.ts", "web"));
+        assert!(is_within("web:src/api/user.ts", "web:src/api"));
+        assert!(is_within("web:src/api/user.ts", "web:src/api/user.ts"));
+        assert!(!is_within("webapp:src/index.ts", "web"));
+        assert!(!is_within("web:src/apis.ts", "web:src/api"));
+    }
+}
@@ -0,0 +1,122 @@
+/*!
+ * 解析対象のパスの絞り込み
+ *
+ * `project.include` と `project.exclude` のglobパターンで解析するファイルを決める
+ * - 巨大なMono-Repoで `crates` や `src` 配下のみを解析できるようにする
+ * - 検索の `path_glob` による絞り込みにも同じ照合規則を使う
+ *
+ * 主な仕様:
+ * - パターンはリポジトリルートからの相対パスに対して照合（区切りは `/`）
+ * - `**` は0個以上のディレクトリ、`*` はパス区切りを含まない任意の文字列
+ * - includeのいずれかに一致し、excludeのいずれにも一致しないファイルを対象とする
+ * - includeが空の場合はすべてのファイルを対象とする
+ *
+ * 制限事項:
+ * - 不正なパターンは解析開始時にエラーとする
+ */
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+/// include・excludeパターンによるフィルタ
+#[derive(Debug, Clone)]
+pub(crate) struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    /// パターンからフィルタを作成
+    ///
+    /// # 引数
+    /// * `include` - 対象とするパターン（空の場合はすべて）
+    /// * `exclude` - 除外するパターン
+    ///
+    /// # 戻り値
+    /// * `Result<PathFilter>` - フィルタ、または不正なパターンのエラー
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_set(include, "project.include")?)
+        };
+        Ok(Self {
+            include,
+            exclude: build_set(exclude, "project.exclude")?,
+        })
+    }
+
+    /// 1つのパターンに一致するファイルだけを対象とするフィルタを作成
+    ///
+    /// # 引数
+    /// * `pattern` - パターン
+    /// * `field` - エラーメッセージに含める項目名
+    ///
+    /// # 戻り値
+    /// * `Result<PathFilter>` - フィルタ、または不正なパターンのエラー
+    pub fn glob(pattern: &str, field: &str) -> Result<Self> {
+        Ok(Self {
+            include: Some(build_set(&[pattern.to_string()], field)?),
+            exclude: GlobSet::empty(),
+        })
+    }
+
+    /// ファイルが解析対象かを判定
+    ///
+    /// # 引数
+    /// * `relative` - リポジトリルートからの相対パス
+    ///
+    /// # 戻り値
+    /// * `bool` - 解析対象の場合true
+    pub fn is_included(&self, relative: &Path) -> bool {
+        let included = self.include.as_ref().is_none_or(|set| set.is_match(relative));
+        included && !self.exclude.is_match(relative)
+    }
+}
+
+/// パターンのリストからGlobSetを作成
+pub(crate) fn build_set(patterns: &[String], field: &str) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(compile(pattern).with_context(|| format!("{}のパターンが不正です: {}", field, pattern))?);
+    }
+    builder
+        .build()
+        .with_context(|| format!("{}のパターンを構築できません", field))
+}
+
+/// パターンをコンパイル（`*` はパス区切りをまたがない）
+fn compile(pattern: &str) -> Result<Glob, globset::Error> {
+    // `./src/**` のような先頭の `./` は相対パスと照合できるよう除く
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_include_and_exclude_patterns() {
+        let filter = PathFilter::new(
+            &["crates/**".to_string(), "./README.md".to_string()],
+            &["**/target/**".to_string(), "crates/*/tests/*.rs".to_string()],
+        )
+        .unwrap();
+
+        assert!(filter.is_included(Path::new("crates/config/src/lib.rs")));
+        assert!(filter.is_included(Path::new("README.md")));
+        assert!(!filter.is_included(Path::new("apps/cli/src/main.rs")));
+        assert!(!filter.is_included(Path::new("crates/config/target/debug/build.rs")));
+        assert!(!filter.is_included(Path::new("crates/config/tests/load.rs")));
+        // `*` はパス区切りをまたがない
+        assert!(filter.is_included(Path::new("crates/config/tests/fixtures/a.rs")));
+
+        let all = PathFilter::new(&[], &["**/node_modules/**".to_string()]).unwrap();
+        assert!(all.is_included(Path::new("src/index.ts")));
+        assert!(!all.is_included(Path::new("node_modules/left-pad/index.js")));
+
+        assert!(PathFilter::new(&["src/[".to_string()], &[]).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+/*!
+ * リポジトリ直下のドキュメントの検出
+ *
+ * プロジェクト全体について書かれたファイルを探す
+ * - README（README.md・README.rst・README.txt・README）
+ * - ライセンス（LICENSE・LICENCE・COPYING、`LICENSE-MIT` のような複数ファイル）
+ * - 貢献ガイド（CONTRIBUTING）
+ * - 変更履歴（CHANGELOG・CHANGES・HISTORY）
+ *
+ * 主な仕様:
+ * - リポジトリ直下、`.github/`、`docs/` の順に探す（READMEとライセンスはリポジトリ直下のみ）
+ * - ファイル名の大文字・小文字は区別しない
+ * - ライセンスの種類は本文の定型句から推定し、複数のファイルがあれば `OR` でつなぐ
+ *   （判定できない場合はCargo.toml・package.json・pyproject.tomlの `license` を使う）
+ * - READMEは冒頭（タイトルを除き、最初の `##` 見出しまで）を保持して概要に掲載する
+ *
+ * 制限事項:
+ * - `include`・`exclude` パターンは適用しない
+ * - ライセンス本文の推定はGPL・LGPL・AGPL・MPL・Apache・MIT・BSD・ISC・Unlicenseのみ
+ */
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::licenses::classify_license_text;
+
+/// 保持するREADMEの冒頭の最大文字数
+const MAX_README_INTRO_CHARS: usize = 2000;
+
+/// リポジトリ直下のドキュメント（パスはリポジトリルートからの相対パス）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectDocs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme: Option<PathBuf>,
+    /// READMEの冒頭（タイトルを除き、最初の `##` 見出しまで）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readme_intro: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub license_files: Vec<PathBuf>,
+    /// ライセンスの種類（SPDX式、判定できない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contributing: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changelog: Option<PathBuf>,
+}
+
+/// リポジトリ直下のドキュメントを検出
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+///
+/// # 戻り値
+/// * `ProjectDocs` - 見つかったドキュメント（見つからないものはNone）
+pub(crate) fn detect_project_docs(repo_path: &Path) -> ProjectDocs {
+    let root = list_files(repo_path, Path::new(""));
+    let mut all = root.clone();
+    all.extend(list_files(repo_path, Path::new(".github")));
+    all.extend(list_files(repo_path, Path::new("docs")));
+
+    let readme = ["README.md", "README.rst", "README.txt", "README"]
+        .iter()
+        .find_map(|name| find_named(&root, |_, file| file.eq_ignore_ascii_case(name)));
+    let readme_intro = readme
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(repo_path.join(path)).ok())
+        .and_then(|content| readme_intro(&content));
+
+    let mut license_files: Vec<PathBuf> = root
+        .iter()
+        .filter(|path| {
+            let upper = file_name(path).to_ascii_uppercase();
+            ["LICENSE", "LICENCE", "COPYING"].iter().any(|prefix| upper.starts_with(prefix))
+        })
+        .cloned()
+        .collect();
+    license_files.sort();
+    let mut kinds: Vec<String> = Vec::new();
+    for path in &license_files {
+        let kind = std::fs::read_to_string(repo_path.join(path)).ok().and_then(|text| classify_license_text(&text));
+        if let Some(kind) = kind.filter(|k| !kinds.contains(k)) {
+            kinds.push(kind);
+        }
+    }
+    let license = match kinds.is_empty() {
+        true => manifest_license(repo_path),
+        false => Some(kinds.join(" OR ")),
+    };
+
+    ProjectDocs {
+        readme,
+        readme_intro,
+        license_files,
+        license,
+        contributing: find_named(&all, |stem, _| stem.eq_ignore_ascii_case("CONTRIBUTING")),
+        changelog: find_named(&all, |stem, _| {
+            ["CHANGELOG", "CHANGES", "HISTORY"].iter().any(|name| stem.eq_ignore_ascii_case(name))
+        }),
+    }
+}
+
+/// ディレクトリ直下のファイル（リポジトリルートからの相対パス、名前順）
+fn list_files(repo_path: &Path, dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(repo_path.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+        .map(|e| dir.join(e.file_name()))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 拡張子を除いた名前とファイル名で最初に一致するファイル
+fn find_named(files: &[PathBuf], matches: impl Fn(&str, &str) -> bool) -> Option<PathBuf> {
+    files
+        .iter()
+        .find(|path| {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            matches(stem, file_name(path))
+        })
+        .cloned()
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(|n| n.to_str()).unwrap_or_default()
+}
+
+/// READMEの冒頭（タイトルの `#` 見出しを除き、最初の `##` 見出しまで）
+fn readme_intro(content: &str) -> Option<String> {
+    let mut lines = content.lines().skip_while(|l| l.trim().is_empty()).peekable();
+    if lines.peek().is_some_and(|l| l.starts_with("# ")) {
+        lines.next();
+    }
+    let intro: Vec<&str> = lines.take_while(|l| !l.starts_with("## ")).collect();
+    let intro = intro.join("\n").trim().to_string();
+    if intro.is_empty() {
+        return None;
+    }
+    match intro.char_indices().nth(MAX_README_INTRO_CHARS) {
+        Some((end, _)) => Some(format!("{}…", &intro[..end])),
+        None => Some(intro),
+    }
+}
+
+/// リポジトリ直下のマニフェストに宣言されたライセンス
+fn manifest_license(repo_path: &Path) -> Option<String> {
+    let read = |name: &str| std::fs::read_to_string(repo_path.join(name)).ok();
+    if let Some(table) = read("Cargo.toml").and_then(|c| c.parse::<toml::Table>().ok()) {
+        let package = table.get("package").or_else(|| table.get("workspace").and_then(|w| w.get("package")));
+        if let Some(license) = package.and_then(|p| p.get("license")).and_then(|l| l.as_str()) {
+            return Some(license.to_string());
+        }
+    }
+    if let Some(json) = read("package.json").and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok()) {
+        if let Some(license) = json.get("license").and_then(|l| l.as_str()) {
+            return Some(license.to_string());
+        }
+    }
+    let pyproject = read("pyproject.toml").and_then(|c| c.parse::<toml::Table>().ok())?;
+    // PEP 621の `license = "MIT"` または `license = { text = "MIT" }`
+    let license = pyproject.get("project")?.get("license")?;
+    license
+        .as_str()
+        .or_else(|| license.get("text").and_then(|t| t.as_str()))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_project_docs() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-project-docs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join(".github")).unwrap();
+        std::fs::write(
+            dir.join("README.md"),
+            "\n# Acme\n\n決済APIのサーバーです。\n\n## インストール\n\ncargo install acme\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("LICENSE-MIT"), "Permission is hereby granted, free of charge, to any person").unwrap();
+        std::fs::write(dir.join("LICENSE-APACHE"), "                 Apache License\n           Version 2.0").unwrap();
+        std::fs::write(dir.join(".github/CONTRIBUTING.md"), "# 貢献方法").unwrap();
+        std::fs::write(dir.join("CHANGELOG.md"), "# 変更履歴").unwrap();
+
+        let docs = detect_project_docs(&dir);
+        assert_eq!(docs.readme, Some(PathBuf::from("README.md")));
+        assert_eq!(docs.readme_intro.as_deref(), Some("決済APIのサーバーです。"));
+        assert_eq!(docs.license_files, vec![PathBuf::from("LICENSE-APACHE"), PathBuf::from("LICENSE-MIT")]);
+        assert_eq!(docs.license.as_deref(), Some("Apache-2.0 OR MIT"));
+        assert_eq!(docs.contributing, Some(PathBuf::from(".github/CONTRIBUTING.md")));
+        assert_eq!(docs.changelog, Some(PathBuf::from("CHANGELOG.md")));
+
+        // 本文から判定できない場合はマニフェストの宣言
+        std::fs::write(dir.join("LICENSE-MIT"), "All rights reserved.").unwrap();
+        std::fs::remove_file(dir.join("LICENSE-APACHE")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[workspace.package]\nlicense = \"MIT\"\n").unwrap();
+        assert_eq!(detect_project_docs(&dir).license.as_deref(), Some("MIT"));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
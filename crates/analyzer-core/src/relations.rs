@@ -0,0 +1,165 @@
+/*!
+ * 型の実装・継承関係の推定
+ *
+ * Goは `implements` を書かずにメソッドを揃えるだけでインターフェースを満たすため、
+ * 構文から抽出できない実装関係をメソッド名の一致から推定する
+ *
+ * 主な仕様:
+ * - インターフェースのメソッド集合には埋め込んだインターフェースのメソッドを含める
+ * - 型のメソッドは同じディレクトリ（パッケージ）のGoファイルから集める
+ * - インターフェースのメソッドをすべて持つ構造体に `inferred` の implements 関係を追加
+ *
+ * 制限事項:
+ * - メソッドのシグネチャ（引数・戻り値の型）は比較しない
+ * - 埋め込みのインターフェースはリポジトリ内で名前が一致するものを使う（標準ライブラリは解決しない）
+ * - メソッドのない空のインターフェースは対象外
+ */
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::syntax::{base_type_name, TypeRelation};
+use crate::FileInfo;
+
+/// Goの構造体がメソッド名の一致で満たすインターフェースを関係に追加
+///
+/// # 引数
+/// * `files` - 解析したファイル（推定した関係は構造体を宣言したファイルに追加）
+pub(crate) fn infer_go_implementations(files: &mut [FileInfo]) {
+    let go_files = || files.iter().enumerate().filter(|(_, f)| f.language == "go");
+
+    // インターフェース名 → 直接のメソッド・埋め込んだインターフェース
+    let mut interfaces: BTreeMap<String, (BTreeSet<String>, Vec<String>)> = BTreeMap::new();
+    // （パッケージ, 型名） → メソッド
+    let mut methods: HashMap<(&Path, String), BTreeSet<String>> = HashMap::new();
+    // （パッケージ, 構造体名） → 宣言したファイル・行
+    let mut structs: BTreeMap<(PathBuf, String), (usize, usize)> = BTreeMap::new();
+
+    for (i, file) in go_files() {
+        let dir = file.path.parent().unwrap_or(Path::new(""));
+        let interface_names: BTreeSet<&str> = file
+            .symbols
+            .iter()
+            .filter(|s| s.kind == "interface")
+            .map(|s| s.name.as_str())
+            .collect();
+        for symbol in &file.symbols {
+            match (symbol.kind.as_str(), &symbol.parent) {
+                ("interface", _) => {
+                    interfaces.entry(symbol.name.clone()).or_default();
+                }
+                ("struct", _) => {
+                    structs.insert((dir.to_path_buf(), symbol.name.clone()), (i, symbol.start_line));
+                }
+                ("method", Some(parent)) if interface_names.contains(parent.as_str()) => {
+                    interfaces.entry(parent.clone()).or_default().0.insert(symbol.name.clone());
+                }
+                ("method", Some(parent)) => {
+                    methods.entry((dir, base_type_name(parent))).or_default().insert(symbol.name.clone());
+                }
+                _ => {}
+            }
+        }
+        for relation in file.relations.iter().filter(|r| r.kind == "extends") {
+            if let Some(interface) = interfaces.get_mut(&relation.name) {
+                interface.1.push(relation.target.clone());
+            }
+        }
+    }
+
+    let method_sets: BTreeMap<&str, BTreeSet<String>> = interfaces
+        .keys()
+        .map(|name| (name.as_str(), interface_methods(&interfaces, name, &mut BTreeSet::new())))
+        .filter(|(_, set)| !set.is_empty())
+        .collect();
+
+    let mut inferred: Vec<(usize, TypeRelation)> = Vec::new();
+    for ((dir, name), (file, line)) in &structs {
+        let Some(own) = methods.get(&(dir.as_path(), name.clone())) else {
+            continue;
+        };
+        for (interface, required) in &method_sets {
+            let declared = files[*file]
+                .relations
+                .iter()
+                .any(|r| r.name == *name && r.target == *interface);
+            if !declared && required.is_subset(own) {
+                inferred.push((
+                    *file,
+                    TypeRelation {
+                        kind: "implements".to_string(),
+                        name: name.clone(),
+                        target: interface.to_string(),
+                        line: *line,
+                        inferred: true,
+                    },
+                ));
+            }
+        }
+    }
+    for (file, relation) in inferred {
+        files[file].relations.push(relation);
+    }
+}
+
+/// 埋め込みを展開したインターフェースのメソッド集合（循環した埋め込みは無視）
+fn interface_methods(
+    interfaces: &BTreeMap<String, (BTreeSet<String>, Vec<String>)>,
+    name: &str,
+    visited: &mut BTreeSet<String>,
+) -> BTreeSet<String> {
+    let Some((own, embedded)) = interfaces.get(name) else {
+        return BTreeSet::new();
+    };
+    if !visited.insert(name.to_string()) {
+        return BTreeSet::new();
+    }
+    let mut set = own.clone();
+    for inner in embedded {
+        set.extend(interface_methods(interfaces, inner, visited));
+    }
+    set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parse_source;
+
+    #[test]
+    fn test_infer_go_interface_implementations() {
+        let file = |path: &str, content: &str| {
+            let parsed = parse_source(content, "go").unwrap();
+            FileInfo {
+                path: PathBuf::from(path),
+                language: "go".to_string(),
+                symbols: parsed.symbols,
+                relations: parsed.relations,
+                ..Default::default()
+            }
+        };
+        let mut files = vec![
+            file(
+                "/repo/store/store.go",
+                "package store\n\ntype Reader interface {\n\tGet(key string) string\n}\n\ntype Store interface {\n\tReader\n\tPut(key, value string)\n}\n\ntype Any interface{}\n",
+            ),
+            file("/repo/store/memory.go", "package store\n\ntype Memory struct{}\n\ntype ReadOnly struct{}\n"),
+            file(
+                "/repo/store/memory_methods.go",
+                "package store\n\nfunc (m *Memory) Get(key string) string { return \"\" }\nfunc (m *Memory) Put(key, value string) {}\nfunc (r ReadOnly) Get(key string) string { return \"\" }\n",
+            ),
+        ];
+        infer_go_implementations(&mut files);
+
+        let relations: Vec<(&str, &str, bool)> = files[1]
+            .relations
+            .iter()
+            .map(|r| (r.name.as_str(), r.target.as_str(), r.inferred))
+            .collect();
+        assert_eq!(
+            relations,
+            vec![("Memory", "Reader", true), ("Memory", "Store", true), ("ReadOnly", "Reader", true)]
+        );
+        assert_eq!(files[1].relations[0].line, 3);
+    }
+}
@@ -0,0 +1,277 @@
+/*!
+ * REST・GraphQLのAPIエンドポイントの抽出
+ *
+ * ルーティングの定義から、HTTPメソッド・パス・ハンドラー関数を取り出す
+ * - Express・Fastify・Koaなど（`app.get("/users", listUsers)`）、Goのgin・chi・net/http
+ * - axum（`.route("/users", get(list).post(create))`）、actix-web（`#[get("/users")]`・`web::get().to(list)`）
+ * - FastAPI・Flask（`@app.get("/users")`・`@app.route("/users", methods=["POST"])`）
+ * - Spring（`@GetMapping("/users")`、クラスの `@RequestMapping` をパスの前に付ける）
+ * - GraphQLのスキーマ（`type Query { ... }`・`type Mutation { ... }` のフィールド）
+ *
+ * 主な仕様:
+ * - 呼び出し形式はパスが `/` で始まる文字列リテラルで、ハンドラーの引数があるもののみを対象とする（`map.get("/key")` のような呼び出しを除くため）
+ * - デコレーター・アノテーションの場合は、直後に定義された関数をハンドラーとする
+ * - ハンドラーが無名関数の場合はNone
+ * - GraphQLのフィールドはメソッドを `QUERY`・`MUTATION`・`SUBSCRIPTION`、パスをフィールド名とする
+ *
+ * 制限事項:
+ * - 1行に書かれたルーティングのみを抽出する（複数行にまたがる呼び出しは最初の行で判定）
+ * - ルーターのマウント（`app.use("/api", router)`・`Router::nest`）によるパスの前置は追わない
+ */
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// APIエンドポイント
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiEndpoint {
+    /// HTTPメソッド（GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS|ANY）、GraphQLはQUERY|MUTATION|SUBSCRIPTION
+    pub method: String,
+    /// パス（GraphQLはフィールド名）
+    pub path: String,
+    /// ハンドラー関数（無名関数の場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handler: Option<String>,
+    pub file: PathBuf,
+    /// 定義した行（1始まり）
+    pub line: usize,
+    /// 抽出したフレームワーク（express|go|axum|actix-web|fastapi|flask|spring|graphql）
+    pub framework: String,
+}
+
+/// `receiver.get("/path", handler)` 形式（Express・Fastify・Koa・gin・chi）
+static CALL_ROUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"\b\w+\.(get|post|put|patch|delete|head|options|all|GET|POST|PUT|PATCH|DELETE|HEAD|OPTIONS|Get|Post|Put|Patch|Delete|HandleFunc|Handle)\(\s*["'`](/[^"'`]*)["'`]\s*,\s*(.*)"#).unwrap()
+});
+/// axum・actix-webの `.route("/path", ...)`
+static RUST_ROUTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\.route\(\s*"(/[^"]*)"\s*,(.*)"#).unwrap());
+/// axumの `get(handler)`・actix-webの `web::get().to(handler)`
+static RUST_METHOD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(get|post|put|patch|delete|head|options|any)\((?:\)\.to\()?([A-Za-z_][\w:]*)\)").unwrap()
+});
+/// actix-webの `#[get("/path")]`
+static RUST_ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^#\[(?:actix_web::)?(get|post|put|patch|delete|head|options)\(\s*"(/[^"]*)""#).unwrap());
+/// FastAPI・Flaskのデコレーター
+static PY_DECORATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^@\w+\.(get|post|put|patch|delete|head|options|route|api_route)\(\s*["'](/[^"']*)["'](.*)"#).unwrap()
+});
+/// Flaskの `methods=["GET", "POST"]`
+static PY_METHODS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"methods\s*=\s*[\[(]([^\])]*)").unwrap());
+/// Springのマッピングアノテーション
+static SPRING_MAPPING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^@(Get|Post|Put|Patch|Delete|Request)Mapping\b(?:\(\s*(?:(?:value|path)\s*=\s*)?\{?\s*"([^"]*)")?(.*)"#).unwrap()
+});
+/// 関数・メソッドの定義（デコレーター・アノテーションの後の行）
+static DEFINITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\bfn|\bdef|\bfunction)\s+(\w+)|^(?:public|private|protected)?\s*(?:static\s+)?[\w<>\[\],.? ]+\s+(\w+)\s*\(").unwrap()
+});
+/// GraphQLのルート型
+static GRAPHQL_TYPE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:extend\s+)?type\s+(Query|Mutation|Subscription)\s*\{").unwrap());
+/// GraphQLのフィールド
+static GRAPHQL_FIELD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\w+)\s*(?:\(|:)").unwrap());
+
+/// ファイルからAPIエンドポイントを抽出
+///
+/// # 引数
+/// * `path` - ファイルパス
+/// * `language` - 言語（GraphQLのスキーマファイルは `graphql`）
+/// * `content` - ファイル内容
+///
+/// # 戻り値
+/// * `Vec<ApiEndpoint>` - 定義順のエンドポイント
+pub(crate) fn extract_endpoints(path: &Path, language: &str, content: &str) -> Vec<ApiEndpoint> {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let mut endpoints = Vec::new();
+    let mut push = |method: &str, route: &str, handler: Option<String>, line: usize, framework: &str| {
+        endpoints.push(ApiEndpoint {
+            method: method.to_uppercase(),
+            path: route.to_string(),
+            handler,
+            file: path.to_path_buf(),
+            line: line + 1,
+            framework: framework.to_string(),
+        });
+    };
+
+    // Springのクラスの `@RequestMapping` はメソッドのパスの前に付ける
+    let mut spring_prefix = String::new();
+    let mut graphql_root: Option<&str> = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("//") || line.starts_with("# ") {
+            continue;
+        }
+
+        // GraphQL（スキーマファイル、またはソースコード中のSDL）
+        if let Some(root) = graphql_root {
+            if line.starts_with('}') {
+                graphql_root = None;
+            } else if let Some(field) = GRAPHQL_FIELD.captures(line) {
+                push(root, &field[1], None, i, "graphql");
+            }
+            continue;
+        }
+        if let Some(captures) = GRAPHQL_TYPE.captures(line) {
+            graphql_root = Some(match &captures[1] {
+                "Query" => "QUERY",
+                "Mutation" => "MUTATION",
+                _ => "SUBSCRIPTION",
+            });
+            continue;
+        }
+
+        match language {
+            "ts" | "js" | "go" => {
+                if let Some(captures) = CALL_ROUTE.captures(line) {
+                    let method = match &captures[1] {
+                        "all" | "HandleFunc" | "Handle" => "ANY",
+                        method => method,
+                    };
+                    let framework = if language == "go" { "go" } else { "express" };
+                    let handler = last_identifier(&captures[3]);
+                    push(method, &captures[2], handler, i, framework);
+                }
+            }
+            "rs" => {
+                if let Some(captures) = RUST_ROUTE.captures(line) {
+                    let framework = if captures[2].contains("web::") { "actix-web" } else { "axum" };
+                    for method in RUST_METHOD.captures_iter(&captures[2]) {
+                        let handler = method[2].rsplit("::").next().map(str::to_string);
+                        push(&method[1], &captures[1], handler, i, framework);
+                    }
+                } else if let Some(captures) = RUST_ATTRIBUTE.captures(line) {
+                    push(&captures[1], &captures[2], next_definition(&lines, i), i, "actix-web");
+                }
+            }
+            "py" => {
+                if let Some(captures) = PY_DECORATOR.captures(line) {
+                    let handler = next_definition(&lines, i);
+                    let framework = if captures[1].eq_ignore_ascii_case("route") { "flask" } else { "fastapi" };
+                    match &captures[1] {
+                        "route" | "api_route" => {
+                            let methods: Vec<String> = PY_METHODS
+                                .captures(&captures[3])
+                                .map(|m| m[1].split(',').map(|s| s.trim().trim_matches(['"', '\'']).to_string()).collect())
+                                .unwrap_or_else(|| vec!["GET".to_string()]);
+                            for method in methods.iter().filter(|m| !m.is_empty()) {
+                                push(method, &captures[2], handler.clone(), i, framework);
+                            }
+                        }
+                        method => push(method, &captures[2], handler, i, framework),
+                    }
+                }
+            }
+            "java" | "kt" => {
+                if let Some(captures) = SPRING_MAPPING.captures(line) {
+                    let route = captures.get(2).map_or("", |m| m.as_str());
+                    let handler = next_definition(&lines, i);
+                    let is_class = handler.is_none()
+                        || lines[i + 1..].iter().find(|l| !l.starts_with('@')).is_some_and(|l| l.contains("class "));
+                    if &captures[1] == "Request" && is_class {
+                        spring_prefix = route.trim_end_matches('/').to_string();
+                        continue;
+                    }
+                    let method = match &captures[1] {
+                        "Request" => spring_request_method(&captures[3]),
+                        method => method,
+                    };
+                    let route = format!("{}/{}", spring_prefix, route.trim_start_matches('/'));
+                    let route = if route.len() > 1 { route.trim_end_matches('/').to_string() } else { route };
+                    push(method, &route, handler, i, "spring");
+                }
+            }
+            _ => {}
+        }
+    }
+    endpoints
+}
+
+/// GraphQLのスキーマファイルか
+pub(crate) fn is_graphql_schema(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|ext| ext == "graphql" || ext == "gql")
+}
+
+/// 呼び出しの引数の最後の識別子（`auth, users.list)` → `users.list`、無名関数の場合はNone）
+fn last_identifier(args: &str) -> Option<String> {
+    let last = args.trim_end().trim_end_matches([';', ')']).rsplit(',').next()?.trim();
+    let is_identifier =
+        !last.is_empty() && last.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '$');
+    is_identifier.then(|| last.to_string())
+}
+
+/// デコレーター・アノテーションの後に定義された関数名
+fn next_definition(lines: &[&str], decorator: usize) -> Option<String> {
+    lines
+        .iter()
+        .skip(decorator + 1)
+        .take(10)
+        .find(|line| !line.starts_with('@') && !line.starts_with("#[") && !line.is_empty())
+        .and_then(|line| DEFINITION.captures(line))
+        .and_then(|captures| captures.get(1).or(captures.get(2)))
+        .map(|name| name.as_str().to_string())
+        .filter(|name| name != "class")
+}
+
+/// Springの `@RequestMapping(method = RequestMethod.POST)` のメソッド
+fn spring_request_method(args: &str) -> &str {
+    ["GET", "POST", "PUT", "PATCH", "DELETE"]
+        .into_iter()
+        .find(|method| args.contains(&format!("RequestMethod.{}", method)))
+        .unwrap_or("ANY")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(endpoints: &[ApiEndpoint]) -> Vec<(String, String, Option<String>)> {
+        endpoints.iter().map(|e| (e.method.clone(), e.path.clone(), e.handler.clone())).collect()
+    }
+
+    fn row(method: &str, path: &str, handler: Option<&str>) -> (String, String, Option<String>) {
+        (method.to_string(), path.to_string(), handler.map(str::to_string))
+    }
+
+    #[test]
+    fn test_extract_express_and_axum_routes() {
+        let express = "const cache = map.get('/x');\napp.get('/users', auth, users.list);\nrouter.post(\"/users\", async (req, res) => {\n});\n";
+        assert_eq!(
+            summary(&extract_endpoints(Path::new("app.js"), "js", express)),
+            vec![row("GET", "/users", Some("users.list")), row("POST", "/users", None)]
+        );
+
+        let axum = "let app = Router::new()\n    .route(\"/users\", get(list_users).post(handlers::create_user))\n    .route(\"/health\", web::get().to(health));\n";
+        let endpoints = extract_endpoints(Path::new("main.rs"), "rs", axum);
+        assert_eq!(
+            summary(&endpoints),
+            vec![row("GET", "/users", Some("list_users")), row("POST", "/users", Some("create_user")), row("GET", "/health", Some("health"))]
+        );
+        assert_eq!((endpoints[0].framework.as_str(), endpoints[2].framework.as_str()), ("axum", "actix-web"));
+    }
+
+    #[test]
+    fn test_extract_decorator_and_annotation_routes() {
+        let python = "@app.get(\"/items/{id}\")\nasync def read_item(id: int):\n    pass\n\n@bp.route(\"/login\", methods=[\"GET\", \"POST\"])\ndef login():\n    pass\n";
+        assert_eq!(
+            summary(&extract_endpoints(Path::new("api.py"), "py", python)),
+            vec![row("GET", "/items/{id}", Some("read_item")), row("GET", "/login", Some("login")), row("POST", "/login", Some("login"))]
+        );
+
+        let java = "@RestController\n@RequestMapping(\"/api/users\")\npublic class UserController {\n    @GetMapping(\"/{id}\")\n    public User find(@PathVariable long id) {\n    }\n    @PostMapping\n    public User create(@RequestBody User user) {\n    }\n}\n";
+        assert_eq!(
+            summary(&extract_endpoints(Path::new("UserController.java"), "java", java)),
+            vec![row("GET", "/api/users/{id}", Some("find")), row("POST", "/api/users", Some("create"))]
+        );
+
+        let graphql = "type Query {\n  users(first: Int): [User!]!\n  me: User\n}\n\ntype Mutation {\n  login(email: String!): Token\n}\n";
+        assert_eq!(
+            summary(&extract_endpoints(Path::new("schema.graphql"), "graphql", graphql)),
+            vec![row("QUERY", "users", None), row("QUERY", "me", None), row("MUTATION", "login", None)]
+        );
+    }
+}
@@ -0,0 +1,417 @@
+/*!
+ * 全文検索インデックス（tantivy）
+ *
+ * ソースコードをチャンクに分割してtantivyでインデックス化し、BM25で検索する
+ * - `index.provider = "tantivy"` のとき `analyze_repo` で構築
+ * - `Index::save` でインデックスファイルの隣（`<path>.search/`）に保存
+ * - `Index::load` で保存済みの検索インデックスを開く
+ *
+ * 主な仕様:
+ * - チャンク分割・トークナイザーはchunkingモジュールと共通（英数字は単語単位、日本語は文字bigram）
+ * - 抜粋はヒットした語を `**` で強調したMarkdown
+ *
+ * 制限事項:
+ * - インデックス作成後にファイルを変更しても検索インデックスは更新されない（再解析が必要）
+ */
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use tantivy::collector::TopDocs;
+use tantivy::directory::Directory;
+use tantivy::query::QueryParser;
+use tantivy::schema::{
+    Field, IndexRecordOption, NumericOptions, Schema, TantivyDocument, TextFieldIndexing, TextOptions, Value, STORED,
+    STRING,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::tokenizer::{TextAnalyzer, Token, TokenStream, Tokenizer};
+use tantivy::{doc, IndexReader, ReloadPolicy};
+
+use crate::chunking::{self, chunk_content, Chunking};
+use crate::{Index, SearchHit};
+
+/// トークナイザー名
+const TOKENIZER_NAME: &str = "deeprepo";
+/// チャンク設定の保存先（検索インデックスのディレクトリ内）
+const CHUNKING_FILE: &str = "deeprepo-chunking.json";
+/// tantivyのメタ情報ファイル
+const META_FILE: &str = "meta.json";
+/// インデックス作成時のメモリ上限
+const WRITER_MEMORY_BYTES: usize = 50_000_000;
+/// 抜粋の最大文字数
+const SNIPPET_MAX_CHARS: usize = 160;
+
+/// 検索インデックス
+pub struct SearchIndex {
+    index: tantivy::Index,
+    reader: IndexReader,
+    path_field: Field,
+    start_line_field: Field,
+    end_line_field: Field,
+    body_field: Field,
+    chunking: Chunking,
+}
+
+impl std::fmt::Debug for SearchIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchIndex")
+            .field("chunking", &self.chunking)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SearchIndex {
+    /// インデックスのファイル内容から検索インデックスを構築
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `chunk_tokens` - 1チャンクあたりのトークン数
+    /// * `chunk_overlap` - 隣接チャンクで重ねるトークン数
+    /// * `dir` - 保存先ディレクトリ（Noneの場合はメモリ上に作成）
+    ///
+    /// # 戻り値
+    /// * `Result<SearchIndex>` - 検索インデックス、またはエラー
+    pub(crate) fn build(index: &Index, chunk_tokens: usize, chunk_overlap: usize, dir: Option<&Path>) -> Result<Self> {
+        let chunking = Chunking::new(chunk_tokens, chunk_overlap);
+        let schema = build_schema();
+        let tantivy_index = match dir {
+            Some(dir) => {
+                prepare_dir(dir, chunking)?;
+                tantivy::Index::create_in_dir(dir, schema.clone())
+                    .with_context(|| format!("検索インデックスを作成できませんでした: {:?}", dir))?
+            }
+            None => tantivy::Index::create_in_ram(schema.clone()),
+        };
+        register_tokenizer(&tantivy_index);
+
+        let search_index = Self::from_index(tantivy_index, chunking)?;
+        let mut writer = search_index
+            .index
+            .writer_with_num_threads(1, WRITER_MEMORY_BYTES)
+            .context("検索インデックスのライターを作成できませんでした")?;
+
+        for file in &index.files {
+            let Some(content) = index.file_content(file) else {
+                continue;
+            };
+            let path = file.path.to_string_lossy().to_string();
+            for chunk in chunk_content(&content, chunking) {
+                writer.add_document(doc!(
+                    search_index.path_field => path.clone(),
+                    search_index.start_line_field => chunk.start_line as u64,
+                    search_index.end_line_field => chunk.end_line as u64,
+                    search_index.body_field => chunk.text,
+                ))?;
+            }
+        }
+        writer.commit().context("検索インデックスのコミットに失敗しました")?;
+        search_index.reader.reload()?;
+
+        Ok(search_index)
+    }
+
+    /// 保存済みの検索インデックスを開く
+    ///
+    /// # 引数
+    /// * `dir` - 検索インデックスのディレクトリ
+    ///
+    /// # 戻り値
+    /// * `Result<SearchIndex>` - 検索インデックス、またはエラー
+    pub(crate) fn open(dir: &Path) -> Result<Self> {
+        let chunking: Chunking = serde_json::from_slice(
+            &std::fs::read(dir.join(CHUNKING_FILE))
+                .with_context(|| format!("検索インデックスの設定を読み込めませんでした: {:?}", dir))?,
+        )?;
+        let tantivy_index = tantivy::Index::open_in_dir(dir)
+            .with_context(|| format!("検索インデックスを開けませんでした: {:?}", dir))?;
+        register_tokenizer(&tantivy_index);
+        Self::from_index(tantivy_index, chunking)
+    }
+
+    /// 構築済みのセグメントを別の場所に保存する
+    ///
+    /// 再構築はせず、メタ情報とセグメントのファイルをそのまま書き出す。
+    /// 保存先が読み込み元と同じでもよいように、すべて読み込んでから書き出す。
+    ///
+    /// # 引数
+    /// * `dir` - 保存先ディレクトリ
+    ///
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub(crate) fn save(&self, dir: &Path) -> Result<()> {
+        let directory = self.index.directory();
+        let mut files = vec![PathBuf::from(META_FILE)];
+        for meta in self.index.searchable_segment_metas()? {
+            files.extend(meta.list_files());
+        }
+        // セグメントの状態によって存在しないファイル（削除情報など）も含まれる
+        let contents = files
+            .into_iter()
+            .filter(|file| directory.exists(file).unwrap_or(false))
+            .map(|file| {
+                let bytes = directory
+                    .atomic_read(&file)
+                    .with_context(|| format!("検索インデックスのファイルを読み込めませんでした: {:?}", file))?;
+                Ok((file, bytes))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        prepare_dir(dir, self.chunking)?;
+        for (file, bytes) in contents {
+            std::fs::write(dir.join(&file), bytes)
+                .with_context(|| format!("検索インデックスを保存できませんでした: {:?}", dir.join(&file)))?;
+        }
+        Ok(())
+    }
+
+    /// BM25で検索
+    ///
+    /// # 引数
+    /// * `query` - 検索クエリ
+    /// * `k` - 返す結果の最大数
+    /// * `allowed` - 対象とするファイルのパス（Noneの場合はすべて）
+    ///
+    /// # 戻り値
+    /// * `Result<Vec<SearchHit>>` - チャンク単位の検索結果、またはエラー
+    pub(crate) fn search(&self, query: &str, k: usize, allowed: Option<&HashSet<String>>) -> Result<Vec<SearchHit>> {
+        let searcher = self.reader.searcher();
+        let parser = QueryParser::for_index(&self.index, vec![self.body_field]);
+        // 利用者の入力をそのまま受け付けるため、構文エラーは無視して解釈できた部分で検索
+        let (query, _errors) = parser.parse_query_lenient(query);
+
+        let mut snippets = SnippetGenerator::create(&searcher, &*query, self.body_field)?;
+        snippets.set_max_num_chars(SNIPPET_MAX_CHARS);
+
+        // 絞り込む場合は一致したチャンクを順位順に走査し、対象のファイルのものをk件集める
+        let limit = match allowed {
+            Some(_) => (searcher.num_docs() as usize).max(1),
+            None => k.max(1),
+        };
+        let mut hits = Vec::new();
+        for (score, address) in searcher.search(&*query, &TopDocs::with_limit(limit))? {
+            if hits.len() >= k {
+                break;
+            }
+            let doc: TantivyDocument = searcher.doc(address)?;
+            let path = doc.get_first(self.path_field).and_then(|v| v.as_str()).unwrap_or_default();
+            if allowed.is_some_and(|allowed| !allowed.contains(path)) {
+                continue;
+            }
+            let start_line = doc.get_first(self.start_line_field).and_then(|v| v.as_u64());
+            let end_line = doc.get_first(self.end_line_field).and_then(|v| v.as_u64());
+
+            let snippet = snippets.snippet_from_doc(&doc);
+            hits.push(SearchHit {
+                path: path.to_string(),
+                score: score as f64,
+                excerpt: highlight(snippet.fragment(), snippet.highlighted()),
+                start_line: start_line.map(|l| l as usize),
+                end_line: end_line.map(|l| l as usize),
+                module: None,
+                matches: Vec::new(),
+                signature: None,
+            });
+        }
+
+        Ok(hits)
+    }
+
+    /// tantivyのインデックスからフィールドとリーダーを取得
+    fn from_index(index: tantivy::Index, chunking: Chunking) -> Result<Self> {
+        let schema = index.schema();
+        let field = |name: &str| schema.get_field(name).context("検索インデックスのスキーマが不正です");
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .context("検索インデックスのリーダーを作成できませんでした")?;
+
+        Ok(Self {
+            path_field: field("path")?,
+            start_line_field: field("start_line")?,
+            end_line_field: field("end_line")?,
+            body_field: field("body")?,
+            index,
+            reader,
+            chunking,
+        })
+    }
+}
+
+/// 検索インデックスの保存先（インデックスファイルのパス + `.search`）
+///
+/// # 引数
+/// * `index_path` - インデックスファイルのパス
+///
+/// # 戻り値
+/// * `PathBuf` - 検索インデックスのディレクトリ
+pub(crate) fn search_dir(index_path: &Path) -> PathBuf {
+    let mut dir = index_path.as_os_str().to_owned();
+    dir.push(".search");
+    PathBuf::from(dir)
+}
+
+/// 保存先ディレクトリを空にしてチャンク設定を書き出す
+fn prepare_dir(dir: &Path, chunking: Chunking) -> Result<()> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)
+            .with_context(|| format!("既存の検索インデックスを削除できませんでした: {:?}", dir))?;
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(CHUNKING_FILE), serde_json::to_vec(&chunking)?)?;
+    Ok(())
+}
+
+/// スキーマを作成
+fn build_schema() -> Schema {
+    let mut builder = Schema::builder();
+    builder.add_text_field("path", STRING | STORED);
+    builder.add_u64_field("start_line", NumericOptions::default().set_stored());
+    builder.add_u64_field("end_line", NumericOptions::default().set_stored());
+    let body_options = TextOptions::default()
+        .set_indexing_options(
+            TextFieldIndexing::default()
+                .set_tokenizer(TOKENIZER_NAME)
+                .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+        )
+        .set_stored();
+    builder.add_text_field("body", body_options);
+    builder.build()
+}
+
+/// トークナイザーを登録
+fn register_tokenizer(index: &tantivy::Index) {
+    index
+        .tokenizers()
+        .register(TOKENIZER_NAME, TextAnalyzer::from(CodeTokenizer));
+}
+
+/// テキストをtantivyのトークンに変換
+fn tokenize(text: &str) -> Vec<Token> {
+    chunking::tokenize(text)
+        .into_iter()
+        .enumerate()
+        .map(|(position, span)| Token {
+            offset_from: span.from,
+            offset_to: span.to,
+            position,
+            text: span.text,
+            position_length: 1,
+        })
+        .collect()
+}
+
+/// ソースコード・日本語混在テキスト用のトークナイザー
+#[derive(Clone)]
+struct CodeTokenizer;
+
+/// `CodeTokenizer` のトークン列
+struct CodeTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl Tokenizer for CodeTokenizer {
+    type TokenStream<'a> = CodeTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CodeTokenStream {
+        CodeTokenStream {
+            tokens: tokenize(text),
+            index: 0,
+        }
+    }
+}
+
+impl TokenStream for CodeTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// ヒット箇所を `**` で強調（bigramの重なりは結合する）
+fn highlight(fragment: &str, ranges: &[std::ops::Range<usize>]) -> String {
+    let mut merged: Vec<std::ops::Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range.clone()),
+        }
+    }
+
+    let mut result = String::new();
+    let mut pos = 0;
+    for range in merged {
+        result.push_str(&fragment[pos..range.start]);
+        result.push_str("**");
+        result.push_str(&fragment[range.start..range.end]);
+        result.push_str("**");
+        pos = range.end;
+    }
+    result.push_str(&fragment[pos..]);
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+
+    #[test]
+    fn test_search_ranks_with_bm25_and_highlights() {
+        let file = |path: &str, content: &str| FileInfo {
+            path: PathBuf::from(path),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            files: vec![
+                file("src/graph.rs", "// モジュールグラフを生成する\nfn build_graph() {}\n\nfn render_graph() {}\n"),
+                file("src/main.rs", "fn main() {\n    // 設定を読み込む\n    build_graph();\n}\n"),
+            ],
+            ..Default::default()
+        };
+
+        let search = SearchIndex::build(&index, 800, 120, None).unwrap();
+        let hits = search.search("グラフ", 10, None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "src/graph.rs");
+        assert!(hits[0].excerpt.contains("**グラフ**"), "{}", hits[0].excerpt);
+
+        let hits = search.search("build_graph", 10, None).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].start_line, hits[0].end_line), (Some(1), Some(4)));
+
+        let allowed = HashSet::from(["src/main.rs".to_string()]);
+        let hits = search.search("build_graph", 10, Some(&allowed)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "src/main.rs");
+
+        // 保存したセグメントをそのまま開ける（保存時にファイル内容は参照しない）
+        let dir = std::env::temp_dir().join(format!("deeprepo-search-{}", uuid::Uuid::new_v4()));
+        let saved = dir.join("index.search");
+        search.save(&saved).unwrap();
+        let opened = SearchIndex::open(&saved).unwrap();
+        assert_eq!(opened.chunking, search.chunking);
+        let hits = opened.search("build_graph", 10, None).unwrap();
+        assert_eq!(hits.len(), 2);
+
+        // 読み込み元と同じ場所にも保存し直せる
+        opened.save(&saved).unwrap();
+        assert_eq!(SearchIndex::open(&saved).unwrap().search("グラフ", 10, None).unwrap().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+/*!
+ * 外部サービスの呼び出しの検出
+ *
+ * importしているクライアントライブラリから、コードが呼び出す外部サービス（クラウド・SaaS・データベース・メッセージキュー・HTTP API）を推定する
+ * - WikiのFAQ「どの外部サービスを呼び出していますか？」に使う
+ *
+ * 主な仕様:
+ * - import文字列の先頭がライブラリ名に一致するか（`boto3`・`@aws-sdk/client-s3`・`reqwest::Client`・`github.com/lib/pq` など）で判定
+ *   - ライブラリ名が `_`・`-`・`/` で終わるものは前方一致（`aws_sdk_s3` など）
+ * - サービスごとに、検出したライブラリと、importしているファイル（テストを除く、パス順）をまとめる
+ * - 接続先を設定する環境変数（.env.exampleの `*_URL`・`*_HOST`・`*_ENDPOINT`・`*_API_KEY` など）も併せて挙げる
+ *
+ * 制限事項:
+ * - `SERVICE_LIBRARIES` にないライブラリ・直接のHTTP呼び出し先のURLは判定しない
+ * - 同じ名前の内部モジュール（`redis` という名前の自作モジュールなど）も外部サービスとして数える
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crate::Index;
+
+/// 外部サービスの種類とクライアントライブラリ（種類の順に並べる）
+const SERVICE_LIBRARIES: &[(&str, &str, &[&str])] = &[
+    ("AWS", "cloud", &["aws_sdk_", "aws-sdk", "@aws-sdk/", "boto3", "botocore", "rusoto_", "github.com/aws/aws-sdk-go", "github.com/aws/aws-sdk-go-v2"]),
+    ("Google Cloud", "cloud", &["@google-cloud/", "google.cloud", "google_cloud_", "cloud.google.com/go"]),
+    ("Azure", "cloud", &["@azure/", "azure", "azure_", "github.com/Azure/azure-sdk-for-go"]),
+    ("Firebase", "cloud", &["firebase", "firebase-admin", "firebase_admin"]),
+    ("Stripe", "saas", &["stripe", "async_stripe", "github.com/stripe/stripe-go"]),
+    ("Twilio", "saas", &["twilio"]),
+    ("SendGrid", "saas", &["sendgrid", "@sendgrid/"]),
+    ("Slack", "saas", &["@slack/", "slack_sdk", "slack_bolt"]),
+    ("OpenAI", "saas", &["openai", "async_openai"]),
+    ("Anthropic", "saas", &["anthropic", "@anthropic-ai/"]),
+    ("PostgreSQL", "database", &["pg", "postgres", "tokio_postgres", "psycopg", "psycopg2", "asyncpg", "github.com/lib/pq", "github.com/jackc/pgx"]),
+    ("MySQL", "database", &["mysql", "mysql2", "pymysql", "github.com/go-sql-driver/mysql"]),
+    ("MongoDB", "database", &["mongodb", "mongoose", "pymongo", "motor", "go.mongodb.org/mongo-driver"]),
+    ("Redis", "database", &["redis", "ioredis", "github.com/redis/go-redis", "github.com/go-redis/redis"]),
+    ("Elasticsearch", "database", &["elasticsearch", "@elastic/elasticsearch"]),
+    ("Kafka", "queue", &["kafka", "kafkajs", "rdkafka", "confluent_kafka", "github.com/segmentio/kafka-go"]),
+    ("RabbitMQ", "queue", &["amqplib", "lapin", "pika", "github.com/rabbitmq/amqp091-go"]),
+    ("HTTP API", "http", &["reqwest", "ureq", "axios", "node-fetch", "got", "requests", "httpx", "aiohttp"]),
+];
+
+/// 接続先の設定とみなす環境変数の名前の末尾
+const ENDPOINT_SUFFIXES: &[&str] = &["_URL", "_URI", "_HOST", "_ENDPOINT", "_DSN", "_API_KEY", "_TOKEN"];
+
+/// コードが呼び出す外部サービス
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExternalService {
+    /// サービス名（AWS・PostgreSQL・HTTP API など）
+    pub name: String,
+    /// 種類（cloud|saas|database|queue|http）
+    pub kind: String,
+    /// 検出したクライアントライブラリ（import文字列の先頭、名前順）
+    pub libraries: Vec<String>,
+    /// ライブラリをimportしているファイル（パス順）
+    pub files: Vec<PathBuf>,
+}
+
+impl ExternalService {
+    /// 種類の表示名
+    pub fn kind_label(&self) -> &'static str {
+        match self.kind.as_str() {
+            "cloud" => "クラウド",
+            "saas" => "SaaS",
+            "database" => "データベース",
+            "queue" => "メッセージキュー",
+            "http" => "HTTP",
+            _ => "その他",
+        }
+    }
+}
+
+/// インデックスから外部サービスの呼び出しを検出
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `Vec<ExternalService>` - `SERVICE_LIBRARIES` の順の外部サービス（importされていないものは含まない）
+pub(crate) fn detect_services(index: &Index) -> Vec<ExternalService> {
+    let mut found: BTreeMap<usize, (BTreeSet<String>, BTreeSet<PathBuf>)> = BTreeMap::new();
+    for file in index.files.iter().filter(|f| !f.is_test) {
+        for import in &file.dependencies {
+            let matched = SERVICE_LIBRARIES.iter().enumerate().find_map(|(i, (_, _, libraries))| {
+                libraries.iter().find(|library| matches_library(import, library)).map(|library| (i, library))
+            });
+            if let Some((i, library)) = matched {
+                let entry = found.entry(i).or_default();
+                entry.0.insert(library_name(import, library));
+                entry.1.insert(file.path.clone());
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(i, (libraries, files))| {
+            let (name, kind, _) = SERVICE_LIBRARIES[i];
+            ExternalService {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                libraries: libraries.into_iter().collect(),
+                files: files.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+/// 接続先を設定する環境変数（.env.exampleなどの設定ファイルの項目名、記載順）
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `Vec<(PathBuf, String)>` - 設定ファイルのパスと環境変数名
+pub(crate) fn endpoint_variables(index: &Index) -> Vec<(PathBuf, String)> {
+    index
+        .config_files
+        .iter()
+        .filter(|file| file.path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(".env")))
+        .flat_map(|file| {
+            file.settings
+                .iter()
+                .filter(|s| ENDPOINT_SUFFIXES.iter().any(|suffix| s.key.to_ascii_uppercase().ends_with(suffix)))
+                .map(|s| (file.path.clone(), s.key.clone()))
+        })
+        .collect()
+}
+
+/// import文字列がライブラリのものか（`_`・`-`・`/` で終わるライブラリ名は前方一致、それ以外は区切りまで一致）
+fn matches_library(import: &str, library: &str) -> bool {
+    if library.ends_with(['_', '-', '/']) {
+        return import.starts_with(library) && import.len() > library.len();
+    }
+    match import.strip_prefix(library) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::") || rest.starts_with(['/', '.']),
+        None => false,
+    }
+}
+
+/// 一致したimport文字列のライブラリ名（前方一致の場合はパッケージ名まで、`aws_sdk_s3::Client` → `aws_sdk_s3`）
+fn library_name(import: &str, library: &str) -> String {
+    if !library.ends_with(['_', '-', '/']) {
+        return library.to_string();
+    }
+    let rest = &import[library.len()..];
+    let end = rest.find([':', '/', '.']).unwrap_or(rest.len());
+    format!("{}{}", library, &rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigFile, ConfigSetting, FileInfo};
+
+    #[test]
+    fn test_matches_library() {
+        assert!(matches_library("boto3", "boto3"));
+        assert!(matches_library("reqwest::Client", "reqwest"));
+        assert!(matches_library("@aws-sdk/client-s3", "@aws-sdk/"));
+        assert!(matches_library("aws_sdk_s3::Client", "aws_sdk_"));
+        assert!(matches_library("github.com/lib/pq", "github.com/lib/pq"));
+        assert!(!matches_library("pgvector", "pg"));
+        assert!(!matches_library("requests_cache", "requests"));
+        assert_eq!(library_name("aws_sdk_s3::Client", "aws_sdk_"), "aws_sdk_s3");
+        assert_eq!(library_name("@aws-sdk/client-s3", "@aws-sdk/"), "@aws-sdk/client-s3");
+    }
+
+    #[test]
+    fn test_detect_services_and_endpoint_variables() {
+        let file = |path: &str, imports: &[&str], is_test: bool| FileInfo {
+            path: PathBuf::from(path),
+            dependencies: imports.iter().map(|s| s.to_string()).collect(),
+            is_test,
+            ..Default::default()
+        };
+        let index = Index {
+            files: vec![
+                file("src/storage.py", &["boto3", "os"], false),
+                file("src/billing.py", &["stripe", "requests"], false),
+                file("src/db.py", &["psycopg2.extras"], false),
+                file("tests/test_storage.py", &["boto3", "moto"], true),
+            ],
+            config_files: vec![ConfigFile {
+                path: PathBuf::from(".env.example"),
+                settings: vec![
+                    ConfigSetting { key: "DATABASE_URL".to_string(), ..Default::default() },
+                    ConfigSetting { key: "LOG_LEVEL".to_string(), ..Default::default() },
+                    ConfigSetting { key: "STRIPE_API_KEY".to_string(), ..Default::default() },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let services = detect_services(&index);
+        let names: Vec<&str> = services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["AWS", "Stripe", "PostgreSQL", "HTTP API"]);
+        assert_eq!(services[0].files, [PathBuf::from("src/storage.py")]);
+        assert_eq!(services[2].libraries, ["psycopg2"]);
+        assert_eq!(services[2].kind_label(), "データベース");
+
+        let variables: Vec<String> = endpoint_variables(&index).into_iter().map(|(_, key)| key).collect();
+        assert_eq!(variables, ["DATABASE_URL", "STRIPE_API_KEY"]);
+    }
+}
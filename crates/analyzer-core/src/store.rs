@@ -0,0 +1,371 @@
+/*!
+ * インデックス保存形式
+ *
+ * 大規模リポジトリ向けのコンパクトなインデックスファイルを読み書きする
+ * - ヘッダー: 内容を除いた構造インデックス（zstd圧縮JSON）
+ * - 本体: ファイル内容をハッシュで参照するzstd圧縮ブロブ
+ *
+ * 主な仕様:
+ * - ファイル形式: マジック（8バイト）+ ヘッダー長（u64 LE）+ ヘッダー + ブロブ領域
+ * - 同一内容のファイルは1つのブロブを共有（SHA-256で重複排除）
+ * - 読み込み時はヘッダーのみを展開し、内容は必要になった時点でブロブを読む
+ * - マジックがないファイルは従来のJSONインデックスとして読み込む
+ * - 解析直後のインデックスは内容を保持せず、解析したファイルを作業ツリーから読む
+ * - 保存する内容は `index.content` で選択（full: 全文、excerpts: 先頭 `EXCERPT_LINES` 行の抜粋、hash: ハッシュのみ）
+ * - excerpts・hashでは全文を作業ツリーから読み直し、保存時のハッシュと一致した場合のみ使う
+ * - ブロブには機密情報を置き換えた内容（`Index::redaction`）を格納する（作業ツリーとの照合には元の内容のハッシュを使う）
+ *   （一致しない場合、excerptsは抜粋を返し、hashは読み込みエラーとする）
+ * - 要約・検索向けに、内容を全体を展開せずに逐次読み込める（`open`、streamingモジュール）
+ *
+ * 制限事項:
+ * - 保存後に元ファイルが移動・変更された場合、遅延読み込みは失敗する
+ * - 保存前のインデックスは、解析後に変更されたファイルの変更後の内容を返す
+ * - excerpts・hashでは全文検索インデックス（本文を格納する）を保存しない
+ */
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+
+use crate::streaming::ContentReader;
+use crate::Index;
+
+/// インデックスファイルのマジック
+const MAGIC: &[u8; 8] = b"DRSIDX01";
+/// zstdの圧縮レベル
+const COMPRESSION_LEVEL: i32 = 3;
+/// `index.content = "excerpts"` で保存する先頭の行数
+const EXCERPT_LINES: usize = 40;
+
+/// ブロブ領域内の位置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct BlobRef {
+    offset: u64,
+    len: u64,
+}
+
+/// ヘッダー
+#[derive(Serialize, Deserialize)]
+struct StoreHeader {
+    index: Index,
+    /// ファイルパス → 内容（全文）のハッシュ
+    contents: HashMap<PathBuf, String>,
+    /// ファイルパス → ブロブに格納した内容（置き換え・抜粋後）のハッシュ（記録していない古いインデックスは `contents` と同じ）
+    #[serde(default)]
+    stored: HashMap<PathBuf, String>,
+    /// 内容のハッシュ → ブロブ位置
+    blobs: HashMap<String, BlobRef>,
+    /// ブロブに格納した内容（full|excerpts|hash、記録していない古いインデックスはfull）
+    #[serde(default = "default_content_mode")]
+    content: String,
+}
+
+fn default_content_mode() -> String {
+    "full".to_string()
+}
+
+/// 遅延読み込み用のコンテンツストア
+#[derive(Debug)]
+pub struct ContentStore {
+    source: ContentSource,
+}
+
+/// ファイル内容の読み込み元
+#[derive(Debug)]
+enum ContentSource {
+    /// 保存したインデックスファイルのブロブ
+    Blobs {
+        path: PathBuf,
+        data_offset: u64,
+        contents: HashMap<PathBuf, String>,
+        stored: HashMap<PathBuf, String>,
+        blobs: HashMap<String, BlobRef>,
+        /// ブロブに格納した内容（full|excerpts|hash）
+        content: String,
+    },
+    /// 解析直後のインデックス（解析したファイルを作業ツリーから読む）
+    WorkingTree { files: HashSet<PathBuf> },
+}
+
+impl ContentStore {
+    /// 作業ツリーから内容を読むストアを作成
+    ///
+    /// 解析中はファイル内容をインデックスに保持せず、必要になった時点で元のファイルを読む。
+    ///
+    /// # 引数
+    /// * `files` - 解析したファイルのパス
+    ///
+    /// # 戻り値
+    /// * `Self` - ストア
+    pub(crate) fn working_tree(files: impl IntoIterator<Item = PathBuf>) -> Self {
+        Self {
+            source: ContentSource::WorkingTree { files: files.into_iter().collect() },
+        }
+    }
+
+    /// ファイル内容を読み込む
+    ///
+    /// # 引数
+    /// * `file_path` - インデックス内のファイルパス
+    ///
+    /// # 戻り値
+    /// * `Result<Option<String>>` - 内容（保存されていない場合はNone）、またはエラー
+    pub(crate) fn read(&self, file_path: &Path) -> Result<Option<String>> {
+        let (path, data_offset, blob) = match &self.source {
+            ContentSource::WorkingTree { files } => {
+                if !files.contains(file_path) {
+                    return Ok(None);
+                }
+                let bytes = std::fs::read(file_path)
+                    .with_context(|| format!("ファイル読み込みエラー: {:?}", file_path))?;
+                // 解析時と同じくShift_JIS・EUC-JPはUTF-8に変換する
+                return Ok(Some(crate::encoding::decode_lossy(&bytes)));
+            }
+            ContentSource::Blobs { path, data_offset, contents, stored, blobs, content } => {
+                let Some(hash) = contents.get(file_path) else {
+                    return Ok(None);
+                };
+                if content != "full" {
+                    if let Some(text) = read_unchanged(file_path, hash) {
+                        return Ok(Some(text));
+                    }
+                    if content == "hash" {
+                        anyhow::bail!(
+                            "インデックスの保存後に変更・削除されたため内容を読めません（index.content = \"hash\"）: {:?}",
+                            file_path
+                        );
+                    }
+                }
+                match blobs.get(stored.get(file_path).unwrap_or(hash)) {
+                    Some(b) => (path, *data_offset, *b),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let mut file = File::open(path)
+            .with_context(|| format!("インデックスファイルを開けませんでした: {:?}", path))?;
+        file.seek(SeekFrom::Start(data_offset + blob.offset))?;
+        let mut compressed = vec![0u8; blob.len as usize];
+        file.read_exact(&mut compressed)
+            .context("インデックスのブロブ読み込みに失敗しました")?;
+
+        let bytes = zstd::decode_all(compressed.as_slice()).context("ブロブの展開に失敗しました")?;
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// ファイル内容を逐次読み込むために開く（全体をメモリに展開しない）
+    ///
+    /// 作業ツリーのファイルはそのまま、ブロブはzstdを展開しながら読む。
+    /// `index.content` が full 以外の場合は作業ツリーのファイルを逐次ハッシュし、
+    /// 保存時から変わっていなければそのファイルを、変わっていれば `read` と同じ内容（抜粋など）を返す。
+    ///
+    /// # 引数
+    /// * `file_path` - インデックス内のファイルパス
+    ///
+    /// # 戻り値
+    /// * `Result<Option<ContentReader>>` - 読み込み元（保存されていない場合はNone）、またはエラー
+    pub(crate) fn open(&self, file_path: &Path) -> Result<Option<ContentReader<'static>>> {
+        let (path, data_offset, blob) = match &self.source {
+            ContentSource::WorkingTree { files } => {
+                if !files.contains(file_path) {
+                    return Ok(None);
+                }
+                let file = File::open(file_path).with_context(|| format!("ファイル読み込みエラー: {:?}", file_path))?;
+                return Ok(Some(Box::new(BufReader::new(file))));
+            }
+            ContentSource::Blobs { path, data_offset, contents, stored, blobs, content } => {
+                let Some(hash) = contents.get(file_path) else {
+                    return Ok(None);
+                };
+                if content != "full" {
+                    if file_hash(file_path).is_some_and(|h| &h == hash) {
+                        let file = File::open(file_path).with_context(|| format!("ファイル読み込みエラー: {:?}", file_path))?;
+                        return Ok(Some(Box::new(BufReader::new(file))));
+                    }
+                    // 文字コードの変換が必要なファイル・変更されたファイルは全体を読む
+                    return Ok(self.read(file_path)?.map(|text| Box::new(Cursor::new(text.into_bytes())) as ContentReader));
+                }
+                match blobs.get(stored.get(file_path).unwrap_or(hash)) {
+                    Some(b) => (path, *data_offset, *b),
+                    None => return Ok(None),
+                }
+            }
+        };
+
+        let mut file = File::open(path)
+            .with_context(|| format!("インデックスファイルを開けませんでした: {:?}", path))?;
+        file.seek(SeekFrom::Start(data_offset + blob.offset))?;
+        let decoder = zstd::stream::read::Decoder::new(file.take(blob.len)).context("ブロブの展開に失敗しました")?;
+        Ok(Some(Box::new(BufReader::new(decoder))))
+    }
+
+    /// 格納されているブロブ数（重複排除後）
+    #[cfg(test)]
+    pub(crate) fn blob_count(&self) -> usize {
+        match &self.source {
+            ContentSource::Blobs { blobs, .. } => blobs.len(),
+            ContentSource::WorkingTree { .. } => 0,
+        }
+    }
+}
+
+/// インデックスをコンパクト形式で保存
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `path` - 保存先
+/// * `content` - ブロブに格納する内容（full|excerpts|hash）
+///
+/// # 戻り値
+/// * `Result<()>` - 成功、またはエラー
+pub(crate) fn save(index: &Index, path: &Path, content: &str) -> Result<()> {
+    if !["full", "excerpts", "hash"].contains(&content) {
+        anyhow::bail!("不明な内容の保存方式: {}（full|excerpts|hash）", content);
+    }
+    let mut contents = HashMap::new();
+    let mut stored = HashMap::new();
+    let mut blobs = HashMap::new();
+    let mut data = Vec::new();
+    let mut structural = index.clone();
+
+    for file in &mut structural.files {
+        let text = match file.content.take() {
+            Some(c) => c,
+            None => match &index.store {
+                Some(store) => match store.read(&file.path)? {
+                    Some(c) => c,
+                    None => continue,
+                },
+                None => continue,
+            },
+        };
+
+        contents.insert(file.path.clone(), content_hash(&text));
+        if content == "hash" {
+            continue;
+        }
+        // 機密情報は置き換えてから格納する（作業ツリーの内容は読み込み時に置き換える）
+        let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+        let (text, _) = index.redaction.redact(relative, &text);
+        let text = match content {
+            "excerpts" => excerpt(&text),
+            _ => text,
+        };
+        let hash = content_hash(&text);
+        stored.insert(file.path.clone(), hash.clone());
+        if blobs.contains_key(&hash) {
+            continue;
+        }
+        let compressed = zstd::encode_all(text.as_bytes(), COMPRESSION_LEVEL)
+            .context("ファイル内容の圧縮に失敗しました")?;
+        blobs.insert(
+            hash,
+            BlobRef {
+                offset: data.len() as u64,
+                len: compressed.len() as u64,
+            },
+        );
+        data.extend_from_slice(&compressed);
+    }
+
+    let header = StoreHeader {
+        index: structural,
+        contents,
+        stored,
+        blobs,
+        content: content.to_string(),
+    };
+    let header_json = serde_json::to_vec(&header)?;
+    let header_bytes = zstd::encode_all(header_json.as_slice(), COMPRESSION_LEVEL)
+        .context("ヘッダーの圧縮に失敗しました")?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = File::create(path)
+        .with_context(|| format!("インデックスファイルを作成できませんでした: {:?}", path))?;
+    file.write_all(MAGIC)?;
+    file.write_all(&(header_bytes.len() as u64).to_le_bytes())?;
+    file.write_all(&header_bytes)?;
+    file.write_all(&data)?;
+
+    Ok(())
+}
+
+/// インデックスを読み込む（内容は遅延読み込み）
+///
+/// # 引数
+/// * `path` - インデックスファイル
+///
+/// # 戻り値
+/// * `Result<Index>` - インデックス、またはエラー
+pub(crate) fn load(path: &Path) -> Result<Index> {
+    let mut file = File::open(path)
+        .with_context(|| format!("インデックスファイルを開けませんでした: {:?}", path))?;
+
+    let mut magic = [0u8; 8];
+    if file.read_exact(&mut magic).is_err() || &magic != MAGIC {
+        // 従来のJSONインデックス
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("インデックスファイルの読み込みに失敗しました: {:?}", path))?;
+        return serde_json::from_str(&json).context("インデックスの解析に失敗しました");
+    }
+
+    let mut len_bytes = [0u8; 8];
+    file.read_exact(&mut len_bytes)?;
+    let header_len = u64::from_le_bytes(len_bytes);
+    let mut header_bytes = vec![0u8; header_len as usize];
+    file.read_exact(&mut header_bytes)
+        .context("インデックスのヘッダー読み込みに失敗しました")?;
+
+    let header_json = zstd::decode_all(header_bytes.as_slice()).context("ヘッダーの展開に失敗しました")?;
+    let header: StoreHeader =
+        serde_json::from_slice(&header_json).context("インデックスの解析に失敗しました")?;
+
+    let mut index = header.index;
+    index.store = Some(std::sync::Arc::new(ContentStore {
+        source: ContentSource::Blobs {
+            path: path.to_path_buf(),
+            data_offset: (MAGIC.len() + 8) as u64 + header_len,
+            contents: header.contents,
+            stored: header.stored,
+            blobs: header.blobs,
+            content: header.content,
+        },
+    }));
+
+    Ok(index)
+}
+
+/// 作業ツリーのファイルを読み、保存時から変わっていなければ内容を返す
+fn read_unchanged(file_path: &Path, hash: &str) -> Option<String> {
+    let bytes = std::fs::read(file_path).ok()?;
+    let text = crate::encoding::decode_lossy(&bytes);
+    (content_hash(&text) == hash).then_some(text)
+}
+
+/// 作業ツリーのファイルを逐次読んでハッシュを求める（UTF-8のファイルは内容のハッシュと一致する）
+fn file_hash(file_path: &Path) -> Option<String> {
+    let mut file = File::open(file_path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 先頭 `EXCERPT_LINES` 行の抜粋
+fn excerpt(content: &str) -> String {
+    content.split_inclusive('\n').take(EXCERPT_LINES).collect()
+}
+
+/// 内容のハッシュ（SHA-256の16進文字列）
+fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,160 @@
+/*!
+ * ファイル内容の逐次読み込み
+ *
+ * 要約・検索でファイル全体をメモリに展開せず、行・行単位の窓ごとに読む
+ * - 作業ツリーのファイル、保存したインデックスのブロブ（zstdの展開）のどちらからも逐次読む（`ContentStore::open`）
+ * - 内容を保持しないインデックス（`index.content = "hash"` など）でも、MCPサーバーの常駐メモリを小さく保つ
+ *
+ * 主な仕様:
+ * - 行は改行を除いた文字列で、`str::lines` と同じく末尾の `\r` を除く（UTF-8として不正なバイトは置換）
+ * - 窓は行単位で、行の重み（既定はバイト数）の合計が上限以内になるようにまとめる
+ *
+ * 制限事項:
+ * - 1行が上限を超える場合も行の途中では分割しない
+ * - Shift_JIS・EUC-JPのファイルは逐次変換できないため、全体を読み込んでから変換する（`Index::open_content`）
+ */
+
+use std::io::BufRead;
+
+/// 逐次読み込みの読み込み元
+pub type ContentReader<'a> = Box<dyn BufRead + Send + 'a>;
+
+/// ファイル内容の行単位の窓
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentWindow {
+    /// 先頭の行（1始まり）
+    pub start_line: usize,
+    /// 末尾の行（1始まり、この行を含む）
+    pub end_line: usize,
+    /// 窓の内容（行を改行で連結したもの）
+    pub text: String,
+}
+
+/// 内容を1行ずつ読む
+pub struct ContentLines<'a> {
+    reader: ContentReader<'a>,
+    buffer: Vec<u8>,
+    first: bool,
+    position: usize,
+}
+
+impl<'a> ContentLines<'a> {
+    /// 読み込み元から作成
+    pub fn new(reader: ContentReader<'a>) -> Self {
+        Self { reader, buffer: Vec::new(), first: true, position: 0 }
+    }
+
+    /// 次の行の先頭のバイト位置（BOMを除いた内容の先頭から）
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for ContentLines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.buffer.clear();
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => self.position += n,
+        }
+        if self.buffer.ends_with(b"\n") {
+            self.buffer.pop();
+            if self.buffer.ends_with(b"\r") {
+                self.buffer.pop();
+            }
+        }
+        let mut line = String::from_utf8_lossy(&self.buffer).into_owned();
+        // UTF-8のBOMは解析時と同じく除く
+        if std::mem::take(&mut self.first) && line.starts_with('\u{feff}') {
+            line.remove(0);
+            self.position -= '\u{feff}'.len_utf8();
+        }
+        Some(line)
+    }
+}
+
+/// 内容を行単位の窓に分けて順に読む
+pub struct ContentWindows<'a> {
+    lines: std::iter::Peekable<ContentLines<'a>>,
+    limit: usize,
+    weight: fn(&str) -> usize,
+    next_line: usize,
+}
+
+impl<'a> ContentWindows<'a> {
+    /// 読み込み元から作成
+    ///
+    /// # 引数
+    /// * `reader` - 読み込み元
+    /// * `limit` - 1つの窓の重みの上限
+    /// * `weight` - 1行の重み（バイト数・トークン数の見積もりなど）
+    ///
+    /// # 戻り値
+    /// * `Self` - 窓のイテレーター
+    pub fn new(reader: ContentReader<'a>, limit: usize, weight: fn(&str) -> usize) -> Self {
+        Self { lines: ContentLines::new(reader).peekable(), limit: limit.max(1), weight, next_line: 1 }
+    }
+}
+
+impl Iterator for ContentWindows<'_> {
+    type Item = ContentWindow;
+
+    fn next(&mut self) -> Option<ContentWindow> {
+        let start_line = self.next_line;
+        let mut text = self.lines.next()?;
+        let mut total = (self.weight)(&text);
+        self.next_line += 1;
+        while let Some(line) = self.lines.peek() {
+            // 改行の分も重みに含める
+            let line_weight = (self.weight)(line) + 1;
+            if total + line_weight > self.limit {
+                break;
+            }
+            total += line_weight;
+            text.push('\n');
+            text.push_str(line);
+            self.lines.next();
+            self.next_line += 1;
+        }
+        Some(ContentWindow { start_line, end_line: self.next_line - 1, text })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(content: &'static str) -> ContentReader<'static> {
+        Box::new(std::io::Cursor::new(content.as_bytes()))
+    }
+
+    #[test]
+    fn test_lines_match_str_lines() {
+        let content = "\u{feff}fn a() {}\r\n\nfn b() {}\nlast";
+        let lines: Vec<String> = ContentLines::new(reader(content)).collect();
+        assert_eq!(lines, vec!["fn a() {}", "", "fn b() {}", "last"]);
+        assert_eq!(ContentLines::new(reader("a\nb\n")).count(), "a\nb\n".lines().count());
+
+        let mut lines = ContentLines::new(reader("a\r\nbc\nd"));
+        lines.next();
+        assert_eq!(lines.position(), 3);
+        lines.next();
+        assert_eq!(lines.position(), 6);
+    }
+
+    #[test]
+    fn test_windows_respect_limit() {
+        let windows: Vec<ContentWindow> = ContentWindows::new(reader("aaaa\nbbbb\ncccc\nddddddddddddd\ne"), 10, str::len).collect();
+        assert_eq!(
+            windows,
+            vec![
+                ContentWindow { start_line: 1, end_line: 2, text: "aaaa\nbbbb".to_string() },
+                ContentWindow { start_line: 3, end_line: 3, text: "cccc".to_string() },
+                ContentWindow { start_line: 4, end_line: 4, text: "ddddddddddddd".to_string() },
+                ContentWindow { start_line: 5, end_line: 5, text: "e".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,1456 @@
+/*!
+ * tree-sitterによる構文解析
+ *
+ * ソースコードを構文木に変換し、シンボルとimportを抽出する
+ * - Rust: 関数、構造体、列挙型、トレイト、implブロック、use宣言
+ * - TypeScript/JavaScript: 関数、アロー関数、クラス、メソッド、インターフェース、import/require
+ * - Python: 関数、クラス、メソッド、import/from import
+ * - Go: 関数、メソッド、構造体、インターフェース、import
+ * - Java: クラス、インターフェース、列挙型、メソッド、コンストラクタ、import
+ * - C/C++: 関数、構造体、列挙型、クラス、メソッド（`Type::method` の定義を含む）、#include
+ * - C#: クラス、構造体、インターフェース、列挙型、メソッド、コンストラクタ、using
+ * - Ruby: クラス、モジュール、メソッド（`def self.x` を含む）、require/require_relative/load
+ * - PHP: クラス、インターフェース、トレイト、列挙型、関数、メソッド、use/require/include
+ * - Kotlin: クラス、インターフェース、object、列挙型、関数、メソッド（拡張関数を含む）、import
+ * - 実装・継承関係: Rustの `impl Trait for` とスーパートレイト、TypeScript/JavaScript・Javaの
+ *   `extends`・`implements`、Pythonの基底クラス、Goのインターフェースの埋め込み
+ * - 型のフィールド: Rust・Goの構造体、TypeScript/JavaScriptのクラス・インターフェース、
+ *   Pythonのクラス（クラス変数と `__init__` での `self.x` への代入）、Java・C#のクラス
+ *
+ * 主な仕様:
+ * - 行番号は1始まり
+ * - 関係の型名はパス・ジェネリクスを除いた末尾の名前（`std::fmt::Display<T>` → `Display`）
+ * - Goのインターフェースのメソッドはインターフェースを所属先とするメソッドとして抽出
+ * - 直前のコメント（Pythonはdocstring）をドキュメントとして取得
+ * - 可視性は修飾子（`pub`・`public` など）から判定し、ない場合は言語の既定値
+ * - 関数・メソッドは引数（名前と型）・戻り値の型・async（Kotlinはsuspend）の有無を構文木から取得
+ *   （Goは先頭の大文字、Pythonは先頭の `_`、TypeScript/JavaScriptはexportの有無）
+ * - TypeScriptは構文エラーがある場合にTSX文法で再解析（JavaScriptはTSX文法で解析）
+ * - Cは構文エラーがある場合にC++文法で再解析（C++を含む `.h` に対応）
+ *
+ * 制限事項:
+ * - 対応言語以外は `None` を返す
+ * - 構文エラーを含むファイルは解析できた範囲のみを返す
+ * - C/C++のクラス内の宣言のみのメソッド（本体なし）は抽出しない
+ * - Ruby/PHPのrequire・includeは文字列リテラル（PHPは `__DIR__ . '/x.php'` の右辺を含む）のみ
+ * - C/C++・C#・Ruby・PHP・Kotlinの実装・継承関係は抽出しない
+ * - C/C++・Rubyの可視性は判定しない（アクセス指定子が宣言の外にあるため）
+ * - C/C++・Ruby・PHP・Kotlinのフィールド、Rustのタプル構造体・Goの埋め込みフィールドは抽出しない
+ * - 引数の既定値・C/C++のポインタを返す関数の `*` は取得しない
+ */
+
+use serde::{Deserialize, Serialize};
+use tree_sitter::{Language, Node, Parser, Tree};
+
+/// ソースコード中のシンボル
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Symbol {
+    /// 種類（function|method|struct|enum|trait|impl|class|interface|module）
+    pub kind: String,
+    pub name: String,
+    /// 所属する型・クラス（メソッドの場合）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり、この行を含む）
+    pub end_line: usize,
+    /// 直前のコメントまたはdocstring
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub documentation: String,
+    /// 可視性（public|private|protected|internal、判定できない言語は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub visibility: String,
+    /// 引数（関数・メソッドのみ、`self`・`cls` などのレシーバーを除く宣言順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<Parameter>,
+    /// 戻り値の型（関数・メソッドのみ、書かれていない場合は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub return_type: String,
+    /// 非同期関数か（`async`、Kotlinは `suspend`）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+}
+
+/// 関数・メソッドの引数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Parameter {
+    /// 引数名（`*args`・`...rest` などは記号を含む、名前のない引数は空）
+    pub name: String,
+    /// 型（書かれていない場合は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub type_name: String,
+}
+
+impl Symbol {
+    /// 型の宣言（struct|enum|trait|class|interface）か
+    pub fn is_type(&self) -> bool {
+        matches!(self.kind.as_str(), "struct" | "enum" | "trait" | "class" | "interface")
+    }
+}
+
+/// 型の実装・継承関係
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeRelation {
+    /// 種類（implements|extends）
+    pub kind: String,
+    /// 実装・継承する側の型
+    pub name: String,
+    /// 実装・継承される側の型（トレイト・インターフェース・基底クラス）
+    pub target: String,
+    /// 宣言の行（1始まり）
+    pub line: usize,
+    /// メソッドの一致から推定した関係（Goのインターフェースの実装）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inferred: bool,
+}
+
+/// 型のフィールド
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TypeField {
+    /// 所属する型
+    pub parent: String,
+    pub name: String,
+    /// 宣言の行（1始まり）
+    pub line: usize,
+}
+
+/// 構文解析の結果
+#[derive(Debug, Clone, Default)]
+pub struct ParsedSource {
+    /// import・use・requireの対象
+    pub imports: Vec<String>,
+    /// 出現順のシンボル
+    pub symbols: Vec<Symbol>,
+    /// 実装・継承関係
+    pub relations: Vec<TypeRelation>,
+    /// 型のフィールド（出現順）
+    pub fields: Vec<TypeField>,
+}
+
+/// ソースコードを構文解析
+///
+/// # 引数
+/// * `content` - ファイル内容
+/// * `language` - 言語識別子（rs|ts|js|py|go|java|c|cpp|cs|rb|php|kt）
+///
+/// # 戻り値
+/// * `Option<ParsedSource>` - 解析結果（未対応の言語の場合はNone）
+pub fn parse_source(content: &str, language: &str) -> Option<ParsedSource> {
+    let tree = parse_tree(content, language)?;
+    Some(collect(&tree, content, language))
+}
+
+/// 構文木からシンボルとimportを収集
+pub(crate) fn collect(tree: &Tree, content: &str, language: &str) -> ParsedSource {
+    let mut collector = Collector {
+        src: content.as_bytes(),
+        language,
+        parsed: ParsedSource::default(),
+    };
+    collector.visit(tree.root_node(), None);
+    collector.visit_fields(tree.root_node());
+    collector.parsed
+}
+
+/// 言語に対応する文法（先頭から順に試す）
+fn grammars(language: &str) -> Vec<Language> {
+    match language {
+        "rs" => vec![tree_sitter_rust::LANGUAGE.into()],
+        "ts" => vec![
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            tree_sitter_typescript::LANGUAGE_TSX.into(),
+        ],
+        // JSXを含むJavaScriptも扱えるようTSX文法で解析
+        "js" => vec![tree_sitter_typescript::LANGUAGE_TSX.into()],
+        "py" => vec![tree_sitter_python::LANGUAGE.into()],
+        "go" => vec![tree_sitter_go::LANGUAGE.into()],
+        "java" => vec![tree_sitter_java::LANGUAGE.into()],
+        "c" => vec![tree_sitter_c::LANGUAGE.into(), tree_sitter_cpp::LANGUAGE.into()],
+        "cpp" => vec![tree_sitter_cpp::LANGUAGE.into()],
+        "cs" => vec![tree_sitter_c_sharp::LANGUAGE.into()],
+        "rb" => vec![tree_sitter_ruby::LANGUAGE.into()],
+        "php" => vec![tree_sitter_php::LANGUAGE_PHP.into()],
+        "kt" => vec![tree_sitter_kotlin_ng::LANGUAGE.into()],
+        _ => Vec::new(),
+    }
+}
+
+/// 構文木を作成（構文エラーのない文法を優先、未対応の言語の場合はNone）
+pub(crate) fn parse_tree(content: &str, language: &str) -> Option<Tree> {
+    let mut fallback = None;
+    for grammar in grammars(language) {
+        let mut parser = Parser::new();
+        if parser.set_language(&grammar).is_err() {
+            continue;
+        }
+        if let Some(tree) = parser.parse(content, None) {
+            if !tree.root_node().has_error() {
+                return Some(tree);
+            }
+            fallback.get_or_insert(tree);
+        }
+    }
+    fallback
+}
+
+/// 構文木を走査してシンボルとimportを収集
+struct Collector<'a> {
+    src: &'a [u8],
+    language: &'a str,
+    parsed: ParsedSource,
+}
+
+impl<'a> Collector<'a> {
+    /// ノードを再帰的に走査
+    ///
+    /// # 引数
+    /// * `node` - ノード
+    /// * `parent` - 囲んでいる型・クラス名
+    fn visit(&mut self, node: Node, parent: Option<&str>) {
+        let mut child_parent = parent.map(String::from);
+
+        match (self.language, node.kind()) {
+            // ---- Rust ----
+            ("rs", "use_declaration") => {
+                if let Some(arg) = node.child_by_field_name("argument") {
+                    self.rust_use_paths(arg);
+                }
+                return;
+            }
+            ("rs", "function_item") | ("rs", "function_signature_item") => {
+                let kind = if parent.is_some() { "method" } else { "function" };
+                self.push_named(node, kind, parent);
+                child_parent = None;
+            }
+            ("rs", "struct_item") => self.push_named(node, "struct", None),
+            ("rs", "enum_item") => self.push_named(node, "enum", None),
+            ("rs", "trait_item") => {
+                self.push_named(node, "trait", None);
+                child_parent = self.field_text(node, "name");
+                if let (Some(name), Some(bounds)) = (&child_parent, node.child_by_field_name("bounds")) {
+                    let supertraits: Vec<Node> = bounds
+                        .named_children(&mut bounds.walk())
+                        .filter(|b| matches!(b.kind(), "type_identifier" | "scoped_type_identifier" | "generic_type"))
+                        .collect();
+                    for supertrait in supertraits {
+                        self.relate(node, "extends", name, supertrait);
+                    }
+                }
+            }
+            ("rs", "impl_item") => {
+                let ty = self.field_text(node, "type").unwrap_or_default();
+                let name = match self.field_text(node, "trait") {
+                    Some(tr) => format!("{} for {}", tr, ty),
+                    None => ty.clone(),
+                };
+                self.push(node, "impl", name, None);
+                // 否定実装（`impl !Send for T`）は関係に含めない
+                if let Some(tr) = node.child_by_field_name("trait").filter(|_| !has_token(node, "!")) {
+                    self.relate(node, "implements", &base_type_name(&ty), tr);
+                }
+                child_parent = Some(ty);
+            }
+
+            // ---- TypeScript/JavaScript ----
+            ("ts" | "js", "import_statement")
+            | ("ts" | "js", "export_statement")
+            | ("ts" | "js", "import_require_clause") => {
+                if let Some(source) = self.field_text(node, "source") {
+                    self.parsed.imports.push(unquote(&source));
+                }
+            }
+            ("ts" | "js", "call_expression") => {
+                let is_require = self.field_text(node, "function").as_deref() == Some("require");
+                if is_require {
+                    let arg = node
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .filter(|a| a.kind() == "string");
+                    if let Some(arg) = arg {
+                        let text = self.text(arg);
+                        self.parsed.imports.push(unquote(&text));
+                    }
+                }
+            }
+            ("ts" | "js", "function_declaration") | ("ts" | "js", "generator_function_declaration") => {
+                self.push_named(node, "function", None);
+                child_parent = None;
+            }
+            ("ts" | "js", "class_declaration") | ("ts" | "js", "abstract_class_declaration") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+                if let Some(name) = &child_parent {
+                    let mut clauses = Vec::new();
+                    collect_kind(node, "extends_clause", &mut clauses);
+                    for clause in clauses.into_iter().filter(|c| c.parent().is_some_and(|p| p.parent() == Some(node))) {
+                        let mut cursor = clause.walk();
+                        let bases: Vec<Node> = clause.children_by_field_name("value", &mut cursor).collect();
+                        for base in bases.into_iter().filter(|b| matches!(b.kind(), "identifier" | "member_expression")) {
+                            self.relate(node, "extends", name, base);
+                        }
+                    }
+                    let mut clauses = Vec::new();
+                    collect_kind(node, "implements_clause", &mut clauses);
+                    for clause in clauses.into_iter().filter(|c| c.parent().is_some_and(|p| p.parent() == Some(node))) {
+                        let interfaces: Vec<Node> = clause.named_children(&mut clause.walk()).collect();
+                        for interface in interfaces {
+                            self.relate(node, "implements", name, interface);
+                        }
+                    }
+                }
+            }
+            ("ts" | "js", "method_definition") => {
+                self.push_named(node, "method", parent);
+                child_parent = None;
+            }
+            ("ts" | "js", "interface_declaration") => {
+                self.push_named(node, "interface", None);
+                let clause = node.named_children(&mut node.walk()).find(|c| c.kind() == "extends_type_clause");
+                if let (Some(name), Some(clause)) = (self.field_text(node, "name"), clause) {
+                    let mut cursor = clause.walk();
+                    let bases: Vec<Node> = clause.children_by_field_name("type", &mut cursor).collect();
+                    for base in bases {
+                        self.relate(node, "extends", &name, base);
+                    }
+                }
+            }
+            ("ts" | "js", "enum_declaration") => self.push_named(node, "enum", None),
+            ("ts" | "js", "variable_declarator") => {
+                let is_function = node
+                    .child_by_field_name("value")
+                    .map(|v| matches!(v.kind(), "arrow_function" | "function_expression" | "function"))
+                    .unwrap_or(false);
+                if is_function {
+                    // 宣言文全体（const ... = () => {}）を範囲とする
+                    let decl = node.parent().filter(|p| p.kind() == "lexical_declaration" || p.kind() == "variable_declaration");
+                    let name = self.field_text(node, "name").unwrap_or_default();
+                    self.push(decl.unwrap_or(node), "function", name, None);
+                    child_parent = None;
+                }
+            }
+
+            // ---- Python ----
+            ("py", "import_statement") => {
+                let mut cursor = node.walk();
+                let names: Vec<Node> = node.children_by_field_name("name", &mut cursor).collect();
+                for name in names {
+                    let target = if name.kind() == "aliased_import" {
+                        name.child_by_field_name("name").unwrap_or(name)
+                    } else {
+                        name
+                    };
+                    let text = self.text(target);
+                    self.parsed.imports.push(text);
+                }
+                return;
+            }
+            ("py", "import_from_statement") => {
+                if let Some(module) = self.field_text(node, "module_name") {
+                    self.parsed.imports.push(module);
+                }
+                return;
+            }
+            ("py", "function_definition") => {
+                let kind = if parent.is_some() { "method" } else { "function" };
+                self.push_named(node, kind, parent);
+                child_parent = None;
+            }
+            ("py", "class_definition") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+                if let (Some(name), Some(bases)) = (&child_parent, node.child_by_field_name("superclasses")) {
+                    // `metaclass=...` などのキーワード引数と、暗黙の基底クラス `object` は除く
+                    let bases: Vec<Node> = bases
+                        .named_children(&mut bases.walk())
+                        .filter_map(|b| match b.kind() {
+                            "identifier" | "attribute" => Some(b),
+                            "subscript" => b.child_by_field_name("value"),
+                            _ => None,
+                        })
+                        .collect();
+                    for base in bases {
+                        if self.text(base) != "object" {
+                            self.relate(node, "extends", name, base);
+                        }
+                    }
+                }
+            }
+
+            // ---- Go ----
+            ("go", "import_spec") => {
+                if let Some(path) = self.field_text(node, "path") {
+                    self.parsed.imports.push(unquote(&path));
+                }
+                return;
+            }
+            ("go", "function_declaration") => self.push_named(node, "function", None),
+            ("go", "method_declaration") => {
+                let receiver = node
+                    .child_by_field_name("receiver")
+                    .and_then(|r| r.named_child(0))
+                    .and_then(|p| p.child_by_field_name("type"))
+                    .map(|t| self.text(t).trim_start_matches('*').to_string());
+                self.push_named(node, "method", receiver.as_deref());
+            }
+            ("go", "type_spec") => {
+                let kind = match node.child_by_field_name("type").map(|t| t.kind()) {
+                    Some("struct_type") => Some("struct"),
+                    Some("interface_type") => Some("interface"),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    // ドキュメントコメントは `type` 宣言の直前にある
+                    let decl = node.parent().filter(|p| p.kind() == "type_declaration").unwrap_or(node);
+                    let name = self.field_text(node, "name").unwrap_or_default();
+                    let mut symbol = self.symbol(decl, kind, name.clone(), None);
+                    symbol.end_line = node.end_position().row + 1;
+                    self.parsed.symbols.push(symbol);
+                    if kind == "interface" {
+                        child_parent = Some(name);
+                    }
+                }
+            }
+            ("go", "method_elem") => self.push_named(node, "method", parent),
+            // インターフェースの埋め込み（`type ReadCloser interface { Reader; Closer }`）
+            ("go", "type_elem") => {
+                if let Some(name) = parent {
+                    let types: Vec<Node> = node.named_children(&mut node.walk()).collect();
+                    for ty in types.into_iter().filter(|t| matches!(t.kind(), "type_identifier" | "qualified_type")) {
+                        self.relate(node, "extends", name, ty);
+                    }
+                }
+                return;
+            }
+
+            // ---- Java ----
+            ("java", "import_declaration") => {
+                let text = self.text(node);
+                let path = text
+                    .trim()
+                    .trim_start_matches("import")
+                    .trim()
+                    .trim_start_matches("static ")
+                    .trim_end_matches(';')
+                    .trim()
+                    .to_string();
+                self.parsed.imports.push(path);
+                return;
+            }
+            ("java", "class_declaration") | ("java", "record_declaration") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+                if let Some(name) = &child_parent {
+                    if let Some(base) = node.child_by_field_name("superclass").and_then(|s| s.named_child(0)) {
+                        self.relate(node, "extends", name, base);
+                    }
+                    if let Some(interfaces) = node.child_by_field_name("interfaces") {
+                        self.java_type_list(node, "implements", name, interfaces);
+                    }
+                }
+            }
+            ("java", "interface_declaration") => {
+                self.push_named(node, "interface", None);
+                child_parent = self.field_text(node, "name");
+                let clause = node.named_children(&mut node.walk()).find(|c| c.kind() == "extends_interfaces");
+                if let (Some(name), Some(clause)) = (&child_parent, clause) {
+                    self.java_type_list(node, "extends", name, clause);
+                }
+            }
+            ("java", "enum_declaration") => {
+                self.push_named(node, "enum", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("java", "method_declaration") | ("java", "constructor_declaration") => {
+                self.push_named(node, "method", parent);
+                child_parent = None;
+            }
+
+            // ---- C/C++ ----
+            ("c" | "cpp", "preproc_include") => {
+                if let Some(path) = self.field_text(node, "path") {
+                    let path = unquote(&path);
+                    self.parsed.imports.push(path.trim_start_matches('<').trim_end_matches('>').to_string());
+                }
+                return;
+            }
+            ("c" | "cpp", "function_definition") => {
+                if let Some(name) = self.c_function_name(node) {
+                    // `Type::method` の定義はクラス外でもTypeのメソッドとする
+                    match name.rsplit_once("::") {
+                        Some((scope, method)) => self.push(node, "method", method.to_string(), Some(scope)),
+                        None if parent.is_some() => self.push(node, "method", name, parent),
+                        None => self.push(node, "function", name, None),
+                    }
+                }
+                child_parent = None;
+            }
+            // 本体のない前方宣言・型の参照は除く
+            ("c" | "cpp", "struct_specifier" | "class_specifier") if node.child_by_field_name("body").is_some() => {
+                let kind = if node.kind() == "class_specifier" { "class" } else { "struct" };
+                self.push_named(node, kind, None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("c" | "cpp", "enum_specifier") if node.child_by_field_name("body").is_some() => {
+                self.push_named(node, "enum", None);
+            }
+
+            // ---- C# ----
+            ("cs", "using_directive") => {
+                let text = self.text(node);
+                let target = text.trim().trim_start_matches("global").trim().trim_start_matches("using").trim();
+                let target = target.trim_start_matches("static ").trim_end_matches(';').trim();
+                // `using Alias = Namespace.Type;` は右辺を依存先とする
+                let target = target.rsplit_once('=').map(|(_, t)| t.trim()).unwrap_or(target);
+                self.parsed.imports.push(target.to_string());
+                return;
+            }
+            ("cs", "class_declaration") | ("cs", "record_declaration") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("cs", "struct_declaration") => {
+                self.push_named(node, "struct", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("cs", "interface_declaration") => {
+                self.push_named(node, "interface", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("cs", "enum_declaration") => self.push_named(node, "enum", None),
+            ("cs", "method_declaration") | ("cs", "constructor_declaration") => {
+                self.push_named(node, "method", parent);
+                child_parent = None;
+            }
+
+            // ---- Ruby ----
+            ("rb", "call") => {
+                let method = self.field_text(node, "method");
+                if matches!(method.as_deref(), Some("require" | "require_relative" | "load")) {
+                    let arg = node
+                        .child_by_field_name("arguments")
+                        .and_then(|args| args.named_child(0))
+                        .filter(|a| a.kind() == "string");
+                    if let Some(arg) = arg {
+                        let text = self.text(arg);
+                        self.parsed.imports.push(unquote(&text));
+                    }
+                }
+            }
+            ("rb", "class") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("rb", "module") => {
+                self.push_named(node, "module", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("rb", "method") | ("rb", "singleton_method") => {
+                let kind = if parent.is_some() { "method" } else { "function" };
+                self.push_named(node, kind, parent);
+                child_parent = None;
+            }
+
+            // ---- PHP ----
+            ("php", "namespace_use_declaration") => {
+                // `use A\{B, C};` はグループの接頭辞を付けて展開する
+                let prefix = node
+                    .named_children(&mut node.walk())
+                    .find(|c| c.kind() == "namespace_name")
+                    .map(|c| self.text(c));
+                let mut clauses = Vec::new();
+                collect_kind(node, "namespace_use_clause", &mut clauses);
+                for clause in clauses {
+                    let Some(name) = clause.named_child(0) else { continue };
+                    let name = self.text(name);
+                    let name = match &prefix {
+                        Some(prefix) => format!("{}\\{}", prefix, name),
+                        None => name,
+                    };
+                    self.parsed.imports.push(name.trim_start_matches('\\').to_string());
+                }
+                return;
+            }
+            ("php", "require_expression")
+            | ("php", "require_once_expression")
+            | ("php", "include_expression")
+            | ("php", "include_once_expression") => {
+                let arg = node.named_child(0).map(|a| match a.kind() {
+                    "binary_expression" => a.child_by_field_name("right").unwrap_or(a),
+                    _ => a,
+                });
+                if let Some(arg) = arg.filter(|a| matches!(a.kind(), "string" | "encapsed_string")) {
+                    let text = self.text(arg);
+                    self.parsed.imports.push(unquote(&text).trim_start_matches('/').to_string());
+                }
+                return;
+            }
+            ("php", "class_declaration") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("php", "interface_declaration") => {
+                self.push_named(node, "interface", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("php", "trait_declaration") => {
+                self.push_named(node, "trait", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("php", "enum_declaration") => {
+                self.push_named(node, "enum", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("php", "function_definition") => {
+                self.push_named(node, "function", None);
+                child_parent = None;
+            }
+            ("php", "method_declaration") => {
+                self.push_named(node, "method", parent);
+                child_parent = None;
+            }
+
+            // ---- Kotlin ----
+            ("kt", "import") => {
+                let text = self.text(node);
+                let path = text.trim().trim_start_matches("import").trim();
+                // `import a.B as C` は元の名前、`import a.*` はパッケージを依存先とする
+                let path = path.split_once(" as ").map(|(p, _)| p.trim()).unwrap_or(path);
+                self.parsed.imports.push(path.trim_end_matches(".*").to_string());
+                return;
+            }
+            ("kt", "class_declaration") => {
+                let kind = if has_token(node, "interface") {
+                    "interface"
+                } else if self.text(node).split_whitespace().take_while(|w| *w != "class").any(|w| w == "enum") {
+                    "enum"
+                } else {
+                    "class"
+                };
+                self.push_named(node, kind, None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("kt", "object_declaration") => {
+                self.push_named(node, "class", None);
+                child_parent = self.field_text(node, "name");
+            }
+            ("kt", "function_declaration") => {
+                // 拡張関数 `fun Type.name()` はレシーバーの型のメソッドとする
+                let receiver = node
+                    .named_children(&mut node.walk())
+                    .take_while(|c| Some(*c) != node.child_by_field_name("name"))
+                    .find(|c| c.kind() == "user_type")
+                    .map(|c| self.text(c));
+                match (receiver.as_deref(), parent) {
+                    (Some(receiver), _) => self.push_named(node, "method", Some(receiver)),
+                    (None, Some(parent)) => self.push_named(node, "method", Some(parent)),
+                    (None, None) => self.push_named(node, "function", None),
+                }
+                child_parent = None;
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        for child in children {
+            self.visit(child, child_parent.as_deref());
+        }
+    }
+
+    /// Rustのuse宣言からパスを収集（`a::{b, c}` は `a` として扱う）
+    fn rust_use_paths(&mut self, node: Node) {
+        match node.kind() {
+            "use_as_clause" => {
+                if let Some(path) = self.field_text(node, "path") {
+                    self.parsed.imports.push(path);
+                }
+            }
+            "scoped_use_list" => match self.field_text(node, "path") {
+                Some(path) => self.parsed.imports.push(path),
+                None => {
+                    if let Some(list) = node.child_by_field_name("list") {
+                        self.rust_use_paths(list);
+                    }
+                }
+            },
+            "use_list" => {
+                let mut cursor = node.walk();
+                let items: Vec<Node> = node.named_children(&mut cursor).collect();
+                for item in items {
+                    self.rust_use_paths(item);
+                }
+            }
+            "use_wildcard" => {
+                let text = self.text(node);
+                self.parsed.imports.push(text.trim_end_matches("::*").to_string());
+            }
+            _ => {
+                let text = self.text(node);
+                self.parsed.imports.push(text);
+            }
+        }
+    }
+
+    /// Javaの `implements A, B` ・ `extends A, B` の型リストを関係として追加
+    fn java_type_list(&mut self, decl: Node, kind: &str, name: &str, clause: Node) {
+        let Some(list) = clause.named_children(&mut clause.walk()).find(|c| c.kind() == "type_list") else {
+            return;
+        };
+        let types: Vec<Node> = list.named_children(&mut list.walk()).collect();
+        for ty in types {
+            self.relate(decl, kind, name, ty);
+        }
+    }
+
+    /// 実装・継承関係を追加
+    ///
+    /// # 引数
+    /// * `decl` - 宣言のノード（行番号に使用）
+    /// * `kind` - 種類（implements|extends）
+    /// * `name` - 実装・継承する側の型
+    /// * `target` - 実装・継承される側の型のノード
+    fn relate(&mut self, decl: Node, kind: &str, name: &str, target: Node) {
+        let target = base_type_name(&self.text(target));
+        if target.is_empty() || name.is_empty() {
+            return;
+        }
+        self.parsed.relations.push(TypeRelation {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            target,
+            line: decl.start_position().row + 1,
+            inferred: false,
+        });
+    }
+
+    /// C/C++の関数定義から関数名を取得（`Type::method` 形式を含む）
+    fn c_function_name(&self, node: Node) -> Option<String> {
+        let name = c_function_declarator(node)?.child_by_field_name("declarator")?;
+        Some(self.text(name))
+    }
+
+    /// 型のフィールドを再帰的に収集（入れ子の型を含む）
+    fn visit_fields(&mut self, node: Node) {
+        if let Some((name, body)) = self.type_body(node) {
+            self.push_fields(&name, body);
+        }
+        let children: Vec<Node> = node.named_children(&mut node.walk()).collect();
+        for child in children {
+            self.visit_fields(child);
+        }
+    }
+
+    /// フィールドを宣言する型の名前と本体
+    fn type_body<'t>(&self, node: Node<'t>) -> Option<(String, Node<'t>)> {
+        let body = match (self.language, node.kind()) {
+            ("rs", "struct_item")
+            | ("ts" | "js", "class_declaration" | "abstract_class_declaration" | "interface_declaration")
+            | ("py", "class_definition")
+            | ("java", "class_declaration")
+            | ("cs", "class_declaration" | "struct_declaration") => node.child_by_field_name("body")?,
+            ("go", "type_spec") => node.child_by_field_name("type").filter(|t| t.kind() == "struct_type")?.named_child(0)?,
+            _ => return None,
+        };
+        Some((self.field_text(node, "name")?, body))
+    }
+
+    /// 型の本体からフィールドを追加
+    fn push_fields(&mut self, parent: &str, body: Node) {
+        let members: Vec<Node> = body.named_children(&mut body.walk()).collect();
+        for member in members {
+            let mut names = Vec::new();
+            match (self.language, member.kind()) {
+                ("rs", "field_declaration")
+                | ("ts" | "js", "public_field_definition" | "property_signature")
+                | ("cs", "property_declaration") => names.extend(member.child_by_field_name("name")),
+                ("go", "field_declaration") => names.extend(member.children_by_field_name("name", &mut member.walk())),
+                ("java", "field_declaration") => {
+                    let declarators: Vec<Node> = member.children_by_field_name("declarator", &mut member.walk()).collect();
+                    names.extend(declarators.into_iter().filter_map(|d| d.child_by_field_name("name")));
+                }
+                ("cs", "field_declaration") => {
+                    let mut declarators = Vec::new();
+                    collect_kind(member, "variable_declarator", &mut declarators);
+                    names.extend(declarators.into_iter().filter_map(|d| {
+                        d.child_by_field_name("name").or_else(|| d.named_child(0).filter(|n| n.kind() == "identifier"))
+                    }));
+                }
+                // クラス変数（`x = 1`・`x: int`）
+                ("py", "expression_statement") => {
+                    let left = member
+                        .named_child(0)
+                        .filter(|a| a.kind() == "assignment")
+                        .and_then(|a| a.child_by_field_name("left"))
+                        .filter(|l| l.kind() == "identifier");
+                    names.extend(left);
+                }
+                // `__init__` でのインスタンス変数（`self.x = ...`）
+                ("py", "function_definition") if self.field_text(member, "name").as_deref() == Some("__init__") => {
+                    let mut assignments = Vec::new();
+                    collect_kind(member, "assignment", &mut assignments);
+                    names.extend(assignments.into_iter().filter_map(|a| {
+                        let left = a.child_by_field_name("left").filter(|l| l.kind() == "attribute")?;
+                        let object = left.child_by_field_name("object")?;
+                        (self.text(object) == "self").then_some(())?;
+                        left.child_by_field_name("attribute")
+                    }));
+                }
+                _ => {}
+            }
+            for name in names {
+                let field = TypeField { parent: parent.to_string(), name: self.text(name), line: name.start_position().row + 1 };
+                if !self.parsed.fields.iter().any(|f| f.parent == field.parent && f.name == field.name) {
+                    self.parsed.fields.push(field);
+                }
+            }
+        }
+    }
+
+    /// `name` フィールドを名前としてシンボルを追加
+    fn push_named(&mut self, node: Node, kind: &str, parent: Option<&str>) {
+        if let Some(name) = self.field_text(node, "name") {
+            self.push(node, kind, name, parent);
+        }
+    }
+
+    /// シンボルを追加
+    fn push(&mut self, node: Node, kind: &str, name: String, parent: Option<&str>) {
+        let symbol = self.symbol(node, kind, name, parent);
+        self.parsed.symbols.push(symbol);
+    }
+
+    /// シンボルを作成
+    fn symbol(&self, node: Node, kind: &str, name: String, parent: Option<&str>) -> Symbol {
+        let visibility = self.visibility(node, &name);
+        let mut symbol = Symbol {
+            kind: kind.to_string(),
+            parent: parent.map(String::from),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            documentation: self.documentation(node),
+            visibility,
+            name,
+            ..Default::default()
+        };
+        if kind == "function" || kind == "method" {
+            let function = function_node(node);
+            symbol.parameters = self.parameters(function);
+            symbol.return_type = self.return_type(function);
+            symbol.is_async = self.is_async(function);
+        }
+        symbol
+    }
+
+    /// 関数の引数（レシーバーを除く）
+    fn parameters(&self, function: Node) -> Vec<Parameter> {
+        let list = match self.language {
+            "c" | "cpp" => c_function_declarator(function).and_then(|d| d.child_by_field_name("parameters")),
+            "kt" => function.named_children(&mut function.walk()).find(|c| c.kind() == "function_value_parameters"),
+            // `x => x` は引数そのものがフィールドになる
+            _ => function.child_by_field_name("parameters").or_else(|| function.child_by_field_name("parameter")),
+        };
+        let Some(list) = list else {
+            return Vec::new();
+        };
+        if list.kind() == "identifier" {
+            return vec![Parameter { name: self.text(list), type_name: String::new() }];
+        }
+
+        let mut parameters = Vec::new();
+        let items: Vec<Node> = list.named_children(&mut list.walk()).collect();
+        for item in items {
+            if item.kind().contains("comment")
+                || matches!(
+                    item.kind(),
+                    "self_parameter" | "attribute_item" | "keyword_separator" | "positional_separator" | "decorator"
+                )
+            {
+                continue;
+            }
+            let type_node = item.child_by_field_name("type").or_else(|| match self.language {
+                // Kotlinの引数は `名前: 型` の2つ目の子ノードが型
+                "kt" => item.named_child(1),
+                _ => None,
+            });
+            let type_name = type_node.map(|t| self.type_text(t)).unwrap_or_default();
+
+            // Goの `a, b int` は同じ型の複数の引数
+            let mut cursor = item.walk();
+            let mut names: Vec<String> = item.children_by_field_name("name", &mut cursor).map(|n| self.text(n)).collect();
+            if names.is_empty() {
+                let name = ["pattern", "left", "declarator"]
+                    .iter()
+                    .find_map(|field| item.child_by_field_name(field))
+                    .or_else(|| {
+                        let leaf = item.named_child_count() == 0 || item.kind().ends_with("pattern");
+                        if leaf {
+                            Some(item)
+                        } else {
+                            item.named_children(&mut item.walk()).find(|c| Some(*c) != type_node)
+                        }
+                    });
+                names.push(name.map(|n| self.text(n)).unwrap_or_default());
+            }
+            for name in names {
+                // C/C++の `char *buf` は `*` を型に含める
+                let pointer: String = match self.language {
+                    "c" | "cpp" => name.chars().take_while(|c| matches!(c, '*' | '&')).collect(),
+                    _ => String::new(),
+                };
+                let type_name = match pointer.is_empty() {
+                    true => type_name.clone(),
+                    false => format!("{} {}", type_name, pointer),
+                };
+                parameters.push(Parameter { name: name[pointer.len()..].trim().to_string(), type_name });
+            }
+        }
+        // Pythonのメソッドの `self`・`cls` はレシーバー
+        if self.language == "py" && parameters.first().is_some_and(|p| p.name == "self" || p.name == "cls") {
+            parameters.remove(0);
+        }
+        parameters
+    }
+
+    /// 関数の戻り値の型（書かれていない場合は空）
+    fn return_type(&self, function: Node) -> String {
+        let ty = match self.language {
+            "go" => function.child_by_field_name("result"),
+            "java" | "c" | "cpp" => function.child_by_field_name("type"),
+            "cs" => function.child_by_field_name("returns").or_else(|| function.child_by_field_name("type")),
+            // Kotlinは引数リストの後、本体の前にある型
+            "kt" => {
+                let children: Vec<Node> = function.named_children(&mut function.walk()).collect();
+                children
+                    .iter()
+                    .skip_while(|c| c.kind() != "function_value_parameters")
+                    .skip(1)
+                    .take_while(|c| c.kind() != "function_body")
+                    .find(|c| c.kind().ends_with("_type"))
+                    .copied()
+            }
+            _ => function.child_by_field_name("return_type"),
+        };
+        ty.map(|t| self.type_text(t)).unwrap_or_default()
+    }
+
+    /// 非同期関数か（`async` のキーワード・修飾子、Kotlinの `suspend`）
+    fn is_async(&self, function: Node) -> bool {
+        has_token(function, "async")
+            || function
+                .children(&mut function.walk())
+                .filter(|c| matches!(c.kind(), "function_modifiers" | "modifiers" | "modifier"))
+                .any(|c| self.text(c).split_whitespace().any(|word| word == "async" || word == "suspend"))
+    }
+
+    /// 型の注釈のテキスト（先頭の `:` を除き、空白を1つにまとめる）
+    fn type_text(&self, node: Node) -> String {
+        let text = self.text(node);
+        text.trim().trim_start_matches(':').split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// 可視性を判定（修飾子、言語ごとの命名規則・既定値から）
+    fn visibility(&self, node: Node, name: &str) -> String {
+        let modifier = node
+            .children(&mut node.walk())
+            .filter(|c| {
+                matches!(c.kind(), "visibility_modifier" | "accessibility_modifier" | "modifiers" | "modifier")
+            })
+            .flat_map(|c| {
+                let text = self.text(c);
+                text.split(|ch: char| !ch.is_alphanumeric()).map(String::from).collect::<Vec<_>>()
+            })
+            .find(|word| matches!(word.as_str(), "pub" | "public" | "private" | "protected" | "internal"));
+
+        let visibility = match (self.language, modifier.as_deref()) {
+            // `pub(crate)`・`pub(super)` はクレート内のみ
+            ("rs", Some("pub")) => {
+                let text = node
+                    .children(&mut node.walk())
+                    .find(|c| c.kind() == "visibility_modifier")
+                    .map(|c| self.text(c))
+                    .unwrap_or_default();
+                if text == "pub" { "public" } else { "internal" }
+            }
+            (_, Some(modifier)) => modifier,
+            // トレイトの項目・トレイト実装のメソッドはトレイトと同じ公開範囲
+            ("rs", None) => {
+                let container = node.parent().and_then(|p| p.parent());
+                match container {
+                    Some(c) if c.kind() == "trait_item" => "public",
+                    Some(c) if c.kind() == "impl_item" && c.child_by_field_name("trait").is_some() => "public",
+                    _ => "private",
+                }
+            }
+            ("ts" | "js", None) => {
+                let exported = node.parent().is_some_and(|p| p.kind() == "export_statement");
+                if name.starts_with('#') {
+                    "private"
+                } else if exported || node.kind() == "method_definition" {
+                    "public"
+                } else {
+                    "private"
+                }
+            }
+            ("py", None) => {
+                if name.starts_with("__") && !name.ends_with("__") {
+                    "private"
+                } else if name.starts_with('_') && !name.starts_with("__") {
+                    "protected"
+                } else {
+                    "public"
+                }
+            }
+            ("go", None) => {
+                if name.starts_with(|c: char| c.is_uppercase()) { "public" } else { "private" }
+            }
+            // インターフェースのメンバーは公開、それ以外はパッケージ内のみ
+            ("java", None) => {
+                let in_interface = node
+                    .parent()
+                    .and_then(|p| p.parent())
+                    .is_some_and(|p| p.kind() == "interface_declaration");
+                if in_interface { "public" } else { "internal" }
+            }
+            ("cs", None) => {
+                let is_member = node.parent().is_some_and(|p| p.kind() == "declaration_list")
+                    && node.parent().and_then(|p| p.parent()).is_some_and(|p| p.kind() != "namespace_declaration");
+                if is_member { "private" } else { "internal" }
+            }
+            ("kt" | "php", None) => "public",
+            // C/C++のアクセス指定子・Rubyの `private` は宣言の外にあるため判定しない
+            _ => "",
+        };
+        visibility.to_string()
+    }
+
+    /// ドキュメント（直前のコメント、Pythonはdocstring）を取得
+    fn documentation(&self, node: Node) -> String {
+        if self.language == "py" {
+            let docstring = node
+                .child_by_field_name("body")
+                .and_then(|body| body.named_child(0))
+                .filter(|stmt| stmt.kind() == "expression_statement")
+                .and_then(|stmt| stmt.named_child(0))
+                .filter(|expr| expr.kind() == "string");
+            if let Some(docstring) = docstring {
+                let text = self.text(docstring);
+                return clean_comment(text.trim_matches(|c| c == '"' || c == '\''));
+            }
+        }
+
+        // TypeScriptの `export function` はexport文、C++のテンプレートはtemplate宣言の前にコメントがある
+        let mut current = match node.parent() {
+            Some(p) if p.kind() == "export_statement" || p.kind() == "template_declaration" => p,
+            _ => node,
+        };
+        // Rubyのクラス本体の最初のメソッドでは、コメントは本体（body_statement）の前にある
+        if self.language == "rb" && current.prev_sibling().is_none() {
+            if let Some(body) = current.parent().filter(|p| p.kind() == "body_statement") {
+                current = body;
+            }
+        }
+        let mut expected_row = current.start_position().row;
+        let mut comments = Vec::new();
+
+        while let Some(prev) = current.prev_sibling() {
+            let is_comment = prev.kind().contains("comment");
+            let is_attribute = matches!(prev.kind(), "attribute_item" | "decorator" | "marker_annotation" | "annotation");
+            if !(is_comment || is_attribute) || prev.end_position().row + 1 < expected_row {
+                break;
+            }
+            if is_comment {
+                comments.push(self.text(prev));
+            }
+            expected_row = prev.start_position().row;
+            current = prev;
+        }
+
+        comments.reverse();
+        let documentation = clean_comment(&comments.join("\n"));
+        if self.language == "cs" {
+            // XMLドキュメントコメントの `<summary>` タグを除く
+            return documentation.replace("<summary>", "").replace("</summary>", "").trim().to_string();
+        }
+        documentation
+    }
+
+    /// フィールドのテキストを取得
+    fn field_text(&self, node: Node, field: &str) -> Option<String> {
+        node.child_by_field_name(field).map(|n| self.text(n))
+    }
+
+    /// ノードのテキストを取得
+    fn text(&self, node: Node) -> String {
+        node.utf8_text(self.src).unwrap_or_default().to_string()
+    }
+}
+
+/// 指定した種類の子孫ノードを収集
+fn collect_kind<'t>(node: Node<'t>, kind: &str, out: &mut Vec<Node<'t>>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == kind {
+            out.push(child);
+        } else {
+            collect_kind(child, kind, out);
+        }
+    }
+}
+
+/// シンボルの範囲のノードから関数本体のノードを取得（`const f = async () => {}` は右辺の関数）
+fn function_node(node: Node) -> Node {
+    let declarator = match node.kind() {
+        "lexical_declaration" | "variable_declaration" => {
+            node.named_children(&mut node.walk()).find(|c| c.kind() == "variable_declarator")
+        }
+        "variable_declarator" => Some(node),
+        _ => None,
+    };
+    declarator.and_then(|d| d.child_by_field_name("value")).unwrap_or(node)
+}
+
+/// C/C++の関数定義の関数宣言子（ポインタ・参照の宣言子を辿る）
+fn c_function_declarator(node: Node) -> Option<Node> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    while declarator.kind() != "function_declarator" {
+        // C++の参照宣言子（`&`）はフィールド名を持たない
+        declarator = declarator
+            .child_by_field_name("declarator")
+            .or_else(|| declarator.named_child(0))?;
+    }
+    Some(declarator)
+}
+
+/// 指定したキーワード（名前のない子ノード）を持つか
+fn has_token(node: Node, token: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|c| !c.is_named() && c.kind() == token);
+    found
+}
+
+/// 型名からパス・ジェネリクスを除いた末尾の名前を取得（`std::fmt::Display<T>` → `Display`）
+pub(crate) fn base_type_name(ty: &str) -> String {
+    let ty = ty.split(['<', '[', '(']).next().unwrap_or(ty).trim();
+    let ty = ty.rsplit(['.', ':', '\\']).next().unwrap_or(ty);
+    ty.trim_start_matches(['&', '*']).trim().to_string()
+}
+
+/// 文字列リテラルの引用符を除去
+fn unquote(s: &str) -> String {
+    s.trim_matches(|c| c == '"' || c == '\'' || c == '`').to_string()
+}
+
+/// コメント記号を除去して本文を取り出す
+fn clean_comment(comment: &str) -> String {
+    let lines: Vec<String> = comment
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = ["///", "//!", "//", "/**", "/*", "#"]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+                .unwrap_or(line);
+            let line = line.strip_suffix("*/").unwrap_or(line).trim();
+            line.strip_prefix('*').unwrap_or(line).trim().to_string()
+        })
+        .collect();
+
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rust_symbols() {
+        let content = r#"
+use std::collections::{HashMap, HashSet};
+use crate::store::ContentStore;
+
+/// インデックス
+pub struct Index {
+    id: String,
+}
+
+impl Index {
+    /// 読み込む
+    #[inline]
+    pub fn load() -> Self {
+        Index { id: String::new() }
+    }
+}
+
+fn helper() {}
+"#;
+        let parsed = parse_source(content, "rs").unwrap();
+        assert_eq!(parsed.imports, vec!["std::collections", "crate::store::ContentStore"]);
+
+        let kinds: Vec<(&str, &str)> = parsed
+            .symbols
+            .iter()
+            .map(|s| (s.kind.as_str(), s.name.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![("struct", "Index"), ("impl", "Index"), ("method", "load"), ("function", "helper")]
+        );
+
+        let load = &parsed.symbols[2];
+        assert_eq!(load.parent.as_deref(), Some("Index"));
+        assert_eq!(load.documentation, "読み込む");
+        assert_eq!((load.start_line, load.end_line), (13, 15));
+    }
+
+    #[test]
+    fn test_parse_symbol_visibility() {
+        let visibility = |content: &str, language: &str| -> Vec<(String, String)> {
+            parse_source(content, language)
+                .unwrap()
+                .symbols
+                .into_iter()
+                .map(|s| (s.name, s.visibility))
+                .collect()
+        };
+        let pairs = |items: &[(&str, &str)]| -> Vec<(String, String)> {
+            items.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect()
+        };
+
+        let rust = "pub struct A;\npub(crate) enum B {}\ntrait T { fn f(&self); }\nimpl T for A { fn f(&self) {} }\nfn g() {}\n";
+        assert_eq!(
+            visibility(rust, "rs"),
+            pairs(&[("A", "public"), ("B", "internal"), ("T", "private"), ("f", "public"), ("T for A", "private"), ("f", "public"), ("g", "private")])
+        );
+
+        let ts = "export class A {\n  private x() {}\n  y() {}\n}\ninterface I {}\n";
+        assert_eq!(visibility(ts, "ts"), pairs(&[("A", "public"), ("x", "private"), ("y", "public"), ("I", "private")]));
+
+        let java = "public class A {\n  protected void m() {}\n  void n() {}\n}\n";
+        assert_eq!(visibility(java, "java"), pairs(&[("A", "public"), ("m", "protected"), ("n", "internal")]));
+
+        let go = "package p\ntype Store struct{}\nfunc helper() {}\n";
+        assert_eq!(visibility(go, "go"), pairs(&[("Store", "public"), ("helper", "private")]));
+
+        let py = "class A:\n    def __secret(self): pass\n    def _hook(self): pass\n";
+        assert_eq!(visibility(py, "py"), pairs(&[("A", "public"), ("__secret", "private"), ("_hook", "protected")]));
+
+        let kt = "internal class A {\n  private fun f() {}\n}\n";
+        assert_eq!(visibility(kt, "kt"), pairs(&[("A", "internal"), ("f", "private")]));
+    }
+
+    #[test]
+    fn test_parse_python_and_go_symbols() {
+        let python = "import os as o\nfrom pathlib import Path\n\nclass A:\n    def run(self):\n        \"\"\"実行する\"\"\"\n        pass\n";
+        let parsed = parse_source(python, "py").unwrap();
+        assert_eq!(parsed.imports, vec!["os", "pathlib"]);
+        assert_eq!(parsed.symbols[1].kind, "method");
+        assert_eq!(parsed.symbols[1].documentation, "実行する");
+
+        let go = "package main\n\nimport (\n\t\"fmt\"\n)\n\n// Server はサーバー\ntype Server struct{}\n\nfunc (s *Server) Start() {}\n";
+        let parsed = parse_source(go, "go").unwrap();
+        assert_eq!(parsed.imports, vec!["fmt"]);
+        assert_eq!(parsed.symbols[0].documentation, "Server はサーバー");
+        assert_eq!(parsed.symbols[1].parent.as_deref(), Some("Server"));
+    }
+
+    #[test]
+    fn test_parse_c_family_symbols() {
+        let cpp = "#include <vector>\n#include \"config.h\"\n\nclass Parser {\npublic:\n    int size() const { return 0; }\n};\n\n// 解析する\nstd::vector<int>& Parser::parse(const char* text) {\n    return items;\n}\n\nint main() { return 0; }\n";
+        let parsed = parse_source(cpp, "cpp").unwrap();
+        assert_eq!(parsed.imports, vec!["vector", "config.h"]);
+        let symbols: Vec<(&str, &str, Option<&str>)> = parsed
+            .symbols
+            .iter()
+            .map(|s| (s.kind.as_str(), s.name.as_str(), s.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                ("class", "Parser", None),
+                ("method", "size", Some("Parser")),
+                ("method", "parse", Some("Parser")),
+                ("function", "main", None),
+            ]
+        );
+        assert_eq!(parsed.symbols[2].documentation, "解析する");
+
+        let cs = "using System;\nusing IO = System.IO;\n\nnamespace App;\n\npublic class Program\n{\n    /// <summary>エントリーポイント</summary>\n    public static void Main(string[] args) {}\n}\n";
+        let parsed = parse_source(cs, "cs").unwrap();
+        assert_eq!(parsed.imports, vec!["System", "System.IO"]);
+        assert_eq!(parsed.symbols[1].name, "Main");
+        assert_eq!(parsed.symbols[1].parent.as_deref(), Some("Program"));
+        assert_eq!(parsed.symbols[1].documentation, "エントリーポイント");
+    }
+
+    #[test]
+    fn test_parse_type_fields() {
+        let fields = |content: &str, language: &str| -> Vec<(String, String)> {
+            parse_source(content, language).unwrap().fields.into_iter().map(|f| (f.parent, f.name)).collect()
+        };
+        let pair = |parent: &str, name: &str| (parent.to_string(), name.to_string());
+
+        let rust = "pub struct Config {\n    pub name: String,\n    port: u16,\n}\n\nstruct Pair(u8, u8);\n";
+        assert_eq!(fields(rust, "rs"), vec![pair("Config", "name"), pair("Config", "port")]);
+        assert_eq!(parse_source(rust, "rs").unwrap().fields[1].line, 3);
+
+        let ts = "interface Options {\n  depth: number;\n}\nclass Walker {\n  private root: string;\n  visit() {}\n}\n";
+        assert_eq!(fields(ts, "ts"), vec![pair("Options", "depth"), pair("Walker", "root")]);
+
+        let py = "class User:\n    table: str = \"users\"\n\n    def __init__(self, name):\n        self.name = name\n        self.name = name.strip()\n        other.x = 1\n";
+        assert_eq!(fields(py, "py"), vec![pair("User", "table"), pair("User", "name")]);
+
+        let go = "package main\n\ntype Server struct {\n\tAddr, Host string\n\thttp.Handler\n}\n";
+        assert_eq!(fields(go, "go"), vec![pair("Server", "Addr"), pair("Server", "Host")]);
+
+        let java = "class Account {\n    private int id, owner;\n    void close() {}\n}\n";
+        assert_eq!(fields(java, "java"), vec![pair("Account", "id"), pair("Account", "owner")]);
+
+        let cs = "public class Order\n{\n    private int count;\n    public string Name { get; set; }\n}\n";
+        assert_eq!(fields(cs, "cs"), vec![pair("Order", "count"), pair("Order", "Name")]);
+    }
+
+    #[test]
+    fn test_parse_ruby_php_and_kotlin_symbols() {
+        let ruby = "require 'json'\nrequire_relative './lib/store'\n\nclass User < Base\n  # 名前を返す\n  def name\n    @name\n  end\n\n  def self.find(id)\n  end\nend\n";
+        let parsed = parse_source(ruby, "rb").unwrap();
+        assert_eq!(parsed.imports, vec!["json", "./lib/store"]);
+        assert_eq!(parsed.symbols[1].name, "name");
+        assert_eq!(parsed.symbols[1].parent.as_deref(), Some("User"));
+        assert_eq!(parsed.symbols[1].documentation, "名前を返す");
+        assert_eq!(parsed.symbols[2].name, "find");
+
+        let php = "<?php\nnamespace App;\n\nuse App\\Models\\User;\nuse Foo\\{Bar, Baz as Q};\nrequire_once __DIR__ . '/vendor/autoload.php';\n\nclass UserController extends Controller {\n    /** 表示する */\n    public function show($id) { return 1; }\n}\n";
+        let parsed = parse_source(php, "php").unwrap();
+        assert_eq!(parsed.imports, vec!["App\\Models\\User", "Foo\\Bar", "Foo\\Baz", "vendor/autoload.php"]);
+        assert_eq!(parsed.symbols[1].name, "show");
+        assert_eq!(parsed.symbols[1].parent.as_deref(), Some("UserController"));
+        assert_eq!(parsed.symbols[1].documentation, "表示する");
+
+        let kotlin = "package app\n\nimport android.os.Bundle\nimport kotlinx.coroutines.*\n\ninterface Repository\n\nclass MainActivity : Activity() {\n    // 作成時\n    override fun onCreate(b: Bundle) {}\n}\n\nfun String.shout() = uppercase()\n";
+        let parsed = parse_source(kotlin, "kt").unwrap();
+        assert_eq!(parsed.imports, vec!["android.os.Bundle", "kotlinx.coroutines"]);
+        let symbols: Vec<(&str, &str, Option<&str>)> = parsed
+            .symbols
+            .iter()
+            .map(|s| (s.kind.as_str(), s.name.as_str(), s.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                ("interface", "Repository", None),
+                ("class", "MainActivity", None),
+                ("method", "onCreate", Some("MainActivity")),
+                ("method", "shout", Some("String")),
+            ]
+        );
+        assert_eq!(parsed.symbols[2].documentation, "作成時");
+    }
+
+    #[test]
+    fn test_parse_type_relations() {
+        let relations = |content: &str, ext: &str| -> Vec<(String, String, String)> {
+            parse_source(content, ext)
+                .unwrap()
+                .relations
+                .into_iter()
+                .map(|r| (r.kind, r.name, r.target))
+                .collect()
+        };
+        let rel = |kind: &str, name: &str, target: &str| (kind.to_string(), name.to_string(), target.to_string());
+
+        let rust = "trait Store: Send + std::fmt::Debug {}\nimpl fmt::Display for Index<T> {}\nimpl !Sync for Index {}\nimpl Index {}\n";
+        assert_eq!(
+            relations(rust, "rs"),
+            vec![rel("extends", "Store", "Send"), rel("extends", "Store", "Debug"), rel("implements", "Index", "Display")]
+        );
+
+        let ts = "class Server extends events.EventEmitter implements Handler, Closable<T> {}\ninterface Handler extends Base<string> {}\n";
+        assert_eq!(
+            relations(ts, "ts"),
+            vec![
+                rel("extends", "Server", "EventEmitter"),
+                rel("implements", "Server", "Handler"),
+                rel("implements", "Server", "Closable"),
+                rel("extends", "Handler", "Base"),
+            ]
+        );
+
+        let python = "class Repo(abc.ABC, Generic[T], object, metaclass=Meta):\n    pass\n";
+        assert_eq!(relations(python, "py"), vec![rel("extends", "Repo", "ABC"), rel("extends", "Repo", "Generic")]);
+
+        let java = "class UserService extends BaseService implements Service, Closeable {}\ninterface Service extends Runnable {}\n";
+        assert_eq!(
+            relations(java, "java"),
+            vec![
+                rel("extends", "UserService", "BaseService"),
+                rel("implements", "UserService", "Service"),
+                rel("implements", "UserService", "Closeable"),
+                rel("extends", "Service", "Runnable"),
+            ]
+        );
+
+        let go = "package io\n\ntype ReadCloser interface {\n\tio.Reader\n\tClose() error\n}\n";
+        let parsed = parse_source(go, "go").unwrap();
+        assert_eq!(parsed.relations[0].target, "Reader");
+        assert_eq!(parsed.symbols[1].kind, "method");
+        assert_eq!(parsed.symbols[1].parent.as_deref(), Some("ReadCloser"));
+    }
+
+    #[test]
+    fn test_parse_function_signatures() {
+        // 関数ごとの（引数名: 型, 戻り値の型, async）
+        let signatures = |content: &str, language: &str| -> Vec<(Vec<String>, String, bool)> {
+            parse_source(content, language)
+                .unwrap()
+                .symbols
+                .into_iter()
+                .filter(|s| s.kind == "function" || s.kind == "method")
+                .map(|s| {
+                    let parameters = s.parameters.iter().map(|p| format!("{}: {}", p.name, p.type_name)).collect();
+                    (parameters, s.return_type, s.is_async)
+                })
+                .collect()
+        };
+        let sig = |parameters: &[&str], return_type: &str, is_async: bool| {
+            (parameters.iter().map(|p| p.to_string()).collect::<Vec<_>>(), return_type.to_string(), is_async)
+        };
+
+        let rust = "impl Store {\n    pub async fn load(&self, path: &Path, retries: u32) -> Result<Config> { todo!() }\n}\nfn main() {}\n";
+        assert_eq!(signatures(rust, "rs"), vec![sig(&["path: &Path", "retries: u32"], "Result<Config>", true), sig(&[], "", false)]);
+
+        let ts = "export async function fetchUser(id: string, opts?: Options): Promise<User> {}\nconst add = (a: number, b) => a + b;\nconst twice = x => x * 2;\n";
+        assert_eq!(
+            signatures(ts, "ts"),
+            vec![
+                sig(&["id: string", "opts: Options"], "Promise<User>", true),
+                sig(&["a: number", "b: "], "", false),
+                sig(&["x: "], "", false),
+            ]
+        );
+
+        let py = "class Repo:\n    async def find(self, key: str, *args, limit: int = 10) -> Optional[Row]:\n        pass\n";
+        assert_eq!(signatures(py, "py"), vec![sig(&["key: str", "*args: ", "limit: int"], "Optional[Row]", true)]);
+
+        let go = "package main\n\nfunc (s *Server) Handle(w http.ResponseWriter, a, b int) (int, error) {\n\treturn 0, nil\n}\n";
+        assert_eq!(signatures(go, "go"), vec![sig(&["w: http.ResponseWriter", "a: int", "b: int"], "(int, error)", false)]);
+
+        let java = "class Api {\n    public List<String> names(int limit, String prefix) { return null; }\n}\n";
+        assert_eq!(signatures(java, "java"), vec![sig(&["limit: int", "prefix: String"], "List<String>", false)]);
+
+        let c = "int copy(char *buf, size_t len) { return 0; }\n";
+        assert_eq!(signatures(c, "c"), vec![sig(&["buf: char *", "len: size_t"], "int", false)]);
+
+        let cs = "public class Client\n{\n    public async Task<int> SendAsync(string body) { return 0; }\n}\n";
+        assert_eq!(signatures(cs, "cs"), vec![sig(&["body: string"], "Task<int>", true)]);
+
+        let kotlin = "suspend fun load(id: Int, name: String): User? { TODO() }\n";
+        assert_eq!(signatures(kotlin, "kt"), vec![sig(&["id: Int", "name: String"], "User?", true)]);
+
+        let php = "<?php\nfunction show(int $id, $opts): string { return ''; }\n";
+        assert_eq!(signatures(php, "php"), vec![sig(&["$id: int", "$opts: "], "string", false)]);
+
+        let ruby = "def find(id, limit: 10)\nend\n";
+        assert_eq!(signatures(ruby, "rb"), vec![sig(&["id: ", "limit: "], "", false)]);
+    }
+}
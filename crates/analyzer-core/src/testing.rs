@@ -0,0 +1,132 @@
+/*!
+ * テストファイルの分類
+ *
+ * ファイルを本体のコードとテストに分け、テスト関数の数を数える
+ * - テストファイル: `tests/`・`test/`・`__tests__/`・`spec/` 配下、`*_test.go`・`test_*.py`・`*.spec.ts`・`*Test.java` など
+ * - 本体に含まれるテスト: Rustの `#[cfg(test)]` モジュール
+ *
+ * 主な仕様:
+ * - テスト関数は言語ごとの書き方で数える
+ *   （Rust: `#[test]`・`#[tokio::test]`、Go: `func Test`、Python: `def test_`、TS/JS: `it(`・`test(`、Java: `@Test`）
+ * - テストファイルが本体のファイルをテストしているかは、importとファイル名（`foo_test.go` → `foo.go`）から判定（`Index::tests_of`）
+ * - ファイル名での対応は、テストファイルが本体と同じディレクトリかその親（`tests/` などを除く）にある場合のみ
+ *
+ * 制限事項:
+ * - テスト関数の数は行の書き出しによる推定（マクロで生成したテスト・パラメータ化テストのケース数は数えない）
+ * - カバレッジ（実行された行）は計測しない
+ */
+
+use std::path::{Path, PathBuf};
+
+/// テストファイルの名前に含まれる印
+const TEST_NAME_MARKERS: &[&str] = &["_test.", ".test.", ".spec.", "_spec.", "Test.", "Tests."];
+/// テストファイルを置くディレクトリ
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec"];
+
+/// テストファイルか（パスのみで判定）
+///
+/// # 引数
+/// * `path` - ファイルパス
+///
+/// # 戻り値
+/// * `bool` - テストファイルの場合true
+pub(crate) fn is_test_path(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let in_test_dir = path
+        .parent()
+        .is_some_and(|dir| dir.components().any(|c| TEST_DIRS.contains(&c.as_os_str().to_str().unwrap_or(""))));
+    in_test_dir
+        || (name.starts_with("test_") && name.ends_with(".py"))
+        || TEST_NAME_MARKERS.iter().any(|marker| name.contains(marker))
+}
+
+/// テスト関数の数を数える
+///
+/// # 引数
+/// * `content` - ファイル内容
+/// * `language` - 言語
+/// * `is_test` - テストファイルか（本体のファイルはRustの `#[cfg(test)]` 以外を数えない）
+///
+/// # 戻り値
+/// * `usize` - テスト関数の数
+pub(crate) fn count_tests(content: &str, language: &str, is_test: bool) -> usize {
+    let lines = content.lines().map(str::trim_start);
+    match language {
+        "rs" if is_test || content.contains("#[cfg(test)]") => lines
+            .filter(|l| *l == "#[test]" || (l.starts_with("#[") && l.ends_with("::test]")))
+            .count(),
+        _ if !is_test => 0,
+        "go" => lines.filter(|l| l.starts_with("func Test")).count(),
+        "py" => lines.filter(|l| l.starts_with("def test") || l.starts_with("async def test")).count(),
+        "ts" | "tsx" | "js" | "jsx" => lines
+            .filter(|l| ["it(", "test(", "it.each", "test.each"].iter().any(|call| l.starts_with(call)))
+            .count(),
+        "java" => lines.filter(|l| l.starts_with("@Test") || l.starts_with("@ParameterizedTest")).count(),
+        _ => 0,
+    }
+}
+
+/// テストファイルの名前が本体のファイルに対応するか
+///
+/// テストファイルのディレクトリ（`tests/` などを除いたもの）が本体のファイルのディレクトリか、
+/// その親である場合のみ対応するとみなす。
+///
+/// # 引数
+/// * `test` - テストファイルのパス
+/// * `source` - 本体のファイルのパス
+///
+/// # 戻り値
+/// * `bool` - 対応する場合true
+pub(crate) fn matches_by_name(test: &Path, source: &Path) -> bool {
+    let stem = source.file_stem().and_then(|s| s.to_str());
+    if stem.is_none() || tested_stem(test).as_deref() != stem {
+        return false;
+    }
+    let test_dir: PathBuf = test
+        .parent()
+        .map(|dir| dir.components().filter(|c| !TEST_DIRS.contains(&c.as_os_str().to_str().unwrap_or(""))).collect())
+        .unwrap_or_default();
+    source.parent().is_some_and(|dir| dir.starts_with(&test_dir))
+}
+
+/// テストファイルの名前から、テスト対象のファイル名（拡張子なし）を推定
+fn tested_stem(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    if let Some(rest) = name.strip_prefix("test_") {
+        return Some(rest.split('.').next().unwrap_or(rest).to_string());
+    }
+    TEST_NAME_MARKERS
+        .iter()
+        .find_map(|marker| name.find(marker))
+        .map(|end| name[..end].to_string())
+        .filter(|stem| !stem.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_and_count_tests() {
+        assert!(is_test_path(Path::new("/repo/pkg/store_test.go")));
+        assert!(is_test_path(Path::new("/repo/web/button.spec.ts")));
+        assert!(is_test_path(Path::new("/repo/crates/core/tests/index.rs")));
+        assert!(is_test_path(Path::new("/repo/app/test_views.py")));
+        assert!(!is_test_path(Path::new("/repo/src/testing.rs")));
+        assert!(!is_test_path(Path::new("/repo/src/contest.py")));
+
+        let rust = "pub fn add() {}\n\n#[cfg(test)]\nmod tests {\n    #[test]\n    fn a() {}\n    #[tokio::test]\n    async fn b() {}\n}\n";
+        assert_eq!(count_tests(rust, "rs", false), 2);
+        assert_eq!(count_tests("describe('x', () => {\n  it('a', () => {});\n  test('b', () => {});\n});\n", "ts", true), 2);
+        assert_eq!(count_tests("func TestA(t *testing.T) {}\nfunc helper() {}\n", "go", false), 0);
+
+        assert_eq!(tested_stem(Path::new("pkg/store_test.go")).as_deref(), Some("store"));
+        assert_eq!(tested_stem(Path::new("app/test_views.py")).as_deref(), Some("views"));
+        assert_eq!(tested_stem(Path::new("web/Button.spec.tsx")).as_deref(), Some("Button"));
+        assert_eq!(tested_stem(Path::new("tests/index.rs")), None);
+
+        assert!(matches_by_name(Path::new("/repo/tests/test_views.py"), Path::new("/repo/app/views.py")));
+        assert!(matches_by_name(Path::new("/repo/web/__tests__/Button.spec.tsx"), Path::new("/repo/web/Button.tsx")));
+        assert!(!matches_by_name(Path::new("/repo/api/index.test.ts"), Path::new("/repo/web/index.ts")));
+    }
+}
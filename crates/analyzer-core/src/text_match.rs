@@ -0,0 +1,172 @@
+/*!
+ * 検索結果の一致位置と抜粋
+ *
+ * エディタやMCPクライアントが一致した位置を直接開けるよう、
+ * 検索語に一致した箇所の行・列・バイト範囲を求め、一致箇所の前後を抜粋する
+ *
+ * 主な仕様:
+ * - 大文字小文字を無視して一致させ、位置は元の本文のバイト範囲で返す（小文字化でバイト長が変わる文字も元の文字に対応付ける）
+ * - 行・列は1始まり、列は文字（Unicodeのスカラー値）単位
+ * - 抜粋は文字境界に合わせて切り出すため、日本語などのマルチバイト文字の途中で切らない
+ *
+ * 制限事項:
+ * - 1件の検索結果に記録する一致は先頭から `MAX_MATCHES` 件まで
+ * - 列は書記素クラスタではなく文字単位のため、結合文字を含む行ではエディタの表示とずれることがある
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// 1件の検索結果に記録する一致の最大数
+pub(crate) const MAX_MATCHES: usize = 100;
+
+/// 検索語に一致した箇所
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchMatch {
+    /// 行（1始まり）
+    pub line: usize,
+    /// 列（1始まり、文字単位）
+    pub column: usize,
+    /// ファイル先頭からの開始バイト位置
+    pub start: usize,
+    /// ファイル先頭からの終了バイト位置（この位置を含まない）
+    pub end: usize,
+}
+
+/// 本文から検索語に一致した箇所を探す
+///
+/// # 引数
+/// * `content` - 本文
+/// * `terms` - 検索語（大文字小文字を無視、空の語は無視）
+/// * `lines` - 対象とする行の範囲（1始まり、両端を含む、Noneの場合は全体）
+///
+/// # 戻り値
+/// * `Vec<SearchMatch>` - 出現順の一致箇所（最大 `MAX_MATCHES` 件）
+pub(crate) fn find_matches(content: &str, terms: &[&str], lines: Option<(usize, usize)>) -> Vec<SearchMatch> {
+    // 小文字化した本文の各バイトに、元の本文の文字のバイト範囲を対応付ける
+    let mut lowered = String::with_capacity(content.len());
+    let mut origins: Vec<(usize, usize)> = Vec::with_capacity(content.len());
+    for (offset, c) in content.char_indices() {
+        let before = lowered.len();
+        lowered.extend(c.to_lowercase());
+        origins.extend(std::iter::repeat_n((offset, offset + c.len_utf8()), lowered.len() - before));
+    }
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for term in terms {
+        let term = term.to_lowercase();
+        if term.is_empty() {
+            continue;
+        }
+        for (pos, matched) in lowered.match_indices(&term) {
+            ranges.push((origins[pos].0, origins[pos + matched.len() - 1].1));
+        }
+    }
+    ranges.sort_unstable();
+    ranges.dedup();
+
+    let mut matches = Vec::new();
+    for (start, end) in ranges {
+        let line_index = line_starts.partition_point(|&s| s <= start) - 1;
+        let line = line_index + 1;
+        if lines.is_some_and(|(first, last)| line < first || line > last) {
+            continue;
+        }
+        matches.push(SearchMatch {
+            line,
+            column: content[line_starts[line_index]..start].chars().count() + 1,
+            start,
+            end,
+        });
+        if matches.len() >= MAX_MATCHES {
+            break;
+        }
+    }
+    matches
+}
+
+/// 一致箇所の前後を抜粋する
+///
+/// # 引数
+/// * `content` - 本文
+/// * `first` - 最初の一致箇所（Noneの場合は本文の先頭を抜粋）
+/// * `max_len` - 一致箇所の前後に含めるおおよそのバイト数の合計
+///
+/// # 戻り値
+/// * `String` - 抜粋（途中で切った側に `...` を付ける）
+pub(crate) fn excerpt(content: &str, first: Option<&SearchMatch>, max_len: usize) -> String {
+    match first {
+        Some(m) => {
+            let start = floor_char_boundary(content, m.start.saturating_sub(max_len / 2));
+            let end = ceil_char_boundary(content, m.end + max_len / 2);
+            format!("...{}...", &content[start..end])
+        }
+        None => {
+            let end = floor_char_boundary(content, max_len);
+            format!("{}...", &content[..end])
+        }
+    }
+}
+
+/// 指定位置以下で最も近い文字境界を返す
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// 指定位置以上で最も近い文字境界を返す
+fn ceil_char_boundary(s: &str, mut index: usize) -> usize {
+    index = index.min(s.len());
+    while !s.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches_in_japanese_text() {
+        let content = "// 設定を読み込む\nfn load_config() {}\n/// Config（設定）を返す\nfn config() {}\n";
+        let matches = find_matches(content, &["CONFIG"], None);
+
+        let positions: Vec<(usize, usize)> = matches.iter().map(|m| (m.line, m.column)).collect();
+        assert_eq!(positions, vec![(2, 9), (3, 5), (4, 4)]);
+        for m in &matches {
+            assert!(content[m.start..m.end].eq_ignore_ascii_case("config"));
+        }
+
+        let positions: Vec<usize> = find_matches(content, &["設定", "fn"], Some((1, 2))).iter().map(|m| m.line).collect();
+        assert_eq!(positions, vec![1, 2]);
+        assert!(find_matches(content, &[""], None).is_empty());
+    }
+
+    #[test]
+    fn test_find_matches_maps_case_folding_to_original_bytes() {
+        // 'İ' は小文字化すると2文字（3バイト）になる
+        let content = "İİ abc";
+        let matches = find_matches(content, &["abc"], None);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(&content[matches[0].start..matches[0].end], "abc");
+        assert_eq!(matches[0].column, 4);
+    }
+
+    #[test]
+    fn test_excerpt_is_utf8_safe() {
+        let content = "あ".repeat(100) + "検索語" + &"い".repeat(100);
+        let matches = find_matches(&content, &["検索語"], None);
+        for max_len in 0..20 {
+            let text = excerpt(&content, matches.first(), max_len);
+            assert!(text.contains("検索語"));
+        }
+        assert_eq!(excerpt("日本語", None, 4), "日...");
+    }
+}
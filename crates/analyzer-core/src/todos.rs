@@ -0,0 +1,108 @@
+/*!
+ * 作業メモのコメントの抽出
+ *
+ * ファイル内容からTODO・FIXME・HACK・XXXのコメントを抜き出し、技術的負債の一覧（Wikiの「技術的負債」章・FAQ）に使う
+ * - 解析時にファイルごとに `FileInfo::todos` に保持し、Gitの履歴がある場合はその行を最後に変更した作成者を付ける
+ *
+ * 主な仕様:
+ * - コメントの記号（`//`・`#`・ブロックコメントの開始と行頭の `*`・`--`・`;`・`<!--`）の後の大文字の目印のみ（`todo!()`・`TodoList` などは対象外）
+ * - 目印の後の `(担当者)`・`:` は内容から除き、内容は `MAX_TEXT_CHARS` 文字まで
+ * - 作成者は `git blame` 相当の行の最終変更者（`analysis.git-history = false` の場合・Gitリポジトリでない場合はNone）
+ *
+ * 制限事項:
+ * - 文字列リテラルの中の `// TODO` も抜き出す
+ * - 作成者はHEADのコミットで判定するため、コミットしていない変更の行は別の作成者になる場合がある
+ */
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 抜き出す目印（深刻な順）
+pub const TODO_MARKERS: &[&str] = &["FIXME", "HACK", "XXX", "TODO"];
+/// 内容の最大文字数
+const MAX_TEXT_CHARS: usize = 160;
+
+/// コメントの記号に続く目印
+static TODO_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(?://+|#+|/\*+|^[ \t]*\*+|--|;+|<!--)[ \t]*@?\b(TODO|FIXME|HACK|XXX)\b(?:\([^)\r\n]*\))?[ \t]*:?[ \t]*([^\r\n]*)").unwrap()
+});
+
+/// 作業メモのコメント
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TodoComment {
+    /// 行番号（1始まり）
+    pub line: usize,
+    /// 目印（TODO|FIXME|HACK|XXX）
+    pub marker: String,
+    /// 目印の後の内容（書かれていない場合は空）
+    pub text: String,
+    /// その行を最後に変更したコミットの作成者（Gitの履歴がない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+}
+
+impl TodoComment {
+    /// 目印の意味（Wikiの表の説明）
+    pub fn marker_label(&self) -> &'static str {
+        marker_label(&self.marker)
+    }
+}
+
+/// 目印の意味
+pub fn marker_label(marker: &str) -> &'static str {
+    match marker {
+        "FIXME" => "不具合・要修正",
+        "HACK" => "暫定的な回避策",
+        "XXX" => "要注意・危険な箇所",
+        _ => "未実装・今後の作業",
+    }
+}
+
+/// ファイル内容から作業メモのコメントを抜き出す
+///
+/// # 引数
+/// * `content` - ファイル内容
+///
+/// # 戻り値
+/// * `Vec<TodoComment>` - 行の順の作業メモ（作成者はNone）
+pub(crate) fn extract_todos(content: &str) -> Vec<TodoComment> {
+    TODO_PATTERN
+        .captures_iter(content)
+        .map(|captures| {
+            let start = captures.get(0).map_or(0, |m| m.start());
+            let text = captures[2].trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+            TodoComment {
+                line: content[..start].matches('\n').count() + 1,
+                marker: captures[1].to_string(),
+                text: text.chars().take(MAX_TEXT_CHARS).collect(),
+                author: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_todos_from_comments() {
+        let content = "fn main() {\n    // TODO: handle errors\n    todo!();\n    let x = 1; // FIXME(alice) overflow on 32-bit\n}\n\
+                       # HACK retry until the API is fixed\n/* XXX: unsafe cast */\nstruct TodoList;\n<!-- TODO -->\n";
+        let todos = extract_todos(content);
+        let found: Vec<(usize, &str, &str)> = todos.iter().map(|t| (t.line, t.marker.as_str(), t.text.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, "TODO", "handle errors"),
+                (4, "FIXME", "overflow on 32-bit"),
+                (6, "HACK", "retry until the API is fixed"),
+                (7, "XXX", "unsafe cast"),
+                (9, "TODO", ""),
+            ]
+        );
+        assert_eq!(todos[1].marker_label(), "不具合・要修正");
+    }
+}
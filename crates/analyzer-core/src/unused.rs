@@ -0,0 +1,273 @@
+/*!
+ * 未使用コードの候補の検出
+ *
+ * 内部依存グラフとシンボルの参照から、使われていない可能性のあるコードを挙げる
+ * - 孤立したモジュール: リポジトリ内のどのファイルからもimportされていないファイル
+ * - 参照されない公開関数: 定義以外のどこにも名前が現れない公開（public）の関数・メソッド
+ *
+ * 主な仕様:
+ * - テストファイル・エントリーポイント・規約上の起点（`main.*`・`lib.rs`・`build.rs`・`__init__.py`・`index.*` など）は
+ *   孤立したモジュールとしない
+ * - 孤立したモジュールの判定はimportをファイル単位で解決できる言語（TypeScript/JavaScript・Python・Rust・Go）のみ
+ *   - Rustは親モジュールの `mod` 宣言、Goは同じパッケージ（ディレクトリ）のファイルのimportも参照とみなす
+ * - 参照は識別子の完全一致で数える（コメント・文字列・同名の別シンボルへの言及も参照とみなす）
+ * - APIエンドポイントのハンドラー・トレイト実装などでよく使う名前（`new`・`fmt`・`from` など）は対象外
+ *
+ * 制限事項:
+ * - リフレクション・動的import・マクロ・設定ファイルからの参照は検出できない
+ * - 公開範囲を判定できない言語のシンボルは対象外
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{Index, Symbol};
+
+/// 孤立したモジュールを判定する言語（importをファイル単位で解決できるもの）
+const ORPHAN_LANGUAGES: &[&str] = &["ts", "js", "py", "rs", "go"];
+
+/// 規約上の起点となるファイル名（拡張子なし）
+const ROOT_STEMS: &[&str] = &["main", "lib", "build", "mod", "index", "__init__", "__main__", "setup", "conftest", "manage"];
+
+/// フレームワーク・トレイトから呼ばれるため対象外とする関数名
+const IGNORED_NAMES: &[&str] = &[
+    "main", "new", "default", "fmt", "from", "into", "try_from", "drop", "clone", "eq", "hash", "deref", "deref_mut",
+    "as_ref", "from_str", "to_string", "toString", "constructor", "init", "__init__", "String", "Error", "ServeHTTP",
+];
+
+/// 未使用コードの候補
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnusedCode {
+    /// どのファイルからもimportされていないファイル（パス順）
+    pub orphan_modules: Vec<PathBuf>,
+    /// 定義以外で参照されない公開関数・メソッド（ファイル・行の順）
+    pub unreferenced_symbols: Vec<UnreferencedSymbol>,
+}
+
+/// 参照されない公開シンボル
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnreferencedSymbol {
+    pub path: PathBuf,
+    pub name: String,
+    /// 種類（function|method）
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 定義の開始行（1始まり）
+    pub line: usize,
+}
+
+impl UnusedCode {
+    /// 候補がないか
+    pub fn is_empty(&self) -> bool {
+        self.orphan_modules.is_empty() && self.unreferenced_symbols.is_empty()
+    }
+}
+
+impl UnreferencedSymbol {
+    /// 型名付きのシンボル名
+    pub fn qualified_name(&self) -> String {
+        match &self.parent {
+            Some(parent) => format!("{}::{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// 未使用コードの候補を検出
+///
+/// # 引数
+/// * `index` - インデックス（ファイル内容は `Index::file_content` で読む）
+///
+/// # 戻り値
+/// * `UnusedCode` - 孤立したモジュールと参照されない公開関数
+pub(crate) fn find_unused_code(index: &Index) -> UnusedCode {
+    let identifier = Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").expect("固定の正規表現");
+    let contents: HashMap<&Path, String> = index
+        .files
+        .iter()
+        .filter_map(|file| Some((file.path.as_path(), index.file_content(file)?.into_owned())))
+        .collect();
+
+    // 識別子ごとの出現回数（全ファイル）
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for content in contents.values() {
+        for word in identifier.find_iter(content) {
+            *occurrences.entry(word.as_str()).or_default() += 1;
+        }
+    }
+
+    UnusedCode {
+        orphan_modules: orphan_modules(index, &contents),
+        unreferenced_symbols: unreferenced_symbols(index, &contents, &occurrences, &identifier),
+    }
+}
+
+/// どのファイルからもimportされていないファイル
+fn orphan_modules(index: &Index, contents: &HashMap<&Path, String>) -> Vec<PathBuf> {
+    let imported: HashSet<&Path> = index.internal_dependencies.values().flatten().map(PathBuf::as_path).collect();
+    let entrypoints: HashSet<PathBuf> = index.all_entrypoints().into_iter().map(|e| e.path).collect();
+    // Goは同じパッケージ（ディレクトリ）のいずれかのファイルがimportされていればよい
+    let imported_go_packages: HashSet<&Path> = index
+        .files
+        .iter()
+        .filter(|f| f.language == "go" && (imported.contains(f.path.as_path()) || entrypoints.contains(&f.path)))
+        .filter_map(|f| f.path.parent())
+        .collect();
+
+    let mut orphans: Vec<PathBuf> = index
+        .files
+        .iter()
+        .filter(|f| ORPHAN_LANGUAGES.contains(&f.language.as_str()) && !f.is_test)
+        .filter(|f| !imported.contains(f.path.as_path()) && !entrypoints.contains(&f.path))
+        .filter(|f| !is_root_file(f.path.strip_prefix(&index.repo_path).unwrap_or(&f.path)))
+        .filter(|f| f.language != "go" || !f.path.parent().is_some_and(|dir| imported_go_packages.contains(dir)))
+        .filter(|f| f.language != "rs" || !is_declared_rust_module(&f.path, contents))
+        .map(|f| f.path.clone())
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// 規約上の起点となるファイル（`main.*`・`lib.rs`・`__init__.py`・`index.ts`・型定義ファイル・`bin/`・`examples/` 配下など）
+///
+/// # 引数
+/// * `path` - リポジトリルートからの相対パス
+fn is_root_file(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    ROOT_STEMS.contains(&stem)
+        || file_name.ends_with(".d.ts")
+        || path.components().any(|c| matches!(c.as_os_str().to_str(), Some("bin" | "examples" | "benches" | "scripts")))
+}
+
+/// Rustのファイルが親モジュールで `mod` 宣言されているか
+///
+/// `dir/x.rs`・`dir/x/mod.rs` を、`dir` の `lib.rs`・`main.rs`・`mod.rs` と `dir.rs` の `mod x` から探す
+fn is_declared_rust_module(path: &Path, contents: &HashMap<&Path, String>) -> bool {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let (name, dir) = match stem {
+        "mod" => (
+            path.parent().and_then(|d| d.file_name()).and_then(|n| n.to_str()).unwrap_or_default(),
+            path.parent().and_then(Path::parent),
+        ),
+        _ => (stem, path.parent()),
+    };
+    let Some(dir) = dir else {
+        return false;
+    };
+    let Ok(declaration) = Regex::new(&format!(r"\bmod\s+{}\s*;", regex::escape(name))) else {
+        return false;
+    };
+    let parent_file = dir.file_name().map(|n| dir.with_file_name(format!("{}.rs", n.to_string_lossy())));
+    contents.iter().any(|(file, content)| {
+        let is_parent = file.parent() == Some(dir) || parent_file.as_deref() == Some(*file);
+        is_parent && *file != path && declaration.is_match(content)
+    })
+}
+
+/// 定義以外で名前が現れない公開関数・メソッド
+fn unreferenced_symbols(
+    index: &Index,
+    contents: &HashMap<&Path, String>,
+    occurrences: &HashMap<&str, usize>,
+    identifier: &Regex,
+) -> Vec<UnreferencedSymbol> {
+    let handlers: HashSet<&str> = index.endpoints.iter().filter_map(|e| e.handler.as_deref()).collect();
+    let mut symbols = Vec::new();
+    for file in index.files.iter().filter(|f| !f.is_test) {
+        let Some(content) = contents.get(file.path.as_path()) else {
+            continue;
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        for symbol in file.symbols.iter().filter(|s| is_candidate(s, &handlers)) {
+            // 定義の範囲（シグネチャ・再帰呼び出し）での出現を除く
+            let start = symbol.start_line.saturating_sub(1).min(lines.len());
+            let end = symbol.end_line.min(lines.len()).max(start);
+            let in_definition = lines[start..end]
+                .iter()
+                .flat_map(|line| identifier.find_iter(line))
+                .filter(|word| word.as_str() == symbol.name)
+                .count();
+            if occurrences.get(symbol.name.as_str()).copied().unwrap_or(0) <= in_definition {
+                symbols.push(UnreferencedSymbol {
+                    path: file.path.clone(),
+                    name: symbol.name.clone(),
+                    kind: symbol.kind.clone(),
+                    parent: symbol.parent.clone(),
+                    line: symbol.start_line,
+                });
+            }
+        }
+    }
+    symbols
+}
+
+/// 参照を調べる公開関数・メソッドか
+fn is_candidate(symbol: &Symbol, handlers: &HashSet<&str>) -> bool {
+    matches!(symbol.kind.as_str(), "function" | "method")
+        && symbol.visibility == "public"
+        && !IGNORED_NAMES.contains(&symbol.name.as_str())
+        && !handlers.contains(symbol.name.as_str())
+        && !symbol.name.starts_with("test")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileInfo;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_find_unused_code() {
+        let repo = PathBuf::from("/repo");
+        let function = |name: &str, line: usize| Symbol {
+            kind: "function".to_string(),
+            name: name.to_string(),
+            start_line: line,
+            end_line: line + 2,
+            visibility: "public".to_string(),
+            ..Default::default()
+        };
+        let file = |path: &str, language: &str, content: &str, symbols: Vec<Symbol>| FileInfo {
+            path: repo.join(path),
+            language: language.to_string(),
+            content: Some(content.to_string()),
+            symbols,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                file("src/lib.rs", "rs", "mod store;\nmod legacy;\npub use store::load;\n", vec![]),
+                file(
+                    "src/store.rs",
+                    "rs",
+                    "pub fn load() {\n    save()\n}\npub fn save() {\n    save()\n}\npub fn new() {\n}\n",
+                    vec![function("load", 1), function("save", 4), function("new", 7)],
+                ),
+                file("src/legacy.rs", "rs", "pub fn old() {\n}\n", vec![]),
+                file("src/unlinked.rs", "rs", "fn helper() {}\n", vec![]),
+                file("web/util.ts", "ts", "export function pad() {}\n", vec![]),
+                file("web/app.ts", "ts", "import './util';\n", vec![]),
+            ],
+            internal_dependencies: BTreeMap::from([(repo.join("web/app.ts"), vec![repo.join("web/util.ts")])]),
+            ..Default::default()
+        };
+
+        let unused = index.unused_code();
+        assert_eq!(unused.orphan_modules, vec![repo.join("src/unlinked.rs"), repo.join("web/app.ts")]);
+        let names: Vec<String> = unused.unreferenced_symbols.iter().map(|s| s.qualified_name()).collect();
+        // `save` は自身の再帰呼び出しのみだが、`load` から呼ばれている
+        assert_eq!(names, Vec::<String>::new());
+
+        let mut index = index;
+        index.files[1].content = Some("pub fn load() {\n}\n\npub fn save() {\n    save()\n}\n".to_string());
+        let unused = index.unused_code();
+        let names: Vec<(String, usize)> = unused.unreferenced_symbols.iter().map(|s| (s.qualified_name(), s.line)).collect();
+        assert_eq!(names, vec![("save".to_string(), 4)]);
+    }
+}
@@ -0,0 +1,79 @@
+/*!
+ * ベンダリング・サードパーティディレクトリの検出
+ *
+ * リポジトリに取り込まれた外部コードのディレクトリをヒューリスティックで判定する
+ * - node_modules, bower_components（JavaScript）
+ * - vendor, _vendor（Go, PHP, Rubyなど）
+ * - third_party, third-party, thirdparty, 3rdparty
+ * - Pods（CocoaPods）、site-packages（Python）
+ *
+ * 主な仕様:
+ * - 判定されたディレクトリ配下はモジュール・統計・図から除外する
+ * - 除外したディレクトリは直下のエントリ名（パッケージ）とともに記録し、Wikiの付録で紹介する
+ * - `analysis.detect-vendored = false` で無効化できる
+ *
+ * 制限事項:
+ * - ディレクトリ名のみで判定（中身が自作コードでも除外される）
+ */
+
+use serde::{Deserialize, Serialize};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+/// 検出されたベンダリングディレクトリ
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VendoredDir {
+    /// リポジトリルートからの相対パス
+    pub path: PathBuf,
+    /// 判定理由（種類の説明）
+    pub reason: String,
+    /// 直下のエントリ名（パッケージ名）
+    pub packages: Vec<String>,
+}
+
+/// ディレクトリ名がベンダリングディレクトリかを判定
+///
+/// # 引数
+/// * `dir_name` - ディレクトリ名
+///
+/// # 戻り値
+/// * `Option<&'static str>` - ベンダリングディレクトリの場合は種類の説明
+pub(crate) fn vendored_reason(dir_name: &OsStr) -> Option<&'static str> {
+    match dir_name.to_str()? {
+        "node_modules" => Some("npmの依存パッケージ"),
+        "bower_components" => Some("Bowerの依存パッケージ"),
+        "vendor" | "_vendor" => Some("ベンダリングされた依存パッケージ"),
+        "third_party" | "third-party" | "thirdparty" | "3rdparty" => Some("サードパーティのソースコード"),
+        "Pods" => Some("CocoaPodsの依存パッケージ"),
+        "site-packages" => Some("Pythonの依存パッケージ"),
+        _ => None,
+    }
+}
+
+/// ベンダリングディレクトリの情報を作成
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `dir` - ベンダリングディレクトリ
+/// * `reason` - 判定理由
+///
+/// # 戻り値
+/// * `VendoredDir` - ディレクトリ情報
+pub(crate) fn describe(repo_path: &Path, dir: &Path, reason: &str) -> VendoredDir {
+    let mut packages: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with('.'))
+                .collect()
+        })
+        .unwrap_or_default();
+    packages.sort();
+
+    VendoredDir {
+        path: dir.strip_prefix(repo_path).unwrap_or(dir).to_path_buf(),
+        reason: reason.to_string(),
+        packages,
+    }
+}
@@ -0,0 +1,177 @@
+/*!
+ * モノレポ・ワークスペースの検出
+ *
+ * マニフェストのワークスペースの定義から、リポジトリとモジュールの間のパッケージの階層を作る
+ * - Cargoワークスペース（`[workspace] members`）
+ * - npm/yarnワークスペース（package.jsonの `workspaces`）・pnpm（pnpm-workspace.yaml）
+ * - Goのマルチモジュール（go.workの `use`、go.workがない場合は複数のgo.mod）
+ *
+ * 主な仕様:
+ * - メンバーのglob（ワイルドカードを含むパス）に一致するディレクトリの、同じエコシステムのマニフェストをパッケージとする
+ * - ワークスペースのルート自身がパッケージを持つ場合（Cargoの `[package]` など）はルートもパッケージとする
+ * - ファイルは最も深いパッケージのディレクトリに属する（`Index::package_of`）
+ * - パッケージ間の依存はマニフェストの内部依存から求める
+ *
+ * 制限事項:
+ * - ワークスペースの `exclude` は考慮しない
+ * - ワークスペースでないリポジトリ（マニフェストが1つ）ではパッケージを作らない
+ */
+
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::Manifest;
+
+/// ワークスペースのパッケージ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackageInfo {
+    pub name: String,
+    /// パッケージのディレクトリ（リポジトリルートからの相対パス、ルートの場合は空）
+    pub path: PathBuf,
+    /// マニフェストのパス（リポジトリルートからの相対パス）
+    pub manifest: PathBuf,
+    /// エコシステム（cargo|npm|go）
+    pub ecosystem: String,
+    /// 依存しているリポジトリ内のパッケージ名
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+}
+
+/// ワークスペースのパッケージを検出
+///
+/// # 引数
+/// * `manifests` - リポジトリ内のマニフェスト（パスはリポジトリルートからの相対パス）
+///
+/// # 戻り値
+/// * `Vec<PackageInfo>` - パッケージ（ディレクトリ順、ワークスペースでない場合は空）
+pub(crate) fn detect_packages(manifests: &[Manifest]) -> Vec<PackageInfo> {
+    let dir_of = |manifest: &Manifest| manifest.path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let mut members: Vec<&Manifest> = Vec::new();
+
+    for root in manifests.iter().filter(|m| !m.workspace_members.is_empty()) {
+        let root_dir = dir_of(root);
+        let mut builder = GlobSetBuilder::new();
+        for member in &root.workspace_members {
+            let member = member.trim_start_matches("./").trim_end_matches('/');
+            // `use .` のようにルート自身を指す場合
+            let pattern = if member.is_empty() || member == "." { root_dir.clone() } else { root_dir.join(member) };
+            let glob = GlobBuilder::new(&pattern.to_string_lossy()).literal_separator(true).build();
+            if let Ok(glob) = glob {
+                builder.add(glob);
+            }
+        }
+        let Ok(globs) = builder.build() else {
+            continue;
+        };
+        if root.name.is_some() {
+            members.push(root);
+        }
+        members.extend(
+            manifests
+                .iter()
+                .filter(|m| m.ecosystem == root.ecosystem && m.name.is_some() && globs.is_match(dir_of(m))),
+        );
+    }
+
+    // go.workのないGoのマルチモジュール
+    let go_modules: Vec<&Manifest> = manifests
+        .iter()
+        .filter(|m| m.path.file_name().is_some_and(|n| n == "go.mod") && m.name.is_some())
+        .collect();
+    if go_modules.len() > 1 && !members.iter().any(|m| m.ecosystem == "go") {
+        members.extend(go_modules);
+    }
+
+    members.sort_by(|a, b| a.path.cmp(&b.path));
+    members.dedup_by(|a, b| a.path == b.path);
+
+    let names: Vec<&str> = members.iter().filter_map(|m| m.name.as_deref()).collect();
+    members
+        .iter()
+        .map(|manifest| {
+            let name = manifest.name.clone().unwrap_or_default();
+            let mut dependencies: Vec<String> = manifest
+                .dependencies
+                .iter()
+                .filter(|dep| dep.internal && dep.name != name && names.contains(&dep.name.as_str()))
+                .map(|dep| dep.name.clone())
+                .collect();
+            dependencies.sort();
+            dependencies.dedup();
+            PackageInfo {
+                name,
+                path: dir_of(manifest),
+                manifest: manifest.path.clone(),
+                ecosystem: manifest.ecosystem.clone(),
+                dependencies,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DeclaredDependency;
+
+    fn manifest(path: &str, ecosystem: &str, name: Option<&str>, members: &[&str]) -> Manifest {
+        Manifest {
+            path: PathBuf::from(path),
+            ecosystem: ecosystem.to_string(),
+            name: name.map(str::to_string),
+            workspace_members: members.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_cargo_and_npm_workspaces() {
+        let mut api = manifest("crates/api/Cargo.toml", "cargo", Some("api"), &[]);
+        api.dependencies.push(DeclaredDependency {
+            name: "core".to_string(),
+            kind: "normal".to_string(),
+            internal: true,
+            ..Default::default()
+        });
+        let manifests = vec![
+            manifest("Cargo.toml", "cargo", None, &["crates/*"]),
+            api,
+            manifest("crates/core/Cargo.toml", "cargo", Some("core"), &[]),
+            manifest("tools/gen/Cargo.toml", "cargo", Some("gen"), &[]),
+            manifest("web/package.json", "npm", Some("web"), &["packages/*"]),
+            manifest("web/packages/ui/package.json", "npm", Some("@app/ui"), &[]),
+        ];
+
+        let packages = detect_packages(&manifests);
+        let summary: Vec<(&str, &Path)> = packages.iter().map(|p| (p.name.as_str(), p.path.as_path())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("api", Path::new("crates/api")),
+                ("core", Path::new("crates/core")),
+                ("web", Path::new("web")),
+                ("@app/ui", Path::new("web/packages/ui")),
+            ]
+        );
+        assert_eq!(packages[0].dependencies, vec!["core".to_string()]);
+
+        assert!(detect_packages(&[manifest("Cargo.toml", "cargo", Some("solo"), &[])]).is_empty());
+    }
+
+    #[test]
+    fn test_detect_go_modules_without_go_work() {
+        let manifests = vec![
+            manifest("go.mod", "go", Some("example.com/app"), &[]),
+            manifest("tools/go.mod", "go", Some("example.com/app/tools"), &[]),
+        ];
+        let packages = detect_packages(&manifests);
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].path, PathBuf::new());
+
+        let mut with_work = manifests.clone();
+        with_work.push(manifest("go.work", "go", None, &[".", "./tools"]));
+        assert_eq!(detect_packages(&with_work), packages);
+    }
+}
@@ -0,0 +1,445 @@
+/*!
+ * シンボルの相互参照
+ *
+ * tree-sitterで抽出したシンボルについて、定義・参照・呼び出し関係をまとめる
+ * - 定義位置、シグネチャ、ドキュメント
+ * - 参照箇所（参照元の関数・メソッド付き）
+ * - 呼び出し元・呼び出し先からなる小さなコールグラフ（Mermaid）
+ *
+ * 主な仕様:
+ * - `Type::method` / `Type.method` 形式で所属する型を指定して絞り込める
+ * - 参照は識別子の完全一致（単語境界）で検出
+ * - 呼び出し先は定義本体の `name(` 形式の呼び出しのうち、インデックス内の関数・メソッドに一致するもの
+ * - シンボル検索（`Index::search` の `kind = "symbol"`）は宣言された名前を完全一致・大文字小文字を無視した一致・前方一致・部分一致・あいまい一致の順に並べ、定義位置とシグネチャを返す
+ *   - あいまい一致は3文字以上のクエリで、略記（`bldwiki` → `build_wiki` のように先頭文字が同じで文字が順に現れる）と小さな打ち間違い（編集距離 `max_typos` 以内）を許す
+ *
+ * 制限事項:
+ * - 名前解決は行わないため、同名の別シンボルへの参照も含まれる
+ * - 参照は最大 `MAX_REFERENCES` 件まで
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use regex::Regex;
+
+use crate::{text_match, FileInfo, Index, SearchHit, Symbol};
+
+/// 返す参照の最大数
+const MAX_REFERENCES: usize = 100;
+/// シグネチャとして読む最大行数
+const MAX_SIGNATURE_LINES: usize = 5;
+
+/// シンボルの解説
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolExplanation {
+    pub name: String,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 定義のあるファイル
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 定義の先頭（本体を除く）
+    pub signature: String,
+    pub documentation: String,
+    /// 参照箇所（定義自身を除く）
+    pub references: Vec<SymbolReference>,
+    /// 呼び出し元の関数・メソッド
+    pub callers: Vec<String>,
+    /// 呼び出し先の関数・メソッド
+    pub callees: Vec<String>,
+    /// 呼び出し元 → シンボル → 呼び出し先のMermaid図
+    pub call_graph_mermaid: String,
+}
+
+/// 参照箇所
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolReference {
+    pub path: PathBuf,
+    /// 行番号（1始まり）
+    pub line: usize,
+    /// 行の内容（前後の空白を除く）
+    pub text: String,
+    /// 参照を含む関数・メソッド
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller: Option<String>,
+}
+
+/// シンボルを解説
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `query` - シンボル名（`Type::method` / `Type.method` も可）
+///
+/// # 戻り値
+/// * `Option<SymbolExplanation>` - 解説（見つからない場合はNone）
+pub(crate) fn explain_symbol(index: &Index, query: &str) -> Option<SymbolExplanation> {
+    let (parent, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, query),
+    };
+
+    let (file, symbol) = index.files.iter().find_map(|file| {
+        file.symbols
+            .iter()
+            .find(|s| s.name == name && s.kind != "impl" && parent.is_none_or(|p| s.parent.as_deref() == Some(p)))
+            .map(|s| (file, s))
+    })?;
+
+    let content = index.file_content(file).unwrap_or_default();
+    let lines: Vec<&str> = content.lines().collect();
+    let word = Regex::new(&format!(r"\b{}\b", regex::escape(name))).ok()?;
+
+    // 参照箇所
+    let mut references = Vec::new();
+    let mut callers = BTreeSet::new();
+    'files: for other in &index.files {
+        let Some(other_content) = index.file_content(other) else {
+            continue;
+        };
+        for (i, line) in other_content.lines().enumerate() {
+            let line_no = i + 1;
+            let in_definition = other.path == file.path && (symbol.start_line..=symbol.end_line).contains(&line_no);
+            if in_definition || !word.is_match(line) {
+                continue;
+            }
+            if references.len() >= MAX_REFERENCES {
+                break 'files;
+            }
+            let caller = enclosing_function(other, line_no).map(qualified_name);
+            if let Some(c) = &caller {
+                callers.insert(c.clone());
+            }
+            references.push(SymbolReference {
+                path: other.path.clone(),
+                line: line_no,
+                text: line.trim().to_string(),
+                caller,
+            });
+        }
+    }
+
+    // 呼び出し先（定義本体の呼び出しのうち、インデックス内の関数・メソッドに一致するもの）
+    let functions: BTreeSet<&str> = index
+        .files
+        .iter()
+        .flat_map(|f| f.symbols.iter())
+        .filter(|s| is_function(s))
+        .map(|s| s.name.as_str())
+        .collect();
+    let call = Regex::new(r"([A-Za-z_][A-Za-z0-9_]*)\s*\(").expect("固定の正規表現");
+    let body_start = symbol.start_line.min(lines.len());
+    let body_end = symbol.end_line.min(lines.len());
+    let callees: BTreeSet<String> = lines[body_start..body_end]
+        .iter()
+        .flat_map(|line| call.captures_iter(line).map(|c| c[1].to_string()).collect::<Vec<_>>())
+        .filter(|callee| callee != name && functions.contains(callee.as_str()))
+        .collect();
+
+    let signature = signature(&lines, symbol.start_line);
+    let display_name = qualified_name(symbol);
+    let callers: Vec<String> = callers.into_iter().filter(|c| c != &display_name).collect();
+    let callees: Vec<String> = callees.into_iter().collect();
+    let call_graph_mermaid = call_graph_mermaid(&display_name, &callers, &callees);
+
+    Some(SymbolExplanation {
+        name: symbol.name.clone(),
+        kind: symbol.kind.clone(),
+        parent: symbol.parent.clone(),
+        path: file.path.clone(),
+        start_line: symbol.start_line,
+        end_line: symbol.end_line,
+        signature,
+        documentation: symbol.documentation.clone(),
+        references,
+        callers,
+        callees,
+        call_graph_mermaid,
+    })
+}
+
+/// 宣言されたシンボルの名前で検索
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `query` - シンボル名またはその一部（`Type::method` / `Type.method` も可）
+/// * `k` - 返す結果の最大数
+/// * `allowed` - 対象とするファイルのパス（Noneの場合はすべて）
+///
+/// # 戻り値
+/// * `Vec<SearchHit>` - 定義位置（行範囲）と、種類・名前・シグネチャを抜粋にしたヒット
+pub(crate) fn search_symbols(index: &Index, query: &str, k: usize, allowed: Option<&HashSet<String>>) -> Vec<SearchHit> {
+    let (parent, name) = match query.rsplit_once("::").or_else(|| query.rsplit_once('.')) {
+        Some((parent, name)) => (Some(parent.to_lowercase()), name),
+        None => (None, query),
+    };
+    let name_lower = name.to_lowercase();
+    if name_lower.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits = Vec::new();
+    for file in &index.files {
+        let path = file.path.to_string_lossy();
+        if allowed.is_some_and(|allowed| !allowed.contains(path.as_ref())) {
+            continue;
+        }
+        let mut lines = None;
+        for symbol in &file.symbols {
+            if symbol.kind == "impl"
+                || parent.as_ref().is_some_and(|p| symbol.parent.as_ref().map(|s| s.to_lowercase()).as_ref() != Some(p))
+            {
+                continue;
+            }
+            let Some(score) = name_score(&symbol.name, name, &name_lower) else {
+                continue;
+            };
+
+            let content = lines.get_or_insert_with(|| index.file_content(file).unwrap_or_default());
+            // 宣言行で最初に現れる名前を定義位置とする
+            let matches = text_match::find_matches(content, &[symbol.name.as_str()], Some((symbol.start_line, symbol.start_line)));
+            let lines: Vec<&str> = content.lines().collect();
+            let signature = signature(&lines, symbol.start_line);
+            hits.push(SearchHit {
+                path: path.to_string(),
+                score,
+                excerpt: format!("{} {}: {}", symbol.kind, qualified_name(symbol), signature),
+                start_line: Some(symbol.start_line),
+                end_line: Some(symbol.end_line),
+                module: None,
+                matches: matches.into_iter().take(1).collect(),
+                signature: Some(signature),
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.start_line.cmp(&b.start_line))
+    });
+    hits.truncate(k);
+    hits
+}
+
+/// シンボル名とクエリの一致の度合い
+///
+/// # 引数
+/// * `name` - 宣言されたシンボル名
+/// * `query` - クエリ
+/// * `query_lower` - 小文字にしたクエリ
+///
+/// # 戻り値
+/// * `Option<f64>` - スコア（完全一致3.0、大文字小文字を無視した一致2.0、前方一致1.0〜2.0、部分一致0.5〜1.0、あいまい一致0〜0.5）、一致しない場合はNone
+fn name_score(name: &str, query: &str, query_lower: &str) -> Option<f64> {
+    let name_lower = name.to_lowercase();
+    let ratio = query_lower.chars().count() as f64 / name_lower.chars().count().max(1) as f64;
+    if name == query {
+        Some(3.0)
+    } else if name_lower == query_lower {
+        Some(2.0)
+    } else if name_lower.starts_with(query_lower) {
+        Some(1.0 + ratio)
+    } else if name_lower.contains(query_lower) {
+        Some(0.5 + 0.5 * ratio)
+    } else if query_lower.chars().count() < 3 {
+        None
+    } else if let Some(distance) = edit_distance(&name_lower, query_lower, max_typos(query_lower)) {
+        Some(0.5 * (1.0 - distance as f64 / name_lower.chars().count() as f64))
+    } else if is_abbreviation(&name_lower, query_lower) {
+        Some(0.5 * ratio.min(1.0))
+    } else {
+        None
+    }
+}
+
+/// あいまい一致で許す打ち間違いの数（5文字以下は1文字、それより長ければ2文字）
+fn max_typos(query: &str) -> usize {
+    if query.chars().count() <= 5 {
+        1
+    } else {
+        2
+    }
+}
+
+/// 編集距離（挿入・削除・置換・隣接する文字の入れ替え）が上限以内ならその距離を返す
+fn edit_distance(a: &str, b: &str, limit: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    // rows[i][j]: aの先頭i文字とbの先頭j文字の距離
+    let mut rows = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    rows[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
+        }
+    }
+    let distance = rows[a.len()][b.len()];
+    (distance <= limit).then_some(distance)
+}
+
+/// クエリが名前の略記か（先頭の文字が同じで、クエリの文字が名前に順に現れる）
+fn is_abbreviation(name: &str, query: &str) -> bool {
+    if name.chars().next() != query.chars().next() {
+        return false;
+    }
+    let mut chars = name.chars();
+    query.chars().all(|q| chars.any(|c| c == q))
+}
+
+/// 関数・メソッドかを判定
+fn is_function(symbol: &Symbol) -> bool {
+    symbol.kind == "function" || symbol.kind == "method"
+}
+
+/// 型名付きのシンボル名
+fn qualified_name(symbol: &Symbol) -> String {
+    match &symbol.parent {
+        Some(parent) => format!("{}::{}", parent, symbol.name),
+        None => symbol.name.clone(),
+    }
+}
+
+/// 指定行を含む最も内側の関数・メソッド
+fn enclosing_function(file: &FileInfo, line: usize) -> Option<&Symbol> {
+    file.symbols
+        .iter()
+        .filter(|s| is_function(s) && (s.start_line..=s.end_line).contains(&line))
+        .min_by_key(|s| s.end_line - s.start_line)
+}
+
+/// 定義の先頭行から本体の開始（`{` または行末の `:`）までをシグネチャとする
+fn signature(lines: &[&str], start_line: usize) -> String {
+    let mut parts = Vec::new();
+    for line in lines.iter().skip(start_line.saturating_sub(1)).take(MAX_SIGNATURE_LINES) {
+        let trimmed = line.trim();
+        if let Some(pos) = trimmed.find('{') {
+            parts.push(trimmed[..pos].trim_end());
+            break;
+        }
+        parts.push(trimmed);
+        if trimmed.ends_with(':') || trimmed.ends_with(';') {
+            break;
+        }
+    }
+    parts.join(" ").trim().to_string()
+}
+
+/// 呼び出し元 → シンボル → 呼び出し先のMermaid図
+fn call_graph_mermaid(name: &str, callers: &[String], callees: &[String]) -> String {
+    let label = |s: &str| s.replace('"', "#quot;");
+    let mut mermaid = String::from("graph LR\n");
+    mermaid.push_str(&format!("    S[\"{}\"]\n", label(name)));
+    for (i, caller) in callers.iter().enumerate() {
+        mermaid.push_str(&format!("    C{}[\"{}\"] --> S\n", i, label(caller)));
+    }
+    for (i, callee) in callees.iter().enumerate() {
+        mermaid.push_str(&format!("    S --> E{}[\"{}\"]\n", i, label(callee)));
+    }
+    mermaid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::parse_source;
+
+    #[test]
+    fn test_explain_symbol_collects_references_and_calls() {
+        let file = |path: &str, content: &str| FileInfo {
+            path: PathBuf::from(path),
+            language: "rs".to_string(),
+            symbols: parse_source(content, "rs").unwrap().symbols,
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            files: vec![
+                file(
+                    "src/config.rs",
+                    "/// 設定を読み込む\npub fn load(path: &str) -> Config {\n    let text = read(path);\n    parse(&text)\n}\n\nfn read(path: &str) -> String { String::new() }\nfn parse(text: &str) -> Config { Config }\n",
+                ),
+                file("src/main.rs", "fn main() {\n    let config = load(\"a.toml\");\n}\n"),
+            ],
+            ..Default::default()
+        };
+
+        let explanation = explain_symbol(&index, "load").unwrap();
+        assert_eq!(explanation.path, PathBuf::from("src/config.rs"));
+        assert_eq!((explanation.start_line, explanation.end_line), (2, 5));
+        assert_eq!(explanation.signature, "pub fn load(path: &str) -> Config");
+        assert_eq!(explanation.documentation, "設定を読み込む");
+        assert_eq!(explanation.references.len(), 1);
+        assert_eq!(explanation.references[0].line, 2);
+        assert_eq!(explanation.callers, vec!["main"]);
+        assert_eq!(explanation.callees, vec!["parse", "read"]);
+        assert!(explanation.call_graph_mermaid.contains("C0[\"main\"] --> S"));
+
+        assert!(explain_symbol(&index, "missing").is_none());
+    }
+
+    #[test]
+    fn test_search_symbols_returns_definitions() {
+        let content = "pub struct Builder;\n\nimpl Builder {\n    pub fn build_wiki(&self) {\n        rebuild_wiki_cache();\n    }\n}\n\nfn rebuild_wiki_cache() {}\n";
+        let index = Index {
+            files: vec![FileInfo {
+                path: PathBuf::from("src/builder.rs"),
+                language: "rs".to_string(),
+                symbols: parse_source(content, "rs").unwrap().symbols,
+                content: Some(content.to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let hits = search_symbols(&index, "build_wiki", 10, None);
+        assert_eq!(hits.len(), 2);
+        assert_eq!((hits[0].start_line, hits[0].end_line), (Some(4), Some(6)));
+        assert_eq!(hits[0].excerpt, "method Builder::build_wiki: pub fn build_wiki(&self)");
+        assert_eq!((hits[0].matches[0].line, hits[0].matches[0].column), (4, 12));
+        assert_eq!(hits[1].start_line, Some(9));
+        assert!(hits[0].score > hits[1].score);
+
+        assert_eq!(search_symbols(&index, "builder.build_wiki", 10, None).len(), 1);
+        assert_eq!(search_symbols(&index, "Builder", 10, None)[0].excerpt, "struct Builder: pub struct Builder;");
+        assert!(search_symbols(&index, "missing", 10, None).is_empty());
+
+        // 前方一致は部分一致より上位、略記・打ち間違いはそれより下位
+        let hits = search_symbols(&index, "rebuild", 10, None);
+        assert_eq!(hits[0].signature.as_deref(), Some("fn rebuild_wiki_cache()"));
+        assert!(hits[0].score > 1.0);
+        assert_eq!(search_symbols(&index, "bld_wiki", 10, None)[0].start_line, Some(4));
+        assert_eq!(search_symbols(&index, "biuld_wiki", 10, None)[0].excerpt, "method Builder::build_wiki: pub fn build_wiki(&self)");
+        assert!(search_symbols(&index, "bw", 10, None).is_empty());
+    }
+
+    #[test]
+    fn test_name_score_ranking() {
+        let score = |name: &str, query: &str| name_score(name, query, &query.to_lowercase());
+        assert_eq!(score("build_wiki", "build_wiki"), Some(3.0));
+        assert_eq!(score("Build_Wiki", "build_wiki"), Some(2.0));
+        assert!(score("build_wiki", "build").unwrap() > score("rebuild_wiki", "wiki").unwrap());
+        assert!(score("rebuild_wiki", "wiki").unwrap() > score("build_wiki", "buidl_wiki").unwrap());
+        assert!(score("build_wiki", "buidl_wiki").is_some());
+        assert!(score("build_wiki", "bldwk").is_some());
+        assert!(score("build_wiki", "xyzzy").is_none());
+        assert_eq!(edit_distance("kitten", "sitting", 3), Some(3));
+        assert_eq!(edit_distance("kitten", "sitting", 2), None);
+    }
+}
@@ -0,0 +1,207 @@
+/*!
+ * 図のキャプションと凡例
+ *
+ * Wiki・スライドに埋め込む図に、何を表す図か・ノード数・生成範囲のキャプションと、
+ * ノードの形・矢印の意味の凡例を付ける
+ *
+ * 主な仕様:
+ * - ノード数・エッジ数は生成した図の内容から数える（Mermaid・Graphviz・GraphML・Cytoscape.js JSON）
+ * - 凡例は図のタイプごとの固定の説明（デプロイメント図はマニフェストの有無で切り替える）
+ * - モジュールグラフの凡例には、図に現れるコンポーネントの種類のアイコンを加える
+ * - `Diagram::to_markdown` でコードブロック・キャプション・凡例をまとめて出力する
+ *
+ * 制限事項:
+ * - Mermaidのノードは `ID[...]`・`ID[(...)]` 形式で定義したもののみを数える（ラベルなしのノードは数えない）
+ */
+
+use std::collections::BTreeSet;
+
+use regex::Regex;
+
+use analyzer_core::{ComponentKind, COMPONENT_STYLES};
+
+use crate::{component_label, Diagram};
+
+impl Diagram {
+    /// キャプション（何を表す図か・ノード数・生成範囲）
+    ///
+    /// # 戻り値
+    /// * `String` - `*図: ...*` 形式の1行
+    pub fn caption(&self) -> String {
+        let title = match self.diagram_type.as_str() {
+            "overview" => "全体構成 — リポジトリの構成要素と規模",
+            "module-graph" => "モジュールグラフ — ファイル間のimport関係",
+            "call-graph" => "コールグラフ — ファイルで定義された関数",
+            "sequence" => "シーケンス図 — モジュール間の呼び出しの流れ（推定）",
+            "deployment" => "デプロイメント図 — パッケージと実行時の外部依存",
+            "class-diagram" => "クラス図 — 型の実装・継承関係",
+            "dependency-cycles" => "循環依存 — importをたどって互いに到達できるファイル",
+            "layers" => "層構成 — モジュールの層と層の間の依存（推定）",
+            "timeline" => "プロジェクトの歩み — リリース・大規模な変更・ドキュメントの再生成",
+            other => other,
+        };
+        let (nodes, edges) = self.count_elements();
+        let mut details = Vec::new();
+        if nodes > 0 {
+            details.push(format!("{}ノード・{}エッジ", nodes, edges));
+        }
+        if !self.scope.is_empty() {
+            details.push(format!("範囲: {}", self.scope));
+        }
+        if details.is_empty() {
+            format!("*図: {}*", title)
+        } else {
+            format!("*図: {}（{}）*", title, details.join("、"))
+        }
+    }
+
+    /// 凡例（ノードの形・矢印の意味）
+    ///
+    /// # 戻り値
+    /// * `String` - `凡例: ...` 形式の1行
+    pub fn legend(&self) -> String {
+        let items: &[&str] = match self.diagram_type.as_str() {
+            "overview" => &["四角: 構成要素（数はインデックスの統計）", "矢印: 内訳"],
+            "module-graph" => &["四角: モジュール", "矢印 A → B: AがBをimport"],
+            "call-graph" => &["四角: 関数（呼び出し関係は描かない）"],
+            "sequence" => &["参加者: モジュール", "実線の矢印: 呼び出し（モジュールの並びからの推定）"],
+            "deployment" if self.content.contains("subgraph \"リポジトリ\"") => &[
+                "四角: リポジトリ内のパッケージ（名前とエコシステム）",
+                "円柱: データストアのクライアント",
+                "矢印: 実行時の依存",
+            ],
+            "deployment" => &["一般的な3層構成の例（マニフェストがないため実際の構成ではない）"],
+            "dependency-cycles" => &[
+                "枠: 1つの循環依存",
+                "矢印 A → B: AがBをimport",
+                "赤い太線: 最短の循環の経路",
+            ],
+            "layers" => &[
+                "枠: 層（名前・import・内部依存から推定）",
+                "矢印 A → B: A層のモジュールがB層をimport（数はimportの数）",
+                "赤い太線: 層の逆転（下位の層から上位の層へのimport）",
+            ],
+            "timeline" => &["区切り: 年", "各行: コミットの日付と出来事の種類・内容"],
+            "class-diagram" => &[
+                "`<<trait>>`・`<<interface>>`: トレイト・インターフェース",
+                "点線の矢印: 実装",
+                "実線の矢印: 継承",
+                "`+` public・`-` private・`#` protected・`~` internal",
+            ],
+            _ => &[],
+        };
+        let mut items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        if self.diagram_type == "module-graph" {
+            // 図に現れるコンポーネントの種類のアイコン
+            let kinds: Vec<String> = COMPONENT_STYLES
+                .iter()
+                .filter(|(kind, icon, _)| self.content.contains(&format!("\"{} ", icon)) && *kind != ComponentKind::Other)
+                .map(|(kind, icon, _)| format!("{} {}", icon, component_label(*kind)))
+                .collect();
+            if !kinds.is_empty() {
+                items.push(kinds.join("・"));
+            }
+        }
+        if items.is_empty() {
+            String::new()
+        } else {
+            format!("凡例: {}", items.join(" ／ "))
+        }
+    }
+
+    /// コードブロック・キャプション・凡例をMarkdownで出力
+    ///
+    /// # 戻り値
+    /// * `String` - 末尾に空行を含むMarkdown
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("```{}\n{}\n```\n\n{}\n\n", self.format, self.content.trim_end(), self.caption());
+        let legend = self.legend();
+        if !legend.is_empty() {
+            markdown.push_str(&legend);
+            markdown.push_str("\n\n");
+        }
+        markdown
+    }
+
+    /// 図の内容からノード数・エッジ数を数える
+    fn count_elements(&self) -> (usize, usize) {
+        match self.format.as_str() {
+            "mermaid" => count_mermaid(&self.content),
+            "graphviz" => {
+                let nodes = self.content.lines().filter(|l| l.contains("[label=")).count();
+                let edges = self.content.lines().filter(|l| l.contains(" -> ")).count();
+                (nodes, edges)
+            }
+            "graphml" => (self.content.matches("<node ").count(), self.content.matches("<edge ").count()),
+            "cytoscape" => {
+                let json: serde_json::Value = serde_json::from_str(&self.content).unwrap_or_default();
+                let count = |key: &str| json["elements"][key].as_array().map_or(0, |a| a.len());
+                (count("nodes"), count("edges"))
+            }
+            _ => (0, 0),
+        }
+    }
+}
+
+/// Mermaidの図のノード数・エッジ数を数える
+fn count_mermaid(content: &str) -> (usize, usize) {
+    const ARROWS: &[&str] = &["-->", "->>", "..|>", "--|>"];
+    let inline_node = Regex::new(r"([A-Za-z_]\w*)\[").unwrap();
+    let mut nodes = BTreeSet::new();
+    let mut edges = 0;
+    for line in content.lines().skip(1).map(str::trim) {
+        if let Some(name) = line.strip_prefix("participant ") {
+            nodes.insert(name.to_string());
+        } else if ARROWS.iter().any(|arrow| line.contains(arrow)) {
+            edges += 1;
+            // クラス図はエッジの両端もクラス
+            if let [from, arrow, to] = line.split_whitespace().collect::<Vec<_>>()[..] {
+                if arrow.ends_with("|>") {
+                    nodes.insert(from.to_string());
+                    nodes.insert(to.to_string());
+                }
+            }
+            // エッジの中で定義したノード（`A[ラベル] --> B[ラベル]`）
+            for captures in inline_node.captures_iter(line) {
+                nodes.insert(captures[1].to_string());
+            }
+        } else if let Some(name) = line.strip_prefix("class ") {
+            nodes.insert(name.trim_end_matches(" {").to_string());
+        } else if let Some(bracket) = line.find('[') {
+            let id = &line[..bracket];
+            if !id.is_empty() && id.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                nodes.insert(id.to_string());
+            }
+        }
+    }
+    (nodes.len(), edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown_adds_caption_and_legend() {
+        let diagram = Diagram {
+            diagram_type: "module-graph".to_string(),
+            format: "mermaid".to_string(),
+            content: "graph TD\n    M0[\"api\"]\n    M1[\"core\"]\n    M0 --> M1\n".to_string(),
+            scope: "リポジトリ全体の2モジュール".to_string(),
+        };
+        assert_eq!(
+            diagram.to_markdown(),
+            "```mermaid\ngraph TD\n    M0[\"api\"]\n    M1[\"core\"]\n    M0 --> M1\n```\n\n\
+             *図: モジュールグラフ — ファイル間のimport関係（2ノード・1エッジ、範囲: リポジトリ全体の2モジュール）*\n\n\
+             凡例: 四角: モジュール ／ 矢印 A → B: AがBをimport\n\n"
+        );
+
+        let class = Diagram {
+            diagram_type: "class-diagram".to_string(),
+            format: "mermaid".to_string(),
+            content: "classDiagram\n    class Store {\n        <<trait>>\n    }\n    Memory ..|> Store\n".to_string(),
+            scope: String::new(),
+        };
+        assert_eq!(class.caption(), "*図: クラス図 — 型の実装・継承関係（2ノード・1エッジ）*");
+    }
+}
@@ -0,0 +1,171 @@
+/*!
+ * グラフエクスポート実装
+ *
+ * モジュールグラフ・コールグラフを外部ツール向けの形式で出力する
+ * - GraphML（yEd, Gephiなど）
+ * - Cytoscape.js JSON（ブラウザでのインタラクティブ表示）
+ *
+ * 主な仕様:
+ * - ノードには言語、行数（LOC）、パッケージを属性として付与
+ * - Mermaid/DOTと同じ依存関係マッチングでエッジを作成
+ *
+ * 制限事項:
+ * - パッケージはパスから推定（`src`ディレクトリの親、なければ親ディレクトリ）
+ */
+
+use serde_json::json;
+use std::path::Path;
+
+/// エクスポート用のグラフノード
+#[derive(Debug, Clone)]
+pub(crate) struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub language: String,
+    pub loc: usize,
+    pub package: String,
+    pub file: String,
+}
+
+/// エクスポート用のグラフエッジ
+#[derive(Debug, Clone)]
+pub(crate) struct GraphEdge {
+    pub source: String,
+    pub target: String,
+}
+
+/// エクスポート用のグラフモデル
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GraphModel {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl GraphModel {
+    /// GraphML形式に変換
+    ///
+    /// # 戻り値
+    /// * `String` - GraphML文書
+    pub fn to_graphml(&self, graph_id: &str) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        xml.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"language\" for=\"node\" attr.name=\"language\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"loc\" for=\"node\" attr.name=\"loc\" attr.type=\"int\"/>\n");
+        xml.push_str("  <key id=\"package\" for=\"node\" attr.name=\"package\" attr.type=\"string\"/>\n");
+        xml.push_str("  <key id=\"file\" for=\"node\" attr.name=\"file\" attr.type=\"string\"/>\n");
+        xml.push_str(&format!(
+            "  <graph id=\"{}\" edgedefault=\"directed\">\n",
+            escape_xml(graph_id)
+        ));
+
+        for node in &self.nodes {
+            xml.push_str(&format!("    <node id=\"{}\">\n", escape_xml(&node.id)));
+            xml.push_str(&format!("      <data key=\"label\">{}</data>\n", escape_xml(&node.label)));
+            xml.push_str(&format!("      <data key=\"language\">{}</data>\n", escape_xml(&node.language)));
+            xml.push_str(&format!("      <data key=\"loc\">{}</data>\n", node.loc));
+            xml.push_str(&format!("      <data key=\"package\">{}</data>\n", escape_xml(&node.package)));
+            xml.push_str(&format!("      <data key=\"file\">{}</data>\n", escape_xml(&node.file)));
+            xml.push_str("    </node>\n");
+        }
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <edge id=\"E{}\" source=\"{}\" target=\"{}\"/>\n",
+                i,
+                escape_xml(&edge.source),
+                escape_xml(&edge.target)
+            ));
+        }
+
+        xml.push_str("  </graph>\n");
+        xml.push_str("</graphml>\n");
+        xml
+    }
+
+    /// Cytoscape.js JSON形式に変換
+    ///
+    /// # 戻り値
+    /// * `String` - `elements` を持つJSON文字列
+    pub fn to_cytoscape_json(&self) -> String {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|n| {
+                json!({
+                    "data": {
+                        "id": n.id,
+                        "label": n.label,
+                        "language": n.language,
+                        "loc": n.loc,
+                        "package": n.package,
+                        "file": n.file,
+                    }
+                })
+            })
+            .collect();
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                json!({
+                    "data": {
+                        "id": format!("E{}", i),
+                        "source": e.source,
+                        "target": e.target,
+                    }
+                })
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&json!({
+            "elements": {
+                "nodes": nodes,
+                "edges": edges,
+            }
+        }))
+        .unwrap_or_default()
+    }
+}
+
+/// パスからパッケージ名を推定
+///
+/// # 引数
+/// * `repo_path` - リポジトリルート
+/// * `path` - ファイルパス
+///
+/// # 戻り値
+/// * `String` - パッケージ名（リポジトリ直下の場合は "."）
+pub(crate) fn infer_package(repo_path: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(repo_path).unwrap_or(path);
+    let parent = match relative.parent() {
+        Some(p) => p,
+        None => return ".".to_string(),
+    };
+
+    let components: Vec<String> = parent
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    let package: Vec<String> = match components.iter().position(|c| c == "src") {
+        Some(pos) => components[..pos].to_vec(),
+        None => components,
+    };
+
+    if package.is_empty() {
+        ".".to_string()
+    } else {
+        package.join("/")
+    }
+}
+
+/// XML特殊文字をエスケープ
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -1,32 +1,58 @@
-/**
+/*!
  * ダイアグラマー実装
  * 
  * コードベースから図表を生成する
  * - Mermaid DSLの生成（flowchart, classDiagram, sequenceDiagram）
  * - Graphviz DOT形式の生成（オプション）
- * - モジュールグラフ、コールグラフ、シーケンス図、デプロイメント図
+ * - GraphML / Cytoscape.js JSON形式のエクスポート（外部ツールでの探索用、`write_graph_exports` でファイルに書き出す。ページには埋め込まない）
+ * - モジュールグラフ（ファイル間の内部依存）、コールグラフ、シーケンス図、デプロイメント図（マニフェストのパッケージと外部依存）、クラス図（実装・継承関係）
+ * - 循環依存の図（循環ごとのsubgraphと、最短の循環の経路を強調した矢印）
+ * - 層構成図（推定したモジュールの層ごとのsubgraphと層の間のimport数、層の逆転を強調した矢印）
+ * 
+ * - 埋め込み用のキャプション（内容・ノード数・範囲）と凡例（captionモジュール）
+ * - 生成できなかった図は警告として記録し、ページには載せない（`try_generate_diagram`）
  * 
  * 主な仕様:
  * - Mermaidをデフォルトレンダラとして使用
  * - Graphvizは外部コマンド呼び出し（オプション）
  * - 複数の図タイプに対応
+ * - モジュールのノードはモジュールID（`web:src/index.ts` など）で表示する
+ * - モジュールグラフのノードにはコンポーネントの種類（API・DB・UI・ワーカー・インフラなど）のアイコンと塗りの色を付ける（`analysis.diagrams.icons`、スライドと共通の `COMPONENT_STYLES`）
  * 
  * 制限事項:
  * - コールグラフは簡易的な解析に基づく（完全な静的解析ではない）
  * - シーケンス図は関数名から推測（実際の呼び出しフローではない）
+ * - クラス図は型名で照合するため、別パッケージの同名の型は1つのクラスになる
+ * - クラス図のメソッドは名前のみ（引数・戻り値の型は描かない）
  */
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
 use tracing::info;
 
 use config::Config;
-use analyzer_core::Index;
+use analyzer_core::{ArchitectureModel, ComponentKind, Diagnostics, Index, ModuleInfo, COMPONENT_STYLES, LAYERS};
+
+mod caption;
+mod export;
+
+use export::{infer_package, GraphEdge, GraphModel, GraphNode};
+
+/// グラフのエクスポートの既定の出力先
+pub const DEFAULT_EXPORT_DIR: &str = "./out/graphs";
+/// エクスポートするグラフ（図タイプ）
+const EXPORT_GRAPHS: &[&str] = &["module-graph", "call-graph"];
+/// エクスポート形式と拡張子
+const EXPORT_FORMATS: &[(&str, &str)] = &[("graphml", "graphml"), ("cytoscape", "cytoscape.json")];
 
 /// ダイアグラマー
 pub struct Diagrammer {
     config: Config,
+    /// 生成できなかった図の記録先
+    diagnostics: Diagnostics,
 }
 
 impl Diagrammer {
@@ -38,14 +64,51 @@ impl Diagrammer {
     /// # 戻り値
     /// * `Self` - ダイアグラマーインスタンス
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self { config, diagnostics: Diagnostics::new() }
+    }
+
+    /// 警告の記録先を指定（解析・要約・Wiki・スライドと共有して最後にまとめて表示する場合）
+    /// 
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    /// 
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたダイアグラマー
+    pub fn with_diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// 警告の記録先
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// 図を生成し、生成できない場合は警告を記録してNoneを返す
+    /// 
+    /// Wiki・スライドのように、図がなくてもページ自体は作れる場合に使用する。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `diagram_type` - 図のタイプ
+    /// 
+    /// # 戻り値
+    /// * `Option<Diagram>` - 生成された図（生成できない場合はNone）
+    pub fn try_generate_diagram(&self, index: &Index, diagram_type: &str) -> Option<Diagram> {
+        match self.generate_diagram(index, diagram_type) {
+            Ok(diagram) => Some(diagram),
+            Err(e) => {
+                self.diagnostics.warn("diagrammer", format!("{}を生成できませんでした: {}", diagram_type, e));
+                None
+            }
+        }
     }
 
     /// 図を生成
     /// 
     /// # 引数
     /// * `index` - インデックス
-    /// * `diagram_type` - 図のタイプ（module-graph|call-graph|sequence|deployment）
+    /// * `diagram_type` - 図のタイプ（module-graph|call-graph|sequence|deployment|class-diagram|dependency-cycles|layers）
     /// 
     /// # 戻り値
     /// * `Result<Diagram>` - 生成された図、またはエラー
@@ -57,6 +120,9 @@ impl Diagrammer {
             "call-graph" => self.generate_call_graph(index)?,
             "sequence" => self.generate_sequence_diagram(index)?,
             "deployment" => self.generate_deployment_diagram(index)?,
+            "class-diagram" => self.generate_class_diagram(index)?,
+            "dependency-cycles" => self.generate_cycle_diagram(index)?,
+            "layers" => return self.generate_layer_diagram(&index.architecture()),
             _ => return Err(anyhow::anyhow!("不明な図タイプ: {}", diagram_type)),
         };
 
@@ -64,9 +130,90 @@ impl Diagrammer {
             diagram_type: diagram_type.to_string(),
             format: format.to_string(),
             content,
+            scope: self.describe_scope(index, diagram_type),
         })
     }
 
+    /// 層構成図を生成
+    /// 
+    /// `generate_diagram(index, "layers")` はインデックスから静的に推定した層を描く。
+    /// ローカルLLMで分類し直したモデル（`Summarizer::infer_architecture`）を描く場合はこちらを使う。
+    /// 
+    /// # 引数
+    /// * `model` - モジュールの層と層の間の依存
+    /// 
+    /// # 戻り値
+    /// * `Result<Diagram>` - 生成された図、またはエラー（Mermaid以外のレンダラ、モジュールがない場合を含む）
+    pub fn generate_layer_diagram(&self, model: &ArchitectureModel) -> Result<Diagram> {
+        if self.config.analysis.diagrams.renderer != "mermaid" {
+            return Err(anyhow::anyhow!("層構成図はMermaidのみサポートされています"));
+        }
+        if model.components.is_empty() {
+            return Err(anyhow::anyhow!("層に分類するモジュールがありません"));
+        }
+
+        let mut mermaid = String::from("graph TD\n");
+        let layers: Vec<&str> = LAYERS.iter().copied().chain(["other"]).collect();
+        for (i, layer) in layers.iter().enumerate() {
+            let components = model.layer(layer);
+            if components.is_empty() {
+                continue;
+            }
+            mermaid.push_str(&format!("    subgraph L{}[\"{}（{}）\"]\n", i, layer_label(layer), components.len()));
+            for (j, component) in components.iter().take(MAX_LAYER_MODULES).enumerate() {
+                mermaid.push_str(&format!("        L{}M{}[\"{}\"]\n", i, j, mermaid_label(&component.id)));
+            }
+            if components.len() > MAX_LAYER_MODULES {
+                mermaid.push_str(&format!("        L{}MORE[\"ほか{}件\"]\n", i, components.len() - MAX_LAYER_MODULES));
+            }
+            mermaid.push_str("    end\n");
+        }
+        let id = |layer: &str| layers.iter().position(|l| *l == layer).map(|i| format!("L{}", i));
+        let mut violations = Vec::new();
+        let mut edge = 0;
+        for dependency in &model.dependencies {
+            if let (Some(from), Some(to)) = (id(&dependency.from), id(&dependency.to)) {
+                mermaid.push_str(&format!("    {} -->|{}| {}\n", from, dependency.imports, to));
+                if dependency.violation {
+                    violations.push(edge.to_string());
+                }
+                edge += 1;
+            }
+        }
+        if !violations.is_empty() {
+            mermaid.push_str(&format!("    linkStyle {} stroke:#d93025,stroke-width:3px\n", violations.join(",")));
+        }
+
+        let classified = model.components.iter().filter(|c| c.layer != "other").count();
+        Ok(Diagram {
+            diagram_type: "layers".to_string(),
+            format: "mermaid".to_string(),
+            content: mermaid,
+            scope: format!("{}モジュールのうち層を推定できた{}件", model.components.len(), classified),
+        })
+    }
+
+    /// 図に含めた範囲の説明（キャプション用）
+    fn describe_scope(&self, index: &Index, diagram_type: &str) -> String {
+        match diagram_type {
+            "module-graph" => format!("リポジトリ全体の{}モジュール", index.modules.len()),
+            "call-graph" => format!("{}ファイルの関数", index.files.len()),
+            "sequence" => format!("先頭の{}モジュール", index.modules.len().min(MAX_SEQUENCE_PARTICIPANTS)),
+            "deployment" if index.manifests.is_empty() => "マニフェストなし".to_string(),
+            "deployment" => format!(
+                "マニフェスト{}件、実行時の外部依存は上位{}件まで",
+                index.manifests.len(),
+                MAX_DEPLOYMENT_EXTERNALS
+            ),
+            "class-diagram" => format!("型の関係は最大{}件", MAX_CLASS_EDGES),
+            "dependency-cycles" => {
+                let cycles = index.dependency_cycles().len();
+                format!("循環{}件のうち{}件", cycles, cycles.min(MAX_CYCLES))
+            }
+            _ => String::new(),
+        }
+    }
+
     /// モジュールグラフを生成
     /// 
     /// # 引数
@@ -86,28 +233,33 @@ impl Diagrammer {
     fn generate_module_graph_mermaid(&self, index: &Index) -> Result<(String, &str)> {
         let mut mermaid = String::from("graph TD\n");
         let mut node_map = HashMap::new();
-        let mut node_id = 0;
 
         // ノードを作成
-        for module in &index.modules {
+        let mut kinds: Vec<(ComponentKind, Vec<String>)> = Vec::new();
+        for (node_id, module) in index.modules.iter().enumerate() {
             let id = format!("M{}", node_id);
             node_map.insert(module.path.clone(), id.clone());
-            let label = module.name.clone();
+            let label = self.module_label(module);
             mermaid.push_str(&format!("    {}[\"{}\"]\n", id, label));
-            node_id += 1;
+            match kinds.iter_mut().find(|(kind, _)| *kind == module.component) {
+                Some((_, ids)) => ids.push(id),
+                None => kinds.push((module.component, vec![id])),
+            }
         }
 
         // エッジを作成（依存関係から）
-        for module in &index.modules {
-            if let Some(from_id) = node_map.get(&module.path) {
-                for dep in &module.dependencies {
-                    // 依存関係からモジュールを検索
-                    if let Some(to_module) = index.modules.iter().find(|m| m.name.contains(dep)) {
-                        if let Some(to_id) = node_map.get(&to_module.path) {
-                            mermaid.push_str(&format!("    {} --> {}\n", from_id, to_id));
-                        }
-                    }
-                }
+        for (from, to) in module_edges(index) {
+            if let (Some(from_id), Some(to_id)) = (node_map.get(from), node_map.get(to)) {
+                mermaid.push_str(&format!("    {} --> {}\n", from_id, to_id));
+            }
+        }
+
+        // コンポーネントの種類ごとの塗り
+        if self.config.analysis.diagrams.icons {
+            kinds.sort_by_key(|(kind, _)| COMPONENT_STYLES.iter().position(|(k, _, _)| k == kind));
+            for (kind, ids) in kinds {
+                mermaid.push_str(&format!("    classDef {} fill:{},stroke:#90a4ae\n", kind.as_str(), kind.color()));
+                mermaid.push_str(&format!("    class {} {}\n", ids.join(","), kind.as_str()));
             }
         }
 
@@ -121,29 +273,25 @@ impl Diagrammer {
         dot.push_str("    node [shape=box];\n\n");
 
         let mut node_map = HashMap::new();
-        let mut node_id = 0;
 
         // ノードを作成
-        for module in &index.modules {
+        for (node_id, module) in index.modules.iter().enumerate() {
             let id = format!("M{}", node_id);
             node_map.insert(module.path.clone(), id.clone());
-            let label = module.name.clone();
-            dot.push_str(&format!("    {} [label=\"{}\"];\n", id, label));
-            node_id += 1;
+            let label = self.module_label(module);
+            if self.config.analysis.diagrams.icons {
+                dot.push_str(&format!("    {} [label=\"{}\", style=filled, fillcolor=\"{}\"];\n", id, label, module.component.color()));
+            } else {
+                dot.push_str(&format!("    {} [label=\"{}\"];\n", id, label));
+            }
         }
 
-        dot.push_str("\n");
+        dot.push('\n');
 
         // エッジを作成
-        for module in &index.modules {
-            if let Some(from_id) = node_map.get(&module.path) {
-                for dep in &module.dependencies {
-                    if let Some(to_module) = index.modules.iter().find(|m| m.name.contains(dep)) {
-                        if let Some(to_id) = node_map.get(&to_module.path) {
-                            dot.push_str(&format!("    {} -> {};\n", from_id, to_id));
-                        }
-                    }
-                }
+        for (from, to) in module_edges(index) {
+            if let (Some(from_id), Some(to_id)) = (node_map.get(from), node_map.get(to)) {
+                dot.push_str(&format!("    {} -> {};\n", from_id, to_id));
             }
         }
 
@@ -152,6 +300,15 @@ impl Diagrammer {
         Ok((dot, "graphviz"))
     }
 
+    /// モジュールのノードのラベル（`diagrams.icons` の場合はコンポーネントの種類のアイコンを前に付ける）
+    fn module_label(&self, module: &ModuleInfo) -> String {
+        if self.config.analysis.diagrams.icons {
+            format!("{} {}", module.component.icon(), module.id)
+        } else {
+            module.id.clone()
+        }
+    }
+
     /// コールグラフを生成
     /// 
     /// # 引数
@@ -174,8 +331,8 @@ impl Diagrammer {
 
         // 関数を抽出
         for file in &index.files {
-            if let Some(content) = &file.content {
-                let funcs = self.extract_functions(content, &file.language);
+            if let Some(content) = index.file_content(file) {
+                let funcs = self.extract_functions(&content, &file.language);
                 functions.extend(funcs);
             }
         }
@@ -200,8 +357,8 @@ impl Diagrammer {
 
         let mut functions = Vec::new();
         for file in &index.files {
-            if let Some(content) = &file.content {
-                let funcs = self.extract_functions(content, &file.language);
+            if let Some(content) = index.file_content(file) {
+                let funcs = self.extract_functions(&content, &file.language);
                 functions.extend(funcs);
             }
         }
@@ -216,6 +373,181 @@ impl Diagrammer {
         Ok((dot, "graphviz"))
     }
 
+    /// グラフを外部ツール向けの形式でエクスポート
+    /// 
+    /// レンダラ設定に関係なく、モジュールグラフ・コールグラフを指定形式で出力する
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `diagram_type` - 図のタイプ（module-graph|call-graph）
+    /// * `format` - 出力形式（graphml|cytoscape）
+    /// 
+    /// # 戻り値
+    /// * `Result<Diagram>` - エクスポートされた図、またはエラー
+    pub fn export_graph(&self, index: &Index, diagram_type: &str, format: &str) -> Result<Diagram> {
+        info!("グラフエクスポート開始: type={}, format={}", diagram_type, format);
+
+        let (model, graph_id) = match diagram_type {
+            "module-graph" => (self.build_module_graph_model(index), "ModuleGraph"),
+            "call-graph" => (self.build_call_graph_model(index), "CallGraph"),
+            _ => return Err(anyhow::anyhow!("エクスポートできない図タイプです: {}", diagram_type)),
+        };
+
+        let content = match format {
+            "graphml" => model.to_graphml(graph_id),
+            "cytoscape" => model.to_cytoscape_json(),
+            _ => return Err(anyhow::anyhow!("不明なエクスポート形式: {}", format)),
+        };
+
+        Ok(Diagram {
+            diagram_type: diagram_type.to_string(),
+            format: format.to_string(),
+            content,
+            scope: self.describe_scope(index, diagram_type),
+        })
+    }
+
+    /// モジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONのファイルに書き出す
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `out_dir` - 出力ディレクトリ（`<図タイプ>.graphml` と `<図タイプ>.cytoscape.json` を書き出す）
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<PathBuf>>` - 書き出したファイル、またはエラー
+    pub fn write_graph_exports(&self, index: &Index, out_dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(out_dir).with_context(|| format!("出力ディレクトリを作成できません: {:?}", out_dir))?;
+        let mut files = Vec::new();
+        for diagram_type in EXPORT_GRAPHS {
+            for (format, extension) in EXPORT_FORMATS {
+                let diagram = self.export_graph(index, diagram_type, format)?;
+                let path = out_dir.join(format!("{}.{}", diagram_type, extension));
+                fs::write(&path, diagram.content).with_context(|| format!("グラフを書き込めません: {:?}", path))?;
+                files.push(path);
+            }
+        }
+        info!("グラフをエクスポートしました: {:?}（{}ファイル）", out_dir, files.len());
+        Ok(files)
+    }
+
+    /// モジュールグラフのエクスポート用モデルを構築
+    fn build_module_graph_model(&self, index: &Index) -> GraphModel {
+        let mut model = GraphModel::default();
+        let mut node_map = HashMap::new();
+
+        for (node_id, module) in index.modules.iter().enumerate() {
+            let id = format!("M{}", node_id);
+            node_map.insert(module.path.clone(), id.clone());
+            let loc = index
+                .files
+                .iter()
+                .find(|f| f.path == module.path)
+                .and_then(|f| index.file_content(f))
+                .map(|c| c.lines().count())
+                .unwrap_or(0);
+            model.nodes.push(GraphNode {
+                id,
+                label: module.id.clone(),
+                language: module.language.clone(),
+                loc,
+                package: infer_package(&index.repo_path, &module.path),
+                file: module.path.display().to_string(),
+            });
+        }
+
+        for (from, to) in module_edges(index) {
+            if let (Some(from_id), Some(to_id)) = (node_map.get(from), node_map.get(to)) {
+                model.edges.push(GraphEdge {
+                    source: from_id.clone(),
+                    target: to_id.clone(),
+                });
+            }
+        }
+
+        model
+    }
+
+    /// コールグラフのエクスポート用モデルを構築
+    /// 
+    /// エッジは関数の本体の `name(` 形式の呼び出しのうち、グラフ内の関数に一致するもの
+    /// （同名の関数が複数ある場合は同じファイルの関数を優先する）
+    fn build_call_graph_model(&self, index: &Index) -> GraphModel {
+        let mut model = GraphModel::default();
+        // ノードごとの関数の本体（定義行を除く）
+        let mut bodies: Vec<String> = Vec::new();
+
+        for file in &index.files {
+            if let Some(content) = index.file_content(file) {
+                let package = infer_package(&index.repo_path, &file.path);
+                let lines: Vec<&str> = content.lines().collect();
+                for func in self.extract_functions(&content, &file.language) {
+                    let (start, loc) = self.function_span(&lines, &func).unwrap_or_default();
+                    bodies.push(lines.get(start + 1..start + loc).map(|body| body.join("\n")).unwrap_or_default());
+                    model.nodes.push(GraphNode {
+                        id: format!("F{}", model.nodes.len()),
+                        label: func,
+                        language: file.language.clone(),
+                        loc,
+                        package: package.clone(),
+                        file: file.path.display().to_string(),
+                    });
+                }
+            }
+        }
+
+        let call = regex::Regex::new(r"([A-Za-z_][A-Za-z0-9_]*)\s*\(").expect("固定の正規表現");
+        let mut seen = BTreeSet::new();
+        for (caller, body) in model.nodes.iter().zip(&bodies) {
+            for name in call.captures_iter(body).map(|c| c.get(1).map_or("", |m| m.as_str())) {
+                if name == caller.label {
+                    continue;
+                }
+                let callee = model
+                    .nodes
+                    .iter()
+                    .find(|n| n.label == name && n.file == caller.file)
+                    .or_else(|| model.nodes.iter().find(|n| n.label == name));
+                if let Some(callee) = callee {
+                    if seen.insert((caller.id.clone(), callee.id.clone())) {
+                        model.edges.push(GraphEdge { source: caller.id.clone(), target: callee.id.clone() });
+                    }
+                }
+            }
+        }
+
+        model
+    }
+
+    /// 関数の位置を概算（定義行から対応する閉じ括弧まで）
+    /// 
+    /// # 戻り値
+    /// * `Option<(usize, usize)>` - (定義行の位置（0始まり）, 行数)、定義が見つからない場合はNone
+    fn function_span(&self, lines: &[&str], func_name: &str) -> Option<(usize, usize)> {
+        let start = lines.iter().position(|l| {
+            l.contains(&format!("fn {}", func_name))
+                || l.contains(&format!("function {}", func_name))
+                || l.contains(&format!("def {}", func_name))
+                || l.contains(&format!("func {}", func_name))
+                || l.contains(&format!("{} =", func_name))
+        })?;
+
+        let mut depth: i64 = 0;
+        let mut opened = false;
+        for (offset, line) in lines[start..].iter().enumerate() {
+            depth += line.matches('{').count() as i64;
+            depth -= line.matches('}').count() as i64;
+            if line.contains('{') {
+                opened = true;
+            }
+            if opened && depth <= 0 {
+                return Some((start, offset + 1));
+            }
+        }
+
+        // ブレースのない言語（Python）はファイル末尾までを上限とする
+        Some((start, lines.len() - start))
+    }
+
     /// シーケンス図を生成
     /// 
     /// # 引数
@@ -234,15 +566,10 @@ impl Diagrammer {
     fn generate_sequence_diagram_mermaid(&self, index: &Index) -> Result<(String, &str)> {
         let mut mermaid = String::from("sequenceDiagram\n");
 
-        // モジュールをアクターとして追加
-        let mut actors = Vec::new();
-        for module in &index.modules {
-            actors.push(module.name.clone());
-        }
-
-        // 最初の3つのモジュールを使用
-        for actor in actors.iter().take(3) {
-            mermaid.push_str(&format!("    participant {}\n", actor));
+        // 最初の3つのモジュールを参加者として追加（IDには `:` や `/` を含むため別名で表示）
+        let actors: Vec<String> = (0..index.modules.len().min(MAX_SEQUENCE_PARTICIPANTS)).map(|i| format!("M{}", i)).collect();
+        for (actor, module) in actors.iter().zip(&index.modules) {
+            mermaid.push_str(&format!("    participant {} as {}\n", actor, module.id));
         }
 
         // 簡易的なシーケンス（実際の呼び出しフローではない）
@@ -271,19 +598,199 @@ impl Diagrammer {
     }
 
     /// Mermaid形式のデプロイメント図を生成
-    fn generate_deployment_diagram_mermaid(&self, _index: &Index) -> Result<(String, &str)> {
+    /// 
+    /// マニフェストがある場合はリポジトリ内のパッケージと、実行時に依存する外部パッケージ
+    /// （データストアとそれ以外に分類）を描く。マニフェストがない場合は一般的な3層構成を描く。
+    fn generate_deployment_diagram_mermaid(&self, index: &Index) -> Result<(String, &str)> {
         let mut mermaid = String::from("graph TB\n");
-        mermaid.push_str("    subgraph \"Frontend\"\n");
-        mermaid.push_str("        FE[フロントエンド]\n");
-        mermaid.push_str("    end\n");
-        mermaid.push_str("    subgraph \"Backend\"\n");
-        mermaid.push_str("        BE[バックエンド]\n");
-        mermaid.push_str("    end\n");
-        mermaid.push_str("    subgraph \"Database\"\n");
-        mermaid.push_str("        DB[データベース]\n");
+
+        // 名前のあるマニフェスト（なければすべてのマニフェスト）をパッケージとする
+        let named: Vec<_> = index.manifests.iter().filter(|m| m.name.is_some()).collect();
+        let packages = if named.is_empty() { index.manifests.iter().collect() } else { named };
+        if packages.is_empty() {
+            mermaid.push_str("    subgraph \"Frontend\"\n");
+            mermaid.push_str("        FE[フロントエンド]\n");
+            mermaid.push_str("    end\n");
+            mermaid.push_str("    subgraph \"Backend\"\n");
+            mermaid.push_str("        BE[バックエンド]\n");
+            mermaid.push_str("    end\n");
+            mermaid.push_str("    subgraph \"Database\"\n");
+            mermaid.push_str("        DB[データベース]\n");
+            mermaid.push_str("    end\n");
+            mermaid.push_str("    FE --> BE\n");
+            mermaid.push_str("    BE --> DB\n");
+            return Ok((mermaid, "mermaid"));
+        }
+
+        // 実行時の外部依存（依存しているパッケージの多い順）
+        let mut usage: BTreeMap<&str, usize> = BTreeMap::new();
+        for dep in packages.iter().flat_map(|m| &m.dependencies).filter(|d| !d.internal && d.kind == "normal") {
+            *usage.entry(dep.name.as_str()).or_default() += 1;
+        }
+        let mut externals: Vec<(&str, usize)> = usage.into_iter().collect();
+        externals.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        externals.truncate(MAX_DEPLOYMENT_EXTERNALS);
+        let (stores, libraries): (Vec<&str>, Vec<&str>) =
+            externals.iter().map(|(name, _)| *name).partition(|name| is_datastore(name));
+
+        mermaid.push_str("    subgraph \"リポジトリ\"\n");
+        for (i, manifest) in packages.iter().enumerate() {
+            let name = manifest.name.clone().unwrap_or_else(|| manifest.path.display().to_string());
+            mermaid.push_str(&format!("        P{}[\"{}<br/>{}\"]\n", i, mermaid_label(&name), manifest.ecosystem));
+        }
         mermaid.push_str("    end\n");
-        mermaid.push_str("    FE --> BE\n");
-        mermaid.push_str("    BE --> DB\n");
+        for (title, names, shape) in [("データストア", &stores, ("[(\"", "\")]")), ("外部ライブラリ", &libraries, ("[\"", "\"]"))] {
+            if names.is_empty() {
+                continue;
+            }
+            mermaid.push_str(&format!("    subgraph \"{}\"\n", title));
+            for name in names.iter() {
+                let i = externals.iter().position(|(n, _)| n == name).unwrap_or(0);
+                mermaid.push_str(&format!("        E{}{}{}{}\n", i, shape.0, mermaid_label(name), shape.1));
+            }
+            mermaid.push_str("    end\n");
+        }
+
+        let mut edges = BTreeSet::new();
+        for (i, manifest) in packages.iter().enumerate() {
+            for dep in manifest.dependencies.iter().filter(|d| d.kind == "normal") {
+                if dep.internal {
+                    if let Some(j) = packages.iter().position(|m| m.name.as_deref() == Some(dep.name.as_str())) {
+                        if i != j {
+                            edges.insert(format!("    P{} --> P{}\n", i, j));
+                        }
+                    }
+                } else if let Some(j) = externals.iter().position(|(name, _)| *name == dep.name) {
+                    edges.insert(format!("    P{} --> E{}\n", i, j));
+                }
+            }
+        }
+        for edge in edges {
+            mermaid.push_str(&edge);
+        }
+
+        Ok((mermaid, "mermaid"))
+    }
+
+    /// 循環依存の図を生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `Result<(String, &str)>` - (内容, フォーマット) またはエラー（循環依存がない場合を含む）
+    fn generate_cycle_diagram(&self, index: &Index) -> Result<(String, &str)> {
+        match self.config.analysis.diagrams.renderer.as_str() {
+            "mermaid" => self.generate_cycle_diagram_mermaid(index),
+            _ => Err(anyhow::anyhow!("循環依存の図はMermaidのみサポートされています")),
+        }
+    }
+
+    /// Mermaid形式の循環依存の図を生成
+    /// 
+    /// 循環ごとにsubgraphで囲み、循環内のimportを矢印で描く。最短の循環の経路の矢印は赤い太線で強調する。
+    fn generate_cycle_diagram_mermaid(&self, index: &Index) -> Result<(String, &str)> {
+        let cycles = index.dependency_cycles();
+        if cycles.is_empty() {
+            return Err(anyhow::anyhow!("循環依存はありません"));
+        }
+
+        let mut mermaid = String::from("graph LR\n");
+        let mut edges = Vec::new();
+        let mut nodes = Vec::new();
+        for (c, cycle) in cycles.iter().take(MAX_CYCLES).enumerate() {
+            let id = |path: &PathBuf| cycle.files.iter().position(|f| f == path).map(|i| format!("C{}N{}", c, i));
+            mermaid.push_str(&format!("    subgraph C{}[\"循環{}（{}ファイル）\"]\n", c, c + 1, cycle.files.len()));
+            for (i, file) in cycle.files.iter().enumerate() {
+                let label = file.strip_prefix(&index.repo_path).unwrap_or(file).display().to_string();
+                mermaid.push_str(&format!("        C{}N{}[\"{}\"]\n", c, i, mermaid_label(&label)));
+                nodes.push(format!("C{}N{}", c, i));
+            }
+            mermaid.push_str("    end\n");
+
+            let on_path: BTreeSet<(&PathBuf, &PathBuf)> = cycle.path.windows(2).map(|w| (&w[0], &w[1])).collect();
+            for from in &cycle.files {
+                for to in index.internal_dependencies.get(from).into_iter().flatten() {
+                    if let (Some(from_id), Some(to_id)) = (id(from), id(to)) {
+                        if from_id != to_id {
+                            edges.push((from_id, to_id, on_path.contains(&(from, to))));
+                        }
+                    }
+                }
+            }
+        }
+        for (from, to, _) in &edges {
+            mermaid.push_str(&format!("    {} --> {}\n", from, to));
+        }
+        mermaid.push_str("    classDef cycle fill:#fdecea,stroke:#d93025,color:#000\n");
+        mermaid.push_str(&format!("    class {} cycle\n", nodes.join(",")));
+        let highlighted: Vec<String> =
+            edges.iter().enumerate().filter(|(_, (_, _, on_path))| *on_path).map(|(i, _)| i.to_string()).collect();
+        if !highlighted.is_empty() {
+            mermaid.push_str(&format!("    linkStyle {} stroke:#d93025,stroke-width:3px\n", highlighted.join(",")));
+        }
+
+        Ok((mermaid, "mermaid"))
+    }
+
+    /// クラス図を生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `Result<(String, &str)>` - (内容, フォーマット) またはエラー
+    fn generate_class_diagram(&self, index: &Index) -> Result<(String, &str)> {
+        match self.config.analysis.diagrams.renderer.as_str() {
+            "mermaid" => self.generate_class_diagram_mermaid(index),
+            _ => Err(anyhow::anyhow!("クラス図はMermaidのみサポートされています")),
+        }
+    }
+
+    /// Mermaid形式のクラス図を生成（implementsは `..|>`、extendsは `--|>`）
+    fn generate_class_diagram_mermaid(&self, index: &Index) -> Result<(String, &str)> {
+        let mut mermaid = String::from("classDiagram\n");
+
+        let mut edges = BTreeSet::new();
+        for (_, relation) in index.type_relations() {
+            let arrow = if relation.kind == "implements" { "..|>" } else { "--|>" };
+            edges.insert((class_id(&relation.name), arrow, class_id(&relation.target)));
+        }
+        let edges: Vec<_> = edges.into_iter().take(MAX_CLASS_EDGES).collect();
+
+        // トレイト・インターフェースとして宣言された型にステレオタイプを付け、メソッドを可視性付きで並べる
+        let classes: BTreeSet<&str> = edges.iter().flat_map(|(from, _, to)| [from.as_str(), to.as_str()]).collect();
+        let mut members: BTreeMap<String, (Option<String>, BTreeSet<String>)> = BTreeMap::new();
+        for symbol in index.files.iter().flat_map(|f| &f.symbols) {
+            if matches!(symbol.kind.as_str(), "trait" | "interface") {
+                let id = class_id(&symbol.name);
+                if classes.contains(id.as_str()) {
+                    members.entry(id).or_default().0 = Some(symbol.kind.clone());
+                }
+            }
+            if let Some(parent) = symbol.parent.as_deref().filter(|_| symbol.kind == "method") {
+                let id = class_id(&strip_generics(parent));
+                if classes.contains(id.as_str()) {
+                    let methods = &mut members.entry(id).or_default().1;
+                    if methods.len() < MAX_CLASS_METHODS {
+                        methods.insert(format!("{}{}()", visibility_marker(&symbol.visibility), class_id(&symbol.name)));
+                    }
+                }
+            }
+        }
+        for (id, (stereotype, methods)) in &members {
+            mermaid.push_str(&format!("    class {} {{\n", id));
+            if let Some(kind) = stereotype {
+                mermaid.push_str(&format!("        <<{}>>\n", kind));
+            }
+            for method in methods {
+                mermaid.push_str(&format!("        {}\n", method));
+            }
+            mermaid.push_str("    }\n");
+        }
+        for (from, arrow, to) in &edges {
+            mermaid.push_str(&format!("    {} {} {}\n", from, arrow, to));
+        }
 
         Ok((mermaid, "mermaid"))
     }
@@ -350,12 +857,121 @@ impl Diagrammer {
     }
 }
 
+/// クラス図に描く関係の最大数（大規模リポジトリで図が読めなくなるのを防ぐ）
+const MAX_CLASS_EDGES: usize = 100;
+
+/// クラス図で1つのクラスに並べるメソッドの最大数
+const MAX_CLASS_METHODS: usize = 10;
+
+/// 可視性をMermaidのメンバーの記号に変換（判定できない場合は記号なし）
+fn visibility_marker(visibility: &str) -> &'static str {
+    match visibility {
+        "public" => "+",
+        "private" => "-",
+        "protected" => "#",
+        "internal" => "~",
+        _ => "",
+    }
+}
+
+/// メソッドの所属先からジェネリクスを除いた型名を取得（`Store<T>` → `Store`）
+fn strip_generics(parent: &str) -> String {
+    parent.split(['<', '[']).next().unwrap_or(parent).trim().to_string()
+}
+
+/// 層構成図で1つの層に描くモジュールの最大数
+const MAX_LAYER_MODULES: usize = 8;
+
+/// 層構成図の層の名前
+fn layer_label(layer: &str) -> &'static str {
+    match layer {
+        "ui" => "UI",
+        "api" => "API・ハンドラー",
+        "domain" => "ドメイン・サービス",
+        "persistence" => "永続化",
+        "utils" => "ユーティリティ",
+        _ => "その他",
+    }
+}
+
+/// コンポーネントの種類の名前（凡例用）
+pub(crate) fn component_label(kind: ComponentKind) -> &'static str {
+    match kind {
+        ComponentKind::Config => "設定",
+        ComponentKind::Api => "API",
+        ComponentKind::Util => "ユーティリティ",
+        ComponentKind::Model => "モデル",
+        ComponentKind::Service => "サービス",
+        ComponentKind::Db => "DB・ストレージ",
+        ComponentKind::Ui => "UI",
+        ComponentKind::Worker => "ワーカー",
+        ComponentKind::Infra => "インフラ",
+        ComponentKind::Other => "その他",
+    }
+}
+
+/// 循環依存の図に描く循環の最大数
+const MAX_CYCLES: usize = 10;
+
+/// シーケンス図に描くモジュールの最大数
+const MAX_SEQUENCE_PARTICIPANTS: usize = 3;
+
+/// デプロイメント図に描く外部パッケージの最大数
+const MAX_DEPLOYMENT_EXTERNALS: usize = 8;
+
+/// データストアのクライアント・ORMとみなすパッケージ名の一部
+const DATASTORE_HINTS: &[&str] = &[
+    "postgres", "mysql", "sqlite", "redis", "mongo", "sqlx", "diesel", "sea-orm", "prisma", "typeorm",
+    "sequelize", "psycopg", "sqlalchemy", "gorm", "pgx", "elasticsearch", "dynamodb", "cassandra",
+];
+
+/// データストアのクライアントか（パッケージ名で判定）
+fn is_datastore(name: &str) -> bool {
+    let name = name.to_lowercase();
+    DATASTORE_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// Mermaidのラベルに使えない `"` を置き換え
+fn mermaid_label(text: &str) -> String {
+    text.replace('"', "'")
+}
+
+/// 型名をMermaidのクラスIDに変換（英数字と `_` 以外は `_`）
+fn class_id(name: &str) -> String {
+    name.chars().map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' }).collect()
+}
+
+/// モジュール間の依存（依存元 → 依存先のファイルパス）
+///
+/// 内部依存グラフのないインデックスではimport文字列とモジュールIDのパスの一致で推定する
+fn module_edges(index: &Index) -> Vec<(&PathBuf, &PathBuf)> {
+    if !index.internal_dependencies.is_empty() {
+        return index
+            .internal_dependencies
+            .iter()
+            .flat_map(|(from, targets)| targets.iter().map(move |to| (from, to)))
+            .collect();
+    }
+    let mut edges = Vec::new();
+    for module in &index.modules {
+        for dep in &module.dependencies {
+            if let Some(to_module) = index.modules.iter().find(|m| m.path != module.path && m.matches_import(dep)) {
+                edges.push((&module.path, &to_module.path));
+            }
+        }
+    }
+    edges
+}
+
 /// 図
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Diagram {
     pub diagram_type: String,
     pub format: String,
     pub content: String,
+    /// 図に含めた範囲（キャプション用、例: 「リポジトリ全体の12モジュール」）
+    #[serde(default)]
+    pub scope: String,
 }
 
 #[cfg(test)]
@@ -392,5 +1008,286 @@ def bar():
         assert!(funcs.contains(&"foo".to_string()));
         assert!(funcs.contains(&"bar".to_string()));
     }
-}
 
+    #[test]
+    fn test_export_module_graph_graphml_and_cytoscape() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let index = Index {
+            id: "test".to_string(),
+            repo_path: std::path::PathBuf::from("/repo"),
+            files: vec![analyzer_core::FileInfo {
+                path: std::path::PathBuf::from("/repo/crates/core/src/lib.rs"),
+                name: "lib".to_string(),
+                language: "rs".to_string(),
+                size: 20,
+                is_module: true,
+                content: Some("fn a() {\n}\n".to_string()),
+                ..Default::default()
+            }],
+            modules: vec![analyzer_core::ModuleInfo {
+                path: std::path::PathBuf::from("/repo/crates/core/src/lib.rs"),
+                id: "core:src/lib.rs".to_string(),
+                name: "lib".to_string(),
+                language: "rs".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let graphml = diagrammer.export_graph(&index, "module-graph", "graphml").unwrap();
+        assert!(graphml.content.contains("<data key=\"package\">crates/core</data>"));
+        assert!(graphml.content.contains("<data key=\"loc\">2</data>"));
+
+        let cytoscape = diagrammer.export_graph(&index, "module-graph", "cytoscape").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&cytoscape.content).unwrap();
+        assert_eq!(json["elements"]["nodes"][0]["data"]["language"], "rs");
+    }
+
+    #[test]
+    fn test_write_graph_exports_with_call_edges() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let file = |path: &str, content: &str| analyzer_core::FileInfo {
+            path: std::path::PathBuf::from(path),
+            language: "rs".to_string(),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: std::path::PathBuf::from("/repo"),
+            files: vec![
+                file("/repo/src/main.rs", "fn main() {\n    let config = load();\n    run(config);\n}\n\nfn run(config: Config) {\n    load();\n}\n"),
+                file("/repo/src/config.rs", "fn load() -> Config {\n    Config::default()\n}\n"),
+            ],
+            ..Default::default()
+        };
+
+        let model = diagrammer.build_call_graph_model(&index);
+        let edges: Vec<(&str, &str)> = model
+            .edges
+            .iter()
+            .map(|e| {
+                let label = |id: &str| model.nodes.iter().find(|n| n.id == id).unwrap().label.as_str();
+                (label(&e.source), label(&e.target))
+            })
+            .collect();
+        assert_eq!(edges, vec![("main", "load"), ("main", "run"), ("run", "load")]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let files = diagrammer.write_graph_exports(&index, dir.path()).unwrap();
+        let names: Vec<String> = files.iter().map(|f| f.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(
+            names,
+            vec!["module-graph.graphml", "module-graph.cytoscape.json", "call-graph.graphml", "call-graph.cytoscape.json"]
+        );
+        let call_graph = fs::read_to_string(dir.path().join("call-graph.graphml")).unwrap();
+        assert_eq!(call_graph.matches("<edge ").count(), 3);
+
+        // エクスポート形式はレンダラとしては選べない（ページに埋め込めないため）
+        let mut config = Config::default();
+        config.analysis.diagrams.renderer = "graphml".to_string();
+        assert!(Diagrammer::new(config).generate_diagram(&index, "call-graph").is_err());
+    }
+
+    #[test]
+    fn test_module_graph_edges_from_internal_dependencies() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let module = |path: &str, name: &str| analyzer_core::ModuleInfo {
+            path: PathBuf::from(path),
+            id: path.trim_start_matches("/repo/").to_string(),
+            name: name.to_string(),
+            language: "ts".to_string(),
+            ..Default::default()
+        };
+        let mut index = Index {
+            repo_path: PathBuf::from("/repo"),
+            modules: vec![module("/repo/src/app.ts", "app"), module("/repo/src/api.ts", "api")],
+            ..Default::default()
+        };
+        index
+            .internal_dependencies
+            .insert(PathBuf::from("/repo/src/app.ts"), vec![PathBuf::from("/repo/src/api.ts")]);
+
+        let diagram = diagrammer.generate_diagram(&index, "module-graph").unwrap();
+        assert!(diagram.content.contains("    M0 --> M1\n"));
+    }
+
+    #[test]
+    fn test_module_graph_distinguishes_same_file_names() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let module = |path: &str, id: &str, dependencies: &[&str]| analyzer_core::ModuleInfo {
+            path: PathBuf::from(path),
+            id: id.to_string(),
+            name: "index".to_string(),
+            language: "ts".to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            modules: vec![
+                module("/repo/packages/web/src/index.ts", "web:src/index.ts", &["./api"]),
+                module("/repo/packages/web/src/api/index.ts", "web:src/api/index.ts", &[]),
+                module("/repo/packages/server/src/index.ts", "server:src/index.ts", &[]),
+            ],
+            ..Default::default()
+        };
+
+        // 内部依存グラフがない場合もimportとIDのパスで対応付ける（同名の `index` とは結ばない）
+        let diagram = diagrammer.generate_diagram(&index, "module-graph").unwrap();
+        assert!(diagram.content.contains("    M0[\"📄 web:src/index.ts\"]\n"));
+        assert!(diagram.content.contains("    M2[\"📄 server:src/index.ts\"]\n"));
+        assert!(diagram.content.contains("    M0 --> M1\n"));
+        assert!(!diagram.content.contains("--> M2"));
+    }
+
+    #[test]
+    fn test_module_graph_decorates_component_kinds() {
+        let module = |path: &str, component: ComponentKind| analyzer_core::ModuleInfo {
+            path: PathBuf::from(path),
+            id: path.to_string(),
+            component,
+            ..Default::default()
+        };
+        let index = Index {
+            modules: vec![
+                module("src/routes.ts", ComponentKind::Api),
+                module("src/db.ts", ComponentKind::Db),
+                module("src/orders_api.ts", ComponentKind::Api),
+            ],
+            ..Default::default()
+        };
+
+        let diagram = Diagrammer::new(Config::default()).generate_diagram(&index, "module-graph").unwrap();
+        assert!(diagram.content.contains("    M1[\"🗄️ src/db.ts\"]\n"), "{}", diagram.content);
+        assert!(diagram.content.contains("    classDef api fill:#e3f2fd,stroke:#90a4ae\n    class M0,M2 api\n"), "{}", diagram.content);
+        assert!(diagram.legend().ends_with("🔌 API・🗄️ DB・ストレージ"), "{}", diagram.legend());
+
+        let mut config = Config::default();
+        config.analysis.diagrams.icons = false;
+        config.analysis.diagrams.renderer = "graphviz".to_string();
+        let diagram = Diagrammer::new(config).generate_diagram(&index, "module-graph").unwrap();
+        assert!(diagram.content.contains("    M1 [label=\"src/db.ts\"];\n"), "{}", diagram.content);
+    }
+
+    #[test]
+    fn test_cycle_diagram_highlights_shortest_path() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let repo = PathBuf::from("/repo");
+        let mut index = Index { repo_path: repo.clone(), ..Default::default() };
+        assert!(diagrammer.generate_diagram(&index, "dependency-cycles").is_err());
+
+        for (from, to) in [("a.ts", vec!["b.ts"]), ("b.ts", vec!["a.ts", "c.ts"]), ("c.ts", vec!["b.ts"]), ("d.ts", vec!["a.ts"])] {
+            index.internal_dependencies.insert(repo.join(from), to.into_iter().map(|t| repo.join(t)).collect());
+        }
+        let diagram = diagrammer.generate_diagram(&index, "dependency-cycles").unwrap();
+        assert!(diagram.content.contains("    subgraph C0[\"循環1（3ファイル）\"]\n        C0N0[\"a.ts\"]\n"));
+        assert!(!diagram.content.contains("d.ts"));
+        // 最短の循環 a → b → a の2本を強調する
+        assert!(diagram.content.contains("    C0N0 --> C0N1\n    C0N1 --> C0N0\n    C0N1 --> C0N2\n    C0N2 --> C0N1\n"));
+        assert!(diagram.content.contains("    class C0N0,C0N1,C0N2 cycle\n"));
+        assert!(diagram.content.contains("    linkStyle 0,1 stroke:#d93025,stroke-width:3px\n"));
+        assert_eq!(diagram.scope, "循環1件のうち1件");
+
+        let mut config = Config::default();
+        config.analysis.diagrams.renderer = "graphviz".to_string();
+        assert!(Diagrammer::new(config).generate_diagram(&index, "dependency-cycles").is_err());
+    }
+
+    #[test]
+    fn test_layer_diagram_groups_modules_by_layer() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let repo = PathBuf::from("/repo");
+        let mut index = Index { repo_path: repo.clone(), ..Default::default() };
+        assert!(diagrammer.generate_diagram(&index, "layers").is_err());
+
+        for path in ["src/handlers/users.rs", "src/db.rs", "src/main.rs"] {
+            index.modules.push(analyzer_core::ModuleInfo { path: repo.join(path), id: path.to_string(), ..Default::default() });
+        }
+        index.internal_dependencies.insert(repo.join("src/handlers/users.rs"), vec![repo.join("src/db.rs")]);
+        index.internal_dependencies.insert(repo.join("src/db.rs"), vec![repo.join("src/handlers/users.rs")]);
+
+        let diagram = diagrammer.generate_diagram(&index, "layers").unwrap();
+        assert!(diagram.content.contains("    subgraph L1[\"API・ハンドラー（1）\"]\n        L1M0[\"src/handlers/users.rs\"]\n    end\n"));
+        assert!(diagram.content.contains("    subgraph L5[\"その他（1）\"]\n"));
+        assert!(diagram.content.contains("    L1 -->|1| L3\n    L3 -->|1| L1\n"));
+        assert!(diagram.content.contains("    linkStyle 1 stroke:#d93025,stroke-width:3px\n"));
+        assert_eq!(diagram.scope, "3モジュールのうち層を推定できた2件");
+        assert!(diagram.legend().contains("層の逆転"));
+    }
+
+    #[test]
+    fn test_class_diagram_from_relations() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let relation = |kind: &str, name: &str, target: &str| analyzer_core::TypeRelation {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            target: target.to_string(),
+            line: 1,
+            inferred: false,
+        };
+        let index = Index {
+            files: vec![analyzer_core::FileInfo {
+                path: std::path::PathBuf::from("/repo/src/store.rs"),
+                symbols: analyzer_core::parse_source("trait Store {}\n", "rs").unwrap().symbols,
+                relations: vec![relation("implements", "Memory", "Store"), relation("extends", "Store", "Send")],
+                ..Default::default()
+            }, analyzer_core::FileInfo {
+                path: std::path::PathBuf::from("/repo/src/memory.rs"),
+                symbols: analyzer_core::parse_source("impl Memory {\n    pub fn new() {}\n    fn evict() {}\n}\n", "rs")
+                    .unwrap()
+                    .symbols,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let diagram = diagrammer.generate_diagram(&index, "class-diagram").unwrap();
+        assert!(diagram.content.starts_with("classDiagram\n"));
+        assert!(diagram.content.contains("    class Store {\n        <<trait>>\n    }\n"));
+        assert!(diagram.content.contains("    class Memory {\n        +new()\n        -evict()\n    }\n"));
+        assert!(diagram.content.contains("    Memory ..|> Store\n"));
+        assert!(diagram.content.contains("    Store --|> Send\n"));
+
+        let mut config = Config::default();
+        config.analysis.diagrams.renderer = "graphviz".to_string();
+        assert!(Diagrammer::new(config.clone()).generate_diagram(&index, "class-diagram").is_err());
+
+        let diagnostics = Diagnostics::new();
+        let diagrammer = Diagrammer::new(config).with_diagnostics(diagnostics.clone());
+        assert!(diagrammer.try_generate_diagram(&index, "class-diagram").is_none());
+        assert_eq!(diagnostics.entries()[0].source, "diagrammer");
+    }
+    #[test]
+    fn test_deployment_diagram_from_manifests() {
+        let diagrammer = Diagrammer::new(Config::default());
+        let dep = |name: &str, kind: &str, internal: bool| analyzer_core::DeclaredDependency {
+            name: name.to_string(),
+            version: Some("1".to_string()),
+            kind: kind.to_string(),
+            internal,
+        };
+        let manifest = |path: &str, name: &str, dependencies| analyzer_core::Manifest {
+            path: std::path::PathBuf::from(path),
+            ecosystem: "cargo".to_string(),
+            name: Some(name.to_string()),
+            dependencies,
+            workspace_members: Vec::new(),
+        };
+        let index = Index {
+            manifests: vec![
+                manifest("api/Cargo.toml", "api", vec![dep("core", "normal", true), dep("axum", "normal", false)]),
+                manifest("core/Cargo.toml", "core", vec![dep("sqlx", "normal", false), dep("tempfile", "dev", false)]),
+            ],
+            ..Default::default()
+        };
+
+        let diagram = diagrammer.generate_diagram(&index, "deployment").unwrap();
+        assert!(diagram.content.contains("        P0[\"api<br/>cargo\"]\n        P1[\"core<br/>cargo\"]\n"));
+        assert!(diagram.content.contains("    subgraph \"データストア\"\n        E1[(\"sqlx\")]\n    end\n"));
+        assert!(diagram.content.contains("    P0 --> E0\n    P0 --> P1\n    P1 --> E1\n"));
+        assert!(!diagram.content.contains("tempfile"));
+
+        let fallback = diagrammer.generate_diagram(&Index::default(), "deployment").unwrap();
+        assert!(fallback.content.contains("FE --> BE"));
+    }
+}
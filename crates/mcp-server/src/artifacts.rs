@@ -0,0 +1,214 @@
+/*!
+ * 生成した成果物の取得
+ *
+ * サーバーのファイルシステムにアクセスできないリモートのMCPクライアントでも、
+ * 生成したスライド（PDFなど）・Wikiのページ・図を取り出せるようにする
+ *
+ * 主な仕様:
+ * - 成果物の種類（`ARTIFACT_KINDS`）: slides（スライドの出力先）、wiki（Wikiの出力先）、diagram（両方の出力先にある図の画像・ソース）
+ * - 成果物のIDは出力先ディレクトリからの相対パス（例: `slides.pdf`、`src/architecture.md`）
+ * - `file://` のURIか、base64でエンコードした内容をバイト位置（offset）から指定した長さずつ返す
+ * - IDは正規化（シンボリックリンク・`..` を解決）してから出力先ディレクトリ配下かを判定
+ *
+ * 制限事項:
+ * - 1回に返す内容は `MAX_CHUNK_BYTES` まで（大きなファイルは `next_offset` で続きを取得する）
+ * - 一覧は出力先ディレクトリ配下を最大 `MAX_LISTED` 件まで
+ */
+
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+use crate::validation::RpcError;
+
+/// 成果物の種類
+pub(crate) const ARTIFACT_KINDS: &[&str] = &["slides", "wiki", "diagram"];
+
+/// 成果物の返し方
+pub(crate) const ARTIFACT_ENCODINGS: &[&str] = &["uri", "base64"];
+
+/// 図として扱うファイルの拡張子
+const DIAGRAM_EXTENSIONS: &[&str] = &["svg", "png", "mmd", "dot", "puml"];
+
+/// 1回に返す内容の最大バイト数
+pub(crate) const MAX_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// 一覧に含める成果物の最大数
+const MAX_LISTED: usize = 1000;
+
+/// 出力先ディレクトリ配下の成果物のIDを一覧にする
+///
+/// # 引数
+/// * `kind` - 成果物の種類
+/// * `roots` - 出力先ディレクトリ（存在しないものは無視）
+///
+/// # 戻り値
+/// * `Vec<String>` - 成果物のID（`/` 区切り、名前順）
+pub(crate) fn list(kind: &str, roots: &[PathBuf]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for root in roots {
+        collect_files(root, root, &mut ids);
+    }
+    ids.retain(|id| kind != "diagram" || is_diagram(Path::new(id)));
+    ids.sort();
+    ids.dedup();
+    ids.truncate(MAX_LISTED);
+    ids
+}
+
+/// ディレクトリ配下のファイルを再帰的に集める
+fn collect_files(root: &Path, dir: &Path, ids: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => collect_files(root, &path, ids),
+            Ok(file_type) if file_type.is_file() => {
+                if let Ok(relative) = path.strip_prefix(root) {
+                    ids.push(relative.to_string_lossy().replace('\\', "/"));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 成果物のIDをファイルのパスに解決
+///
+/// # 引数
+/// * `kind` - 成果物の種類
+/// * `id` - 出力先ディレクトリからの相対パス
+/// * `roots` - 出力先ディレクトリ（先に見つかったものを使う）
+///
+/// # 戻り値
+/// * `Result<PathBuf, RpcError>` - 正規化したパス、またはパラメータ不正のエラー
+pub(crate) fn resolve(kind: &str, id: &str, roots: &[PathBuf]) -> Result<PathBuf, RpcError> {
+    let relative = Path::new(id);
+    let escapes = relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if id.trim().is_empty() || escapes {
+        return Err(RpcError::invalid_params(format!(
+            "成果物のIDは出力先ディレクトリからの相対パスで指定してください: {}",
+            id
+        )));
+    }
+    if kind == "diagram" && !is_diagram(relative) {
+        return Err(RpcError::invalid_params(format!(
+            "図の拡張子は {} のいずれかである必要があります: {}",
+            DIAGRAM_EXTENSIONS.join(", "),
+            id
+        )));
+    }
+
+    for root in roots {
+        let (Ok(root), Ok(path)) = (root.canonicalize(), root.join(relative).canonicalize()) else {
+            continue;
+        };
+        if path.starts_with(&root) && path.is_file() {
+            return Ok(path);
+        }
+    }
+    Err(RpcError::invalid_params(format!("成果物が見つかりません: {} {}", kind, id)))
+}
+
+/// ファイルの指定した範囲を読み込む
+///
+/// # 引数
+/// * `path` - ファイルのパス
+/// * `offset` - 読み込みを始めるバイト位置
+/// * `length` - 読み込む最大バイト数
+///
+/// # 戻り値
+/// * `std::io::Result<Vec<u8>>` - 読み込んだ内容（ファイルの末尾を超えた場合は空）
+pub(crate) fn read_chunk(path: &Path, offset: u64, length: usize) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut data = Vec::new();
+    file.take(length as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// 拡張子からMIMEタイプを推定
+pub(crate) fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("pptx") => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        Some("html") | Some("htm") => "text/html",
+        Some("md") => "text/markdown",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("toml") => "application/toml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("mmd") | Some("dot") | Some("puml") | Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 絶対パスを `file://` のURIにする
+///
+/// # 引数
+/// * `path` - 絶対パス
+///
+/// # 戻り値
+/// * `String` - URI（英数字・`-._~/` 以外はパーセントエンコード）
+pub(crate) fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// 図として扱うファイルか
+fn is_diagram(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| DIAGRAM_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_and_read_artifacts() {
+        let root = std::env::temp_dir().join(format!("deeprepo-artifacts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("src/badges")).unwrap();
+        std::fs::write(root.join("slides.pdf"), b"%PDF-1.7 data").unwrap();
+        std::fs::write(root.join("src/badges/coverage.svg"), "<svg/>").unwrap();
+        std::fs::write(root.join("src/アーキテクチャ.md"), "# 構成").unwrap();
+        let roots = vec![root.clone()];
+
+        assert_eq!(list("slides", &roots), vec!["slides.pdf", "src/badges/coverage.svg", "src/アーキテクチャ.md"]);
+        assert_eq!(list("diagram", &roots), vec!["src/badges/coverage.svg"]);
+
+        let path = resolve("slides", "slides.pdf", &roots).unwrap();
+        assert_eq!(mime_type(&path), "application/pdf");
+        assert_eq!(read_chunk(&path, 9, 3).unwrap(), b"dat");
+        assert!(read_chunk(&path, 100, 3).unwrap().is_empty());
+
+        let uri = file_uri(&resolve("wiki", "src/アーキテクチャ.md", &roots).unwrap());
+        assert!(uri.starts_with("file:///") && uri.ends_with("/src/%E3%82%A2%E3%83%BC%E3%82%AD%E3%83%86%E3%82%AF%E3%83%81%E3%83%A3.md"));
+
+        // 出力先の外・存在しないファイル・図以外の拡張子は拒否
+        assert!(resolve("wiki", "../secret.txt", &roots).is_err());
+        assert!(resolve("wiki", "/etc/passwd", &roots).is_err());
+        assert!(resolve("wiki", "missing.md", &roots).is_err());
+        assert!(resolve("diagram", "slides.pdf", &roots).is_err());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
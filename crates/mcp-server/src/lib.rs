@@ -1,4 +1,4 @@
-/**
+/*!
  * MCPサーバー実装
  * 
  * JSON-RPC over stdioでMCPクライアントと通信し、
@@ -7,28 +7,67 @@
  * 主な仕様:
  * - JSON-RPC 2.0プロトコルに準拠
  * - 標準入出力経由で通信
- * - ツール: index_repo, summarize, generate_wiki, generate_slides, publish_pages, search
+ * - MCPのライフサイクル（initialize, tools/list, tools/call, ping）に対応
+ * - ツール: index_repo, summarize, set_options, generate_wiki, generate_page, generate_slides, publish_pages, search, semantic_search, explain_symbol, ask, diff, export_graph, get_artifact
+ * - export_graphはモジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONのファイルに書き出す（Wiki・スライドの図には使わない）
+ * - diffは2つの版（index_idまたはコミットSHA）の差分を、Wiki・リリースのスライドに貼れる変更履歴のMarkdownで返す
+ * - askは検索・埋め込みのインデックスから取り出した抜粋をもとに、要約と同じバックエンド（ローカルLLM、なければ抜粋）で引用付きの日本語の回答を返す
+ * - set_optionsで指定したオプションは、セッション（次のinitializeまで）の以降の呼び出しに適用
+ * - 後方互換性のため、ツール名をメソッドとして直接呼び出すこともできる
+ * - レスポンスは書き込みタスク経由で出力（`server.framing` で改行区切り / Content-Lengthを選択）
+ * - リクエストサイズの上限と、解析対象・出力先・公開元のパスの許可ディレクトリによる制限（`server.max-request-bytes`, `server.allowed-roots`）
+ * - 作成したインデックスは `server.index-dir` に保存し、再起動後もindex_id・repo_pathから読み込む
+ * - summarize/search/semantic_search/ask/generate_wikiは `as_of`（index_idまたはコミットSHA）で過去の版を指定できる
+ * - generate_wiki・generate_slidesは `path` で生成対象をサブディレクトリ配下に絞り込める（`Index::subset`）
+ * - generate_wikiは `resume` で中断したビルドを続きから再開できる（生成済みのページ・モジュールの解説を再利用）
+ * - generate_slidesは `language`（ja|en）でスライドの言語を指定できる
+ * - summarize・generate_wiki・generate_page・generate_slidesは `lang`（ja|en）でその呼び出しに限り出力言語を上書きできる（セッションのオプション・設定ファイルより優先）
+ * - `security.read-only-repo` の場合、インデックス・Wiki・スライド・公開の出力先がリポジトリ内なら作業ディレクトリ配下に置き換える（解析したリポジトリ内には保存しない）
+ * - searchは `kind`（text|symbol|path）で本文・宣言されたシンボル名・ファイルパスのいずれを検索するかを選べる
+ * - searchは `language`・`path_glob`・`module` で対象のファイルを絞り込み、`offset` でページ送りできる
+ * - search・semantic_searchは `index_id`・`repo_path` で検索するインデックスを指定できる（省略時はメモリ上の最新のインデックス）
+ * - get_artifactは生成したスライド・Wikiのページ・図を `file://` のURIか、base64の内容（`offset`・`length` で分割）で返す（サーバーのファイルシステムにアクセスできないクライアント向け、artifactsモジュール）
+ * - `server.metrics-addr` を指定すると `GET /metrics` でPrometheusメトリクスを公開
+ * - エラーはJSON-RPCのエラーコード（-32700, -32600, -32601, -32602, -32603, -32001）で返す
  * 
  * 制限事項:
  * - リクエストの並列処理は現在サポートしていない（順次処理）
  */
 
+use base64::Engine;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::RwLock;
-use tracing::{debug, error, info};
+use tokio::io::{self, BufReader};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{debug, error, info, warn};
 use chrono::Utc;
 
-use config::Config;
-use analyzer_core::{Analyzer, Index, IndexStats, SearchHit};
-use summarizer::{Summarizer, SummarizeResult};
+use config::{Config, LANGUAGES, RENDERERS};
+use analyzer_core::{
+    search_docs, Analyzer, Diagnostic, Executor, Index, IndexStats, SearchFilter, SearchHit, SymbolExplanation,
+};
+use summarizer::{AskResult, DiffResult, Summarizer, SummarizeResult, Tools};
 use diagrammer::Diagrammer;
 use site_mdbook::{MdBookBuilder, WikiResult};
 use slides::{SlideBuilder, SlideResult};
-use publisher_ghpages::{Publisher, PublishResult};
+use publisher_ghpages::{BuildSummary, Notifier, Publisher, PublishResult};
+
+mod artifacts;
+mod metrics;
+mod persist;
+mod session;
+mod transport;
+mod validation;
+
+pub use transport::Framing;
+use metrics::Metrics;
+use session::SessionOptions;
+use transport::Incoming;
+use validation::RpcError;
 
 /// MCPサーバーの実装
 pub struct McpServer {
@@ -36,13 +75,16 @@ pub struct McpServer {
     config: Config,
     /// インデックスストレージ（index_id -> Index）
     indexes: Arc<RwLock<HashMap<String, Index>>>,
-    /// アナライザー
-    analyzer: Arc<Analyzer>,
-    /// サマライザー
-    summarizer: Arc<Summarizer>,
-    /// ダイアグラマー
-    #[allow(dead_code)]
-    diagrammer: Arc<Diagrammer>,
+    /// 解析を許可するディレクトリ（正規化済み）
+    allowed_roots: Vec<PathBuf>,
+    /// Prometheusメトリクス
+    metrics: Arc<Metrics>,
+    /// set_optionsで指定されたセッションのオプション
+    session: RwLock<SessionOptions>,
+    /// Wiki・スライドの生成タスクを実行するワーカープール（呼び出しをまたいで共有）
+    executor: Executor,
+    /// Wiki・スライドのビルダーが使う外部ツール（失敗数をメトリクスと共有）
+    tools: Tools,
 }
 
 impl McpServer {
@@ -53,13 +95,18 @@ impl McpServer {
     /// 
     /// # 戻り値
     /// * `Self` - MCPサーバーインスタンス
-    pub fn new(config: Config) -> Self {
+    pub fn new(mut config: Config) -> Self {
+        // security.read-only-repoの場合、リポジトリ内のインデックスの保存先は作業ディレクトリ配下に置き換える
+        config.server.index_dir = config.output_path(&config.server.index_dir);
+        let tools = Tools::system();
         Self {
             config: config.clone(),
             indexes: Arc::new(RwLock::new(HashMap::new())),
-            analyzer: Arc::new(Analyzer::new(config.clone())),
-            summarizer: Arc::new(Summarizer::new(config.clone())),
-            diagrammer: Arc::new(Diagrammer::new(config.clone())),
+            allowed_roots: validation::canonical_roots(&config.server.allowed_roots),
+            metrics: Arc::new(Metrics::new(tools.clone())),
+            session: RwLock::new(SessionOptions::default()),
+            executor: Executor::new(config.summarization.workers),
+            tools,
         }
     }
 
@@ -70,44 +117,63 @@ impl McpServer {
     pub async fn serve(&self) -> anyhow::Result<()> {
         info!("DeepRepoSlides MCPサーバーを起動しました");
 
-        let stdin = io::stdin();
-        let mut stdin_reader = BufReader::new(stdin).lines();
-        let mut stdout = io::stdout();
+        if let Some(addr) = self.config.server.metrics_addr.clone() {
+            let metrics = self.metrics.clone();
+            let indexes = self.indexes.clone();
+            tokio::spawn(async move {
+                if let Err(e) = metrics::serve_metrics(&addr, metrics, indexes).await {
+                    error!("メトリクスサーバーエラー: {}", e);
+                }
+            });
+        }
+
+        let framing = Framing::parse(&self.config.server.framing)?;
+        let (tx, writer) = transport::spawn_writer(io::stdout(), framing);
+        let mut stdin_reader = BufReader::new(io::stdin());
+        let max_bytes = self.config.server.max_request_bytes;
 
         loop {
-            tokio::select! {
-                result = stdin_reader.next_line() => {
-                    match result {
-                        Ok(Some(line)) => {
-                            // 空行をスキップ
-                            let trimmed = line.trim();
-                            if trimmed.is_empty() {
-                                continue;
-                            }
-
-                            if let Err(e) = self.handle_request(trimmed, &mut stdout).await {
-                                error!("リクエスト処理エラー: {}", e);
-                                let error_response = self.create_error_response(
-                                    None,
-                                    -32603,
-                                    &format!("内部エラー: {}", e),
-                                );
-                                self.write_response(&mut stdout, &error_response).await?;
-                            }
-                        }
-                        Ok(None) => {
-                            debug!("標準入力が閉じられました");
-                            break;
-                        }
-                        Err(e) => {
-                            error!("標準入力読み込みエラー: {}", e);
-                            break;
-                        }
+            match transport::read_message(&mut stdin_reader, framing, max_bytes).await {
+                Ok(Some(Incoming::TooLarge)) => {
+                    error!("リクエストが上限サイズを超えたため読み飛ばしました");
+                    let error_response = self.create_error_response(
+                        None,
+                        validation::REQUEST_TOO_LARGE,
+                        &format!("リクエストが大きすぎます（上限: {} バイト）", max_bytes),
+                    );
+                    self.write_response(&tx, &error_response).await?;
+                }
+                Ok(Some(Incoming::Message(message))) => {
+                    // 空行をスキップ
+                    let trimmed = message.trim();
+                    if trimmed.is_empty() {
+                        continue;
                     }
+
+                    if let Err(e) = self.handle_request(trimmed, &tx).await {
+                        error!("リクエスト処理エラー: {}", e);
+                        let error_response = self.create_error_response(
+                            None,
+                            validation::INTERNAL_ERROR,
+                            &format!("内部エラー: {}", e),
+                        );
+                        self.write_response(&tx, &error_response).await?;
+                    }
+                }
+                Ok(None) => {
+                    debug!("標準入力が閉じられました");
+                    break;
+                }
+                Err(e) => {
+                    error!("標準入力読み込みエラー: {}", e);
+                    break;
                 }
             }
         }
 
+        // 送信側を閉じ、書き込み待ちのレスポンスを出し切るまで待つ
+        drop(tx);
+        writer.await??;
         Ok(())
     }
 
@@ -115,14 +181,14 @@ impl McpServer {
     /// 
     /// # 引数
     /// * `line` - JSON-RPCリクエスト文字列
-    /// * `stdout` - 標準出力ライター
+    /// * `tx` - 書き込みタスクへの送信側
     /// 
     /// # 戻り値
     /// * `anyhow::Result<()>` - 処理成功、またはエラー
     async fn handle_request(
         &self,
         line: &str,
-        stdout: &mut io::Stdout,
+        tx: &mpsc::Sender<String>,
     ) -> anyhow::Result<()> {
         // 空行は無視（既に呼び出し側でチェック済みだが、念のため）
         if line.trim().is_empty() {
@@ -131,16 +197,37 @@ impl McpServer {
 
         debug!("リクエスト受信: {}", line);
 
-        let request: JsonRpcRequest = match serde_json::from_str(line) {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                debug!("JSON-RPCパースエラー: {}", e);
+                let response = self.create_error_response(
+                    None,
+                    validation::PARSE_ERROR,
+                    &format!("JSONの解析に失敗しました: {}", e),
+                );
+                return self.write_response(tx, &response).await;
+            }
+        };
+        let request: JsonRpcRequest = match serde_json::from_value(value) {
             Ok(req) => req,
             Err(e) => {
-                // パースエラーは警告のみ（無効なリクエストは無視）
-                debug!("JSON-RPCパースエラー（無視）: {}", e);
-                // エラーレスポンスを返さない（リクエストIDがない場合）
-                return Ok(());
+                debug!("不正なJSON-RPCリクエスト: {}", e);
+                let response = self.create_error_response(
+                    None,
+                    validation::INVALID_REQUEST,
+                    &format!("不正なリクエストです: {}", e),
+                );
+                return self.write_response(tx, &response).await;
             }
         };
 
+        // MCPの通知（notifications/initializedなど）にはレスポンスを返さない
+        if request.id.is_none() && request.method.starts_with("notifications/") {
+            debug!("通知を受信: {}", request.method);
+            return Ok(());
+        }
+
         let response = match self.dispatch_tool(&request.method, request.params).await {
             Ok(result) => JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
@@ -152,57 +239,162 @@ impl McpServer {
                 error!("ツール実行エラー: {}", e);
                 self.create_error_response(
                     request.id,
-                    -32603,
+                    validation::error_code(&e),
                     &format!("ツール実行エラー: {}", e),
                 )
             }
         };
 
-        self.write_response(stdout, &response).await?;
+        self.write_response(tx, &response).await?;
         Ok(())
     }
 
     /// ツールをディスパッチ
     /// 
+    /// MCPのライフサイクルメソッドを処理し、それ以外はツール名として直接呼び出す。
+    /// 
     /// # 引数
-    /// * `method` - ツール名
+    /// * `method` - メソッド名（initialize|tools/list|tools/call|ping、またはツール名）
     /// * `params` - パラメータ
     /// 
     /// # 戻り値
     /// * `anyhow::Result<Value>` - 結果、またはエラー
     async fn dispatch_tool(&self, method: &str, params: Value) -> anyhow::Result<Value> {
         match method {
+            "initialize" => {
+                // 新しいセッションの開始として、前のクライアントのオプションを破棄する
+                *self.session.write().await = SessionOptions::default();
+                // 対応しているバージョンならそのまま受け入れ、それ以外はサーバーのバージョンを返す
+                let protocol_version = params
+                    .get("protocolVersion")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+                    .unwrap_or(PROTOCOL_VERSION);
+                Ok(json!({
+                    "protocolVersion": protocol_version,
+                    "capabilities": { "tools": {} },
+                    "serverInfo": {
+                        "name": "deeprepo-slides",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    },
+                }))
+            }
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => {
+                let call: ToolCallParams = parse_args(params)?;
+                // ツールの実行エラーはJSON-RPCエラーではなく、isErrorで返す（MCPの仕様）
+                let (text, is_error) = match self.call_tool(&call.name, call.arguments).await {
+                    Ok(result) => (serde_json::to_string_pretty(&result)?, false),
+                    Err(e) => {
+                        error!("ツール実行エラー: {}", e);
+                        (format!("ツール実行エラー: {}", e), true)
+                    }
+                };
+                Ok(json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": is_error,
+                }))
+            }
+            _ => self.call_tool(method, params).await,
+        }
+    }
+
+    /// ツールを呼び出し、メトリクスを記録
+    /// 
+    /// # 引数
+    /// * `name` - ツール名
+    /// * `params` - 引数
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<Value>` - 結果、またはエラー
+    async fn call_tool(&self, name: &str, params: Value) -> anyhow::Result<Value> {
+        let started = std::time::Instant::now();
+        let result = self.run_tool(name, params).await;
+
+        // 不明なツール名はラベルの種類が増えないようにまとめる
+        let label = match &result {
+            Err(e) if validation::error_code(e) == validation::METHOD_NOT_FOUND => "unknown",
+            _ => name,
+        };
+        self.metrics.record_tool(label, started.elapsed(), result.as_ref().err());
+        result
+    }
+
+    /// ツールを実行
+    async fn run_tool(&self, name: &str, params: Value) -> anyhow::Result<Value> {
+        match name {
             "index_repo" => {
-                let args: IndexRepoArgs = serde_json::from_value(params)?;
+                let args: IndexRepoArgs = parse_args(params)?;
                 let result = self.index_repo(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
             "summarize" => {
-                let args: SummarizeArgs = serde_json::from_value(params)?;
+                let args: SummarizeArgs = parse_args(params)?;
                 let result = self.summarize(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
             "generate_wiki" => {
-                let args: GenerateWikiArgs = serde_json::from_value(params)?;
+                let args: GenerateWikiArgs = parse_args(params)?;
                 let result = self.generate_wiki(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
+            "generate_page" => {
+                let args: GeneratePageArgs = parse_args(params)?;
+                let result = self.generate_page(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
             "generate_slides" => {
-                let args: GenerateSlidesArgs = serde_json::from_value(params)?;
+                let args: GenerateSlidesArgs = parse_args(params)?;
                 let result = self.generate_slides(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
             "publish_pages" => {
-                let args: PublishPagesArgs = serde_json::from_value(params)?;
+                let args: PublishPagesArgs = parse_args(params)?;
                 let result = self.publish_pages(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
             "search" => {
-                let args: SearchArgs = serde_json::from_value(params)?;
+                let args: SearchArgs = parse_args(params)?;
                 let result = self.search(args).await?;
                 Ok(serde_json::to_value(result)?)
             }
-            _ => Err(anyhow::anyhow!("不明なツール: {}", method)),
+            "semantic_search" => {
+                let args: SemanticSearchArgs = parse_args(params)?;
+                let result = self.semantic_search(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "set_options" => {
+                let args: SetOptionsArgs = parse_args(params)?;
+                let result = self.set_options(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "explain_symbol" => {
+                let args: ExplainSymbolArgs = parse_args(params)?;
+                let result = self.explain_symbol(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "ask" => {
+                let args: AskArgs = parse_args(params)?;
+                let result = self.ask(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "diff" => {
+                let args: DiffArgs = parse_args(params)?;
+                let result = self.diff(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "export_graph" => {
+                let args: ExportGraphArgs = parse_args(params)?;
+                let result = self.export_graph(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            "get_artifact" => {
+                let args: GetArtifactArgs = parse_args(params)?;
+                let result = self.get_artifact(args).await?;
+                Ok(serde_json::to_value(result)?)
+            }
+            _ => Err(RpcError::method_not_found(name).into()),
         }
     }
 
@@ -216,17 +408,50 @@ impl McpServer {
     async fn index_repo(&self, args: IndexRepoArgs) -> anyhow::Result<IndexRepoResult> {
         info!("リポジトリをインデックス化中: {:?}", args.repo_path);
 
+        let repo_path = validation::resolve_allowed_path(&args.repo_path, &self.allowed_roots)?;
         let config = if let Some(config_path) = args.config {
-            Config::load(Some(config_path))?
+            Config::load(Some(validation::resolve_allowed_path(&config_path, &self.allowed_roots)?))?
         } else {
-            self.config.clone()
+            self.session_config().await
         };
 
-        let index = self.analyzer.analyze_repo(&args.repo_path, &config).await?;
-        let index_id = format!("idx_{}", Utc::now().format("%Y%m%d_%H%M%S"));
+        // 警告を要求ごとに返すため、アナライザーは要求ごとに作成する
+        let index = Analyzer::new(config.clone()).analyze_repo(&repo_path, &config).await?;
+        let index_id = format!("idx_{}", Utc::now().format("%Y%m%d_%H%M%S_%3f"));
+
+        // 再起動後もindex_id・repo_pathから読み込めるよう保存する（失敗してもメモリ上のインデックスは使える）
+        // 保存できた場合は保存した版から内容を読む（解析後に作業ツリーが変わっても版の内容は変わらない）
+        let content = config.index.content.as_str();
+        // read-only-repoの場合、解析したリポジトリ内には保存しない
+        let saved = self
+            .config
+            .security
+            .ensure_writable(&index.repo_path, &self.config.server.index_dir, "インデックスの保存")
+            .and_then(|()| {
+                persist::save_index(&self.config.server.index_dir, &index_id, &index, self.config.server.index_history, content)
+            });
+        let (index, expired) = match saved {
+            // excerpts・hashでは版に全文がなく検索インデックスも保存しないため、解析直後のインデックスを使う
+            Ok(expired) if content != "full" => (index, expired),
+            Ok(expired) => {
+                let saved = persist::index_file(&self.config.server.index_dir, &index_id)
+                    .ok()
+                    .and_then(|path| Index::load(&path).ok())
+                    .unwrap_or(index);
+                (saved, expired)
+            }
+            Err(e) => {
+                warn!("インデックスの保存に失敗しました: {}", e);
+                (index, Vec::new())
+            }
+        };
 
         {
+            // 保持数を超えて削除した古い版はメモリからも破棄する
             let mut indexes = self.indexes.write().await;
+            for id in &expired {
+                indexes.remove(id);
+            }
             indexes.insert(index_id.clone(), index.clone());
         }
 
@@ -234,12 +459,14 @@ impl McpServer {
             files: index.files.len(),
             languages: index.languages.clone(),
             modules: index.modules.len(),
+            skipped: index.stats.skipped.clone(),
         };
 
         Ok(IndexRepoResult {
             ok: true,
             index_id,
             stats,
+            diagnostics: index.diagnostics.clone(),
         })
     }
 
@@ -253,15 +480,20 @@ impl McpServer {
     async fn summarize(&self, args: SummarizeArgs) -> anyhow::Result<SummarizeResult> {
         info!("要約生成中: scope={}, target={}", args.scope, args.target);
 
+        let mut config = self.session_config().await;
+        if let Some(style) = args.style {
+            config.summarization.style = style;
+        }
+        let config = with_lang(config, args.lang.as_deref())?;
+        let index_id = self.resolve_as_of(args.as_of.as_deref(), args.index_id, args.repo_path.as_deref())?;
+        let index_id = self.ensure_index(index_id.as_deref(), args.repo_path.as_deref()).await?;
         let indexes = self.indexes.read().await;
-        let index = indexes
-            .values()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("インデックスが見つかりません"))?;
+        let index = self.find_index(&indexes, index_id.as_deref())?;
 
-        let result = self
-            .summarizer
-            .summarize(index, &args.scope, &args.target, &args.style)
+        let style = config.summary_style();
+        // 警告を要求ごとに返すため、サマライザーは要求ごとに作成する
+        let result = Summarizer::new(config)
+            .summarize(index, &args.scope, &args.target, &style)
             .await?;
 
         Ok(result)
@@ -275,18 +507,27 @@ impl McpServer {
     /// # 戻り値
     /// * `anyhow::Result<WikiResult>` - 結果、またはエラー
     async fn generate_wiki(&self, args: GenerateWikiArgs) -> anyhow::Result<WikiResult> {
-        info!("Wiki生成中: index_id={}", args.index_id);
+        info!(
+            "Wiki生成中: index_id={:?}, repo_path={:?}, as_of={:?}, path={:?}",
+            args.index_id, args.repo_path, args.as_of, args.path
+        );
 
+        let config = with_lang(self.session_config().await, args.lang.as_deref())?;
+        // 省略時は設定の出力先（read-only-repoの場合の置き換えは生成側で行う）
+        let out_dir = match &args.out_dir {
+            Some(out_dir) => self.allowed_output_path(out_dir)?.to_string_lossy().into_owned(),
+            None => config.site.out_dir.to_string_lossy().into_owned(),
+        };
+        let index_id = self.resolve_as_of(args.as_of.as_deref(), args.index_id, args.repo_path.as_deref())?;
+        let index_id = self.ensure_index(index_id.as_deref(), args.repo_path.as_deref()).await?;
         let indexes = self.indexes.read().await;
-        let index = indexes
-            .get(&args.index_id)
-            .ok_or_else(|| anyhow::anyhow!("インデックスが見つかりません: {}", args.index_id))?;
+        let index = scoped_index(self.find_index(&indexes, index_id.as_deref())?, args.path.as_deref())?;
 
-        let builder = MdBookBuilder::new(self.config.clone());
+        let builder = MdBookBuilder::new(config).with_executor(self.executor.clone()).with_tools(self.tools.clone()).with_resume(args.resume);
         let result = builder
             .build_wiki(
-                index,
-                &args.out_dir.unwrap_or_else(|| "./out/wiki".into()),
+                &index,
+                &out_dir,
                 args.with_diagrams,
                 &args.toc,
             )
@@ -295,6 +536,36 @@ impl McpServer {
         Ok(result)
     }
 
+    /// Wikiの1ページを生成（ディスクには書き込まない）
+    /// 
+    /// # 引数
+    /// * `args` - ページ生成パラメータ
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<GeneratePageResult>` - 結果、またはエラー
+    async fn generate_page(&self, args: GeneratePageArgs) -> anyhow::Result<GeneratePageResult> {
+        let page = match (args.section, args.module) {
+            (Some(section), None) => section,
+            (None, Some(module)) => module,
+            _ => return Err(anyhow::anyhow!("sectionとmoduleのどちらか一方を指定してください")),
+        };
+        info!("ページ生成中: page={}", page);
+
+        let config = with_lang(self.session_config().await, args.lang.as_deref())?;
+        let index_id = self.ensure_index(args.index_id.as_deref(), None).await?;
+        let indexes = self.indexes.read().await;
+        let index = self.find_index(&indexes, index_id.as_deref())?;
+
+        let builder = MdBookBuilder::new(config).with_executor(self.executor.clone()).with_tools(self.tools.clone());
+        let content_md = builder.render_page(index, &page, args.with_diagrams).await?;
+
+        Ok(GeneratePageResult {
+            ok: true,
+            page,
+            content_md,
+        })
+    }
+
     /// スライドを生成
     /// 
     /// # 引数
@@ -303,27 +574,57 @@ impl McpServer {
     /// # 戻り値
     /// * `anyhow::Result<SlideResult>` - 結果、またはエラー
     async fn generate_slides(&self, args: GenerateSlidesArgs) -> anyhow::Result<SlideResult> {
-        info!("スライド生成中: index_id={}", args.index_id);
+        info!("スライド生成中: index_id={:?}, repo_path={:?}, path={:?}", args.index_id, args.repo_path, args.path);
 
+        let config = with_lang(self.session_config().await, args.lang.as_deref())?;
+        // 省略時は設定の出力先（read-only-repoの場合の置き換えは生成側で行う）
+        let out_dir = match &args.out_dir {
+            Some(out_dir) => self.allowed_output_path(out_dir)?.to_string_lossy().into_owned(),
+            None => config.slides.out_dir.to_string_lossy().into_owned(),
+        };
+        let index_id = self.ensure_index(args.index_id.as_deref(), args.repo_path.as_deref()).await?;
         let indexes = self.indexes.read().await;
-        let index = indexes
-            .get(&args.index_id)
-            .ok_or_else(|| anyhow::anyhow!("インデックスが見つかりません: {}", args.index_id))?;
+        let index = scoped_index(self.find_index(&indexes, index_id.as_deref())?, args.path.as_deref())?;
 
-        let builder = SlideBuilder::new(self.config.clone());
+        let language = args.language.unwrap_or_else(|| config.slides_language().to_string());
+        let builder = SlideBuilder::new(config).with_executor(self.executor.clone()).with_tools(self.tools.clone());
         let result = builder
             .build_slides(
-                index,
+                &index,
                 &args.flavor,
-                &args.out_dir.unwrap_or_else(|| "./out/slides".into()),
+                &out_dir,
                 &args.sections,
                 &args.export,
+                &language,
             )
             .await?;
 
         Ok(result)
     }
 
+    /// モジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONのファイルに書き出す
+    /// 
+    /// # 引数
+    /// * `args` - エクスポートパラメータ
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<ExportGraphResult>` - 書き出したファイル、またはエラー
+    async fn export_graph(&self, args: ExportGraphArgs) -> anyhow::Result<ExportGraphResult> {
+        info!("グラフのエクスポート中: index_id={:?}, repo_path={:?}, path={:?}", args.index_id, args.repo_path, args.path);
+
+        let config = self.session_config().await;
+        let out_dir = match &args.out_dir {
+            Some(out_dir) => self.allowed_output_path(out_dir)?,
+            None => config.output_path(diagrammer::DEFAULT_EXPORT_DIR),
+        };
+        let index_id = self.ensure_index(args.index_id.as_deref(), args.repo_path.as_deref()).await?;
+        let indexes = self.indexes.read().await;
+        let index = scoped_index(self.find_index(&indexes, index_id.as_deref())?, args.path.as_deref())?;
+
+        let files = Diagrammer::new(config).write_graph_exports(&index, &out_dir)?;
+        Ok(ExportGraphResult { ok: true, files })
+    }
+
     /// GitHub Pagesに公開
     /// 
     /// # 引数
@@ -334,17 +635,33 @@ impl McpServer {
     async fn publish_pages(&self, args: PublishPagesArgs) -> anyhow::Result<PublishResult> {
         info!("GitHub Pages公開中: mode={}", args.mode);
 
+        // 公開先のリポジトリと公開するディレクトリは許可ディレクトリ配下に限る
+        let repo_root = validation::resolve_allowed_path(&args.repo_root, &self.allowed_roots)?;
+        let site_dir = self.allowed_output_path(&args.site_dir)?;
+        let slides_dir = self.allowed_output_path(&args.slides_dir)?;
+
         let publisher = Publisher::new(self.config.clone());
         let result = publisher
             .publish(
                 &args.mode,
-                &args.site_dir,
-                &args.slides_dir,
-                &args.repo_root,
+                &site_dir.to_string_lossy(),
+                &slides_dir.to_string_lossy(),
+                &repo_root.to_string_lossy(),
                 &args.branch,
             )
             .await?;
 
+        Notifier::new(self.config.clone())
+            .notify(&BuildSummary {
+                event: "publish".to_string(),
+                project: self.config.project.name.clone(),
+                ok: result.ok,
+                url: result.url.clone(),
+                message: result.hint.clone(),
+                ..Default::default()
+            })
+            .await;
+
         Ok(result)
     }
 
@@ -356,19 +673,381 @@ impl McpServer {
     /// # 戻り値
     /// * `anyhow::Result<SearchResult>` - 結果、またはエラー
     async fn search(&self, args: SearchArgs) -> anyhow::Result<SearchResult> {
-        info!("検索実行中: q={}", args.q);
+        info!("検索実行中: q={}, in={}, kind={}", args.q, args.search_in, args.kind);
 
-        let indexes = self.indexes.read().await;
-        let index = indexes
-            .values()
-            .next()
-            .ok_or_else(|| anyhow::anyhow!("インデックスが見つかりません"))?;
+        if !["source", "docs", "all"].contains(&args.search_in.as_str()) {
+            return Err(RpcError::invalid_params(format!("不明な検索対象: {}（source|docs|all）", args.search_in)).into());
+        }
+        let mut hits = Vec::new();
+        if args.kind != "text" && args.search_in == "docs" {
+            return Err(anyhow::anyhow!("kind={}はソースの検索（in: source|all）のみ対応しています", args.kind));
+        }
+        let filter = &args.filter;
+        if (filter.language.is_some() || filter.path_glob.is_some() || filter.module.is_some()) && args.search_in != "source" {
+            return Err(anyhow::anyhow!("language・path_glob・moduleの絞り込みはソースの検索（in: source）のみ対応しています"));
+        }
+
+        // ソースのみの場合はページ送りも検索に任せ、ドキュメントと混ぜる場合は統合してから読み飛ばす
+        let merged = args.search_in != "source";
+        let (k, source_filter) = match merged {
+            true => (args.k + filter.offset, SearchFilter { offset: 0, ..filter.clone() }),
+            false => (args.k, filter.clone()),
+        };
+
+        if args.search_in == "source" || args.search_in == "all" {
+            let index_id = self.resolve_as_of(args.as_of.as_deref(), args.index_id, args.repo_path.as_deref())?;
+            let index_id = self.ensure_index(index_id.as_deref(), args.repo_path.as_deref()).await?;
+            let indexes = self.indexes.read().await;
+            let index = self.find_index(&indexes, index_id.as_deref())?;
+            hits.extend(index.search(&args.q, k, &args.kind, &source_filter).await?);
+        }
+
+        // シンボル名・パスの検索は生成済みドキュメントには適用しない
+        if (args.search_in == "docs" || args.search_in == "all") && args.kind == "text" {
+            let doc_dirs: Vec<std::path::PathBuf> = if args.docs_dirs.is_empty() {
+                vec![self.config.site.out_dir.clone(), self.config.slides.out_dir.clone()]
+            } else {
+                args.docs_dirs.iter().map(|dir| self.allowed_output_path(dir)).collect::<Result<_, _>>()?
+            };
+            hits.extend(search_docs(&doc_dirs, &self.config.site.layout.build_dir, &args.q, k).await?);
+        }
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        if merged {
+            hits.drain(..filter.offset.min(hits.len()));
+        }
+        hits.truncate(args.k);
+
+        Ok(SearchResult { ok: true, hits })
+    }
+
+    /// 意味検索を実行（埋め込みベクトルで自然文のクエリに近いコードのチャンクを返す）
+    /// 
+    /// # 引数
+    /// * `args` - 意味検索パラメータ
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<SearchResult>` - 結果、またはエラー
+    async fn semantic_search(&self, args: SemanticSearchArgs) -> anyhow::Result<SearchResult> {
+        info!("意味検索実行中: q={}", args.q);
 
-        let hits = index.search(&args.q, args.k).await?;
+        let index_id = self.resolve_as_of(args.as_of.as_deref(), args.index_id, args.repo_path.as_deref())?;
+        let index_id = self.ensure_index(index_id.as_deref(), args.repo_path.as_deref()).await?;
+        let indexes = self.indexes.read().await;
+        let index = self.find_index(&indexes, index_id.as_deref())?;
+        let hits = index.semantic_search(&args.q, args.k).await?;
 
         Ok(SearchResult { ok: true, hits })
     }
 
+    /// シンボルを解説（定義・シグネチャ・ドキュメント・参照・コールグラフ）
+    /// 
+    /// # 引数
+    /// * `args` - シンボル解説パラメータ
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<ExplainSymbolResult>` - 結果、またはエラー
+    async fn explain_symbol(&self, args: ExplainSymbolArgs) -> anyhow::Result<ExplainSymbolResult> {
+        info!("シンボル解説中: index_id={}, name={}", args.index_id, args.name);
+
+        self.ensure_index(Some(&args.index_id), None).await?;
+        let indexes = self.indexes.read().await;
+        let index = self.find_index(&indexes, Some(&args.index_id))?;
+
+        let symbol = index
+            .explain_symbol(&args.name)
+            .ok_or_else(|| anyhow::anyhow!("シンボルが見つかりません: {}", args.name))?;
+
+        Ok(ExplainSymbolResult { ok: true, symbol })
+    }
+
+    /// インデックスに対する質問に、関連するコードの抜粋をもとに引用付きで回答
+    /// 
+    /// # 引数
+    /// * `args` - 質問パラメータ
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<AskResult>` - 回答（根拠の引用と警告を含む）、またはエラー
+    async fn ask(&self, args: AskArgs) -> anyhow::Result<AskResult> {
+        info!("質問に回答中: question={}", args.question);
+        if args.question.trim().is_empty() {
+            return Err(RpcError::invalid_params("questionが空です").into());
+        }
+
+        let index_id = self.resolve_as_of(args.as_of.as_deref(), args.index_id, args.repo_path.as_deref())?;
+        let index_id = self.ensure_index(index_id.as_deref(), args.repo_path.as_deref()).await?;
+        let indexes = self.indexes.read().await;
+        let index = self.find_index(&indexes, index_id.as_deref())?;
+
+        // 警告を要求ごとに返すため、サマライザーは要求ごとに作成する
+        Summarizer::new(self.session_config().await).ask(index, &args.question).await
+    }
+
+    /// 2つの版の差分を変更履歴にする
+    /// 
+    /// # 引数
+    /// * `args` - 以前の版・新しい版（省略時はrepo_pathの最新のインデックス）
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<DiffResult>` - 変更履歴のMarkdownと差分、またはエラー
+    async fn diff(&self, args: DiffArgs) -> anyhow::Result<DiffResult> {
+        info!("版の差分を要約中: old={}, new={:?}", args.old, args.new);
+        let old_id = self.resolve_version(&args.old, args.repo_path.as_deref()).await?;
+        let new_id = match args.new.as_deref() {
+            Some(new) => Some(self.resolve_version(new, args.repo_path.as_deref()).await?),
+            None => self.ensure_index(None, args.repo_path.as_deref()).await?,
+        };
+        let indexes = self.indexes.read().await;
+        let old = self.find_index(&indexes, Some(&old_id))?;
+        let new = self.find_index(&indexes, new_id.as_deref())?;
+
+        // 警告を要求ごとに返すため、サマライザーは要求ごとに作成する
+        Ok(Summarizer::new(self.session_config().await).summarize_diff(old, new))
+    }
+
+    /// index_id（メモリ上・保存済み）またはコミットSHAの版をメモリに用意する
+    async fn resolve_version(&self, version: &str, repo_path: Option<&str>) -> anyhow::Result<String> {
+        let id = match self.indexes.read().await.contains_key(version) {
+            true => version.to_string(),
+            false => self.resolve_as_of(Some(version), None, repo_path)?.unwrap_or_default(),
+        };
+        self.ensure_index(Some(&id), None).await?;
+        Ok(id)
+    }
+
+    /// セッションのオプションを設定
+    /// 
+    /// # 引数
+    /// * `args` - オプション（未指定の項目は現在の値を維持）
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<SetOptionsResult>` - 反映後のオプション、またはエラー
+    async fn set_options(&self, args: SetOptionsArgs) -> anyhow::Result<SetOptionsResult> {
+        let mut session = self.session.write().await;
+        if args.reset {
+            *session = SessionOptions::default();
+        }
+        session.merge(args.options)?;
+        info!("セッションのオプションを設定しました: {:?}", *session);
+
+        let config = session.apply(&self.config);
+        Ok(SetOptionsResult {
+            ok: true,
+            style: config.summary_style(),
+            diagram_renderer: config.analysis.diagrams.renderer,
+        })
+    }
+
+    /// 生成した成果物を取得
+    ///
+    /// # 引数
+    /// * `args` - 取得パラメータ（idを省略した場合は成果物の一覧）
+    ///
+    /// # 戻り値
+    /// * `anyhow::Result<GetArtifactResult>` - 結果、またはエラー
+    async fn get_artifact(&self, args: GetArtifactArgs) -> anyhow::Result<GetArtifactResult> {
+        info!("成果物取得中: kind={}, id={:?}, encoding={}", args.kind, args.id, args.encoding);
+
+        if !artifacts::ARTIFACT_KINDS.contains(&args.kind.as_str()) {
+            return Err(RpcError::invalid_params(format!(
+                "kindは {} のいずれかである必要があります: {}",
+                artifacts::ARTIFACT_KINDS.join(", "),
+                args.kind
+            ))
+            .into());
+        }
+        if !artifacts::ARTIFACT_ENCODINGS.contains(&args.encoding.as_str()) {
+            return Err(RpcError::invalid_params(format!(
+                "encodingは {} のいずれかである必要があります: {}",
+                artifacts::ARTIFACT_ENCODINGS.join(", "),
+                args.encoding
+            ))
+            .into());
+        }
+
+        // クライアントが指定した出力先は許可ディレクトリ（read-only-repoの場合は作業ディレクトリも）配下に限る
+        let roots = match &args.out_dir {
+            Some(out_dir) => vec![self.allowed_output_path(out_dir)?],
+            // 省略時は設定の出力先（read-only-repoの場合は置き換えた作業ディレクトリ配下）
+            None => {
+                let config = self.session_config().await;
+                let wiki = config.output_path(&config.site.out_dir);
+                let slides = config.output_path(&config.slides.out_dir);
+                match args.kind.as_str() {
+                    "slides" => vec![slides],
+                    "wiki" => vec![wiki],
+                    _ => vec![wiki, slides],
+                }
+            }
+        };
+
+        let Some(id) = args.id else {
+            return Ok(GetArtifactResult {
+                ok: true,
+                kind: args.kind.clone(),
+                artifacts: artifacts::list(&args.kind, &roots),
+                ..Default::default()
+            });
+        };
+
+        let path = artifacts::resolve(&args.kind, &id, &roots)?;
+        let size = std::fs::metadata(&path)?.len();
+        let mut result = GetArtifactResult {
+            ok: true,
+            kind: args.kind,
+            uri: Some(artifacts::file_uri(&path)),
+            mime_type: Some(artifacts::mime_type(&path).to_string()),
+            size: Some(size),
+            id: Some(id),
+            ..Default::default()
+        };
+        if args.encoding == "base64" {
+            let length = args.length.clamp(1, artifacts::MAX_CHUNK_BYTES);
+            let data = artifacts::read_chunk(&path, args.offset, length)?;
+            let end = args.offset + data.len() as u64;
+            result.offset = Some(args.offset);
+            result.next_offset = (end < size).then_some(end);
+            result.data = Some(base64::engine::general_purpose::STANDARD.encode(&data));
+        }
+        Ok(result)
+    }
+
+    /// クライアントが指定した出力先・生成済みドキュメントのディレクトリを検証
+    /// 
+    /// 許可ディレクトリ（read-only-repoの場合は作業ディレクトリも）配下に限る。まだ存在しない出力先も指定できる。
+    /// 
+    /// # 引数
+    /// * `path` - クライアントが指定したパス
+    /// 
+    /// # 戻り値
+    /// * `Result<PathBuf, RpcError>` - 正規化したパス、またはパラメータ不正のエラー
+    fn allowed_output_path(&self, path: &str) -> Result<PathBuf, RpcError> {
+        let mut allowed = self.allowed_roots.clone();
+        if self.config.security.read_only_repo {
+            allowed.extend(validation::canonical_roots(&[self.config.security.workspace_dir()]));
+        }
+        validation::resolve_allowed_output_path(path, &allowed)
+    }
+
+    /// セッションのオプションを反映した設定
+    async fn session_config(&self) -> Config {
+        self.session.read().await.apply(&self.config)
+    }
+
+    /// `as_of`（index_idまたはコミットSHA）を保存済みの版のindex_idに解決
+    /// 
+    /// # 引数
+    /// * `as_of` - index_idまたはコミットSHA（省略時は `index_id` をそのまま返す）
+    /// * `index_id` - 明示されたインデックスID
+    /// * `repo_path` - 探す対象のリポジトリ（省略時はすべてのリポジトリ）
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<Option<String>>` - インデックスID、またはエラー
+    fn resolve_as_of(&self, as_of: Option<&str>, index_id: Option<String>, repo_path: Option<&str>) -> anyhow::Result<Option<String>> {
+        let Some(as_of) = as_of else {
+            return Ok(index_id);
+        };
+        let repo_path = repo_path
+            .map(|p| validation::resolve_allowed_path(p, &self.allowed_roots))
+            .transpose()?;
+        let found = persist::find_version(&self.config.server.index_dir, repo_path.as_deref(), as_of)
+            .ok_or_else(|| RpcError::invalid_params(format!("as_ofに一致するインデックスの版が見つかりません: {}", as_of)))?;
+        Ok(Some(found))
+    }
+
+    /// インデックスをメモリに用意する
+    /// 
+    /// index_idを指定した場合は、メモリになければ保存済みのインデックスを読み込む。
+    /// repo_pathを指定した場合は、メモリ上・保存済みのインデックスを探し、なければ再インデックス化する。
+    /// メモリ上にあったか（読み込み・再インデックス化が必要だったか）をメトリクスに記録する。
+    /// 
+    /// # 引数
+    /// * `index_id` - インデックスID
+    /// * `repo_path` - リポジトリのパス（index_idを指定しない場合のみ使用）
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<Option<String>>` - 用意したインデックスのID（どちらも指定しない場合はNone）、またはエラー
+    async fn ensure_index(&self, index_id: Option<&str>, repo_path: Option<&str>) -> anyhow::Result<Option<String>> {
+        let index_dir = &self.config.server.index_dir;
+
+        if let Some(id) = index_id {
+            let cached = self.indexes.read().await.contains_key(id);
+            self.metrics.record_index_lookup(cached);
+            if !cached {
+                let path = persist::index_file(index_dir, id)?;
+                if path.exists() {
+                    self.load_persisted_index(id, &path).await?;
+                }
+            }
+            return Ok(Some(id.to_string()));
+        }
+
+        let Some(repo_path) = repo_path else {
+            return Ok(None);
+        };
+        let repo_path = validation::resolve_allowed_path(repo_path, &self.allowed_roots)?;
+
+        // メモリ上の最新のインデックス（index_idは作成日時順に並ぶ）
+        let in_memory = self
+            .indexes
+            .read()
+            .await
+            .iter()
+            .filter(|(_, index)| index.repo_path == repo_path)
+            .map(|(id, _)| id.clone())
+            .max();
+        self.metrics.record_index_lookup(in_memory.is_some());
+        if let Some(id) = in_memory {
+            return Ok(Some(id));
+        }
+
+        if let Some(id) = persist::lookup_repo(index_dir, &repo_path) {
+            let path = persist::index_file(index_dir, &id)?;
+            if path.exists() {
+                self.load_persisted_index(&id, &path).await?;
+                return Ok(Some(id));
+            }
+        }
+
+        info!("インデックスが見つからないため再インデックス化します: {:?}", repo_path);
+        let result = self
+            .index_repo(IndexRepoArgs {
+                repo_path: repo_path.to_string_lossy().into_owned(),
+                config: None,
+                refresh: false,
+            })
+            .await?;
+        Ok(Some(result.index_id))
+    }
+
+    /// 保存済みのインデックスを読み込んでメモリに追加
+    async fn load_persisted_index(&self, index_id: &str, path: &std::path::Path) -> anyhow::Result<()> {
+        info!("保存済みのインデックスを読み込みます: {:?}", path);
+        let index = Index::load(path)?;
+        self.indexes.write().await.insert(index_id.to_string(), index);
+        Ok(())
+    }
+
+    /// 保持中のインデックスを取得し、参照結果をメトリクスに記録
+    /// 
+    /// # 引数
+    /// * `indexes` - 保持中のインデックス
+    /// * `index_id` - インデックスID（省略時はindex_idが最も新しい、最後に作成した1件）
+    /// 
+    /// # 戻り値
+    /// * `anyhow::Result<&Index>` - インデックス、またはエラー
+    fn find_index<'a>(&self, indexes: &'a HashMap<String, Index>, index_id: Option<&str>) -> anyhow::Result<&'a Index> {
+        let index = match index_id {
+            Some(id) => indexes.get(id),
+            // index_idは作成日時順に並ぶ
+            None => indexes.iter().max_by(|(a, _), (b, _)| a.cmp(b)).map(|(_, index)| index),
+        };
+        index.ok_or_else(|| match index_id {
+            Some(id) => anyhow::anyhow!("インデックスが見つかりません: {}", id),
+            None => anyhow::anyhow!("インデックスが見つかりません"),
+        })
+    }
+
     /// エラーレスポンスを作成
     fn create_error_response(&self, id: Option<Value>, code: i32, message: &str) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -383,26 +1062,268 @@ impl McpServer {
         }
     }
 
-    /// レスポンスを書き込み
-    async fn write_response(&self, stdout: &mut io::Stdout, response: &JsonRpcResponse) -> anyhow::Result<()> {
+    /// レスポンスを書き込みタスクへ送信
+    async fn write_response(&self, tx: &mpsc::Sender<String>, response: &JsonRpcResponse) -> anyhow::Result<()> {
         let json = serde_json::to_string(response)?;
-        stdout.write_all(json.as_bytes()).await?;
-        stdout.write_all(b"\n").await?;
-        stdout.flush().await?;
+        tx.send(json)
+            .await
+            .map_err(|_| anyhow::anyhow!("書き込みタスクが終了しています"))?;
         Ok(())
     }
 }
 
+/// 引数をデシリアライズ（失敗はパラメータ不正のエラーにする）
+fn parse_args<T: serde::de::DeserializeOwned>(params: Value) -> anyhow::Result<T> {
+    serde_json::from_value(params).map_err(|e| RpcError::invalid_params(format!("引数が不正です: {}", e)).into())
+}
+
+/// 対応するMCPプロトコルのバージョン（クライアントが指定しない場合・未対応のバージョンを要求した場合）
+const PROTOCOL_VERSION: &str = "2024-11-05";
+/// クライアントの要求に応じて受け入れるMCPプロトコルのバージョン
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[PROTOCOL_VERSION, "2025-03-26"];
+
+/// ツール定義（tools/listで返す名前・説明・引数のJSON Schema）
+fn tool_definitions() -> Value {
+    let lang = json!({ "type": "string", "enum": LANGUAGES, "description": "この呼び出しに限り出力言語を上書き（set_optionsの値・project.languageより優先）" });
+    json!([
+        {
+            "name": "index_repo",
+            "description": "リポジトリを解析してインデックスを作成します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo_path": { "type": "string", "description": "リポジトリのパス" },
+                    "config": { "type": "string", "description": "設定ファイルのパス" },
+                    "refresh": { "type": "boolean", "description": "インデックスを再作成するか" }
+                },
+                "required": ["repo_path"]
+            }
+        },
+        {
+            "name": "summarize",
+            "description": "リポジトリ・パッケージ・モジュール・ファイルの日本語要約、またはREADME.mdの下書き（scope = readme）を生成します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string" },
+                    "repo_path": { "type": "string", "description": "index_idの代わりに指定（インデックスがなければ再インデックス化）" },
+                    "as_of": { "type": "string", "description": "過去の版（index_idまたはコミットSHA）" },
+                    "scope": { "type": "string", "enum": ["repo", "package", "module", "file", "readme"] },
+                    "target": { "type": "string", "description": "対象のパス（repo・readmeの場合は空文字列）" },
+                    "style": { "type": "string", "enum": ["concise-ja", "detailed-ja", "concise-en", "detailed-en"], "description": "省略時はset_optionsの値、またはsummarization.style" },
+                    "lang": lang
+                },
+                "required": ["scope", "target"]
+            }
+        },
+        {
+            "name": "set_options",
+            "description": "このセッションの以降の呼び出しで使うオプションを設定します（グローバル設定より優先）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "style": { "type": "string", "enum": ["concise-ja", "detailed-ja", "concise-en", "detailed-en", "concise", "detailed"] },
+                    "language": { "type": "string", "enum": ["ja", "en"], "description": "要約・Wiki・スライドの出力言語（project.languageを上書き）" },
+                    "diagram_renderer": { "type": "string", "enum": RENDERERS },
+                    "reset": { "type": "boolean", "default": false, "description": "既存のオプションを破棄してから設定" }
+                }
+            }
+        },
+        {
+            "name": "generate_wiki",
+            "description": "mdBookのWikiサイトを生成します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string" },
+                    "repo_path": { "type": "string", "description": "index_idの代わりに指定（インデックスがなければ再インデックス化）" },
+                    "as_of": { "type": "string", "description": "過去の版（index_idまたはコミットSHA）" },
+                    "path": { "type": "string", "description": "生成対象をこのディレクトリ配下に絞る（リポジトリルートからの相対パス、例: crates/summarizer）" },
+                    "out_dir": { "type": "string" },
+                    "with_diagrams": { "type": "boolean", "default": false },
+                    "toc": { "type": "array", "items": { "type": "string" } },
+                    "resume": { "type": "boolean", "default": false, "description": "中断したビルドを続きから再開（入力が前回と同じなら生成済みのページを再利用）" },
+                    "lang": lang
+                }
+            }
+        },
+        {
+            "name": "generate_page",
+            "description": "Wikiの1ページ（セクションまたはモジュール）をMarkdownで返します（ディスクには書き込みません）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string", "description": "省略時はメモリ上の最新のインデックス" },
+                    "section": {
+                        "type": "string",
+                        "enum": ["overview", "architecture", "packages", "metrics", "hotspots", "timeline", "tests", "unused", "debt", "api", "modules", "flows", "deploy", "config", "glossary", "faq", "licenses", "third-party"]
+                    },
+                    "module": { "type": "string", "description": "モジュールのパス" },
+                    "with_diagrams": { "type": "boolean", "default": true },
+                    "lang": lang
+                }
+            }
+        },
+        {
+            "name": "generate_slides",
+            "description": "スライドを生成します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string" },
+                    "repo_path": { "type": "string", "description": "index_idの代わりに指定（インデックスがなければ再インデックス化）" },
+                    "flavor": { "type": "string", "enum": ["mdbook-reveal", "marp"], "default": "mdbook-reveal" },
+                    "path": { "type": "string", "description": "生成対象をこのディレクトリ配下に絞る（リポジトリルートからの相対パス）" },
+                    "out_dir": { "type": "string" },
+                    "sections": { "type": "array", "items": { "type": "string" } },
+                    "export": { "type": "array", "items": { "type": "string", "enum": ["html", "pdf", "pptx"] } },
+                    "language": { "type": "string", "enum": ["ja", "en"], "description": "スライドの言語（省略時は lang・slides.language、なければ project.language）" },
+                    "lang": lang
+                }
+            }
+        },
+        {
+            "name": "export_graph",
+            "description": "モジュールグラフ・コールグラフをGraphMLとCytoscape.js JSONのファイルに書き出します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string" },
+                    "repo_path": { "type": "string", "description": "index_idの代わりに指定（インデックスがなければ再インデックス化）" },
+                    "path": { "type": "string", "description": "対象をこのディレクトリ配下に絞る（リポジトリルートからの相対パス）" },
+                    "out_dir": { "type": "string", "description": "出力ディレクトリ（省略時は ./out/graphs）" }
+                }
+            }
+        },
+        {
+            "name": "publish_pages",
+            "description": "WikiとスライドをGitHub Pagesに公開します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "mode": { "type": "string", "enum": ["docs", "gh-pages"] },
+                    "site_dir": { "type": "string" },
+                    "slides_dir": { "type": "string" },
+                    "repo_root": { "type": "string" },
+                    "branch": { "type": "string", "default": "gh-pages" }
+                },
+                "required": ["mode", "site_dir", "slides_dir", "repo_root"]
+            }
+        },
+        {
+            "name": "explain_symbol",
+            "description": "シンボルの定義位置・シグネチャ・ドキュメント・参照箇所とコールグラフ（Mermaid）を返します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "index_id": { "type": "string" },
+                    "name": { "type": "string", "description": "シンボル名（Type::method形式も可）" }
+                },
+                "required": ["index_id", "name"]
+            }
+        },
+        {
+            "name": "search",
+            "description": "ソースコードまたは生成済みドキュメントを全文検索します（kind: symbolで関数・型の定義位置、kind: pathでファイルパスを検索）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "q": { "type": "string", "description": "検索クエリ" },
+                    "k": { "type": "integer", "default": 20 },
+                    "in": { "type": "string", "enum": ["source", "docs", "all"], "default": "source" },
+                    "kind": { "type": "string", "enum": ["text", "symbol", "path"], "default": "text", "description": "text: 本文、symbol: 関数・型の宣言名（前方一致・略記や打ち間違いのあいまい一致、定義位置・行番号・シグネチャを返す）、path: ファイルパス" },
+                    "language": { "type": "string", "description": "言語で絞り込む（例: py、in: sourceのみ）" },
+                    "path_glob": { "type": "string", "description": "リポジトリルートからの相対パスのglobで絞り込む（例: services/**、in: sourceのみ）" },
+                    "module": { "type": "string", "description": "モジュールID、またはパッケージ名・ディレクトリ（例: web、web:src/api）で絞り込む（in: sourceのみ）" },
+                    "offset": { "type": "integer", "default": 0, "description": "読み飛ばす結果の数（ページ送り）" },
+                    "index_id": { "type": "string", "description": "ソース検索に使うインデックス（省略時はrepo_path、またはメモリ上の最新のインデックス）" },
+                    "repo_path": { "type": "string", "description": "インデックスがなければ作成するリポジトリのパス" },
+                    "as_of": { "type": "string", "description": "過去の版（index_idまたはコミットSHA）" },
+                    "docs_dirs": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["q"]
+            }
+        },
+        {
+            "name": "semantic_search",
+            "description": "埋め込みベクトルで、自然文のクエリと意味の近いソースコードのチャンク（ファイル・行範囲・抜粋）を返します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "q": { "type": "string", "description": "検索クエリ（自然文）" },
+                    "k": { "type": "integer", "default": 20 },
+                    "index_id": { "type": "string", "description": "検索に使うインデックス（省略時はrepo_path、またはメモリ上の最新のインデックス）" },
+                    "repo_path": { "type": "string", "description": "インデックスがなければ作成するリポジトリのパス" },
+                    "as_of": { "type": "string", "description": "過去の版（index_idまたはコミットSHA）" }
+                },
+                "required": ["q"]
+            }
+        },
+        {
+            "name": "ask",
+            "description": "リポジトリについての自然文の質問に、検索・埋め込みのインデックスから取り出したコードの抜粋をもとに、ファイルと行範囲の引用付きの日本語で回答します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "question": { "type": "string", "description": "質問（例: 認証はどこで行われていますか？）" },
+                    "index_id": { "type": "string" },
+                    "repo_path": { "type": "string", "description": "インデックスがなければ作成するリポジトリのパス" },
+                    "as_of": { "type": "string", "description": "過去の版（index_idまたはコミットSHA）" }
+                },
+                "required": ["question"]
+            }
+        },
+        {
+            "name": "diff",
+            "description": "2つの版（コミット・スナップショット）の差分から、追加したモジュール・削除したファイル・変更の多いファイル・外部依存の変更をまとめた変更履歴のMarkdownを返します",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "old": { "type": "string", "description": "以前の版（index_idまたはコミットSHA）" },
+                    "new": { "type": "string", "description": "新しい版（index_idまたはコミットSHA、省略時はrepo_pathの最新のインデックス）" },
+                    "repo_path": { "type": "string", "description": "版を探すリポジトリのパス（newを省略し、インデックスがなければ作成）" }
+                },
+                "required": ["old"]
+            }
+        },
+        {
+            "name": "get_artifact",
+            "description": "生成したスライド（PDFなど）・Wikiのページ・図を、file://のURIかbase64の内容で取得します（idを省略すると一覧）",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "kind": { "type": "string", "enum": ["slides", "wiki", "diagram"] },
+                    "id": { "type": "string", "description": "出力先ディレクトリからの相対パス（例: slides.pdf、src/architecture.md）" },
+                    "encoding": { "type": "string", "enum": ["uri", "base64"], "default": "uri" },
+                    "offset": { "type": "integer", "default": 0, "description": "base64で返す内容の開始バイト位置（前回のnext_offset）" },
+                    "length": { "type": "integer", "default": 1048576, "description": "base64で返す内容の最大バイト数（最大4MiB）" },
+                    "out_dir": { "type": "string", "description": "出力先ディレクトリ（generate_wiki・generate_slidesのout_dirを指定した場合）" }
+                },
+                "required": ["kind"]
+            }
+        }
+    ])
+}
+
 /// JSON-RPCリクエスト
 #[derive(Debug, Deserialize)]
 struct JsonRpcRequest {
     #[allow(dead_code)]
     jsonrpc: String,
     method: String,
+    #[serde(default)]
     params: Value,
     id: Option<Value>,
 }
 
+/// tools/callのパラメータ
+#[derive(Debug, Deserialize)]
+struct ToolCallParams {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
 /// JSON-RPCレスポンス
 #[derive(Debug, Serialize)]
 struct JsonRpcResponse {
@@ -441,51 +1362,195 @@ struct IndexRepoResult {
     ok: bool,
     index_id: String,
     stats: IndexStats,
+    /// 解析中に記録した警告（スキップしたファイルなど）
+    diagnostics: Vec<Diagnostic>,
 }
 
 /// summarizeツールの引数
 #[derive(Debug, Deserialize)]
 struct SummarizeArgs {
+    /// 省略時はrepo_path、またはメモリ上の最新のインデックスを使用
+    #[serde(default)]
+    index_id: Option<String>,
+    /// インデックスがなければ再インデックス化するリポジトリのパス
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// 過去の版（index_idまたはコミットSHA）
+    #[serde(default)]
+    as_of: Option<String>,
     scope: String,
     target: String,
-    #[serde(default = "default_style")]
-    style: String,
+    /// 省略時はセッションのオプション、またはsummarization.style
+    #[serde(default)]
+    style: Option<String>,
+    /// この呼び出しに限り出力言語を上書き（ja|en）
+    #[serde(default)]
+    lang: Option<String>,
 }
 
-fn default_style() -> String {
-    "concise-ja".to_string()
+/// set_optionsツールの引数
+#[derive(Debug, Deserialize)]
+struct SetOptionsArgs {
+    #[serde(flatten)]
+    options: SessionOptions,
+    /// trueの場合は既存のオプションを破棄してから設定
+    #[serde(default)]
+    reset: bool,
+}
+
+/// set_optionsツールの結果（反映後の値）
+#[derive(Debug, Serialize)]
+struct SetOptionsResult {
+    ok: bool,
+    style: String,
+    diagram_renderer: String,
 }
 
 /// generate_wikiツールの引数
 #[derive(Debug, Deserialize)]
 struct GenerateWikiArgs {
-    index_id: String,
+    /// index_idかrepo_pathのいずれかを指定
+    #[serde(default)]
+    index_id: Option<String>,
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// 過去の版（index_idまたはコミットSHA）
+    #[serde(default)]
+    as_of: Option<String>,
+    /// 生成対象を絞るディレクトリ（リポジトリルートからの相対パス）
+    #[serde(default)]
+    path: Option<String>,
     #[serde(default)]
     out_dir: Option<String>,
     #[serde(default)]
     with_diagrams: bool,
     #[serde(default)]
     toc: Vec<String>,
+    /// 中断したビルドを続きから再開する（生成済みのページ・モジュールの解説を再利用）
+    #[serde(default)]
+    resume: bool,
+    /// この呼び出しに限り出力言語を上書き（ja|en）
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+/// generate_pageツールの引数
+#[derive(Debug, Deserialize)]
+struct GeneratePageArgs {
+    /// 省略時はメモリ上の最新のインデックスを使用
+    #[serde(default)]
+    index_id: Option<String>,
+    /// セクション名（overview|architecture|packages|metrics|hotspots|timeline|tests|unused|debt|api|modules|flows|deploy|config|glossary|faq|licenses|third-party）
+    #[serde(default)]
+    section: Option<String>,
+    /// モジュールのパス
+    #[serde(default)]
+    module: Option<String>,
+    #[serde(default = "default_with_diagrams")]
+    with_diagrams: bool,
+    /// この呼び出しに限り出力言語を上書き（ja|en）
+    #[serde(default)]
+    lang: Option<String>,
+}
+
+fn default_with_diagrams() -> bool {
+    true
+}
+
+/// generate_pageツールの結果
+#[derive(Debug, Serialize)]
+struct GeneratePageResult {
+    ok: bool,
+    page: String,
+    content_md: String,
 }
 
 /// generate_slidesツールの引数
 #[derive(Debug, Deserialize)]
 struct GenerateSlidesArgs {
-    index_id: String,
+    /// index_idかrepo_pathのいずれかを指定
+    #[serde(default)]
+    index_id: Option<String>,
+    #[serde(default)]
+    repo_path: Option<String>,
     #[serde(default = "default_flavor")]
     flavor: String,
+    /// 生成対象を絞るディレクトリ（リポジトリルートからの相対パス）
+    #[serde(default)]
+    path: Option<String>,
     #[serde(default)]
     out_dir: Option<String>,
     #[serde(default)]
     sections: Vec<String>,
     #[serde(default)]
     export: Vec<String>,
+    /// スライドの言語（ja|en、省略時は `lang`、`slides.language`）
+    #[serde(default)]
+    language: Option<String>,
+    /// この呼び出しに限り出力言語を上書き（ja|en）
+    #[serde(default)]
+    lang: Option<String>,
 }
 
 fn default_flavor() -> String {
     "mdbook-reveal".to_string()
 }
 
+/// 呼び出しごとの `lang` の指定を設定に反映する
+///
+/// # 引数
+/// * `config` - セッションの設定
+/// * `lang` - 出力言語（ja|en、Noneの場合はそのまま）
+///
+/// # 戻り値
+/// * `anyhow::Result<Config>` - 設定、または未対応の言語のパラメータ不正のエラー
+fn with_lang(mut config: Config, lang: Option<&str>) -> anyhow::Result<Config> {
+    if let Some(lang) = lang {
+        config
+            .override_language(lang)
+            .map_err(|e| RpcError::invalid_params(e.to_string()))?;
+    }
+    Ok(config)
+}
+
+/// パスの指定があればその配下に絞り込んだインデックスを返す
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `path` - 絞り込むディレクトリ（Noneの場合はインデックスをそのまま使う）
+///
+/// # 戻り値
+/// * `anyhow::Result<Cow<Index>>` - インデックス、またはエラー（配下に解析したファイルがない場合）
+fn scoped_index<'a>(index: &'a Index, path: Option<&str>) -> anyhow::Result<Cow<'a, Index>> {
+    match path {
+        Some(path) => Ok(Cow::Owned(index.subset(Path::new(path))?)),
+        None => Ok(Cow::Borrowed(index)),
+    }
+}
+
+/// export_graphツールの引数
+#[derive(Debug, Deserialize)]
+struct ExportGraphArgs {
+    /// index_idかrepo_pathのいずれかを指定
+    #[serde(default)]
+    index_id: Option<String>,
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// 対象を絞るディレクトリ（リポジトリルートからの相対パス）
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    out_dir: Option<String>,
+}
+
+/// export_graphツールの結果
+#[derive(Debug, Serialize)]
+struct ExportGraphResult {
+    ok: bool,
+    /// 書き出したファイル
+    files: Vec<PathBuf>,
+}
+
 /// publish_pagesツールの引数
 #[derive(Debug, Deserialize)]
 struct PublishPagesArgs {
@@ -501,18 +1566,106 @@ fn default_branch() -> String {
     "gh-pages".to_string()
 }
 
+/// explain_symbolツールの引数
+#[derive(Debug, Deserialize)]
+struct ExplainSymbolArgs {
+    index_id: String,
+    name: String,
+}
+
+/// explain_symbolツールの結果
+#[derive(Debug, Serialize)]
+struct ExplainSymbolResult {
+    ok: bool,
+    symbol: SymbolExplanation,
+}
+
 /// searchツールの引数
 #[derive(Debug, Deserialize)]
 struct SearchArgs {
     q: String,
     #[serde(default = "default_k")]
     k: usize,
+    /// 検索対象（source|docs|all）
+    #[serde(rename = "in", default = "default_search_in")]
+    search_in: String,
+    /// 検索の種類（text|symbol|path、symbol・pathはソースのみ）
+    #[serde(default = "default_search_kind")]
+    kind: String,
+    /// 言語・パスのglob・モジュールによる絞り込み（ソースのみ）と、読み飛ばす結果の数
+    #[serde(flatten)]
+    filter: SearchFilter,
+    /// ソース検索に使うインデックス（省略時はrepo_path、またはメモリ上の最新のインデックス）
+    #[serde(default)]
+    index_id: Option<String>,
+    /// インデックスがなければ再インデックス化するリポジトリのパス
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// ソース検索に使う過去の版（index_idまたはコミットSHA）
+    #[serde(default)]
+    as_of: Option<String>,
+    /// docs検索の対象ディレクトリ（省略時はsite/slidesの出力先）
+    #[serde(default)]
+    docs_dirs: Vec<String>,
+}
+
+/// semantic_searchツールの引数
+#[derive(Debug, Deserialize)]
+struct SemanticSearchArgs {
+    q: String,
+    #[serde(default = "default_k")]
+    k: usize,
+    /// 検索に使うインデックス（省略時はrepo_path、またはメモリ上の最新のインデックス）
+    #[serde(default)]
+    index_id: Option<String>,
+    /// インデックスがなければ再インデックス化するリポジトリのパス
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// 検索に使う過去の版（index_idまたはコミットSHA）
+    #[serde(default)]
+    as_of: Option<String>,
+}
+
+/// askツールの引数
+#[derive(Debug, Deserialize)]
+struct AskArgs {
+    question: String,
+    /// 省略時はrepo_path、またはメモリ上の最新のインデックスを使用
+    #[serde(default)]
+    index_id: Option<String>,
+    /// インデックスがなければ再インデックス化するリポジトリのパス
+    #[serde(default)]
+    repo_path: Option<String>,
+    /// 過去の版（index_idまたはコミットSHA）
+    #[serde(default)]
+    as_of: Option<String>,
+}
+
+/// diffツールの引数
+#[derive(Debug, Deserialize)]
+struct DiffArgs {
+    /// 以前の版（index_idまたはコミットSHA）
+    old: String,
+    /// 新しい版（省略時はrepo_pathの最新のインデックス、またはメモリ上の最新のインデックス）
+    #[serde(default)]
+    new: Option<String>,
+    /// 版を探すリポジトリのパス
+    #[serde(default)]
+    repo_path: Option<String>,
 }
 
 fn default_k() -> usize {
     20
 }
 
+fn default_search_kind() -> String {
+    "text".to_string()
+}
+
+fn default_search_in() -> String {
+    "source".to_string()
+}
+
 /// searchツールの結果
 #[derive(Debug, Serialize)]
 struct SearchResult {
@@ -520,6 +1673,64 @@ struct SearchResult {
     hits: Vec<SearchHit>,
 }
 
+/// get_artifactツールの引数
+#[derive(Debug, Deserialize)]
+struct GetArtifactArgs {
+    /// 成果物の種類（slides|wiki|diagram）
+    kind: String,
+    /// 出力先ディレクトリからの相対パス（省略時は成果物の一覧を返す）
+    #[serde(default)]
+    id: Option<String>,
+    /// 返し方（uri|base64）
+    #[serde(default = "default_artifact_encoding")]
+    encoding: String,
+    /// base64で返す内容の開始バイト位置
+    #[serde(default)]
+    offset: u64,
+    /// base64で返す内容の最大バイト数
+    #[serde(default = "default_artifact_length")]
+    length: usize,
+    /// 出力先ディレクトリ（省略時はgenerate_wiki・generate_slidesの既定の出力先）
+    #[serde(default)]
+    out_dir: Option<String>,
+}
+
+fn default_artifact_encoding() -> String {
+    "uri".to_string()
+}
+
+fn default_artifact_length() -> usize {
+    1024 * 1024
+}
+
+/// get_artifactツールの結果
+#[derive(Debug, Default, Serialize)]
+struct GetArtifactResult {
+    ok: bool,
+    kind: String,
+    /// idを省略した場合の成果物の一覧
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    artifacts: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+    /// ファイル全体のバイト数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    /// base64の場合の内容の開始バイト位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    /// base64でエンコードした内容
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+    /// 続きがある場合の次の開始バイト位置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_offset: Option<u64>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -530,5 +1741,352 @@ mod tests {
         let req: JsonRpcRequest = serde_json::from_str(json).unwrap();
         assert_eq!(req.method, "index_repo");
     }
-}
 
+    #[test]
+    fn test_search_args_in_parameter() {
+        let args: SearchArgs = serde_json::from_str(r#"{"q":"graph","in":"docs"}"#).unwrap();
+        assert_eq!(args.search_in, "docs");
+        let args: SearchArgs = serde_json::from_str(r#"{"q":"graph"}"#).unwrap();
+        assert_eq!(args.search_in, "source");
+        assert_eq!(args.kind, "text");
+        let args: SearchArgs = serde_json::from_str(r#"{"q":"build_wiki","kind":"symbol"}"#).unwrap();
+        assert_eq!(args.kind, "symbol");
+        let args: SearchArgs =
+            serde_json::from_str(r#"{"q":"graph","language":"py","path_glob":"services/**","offset":20}"#).unwrap();
+        assert_eq!(args.filter.language.as_deref(), Some("py"));
+        assert_eq!(args.filter.path_glob.as_deref(), Some("services/**"));
+        assert_eq!(args.filter.offset, 20);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_lifecycle() {
+        let server = McpServer::new(Config::default());
+
+        let init = server
+            .dispatch_tool("initialize", json!({ "protocolVersion": "2025-03-26", "capabilities": {} }))
+            .await
+            .unwrap();
+        assert_eq!(init["protocolVersion"], "2025-03-26");
+        assert!(init["capabilities"]["tools"].is_object());
+
+        // 未対応のバージョンにはサーバーのバージョンで応答する
+        let init = server
+            .dispatch_tool("initialize", json!({ "protocolVersion": "2099-01-01", "capabilities": {} }))
+            .await
+            .unwrap();
+        assert_eq!(init["protocolVersion"], PROTOCOL_VERSION);
+
+        let list = server.dispatch_tool("tools/list", Value::Null).await.unwrap();
+        let tools = list["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 14);
+        assert!(tools.iter().all(|t| t["inputSchema"]["type"] == "object"));
+
+        // ツールのエラーはisErrorとして返る
+        let call = server
+            .dispatch_tool("tools/call", json!({ "name": "summarize", "arguments": { "scope": "repo", "target": "" } }))
+            .await
+            .unwrap();
+        assert_eq!(call["isError"], true);
+        assert!(call["content"][0]["text"].as_str().unwrap().contains("インデックスが見つかりません"));
+    }
+
+    #[tokio::test]
+    async fn test_index_survives_server_restart() {
+        let root = std::env::temp_dir().join(format!("deeprepo-restart-{}", std::process::id()));
+        let repo = root.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+
+        // repo_pathだけを指定すると、インデックスがなければ作成する
+        let server = McpServer::new(config.clone());
+        let index_id = server.ensure_index(None, repo.to_str()).await.unwrap().unwrap();
+
+        // 再起動後も、index_id・repo_pathのどちらからでも読み込める
+        let restarted = McpServer::new(config);
+        assert_eq!(restarted.ensure_index(Some(&index_id), None).await.unwrap().as_deref(), Some(index_id.as_str()));
+        let restarted = McpServer::new(restarted.config.clone());
+        assert_eq!(restarted.ensure_index(None, repo.to_str()).await.unwrap().as_deref(), Some(index_id.as_str()));
+        assert_eq!(restarted.indexes.read().await[&index_id].files.len(), 1);
+
+        // ディスクから読み込んだ参照はミス、メモリ上にあった参照はヒットとして数える
+        restarted.ensure_index(Some(&index_id), None).await.unwrap();
+        let text = restarted.metrics.render(&HashMap::new());
+        assert!(text.contains("deeprepo_index_cache_misses_total 1"), "{}", text);
+        assert!(text.contains("deeprepo_index_cache_hits_total 1"), "{}", text);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_diff_between_versions() {
+        let root = std::env::temp_dir().join(format!("deeprepo-diff-{}", std::process::id()));
+        let repo = root.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+        let server = McpServer::new(config);
+        let mut ids = Vec::new();
+        for file in [None, Some("util.rs")] {
+            if let Some(file) = file {
+                std::thread::sleep(std::time::Duration::from_millis(5));
+                std::fs::write(repo.join(file), "pub fn helper() {}\n").unwrap();
+            }
+            let call = server
+                .dispatch_tool("tools/call", json!({ "name": "index_repo", "arguments": { "repo_path": repo, "refresh": true } }))
+                .await
+                .unwrap();
+            let result: Value = serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+            ids.push(result["index_id"].as_str().unwrap().to_string());
+        }
+        let old = &ids[0];
+
+        // newを省略するとrepo_pathの最新の版と比べる
+        let call = server
+            .dispatch_tool("tools/call", json!({ "name": "diff", "arguments": { "old": old, "repo_path": repo } }))
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(result["diff"]["added_files"], json!(["util.rs"]));
+        assert!(result["content_md"].as_str().unwrap().starts_with("## 変更点"));
+
+        let call = server
+            .dispatch_tool("tools/call", json!({ "name": "diff", "arguments": { "old": "0000000deadbeef" } }))
+            .await
+            .unwrap();
+        assert_eq!(call["isError"], true);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_search_selects_repo_and_drops_expired_versions() {
+        let root = std::env::temp_dir().join(format!("deeprepo-search-repo-{}", uuid::Uuid::new_v4()));
+        for (repo, body) in [("alpha", "fn alpha_only() {}\n"), ("beta", "fn beta_only() {}\n")] {
+            std::fs::create_dir_all(root.join(repo)).unwrap();
+            std::fs::write(root.join(repo).join("main.rs"), body).unwrap();
+        }
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+        config.server.index_history = 1;
+        let server = McpServer::new(config);
+        let index = |repo: &str| {
+            let server = &server;
+            let repo = root.join(repo);
+            async move {
+                let call = server
+                    .dispatch_tool("tools/call", json!({ "name": "index_repo", "arguments": { "repo_path": repo, "refresh": true } }))
+                    .await
+                    .unwrap();
+                let result: Value = serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+                result["index_id"].as_str().unwrap().to_string()
+            }
+        };
+        let first_alpha = index("alpha").await;
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        index("beta").await;
+
+        // repo_pathを指定すると、ほかのリポジトリのインデックスがあってもそのリポジトリを検索する
+        let search = |arguments: Value| server.search(serde_json::from_value(arguments).unwrap());
+        let hits = search(json!({ "q": "alpha_only", "repo_path": root.join("alpha") })).await.unwrap().hits;
+        assert_eq!(hits.len(), 1);
+        assert!(search(json!({ "q": "alpha_only", "repo_path": root.join("beta") })).await.unwrap().hits.is_empty());
+        // 省略時は最後に作成したインデックス
+        assert_eq!(search(json!({ "q": "beta_only" })).await.unwrap().hits.len(), 1);
+
+        // 保持数を超えてディスクから削除した版はメモリからも破棄する
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second_alpha = index("alpha").await;
+        let indexes = server.indexes.read().await;
+        assert!(!indexes.contains_key(&first_alpha));
+        assert!(indexes.contains_key(&second_alpha));
+        assert_eq!(indexes.len(), 2);
+        drop(indexes);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_ask_answers_with_citations() {
+        let root = std::env::temp_dir().join(format!("deeprepo-ask-{}", std::process::id()));
+        let repo = root.join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(repo.join("src/main.rs"), "mod auth;\n\nfn main() {}\n").unwrap();
+        std::fs::write(repo.join("src/auth.rs"), "pub fn verify_token(token: &str) -> bool {\n    !token.is_empty()\n}\n").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+        let server = McpServer::new(config);
+
+        let call = server
+            .dispatch_tool(
+                "tools/call",
+                json!({ "name": "ask", "arguments": { "question": "トークンの認証はどこですか？", "repo_path": repo } }),
+            )
+            .await
+            .unwrap();
+        let result: Value = serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(result["ok"], true);
+        assert_eq!(result["mode"], "excerpt");
+        assert!(result["answer_md"].as_str().unwrap().contains("`src/auth.rs` の `function verify_token`"));
+        assert_eq!(result["citations"][0]["start_line"], 1);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_export_graph_writes_files() {
+        let root = std::env::temp_dir().join(format!("deeprepo-graph-{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() {\n    run();\n}\n\nfn run() {}\n").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+        let server = McpServer::new(config);
+        let out_dir = root.join("graphs");
+        let call = server
+            .dispatch_tool(
+                "tools/call",
+                json!({ "name": "export_graph", "arguments": { "repo_path": repo, "out_dir": out_dir } }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(call["isError"], false, "{}", call);
+        let result: Value = serde_json::from_str(call["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(result["files"].as_array().unwrap().len(), 4);
+        let call_graph = std::fs::read_to_string(out_dir.join("call-graph.cytoscape.json")).unwrap();
+        let call_graph: Value = serde_json::from_str(&call_graph).unwrap();
+        assert_eq!(call_graph["elements"]["edges"].as_array().unwrap().len(), 1);
+
+        // 許可ディレクトリの外の出力先は拒否
+        let error = server
+            .dispatch_tool("export_graph", json!({ "repo_path": repo, "out_dir": "/deeprepo-graphs" }))
+            .await
+            .unwrap_err();
+        assert_eq!(validation::error_code(&error), validation::INVALID_PARAMS);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_get_artifact_returns_base64_chunks() {
+        let root = std::env::temp_dir().join(format!("deeprepo-artifact-{}", uuid::Uuid::new_v4()));
+        let slides = root.join("slides");
+        std::fs::create_dir_all(&slides).unwrap();
+        std::fs::write(slides.join("slides.pdf"), b"%PDF-1.7").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.slides.out_dir = slides.clone();
+        let server = McpServer::new(config);
+        let get = |arguments: Value| {
+            let server = &server;
+            async move {
+                let call = server
+                    .dispatch_tool("tools/call", json!({ "name": "get_artifact", "arguments": arguments }))
+                    .await
+                    .unwrap();
+                serde_json::from_str::<Value>(call["content"][0]["text"].as_str().unwrap()).unwrap()
+            }
+        };
+
+        let listed = get(json!({ "kind": "slides", "out_dir": slides })).await;
+        assert_eq!(listed["artifacts"], json!(["slides.pdf"]));
+        // out_dirを省略すると設定の出力先（slides.out-dir）から探す
+        let configured = get(json!({ "kind": "slides" })).await;
+        assert_eq!(configured["artifacts"], json!(["slides.pdf"]));
+
+        let first = get(json!({ "kind": "slides", "id": "slides.pdf", "out_dir": slides, "encoding": "base64", "length": 5 })).await;
+        assert_eq!(first["mime_type"], "application/pdf");
+        assert_eq!(first["size"], 8);
+        assert_eq!(first["data"], base64::engine::general_purpose::STANDARD.encode(b"%PDF-"));
+        assert_eq!(first["next_offset"], 5);
+        let rest = get(json!({ "kind": "slides", "id": "slides.pdf", "out_dir": slides, "encoding": "base64", "offset": 5 })).await;
+        assert_eq!(rest["data"], base64::engine::general_purpose::STANDARD.encode(b"1.7"));
+        assert!(rest.get("next_offset").is_none());
+
+        let uri = get(json!({ "kind": "slides", "id": "slides.pdf", "out_dir": slides })).await;
+        assert!(uri["uri"].as_str().unwrap().starts_with("file:///"));
+        assert!(uri.get("data").is_none());
+
+        // 許可ディレクトリの外の出力先は拒否
+        let call = server
+            .dispatch_tool("tools/call", json!({ "name": "get_artifact", "arguments": { "kind": "wiki", "out_dir": "/" } }))
+            .await
+            .unwrap();
+        assert_eq!(call["isError"], true);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_map_to_error_codes() {
+        let server = McpServer::new(Config::default());
+
+        let root = server.dispatch_tool("index_repo", json!({ "repo_path": "/" })).await.unwrap_err();
+        assert_eq!(validation::error_code(&root), validation::INVALID_PARAMS);
+
+        let missing = server.dispatch_tool("index_repo", json!({})).await.unwrap_err();
+        assert_eq!(validation::error_code(&missing), validation::INVALID_PARAMS);
+
+        let unknown = server.dispatch_tool("no_such_tool", Value::Null).await.unwrap_err();
+        assert_eq!(validation::error_code(&unknown), validation::METHOD_NOT_FOUND);
+
+        let lang = server.dispatch_tool("generate_page", json!({ "section": "overview", "lang": "fr" })).await.unwrap_err();
+        assert_eq!(validation::error_code(&lang), validation::INVALID_PARAMS);
+
+        // 出力先・公開元・検索するドキュメントも許可ディレクトリの外は拒否（不明な検索対象も検索の前に拒否）
+        for (tool, arguments) in [
+            ("generate_wiki", json!({ "out_dir": "/deeprepo-outside/wiki" })),
+            ("generate_slides", json!({ "out_dir": "/deeprepo-outside/slides" })),
+            ("publish_pages", json!({ "mode": "docs", "site_dir": "out/wiki", "slides_dir": "out/slides", "repo_root": "/" })),
+            ("publish_pages", json!({ "mode": "docs", "site_dir": "/", "slides_dir": "out/slides", "repo_root": "." })),
+            ("search", json!({ "q": "main", "in": "docs", "docs_dirs": ["/"] })),
+            ("search", json!({ "q": "main", "in": "everywhere" })),
+        ] {
+            let outside = server.dispatch_tool(tool, arguments).await.unwrap_err();
+            assert_eq!(validation::error_code(&outside), validation::INVALID_PARAMS, "{}: {}", tool, outside);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lang_overrides_language_per_call() {
+        let root = std::env::temp_dir().join(format!("deeprepo-lang-{}", uuid::Uuid::new_v4()));
+        let repo = root.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+
+        let mut config = Config::default();
+        config.server.allowed_roots = vec![root.clone()];
+        config.server.index_dir = root.join("indexes");
+        let server = McpServer::new(config);
+        let index_id = server.ensure_index(None, repo.to_str()).await.unwrap();
+
+        let page = |lang: Option<&str>| GeneratePageArgs {
+            index_id: index_id.clone(),
+            section: Some("overview".to_string()),
+            module: None,
+            with_diagrams: false,
+            lang: lang.map(str::to_string),
+        };
+        let en = server.generate_page(page(Some("en"))).await.unwrap();
+        assert!(en.content_md.starts_with("# Overview"));
+
+        // 上書きはその呼び出しだけで、次の呼び出しは設定の言語に戻る
+        let ja = server.generate_page(page(None)).await.unwrap();
+        assert!(ja.content_md.starts_with("# 概要"));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,319 @@
+/*!
+ * Prometheusメトリクス
+ *
+ * 共有のドキュメント生成サービスとして運用する際の監視用メトリクスを集計し、
+ * `server.metrics-addr` で指定したアドレスの `GET /metrics` で公開する
+ *
+ * 主な仕様:
+ * - `deeprepo_tool_requests_total{tool,status}`: ツールごとのリクエスト数（status: ok|error）
+ * - `deeprepo_tool_duration_seconds{tool}`: ツールごとの処理時間（ヒストグラム）
+ * - `deeprepo_indexes` / `deeprepo_index_files{index_id}` / `deeprepo_index_symbols{index_id}`: 保持中のインデックスの規模
+ * - `deeprepo_index_cache_hits_total` / `deeprepo_index_cache_misses_total`: 指定したインデックスがメモリ上にあった回数と、ディスクからの読み込み・再インデックス化が必要だった回数
+ * - `deeprepo_external_command_failures_total{command}`: 外部コマンド（mdbook, marp, chromium）の失敗数
+ *   （Wiki・スライドのビルダーと共有する `Tools` が実行時に数えたもの）
+ * - リクエスト行は `MAX_REQUEST_LINE` バイトまでしか読まず、`REQUEST_TIMEOUT` 以内に届かない接続は閉じる
+ *
+ * 制限事項:
+ * - HTTPは `GET /metrics` のみに応答する最小限の実装
+ */
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+use tracing::{debug, info};
+
+use analyzer_core::Index;
+use summarizer::Tools;
+
+/// 処理時間ヒストグラムのバケット境界（秒）
+const DURATION_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+/// リクエスト行の最大バイト数（超えた分は読まない）
+const MAX_REQUEST_LINE: u64 = 8 * 1024;
+/// リクエスト行を待つ最大時間
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 失敗数を常に出力する外部コマンド（失敗していなくても0を出力する）
+const EXTERNAL_COMMANDS: &[&str] = &["mdbook", "marp", "chromium"];
+
+/// メトリクスの集計
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    inner: Mutex<MetricsInner>,
+    /// ビルダーと共有する外部ツール（失敗数を読み出す）
+    tools: Tools,
+}
+
+#[derive(Debug, Default)]
+struct MetricsInner {
+    /// (ツール名, status) -> 件数
+    requests: BTreeMap<(String, &'static str), u64>,
+    /// ツール名 -> 処理時間
+    durations: BTreeMap<String, Histogram>,
+    index_cache_hits: u64,
+    index_cache_misses: u64,
+}
+
+/// 処理時間のヒストグラム
+#[derive(Debug, Default)]
+struct Histogram {
+    /// 各バケット境界以下の件数（累積ではない）
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Metrics {
+    /// メトリクスの集計を開始
+    ///
+    /// # 引数
+    /// * `tools` - ビルダーと共有する外部ツール（外部コマンドの失敗数を読み出す）
+    ///
+    /// # 戻り値
+    /// * `Self` - メトリクスの集計
+    pub fn new(tools: Tools) -> Self {
+        Self { inner: Mutex::default(), tools }
+    }
+
+    /// ツールの実行結果を記録
+    ///
+    /// # 引数
+    /// * `tool` - ツール名
+    /// * `elapsed` - 処理時間
+    /// * `error` - 失敗した場合のエラー
+    pub fn record_tool(&self, tool: &str, elapsed: Duration, error: Option<&anyhow::Error>) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let status = if error.is_some() { "error" } else { "ok" };
+        *inner.requests.entry((tool.to_string(), status)).or_default() += 1;
+
+        let seconds = elapsed.as_secs_f64();
+        let histogram = inner.durations.entry(tool.to_string()).or_default();
+        if histogram.buckets.is_empty() {
+            histogram.buckets = vec![0; DURATION_BUCKETS.len()];
+        }
+        if let Some(i) = DURATION_BUCKETS.iter().position(|&bound| seconds <= bound) {
+            histogram.buckets[i] += 1;
+        }
+        histogram.sum += seconds;
+        histogram.count += 1;
+    }
+
+    /// インデックスの参照結果を記録
+    ///
+    /// # 引数
+    /// * `hit` - 見つかった場合はtrue
+    pub fn record_index_lookup(&self, hit: bool) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if hit {
+            inner.index_cache_hits += 1;
+        } else {
+            inner.index_cache_misses += 1;
+        }
+    }
+
+    /// Prometheusのテキスト形式で出力
+    ///
+    /// # 引数
+    /// * `indexes` - 保持中のインデックス（index_id -> Index）
+    ///
+    /// # 戻り値
+    /// * `String` - メトリクス（text/plain; version=0.0.4）
+    pub fn render(&self, indexes: &HashMap<String, Index>) -> String {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        out.push_str("# HELP deeprepo_tool_requests_total ツールごとのリクエスト数\n");
+        out.push_str("# TYPE deeprepo_tool_requests_total counter\n");
+        for ((tool, status), count) in &inner.requests {
+            let _ = writeln!(out, "deeprepo_tool_requests_total{{tool=\"{}\",status=\"{}\"}} {}", escape(tool), status, count);
+        }
+
+        out.push_str("# HELP deeprepo_tool_duration_seconds ツールごとの処理時間\n");
+        out.push_str("# TYPE deeprepo_tool_duration_seconds histogram\n");
+        for (tool, histogram) in &inner.durations {
+            let tool = escape(tool);
+            let mut cumulative = 0;
+            for (bound, count) in DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(out, "deeprepo_tool_duration_seconds_bucket{{tool=\"{}\",le=\"{}\"}} {}", tool, bound, cumulative);
+            }
+            let _ = writeln!(out, "deeprepo_tool_duration_seconds_bucket{{tool=\"{}\",le=\"+Inf\"}} {}", tool, histogram.count);
+            let _ = writeln!(out, "deeprepo_tool_duration_seconds_sum{{tool=\"{}\"}} {}", tool, histogram.sum);
+            let _ = writeln!(out, "deeprepo_tool_duration_seconds_count{{tool=\"{}\"}} {}", tool, histogram.count);
+        }
+
+        out.push_str("# HELP deeprepo_indexes 保持中のインデックス数\n");
+        out.push_str("# TYPE deeprepo_indexes gauge\n");
+        let _ = writeln!(out, "deeprepo_indexes {}", indexes.len());
+        let mut ids: Vec<&String> = indexes.keys().collect();
+        ids.sort();
+        out.push_str("# HELP deeprepo_index_files インデックスのファイル数\n");
+        out.push_str("# TYPE deeprepo_index_files gauge\n");
+        for id in &ids {
+            let _ = writeln!(out, "deeprepo_index_files{{index_id=\"{}\"}} {}", escape(id), indexes[*id].files.len());
+        }
+        out.push_str("# HELP deeprepo_index_symbols インデックスのシンボル数\n");
+        out.push_str("# TYPE deeprepo_index_symbols gauge\n");
+        for id in &ids {
+            let symbols: usize = indexes[*id].files.iter().map(|f| f.symbols.len()).sum();
+            let _ = writeln!(out, "deeprepo_index_symbols{{index_id=\"{}\"}} {}", escape(id), symbols);
+        }
+
+        out.push_str("# HELP deeprepo_index_cache_hits_total 指定したインデックスがメモリ上にあった回数\n");
+        out.push_str("# TYPE deeprepo_index_cache_hits_total counter\n");
+        let _ = writeln!(out, "deeprepo_index_cache_hits_total {}", inner.index_cache_hits);
+        out.push_str("# HELP deeprepo_index_cache_misses_total ディスクからの読み込み・再インデックス化が必要だった回数\n");
+        out.push_str("# TYPE deeprepo_index_cache_misses_total counter\n");
+        let _ = writeln!(out, "deeprepo_index_cache_misses_total {}", inner.index_cache_misses);
+
+        out.push_str("# HELP deeprepo_external_command_failures_total 外部コマンドの失敗数\n");
+        out.push_str("# TYPE deeprepo_external_command_failures_total counter\n");
+        let mut failures = self.tools.failures();
+        for command in EXTERNAL_COMMANDS {
+            failures.entry(command.to_string()).or_default();
+        }
+        for (command, count) in &failures {
+            let _ = writeln!(out, "deeprepo_external_command_failures_total{{command=\"{}\"}} {}", escape(command), count);
+        }
+
+        out
+    }
+}
+
+/// ラベル値をエスケープ
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// `GET /metrics` に応答するHTTPサーバーを起動
+///
+/// # 引数
+/// * `addr` - 待ち受けアドレス（例: 127.0.0.1:9464）
+/// * `metrics` - メトリクス
+/// * `indexes` - 保持中のインデックス
+///
+/// # 戻り値
+/// * `anyhow::Result<()>` - 待ち受けに失敗した場合はエラー（成功時は終了しない）
+pub(crate) async fn serve_metrics(
+    addr: &str,
+    metrics: Arc<Metrics>,
+    indexes: Arc<RwLock<HashMap<String, Index>>>,
+) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("メトリクスのアドレスで待ち受けできません: {}: {}", addr, e))?;
+    info!("メトリクスを公開しました: http://{}/metrics", listener.local_addr()?);
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let metrics = metrics.clone();
+        let indexes = indexes.clone();
+        tokio::spawn(async move {
+            debug!("メトリクスへの接続: {}", peer);
+            respond(stream, &metrics, &indexes).await;
+        });
+    }
+}
+
+/// 1つの接続に応答する（リクエスト行が大きすぎる・届かない場合も接続を保持し続けない）
+///
+/// # 引数
+/// * `stream` - 接続
+/// * `metrics` - メトリクス
+/// * `indexes` - 保持中のインデックス
+async fn respond<S>(stream: S, metrics: &Metrics, indexes: &RwLock<HashMap<String, Index>>)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_LINE));
+    if !matches!(tokio::time::timeout(REQUEST_TIMEOUT, reader.read_line(&mut request_line)).await, Ok(Ok(_))) {
+        return;
+    }
+    debug!("メトリクスのリクエスト: {}", request_line.trim());
+
+    let (status, body) = if request_line.starts_with("GET /metrics ") {
+        ("200 OK", metrics.render(&*indexes.read().await))
+    } else {
+        ("404 Not Found", "not found\n".to_string())
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = writer.write_all(response.as_bytes()).await;
+    let _ = writer.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let fake = summarizer::FakeTools::new().with("mdbook", summarizer::FakeRun::failure("error: failed\n"));
+        let tools = Tools::new(fake);
+        let metrics = Metrics::new(tools.clone());
+        metrics.record_tool("generate_wiki", Duration::from_millis(30), None);
+        let dir = std::env::temp_dir();
+        let failure = tools.run(&summarizer::ToolCommand::new("mdbook").arg("build"), &dir.join("book")).unwrap_err();
+        metrics.record_tool("generate_wiki", Duration::from_secs(2), Some(&anyhow::anyhow!("mdBookビルドエラー: {}", failure)));
+        // エラーメッセージに外部コマンドの名前を含んでも、実行していなければ数えない
+        let unrelated = anyhow::anyhow!("marpの設定が見つかりません");
+        metrics.record_tool("generate_slides", Duration::from_millis(5), Some(&unrelated));
+        metrics.record_index_lookup(true);
+        metrics.record_index_lookup(false);
+
+        let mut indexes = HashMap::new();
+        indexes.insert("idx_1".to_string(), Index::default());
+        let text = metrics.render(&indexes);
+
+        assert!(text.contains("deeprepo_tool_requests_total{tool=\"generate_wiki\",status=\"ok\"} 1"));
+        assert!(text.contains("deeprepo_tool_requests_total{tool=\"generate_wiki\",status=\"error\"} 1"));
+        assert!(text.contains("deeprepo_tool_duration_seconds_bucket{tool=\"generate_wiki\",le=\"0.05\"} 1"));
+        assert!(text.contains("deeprepo_tool_duration_seconds_bucket{tool=\"generate_wiki\",le=\"+Inf\"} 2"));
+        assert!(text.contains("deeprepo_index_files{index_id=\"idx_1\"} 0"));
+        assert!(text.contains("deeprepo_index_cache_misses_total 1"));
+        assert!(text.contains("deeprepo_external_command_failures_total{command=\"mdbook\"} 1"));
+        assert!(text.contains("deeprepo_external_command_failures_total{command=\"marp\"} 0"));
+        assert!(!text.contains("command=\"git\""));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_respond_limits_request_line() {
+        let metrics = Metrics::new(Tools::default());
+        let indexes = RwLock::new(HashMap::new());
+        let exchange = |request: Vec<u8>| {
+            let (metrics, indexes) = (&metrics, &indexes);
+            async move {
+                let (mut client, server) = tokio::io::duplex(1024);
+                let send = async {
+                    let _ = client.write_all(&request).await;
+                    let mut response = String::new();
+                    let _ = client.read_to_string(&mut response).await;
+                    response
+                };
+                let (response, ()) = tokio::join!(send, respond(server, metrics, indexes));
+                response
+            }
+        };
+
+        let ok = exchange(b"GET /metrics HTTP/1.1\r\n\r\n".to_vec()).await;
+        assert!(ok.starts_with("HTTP/1.1 200 OK"), "{}", ok);
+        // 改行のない巨大なリクエスト行は上限までしか読まずに応答する
+        let huge = exchange(vec![b'A'; 1024 * 1024]).await;
+        assert!(huge.starts_with("HTTP/1.1 404 Not Found"), "{}", huge);
+        // 何も送らない接続は時間切れで閉じる
+        let (_client, server) = tokio::io::duplex(1024);
+        let started = tokio::time::Instant::now();
+        respond(server, &metrics, &indexes).await;
+        assert!(started.elapsed() >= REQUEST_TIMEOUT);
+    }
+}
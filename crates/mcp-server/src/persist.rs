@@ -0,0 +1,172 @@
+/*!
+ * インデックスの永続化
+ *
+ * サーバーが作成したインデックスを `server.index-dir` に保存し、再起動後も
+ * index_idやrepo_pathから読み込めるようにする
+ * - リポジトリごとに過去のインデックスを保持し、`as_of`（index_idまたはコミットSHA）で参照できる
+ *
+ * 主な仕様:
+ * - インデックスは `<index-dir>/<index_id>.idx` に保存（`Index::save` のコンパクト形式）
+ * - リポジトリごとの履歴（index_id・コミットSHA・作成日時）を `<index-dir>/history.json` に記録
+ * - 履歴は `server.index-history` 件まで保持し、古いものはファイルごと削除
+ *
+ * 制限事項:
+ * - コミットSHAは解析時のHEAD（未コミットの変更は区別しない）
+ */
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use analyzer_core::Index;
+
+use crate::validation::RpcError;
+
+/// インデックスの履歴のファイル名
+const HISTORY_FILE: &str = "history.json";
+
+/// 保存したインデックスの版
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct IndexVersion {
+    pub index_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// リポジトリ（正規化済みのパス）→ 古い順の版
+type History = BTreeMap<PathBuf, Vec<IndexVersion>>;
+
+/// インデックスファイルのパスを取得
+///
+/// # 引数
+/// * `dir` - 保存先ディレクトリ
+/// * `index_id` - インデックスID
+///
+/// # 戻り値
+/// * `Result<PathBuf, RpcError>` - パス（IDにパス区切りなどが含まれる場合はパラメータ不正のエラー）
+pub(crate) fn index_file(dir: &Path, index_id: &str) -> Result<PathBuf, RpcError> {
+    let valid = !index_id.is_empty() && index_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(RpcError::invalid_params(format!("不正なindex_idです: {}", index_id)));
+    }
+    Ok(dir.join(format!("{}.idx", index_id)))
+}
+
+/// インデックスを保存し、リポジトリの履歴に追加
+///
+/// # 引数
+/// * `dir` - 保存先ディレクトリ
+/// * `index_id` - インデックスID
+/// * `index` - インデックス
+/// * `keep` - リポジトリごとに保持する版の数（超えた古い版は削除）
+/// * `content` - 保存するファイル内容（full|excerpts|hash、`index.content` の値）
+///
+/// # 戻り値
+/// * `Result<Vec<String>>` - 削除した古い版のindex_id（メモリ上の版も破棄する）、またはエラー
+pub(crate) fn save_index(dir: &Path, index_id: &str, index: &Index, keep: usize, content: &str) -> Result<Vec<String>> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("インデックスの保存先を作成できません: {:?}", dir))?;
+    index.save_with(&index_file(dir, index_id)?, content)?;
+
+    let mut history = read_history(dir);
+    let versions = history.entry(index.repo_path.clone()).or_default();
+    versions.retain(|v| v.index_id != index_id);
+    versions.push(IndexVersion {
+        index_id: index_id.to_string(),
+        commit: index.commit.clone(),
+        created_at: Utc::now(),
+    });
+    let expired = versions.len().saturating_sub(keep.max(1));
+    let mut removed = Vec::new();
+    for old in versions.drain(..expired) {
+        if let Err(e) = Index::remove_saved(&index_file(dir, &old.index_id)?) {
+            tracing::warn!("古いインデックスの削除に失敗しました: {}", e);
+        }
+        removed.push(old.index_id);
+    }
+    std::fs::write(dir.join(HISTORY_FILE), serde_json::to_string_pretty(&history)?)?;
+    Ok(removed)
+}
+
+/// リポジトリの最新のindex_idを取得
+///
+/// # 引数
+/// * `dir` - 保存先ディレクトリ
+/// * `repo_path` - 正規化済みのリポジトリのパス
+///
+/// # 戻り値
+/// * `Option<String>` - index_id（記録がない場合はNone）
+pub(crate) fn lookup_repo(dir: &Path, repo_path: &Path) -> Option<String> {
+    read_history(dir).remove(repo_path)?.pop().map(|v| v.index_id)
+}
+
+/// `as_of`（index_idまたはコミットSHA）に一致する版のindex_idを取得
+///
+/// コミットSHAは7文字以上の前方一致で照合し、同じコミットの版が複数ある場合は最新のものを返す。
+///
+/// # 引数
+/// * `dir` - 保存先ディレクトリ
+/// * `repo_path` - 正規化済みのリポジトリのパス（省略時はすべてのリポジトリから探す）
+/// * `as_of` - index_idまたはコミットSHA
+///
+/// # 戻り値
+/// * `Option<String>` - index_id（見つからない場合はNone）
+pub(crate) fn find_version(dir: &Path, repo_path: Option<&Path>, as_of: &str) -> Option<String> {
+    let as_of = as_of.trim();
+    let is_commit = |v: &IndexVersion| {
+        as_of.len() >= 7 && v.commit.as_deref().is_some_and(|c| c.starts_with(&as_of.to_ascii_lowercase()))
+    };
+    read_history(dir)
+        .into_iter()
+        .filter(|(repo, _)| repo_path.is_none_or(|p| p == repo))
+        .flat_map(|(_, versions)| versions)
+        .filter(|v| v.index_id == as_of || is_commit(v))
+        .max_by_key(|v| v.created_at)
+        .map(|v| v.index_id)
+}
+
+/// 履歴を読み込む（存在しない・壊れている場合は空）
+fn read_history(dir: &Path) -> History {
+    std::fs::read_to_string(dir.join(HISTORY_FILE))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_lookup_index() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-persist-{}", std::process::id()));
+        let version = |commit: &str| Index {
+            repo_path: PathBuf::from("/work/repo"),
+            commit: Some(commit.to_string()),
+            ..Default::default()
+        };
+
+        save_index(&dir, "idx_1", &version("1111111aaaa"), 2, "full").unwrap();
+        save_index(&dir, "idx_2", &version("2222222bbbb"), 2, "full").unwrap();
+        assert_eq!(save_index(&dir, "idx_3", &version("3333333cccc"), 2, "full").unwrap(), vec!["idx_1".to_string()]);
+        assert_eq!(lookup_repo(&dir, Path::new("/work/repo")).as_deref(), Some("idx_3"));
+        assert_eq!(lookup_repo(&dir, Path::new("/work/other")), None);
+
+        // 保持数を超えた古い版は削除される
+        assert!(!index_file(&dir, "idx_1").unwrap().exists());
+        assert_eq!(find_version(&dir, None, "1111111"), None);
+
+        // index_idまたはコミットSHAの前方一致で過去の版を参照できる
+        assert_eq!(find_version(&dir, None, "2222222").as_deref(), Some("idx_2"));
+        assert_eq!(find_version(&dir, Some(Path::new("/work/repo")), "idx_2").as_deref(), Some("idx_2"));
+        assert_eq!(find_version(&dir, Some(Path::new("/work/other")), "idx_2"), None);
+        assert_eq!(find_version(&dir, None, "222"), None);
+
+        assert!(index_file(&dir, "../etc/passwd").is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,148 @@
+/*!
+ * セッション単位の設定
+ *
+ * `set_options` ツールで指定したオプションを、以降の呼び出しでグローバル設定より優先する
+ * - stdioのMCPサーバーは1プロセス1クライアントのため、セッションはinitializeから次のinitializeまで
+ *
+ * 主な仕様:
+ * - `style`: 要約スタイル（concise-ja|detailed-ja|concise-en|detailed-en、`language` と組み合わせて concise|detailed も可）
+ * - `language`: 要約・Wiki・スライドの出力言語（`project.language` を上書き）
+ * - `diagram_renderer`: 図のレンダラ（mermaid|graphviz、`analysis.diagrams.renderer` を上書き）
+ *
+ * 制限事項:
+ * - `slides.language` を設定している場合、スライドの言語はそちらを優先する
+ */
+
+use serde::{Deserialize, Serialize};
+
+use config::{Config, LANGUAGES, RENDERERS};
+
+use crate::validation::RpcError;
+
+/// 指定できる要約スタイル（言語を除く）
+const STYLES: &[&str] = &["concise", "detailed"];
+
+/// セッションのオプション（未指定の項目はグローバル設定を使う）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct SessionOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagram_renderer: Option<String>,
+}
+
+impl SessionOptions {
+    /// 新しいオプションを検証して重ねる
+    ///
+    /// # 引数
+    /// * `update` - 指定されたオプション（未指定の項目は現在の値を維持）
+    ///
+    /// # 戻り値
+    /// * `Result<(), RpcError>` - 成功、またはパラメータ不正のエラー
+    pub fn merge(&mut self, update: SessionOptions) -> Result<(), RpcError> {
+        if let Some(language) = &update.language {
+            if !LANGUAGES.contains(&language.as_str()) {
+                return Err(RpcError::invalid_params(format!(
+                    "未対応の言語です: {}（{}）",
+                    language,
+                    LANGUAGES.join("|")
+                )));
+            }
+        }
+        if let Some(style) = &update.style {
+            let base = match style.rsplit_once('-') {
+                Some((base, language)) if LANGUAGES.contains(&language) => base,
+                _ => style,
+            };
+            if !STYLES.contains(&base) {
+                return Err(RpcError::invalid_params(format!(
+                    "不明なスタイルです: {}（concise|detailed、言語を付ける場合は -ja|-en）",
+                    style
+                )));
+            }
+        }
+        if let Some(renderer) = &update.diagram_renderer {
+            if !RENDERERS.contains(&renderer.as_str()) {
+                return Err(RpcError::invalid_params(format!(
+                    "不明なレンダラです: {}（{}）",
+                    renderer,
+                    RENDERERS.join("|")
+                )));
+            }
+        }
+
+        if update.style.is_some() {
+            self.style = update.style;
+        }
+        if update.language.is_some() {
+            self.language = update.language;
+        }
+        if update.diagram_renderer.is_some() {
+            self.diagram_renderer = update.diagram_renderer;
+        }
+        Ok(())
+    }
+
+    /// グローバル設定にオプションを反映した設定を作成
+    ///
+    /// # 引数
+    /// * `config` - グローバル設定
+    ///
+    /// # 戻り値
+    /// * `Config` - セッションの設定
+    pub fn apply(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if let Some(language) = &self.language {
+            config.project.language = language.clone();
+        }
+        if self.style.is_some() || self.language.is_some() {
+            // 言語を指定した場合は、スタイルに付いた言語より優先する
+            let style = self.style.clone().unwrap_or_else(|| config.summarization.style.clone());
+            config.summarization.style = match (&self.language, style.rsplit_once('-')) {
+                (Some(_), Some((base, language))) if LANGUAGES.contains(&language) => base.to_string(),
+                _ => style,
+            };
+        }
+        if let Some(renderer) = &self.diagram_renderer {
+            config.analysis.diagrams.renderer = renderer.clone();
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_options_override_config() {
+        let mut options = SessionOptions::default();
+        options
+            .merge(SessionOptions {
+                style: Some("detailed".to_string()),
+                language: Some("ja".to_string()),
+                diagram_renderer: Some("graphviz".to_string()),
+            })
+            .unwrap();
+
+        let config = options.apply(&Config::default());
+        assert_eq!(config.summary_style(), "detailed-ja");
+        assert_eq!(config.analysis.diagrams.renderer, "graphviz");
+
+        // 言語を切り替えると要約・Wiki・スライドの言語がすべて変わる
+        options.merge(SessionOptions { language: Some("en".to_string()), ..Default::default() }).unwrap();
+        let config = options.apply(&Config::default());
+        assert_eq!(config.summary_style(), "detailed-en");
+        assert_eq!(config.slides_language(), "en");
+
+        // 不正な値は拒否され、現在の値は変わらない
+        assert!(options.merge(SessionOptions { language: Some("fr".to_string()), ..Default::default() }).is_err());
+        assert!(options.merge(SessionOptions { style: Some("concise-fr".to_string()), ..Default::default() }).is_err());
+        assert!(options.merge(SessionOptions { diagram_renderer: Some("ascii".to_string()), ..Default::default() }).is_err());
+        // ページに埋め込めないエクスポート形式はレンダラとして選べない（export_graphで出力する）
+        assert!(options.merge(SessionOptions { diagram_renderer: Some("graphml".to_string()), ..Default::default() }).is_err());
+        assert_eq!(options.diagram_renderer.as_deref(), Some("graphviz"));
+    }
+}
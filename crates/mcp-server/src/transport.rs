@@ -0,0 +1,271 @@
+/*!
+ * 標準入出力のメッセージ転送
+ *
+ * JSON-RPCメッセージの読み込みと、並行して書き込まれるレスポンスの直列化を行う
+ * - 書き込みは専用タスクがmpscチャネルから1メッセージずつ行う（途中で他のメッセージと混ざらない）
+ * - 区切り方式は改行区切り（既定）とLSP形式の `Content-Length` ヘッダーに対応
+ *
+ * 主な仕様:
+ * - `server.framing = "line"`: 1行1メッセージ
+ * - `server.framing = "content-length"`: `Content-Length: N\r\n\r\n` の後にNバイトの本文
+ *
+ * - `server.max-request-bytes` を超えるメッセージは読み飛ばす（メモリには保持しない）
+ *
+ * 制限事項:
+ * - `Content-Length` 以外のヘッダー（Content-Typeなど）は読み飛ばす
+ */
+
+use anyhow::Result;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// 書き込み待ちメッセージの上限（超えると送信側が待機する）
+const WRITER_QUEUE: usize = 64;
+
+/// メッセージの区切り方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// 改行区切り
+    Line,
+    /// LSP形式の `Content-Length` ヘッダー
+    ContentLength,
+}
+
+impl Framing {
+    /// 設定値から区切り方式を取得
+    ///
+    /// # 引数
+    /// * `value` - 設定値（line|content-length）
+    ///
+    /// # 戻り値
+    /// * `Result<Framing>` - 区切り方式、またはエラー
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "line" => Ok(Self::Line),
+            "content-length" => Ok(Self::ContentLength),
+            _ => Err(anyhow::anyhow!("不明なメッセージ区切り: {}（line|content-length）", value)),
+        }
+    }
+
+    /// メッセージを区切り付きのバイト列にする
+    fn frame(self, message: &str) -> Vec<u8> {
+        match self {
+            Self::Line => format!("{}\n", message).into_bytes(),
+            Self::ContentLength => format!("Content-Length: {}\r\n\r\n{}", message.len(), message).into_bytes(),
+        }
+    }
+}
+
+/// 書き込みタスクを起動
+///
+/// # 引数
+/// * `writer` - 書き込み先（標準出力など）
+/// * `framing` - 区切り方式
+///
+/// # 戻り値
+/// * `(mpsc::Sender<String>, JoinHandle<Result<()>>)` - 送信側と書き込みタスク（送信側をすべて破棄すると終了）
+pub(crate) fn spawn_writer<W>(mut writer: W, framing: Framing) -> (mpsc::Sender<String>, JoinHandle<Result<()>>)
+where
+    W: AsyncWrite + Unpin + Send + 'static,
+{
+    let (tx, mut rx) = mpsc::channel::<String>(WRITER_QUEUE);
+    let handle = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            // 1メッセージを書き切ってから次を処理するため、並行して送信されても混ざらない
+            writer.write_all(&framing.frame(&message)).await?;
+            writer.flush().await?;
+        }
+        Ok(())
+    });
+    (tx, handle)
+}
+
+/// 読み込んだメッセージ
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Incoming {
+    /// メッセージ本文
+    Message(String),
+    /// 上限サイズを超えたため読み飛ばしたメッセージ
+    TooLarge,
+}
+
+/// メッセージを1つ読み込む
+///
+/// 上限サイズを超えるメッセージは保持せずに読み飛ばし、`Incoming::TooLarge` を返す
+///
+/// # 引数
+/// * `reader` - 読み込み元（標準入力など）
+/// * `framing` - 区切り方式
+/// * `max_bytes` - 1メッセージの最大サイズ（バイト）
+///
+/// # 戻り値
+/// * `Result<Option<Incoming>>` - メッセージ（入力の終端ではNone）、またはエラー
+pub(crate) async fn read_message<R>(reader: &mut R, framing: Framing, max_bytes: usize) -> Result<Option<Incoming>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    match framing {
+        Framing::Line => read_line_limited(reader, max_bytes).await,
+        Framing::ContentLength => {
+            let mut content_length = None;
+            loop {
+                let header = match read_line_limited(reader, max_bytes).await? {
+                    None => return Ok(None),
+                    Some(Incoming::Message(header)) => header,
+                    Some(Incoming::TooLarge) => return Err(anyhow::anyhow!("ヘッダーが大きすぎます")),
+                };
+                if header.is_empty() {
+                    // ヘッダー前の空行は読み飛ばす
+                    if content_length.is_some() {
+                        break;
+                    }
+                    continue;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("content-length") {
+                        content_length = Some(value.trim().parse::<usize>().map_err(|_| {
+                            anyhow::anyhow!("Content-Lengthヘッダーが不正です: {}", value.trim())
+                        })?);
+                    }
+                }
+            }
+
+            let content_length = content_length.unwrap_or_default();
+            if content_length > max_bytes {
+                // 本文を確保せずに読み捨て、次のメッセージの境界に合わせる
+                tokio::io::copy(&mut (&mut *reader).take(content_length as u64), &mut tokio::io::sink()).await?;
+                return Ok(Some(Incoming::TooLarge));
+            }
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body).await?;
+            Ok(Some(Incoming::Message(String::from_utf8_lossy(&body).into_owned())))
+        }
+    }
+}
+
+/// 上限サイズ付きで1行読み込む（末尾の改行は除く）
+async fn read_line_limited<R>(reader: &mut R, max_bytes: usize) -> Result<Option<Incoming>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = Vec::new();
+    let mut read_any = false;
+    let mut too_large = false;
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            break;
+        }
+        read_any = true;
+        let (chunk, done) = match available.iter().position(|&b| b == b'\n') {
+            Some(pos) => (&available[..=pos], true),
+            None => (available, false),
+        };
+        let consumed = chunk.len();
+        // 改行（\r\n）の分だけ余裕を持たせ、超えた時点で以降は保持しない
+        if !too_large && line.len() + consumed > max_bytes + 2 {
+            too_large = true;
+            line = Vec::new();
+        }
+        if !too_large {
+            line.extend_from_slice(chunk);
+        }
+        reader.consume(consumed);
+        if done {
+            break;
+        }
+    }
+
+    if !read_any {
+        return Ok(None);
+    }
+    while matches!(line.last(), Some(b'\n' | b'\r')) {
+        line.pop();
+    }
+    if too_large || line.len() > max_bytes {
+        return Ok(Some(Incoming::TooLarge));
+    }
+    Ok(Some(Incoming::Message(String::from_utf8_lossy(&line).into_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    /// 多数のタスクから同時に送信しても、各メッセージが壊れずに読み戻せる
+    async fn assert_no_interleaving(framing: Framing) {
+        let (client, server) = tokio::io::duplex(1024);
+        let (tx, writer) = spawn_writer(server, framing);
+
+        let mut senders = Vec::new();
+        for task in 0..32 {
+            let tx = tx.clone();
+            senders.push(tokio::spawn(async move {
+                for i in 0..50 {
+                    // 大きめの本文でバッファの分割書き込みを起こす
+                    let message = format!(r#"{{"id":"{}-{}","pad":"{}"}}"#, task, i, "あ".repeat(200));
+                    tx.send(message).await.unwrap();
+                }
+            }));
+        }
+        drop(tx);
+
+        let reader_task = tokio::spawn(async move {
+            let mut reader = BufReader::new(client);
+            let mut messages = Vec::new();
+            while let Some(Incoming::Message(message)) = read_message(&mut reader, framing, 4096).await.unwrap() {
+                messages.push(message);
+            }
+            messages
+        });
+
+        for sender in senders {
+            sender.await.unwrap();
+        }
+        writer.await.unwrap().unwrap();
+
+        let messages = reader_task.await.unwrap();
+        assert_eq!(messages.len(), 32 * 50);
+        for message in &messages {
+            let value: serde_json::Value = serde_json::from_str(message).unwrap();
+            assert_eq!(value["pad"].as_str().unwrap().chars().count(), 200);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_writer_serializes_concurrent_messages() {
+        assert_no_interleaving(Framing::Line).await;
+        assert_no_interleaving(Framing::ContentLength).await;
+    }
+
+    #[tokio::test]
+    async fn test_read_content_length_message() {
+        let input = "Content-Length: 16\r\nContent-Type: application/json\r\n\r\n{\"method\":\"あ\"}";
+        let mut reader = BufReader::new(input.as_bytes());
+        let message = read_message(&mut reader, Framing::ContentLength, 1024).await.unwrap();
+        assert_eq!(message, Some(Incoming::Message("{\"method\":\"あ\"}".to_string())));
+        assert_eq!(read_message(&mut reader, Framing::ContentLength, 1024).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_messages_are_skipped() {
+        let large = "x".repeat(100);
+        let input = format!("{}\n{{\"id\":1}}\n", large);
+        let mut reader = BufReader::with_capacity(16, input.as_bytes());
+        assert_eq!(read_message(&mut reader, Framing::Line, 32).await.unwrap(), Some(Incoming::TooLarge));
+        assert_eq!(
+            read_message(&mut reader, Framing::Line, 32).await.unwrap(),
+            Some(Incoming::Message("{\"id\":1}".to_string()))
+        );
+
+        let input = format!("Content-Length: 100\r\n\r\n{}Content-Length: 8\r\n\r\n{{\"id\":2}}", large);
+        let mut reader = BufReader::new(input.as_bytes());
+        assert_eq!(read_message(&mut reader, Framing::ContentLength, 32).await.unwrap(), Some(Incoming::TooLarge));
+        assert_eq!(
+            read_message(&mut reader, Framing::ContentLength, 32).await.unwrap(),
+            Some(Incoming::Message("{\"id\":2}".to_string()))
+        );
+    }
+}
@@ -0,0 +1,184 @@
+/*!
+ * リクエストの検証
+ *
+ * クライアントからの入力を検証し、JSON-RPCのエラーコード付きで拒否する
+ * - 巨大なリクエストによるメモリ枯渇を防ぐ
+ * - `/` などを指定したファイルシステム全体の解析を防ぐ
+ *
+ * 主な仕様:
+ * - パスは正規化（シンボリックリンク・`..` を解決）してから許可ディレクトリ配下かを判定
+ * - 許可ディレクトリは `server.allowed-roots`（空の場合はサーバー起動時のカレントディレクトリ）
+ * - 出力先・生成済みドキュメントのディレクトリは、存在する最も近い親を正規化して判定する（まだ作られていない出力先も指定できる）
+ *
+ * 制限事項:
+ * - 解析対象など読み込むパスは、存在しない場合は正規化できないため拒否する
+ */
+
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// JSON-RPC: JSONとして解析できない
+pub(crate) const PARSE_ERROR: i32 = -32700;
+/// JSON-RPC: リクエストとして不正
+pub(crate) const INVALID_REQUEST: i32 = -32600;
+/// JSON-RPC: メソッドが存在しない
+pub(crate) const METHOD_NOT_FOUND: i32 = -32601;
+/// JSON-RPC: パラメータが不正
+pub(crate) const INVALID_PARAMS: i32 = -32602;
+/// JSON-RPC: 内部エラー
+pub(crate) const INTERNAL_ERROR: i32 = -32603;
+/// サーバー定義: リクエストが上限サイズを超えた
+pub(crate) const REQUEST_TOO_LARGE: i32 = -32001;
+
+/// エラーコード付きのエラー
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub(crate) struct RpcError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl RpcError {
+    /// パラメータ不正のエラーを作成
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+
+    /// 不明なメソッドのエラーを作成
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: METHOD_NOT_FOUND,
+            message: format!("不明なツール: {}", method),
+        }
+    }
+}
+
+/// anyhowのエラーからJSON-RPCのエラーコードを取得
+///
+/// # 引数
+/// * `error` - エラー
+///
+/// # 戻り値
+/// * `i32` - エラーコード（`RpcError` 以外は内部エラー）
+pub(crate) fn error_code(error: &anyhow::Error) -> i32 {
+    error
+        .downcast_ref::<RpcError>()
+        .map(|e| e.code)
+        .unwrap_or(INTERNAL_ERROR)
+}
+
+/// 許可ディレクトリを正規化
+///
+/// # 引数
+/// * `allowed_roots` - 設定の許可ディレクトリ（空の場合はカレントディレクトリ）
+///
+/// # 戻り値
+/// * `Vec<PathBuf>` - 正規化した許可ディレクトリ（存在しないものは除く）
+pub(crate) fn canonical_roots(allowed_roots: &[PathBuf]) -> Vec<PathBuf> {
+    let roots = if allowed_roots.is_empty() {
+        std::env::current_dir().into_iter().collect()
+    } else {
+        allowed_roots.to_vec()
+    };
+    roots
+        .iter()
+        .filter_map(|root| match root.canonicalize() {
+            Ok(root) => Some(root),
+            Err(e) => {
+                tracing::warn!("許可ディレクトリを正規化できません（無視）: {:?}: {}", root, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// パスを正規化し、許可ディレクトリ配下かを検証
+///
+/// # 引数
+/// * `path` - クライアントが指定したパス
+/// * `roots` - 正規化済みの許可ディレクトリ
+///
+/// # 戻り値
+/// * `Result<PathBuf, RpcError>` - 正規化したパス、またはパラメータ不正のエラー
+pub(crate) fn resolve_allowed_path(path: &str, roots: &[PathBuf]) -> Result<PathBuf, RpcError> {
+    if path.trim().is_empty() {
+        return Err(RpcError::invalid_params("パスが空です"));
+    }
+    let resolved = Path::new(path)
+        .canonicalize()
+        .map_err(|e| RpcError::invalid_params(format!("パスを解決できません: {}: {}", path, e)))?;
+
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        Ok(resolved)
+    } else {
+        Err(RpcError::invalid_params(format!(
+            "許可されていないパスです: {}（server.allowed-rootsの配下を指定してください）",
+            resolved.display()
+        )))
+    }
+}
+
+/// 出力先のパスを正規化し、許可ディレクトリ配下かを検証（まだ存在しないディレクトリも指定できる）
+///
+/// # 引数
+/// * `path` - クライアントが指定した出力先
+/// * `roots` - 正規化済みの許可ディレクトリ
+///
+/// # 戻り値
+/// * `Result<PathBuf, RpcError>` - 正規化したパス、またはパラメータ不正のエラー
+pub(crate) fn resolve_allowed_output_path(path: &str, roots: &[PathBuf]) -> Result<PathBuf, RpcError> {
+    if path.trim().is_empty() || Path::new(path).exists() {
+        return resolve_allowed_path(path, roots);
+    }
+    let absolute = std::path::absolute(path)
+        .map_err(|e| RpcError::invalid_params(format!("パスを解決できません: {}: {}", path, e)))?;
+    if absolute.components().any(|c| c == std::path::Component::ParentDir) {
+        return Err(RpcError::invalid_params(format!("存在しない出力先に `..` は指定できません: {}", path)));
+    }
+    // 存在する最も近い親を正規化し、残りの部分を付け直す
+    let mut existing = absolute.as_path();
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+            break;
+        };
+        missing.push(name);
+        existing = parent;
+    }
+    let mut resolved = resolve_allowed_path(&existing.to_string_lossy(), roots)?;
+    resolved.extend(missing.iter().rev());
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_allowed_path_rejects_outside_roots() {
+        let root = std::env::temp_dir().join(format!("deeprepo-validation-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("repo")).unwrap();
+        let roots = canonical_roots(std::slice::from_ref(&root));
+
+        let inside = resolve_allowed_path(root.join("repo").to_str().unwrap(), &roots).unwrap();
+        assert!(inside.ends_with("repo"));
+
+        // `..` で許可ディレクトリの外に出る指定やルートは拒否
+        let escaped = resolve_allowed_path(root.join("repo/../..").to_str().unwrap(), &roots).unwrap_err();
+        assert_eq!(escaped.code, INVALID_PARAMS);
+        assert!(resolve_allowed_path("/", &roots).is_err());
+        assert!(resolve_allowed_path(root.join("missing").to_str().unwrap(), &roots).is_err());
+
+        // 出力先はまだ存在しなくてもよいが、許可ディレクトリの外は拒否
+        let output = resolve_allowed_output_path(root.join("repo/out/wiki").to_str().unwrap(), &roots).unwrap();
+        assert!(output.ends_with("repo/out/wiki"));
+        assert!(output.starts_with(&roots[0]));
+        assert!(resolve_allowed_output_path(root.join("repo/out/../../../escaped").to_str().unwrap(), &roots).is_err());
+        assert!(resolve_allowed_output_path("/tmp-deeprepo-outside/out", &roots).is_err());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
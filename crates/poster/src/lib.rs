@@ -0,0 +1,565 @@
+/*!
+ * アーキテクチャポスター生成実装
+ *
+ * モジュール構成図・統計・主要なフロー・担当者の表を1枚の大判ページ（HTML・PDF）にまとめる
+ * - 障害対応の作業部屋などに貼り出す印刷用で、Wiki・スライドとは別の成果物
+ * - 用紙サイズと向きは `[poster]`（既定はA3横）、CSSのページメディア（`@page`）で1ページに収める
+ *
+ * 主な仕様:
+ * - タイトルは `[branding]` の製品名・タイトル、チーム・問い合わせ先を見出しに掲載
+ * - モジュール構成図はMermaidのコードを埋め込み、ブラウザでMermaidのスクリプトが描画する
+ * - 主要なフローはエントリーポイント・APIエンドポイント（それぞれ先頭の数件）とシーケンス図
+ * - 担当者はパッケージ（パッケージ外はトップレベルのディレクトリ）ごとに、コミット数の最も多い作者をGitの履歴から推定
+ * - PDFはヘッドレスのChromium（chromium|chromium-browser|google-chrome）の印刷で出力
+ * - `security.read-only-repo` の場合、出力先が解析対象のリポジトリ内なら作業ディレクトリ（`security.workspace-dir`）配下に出力
+ * - ポスターの言語は `project.language`（ja|en）
+ *
+ * 制限事項:
+ * - Mermaidのスクリプトはネットワークから読み込むため、オフラインで開くと図はコードのまま表示される
+ * - PlantUMLの図は描画せずコードのまま掲載する
+ * - 内容が多い場合に文字を縮小して1ページに収める調整はしない（掲載する件数の上限で抑える）
+ * - Chromiumが見つからない場合・失敗した場合（時間切れ・PDFの欠けを含む）はHTMLのみを生成し、PDFをスキップした成果物として返す
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use tracing::info;
+
+use config::Config;
+use analyzer_core::{Diagnostic, Diagnostics, Index};
+use diagrammer::{Diagram, Diagrammer};
+use summarizer::{SkippedArtifact, ToolCommand, ToolFailure, Tools};
+
+mod locale;
+
+pub use locale::Locale;
+use locale::PosterText;
+
+/// PDFの出力に使うブラウザ（見つかった最初のもの）
+const BROWSERS: &[&str] = &["chromium", "chromium-browser", "google-chrome"];
+/// 主要なフローに載せるエントリーポイント数
+const MAX_ENTRYPOINTS: usize = 8;
+/// 主要なフローに載せるAPIエンドポイント数
+const MAX_ENDPOINTS: usize = 12;
+/// 担当者の表に載せる領域数（コミット数の多い順）
+const MAX_OWNER_ROWS: usize = 15;
+/// PDFの印刷前にMermaidの描画を待つ時間（ミリ秒）
+const RENDER_BUDGET_MS: u32 = 10_000;
+
+/// ポスタービルダー
+pub struct PosterBuilder {
+    config: Config,
+    diagrammer: Diagrammer,
+    /// 警告の記録先
+    diagnostics: Diagnostics,
+    /// ブラウザの実行方法（テストでは失敗を再現する実行方法に差し替える）
+    tools: Tools,
+}
+
+impl PosterBuilder {
+    /// 新しいポスタービルダーインスタンスを作成
+    ///
+    /// # 引数
+    /// * `config` - 設定
+    ///
+    /// # 戻り値
+    /// * `Self` - ポスタービルダーインスタンス
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: config.clone(),
+            diagrammer: Diagrammer::new(config),
+            diagnostics: Diagnostics::new(),
+            tools: Tools::system(),
+        }
+    }
+
+    /// 警告の記録先を指定（解析と共有して最後にまとめて表示する場合）
+    ///
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    ///
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたポスタービルダー
+    pub fn with_diagnostics(self, diagnostics: Diagnostics) -> Self {
+        Self {
+            diagrammer: self.diagrammer.with_diagnostics(diagnostics.clone()),
+            diagnostics,
+            ..self
+        }
+    }
+
+    /// 外部ツールの実行方法を指定（テストでブラウザの失敗・タイムアウトを再現する場合）
+    ///
+    /// # 引数
+    /// * `tools` - 外部ツールの実行方法
+    ///
+    /// # 戻り値
+    /// * `Self` - 実行方法を差し替えたポスタービルダー
+    pub fn with_tools(self, tools: Tools) -> Self {
+        Self { tools, ..self }
+    }
+
+    /// ポスターをビルド
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `out_dir` - 出力ディレクトリ
+    /// * `pdf` - PDFも出力するか
+    ///
+    /// # 戻り値
+    /// * `Result<PosterResult>` - 出力したファイルとスキップした成果物、またはエラー
+    pub fn build(&self, index: &Index, out_dir: impl AsRef<Path>, pdf: bool) -> Result<PosterResult> {
+        let out_dir = self.config.output_path(out_dir.as_ref());
+        info!("ポスター生成開始: {:?}", out_dir);
+        fs::create_dir_all(&out_dir).with_context(|| format!("出力ディレクトリを作成できません: {:?}", out_dir))?;
+
+        let locale = Locale::parse(&self.config.project.language)?;
+        let html_path = out_dir.join("poster.html");
+        fs::write(&html_path, self.render_html(index, locale))
+            .with_context(|| format!("ポスターを書き込めません: {:?}", html_path))?;
+        let mut files = vec![html_path.clone()];
+        let mut skipped = Vec::new();
+
+        if pdf {
+            let pdf_path = out_dir.join("poster.pdf");
+            match BROWSERS.iter().map(|name| self.tools.check(name)).find(|tool| tool.available) {
+                Some(browser) => match self.export_pdf(&browser.name, &html_path, &pdf_path)? {
+                    Ok(()) => files.push(pdf_path),
+                    Err(failure) => {
+                        self.diagnostics.warn_at("poster", &pdf_path, format!("PDFの出力に失敗したため、HTMLのみを出力しました: {}", failure));
+                        skipped.push(SkippedArtifact::failed_tool(pdf_path, &failure));
+                    }
+                },
+                None => skipped.push(SkippedArtifact::missing_tool(pdf_path, &self.tools.check(BROWSERS[0]))),
+            }
+        }
+
+        info!("ポスター生成完了: {}ファイル", files.len());
+        Ok(PosterResult { ok: true, files, skipped, diagnostics: self.diagnostics.entries() })
+    }
+
+    /// ポスターのHTMLを生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `locale` - ポスターの言語
+    ///
+    /// # 戻り値
+    /// * `String` - 1ページのHTML（CSS・Mermaidの読み込みを含む）
+    fn render_html(&self, index: &Index, locale: Locale) -> String {
+        let (width, height) = self.config.poster.page_size().unwrap_or((420, 297));
+        // A3横（長辺420mm）を基準に文字の大きさを用紙に合わせる
+        let font_size = 9.0 * width.max(height) as f64 / 420.0;
+        let title = poster_title(index, &self.config, locale);
+        let module_graph = self.diagrammer.try_generate_diagram(index, "module-graph");
+        let sequence = match index.entrypoint_details.is_empty() && index.endpoints.is_empty() {
+            true => None,
+            false => self.diagrammer.try_generate_diagram(index, "sequence"),
+        };
+
+        let mut html = String::new();
+        html.push_str(&format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n",
+            locale.code(),
+            escape_html(&title),
+            stylesheet(width, height, font_size)
+        ));
+
+        html.push_str("<header>\n");
+        html.push_str(&format!("<h1>{}</h1>\n<p class=\"subtitle\">{}</p>\n", escape_html(&title), locale.text("title.subtitle")));
+        let mut meta = Vec::new();
+        if let Some(team) = &self.config.branding.team {
+            meta.push(escape_html(team));
+        }
+        if let Some(contact) = &self.config.branding.contact {
+            meta.push(escape_html(&locale.format("title.contact", &[contact])));
+        }
+        meta.push(locale.format("title.generated", &[&chrono::Local::now().format("%Y-%m-%d")]));
+        html.push_str(&format!("<p class=\"meta\">{}</p>\n</header>\n", meta.join(" ・ ")));
+
+        html.push_str("<main>\n<section class=\"panel graph\">\n");
+        html.push_str(&format!("<h2>{}</h2>\n", locale.text("graph.title")));
+        match &module_graph {
+            Some(diagram) => html.push_str(&render_diagram(diagram)),
+            None => html.push_str(&format!("<p class=\"empty\">{}</p>\n", locale.text("graph.none"))),
+        }
+        html.push_str("</section>\n<div class=\"side\">\n");
+        html.push_str(&render_stats(index, locale));
+        html.push_str(&render_flows(index, sequence.as_ref(), locale));
+        html.push_str(&render_owners(&owner_table(index), locale));
+        html.push_str("</div>\n</main>\n");
+
+        if let Some(footer) = self.config.branding.footer(&self.config.project.name) {
+            html.push_str(&format!("<footer>{}</footer>\n", escape_html(&footer)));
+        }
+        if [&module_graph, &sequence].iter().any(|d| d.as_ref().is_some_and(|d| d.format == "mermaid")) {
+            html.push_str(
+                "<script src=\"https://cdn.jsdelivr.net/npm/mermaid@10/dist/mermaid.min.js\"></script>\n\
+                 <script>mermaid.initialize({ startOnLoad: true });</script>\n",
+            );
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// ヘッドレスのブラウザでHTMLをPDFに印刷
+    ///
+    /// # 引数
+    /// * `browser` - ブラウザのコマンド名
+    /// * `html` - ポスターのHTML
+    /// * `pdf` - 出力するPDF
+    ///
+    /// # 戻り値
+    /// * `Result<Result<(), ToolFailure>>` - 印刷の結果（ブラウザの失敗・時間切れ・PDFの欠け）、HTMLが見つからない場合はエラー
+    fn export_pdf(&self, browser: &str, html: &Path, pdf: &Path) -> Result<Result<(), ToolFailure>> {
+        let html = fs::canonicalize(html).with_context(|| format!("ポスターが見つかりません: {:?}", html))?;
+        let command = ToolCommand::new(browser)
+            .arg("--headless")
+            .arg("--disable-gpu")
+            .arg("--no-pdf-header-footer")
+            .arg(format!("--virtual-time-budget={}", RENDER_BUDGET_MS))
+            .arg(format!("--print-to-pdf={}", pdf.display()))
+            .arg(format!("file://{}", html.display()));
+        Ok(self.tools.run(&command, pdf).map(|_| ()))
+    }
+}
+
+/// ポスタービルド結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PosterResult {
+    pub ok: bool,
+    /// 出力したファイル（poster.html、PDFを出力した場合はposter.pdf）
+    pub files: Vec<PathBuf>,
+    /// 外部ツールがない・失敗したために生成しなかった成果物
+    #[serde(default)]
+    pub skipped: Vec<SkippedArtifact>,
+    /// ビルド中に記録した警告（共有した記録先の場合は解析などの警告を含む）
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 担当者の表の1行（パッケージ、またはトップレベルのディレクトリ）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OwnerRow {
+    /// 領域（パッケージ名、パッケージ外はトップレベルのディレクトリ、ルート直下のファイルは `/`）
+    pub area: String,
+    pub files: usize,
+    /// 領域のファイルのコミット数の合計
+    pub commits: usize,
+    /// コミット数の最も多い作者
+    pub owner: String,
+    /// 主な担当者のコミット数
+    pub owner_commits: usize,
+    /// 最終更新日時（UNIX時間）
+    pub last_modified: i64,
+}
+
+/// パッケージ・トップレベルのディレクトリごとに担当者を推定
+///
+/// # 引数
+/// * `index` - インデックス（Gitの履歴を含むもの）
+///
+/// # 戻り値
+/// * `Vec<OwnerRow>` - 履歴のある領域（コミット数の多い順、同数は領域名の順）
+pub fn owner_table(index: &Index) -> Vec<OwnerRow> {
+    let mut areas: HashMap<String, AreaHistory> = HashMap::new();
+    for file in &index.files {
+        let Some(history) = &file.history else {
+            continue;
+        };
+        let area = match index.package_of(&file.path) {
+            Some(package) => package.name.clone(),
+            None => top_level_dir(&index.repo_path, &file.path),
+        };
+        let totals = areas.entry(area).or_default();
+        totals.files += 1;
+        totals.commits += history.commits;
+        totals.last_modified = totals.last_modified.max(history.last_modified);
+        for author in &history.authors {
+            *totals.authors.entry(author.name.clone()).or_default() += author.commits;
+        }
+    }
+
+    let mut rows: Vec<OwnerRow> = areas
+        .into_iter()
+        .filter_map(|(area, totals)| {
+            // 同数の作者は名前の順で決める
+            let (owner, owner_commits) = totals.authors.into_iter().max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))?;
+            Some(OwnerRow {
+                area,
+                files: totals.files,
+                commits: totals.commits,
+                owner,
+                owner_commits,
+                last_modified: totals.last_modified,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.area.cmp(&b.area)));
+    rows
+}
+
+/// 領域ごとの履歴の集計
+#[derive(Default)]
+struct AreaHistory {
+    files: usize,
+    commits: usize,
+    /// 作者 → コミット数
+    authors: HashMap<String, usize>,
+    last_modified: i64,
+}
+
+/// ファイルのトップレベルのディレクトリ（ルート直下のファイルは `/`）
+fn top_level_dir(repo_path: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(repo_path).unwrap_or(path);
+    let mut components = relative.components();
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => format!("{}/", first.as_os_str().to_string_lossy()),
+        _ => "/".to_string(),
+    }
+}
+
+/// ポスターのタイトル（`[branding]` の製品名・タイトルを優先し、未指定の場合はリポジトリのディレクトリ名）
+fn poster_title(index: &Index, config: &Config, locale: Locale) -> String {
+    let branding = &config.branding;
+    if branding.product_name.is_some() || branding.title.is_some() {
+        return branding.title(&config.project.name);
+    }
+    index.repo_path.file_name().and_then(|n| n.to_str()).unwrap_or(locale.text("title.fallback")).to_string()
+}
+
+/// 統計のパネル
+fn render_stats(index: &Index, locale: Locale) -> String {
+    let lines: usize = index.files.iter().map(|f| f.metrics.lines).sum();
+    let numbers = [
+        ("stats.files", index.stats.files),
+        ("stats.lines", lines),
+        ("stats.modules", index.stats.modules),
+        ("stats.packages", index.packages.len()),
+        ("stats.endpoints", index.endpoints.len()),
+    ];
+    let mut html = format!("<section class=\"panel stats\">\n<h2>{}</h2>\n<dl>\n", locale.text("stats.title"));
+    for (key, value) in numbers {
+        html.push_str(&format!("<div><dt>{}</dt><dd>{}</dd></div>\n", locale.text(key), value));
+    }
+    html.push_str("</dl>\n");
+    if !index.stats.languages.is_empty() {
+        html.push_str(&format!(
+            "<p>{}: {}</p>\n",
+            locale.text("stats.languages"),
+            escape_html(&index.stats.languages.join(", "))
+        ));
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+/// 主要なフローのパネル（エントリーポイント・APIエンドポイント・シーケンス図）
+fn render_flows(index: &Index, sequence: Option<&Diagram>, locale: Locale) -> String {
+    let mut html = format!("<section class=\"panel flows\">\n<h2>{}</h2>\n", locale.text("flows.title"));
+    if index.entrypoint_details.is_empty() && index.endpoints.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n</section>\n", locale.text("flows.none")));
+        return html;
+    }
+    let relative = |path: &Path| path.strip_prefix(&index.repo_path).unwrap_or(path).display().to_string();
+
+    if !index.entrypoint_details.is_empty() {
+        html.push_str(&format!("<h3>{}</h3>\n<ul>\n", locale.text("flows.entrypoints")));
+        for entrypoint in index.entrypoint_details.iter().take(MAX_ENTRYPOINTS) {
+            let kind = match locale {
+                Locale::Ja => entrypoint.kind_label(),
+                Locale::En => entrypoint.kind.as_str(),
+            };
+            html.push_str(&format!("<li><code>{}</code> — {}</li>\n", escape_html(&relative(&entrypoint.path)), escape_html(kind)));
+        }
+        html.push_str(&more_item(index.entrypoint_details.len(), MAX_ENTRYPOINTS, locale));
+        html.push_str("</ul>\n");
+    }
+    if !index.endpoints.is_empty() {
+        html.push_str(&format!("<h3>{}</h3>\n<table>\n", locale.text("flows.endpoints")));
+        for endpoint in index.endpoints.iter().take(MAX_ENDPOINTS) {
+            html.push_str(&format!(
+                "<tr><td class=\"method\">{}</td><td><code>{}</code></td><td>{}</td></tr>\n",
+                escape_html(&endpoint.method),
+                escape_html(&endpoint.path),
+                escape_html(endpoint.handler.as_deref().unwrap_or("-"))
+            ));
+        }
+        html.push_str("</table>\n");
+        if index.endpoints.len() > MAX_ENDPOINTS {
+            html.push_str(&format!("<p class=\"more\">{}</p>\n", locale.format("flows.more", &[&(index.endpoints.len() - MAX_ENDPOINTS)])));
+        }
+    }
+    if let Some(diagram) = sequence {
+        html.push_str(&format!("<h3>{}</h3>\n", locale.text("flows.sequence")));
+        html.push_str(&render_diagram(diagram));
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+/// 担当者のパネル
+fn render_owners(rows: &[OwnerRow], locale: Locale) -> String {
+    let mut html = format!("<section class=\"panel owners\">\n<h2>{}</h2>\n", locale.text("owners.title"));
+    if rows.is_empty() {
+        html.push_str(&format!("<p class=\"empty\">{}</p>\n</section>\n", locale.text("owners.none")));
+        return html;
+    }
+    let header = ["owners.header.area", "owners.header.files", "owners.header.commits", "owners.header.owner", "owners.header.updated"];
+    html.push_str("<table>\n<tr>");
+    for key in header {
+        html.push_str(&format!("<th>{}</th>", locale.text(key)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows.iter().take(MAX_OWNER_ROWS) {
+        let updated = chrono::DateTime::from_timestamp(row.last_modified, 0).map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default();
+        html.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{} ({}%)</td><td>{}</td></tr>\n",
+            escape_html(&row.area),
+            row.files,
+            row.commits,
+            escape_html(&row.owner),
+            row.owner_commits * 100 / row.commits.max(1),
+            updated
+        ));
+    }
+    html.push_str("</table>\n");
+    if rows.len() > MAX_OWNER_ROWS {
+        html.push_str(&format!("<p class=\"more\">{}</p>\n", locale.format("flows.more", &[&(rows.len() - MAX_OWNER_ROWS)])));
+    }
+    html.push_str("</section>\n");
+    html
+}
+
+/// 図の埋め込み（MermaidはMermaidのスクリプトが描画する要素、それ以外はコード）
+fn render_diagram(diagram: &Diagram) -> String {
+    let class = if diagram.format == "mermaid" { "mermaid" } else { "diagram-source" };
+    let mut html = format!("<pre class=\"{}\">\n{}</pre>\n", class, escape_html(&diagram.content));
+    if !diagram.scope.is_empty() {
+        html.push_str(&format!("<p class=\"caption\">{}</p>\n", escape_html(&diagram.scope)));
+    }
+    html
+}
+
+/// 省略したエントリーポイントの件数の項目（省略していない場合は空）
+fn more_item(total: usize, shown: usize, locale: Locale) -> String {
+    match total > shown {
+        true => format!("<li class=\"more\">{}</li>\n", locale.format("flows.more", &[&(total - shown)])),
+        false => String::new(),
+    }
+}
+
+/// 用紙サイズに合わせたスタイルシート
+///
+/// # 引数
+/// * `width` - 用紙の幅（mm）
+/// * `height` - 用紙の高さ（mm）
+/// * `font_size` - 本文の文字の大きさ（pt）
+///
+/// # 戻り値
+/// * `String` - CSS（`@page` で用紙サイズと余白を指定）
+fn stylesheet(width: u32, height: u32, font_size: f64) -> String {
+    format!(
+        "@page {{ size: {w}mm {h}mm; margin: 10mm; }}\n\
+         html {{ font-size: {f:.1}pt; }}\n\
+         body {{ margin: 0 auto; width: {bw}mm; min-height: {bh}mm; font-family: sans-serif; color: #202124; \
+         display: flex; flex-direction: column; -webkit-print-color-adjust: exact; print-color-adjust: exact; }}\n\
+         header {{ border-bottom: 0.6mm solid #1a73e8; margin-bottom: 4mm; }}\n\
+         h1 {{ font-size: 2.6rem; margin: 0; }}\n\
+         h2 {{ font-size: 1.4rem; margin: 0 0 2mm; color: #1a73e8; }}\n\
+         h3 {{ font-size: 1.1rem; margin: 3mm 0 1mm; }}\n\
+         .subtitle {{ font-size: 1.3rem; margin: 1mm 0; }}\n\
+         .meta, .caption, .more {{ color: #5f6368; }}\n\
+         main {{ flex: 1; display: grid; grid-template-columns: 3fr 2fr; gap: 5mm; }}\n\
+         .side {{ display: flex; flex-direction: column; gap: 5mm; }}\n\
+         .panel {{ border: 0.3mm solid #dadce0; border-radius: 2mm; padding: 4mm; break-inside: avoid; }}\n\
+         .stats dl {{ display: grid; grid-template-columns: repeat(5, 1fr); margin: 0; }}\n\
+         .stats dt {{ color: #5f6368; }}\n\
+         .stats dd {{ font-size: 1.8rem; font-weight: bold; margin: 0; }}\n\
+         table {{ border-collapse: collapse; width: 100%; }}\n\
+         th, td {{ border-bottom: 0.2mm solid #dadce0; padding: 0.8mm 1.5mm; text-align: left; }}\n\
+         .method {{ font-weight: bold; }}\n\
+         pre.mermaid {{ background: none; text-align: center; }}\n\
+         pre.diagram-source {{ font-size: 0.7rem; white-space: pre-wrap; }}\n\
+         footer {{ margin-top: 4mm; color: #5f6368; }}\n",
+        w = width,
+        h = height,
+        f = font_size,
+        bw = width.saturating_sub(20),
+        bh = height.saturating_sub(20)
+    )
+}
+
+/// HTMLの特殊文字をエスケープ
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analyzer_core::{ApiEndpoint, AuthorCommits, Entrypoint, FileHistory, FileInfo};
+
+    fn file(path: &str, commits: &[(&str, usize)], last_modified: i64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(path),
+            history: Some(FileHistory {
+                commits: commits.iter().map(|(_, n)| n).sum(),
+                authors: commits.iter().map(|(name, n)| AuthorCommits { name: name.to_string(), commits: *n }).collect(),
+                last_modified,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_owner_table_groups_by_top_level_dir() {
+        let index = Index {
+            repo_path: PathBuf::from("/r"),
+            files: vec![
+                file("/r/api/routes.py", &[("alice", 5), ("bob", 2)], 100),
+                file("/r/api/models.py", &[("bob", 4)], 300),
+                file("/r/web/app.ts", &[("carol", 3)], 200),
+                file("/r/setup.py", &[("dave", 1)], 50),
+                FileInfo { path: PathBuf::from("/r/docs/readme.md"), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        let rows = owner_table(&index);
+        let summary: Vec<(&str, usize, usize, &str, usize, i64)> =
+            rows.iter().map(|r| (r.area.as_str(), r.files, r.commits, r.owner.as_str(), r.owner_commits, r.last_modified)).collect();
+        assert_eq!(summary, vec![("api/", 2, 11, "bob", 6, 300), ("web/", 1, 3, "carol", 3, 200), ("/", 1, 1, "dave", 1, 50)]);
+    }
+
+    #[test]
+    fn test_render_html_lays_out_panels_on_one_page() {
+        let mut config = Config::default();
+        config.project.language = "en".to_string();
+        config.poster.size = "A2".to_string();
+        let index = Index {
+            repo_path: PathBuf::from("/r/shop"),
+            files: vec![file("/r/shop/api/routes.py", &[("alice", 5)], 0)],
+            entrypoint_details: vec![Entrypoint { path: PathBuf::from("/r/shop/main.py"), kind: "fastapi".to_string(), ..Default::default() }],
+            endpoints: vec![ApiEndpoint {
+                method: "GET".to_string(),
+                path: "/items/<id>".to_string(),
+                handler: Some("get_item".to_string()),
+                file: PathBuf::from("/r/shop/api/routes.py"),
+                line: 3,
+                framework: "fastapi".to_string(),
+            }],
+            ..Default::default()
+        };
+        let html = PosterBuilder::new(config).render_html(&index, Locale::En);
+        assert!(html.contains("@page { size: 594mm 420mm; margin: 10mm; }"), "{}", html);
+        assert!(html.contains("<h1>shop</h1>"), "{}", html);
+        assert!(html.contains("<h2>Key flows</h2>"), "{}", html);
+        assert!(html.contains("<li><code>main.py</code> — fastapi</li>"), "{}", html);
+        assert!(html.contains("<code>/items/&lt;id&gt;</code>"), "{}", html);
+        assert!(html.contains("<td><code>api/</code></td><td>1</td><td>5</td><td>alice (100%)</td>"), "{}", html);
+    }
+}
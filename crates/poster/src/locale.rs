@@ -0,0 +1,81 @@
+/*!
+ * ポスターの言語
+ *
+ * ポスターに書く固定の文言（パネルの見出し・表の列名）を言語ごとのカタログから引く
+ *
+ * 主な仕様:
+ * - 言語は `project.language`（ja|en）、言語（`Locale`）は要約・Wiki・スライドと共有
+ * - 文言はキーで引き、`{}` は引数で順に置き換える
+ * - カタログにないキーはキーをそのまま返す
+ *
+ * 制限事項:
+ * - 図のラベル・エントリーポイントの種類の説明は翻訳しない
+ */
+
+use std::fmt::Display;
+
+use summarizer::Catalog;
+pub use summarizer::Locale;
+
+/// 文言のカタログ（キー、日本語、英語）
+const CATALOG: &Catalog = &[
+    ("title.fallback", "プロジェクト", "Project"),
+    ("title.subtitle", "アーキテクチャ概要", "Architecture at a glance"),
+    ("title.generated", "生成日: {}", "Generated: {}"),
+    ("title.contact", "問い合わせ: {}", "Contact: {}"),
+    ("stats.title", "統計", "Stats"),
+    ("stats.files", "ファイル", "Files"),
+    ("stats.lines", "行", "Lines"),
+    ("stats.modules", "モジュール", "Modules"),
+    ("stats.packages", "パッケージ", "Packages"),
+    ("stats.endpoints", "APIエンドポイント", "API endpoints"),
+    ("stats.languages", "言語", "Languages"),
+    ("graph.title", "モジュール構成", "Module graph"),
+    ("graph.none", "モジュール構成図を生成できませんでした。", "The module graph could not be generated."),
+    ("flows.title", "主要なフロー", "Key flows"),
+    ("flows.entrypoints", "エントリーポイント", "Entry points"),
+    ("flows.endpoints", "APIエンドポイント", "API endpoints"),
+    ("flows.sequence", "シーケンス", "Sequence"),
+    ("flows.none", "エントリーポイント・APIエンドポイントが見つかりませんでした。", "No entry points or API endpoints were found."),
+    ("flows.more", "ほか{}件", "and {} more"),
+    ("owners.title", "担当者", "Owners"),
+    ("owners.header.area", "領域", "Area"),
+    ("owners.header.files", "ファイル", "Files"),
+    ("owners.header.commits", "コミット", "Commits"),
+    ("owners.header.owner", "主な担当者", "Main owner"),
+    ("owners.header.updated", "最終更新", "Last updated"),
+    ("owners.none", "Gitの履歴がないため担当者を推定できませんでした。", "Owners could not be inferred because there is no Git history."),
+];
+
+/// ポスターの文言
+pub(crate) trait PosterText {
+    /// 文言を取得（カタログにない場合はキー）
+    fn text(self, key: &str) -> &str;
+    /// 引数を `{}` に順に埋め込んだ文言を取得
+    fn format(self, key: &str, args: &[&dyn Display]) -> String;
+}
+
+impl PosterText for Locale {
+    fn text(self, key: &str) -> &str {
+        self.lookup(CATALOG, key)
+    }
+
+    fn format(self, key: &str, args: &[&dyn Display]) -> String {
+        self.lookup_format(CATALOG, key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_covers_languages() {
+        for (key, ja, en) in CATALOG {
+            assert!(!ja.is_empty() && !en.is_empty(), "{}", key);
+            assert_eq!(ja.matches("{}").count(), en.matches("{}").count(), "{}", key);
+        }
+        assert_eq!(Locale::En.format("flows.more", &[&3]), "and 3 more");
+        assert_eq!(Locale::Ja.text("owners.title"), "担当者");
+    }
+}
@@ -0,0 +1,38 @@
+//! ブラウザの失敗・タイムアウト・PDFの欠けでポスターがHTMLのみの出力に切り替わることの確認
+
+use analyzer_core::Index;
+use config::Config;
+use poster::PosterBuilder;
+use summarizer::{FakeRun, FakeTools, Tools};
+
+#[test]
+fn test_poster_keeps_html_when_browser_fails() {
+    for run in [FakeRun::failure("[ERROR:headless_shell.cc] Failed to print"), FakeRun::timeout(), FakeRun::success()] {
+        let out = tempfile::tempdir().unwrap();
+        let tools = FakeTools::new().with("google-chrome", run);
+        let result = PosterBuilder::new(Config::default())
+            .with_tools(Tools::new(tools.clone()))
+            .build(&Index::default(), out.path(), true)
+            .unwrap();
+        assert!(result.ok);
+        assert_eq!(result.files, vec![out.path().join("poster.html")]);
+        assert_eq!((result.skipped[0].path.clone(), result.skipped[0].tool.as_str()), (out.path().join("poster.pdf"), "google-chrome"));
+        assert_eq!(tools.calls()[0].program, "google-chrome");
+        assert!(result.diagnostics.iter().any(|d| d.message.starts_with("PDFの出力に失敗したため")));
+    }
+}
+
+#[test]
+fn test_poster_prints_pdf_with_first_browser() {
+    let out = tempfile::tempdir().unwrap();
+    let tools = FakeTools::new()
+        .with("chromium-browser", FakeRun::success().writing(out.path().join("poster.pdf")))
+        .with("google-chrome", FakeRun::failure("unused"));
+    let result = PosterBuilder::new(Config::default())
+        .with_tools(Tools::new(tools.clone()))
+        .build(&Index::default(), out.path(), true)
+        .unwrap();
+    assert_eq!(result.files.len(), 2);
+    assert!(result.skipped.is_empty());
+    assert!(tools.calls()[0].args.iter().any(|a| a.starts_with("--print-to-pdf=")));
+}
@@ -1,34 +1,60 @@
-/**
+/*!
  * GitHub Pages公開実装
  * 
  * 生成されたWikiやスライドをGitHub Pagesに公開する
  * - docs/モード: /docsディレクトリにコピー
  * - gh-pagesモード: gh-pagesブランチにコミット・プッシュ
  * - GitHub Actions YAMLの自動生成
+ * - 完了時のWebhook通知（notifyモジュール）
+ * - 同時実行の排他（lockモジュール）と、内容が変わらない場合の公開の省略
+ * - 前回の公開から変わったWikiのページの一覧（whats_newモジュール、`publish.whats-new`）
+ * - 公開前の縮小と参照されないテーマのファイルの削除（optimizeモジュール、`publish.optimize`）
+ * - `security.read-only-repo` の場合はリポジトリに書き込まない（docs/・Actions YAML・ロックは作業ディレクトリに出力）
  * 
  * 主な仕様:
- * - docs/モードはローカルでファイルをコピー
- * - gh-pagesモードはgit操作でブランチを更新
+ * - サイトとスライドを一時ディレクトリにまとめ、内容のハッシュ（SHA-256）を計算
+ * - docs/モードはハッシュが既存のdocs/と同じならコピーしない
+ * - gh-pagesモードは作業ツリーを変更せずにツリーを作成し、ブランチ先端と同じならコミットしない
+ * - ブランチは取得時の先端と一致する場合のみ更新（他の更新と競合した場合は再試行）
+ * - 変更がない場合も成功とし、`status = "unchanged"` を返す
  * - Actions YAMLは任意で生成
+ * - 公開先URLは設定値、なければoriginリモートから推定
  * 
  * 制限事項:
- * - gh-pagesモードはgit操作が必要（認証情報が必要な場合あり）
+ * - gh-pagesモードはローカルのブランチを更新するのみ（プッシュは別途必要）
+ * - 公開先ブランチをチェックアウト中の場合、作業ツリーは更新されない
+ * - docs/にのみ存在する古いファイルは削除しない
  * - Actions YAMLはテンプレートベース
+ * - `security.read-only-repo` の場合、gh-pagesモードはブランチを作成できないためエラー
  */
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::process::Command;
+use std::time::Duration;
 use anyhow::{Context, Result};
-use tracing::info;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
 
 use config::Config;
-use git2::Repository;
+use git2::{Oid, Repository, Signature};
+
+mod lock;
+mod notify;
+mod optimize;
+mod whats_new;
+
+use lock::PublishLock;
+pub use optimize::{KindSize, OptimizeReport};
+pub use notify::{BuildSummary, Notifier};
+pub use whats_new::PageChange;
+
+/// ブランチの比較更新が競合した場合の試行回数
+const BRANCH_UPDATE_ATTEMPTS: usize = 3;
 
 /// パブリッシャー
 pub struct Publisher {
-    #[allow(dead_code)]
     config: Config,
 }
 
@@ -65,9 +91,24 @@ impl Publisher {
     ) -> Result<PublishResult> {
         info!("GitHub Pages公開開始: mode={}", mode);
 
+        let lock_path = self.output_path(repo_root, &lock_path(repo_root));
+        if let Some(parent) = lock_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = PublishLock::acquire(
+            &lock_path,
+            Duration::from_secs(self.config.publish.lock_wait_secs),
+            Duration::from_secs(self.config.publish.lock_stale_secs),
+        )
+        .await?;
+
+        // read-only-repoの場合、リポジトリ内を指す生成済みのWiki・スライドは作業ディレクトリに出力されている
+        let site_dir = self.output_path(repo_root, Path::new(site_dir)).to_string_lossy().into_owned();
+        let slides_dir = self.output_path(repo_root, Path::new(slides_dir)).to_string_lossy().into_owned();
+
         match mode {
-            "docs" => self.publish_docs(site_dir, slides_dir, repo_root).await,
-            "gh-pages" => self.publish_gh_pages(site_dir, slides_dir, repo_root, branch).await,
+            "docs" => self.publish_docs(&site_dir, &slides_dir, repo_root).await,
+            "gh-pages" => self.publish_gh_pages(&site_dir, &slides_dir, repo_root, branch).await,
             _ => Err(anyhow::anyhow!("不明なモード: {}", mode)),
         }
     }
@@ -89,31 +130,48 @@ impl Publisher {
     ) -> Result<PublishResult> {
         info!("docs/モードで公開中...");
 
-        let repo_path = PathBuf::from(repo_root);
-        let docs_dir = repo_path.join("docs");
-
-        // docs/ディレクトリを作成
-        fs::create_dir_all(&docs_dir)?;
-
-        // サイトをコピー
-        let site_source = PathBuf::from(site_dir);
-        if site_source.exists() {
-            self.copy_directory(&site_source, &docs_dir)?;
-            info!("サイトをdocs/にコピーしました");
-        }
-
-        // スライドをコピー（オプション）
-        let slides_source = PathBuf::from(slides_dir);
-        if slides_source.exists() {
-            let slides_dest = docs_dir.join("slides");
-            fs::create_dir_all(&slides_dest)?;
-            self.copy_directory(&slides_source, &slides_dest)?;
-            info!("スライドをdocs/slides/にコピーしました");
+        let docs_dir = self.output_path(repo_root, &Path::new(repo_root).join("docs"));
+
+        let staging = tempfile::tempdir()?;
+        self.stage_content(site_dir, slides_dir, staging.path())?;
+        let optimization = self.optimize(staging.path())?;
+        let page_changes = match self.config.publish.whats_new {
+            true => self.write_whats_new(staging.path(), previous_docs_publish(&docs_dir, staging.path())?)?,
+            false => Vec::new(),
+        };
+        let files = list_files(staging.path())?;
+        let content_hash = content_hash(staging.path(), &files)?;
+
+        if content_hash == content_hash_of_existing(&docs_dir, &files)? {
+            info!("docs/は最新のため公開を省略しました: {}", content_hash);
+            return Ok(PublishResult {
+                ok: true,
+                status: PublishStatus::Unchanged,
+                hint: "公開する変更はありません（docs/は最新です）。".to_string(),
+                url: self.site_url(repo_root),
+                content_hash,
+                page_changes: Vec::new(),
+                optimization,
+            });
         }
 
+        fs::create_dir_all(&docs_dir)?;
+        self.copy_directory(staging.path(), &docs_dir)?;
+        info!("サイトとスライドを{:?}にコピーしました", docs_dir);
+
+        let hint = if self.config.security.read_only_repo {
+            format!("リポジトリには書き込まず、{:?} に出力しました（security.read-only-repo）。", docs_dir)
+        } else {
+            "リポジトリの設定で、GitHub Pagesのソースを 'main /docs' に設定してください。".to_string()
+        };
         Ok(PublishResult {
             ok: true,
-            hint: "リポジトリの設定で、GitHub Pagesのソースを 'main /docs' に設定してください。".to_string(),
+            status: PublishStatus::Published,
+            hint,
+            url: self.site_url(repo_root),
+            content_hash,
+            page_changes,
+            optimization,
         })
     }
 
@@ -138,32 +196,141 @@ impl Publisher {
 
         let repo = Repository::open(repo_root)
             .with_context(|| format!("リポジトリを開けませんでした: {}", repo_root))?;
+        self.config.security.ensure_writable(
+            Path::new(repo_root),
+            repo.path(),
+            "gh-pagesブランチへの公開（docsモードは作業ディレクトリに出力します）",
+        )?;
+
+        let staging = tempfile::tempdir()?;
+        self.stage_content(site_dir, slides_dir, staging.path())?;
+        let optimization = self.optimize(staging.path())?;
+        let page_changes = match self.config.publish.whats_new {
+            true => self.write_whats_new(staging.path(), previous_branch_publish(&repo, branch)?)?,
+            false => Vec::new(),
+        };
+        let content_hash = content_hash(staging.path(), &list_files(staging.path())?)?;
+
+        if !self.commit_to_branch(&repo, branch, staging.path(), &content_hash)? {
+            info!("{}ブランチは最新のため公開を省略しました: {}", branch, content_hash);
+            return Ok(PublishResult {
+                ok: true,
+                status: PublishStatus::Unchanged,
+                hint: format!("公開する変更はありません（ブランチ '{}' は最新です）。", branch),
+                url: self.site_url(repo_root),
+                content_hash,
+                page_changes: Vec::new(),
+                optimization,
+            });
+        }
 
-        // 作業ディレクトリを一時的に作成
-        let temp_dir = tempfile::tempdir()?;
-        let temp_path = temp_dir.path();
+        Ok(PublishResult {
+            ok: true,
+            status: PublishStatus::Published,
+            hint: format!("gh-pagesブランチに公開しました。GitHub Pagesの設定でブランチ '{}' を選択してください。", branch),
+            url: self.site_url(repo_root),
+            content_hash,
+            page_changes,
+            optimization,
+        })
+    }
+
+    /// 書き込み先のパス（`security.read-only-repo` の場合、リポジトリ内のパスは作業ディレクトリ配下に置き換える）
+    fn output_path(&self, repo_root: &str, path: &Path) -> PathBuf {
+        self.config.security.output_path(Path::new(repo_root), path)
+    }
 
-        // サイトをコピー
+    /// 公開先URLを取得
+    /// 
+    /// # 引数
+    /// * `repo_root` - リポジトリルート
+    /// 
+    /// # 戻り値
+    /// * `Option<String>` - 設定値、またはoriginリモートから推定したGitHub PagesのURL
+    fn site_url(&self, repo_root: &str) -> Option<String> {
+        if let Some(url) = &self.config.publish.site_url {
+            return Some(url.clone());
+        }
+        let repo = Repository::discover(repo_root).ok()?;
+        let remote = repo.find_remote("origin").ok()?;
+        github_pages_url(remote.url()?)
+    }
+
+    /// 公開する内容を最適化（`publish.optimize = false` の場合は何もしない）
+    ///
+    /// # 引数
+    /// * `staging` - 公開する内容をまとめたディレクトリ
+    ///
+    /// # 戻り値
+    /// * `Result<Option<OptimizeReport>>` - 最適化のレポート（最適化しない場合はNone）、またはエラー
+    fn optimize(&self, staging: &Path) -> Result<Option<OptimizeReport>> {
+        if !self.config.publish.optimize {
+            return Ok(None);
+        }
+        let report = optimize::optimize_site(staging)?;
+        info!(
+            "公開する内容を最適化しました: {} → {} バイト（削除したファイル: {}件）",
+            report.before_bytes,
+            report.after_bytes,
+            report.removed.len()
+        );
+        Ok(Some(report))
+    }
+
+    /// 公開する内容を1つのディレクトリにまとめる
+    /// 
+    /// # 引数
+    /// * `site_dir` - サイトディレクトリ（直下に配置）
+    /// * `slides_dir` - スライドディレクトリ（`slides/` に配置）
+    /// * `dest` - まとめ先のディレクトリ
+    /// 
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    fn stage_content(&self, site_dir: &str, slides_dir: &str, dest: &Path) -> Result<()> {
         let site_source = PathBuf::from(site_dir);
         if site_source.exists() {
-            self.copy_directory(&site_source, temp_path)?;
+            self.copy_directory(&site_source, dest)?;
         }
 
-        // スライドをコピー
         let slides_source = PathBuf::from(slides_dir);
         if slides_source.exists() {
-            let slides_dest = temp_path.join("slides");
+            let slides_dest = dest.join("slides");
             fs::create_dir_all(&slides_dest)?;
             self.copy_directory(&slides_source, &slides_dest)?;
         }
+        Ok(())
+    }
 
-        // gh-pagesブランチにコミット
-        self.commit_to_branch(&repo, branch, temp_path).await?;
+    /// 今回の更新内容のページをまとめ先に書き出す
+    /// 
+    /// ページの変更がない場合は前回のページをそのまま使う（内容が同じなら公開を省略できるように）。
+    /// 
+    /// # 引数
+    /// * `staging` - 公開する内容をまとめたディレクトリ
+    /// * `previous` - 前回の公開（初回の公開の場合はNone）
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<PageChange>>` - 前回の公開から変わったページ（Wikiのページがない場合は空）、またはエラー
+    fn write_whats_new(&self, staging: &Path, previous: Option<PreviousPublish>) -> Result<Vec<PageChange>> {
+        let mut current = BTreeMap::new();
+        for path in list_files(staging)?.into_iter().filter(|p| whats_new::is_wiki_page(p)) {
+            let bytes = fs::read(staging.join(&path))?;
+            current.insert(path, bytes);
+        }
+        if current.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        Ok(PublishResult {
-            ok: true,
-            hint: format!("gh-pagesブランチに公開しました。GitHub Pagesの設定でブランチ '{}' を選択してください。", branch),
-        })
+        let changes = whats_new::diff_pages(previous.as_ref().map_or(&BTreeMap::new(), |p| &p.pages), &current);
+        let page = match previous.as_ref().and_then(|p| p.page.clone()) {
+            Some(page) if changes.is_empty() => page,
+            _ => {
+                let generated = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+                whats_new::render_page(&changes, previous.as_ref().map(|p| p.label.as_str()), &generated).into_bytes()
+            }
+        };
+        fs::write(staging.join(whats_new::PAGE_PATH), page)?;
+        Ok(changes)
     }
 
     /// ディレクトリをコピー
@@ -204,73 +371,60 @@ impl Publisher {
 
     /// ブランチにコミット
     /// 
+    /// 作業ツリーやHEADは変更せず、内容からツリーを作成してブランチの参照のみを更新する。
+    /// 
     /// # 引数
     /// * `repo` - リポジトリ
     /// * `branch` - ブランチ名
     /// * `content_dir` - コンテンツディレクトリ
+    /// * `content_hash` - 内容のハッシュ（コミットメッセージに記録）
     /// 
     /// # 戻り値
-    /// * `Result<()>` - 成功、またはエラー
-    async fn commit_to_branch(
+    /// * `Result<bool>` - コミットした場合true（ブランチ先端と内容が同じ場合false）、またはエラー
+    fn commit_to_branch(
         &self,
         repo: &Repository,
         branch: &str,
         content_dir: &Path,
-    ) -> Result<()> {
-        // 簡易実装: gitコマンドを使用してブランチにコミット
-        // 実際のプロダクション実装では、git2のAPIを使用して適切に実装する必要がある
-        
-        let repo_path = repo.path().parent().unwrap();
-        
-        // ブランチをチェックアウトまたは作成
-        let output = Command::new("git")
-            .arg("checkout")
-            .arg("-b")
-            .arg(branch)
-            .current_dir(repo_path)
-            .output();
-
-        // ブランチが既に存在する場合はチェックアウト
-        if let Err(_) = output {
-            let output = Command::new("git")
-                .arg("checkout")
-                .arg(branch)
-                .current_dir(repo_path)
-                .output()?;
-            if !output.status.success() {
-                return Err(anyhow::anyhow!("ブランチのチェックアウトに失敗しました"));
+        content_hash: &str,
+    ) -> Result<bool> {
+        let tree = repo.find_tree(write_tree(repo, content_dir)?)?;
+        let refname = format!("refs/heads/{}", branch);
+        let signature = repo
+            .signature()
+            .or_else(|_| Signature::now("deeprepo-slides", "deeprepo-slides@localhost"))?;
+        let message = format!("Update GitHub Pages\n\ncontent-hash: {}", content_hash);
+
+        for attempt in 1..=BRANCH_UPDATE_ATTEMPTS {
+            let parent = repo
+                .find_reference(&refname)
+                .ok()
+                .and_then(|r| r.peel_to_commit().ok());
+            if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+                return Ok(false);
             }
-        }
 
-        // すべてのファイルを削除（クリーンな状態にする）
-        Command::new("git")
-            .arg("rm")
-            .arg("-rf")
-            .arg(".")
-            .current_dir(repo_path)
-            .output()?;
-
-        // コンテンツをコピー
-        self.copy_directory(content_dir, repo_path)?;
-
-        // ファイルを追加
-        Command::new("git")
-            .arg("add")
-            .arg(".")
-            .current_dir(repo_path)
-            .output()?;
-
-        // コミット
-        Command::new("git")
-            .arg("commit")
-            .arg("-m")
-            .arg("Update GitHub Pages")
-            .current_dir(repo_path)
-            .output()?;
-
-        info!("{}ブランチにコミットしました", branch);
-
-        Ok(())
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let commit = repo.commit(None, &signature, &signature, &message, &tree, &parents)?;
+            // 取得時の先端から変わっていない場合のみ更新する（新規作成は既存がない場合のみ）
+            let updated = match &parent {
+                Some(parent) => repo.reference_matching(&refname, commit, true, parent.id(), "deeprepo-slides: publish"),
+                None => repo.reference(&refname, commit, false, "deeprepo-slides: publish"),
+            };
+            match updated {
+                Ok(_) => {
+                    info!("{}ブランチにコミットしました: {}", branch, commit);
+                    return Ok(true);
+                }
+                Err(e) if attempt < BRANCH_UPDATE_ATTEMPTS => {
+                    warn!("{}ブランチが同時に更新されたため再試行します: {}", branch, e);
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("{}ブランチを更新できませんでした", branch));
+                }
+            }
+        }
+        unreachable!("試行回数は1以上")
     }
 
     /// GitHub Actions YAMLを生成
@@ -283,8 +437,7 @@ impl Publisher {
     pub fn generate_actions_yaml(&self, repo_root: &str) -> Result<PathBuf> {
         info!("GitHub Actions YAMLを生成中...");
 
-        let repo_path = PathBuf::from(repo_root);
-        let workflows_dir = repo_path.join(".github").join("workflows");
+        let workflows_dir = self.output_path(repo_root, &Path::new(repo_root).join(".github").join("workflows"));
         fs::create_dir_all(&workflows_dir)?;
 
         let yaml_content = r#"name: Deploy Pages
@@ -320,11 +473,227 @@ jobs:
     }
 }
 
+/// GitHubのリモートURLからGitHub PagesのURLを推定
+/// 
+/// # 引数
+/// * `remote_url` - リモートURL（`git@github.com:owner/repo.git` または `https://github.com/owner/repo`）
+/// 
+/// # 戻り値
+/// * `Option<String>` - GitHub PagesのURL（GitHub以外の場合はNone）
+fn github_pages_url(remote_url: &str) -> Option<String> {
+    let path = remote_url
+        .strip_prefix("git@github.com:")
+        .or_else(|| remote_url.strip_prefix("https://github.com/"))
+        .or_else(|| remote_url.strip_prefix("ssh://git@github.com/"))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, repo) = path.split_once('/')?;
+
+    let host = format!("{}.github.io", owner.to_lowercase());
+    if repo.to_lowercase() == host {
+        Some(format!("https://{}/", host))
+    } else {
+        Some(format!("https://{}/{}/", host, repo))
+    }
+}
+
+/// 公開ロックのパス（gitリポジトリの場合は `.git/` 内）
+fn lock_path(repo_root: &str) -> PathBuf {
+    match Repository::discover(repo_root) {
+        Ok(repo) => repo.path().join("deeprepo-publish.lock"),
+        Err(_) => Path::new(repo_root).join(".deeprepo-publish.lock"),
+    }
+}
+
+/// ディレクトリ内のファイルを相対パスで列挙（ソート済み）
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                walk(root, &path, files)?;
+            } else {
+                files.push(path.strip_prefix(root)?.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir, dir, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// ファイルのパスと内容からハッシュを計算
+/// 
+/// # 引数
+/// * `root` - 基準ディレクトリ
+/// * `files` - 対象ファイル（`root` からの相対パス）
+/// 
+/// # 戻り値
+/// * `Result<String>` - SHA-256の16進文字列（存在しないファイルも区別して含む）
+fn content_hash(root: &Path, files: &[PathBuf]) -> Result<String> {
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        match fs::read(root.join(file)) {
+            Ok(bytes) => {
+                hasher.update((bytes.len() as u64).to_le_bytes());
+                hasher.update(&bytes);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => hasher.update(b"missing"),
+            Err(e) => return Err(e).with_context(|| format!("ファイルを読み込めません: {:?}", root.join(file))),
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 既存の公開先の同じファイルのハッシュを計算（公開先がない場合は空文字列）
+fn content_hash_of_existing(dir: &Path, files: &[PathBuf]) -> Result<String> {
+    if !dir.exists() {
+        return Ok(String::new());
+    }
+    content_hash(dir, files)
+}
+
+/// 前回の公開（今回の更新内容のページの比較元）
+struct PreviousPublish {
+    /// 前回の公開の説明（例: 「前回の公開（コミット abc1234、2026-10-01 09:00）」）
+    label: String,
+    /// Wikiのページ（サイト直下からの相対パス → 内容）
+    pages: BTreeMap<PathBuf, Vec<u8>>,
+    /// 前回の今回の更新内容のページ
+    page: Option<Vec<u8>>,
+}
+
+/// 公開先ブランチの先端のコミットを前回の公開として読み込む
+/// 
+/// # 引数
+/// * `repo` - リポジトリ
+/// * `branch` - ブランチ名
+/// 
+/// # 戻り値
+/// * `Result<Option<PreviousPublish>>` - 前回の公開（ブランチがない場合はNone）、またはエラー
+fn previous_branch_publish(repo: &Repository, branch: &str) -> Result<Option<PreviousPublish>> {
+    let Some(commit) = repo
+        .find_reference(&format!("refs/heads/{}", branch))
+        .ok()
+        .and_then(|r| r.peel_to_commit().ok())
+    else {
+        return Ok(None);
+    };
+
+    let mut pages = BTreeMap::new();
+    let mut page = None;
+    commit.tree()?.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        let path = PathBuf::from(format!("{}{}", dir, entry.name().unwrap_or_default()));
+        let is_page = path == Path::new(whats_new::PAGE_PATH);
+        if entry.kind() == Some(git2::ObjectType::Blob) && (is_page || whats_new::is_wiki_page(&path)) {
+            if let Ok(blob) = repo.find_blob(entry.id()) {
+                match is_page {
+                    true => page = Some(blob.content().to_vec()),
+                    false => {
+                        pages.insert(path, blob.content().to_vec());
+                    }
+                }
+            }
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+
+    let time = commit.time();
+    let committed = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)
+        .and_then(|offset| chrono::DateTime::from_timestamp(time.seconds(), 0).map(|t| t.with_timezone(&offset)))
+        .map(|t| format!("、{}", t.format("%Y-%m-%d %H:%M")))
+        .unwrap_or_default();
+    let id = commit.id().to_string();
+    Ok(Some(PreviousPublish {
+        label: format!("前回の公開（コミット {}{}）", &id[..7], committed),
+        pages,
+        page,
+    }))
+}
+
+/// 既存のdocs/を前回の公開として読み込む（今回も公開するページのみ、docs/は古いファイルを削除しないため）
+/// 
+/// # 引数
+/// * `docs_dir` - docs/ディレクトリ
+/// * `staging` - 今回公開する内容をまとめたディレクトリ
+/// 
+/// # 戻り値
+/// * `Result<Option<PreviousPublish>>` - 前回の公開（docs/に公開したページがない場合はNone）、またはエラー
+fn previous_docs_publish(docs_dir: &Path, staging: &Path) -> Result<Option<PreviousPublish>> {
+    let page = fs::read(docs_dir.join(whats_new::PAGE_PATH)).ok();
+    let mut pages = BTreeMap::new();
+    for path in list_files(staging)?.into_iter().filter(|p| whats_new::is_wiki_page(p)) {
+        if let Ok(bytes) = fs::read(docs_dir.join(&path)) {
+            pages.insert(path, bytes);
+        }
+    }
+    if pages.is_empty() && page.is_none() {
+        return Ok(None);
+    }
+    Ok(Some(PreviousPublish { label: "前回の公開（docs/）".to_string(), pages, page }))
+}
+
+/// ディレクトリからgitのツリーを作成
+/// 
+/// # 引数
+/// * `repo` - リポジトリ
+/// * `dir` - ディレクトリ
+/// 
+/// # 戻り値
+/// * `Result<Oid>` - ツリーのID、またはエラー
+fn write_tree(repo: &Repository, dir: &Path) -> Result<Oid> {
+    let mut builder = repo.treebuilder(None)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("UTF-8でないファイル名は公開できません: {:?}", path))?;
+        if path.is_dir() {
+            builder.insert(name, write_tree(repo, &path)?, 0o040000)?;
+        } else {
+            builder.insert(name, repo.blob_path(&path)?, 0o100644)?;
+        }
+    }
+    Ok(builder.write()?)
+}
+
+/// 公開の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishStatus {
+    /// 公開先を更新した
+    #[default]
+    Published,
+    /// 内容が同じため公開先を更新しなかった
+    Unchanged,
+}
+
 /// 公開結果
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PublishResult {
     pub ok: bool,
+    /// 公開先を更新したかどうか
+    #[serde(default)]
+    pub status: PublishStatus,
     pub hint: String,
+    /// 公開先URL（推定できない場合はNone）
+    #[serde(default)]
+    pub url: Option<String>,
+    /// 公開した内容のハッシュ（SHA-256）
+    #[serde(default)]
+    pub content_hash: String,
+    /// 前回の公開から変わったWikiのページ（公開を省略した場合・`publish.whats-new = false` の場合は空）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_changes: Vec<PageChange>,
+    /// 公開前の最適化のレポート（`publish.optimize = false` の場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimization: Option<OptimizeReport>,
 }
 
 #[cfg(test)]
@@ -347,5 +716,116 @@ mod tests {
         let copied_file = temp_dest.path().join("test.txt");
         assert!(copied_file.exists());
     }
+
+    #[tokio::test]
+    async fn test_publish_skips_unchanged_content() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        Repository::init(repo_dir.path()).unwrap();
+        let site = tempfile::tempdir().unwrap();
+        fs::write(site.path().join("index.html"), "<h1>v1</h1>").unwrap();
+        let site_dir = site.path().to_str().unwrap();
+        let repo_root = repo_dir.path().to_str().unwrap();
+        let publisher = Publisher::new(Config::default());
+
+        for mode in ["docs", "gh-pages"] {
+            let first = publisher.publish(mode, site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+            assert_eq!(first.status, PublishStatus::Published);
+            let second = publisher.publish(mode, site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+            assert_eq!(second.status, PublishStatus::Unchanged);
+            assert_eq!(first.content_hash, second.content_hash);
+        }
+
+        fs::write(site.path().join("index.html"), "<h1>v2</h1>").unwrap();
+        let updated = publisher.publish("gh-pages", site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+        assert_eq!(updated.status, PublishStatus::Published);
+
+        let repo = Repository::open(repo_root).unwrap();
+        let tip = repo.find_reference("refs/heads/gh-pages").unwrap().peel_to_commit().unwrap();
+        assert_eq!(tip.parent_count(), 1);
+        assert!(tip.tree().unwrap().get_name("index.html").is_some());
+        assert!(!lock_path(repo_root).exists());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_repo_publishes_to_workspace() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        Repository::init(repo_dir.path()).unwrap();
+        let workspace = tempfile::tempdir().unwrap();
+        let site = tempfile::tempdir().unwrap();
+        fs::write(site.path().join("index.html"), "<h1>v1</h1>").unwrap();
+        let site_dir = site.path().to_str().unwrap();
+        let repo_root = repo_dir.path().to_str().unwrap();
+
+        let mut config = Config::default();
+        config.security.read_only_repo = true;
+        config.security.workspace_dir = Some(workspace.path().to_path_buf());
+        let publisher = Publisher::new(config);
+
+        let result = publisher.publish("docs", site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+        assert_eq!(result.status, PublishStatus::Published);
+        let repo_name = repo_dir.path().file_name().unwrap();
+        assert!(workspace.path().join(repo_name).join("docs").join("index.html").exists());
+        assert!(publisher.generate_actions_yaml(repo_root).unwrap().starts_with(workspace.path()));
+        assert!(publisher.publish("gh-pages", site_dir, "missing", repo_root, "gh-pages").await.is_err());
+
+        let mut entries: Vec<String> = fs::read_dir(repo_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec![".git"]);
+        assert!(Repository::open(repo_root).unwrap().find_reference("refs/heads/gh-pages").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_writes_whats_new_page() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        Repository::init(repo_dir.path()).unwrap();
+        let site = tempfile::tempdir().unwrap();
+        let page = |title: &str, main: &str| format!("<title>{} - Acme</title><main>{}</main>", title, main);
+        fs::write(site.path().join("index.html"), page("概要", "v1")).unwrap();
+        fs::write(site.path().join("faq.html"), page("FAQ", "v1")).unwrap();
+        let site_dir = site.path().to_str().unwrap();
+        let repo_root = repo_dir.path().to_str().unwrap();
+        let publisher = Publisher::new(Config::default());
+        let read_page = || {
+            let repo = Repository::open(repo_root).unwrap();
+            let tip = repo.find_reference("refs/heads/gh-pages").unwrap().peel_to_commit().unwrap();
+            let entry = tip.tree().unwrap().get_name(whats_new::PAGE_PATH).unwrap().id();
+            let blob = repo.find_blob(entry).unwrap();
+            String::from_utf8_lossy(blob.content()).into_owned()
+        };
+
+        let first = publisher.publish("gh-pages", site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+        assert_eq!(first.page_changes.len(), 2);
+        assert!(read_page().contains("初回の公開です。"));
+
+        fs::write(site.path().join("faq.html"), page("FAQ", "v2")).unwrap();
+        fs::write(site.path().join("api.html"), page("API", "v1")).unwrap();
+        let second = publisher.publish("gh-pages", site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+        let kinds: Vec<(&str, &str)> = second.page_changes.iter().map(|c| (c.kind.as_str(), c.path.as_str())).collect();
+        assert_eq!(kinds, vec![("added", "api.html"), ("modified", "faq.html")]);
+        let html = read_page();
+        assert!(html.contains("から変更されたWikiのページです。"));
+        assert!(html.contains("<li><a href=\"faq.html\">FAQ</a> <code>faq.html</code></li>"));
+
+        // ページが変わらなければ前回のページを引き継ぎ、公開を省略する
+        let third = publisher.publish("gh-pages", site_dir, "missing", repo_root, "gh-pages").await.unwrap();
+        assert_eq!(third.status, PublishStatus::Unchanged);
+        assert_eq!(read_page(), html);
+    }
+
+    #[test]
+    fn test_github_pages_url() {
+        assert_eq!(
+            github_pages_url("git@github.com:tacyan/DeepRepoSlides.git"),
+            Some("https://tacyan.github.io/DeepRepoSlides/".to_string())
+        );
+        assert_eq!(
+            github_pages_url("https://github.com/tacyan/tacyan.github.io"),
+            Some("https://tacyan.github.io/".to_string())
+        );
+        assert_eq!(github_pages_url("https://gitlab.com/a/b.git"), None);
+    }
 }
 
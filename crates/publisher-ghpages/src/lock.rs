@@ -0,0 +1,112 @@
+/*!
+ * 公開ロック
+ *
+ * 同じリポジトリへの公開（CIと開発者のbuild-allなど）が同時に走らないよう排他する
+ * - ロックファイルを新規作成できたプロセスのみが公開する
+ * - 他のプロセスが保持している間は `publish.lock-wait-secs` まで待機する
+ *
+ * 主な仕様:
+ * - ロックファイルは `.git/deeprepo-publish.lock`（gitリポジトリでない場合はリポジトリルート直下）
+ * - 中身はPIDと取得時刻（UNIX秒）で、調査用
+ * - 更新から `publish.lock-stale-secs` を過ぎたロックは異常終了の残骸とみなして取り直す
+ * - ガードの破棄時にロックファイルを削除
+ *
+ * 制限事項:
+ * - 別のマシン間（共有ファイルシステムを除く）の排他はできない（gh-pagesはブランチの比較更新で保護）
+ */
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// ロックの取得を再試行する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 公開ロックのガード（破棄時に解放）
+#[derive(Debug)]
+pub(crate) struct PublishLock {
+    path: PathBuf,
+}
+
+impl PublishLock {
+    /// ロックを取得
+    ///
+    /// # 引数
+    /// * `path` - ロックファイルのパス
+    /// * `wait` - 他のプロセスが保持している場合に待機する最大時間
+    /// * `stale` - この時間を過ぎたロックは取り直す
+    ///
+    /// # 戻り値
+    /// * `Result<PublishLock>` - ガード、または待機時間内に取得できなかった場合のエラー
+    pub async fn acquire(path: &Path, wait: Duration, stale: Duration) -> Result<Self> {
+        let deadline = Instant::now() + wait;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+                    writeln!(file, "pid={} acquired-at={}", std::process::id(), now.as_secs())?;
+                    return Ok(Self { path: path.to_path_buf() });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(path, stale) {
+                        warn!("古い公開ロックを削除して取り直します: {:?}", path);
+                        fs::remove_file(path).ok();
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(anyhow::anyhow!(
+                            "別の公開処理が実行中です（ロック: {:?}）。終了を待つか、残っている場合は削除してください",
+                            path
+                        ));
+                    }
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                Err(e) => {
+                    return Err(e).with_context(|| format!("公開ロックを作成できません: {:?}", path));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for PublishLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("公開ロックの削除に失敗しました: {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// ロックファイルが更新から `stale` 以上経過しているか
+fn is_stale(path: &Path, stale: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed >= stale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive_until_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("publish.lock");
+        let long = Duration::from_secs(3600);
+
+        let lock = PublishLock::acquire(&path, Duration::ZERO, long).await.unwrap();
+        assert!(PublishLock::acquire(&path, Duration::ZERO, long).await.is_err());
+        drop(lock);
+        assert!(!path.exists());
+
+        // 期限切れのロックは取り直せる
+        fs::write(&path, "pid=0").unwrap();
+        let lock = PublishLock::acquire(&path, Duration::ZERO, Duration::ZERO).await.unwrap();
+        drop(lock);
+    }
+}
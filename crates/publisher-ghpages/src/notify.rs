@@ -0,0 +1,212 @@
+/*!
+ * Webhook通知実装
+ *
+ * build-all・publishの完了時に設定されたWebhookへ通知を送信する
+ * - Slack（Incoming Webhook）
+ * - Microsoft Teams（MessageCard）
+ * - 汎用HTTP（サマリーをJSONでPOST）
+ *
+ * 主な仕様:
+ * - `[notifications]` の `webhooks` ごとに、対象イベントであれば送信
+ * - 送信先URLは設定値、なければ `url-env` の環境変数から読み込む
+ * - `security.offline`（既定）の場合は外部に送信せず、警告ログのみ出力
+ * - 応答のないWebhookでビルド・公開が止まらないよう、`WEBHOOK_TIMEOUT_SECS` 秒で打ち切る
+ *
+ * 制限事項:
+ * - 通知の失敗はビルド・公開を失敗させず、警告ログのみ出力
+ * - 再送は行わない
+ */
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use config::{Config, WebhookConfig};
+
+/// Webhookへの送信のタイムアウト（秒）
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// 通知するビルドサマリー
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildSummary {
+    /// イベント名（build-all|publish）
+    pub event: String,
+    /// プロジェクト名
+    pub project: String,
+    /// 成功したかどうか
+    pub ok: bool,
+    pub files: usize,
+    pub modules: usize,
+    pub wiki_pages: usize,
+    pub slide_files: usize,
+    /// 公開先URL
+    pub url: Option<String>,
+    /// 補足メッセージ
+    pub message: String,
+}
+
+impl BuildSummary {
+    /// 通知本文を作成
+    fn text(&self) -> String {
+        let status = if self.ok { "完了" } else { "失敗" };
+        let mut text = format!("[{}] {} が{}しました", self.project, self.event, status);
+        if self.files > 0 || self.modules > 0 {
+            text.push_str(&format!("\n- 解析: {}ファイル, {}モジュール", self.files, self.modules));
+        }
+        if self.wiki_pages > 0 {
+            text.push_str(&format!("\n- Wiki: {}ページ", self.wiki_pages));
+        }
+        if self.slide_files > 0 {
+            text.push_str(&format!("\n- スライド: {}ファイル", self.slide_files));
+        }
+        if let Some(url) = &self.url {
+            text.push_str(&format!("\n- URL: {}", url));
+        }
+        if !self.message.is_empty() {
+            text.push_str(&format!("\n{}", self.message));
+        }
+        text
+    }
+}
+
+/// Webhook通知
+pub struct Notifier {
+    config: Config,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    /// 新しい通知インスタンスを作成
+    ///
+    /// # 引数
+    /// * `config` - 設定
+    ///
+    /// # 戻り値
+    /// * `Self` - 通知インスタンス
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 対象のWebhookすべてに通知を送信
+    ///
+    /// # 引数
+    /// * `summary` - ビルドサマリー
+    ///
+    /// # 戻り値
+    /// * `usize` - 送信に成功したWebhook数（`security.offline` の場合は0）
+    pub async fn notify(&self, summary: &BuildSummary) -> usize {
+        let mut sent = 0;
+        if self.config.security.offline && !self.config.notifications.webhooks.is_empty() {
+            warn!("security.offlineのためWebhook通知を送信しません（送信するには offline = false を設定してください）");
+            return sent;
+        }
+
+        for webhook in &self.config.notifications.webhooks {
+            if !webhook.events.is_empty() && !webhook.events.contains(&summary.event) {
+                continue;
+            }
+            match self.send(webhook, summary).await {
+                Ok(()) => sent += 1,
+                Err(e) => warn!("Webhook通知に失敗しました（{}）: {}", webhook.kind, e),
+            }
+        }
+
+        if sent > 0 {
+            info!("Webhook通知を送信しました: {}件", sent);
+        }
+        sent
+    }
+
+    /// 1つのWebhookに送信
+    async fn send(&self, webhook: &WebhookConfig, summary: &BuildSummary) -> Result<()> {
+        let url = webhook_url(webhook)?;
+        let response = self
+            .client
+            .post(&url)
+            .json(&payload(&webhook.kind, summary))
+            .send()
+            .await
+            .context("Webhookへの接続に失敗しました")?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Webhookがエラーを返しました: {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// 送信先URLを取得
+fn webhook_url(webhook: &WebhookConfig) -> Result<String> {
+    if let Some(url) = &webhook.url {
+        return Ok(url.clone());
+    }
+    let env = webhook
+        .url_env
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Webhookのurlまたはurl-envを設定してください"))?;
+    std::env::var(env).map_err(|_| anyhow::anyhow!("環境変数 {} が設定されていません", env))
+}
+
+/// 種類に応じたペイロードを作成
+fn payload(kind: &str, summary: &BuildSummary) -> Value {
+    match kind {
+        "slack" => json!({ "text": summary.text() }),
+        "teams" => json!({
+            "@type": "MessageCard",
+            "@context": "http://schema.org/extensions",
+            "summary": format!("{} {}", summary.project, summary.event),
+            "themeColor": if summary.ok { "2EB886" } else { "D00000" },
+            "title": format!("{} {}", summary.project, summary.event),
+            "text": summary.text().replace('\n', "<br>"),
+        }),
+        _ => serde_json::to_value(summary).unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_by_kind() {
+        let summary = BuildSummary {
+            event: "publish".to_string(),
+            project: "demo".to_string(),
+            ok: true,
+            url: Some("https://example.github.io/demo/".to_string()),
+            ..Default::default()
+        };
+
+        let slack = payload("slack", &summary);
+        assert!(slack["text"].as_str().unwrap().contains("https://example.github.io/demo/"));
+
+        let teams = payload("teams", &summary);
+        assert_eq!(teams["@type"], "MessageCard");
+
+        let generic = payload("generic", &summary);
+        assert_eq!(generic["event"], "publish");
+    }
+
+    #[tokio::test]
+    async fn test_offline_skips_webhooks() {
+        let mut config = Config::default();
+        config.notifications.webhooks = vec![WebhookConfig {
+            kind: "generic".to_string(),
+            url: Some("http://127.0.0.1:9/hook".to_string()),
+            url_env: None,
+            events: Vec::new(),
+        }];
+        let summary = BuildSummary { event: "publish".to_string(), ..Default::default() };
+        assert!(config.security.offline);
+        assert_eq!(Notifier::new(config).notify(&summary).await, 0);
+    }
+}
@@ -0,0 +1,278 @@
+/*!
+ * 公開前の最適化
+ *
+ * 公開する内容をまとめたディレクトリのHTML・CSS・JavaScriptを縮小し、SVGを圧縮し、どこからも参照されない
+ * テーマのファイル（CSS・JavaScript・フォント）を削除する（`publish.optimize`）
+ * - 大きなリポジトリのmdBookのサイトは数十MBになり、GitHub Pagesでの読み込みが遅くなるため
+ *
+ * 主な仕様:
+ * - HTML: コメントを除き、行頭の空白と空行を詰める（`<pre>`・`<textarea>`・`<script>`・`<style>` の中は変更しない）
+ * - CSS: コメントを除き、行頭・行末の空白と改行、`{`・`}`・`;`・`,` の前後の空白を詰める
+ * - JavaScript: 行頭の空白・空行・1行の `//` コメントを除く（`.min.js` とテンプレート文字列を含むファイルは変更しない）
+ * - SVG: XML宣言・コメント・`<metadata>` を除き、行頭の空白を詰める（`<text>` のないSVGはタグの間の空白も詰める）
+ * - 削除するのは、ほかのHTML・CSS・JavaScript・JSONにファイル名が現れないCSS・JavaScript・フォント（画像・バッジは残す）
+ * - 縮小しても小さくならないファイルは元のまま残し、種類ごとの縮小前後のサイズをレポートにまとめる
+ *
+ * 制限事項:
+ * - 構文解析をしない文字列の置き換えのため、CSSの文字列の中にあるコメントの開始記号もコメントとして扱う
+ * - 変数名の短縮などの本格的な縮小はしない
+ * - docs/モードは公開先の古いファイルを削除しないため、削除したテーマのファイルも公開先には残る
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::list_files;
+
+/// 縮小する種類（拡張子）
+const MINIFIED_KINDS: &[&str] = &["html", "css", "js", "svg"];
+/// 参照されない場合に削除するテーマのファイルの拡張子
+const THEME_ASSET_KINDS: &[&str] = &["css", "js", "woff", "woff2", "ttf", "eot", "otf"];
+/// ファイル名の参照を探すファイルの拡張子
+const REFERENCING_KINDS: &[&str] = &["html", "css", "js", "json"];
+
+/// HTMLの中で変更しない要素
+static HTML_PROTECTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<pre\b.*?</pre>|<textarea\b.*?</textarea>|<script\b.*?</script>|<style\b.*?</style>").unwrap());
+/// HTMLのコメント（IEの条件付きコメントを除く）
+static HTML_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<!--[^\[].*?-->").unwrap());
+/// CSSのコメント
+static CSS_COMMENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)/\*.*?\*/").unwrap());
+/// CSSの区切り記号の前後の空白
+static CSS_PUNCTUATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*([{};,])\s*").unwrap());
+/// SVGの中で除く部分（XML宣言・コメント・メタデータ）
+static SVG_NOISE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<\?xml.*?\?>|<!--.*?-->|<metadata\b.*?</metadata>").unwrap());
+/// SVGのタグの間の空白
+static SVG_TAG_GAP: LazyLock<Regex> = LazyLock::new(|| Regex::new(r">\s+<").unwrap());
+
+/// 種類ごとのサイズ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KindSize {
+    /// 拡張子（html|css|js|svg）
+    pub kind: String,
+    pub files: usize,
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+/// 最適化のレポート
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    /// 公開する内容の合計サイズ（最適化前）
+    pub before_bytes: u64,
+    /// 公開する内容の合計サイズ（最適化後）
+    pub after_bytes: u64,
+    /// 種類ごとの縮小前後のサイズ
+    pub kinds: Vec<KindSize>,
+    /// 参照されないため削除したテーマのファイル（まとめ先からの相対パス）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed: Vec<PathBuf>,
+}
+
+impl OptimizeReport {
+    /// 削減したサイズの割合（0.0〜1.0）
+    pub fn saved_ratio(&self) -> f64 {
+        match self.before_bytes {
+            0 => 0.0,
+            before => before.saturating_sub(self.after_bytes) as f64 / before as f64,
+        }
+    }
+}
+
+/// 公開する内容を最適化
+///
+/// # 引数
+/// * `root` - 公開する内容をまとめたディレクトリ（ファイルを書き換える）
+///
+/// # 戻り値
+/// * `Result<OptimizeReport>` - 最適化前後のサイズと削除したファイル、またはエラー
+pub(crate) fn optimize_site(root: &Path) -> Result<OptimizeReport> {
+    let files = list_files(root)?;
+    let mut report = OptimizeReport::default();
+    for file in &files {
+        report.before_bytes += fs::metadata(root.join(file))?.len();
+    }
+
+    report.removed = unreferenced_assets(root, &files)?;
+    for file in &report.removed {
+        fs::remove_file(root.join(file)).with_context(|| format!("ファイルを削除できません: {:?}", file))?;
+    }
+
+    let mut kinds: BTreeMap<&str, KindSize> = BTreeMap::new();
+    for file in files.iter().filter(|f| !report.removed.contains(f)) {
+        let Some(kind) = extension(file).and_then(|e| MINIFIED_KINDS.iter().find(|k| **k == e)) else {
+            continue;
+        };
+        let path = root.join(file);
+        let bytes = fs::read(&path)?;
+        // UTF-8でないファイル（文字コードの異なるテーマなど）は変更しない
+        let Ok(text) = String::from_utf8(bytes) else {
+            continue;
+        };
+        let minified = match *kind {
+            "html" => minify_html(&text),
+            "css" => minify_css(&text),
+            "js" if is_minifiable_js(file, &text) => minify_js(&text),
+            "svg" => minify_svg(&text),
+            _ => text.clone(),
+        };
+        let entry = kinds.entry(kind).or_insert_with(|| KindSize { kind: kind.to_string(), ..Default::default() });
+        entry.files += 1;
+        entry.before_bytes += text.len() as u64;
+        if minified.len() < text.len() {
+            fs::write(&path, &minified).with_context(|| format!("ファイルを書き込めません: {:?}", path))?;
+            entry.after_bytes += minified.len() as u64;
+        } else {
+            entry.after_bytes += text.len() as u64;
+        }
+    }
+    report.kinds = kinds.into_values().collect();
+
+    for file in list_files(root)? {
+        report.after_bytes += fs::metadata(root.join(&file))?.len();
+    }
+    Ok(report)
+}
+
+/// ほかのファイルからファイル名が参照されないテーマのファイル
+fn unreferenced_assets(root: &Path, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut referencing = String::new();
+    for file in files.iter().filter(|f| extension(f).is_some_and(|e| REFERENCING_KINDS.contains(&e.as_str()))) {
+        referencing.push_str(&String::from_utf8_lossy(&fs::read(root.join(file))?));
+        referencing.push('\n');
+    }
+    Ok(files
+        .iter()
+        .filter(|f| extension(f).is_some_and(|e| THEME_ASSET_KINDS.contains(&e.as_str())))
+        .filter(|f| {
+            let name = f.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            !referencing.contains(name)
+        })
+        .cloned()
+        .collect())
+}
+
+/// 小文字の拡張子
+fn extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase())
+}
+
+/// 保護する要素の外側だけを変換する
+fn outside_protected(text: &str, protected: &Regex, transform: impl Fn(&str) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for block in protected.find_iter(text) {
+        output.push_str(&transform(&text[last..block.start()]));
+        output.push_str(block.as_str());
+        last = block.end();
+    }
+    output.push_str(&transform(&text[last..]));
+    output
+}
+
+/// 行頭の空白と空行を詰める（先頭の空白は区切りとして改行1つを残す）
+fn trim_lines(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    if text.starts_with(char::is_whitespace) {
+        output.push('\n');
+    }
+    for line in text.lines().map(str::trim_start).filter(|l| !l.trim().is_empty()) {
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+/// HTMLを縮小
+pub(crate) fn minify_html(html: &str) -> String {
+    outside_protected(html, &HTML_PROTECTED, |part| trim_lines(&HTML_COMMENT.replace_all(part, "")))
+}
+
+/// CSSを縮小
+pub(crate) fn minify_css(css: &str) -> String {
+    let without_comments = CSS_COMMENT.replace_all(css, "");
+    let joined: Vec<&str> = without_comments.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    CSS_PUNCTUATION.replace_all(&joined.join(" "), "$1").replace(";}", "}")
+}
+
+/// JavaScriptを縮小できるか（縮小済みのファイル・テンプレート文字列を含むファイルは行の扱いが変わるため対象外）
+fn is_minifiable_js(path: &Path, js: &str) -> bool {
+    !path.to_string_lossy().ends_with(".min.js") && !js.contains('`')
+}
+
+/// JavaScriptを縮小
+pub(crate) fn minify_js(js: &str) -> String {
+    let mut output = String::with_capacity(js.len());
+    for line in js.lines().map(str::trim_start).filter(|l| !l.trim().is_empty() && !l.starts_with("//")) {
+        output.push_str(line);
+        output.push('\n');
+    }
+    output
+}
+
+/// SVGを圧縮
+pub(crate) fn minify_svg(svg: &str) -> String {
+    let cleaned = trim_lines(SVG_NOISE.replace_all(svg, "").trim_start());
+    // <text> の中の改行は単語の区切りになるため、文字のあるSVGはタグの間を詰めない
+    match cleaned.contains("<text") {
+        true => cleaned,
+        false => SVG_TAG_GAP.replace_all(cleaned.trim_end(), "><").into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_keeps_protected_blocks() {
+        let html = "<html>\n  <!-- nav -->\n  <body>\n\n    <pre><code>fn main() {\n    run();\n}</code></pre>\n    <script>\n  var a = 1;\n</script>\n  </body>\n</html>\n";
+        assert_eq!(
+            minify_html(html),
+            "<html>\n<body>\n<pre><code>fn main() {\n    run();\n}</code></pre>\n<script>\n  var a = 1;\n</script>\n</body>\n</html>\n"
+        );
+        assert_eq!(minify_css("/* theme */\n.a, .b {\n  color: red;\n  margin : 0 ;\n}\n"), ".a,.b{color: red;margin : 0}");
+        assert_eq!(minify_js("// helper\nfunction f() {\n    return 1; // one\n}\n"), "function f() {\nreturn 1; // one\n}\n");
+        assert_eq!(minify_svg("<?xml version=\"1.0\"?>\n<svg>\n  <metadata>x</metadata>\n  <rect/>\n</svg>\n"), "<svg><rect/></svg>");
+        assert!(minify_svg("<svg>\n  <text>a</text>\n  <text>b</text>\n</svg>").contains("</text>\n<text>"));
+    }
+
+    #[test]
+    fn test_optimize_site_reports_sizes_and_removes_unused_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("index.html", "<html>\n    <link rel=\"stylesheet\" href=\"css/general.css\">\n    <script src=\"book.js\"></script>\n</html>\n");
+        write("css/general.css", "/* general */\nbody {\n    margin: 0;\n}\n");
+        write("book.js", "const template = `a`;\n");
+        write("ayu-highlight.css", ".hljs { color: #fff; }\n");
+        write("fonts/unused.woff2", "font");
+        write("badges/docs-coverage.svg", "<svg>\n  <text>80%</text>\n</svg>\n");
+        write("fonts/UNUSED.TTF", "font");
+
+        let report = optimize_site(dir.path()).unwrap();
+        let mut removed = report.removed.clone();
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![PathBuf::from("ayu-highlight.css"), PathBuf::from("fonts/UNUSED.TTF"), PathBuf::from("fonts/unused.woff2")]
+        );
+        assert!(!dir.path().join("ayu-highlight.css").exists());
+        assert!(dir.path().join("badges/docs-coverage.svg").exists());
+        assert_eq!(fs::read_to_string(dir.path().join("css/general.css")).unwrap(), "body{margin: 0}");
+        assert_eq!(fs::read_to_string(dir.path().join("book.js")).unwrap(), "const template = `a`;\n");
+        let kinds: Vec<(&str, usize)> = report.kinds.iter().map(|k| (k.kind.as_str(), k.files)).collect();
+        assert_eq!(kinds, vec![("css", 1), ("html", 1), ("js", 1), ("svg", 1)]);
+        assert!(report.after_bytes < report.before_bytes);
+        assert!(report.saved_ratio() > 0.0);
+    }
+}
@@ -0,0 +1,210 @@
+/*!
+ * 今回の更新内容のページ
+ *
+ * 公開するWikiを前回の公開と比べ、追加・更新・削除されたページの一覧を公開するサイトに含める
+ * - gh-pagesモード: 公開先ブランチの先端のコミット（前回の公開）のツリーと比べる
+ * - docs/モード: 既存のdocs/のファイルと比べる
+ *
+ * 主な仕様:
+ * - サイト直下の `whats-new.html` に書き出す（スライド・mdBookの `print.html`・`404.html` は対象外）
+ * - mdBookのページは `<main>` の中身で比べる（目次の変更だけで全ページが更新扱いにならないように）
+ * - ページのタイトルは `<title>` からmdBookが付ける ` - <書名>` を除いたもの
+ * - 変更がない場合は前回のページをそのまま使う（内容が同じなら公開を省略できるように）
+ *
+ * 制限事項:
+ * - docs/モードは古いファイルを削除しないため、削除されたページは検出しない
+ * - HTML以外のページ（mdbookがなくMarkdownのみを公開した場合）は対象外
+ */
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// 今回の更新内容のページのパス（サイト直下）
+pub(crate) const PAGE_PATH: &str = "whats-new.html";
+
+/// 比較の対象外とするmdBookのページ
+const IGNORED_PAGES: &[&str] = &[PAGE_PATH, "print.html", "404.html", "toc.html"];
+
+/// 前回の公開から変わったWikiのページ
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageChange {
+    /// サイト内のパス（`/` 区切り）
+    pub path: String,
+    /// ページのタイトル
+    pub title: String,
+    /// 変更の種類（added|modified|removed）
+    pub kind: String,
+}
+
+/// Wikiのページとして比べるファイルか
+///
+/// # 引数
+/// * `path` - サイト直下からの相対パス
+pub(crate) fn is_wiki_page(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "html")
+        && !path.starts_with("slides")
+        && !IGNORED_PAGES.iter().any(|p| path == Path::new(p))
+}
+
+/// 前回の公開と今回のページを比べる
+///
+/// # 引数
+/// * `previous` - 前回の公開のページ（パス → 内容）
+/// * `current` - 今回のページ（パス → 内容）
+///
+/// # 戻り値
+/// * `Vec<PageChange>` - 追加・更新・削除されたページ（種類ごとにパス順）
+pub(crate) fn diff_pages(previous: &BTreeMap<PathBuf, Vec<u8>>, current: &BTreeMap<PathBuf, Vec<u8>>) -> Vec<PageChange> {
+    let change = |path: &Path, bytes: &[u8], kind: &str| PageChange {
+        path: path.to_string_lossy().replace('\\', "/"),
+        title: page_title(bytes).unwrap_or_else(|| path.to_string_lossy().into_owned()),
+        kind: kind.to_string(),
+    };
+    let mut changes: Vec<PageChange> = Vec::new();
+    for (path, bytes) in current {
+        match previous.get(path) {
+            None => changes.push(change(path, bytes, "added")),
+            Some(old) if page_body(old) != page_body(bytes) => changes.push(change(path, bytes, "modified")),
+            Some(_) => {}
+        }
+    }
+    for (path, bytes) in previous {
+        if !current.contains_key(path) {
+            changes.push(change(path, bytes, "removed"));
+        }
+    }
+    changes.sort_by_key(|c| match c.kind.as_str() {
+        "added" => 0,
+        "modified" => 1,
+        _ => 2,
+    });
+    changes
+}
+
+/// 比べる本文（mdBookのページは `<main>` の中身、それ以外は全体）
+fn page_body(bytes: &[u8]) -> &[u8] {
+    let find = |needle: &[u8], from: usize| {
+        bytes[from..].windows(needle.len()).position(|w| w == needle).map(|i| i + from)
+    };
+    match find(b"<main>", 0) {
+        Some(start) => match find(b"</main>", start) {
+            Some(end) => &bytes[start..end],
+            None => &bytes[start..],
+        },
+        None => bytes,
+    }
+}
+
+/// `<title>` からページのタイトルを取り出す（mdBookの ` - <書名>` は除く）
+fn page_title(bytes: &[u8]) -> Option<String> {
+    let html = String::from_utf8_lossy(bytes);
+    let start = html.find("<title>")? + "<title>".len();
+    let end = start + html[start..].find("</title>")?;
+    let title = html[start..end].trim();
+    let title = title.rsplit_once(" - ").map_or(title, |(chapter, _)| chapter.trim());
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// 今回の更新内容のページを描画
+///
+/// # 引数
+/// * `changes` - 前回の公開から変わったページ
+/// * `previous` - 前回の公開の説明（初回の公開の場合はNone）
+/// * `generated` - 公開日時
+///
+/// # 戻り値
+/// * `String` - HTML
+pub(crate) fn render_page(changes: &[PageChange], previous: Option<&str>, generated: &str) -> String {
+    let mut body = String::new();
+    match previous {
+        Some(previous) if changes.is_empty() => {
+            body.push_str(&format!("<p>{}から変更されたページはありません。</p>\n", escape_html(previous)));
+        }
+        Some(previous) => {
+            body.push_str(&format!("<p>{}から変更されたWikiのページです。</p>\n", escape_html(previous)));
+        }
+        None => body.push_str("<p>初回の公開です。公開したWikiのページは次のとおりです。</p>\n"),
+    }
+    for (kind, heading) in [("added", "追加されたページ"), ("modified", "更新されたページ"), ("removed", "削除されたページ")] {
+        let pages: Vec<&PageChange> = changes.iter().filter(|c| c.kind == kind).collect();
+        if pages.is_empty() {
+            continue;
+        }
+        body.push_str(&format!("<h2>{}（{}件）</h2>\n<ul>\n", heading, pages.len()));
+        for page in pages {
+            let title = escape_html(&page.title);
+            let path = escape_html(&page.path);
+            match kind {
+                "removed" => body.push_str(&format!("<li>{} <code>{}</code></li>\n", title, path)),
+                _ => body.push_str(&format!("<li><a href=\"{}\">{}</a> <code>{}</code></li>\n", path, title, path)),
+            }
+        }
+        body.push_str("</ul>\n");
+    }
+
+    format!(
+        concat!(
+            "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n",
+            "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n",
+            "<title>今回の更新内容</title>\n",
+            "<style>body{{font-family:sans-serif;line-height:1.6;max-width:48rem;margin:2rem auto;padding:0 1rem}}</style>\n",
+            "</head>\n<body>\n<h1>今回の更新内容</h1>\n{body}",
+            "<p><small>公開日時: {generated}</small></p>\n<p><a href=\"index.html\">Wikiのトップへ</a></p>\n</body>\n</html>\n"
+        ),
+        body = body,
+        generated = escape_html(generated),
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_pages_ignores_sidebar_changes() {
+        let page = |title: &str, sidebar: &str, main: &str| {
+            format!("<title>{} - Acme</title><nav>{}</nav><main>{}</main>", title, sidebar, main).into_bytes()
+        };
+        let previous: BTreeMap<PathBuf, Vec<u8>> = [
+            ("overview.html", page("概要", "a", "v1")),
+            ("faq.html", page("FAQ", "a", "v1")),
+            ("flows.html", page("フロー", "a", "v1")),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| (PathBuf::from(path), bytes))
+        .collect();
+        let current: BTreeMap<PathBuf, Vec<u8>> = [
+            ("overview.html", page("概要", "a b", "v1")),
+            ("faq.html", page("FAQ", "a b", "v2")),
+            ("unused.html", page("未使用コード", "a b", "v1")),
+        ]
+        .into_iter()
+        .map(|(path, bytes)| (PathBuf::from(path), bytes))
+        .collect();
+
+        let changes = diff_pages(&previous, &current);
+        let summary: Vec<(&str, &str, &str)> =
+            changes.iter().map(|c| (c.kind.as_str(), c.path.as_str(), c.title.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![("added", "unused.html", "未使用コード"), ("modified", "faq.html", "FAQ"), ("removed", "flows.html", "フロー")]
+        );
+
+        let html = render_page(&changes, Some("前回の公開（コミット abc1234）"), "2026-10-15 09:00");
+        assert!(html.contains("<p>前回の公開（コミット abc1234）から変更されたWikiのページです。</p>"));
+        assert!(html.contains("<h2>追加されたページ（1件）</h2>\n<ul>\n<li><a href=\"unused.html\">未使用コード</a>"));
+        assert!(html.contains("<li>フロー <code>flows.html</code></li>"));
+        assert!(render_page(&[], None, "2026-10-15 09:00").contains("初回の公開です。"));
+
+        assert!(is_wiki_page(Path::new("modules/store.html")));
+        assert!(!is_wiki_page(Path::new("slides/index.html")));
+        assert!(!is_wiki_page(Path::new("print.html")));
+        assert!(!is_wiki_page(Path::new("searchindex.js")));
+    }
+}
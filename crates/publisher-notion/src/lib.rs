@@ -0,0 +1,515 @@
+/*!
+ * Notionエクスポート実装
+ *
+ * 生成されたWikiをNotion APIでページツリーとして公開する
+ * - ルートページの下に各セクション（SUMMARY.mdの順序・入れ子）をページとして作成
+ * - モジュールセクションはモジュールごとの子ページに分割（パッケージ別のページがある場合はSUMMARY.mdの入れ子のまま）
+ * - 見出し・段落・リスト・コードをNotionブロックに変換
+ * - Mermaid図は言語 `mermaid` のコードブロックとして送信（Notion上で図として表示できる）
+ *
+ * 主な仕様:
+ * - 親ページID、または親データベースIDを出力先として指定可能（データベースはタイトルのプロパティ名を取得して使う）
+ * - トークンは設定値、なければ環境変数（既定: NOTION_TOKEN）から読み込む
+ * - 1リクエストあたりの子ブロック上限（100件）を超える場合は追記APIで分割送信
+ * - `security.offline`（既定）の場合は送信せずエラーにする
+ *
+ * 制限事項:
+ * - インライン装飾（太字・リンク）はプレーンテキストとして送信
+ */
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use tracing::info;
+
+use config::Config;
+
+/// Notion APIのバージョン
+const NOTION_VERSION: &str = "2022-06-28";
+/// 1リクエストで送信できる子ブロックの上限
+const MAX_BLOCKS_PER_REQUEST: usize = 100;
+/// リッチテキスト1要素あたりの文字数上限
+const MAX_RICH_TEXT_CHARS: usize = 2000;
+
+/// Notionパブリッシャー
+pub struct NotionPublisher {
+    config: Config,
+    client: reqwest::Client,
+}
+
+impl NotionPublisher {
+    /// 新しいNotionパブリッシャーインスタンスを作成
+    ///
+    /// # 引数
+    /// * `config` - 設定
+    ///
+    /// # 戻り値
+    /// * `Self` - Notionパブリッシャーインスタンス
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// WikiをNotionにエクスポート
+    ///
+    /// # 引数
+    /// * `wiki_dir` - Wikiの出力ディレクトリ（`site.layout.src-dir` に `SUMMARY.md` を含む）
+    ///
+    /// # 戻り値
+    /// * `Result<NotionExportResult>` - エクスポート結果、またはエラー
+    pub async fn export_wiki(&self, wiki_dir: &str) -> Result<NotionExportResult> {
+        info!("Notionエクスポート開始: wiki_dir={}", wiki_dir);
+        if self.config.security.offline {
+            return Err(anyhow::anyhow!(
+                "security.offlineのためNotionにエクスポートしません（エクスポートするには offline = false を設定してください）"
+            ));
+        }
+
+        let layout = &self.config.site.layout;
+        let src_dir = PathBuf::from(wiki_dir).join(&layout.src_dir);
+        let modules_file = layout.section_file("modules");
+        let sections = read_sections(&src_dir)?;
+        let notion = &self.config.publish.notion;
+
+        // 出力先を決定（データベース指定時は各セクションを行として作成）
+        let (section_parent, section_title_property, root_page) = if let Some(database_id) = &notion.parent_database_id {
+            let title_property = self.database_title_property(database_id).await?;
+            (json!({ "database_id": database_id }), title_property, None)
+        } else {
+            let parent_page_id = notion.parent_page_id.as_ref().ok_or_else(|| {
+                anyhow::anyhow!("publish.notion.parent-page-id または parent-database-id を設定してください")
+            })?;
+            let root = self
+                .create_page(
+                    json!({ "page_id": parent_page_id }),
+                    "title",
+                    &self.config.project.name,
+                    Vec::new(),
+                )
+                .await?;
+            (json!({ "page_id": root.id }), "title".to_string(), Some(root))
+        };
+
+        let mut pages = 0;
+        let mut first_url = root_page.as_ref().map(|p| p.url.clone());
+        // 入れ子の深さごとに、直近に作成したページのID（子のセクションの親）
+        let mut parents: Vec<String> = Vec::new();
+
+        for (i, section) in sections.iter().enumerate() {
+            let markdown = fs::read_to_string(src_dir.join(&section.file))
+                .with_context(|| format!("Wikiページの読み込みに失敗しました: {}", section.file))?;
+            parents.truncate(section.depth);
+            let (parent, title_property) = match parents.last() {
+                Some(id) => (json!({ "page_id": id }), "title"),
+                None => (section_parent.clone(), section_title_property.as_str()),
+            };
+            let has_children = sections.get(i + 1).is_some_and(|next| next.depth > section.depth);
+
+            let page = if section.file == modules_file && !has_children {
+                // モジュールセクションは各モジュールを子ページに分割
+                let (intro, modules) = split_module_pages(&markdown);
+                let page = self.create_page(parent, title_property, &section.title, markdown_to_blocks(&intro)).await?;
+                for (module_title, body) in modules {
+                    self.create_page(json!({ "page_id": page.id }), "title", &module_title, markdown_to_blocks(&body))
+                        .await?;
+                    pages += 1;
+                }
+                page
+            } else {
+                self.create_page(parent, title_property, &section.title, markdown_to_blocks(&markdown)).await?
+            };
+            pages += 1;
+            first_url.get_or_insert(page.url.clone());
+            parents.push(page.id);
+        }
+
+        info!("Notionエクスポート完了: {}ページ", pages);
+
+        Ok(NotionExportResult {
+            ok: true,
+            pages,
+            root_page_id: root_page.map(|p| p.id),
+            url: first_url,
+        })
+    }
+
+    /// データベースのタイトルのプロパティ名を取得
+    async fn database_title_property(&self, database_id: &str) -> Result<String> {
+        let database = self.request(reqwest::Method::GET, &format!("/v1/databases/{}", database_id), None).await?;
+        database["properties"]
+            .as_object()
+            .and_then(|properties| properties.iter().find(|(_, property)| property["type"] == "title"))
+            .map(|(name, _)| name.clone())
+            .ok_or_else(|| anyhow::anyhow!("Notionのデータベースにタイトルのプロパティがありません: {}", database_id))
+    }
+
+    /// ページを作成し、ブロックを追加
+    async fn create_page(&self, parent: Value, title_property: &str, title: &str, blocks: Vec<Value>) -> Result<CreatedPage> {
+        let mut chunks = blocks.chunks(MAX_BLOCKS_PER_REQUEST);
+        let first: Vec<Value> = chunks.next().map(|c| c.to_vec()).unwrap_or_default();
+
+        let body = json!({
+            "parent": parent,
+            "properties": {
+                title_property: { "title": rich_text(title) }
+            },
+            "children": first,
+        });
+
+        let response: Value = self.request(reqwest::Method::POST, "/v1/pages", Some(&body)).await?;
+        let id = response["id"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("NotionのレスポンスにページIDがありません"))?
+            .to_string();
+        let url = response["url"].as_str().unwrap_or_default().to_string();
+
+        for chunk in chunks {
+            self.request(
+                reqwest::Method::PATCH,
+                &format!("/v1/blocks/{}/children", id),
+                Some(&json!({ "children": chunk })),
+            )
+            .await?;
+        }
+
+        Ok(CreatedPage { id, url })
+    }
+
+    /// Notion APIを呼び出す
+    async fn request(&self, method: reqwest::Method, path: &str, body: Option<&Value>) -> Result<Value> {
+        let url = format!("{}{}", self.config.publish.notion.api_base_url.trim_end_matches('/'), path);
+        let mut request = self
+            .client
+            .request(method, &url)
+            .bearer_auth(self.token()?)
+            .header("Notion-Version", NOTION_VERSION);
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Notion APIへの接続に失敗しました: {}", url))?;
+
+        let status = response.status();
+        let json: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            return Err(anyhow::anyhow!(
+                "Notion APIエラー ({}): {}",
+                status,
+                json["message"].as_str().unwrap_or("不明なエラー")
+            ));
+        }
+
+        Ok(json)
+    }
+
+    /// インテグレーショントークンを取得
+    fn token(&self) -> Result<String> {
+        let notion = &self.config.publish.notion;
+        if let Some(token) = &notion.token {
+            return Ok(token.clone());
+        }
+        std::env::var(&notion.token_env).map_err(|_| {
+            anyhow::anyhow!(
+                "Notionのトークンが設定されていません。publish.notion.token または環境変数 {} を設定してください",
+                notion.token_env
+            )
+        })
+    }
+}
+
+/// 作成したページ
+struct CreatedPage {
+    id: String,
+    url: String,
+}
+
+/// Notionエクスポート結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotionExportResult {
+    pub ok: bool,
+    pub pages: usize,
+    pub root_page_id: Option<String>,
+    pub url: Option<String>,
+}
+
+/// SUMMARY.mdのセクション
+#[derive(Debug, PartialEq)]
+struct Section {
+    title: String,
+    /// ソースディレクトリからの相対パス
+    file: String,
+    /// 入れ子の深さ（トップレベルは0）
+    depth: usize,
+}
+
+/// SUMMARY.mdからセクションを順序どおりに読み込む（入れ子の深さを保つ）
+fn read_sections(src_dir: &Path) -> Result<Vec<Section>> {
+    let summary_path = src_dir.join("SUMMARY.md");
+    let summary = fs::read_to_string(&summary_path)
+        .with_context(|| format!("SUMMARY.mdが見つかりません。先にWikiを生成してください: {:?}", summary_path))?;
+    Ok(parse_summary(&summary))
+}
+
+/// SUMMARY.mdの内容をセクションのリストに変換
+fn parse_summary(summary: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    // 親のセクションのインデント幅（深さの順）
+    let mut indents: Vec<usize> = Vec::new();
+    for line in summary.lines() {
+        let content = line.trim_start();
+        let Some((title, file)) = content.strip_prefix("- ").and_then(parse_summary_link) else {
+            continue;
+        };
+        let indent = line.len() - content.len();
+        while indents.last().is_some_and(|&parent| parent >= indent) {
+            indents.pop();
+        }
+        // 親のないインデント（SUMMARY.mdの書き誤り）は1段だけ深くする
+        let depth = indents.len().min(sections.last().map_or(0, |s| s.depth + 1));
+        indents.truncate(depth);
+        indents.push(indent);
+        sections.push(Section { title, file, depth });
+    }
+    sections
+}
+
+/// `[タイトル](ファイル)` 形式のリンクを解析
+fn parse_summary_link(s: &str) -> Option<(String, String)> {
+    let s = s.strip_prefix('[')?;
+    let (title, rest) = s.split_once("](")?;
+    let (file, _) = rest.split_once(')')?;
+    Some((title.to_string(), file.to_string()))
+}
+
+/// modules.mdを導入部と各モジュール（`## ` 見出し単位）に分割
+///
+/// モジュールの見出しは、モジュール一覧のページ内リンク（`- [id](#anchor)`）に載っているものとみなす
+/// （一覧の見出しはサイトの言語で変わるため、見出しの文言では判定しない）
+fn split_module_pages(markdown: &str) -> (String, Vec<(String, String)>) {
+    let listed: Vec<String> = markdown
+        .lines()
+        .filter_map(|line| parse_summary_link(line.trim().strip_prefix("- ")?))
+        .filter(|(_, target)| target.starts_with('#'))
+        .map(|(title, _)| title)
+        .collect();
+    let mut intro = String::new();
+    let mut modules: Vec<(String, String)> = Vec::new();
+
+    for line in markdown.lines() {
+        if let Some(title) = line.strip_prefix("## ") {
+            if listed.iter().any(|module| module == title.trim()) {
+                modules.push((title.trim().to_string(), String::new()));
+                continue;
+            }
+        }
+        match modules.last_mut() {
+            Some((_, body)) => {
+                body.push_str(line);
+                body.push('\n');
+            }
+            None => {
+                intro.push_str(line);
+                intro.push('\n');
+            }
+        }
+    }
+
+    (intro, modules)
+}
+
+/// MarkdownをNotionブロックに変換
+///
+/// # 引数
+/// * `markdown` - Markdown文字列
+///
+/// # 戻り値
+/// * `Vec<Value>` - Notionブロックのリスト
+pub fn markdown_to_blocks(markdown: &str) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut code: Option<(String, Vec<String>)> = None;
+
+    let flush_paragraph = |blocks: &mut Vec<Value>, paragraph: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            blocks.push(text_block("paragraph", &paragraph.join("\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        if let Some((lang, lines)) = code.as_mut() {
+            if line.trim_start().starts_with("```") {
+                blocks.push(code_block(lang, &lines.join("\n")));
+                code = None;
+            } else {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if let Some(lang) = trimmed.strip_prefix("```") {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            code = Some((lang.trim().to_string(), Vec::new()));
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut blocks, &mut paragraph);
+        } else if trimmed == "---" {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(json!({ "object": "block", "type": "divider", "divider": {} }));
+        } else if let Some(text) = trimmed.strip_prefix("# ") {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(text_block("heading_1", text));
+        } else if let Some(text) = trimmed.strip_prefix("## ") {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(text_block("heading_2", text));
+        } else if trimmed.starts_with("###") {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(text_block("heading_3", trimmed.trim_start_matches('#').trim()));
+        } else if let Some(text) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(text_block("bulleted_list_item", text));
+        } else if let Some(text) = numbered_item(trimmed) {
+            flush_paragraph(&mut blocks, &mut paragraph);
+            blocks.push(text_block("numbered_list_item", text));
+        } else {
+            paragraph.push(trimmed.to_string());
+        }
+    }
+
+    // 閉じられていないコードブロックもそのまま出力
+    if let Some((lang, lines)) = code {
+        blocks.push(code_block(&lang, &lines.join("\n")));
+    }
+    flush_paragraph(&mut blocks, &mut paragraph);
+
+    blocks
+}
+
+/// `1. 項目` 形式の番号付きリストを判定
+fn numbered_item(line: &str) -> Option<&str> {
+    let (number, rest) = line.split_once(". ")?;
+    if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// テキストを持つブロックを作成
+fn text_block(block_type: &str, text: &str) -> Value {
+    json!({
+        "object": "block",
+        "type": block_type,
+        block_type: { "rich_text": rich_text(&text.replace("**", "")) }
+    })
+}
+
+/// コードブロックを作成
+fn code_block(lang: &str, source: &str) -> Value {
+    json!({
+        "object": "block",
+        "type": "code",
+        "code": {
+            "rich_text": rich_text(source),
+            "language": notion_language(lang),
+        }
+    })
+}
+
+/// リッチテキスト配列を作成（文字数上限ごとに分割）
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.chars().collect();
+    let parts: Vec<Value> = chars
+        .chunks(MAX_RICH_TEXT_CHARS)
+        .map(|chunk| {
+            json!({
+                "type": "text",
+                "text": { "content": chunk.iter().collect::<String>() }
+            })
+        })
+        .collect();
+    Value::Array(parts)
+}
+
+/// コードフェンスの言語名をNotionの言語名に変換
+fn notion_language(lang: &str) -> &'static str {
+    match lang {
+        "rs" | "rust" => "rust",
+        "ts" | "tsx" | "typescript" => "typescript",
+        "js" | "jsx" | "javascript" => "javascript",
+        "py" | "python" => "python",
+        "go" => "go",
+        "java" => "java",
+        "sh" | "bash" | "shell" => "shell",
+        "json" => "json",
+        "yaml" | "yml" => "yaml",
+                "mermaid" => "mermaid",
+        _ => "plain text",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_blocks() {
+        let markdown = "# 概要\n\n説明文です。\n\n- 項目\n\n```rs\nfn main() {}\n```\n\n```mermaid\ngraph TD\n```\n";
+        let blocks = markdown_to_blocks(markdown);
+        let types: Vec<&str> = blocks.iter().map(|b| b["type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["heading_1", "paragraph", "bulleted_list_item", "code", "code"]);
+        assert_eq!(blocks[3]["code"]["language"], "rust");
+        // Mermaid図は外部サービスに送らず、コードブロックとして送信する
+        assert_eq!(blocks[4]["code"]["language"], "mermaid");
+        assert_eq!(blocks[4]["code"]["rich_text"][0]["text"]["content"], "graph TD");
+        assert_eq!(notion_language("toml"), "plain text");
+    }
+
+    #[test]
+    fn test_parse_nested_summary() {
+        let summary = "# Summary\n\n- [概要](overview.md)\n- [モジュール](modules.md)\n  - [core](modules/core/index.md)\n    - [core::store](modules/core/store.md)\n  - [cli](modules/cli/index.md)\n- [FAQ](faq.md)\n";
+        let sections = parse_summary(summary);
+        let sections: Vec<(&str, usize)> = sections.iter().map(|s| (s.file.as_str(), s.depth)).collect();
+        assert_eq!(
+            sections,
+            vec![
+                ("overview.md", 0),
+                ("modules.md", 0),
+                ("modules/core/index.md", 1),
+                ("modules/core/store.md", 2),
+                ("modules/cli/index.md", 1),
+                ("faq.md", 0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offline_refuses_export() {
+        let config = Config::default();
+        assert!(config.security.offline);
+        let error = NotionPublisher::new(config).export_wiki("missing").await.unwrap_err();
+        assert!(error.to_string().contains("security.offline"), "{}", error);
+    }
+
+    #[test]
+    fn test_split_module_pages() {
+        let markdown = "# モジュール\n\n## モジュール一覧\n\n- [a](#a)\n\n## a\n\n本文\n";
+        let (intro, modules) = split_module_pages(markdown);
+        assert!(intro.contains("モジュール一覧"));
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].0, "a");
+
+        // 英語のサイトでも一覧の見出しをモジュールとして扱わない
+        let markdown = "# Modules\n\n## Module list\n\n- [core::store](#corestore)\n\n---\n\n## core::store\n\nBody\n\n## Notes\n";
+        let (intro, modules) = split_module_pages(markdown);
+        assert!(intro.contains("Module list"));
+        assert_eq!(modules.iter().map(|(title, _)| title.as_str()).collect::<Vec<_>>(), vec!["core::store"]);
+        assert!(modules[0].1.contains("## Notes"));
+    }
+}
@@ -0,0 +1,199 @@
+/*!
+ * ドキュメントの状態を示すSVGバッジ
+ *
+ * 生成したWikiの状態をshields.io風のバッジにする
+ * - docs coverage: ドキュメントコメントのある関数・型の割合
+ * - modules documented: Wikiに詳細ページを掲載したモジュール数と全モジュール数
+ * - docs generated: Wikiを生成した日付
+ *
+ * 主な仕様:
+ * - ソースディレクトリの `badges/` に書き出し、mdBookのビルドで公開するサイトにコピーされる
+ *   （リポジトリのREADMEから `https://<公開先>/badges/docs-coverage.svg` で参照できる）
+ * - カバレッジはテストファイルと非公開（private）のシンボルを除いて数える
+ * - カバレッジ80%以上は緑、50%以上は黄、それ未満は赤
+ *
+ * 制限事項:
+ * - 文字幅は等幅に近い概算のため、フォントによっては余白がずれる
+ * - 外部のバッジサービスは使わない（オフラインで生成する）
+ */
+
+use analyzer_core::Index;
+
+/// ドキュメントの有無を数えるシンボルの種類
+const DOCUMENTABLE_KINDS: &[&str] = &["function", "method", "struct", "enum", "trait", "class", "interface"];
+
+/// バッジ1件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Badge {
+    /// ファイル名（拡張子なし）
+    pub name: String,
+    /// 左側のラベル
+    pub label: String,
+    /// 右側の値
+    pub message: String,
+    /// 右側の背景色
+    pub color: String,
+}
+
+impl Badge {
+    fn new(name: &str, label: &str, message: String, color: &str) -> Self {
+        Self { name: name.to_string(), label: label.to_string(), message, color: color.to_string() }
+    }
+
+    /// SVGとして出力
+    pub fn to_svg(&self) -> String {
+        render_badge(&self.label, &self.message, &self.color)
+    }
+}
+
+/// Wikiの状態を示すバッジを作成
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `documented_modules` - 詳細ページを掲載したモジュール数
+/// * `generated` - 生成日（`YYYY-MM-DD`）
+///
+/// # 戻り値
+/// * `Vec<Badge>` - docs-coverage・modules-documented・docs-generatedのバッジ
+pub fn health_badges(index: &Index, documented_modules: usize, generated: &str) -> Vec<Badge> {
+    let coverage = match doc_coverage(index) {
+        Some(ratio) => {
+            let color = match ratio {
+                r if r >= 0.8 => "#4c1",
+                r if r >= 0.5 => "#dfb317",
+                _ => "#e05d44",
+            };
+            Badge::new("docs-coverage", "docs coverage", format!("{:.0}%", ratio * 100.0), color)
+        }
+        None => Badge::new("docs-coverage", "docs coverage", "n/a".to_string(), "#9f9f9f"),
+    };
+    let modules = index.modules.len();
+    let modules_color = if modules > 0 && documented_modules >= modules { "#4c1" } else { "#007ec6" };
+    vec![
+        coverage,
+        Badge::new("modules-documented", "modules documented", format!("{}/{}", documented_modules, modules), modules_color),
+        Badge::new("docs-generated", "docs generated", generated.to_string(), "#007ec6"),
+    ]
+}
+
+/// ドキュメントコメントのある関数・型の割合（対象のシンボルがない場合はNone）
+pub fn doc_coverage(index: &Index) -> Option<f64> {
+    let (mut documented, mut total) = (0usize, 0usize);
+    let symbols = index
+        .files
+        .iter()
+        .filter(|f| !f.is_test)
+        .flat_map(|f| &f.symbols)
+        .filter(|s| DOCUMENTABLE_KINDS.contains(&s.kind.as_str()) && s.visibility != "private");
+    for symbol in symbols {
+        total += 1;
+        if !symbol.documentation.trim().is_empty() {
+            documented += 1;
+        }
+    }
+    (total > 0).then(|| documented as f64 / total as f64)
+}
+
+/// shields.io風（flat）のバッジのSVGを描画
+///
+/// # 引数
+/// * `label` - 左側のラベル
+/// * `message` - 右側の値
+/// * `color` - 右側の背景色（CSSの色）
+///
+/// # 戻り値
+/// * `String` - SVG
+pub fn render_badge(label: &str, message: &str, color: &str) -> String {
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(message) + 10;
+    let width = label_width + message_width;
+    let (label, message, color) = (escape_xml(label), escape_xml(message), escape_xml(color));
+    format!(
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"20\" role=\"img\" aria-label=\"{label}: {message}\">",
+            "<title>{label}: {message}</title>",
+            "<linearGradient id=\"s\" x2=\"0\" y2=\"100%\"><stop offset=\"0\" stop-color=\"#bbb\" stop-opacity=\".1\"/><stop offset=\"1\" stop-opacity=\".1\"/></linearGradient>",
+            "<clipPath id=\"r\"><rect width=\"{width}\" height=\"20\" rx=\"3\" fill=\"#fff\"/></clipPath>",
+            "<g clip-path=\"url(#r)\"><rect width=\"{label_width}\" height=\"20\" fill=\"#555\"/>",
+            "<rect x=\"{label_width}\" width=\"{message_width}\" height=\"20\" fill=\"{color}\"/>",
+            "<rect width=\"{width}\" height=\"20\" fill=\"url(#s)\"/></g>",
+            "<g fill=\"#fff\" text-anchor=\"middle\" font-family=\"Verdana,Geneva,DejaVu Sans,sans-serif\" font-size=\"11\">",
+            "<text x=\"{label_x}\" y=\"14\">{label}</text><text x=\"{message_x}\" y=\"14\">{message}</text></g></svg>\n"
+        ),
+        width = width,
+        label_width = label_width,
+        message_width = message_width,
+        label_x = label_width as f64 / 2.0,
+        message_x = label_width as f64 + message_width as f64 / 2.0,
+        label = label,
+        message = message,
+        color = color,
+    )
+}
+
+/// 11pxのVerdanaでの文字列の幅の概算（全角文字は半角の約2倍）
+fn text_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 7 } else { 12 }).sum()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analyzer_core::{FileInfo, Symbol};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_health_badges() {
+        let symbol = |kind: &str, documentation: &str, visibility: &str| Symbol {
+            kind: kind.to_string(),
+            documentation: documentation.to_string(),
+            visibility: visibility.to_string(),
+            ..Default::default()
+        };
+        let index = Index {
+            files: vec![
+                FileInfo {
+                    path: PathBuf::from("src/lib.rs"),
+                    symbols: vec![
+                        symbol("function", "/// 設定を読み込む", "public"),
+                        symbol("struct", "", "public"),
+                        symbol("function", "", "private"),
+                        symbol("impl", "", ""),
+                    ],
+                    ..Default::default()
+                },
+                FileInfo {
+                    path: PathBuf::from("tests/it.rs"),
+                    is_test: true,
+                    symbols: vec![symbol("function", "", "")],
+                    ..Default::default()
+                },
+            ],
+            modules: vec![Default::default(), Default::default()],
+            ..Default::default()
+        };
+        assert_eq!(doc_coverage(&index), Some(0.5));
+
+        let badges = health_badges(&index, 2, "2026-10-15");
+        let summary: Vec<(&str, &str, &str)> =
+            badges.iter().map(|b| (b.name.as_str(), b.message.as_str(), b.color.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("docs-coverage", "50%", "#dfb317"),
+                ("modules-documented", "2/2", "#4c1"),
+                ("docs-generated", "2026-10-15", "#007ec6"),
+            ]
+        );
+        assert_eq!(health_badges(&Index::default(), 0, "2026-10-15")[0].message, "n/a");
+
+        let svg = render_badge("docs <coverage>", "50%", "#dfb317");
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"146\" height=\"20\""));
+        assert!(svg.contains("<title>docs &lt;coverage&gt;: 50%</title>"));
+        assert!(svg.contains("fill=\"#dfb317\""));
+    }
+}
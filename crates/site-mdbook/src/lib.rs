@@ -1,4 +1,4 @@
-/**
+/*!
  * mdBookサイト生成実装
  * 
  * DeepWiki風のドキュメントサイトをmdBookで構築する
@@ -8,34 +8,110 @@
  * - Mermaid図の埋め込み
  * 
  * 主な仕様:
- * - Overview, Architecture, Modules, Flows, Deploy, FAQの章構成
+ * - Overview, Architecture, Metrics, Hotspots, Tests, Modules, Flows, Deploy, FAQの章構成
+ * - メトリクスの章に行数・コメント率と、複雑度の高いファイル・関数の表を掲載
+ * - モノレポ・ワークスペースの場合は「パッケージ」の章にパッケージの一覧と依存関係、パッケージごとの要約を掲載
+ * - ホットスポットの章にGit履歴の変更回数と複雑度から選んだファイルを、順位・ソースへのリンク・月ごとの変更回数の推移とともに掲載
+ * - デプロイの章にDockerfileの起動コマンドと、Webフレームワーク・実行ファイルのエントリーポイントを掲載
+ * - プロジェクトの歩みの章に、タグ（リリース）・大規模な変更・ドキュメントの再生成をMermaidのタイムラインと表で掲載
+ * - テストの章にモジュールごとのテストの有無・テスト数と、テストのないモジュール（重要度順）を掲載
+ * - 未使用コードの章にどこからもimportされないモジュールと参照されない公開関数・メソッドを掲載
+ * - 技術的負債の章にTODO・FIXME・HACK・XXXのコメントを目印・作成者ごとの件数と一覧で掲載し、FAQに件数と多いファイルを掲載
+ * - APIエンドポイントを検出した場合は「API」の章にREST・GraphQLのメソッド・パス・ハンドラーの一覧を追加
+ * - ベンダリングディレクトリがある場合は「サードパーティ」付録を追加
+ * - アーキテクチャの章に循環依存の警告（最短の循環の経路）と、循環を強調した図を掲載
+ * - アーキテクチャの章に条件付きでビルド・読み込まれるモジュール（Cargoのフィーチャー・`cfg`・プリプロセッサ・環境変数）と条件を掲載
+ * - アーキテクチャの章にARCHITECTURE.md・ADRに書かれた構成と実装の差分（ドキュメントにあってコードにないもの・その逆）を掲載
+ * - 設定ファイル（tsconfig・Cargo.tomlのフィーチャー・.env.example・webpack）がある場合は「設定ファイル」の章に項目と説明を掲載
+ * - 名前に繰り返し現れるドメインの用語がある場合は「用語集」の章に定義を掲載し、モジュールのページから用語へリンク
+ * - 外部依存がある場合は、解決したバージョン・ライセンスとコピーレフトの警告を「依存ライセンス」付録に掲載
+ * - パッケージにREADME.mdがある場合は、生成した解説より先に掲載
+ * - 概要にリポジトリのREADMEの冒頭とライセンス・貢献ガイド・変更履歴へのリンクを、FAQにライセンスの種類を掲載
+ * - FAQにテスト・設定ファイルの場所、モジュールの連携、呼び出す外部サービスを該当するファイルへのリンク付きで掲載
+ * - `[branding]` のタイトル・チームをbook.tomlに、フッターを各ページの末尾に、問い合わせ先・リンクをFAQに掲載
+ * - 概要とモジュールの解説はテンプレート（`wiki/overview.md`・`wiki/module.md`、`templates.dir` で上書き可能）で組み立てる
+ * - 生成した各ページの品質スコアを結果に含める（qualityモジュール）
+ * - ドキュメントのカバレッジ・掲載モジュール数・生成日のSVGバッジを `badges/` に出力（badgesモジュール、`site.badges`）
+ * - 生成できなかった図・モジュールの解説、掲載を省略したモジュール、見つからないmdBookは警告として結果に含める
+ * - ドライラン（plan_wiki）は書き込まずに生成予定のファイルとサイズを返す
+ * - 生成を終えたセクションのページ・モジュールの解説を出力ディレクトリに記録し、`with_resume` で中断したビルドを続きから再開する（progressモジュール）
+ * - セクション・モジュールはワーカープール（`summarization.workers`、`with_executor` でスライドと共有）で並列に生成し、
+ *   セクション → モジュール → 付録（未使用コード・依存ライセンス・サードパーティ）の順に優先する
+ * - ソース・ビルド先のディレクトリとセクションごとのファイル名は `site.layout` で変更可能
+ * - ワークスペースのパッケージが複数ある場合（または `site.layout.package-dirs`）は、モジュールのページをパッケージごとのディレクトリに分割し、
+ *   パッケージの目次ページ（要約・モジュールグラフ・モジュールへのリンク）の下にモジュールごとのページを入れ子で掲載
+ * - `security.read-only-repo` の場合、出力先が解析対象のリポジトリ内なら作業ディレクトリ（`security.workspace-dir`）配下に出力
+ * - モジュールの見出しはモジュールID（`web:src/index.ts` など）で、別パッケージの同名ファイルでもアンカーが重複しない
+ * - アーキテクチャに言語別・パッケージ別の規模の表と、型の実装・継承関係（トレイト・インターフェースごとの一覧とクラス図）を掲載
+ * - 章の名前・ページの見出しと概要・モジュールの定型文は `project.language`（ja|en）に合わせ、要約も同じ言語のスタイルで生成（localeモジュール）
  * - Mermaid対応のテーマ設定
  * - GitHub Pages対応（/docsディレクトリに出力可能）
  * 
  * 制限事項:
  * - mdBookは外部コマンドとして実行（crate APIは使用しない）
+ * - mdBookがない場合・失敗した場合（時間切れ・出力の欠けを含む）はMarkdownのみを生成し、ビルド結果をスキップした成果物として返す
  * - カスタムテーマは最小限の設定のみ
+ * - 英語の場合も、メトリクス・依存ライセンスなどの章の表と説明文は日本語のまま
  */
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::process::Command;
 use anyhow::{Context, Result};
 use tracing::info;
 
 use config::Config;
-use analyzer_core::Index;
-use summarizer::Summarizer;
-use diagrammer::Diagrammer;
+use analyzer_core::{marker_label, Diagnostic, Diagnostics, Executor, Index, Priority, TimelineKind, TODO_MARKERS};
+use summarizer::{Locale, PlannedFile, SkippedArtifact, Summarizer, Templates, ToolCommand, ToolFailure, Tools};
+use diagrammer::{Diagram, Diagrammer};
+
+mod badges;
+mod locale;
+mod progress;
+mod quality;
+
+use locale::WikiText;
+use progress::Progress;
+
+/// modules以外で内容を生成できるセクション
+const SECTIONS: &[&str] = &["overview", "architecture", "packages", "metrics", "hotspots", "timeline", "tests", "unused", "debt", "api", "flows", "deploy", "config", "glossary", "faq", "licenses", "third-party"];
+/// 付録として他のページより後に生成するセクション
+const APPENDIX_SECTIONS: &[&str] = &["unused", "licenses", "third-party"];
+/// アーキテクチャの実装・継承関係の一覧に載せるトレイト・インターフェースの最大数
+const MAX_RELATION_TARGETS: usize = 30;
+/// アーキテクチャの層ごとの一覧・層の逆転の一覧に載せるモジュールの最大数
+const MAX_LAYER_ROWS: usize = 30;
+/// アーキテクチャの循環依存の警告に載せる循環の最大数
+const MAX_CYCLE_WARNINGS: usize = 20;
+/// アーキテクチャの条件付きのモジュールの一覧に載せる最大数
+const MAX_CONDITIONAL_ROWS: usize = 50;
+/// FAQの回答に並べる項目（ディレクトリ・ファイル）の最大数
+const MAX_FAQ_ITEMS: usize = 5;
+/// アーキテクチャのドキュメントと実装の差分の一覧に載せる最大数
+const MAX_DRIFT_ROWS: usize = 50;
+/// メトリクスの表に載せるファイル・関数の最大数
+const MAX_METRICS_ROWS: usize = 20;
+/// APIの章に載せるエンドポイントの最大数
+const MAX_API_ROWS: usize = 200;
+
+pub use badges::{doc_coverage, health_badges, render_badge, Badge};
+pub use quality::{score_page, PageQuality};
 
 /// mdBookビルダー
 pub struct MdBookBuilder {
     config: Config,
-    #[allow(dead_code)] // 後方互換性のため保持（非並列実行時のgenerate_sectionメソッドで使用）
+    /// 単一ページの生成（render_page）と非並列実行時のgenerate_sectionメソッドで使用
     summarizer: Summarizer,
-    #[allow(dead_code)] // 後方互換性のため保持（非並列実行時のgenerate_sectionメソッドで使用）
     diagrammer: Diagrammer,
+    /// 警告の記録先（並列に生成するセクション・モジュールと共有）
+    diagnostics: Diagnostics,
+    /// セクション・モジュールの生成タスクを実行するワーカープール（スライドと共有可能）
+    executor: Executor,
+    /// mdBookの実行方法（テストでは失敗を再現する実行方法に差し替える）
+    tools: Tools,
+    /// 前回の途中経過から生成済みのページを再利用するか
+    resume: bool,
 }
 
 impl MdBookBuilder {
@@ -51,9 +127,64 @@ impl MdBookBuilder {
             config: config.clone(),
             summarizer: Summarizer::new(config.clone()),
             diagrammer: Diagrammer::new(config.clone()),
+            diagnostics: Diagnostics::new(),
+            executor: Executor::new(config.summarization.workers),
+            tools: Tools::system(),
+            resume: false,
+        }
+    }
+
+    /// 警告の記録先を指定（解析・スライドと共有して最後にまとめて表示する場合）
+    /// 
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    /// 
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたmdBookビルダー
+    pub fn with_diagnostics(self, diagnostics: Diagnostics) -> Self {
+        Self {
+            summarizer: self.summarizer.with_diagnostics(diagnostics.clone()),
+            diagrammer: self.diagrammer.with_diagnostics(diagnostics.clone()),
+            diagnostics,
+            ..self
         }
     }
 
+    /// ワーカープールを指定（スライドと同時実行数の上限を共有する場合）
+    /// 
+    /// # 引数
+    /// * `executor` - ワーカープール
+    /// 
+    /// # 戻り値
+    /// * `Self` - ワーカープールを差し替えたmdBookビルダー
+    pub fn with_executor(self, executor: Executor) -> Self {
+        Self { executor, ..self }
+    }
+
+    /// 外部ツールの実行方法を指定（テストでmdBookの失敗・タイムアウトを再現する場合）
+    /// 
+    /// # 引数
+    /// * `tools` - 外部ツールの実行方法
+    /// 
+    /// # 戻り値
+    /// * `Self` - 実行方法を差し替えたmdBookビルダー
+    pub fn with_tools(self, tools: Tools) -> Self {
+        Self { tools, ..self }
+    }
+
+    /// 中断したビルドを続きから再開するか指定（`wiki --resume`）
+    /// 
+    /// 入力（インデックス・設定・図の有無・目次）が前回と同じ場合、生成済みのセクションのページとモジュールの解説を再利用する。
+    /// 
+    /// # 引数
+    /// * `resume` - 前回の途中経過を再利用するか
+    /// 
+    /// # 戻り値
+    /// * `Self` - 指定を反映したmdBookビルダー
+    pub fn with_resume(self, resume: bool) -> Self {
+        Self { resume, ..self }
+    }
+
     /// Wikiをビルド
     /// 
     /// # 引数
@@ -73,117 +204,283 @@ impl MdBookBuilder {
     ) -> Result<WikiResult> {
         info!("Wikiビルド開始: out_dir={}", out_dir);
 
-        let out_path = PathBuf::from(out_dir);
-        let src_dir = out_path.join("src");
+        // 生成後にmdBookがないことで失敗しないよう、先に確認してMarkdownのみの出力に切り替える
+        let mdbook = self.tools.check("mdbook");
+        if !mdbook.available {
+            self.diagnostics.warn("wiki", "mdbookが見つからないため、Markdownのみを生成しました");
+        }
+
+        let layout = &self.config.site.layout;
+        // security.read-only-repoの場合、リポジトリ内の出力先は作業ディレクトリ配下に置き換える
+        let out_path = self.config.security.output_path(&index.repo_path, Path::new(out_dir));
+        let src_dir = out_path.join(&layout.src_dir);
         fs::create_dir_all(&src_dir)?;
 
+        let toc = effective_toc(index, toc);
+        let toc = toc.as_slice();
+        // templates.dirの誤りは生成を始める前にエラーにする
+        let templates = Templates::load(&self.config)?;
+        // 生成を終えたページを記録し、再開時は入力が同じなら生成済みのページを再利用する
+        let progress = Progress::start(&out_path, &progress::fingerprint(index, &self.config, with_diagrams, toc), self.resume)?;
+        let mut resumed = 0;
+        let mut resumed_modules = 0;
+
         // book.tomlを生成
         self.generate_book_toml(&out_path)?;
 
         // SUMMARY.mdを生成
-        self.generate_summary(&src_dir, toc)?;
+        self.generate_summary(index, &src_dir, toc)?;
 
-        // 各章を並列生成（50並列対応：tech-book-readerの実装を参考）
+        // 各章をワーカープールで並列生成（付録はほかのセクション・モジュールの後）
         // インデックスと設定をクローンして各セクションで使用可能にする
         let index_clone = index.clone();
         let config_clone = self.config.clone();
         
         // 各セクションの生成を並列実行
         let mut section_handles = Vec::new();
+        let mut pages = 0;
         for section in toc {
             let section = section.clone();
-            let src_dir_clone = src_dir.clone();
-            let with_diagrams = with_diagrams;
+            let file = layout.section_file(&section);
+            if section != "modules" && progress.page_done(&src_dir, &file) {
+                info!("生成済みのページを再利用: {}", file);
+                pages += 1;
+                resumed += 1;
+                continue;
+            }
+            let file_path = src_dir.join(&file);
+            let src_dir = src_dir.clone();
+            let progress = progress.clone();
             let index_for_section = index_clone.clone();
             let config_for_section = config_clone.clone();
+            let diagnostics = self.diagnostics.clone();
+            let templates = templates.clone();
+            let priority = section_priority(&section);
             
-            let handle = tokio::spawn(async move {
+            let handle = self.executor.spawn(priority, async move {
                 // 各セクション用に新しいインスタンスを作成
-                let summarizer = Summarizer::new(config_for_section.clone());
-                let diagrammer = Diagrammer::new(config_for_section.clone());
+                let summarizer = Summarizer::new(config_for_section.clone())
+                    .with_diagnostics(diagnostics.clone())
+                    .with_templates(templates);
+                let diagrammer = Diagrammer::new(config_for_section.clone()).with_diagnostics(diagnostics.clone());
                 
-                Self::generate_section_parallel(
+                let page_count = Self::generate_section_parallel(
                     &index_for_section,
-                    &src_dir_clone,
+                    &file_path,
                     &section,
                     with_diagrams,
+                    &config_for_section,
                     &summarizer,
                     &diagrammer,
-                ).await
+                ).await?;
+                if section != "modules" {
+                    if let Err(e) = progress.record_page(&src_dir, &file) {
+                        diagnostics.warn("wiki", format!("生成の途中経過を記録できませんでした: {}", e));
+                    }
+                }
+                Ok::<usize, anyhow::Error>(page_count)
             });
             section_handles.push(handle);
         }
         
-        // モジュールページは50並列で1つのファイルにまとめて生成
+        // モジュールページはワーカープールで並列に生成し、1つのファイル（パッケージ別の場合はパッケージごと）にまとめる
         if toc.contains(&"modules".to_string()) {
-            let index_for_modules = index_clone.clone();
-            let config_for_modules = config_clone.clone();
-            
-            // まず、モジュール一覧を生成
-            let mut modules_content = String::from("# モジュール\n\n");
-            modules_content.push_str("このセクションでは、各モジュールについて詳しく説明します。\n\n");
-            modules_content.push_str("## モジュール一覧\n\n");
-            for module in &index.modules {
-                // mdBookのアンカーリンクは見出しから自動生成されるため、見出しテキストをそのまま使用
-                // 特殊文字はmdBookが自動的に処理するので、そのまま使用
-                modules_content.push_str(&format!("- [{}](#{})\n", module.name, module.name));
-            }
-            modules_content.push_str("\n\n---\n\n");
-            
-            // 各モジュールごとに50並列で処理して、1つのファイルにまとめる
-            let mut module_handles = Vec::new();
-            let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(50));
-            
-            for module in &index.modules {
-                let module = module.clone();
-                let index_for_module = index_for_modules.clone();
-                let config_for_module = config_for_modules.clone();
-                let permit = semaphore.clone();
-                
-                let handle = tokio::spawn(async move {
-                    let _permit = permit.acquire().await.unwrap();
-                    let summarizer = Summarizer::new(config_for_module.clone());
-                    
-                    Self::generate_module_content_detailed(
-                        &index_for_module,
-                        &module,
-                        &summarizer,
-                    ).await
-                });
-                module_handles.push(handle);
-            }
-            
-            // すべてのモジュールページを並列実行して結果を収集
-            for handle in module_handles {
-                if let Ok(Ok(module_content)) = handle.await {
-                    modules_content.push_str(&module_content);
-                    modules_content.push_str("\n\n---\n\n");
-                }
+            let module_pages = Self::generate_module_pages(
+                index,
+                &self.config,
+                with_diagrams,
+                &templates,
+                &self.diagnostics,
+                &self.executor,
+                Some(&progress),
+            )
+            .await;
+            resumed_modules = module_pages.resumed;
+            write_page(&src_dir.join(layout.section_file("modules")), &module_pages.index)?;
+            for (file, content) in &module_pages.packages {
+                write_page(&src_dir.join(file), content)?;
             }
-            
-            // 1つのファイルにまとめる
-            let modules_file_path = src_dir.join("modules.md");
-            fs::write(&modules_file_path, modules_content)
-                .with_context(|| format!("modules.mdの書き込みに失敗しました: {:?}", modules_file_path))?;
+            pages += module_pages.packages.len();
         }
         
         // すべてのセクションを並列実行して結果を収集
-        let mut pages = 0;
         for handle in section_handles {
             let page_count = handle.await??;
             pages += page_count;
         }
 
+        // 生成したページを採点
+        let quality: Vec<PageQuality> = toc
+            .iter()
+            .filter_map(|section| {
+                let markdown = fs::read_to_string(src_dir.join(layout.section_file(section))).ok()?;
+                Some(score_page(section, &markdown))
+            })
+            .collect();
+
+        // ドキュメントの状態を示すバッジ（ソースディレクトリに置き、mdBookのビルドで公開するサイトにコピーされる）
+        let mut badges = Vec::new();
+        if self.config.site.badges {
+            fs::create_dir_all(src_dir.join("badges"))?;
+            for badge in self.health_badges(index, toc) {
+                let path = src_dir.join("badges").join(format!("{}.svg", badge.name));
+                write_page(&path, &badge.to_svg())?;
+                badges.push(path);
+            }
+        }
+
         // mdBookをビルド
-        self.build_mdbook(&out_path)?;
+        let site_dir = out_path.join(&layout.build_dir);
+        let mut skipped = Vec::new();
+        if !mdbook.available {
+            skipped.push(SkippedArtifact::missing_tool(&site_dir, &mdbook));
+        } else if let Err(failure) = self.build_mdbook(&out_path, &site_dir) {
+            // 生成したMarkdownは残し、HTMLのサイトだけをスキップした成果物として返す
+            self.diagnostics.warn("wiki", format!("mdBookのビルドに失敗したため、Markdownのみを生成しました: {}", failure));
+            skipped.push(SkippedArtifact::failed_tool(&site_dir, &failure));
+        }
 
         Ok(WikiResult {
             ok: true,
-            site_dir: out_path.join("book"),
+            site_dir,
             pages,
+            resumed,
+            resumed_modules,
+            quality,
+            badges,
+            skipped,
+            diagnostics: self.diagnostics.entries(),
         })
     }
 
+    /// Wikiの状態を示すバッジを作成（生成日は今日）
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `toc` - 実際に生成する目次（モジュールの章がなければ掲載モジュール数は0）
+    ///
+    /// # 戻り値
+    /// * `Vec<Badge>` - バッジ
+    fn health_badges(&self, index: &Index, toc: &[String]) -> Vec<Badge> {
+        let documented_modules = match toc.iter().any(|s| s == "modules") {
+            true => index.modules_by_importance(self.config.site.max_modules).len(),
+            false => 0,
+        };
+        health_badges(index, documented_modules, &chrono::Local::now().format("%Y-%m-%d").to_string())
+    }
+
+    /// Wikiのビルドで生成されるファイルを列挙（ディスクには書き込まない）
+    /// 
+    /// 各ページをメモリ上で生成してサイズを求める。mdBookの出力（ビルド先）はサイズ不明とする。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `with_diagrams` - 図を含めるか
+    /// * `toc` - 目次セクションのリスト
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<PlannedFile>>` - 出力ディレクトリからの相対パスで表した生成予定のファイル、またはエラー
+    pub async fn plan_wiki(&self, index: &Index, with_diagrams: bool, toc: &[String]) -> Result<Vec<PlannedFile>> {
+        let layout = &self.config.site.layout;
+        let toc = effective_toc(index, toc);
+        let summarizer = self.templated_summarizer()?;
+        let mut files = vec![
+            PlannedFile::new("book.toml", Some(self.book_toml().len()), "static"),
+            PlannedFile::new(
+                layout.src_dir.join("SUMMARY.md"),
+                Some(self.summary_markdown(index, &toc).len()),
+                "static",
+            ),
+        ];
+
+        for section in &toc {
+            // Wikiの生成にLLMは使わず、モジュールページのみヒューリスティックで解説する
+            let (content, generator) = if section == "modules" {
+                let module_pages = Self::generate_module_pages(
+                    index,
+                    &self.config,
+                    with_diagrams,
+                    summarizer.templates(),
+                    &self.diagnostics,
+                    &self.executor,
+                    None,
+                )
+                .await;
+                for (file, content) in &module_pages.packages {
+                    files.push(PlannedFile::new(layout.src_dir.join(file), Some(content.len()), "heuristic"));
+                }
+                (module_pages.index, "heuristic")
+            } else if SECTIONS.contains(&section.as_str()) {
+                let content =
+                    Self::generate_section_content(index, section, with_diagrams, &self.config, &summarizer, &self.diagrammer).await?;
+                (content, "static")
+            } else {
+                (placeholder_page(section, Locale::of(&self.config)), "static")
+            };
+            files.push(PlannedFile::new(layout.src_dir.join(layout.section_file(section)), Some(content.len()), generator));
+        }
+
+        if self.config.site.badges {
+            for badge in self.health_badges(index, &toc) {
+                let path = layout.src_dir.join("badges").join(format!("{}.svg", badge.name));
+                files.push(PlannedFile::new(path, Some(badge.to_svg().len()), "static"));
+            }
+        }
+
+        files.push(PlannedFile::new(format!("{}/", layout.build_dir.display()), None, "mdbook"));
+        Ok(files)
+    }
+
+    /// Wikiの1ページだけを生成（ディスクには書き込まない）
+    /// 
+    /// IDEなどでWiki全体をビルドせずにページを表示する用途を想定。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `page` - セクション名（overview|architecture|packages|metrics|hotspots|timeline|tests|unused|debt|api|modules|flows|deploy|config|glossary|faq|licenses|third-party）またはモジュールのパス
+    /// * `with_diagrams` - 図を含めるか
+    /// 
+    /// # 戻り値
+    /// * `Result<String>` - ページのMarkdown、またはエラー
+    pub async fn render_page(&self, index: &Index, page: &str, with_diagrams: bool) -> Result<String> {
+        info!("ページ生成: page={}", page);
+        let summarizer = self.templated_summarizer()?;
+
+        if page == "modules" {
+            let templates = summarizer.templates();
+            return Ok(Self::generate_module_pages(index, &self.config, with_diagrams, templates, &self.diagnostics, &self.executor, None)
+                .await
+                .index);
+        }
+        if SECTIONS.contains(&page) {
+            return Self::generate_section_content(index, page, with_diagrams, &self.config, &summarizer, &self.diagrammer).await;
+        }
+
+        // モジュールのパス（リポジトリルートからの相対パスも可）
+        let page_path = Path::new(page);
+        let module = index
+            .modules
+            .iter()
+            .find(|m| m.path == page_path || m.path.strip_prefix(&index.repo_path).is_ok_and(|p| p == page_path))
+            .ok_or_else(|| anyhow::anyhow!("不明なページです（セクション名またはモジュールのパスを指定してください）: {}", page))?;
+
+        Self::generate_module_content_detailed(
+            index,
+            module,
+            index.package_readme(&module.path),
+            module_term_links(index, &self.config, &[module]).get(&module.path).map_or("", |s| s.as_str()),
+            &summarizer,
+            self.config.site.max_methods_per_module,
+            Locale::of(&self.config),
+        )
+        .await
+    }
+
+    /// `templates.dir` のテンプレートを読み込んだサマライザー（1ページの生成・ドライラン用）
+    fn templated_summarizer(&self) -> Result<Summarizer> {
+        Ok(self.summarizer.clone().with_templates(Templates::load(&self.config)?))
+    }
+
     /// book.tomlを生成
     /// 
     /// # 引数
@@ -192,14 +489,27 @@ impl MdBookBuilder {
     /// # 戻り値
     /// * `Result<()>` - 成功、またはエラー
     fn generate_book_toml(&self, out_dir: &Path) -> Result<()> {
-        let book_toml = format!(
+        let book_toml = self.book_toml();
+        let book_toml_path = out_dir.join("book.toml");
+        fs::write(&book_toml_path, book_toml)
+            .with_context(|| format!("book.tomlの書き込みに失敗しました: {:?}", book_toml_path))?;
+
+        info!("book.tomlを生成しました: {:?}", book_toml_path);
+        Ok(())
+    }
+
+    /// book.tomlの内容
+    fn book_toml(&self) -> String {
+        let layout = &self.config.site.layout;
+        format!(
             r#"[book]
-title = "{}"
-authors = ["DeepRepoSlides"]
-language = "ja"
+title = {}
+authors = [{}]
+language = "{}"
+src = "{}"
 
 [build]
-build-dir = "book"
+build-dir = "{}"
 
 [output.html]
 default-theme = "navy"
@@ -208,34 +518,26 @@ preferred-dark-theme = "navy"
 [output.reveal]
 optional = true
 "#,
-            self.config.project.name
-        );
-
-        let book_toml_path = out_dir.join("book.toml");
-        fs::write(&book_toml_path, book_toml)
-            .with_context(|| format!("book.tomlの書き込みに失敗しました: {:?}", book_toml_path))?;
-
-        info!("book.tomlを生成しました: {:?}", book_toml_path);
-        Ok(())
+            // TOMLの基本文字列として書く（JSONの文字列リテラルと同じエスケープ）
+            serde_json::Value::from(self.config.branding.title(&self.config.project.name)),
+            serde_json::Value::from(self.config.branding.author(&self.config.project.name)),
+            Locale::of(&self.config).code(),
+            toml_path(&layout.src_dir),
+            toml_path(&layout.build_dir)
+        )
     }
 
     /// SUMMARY.mdを生成
     /// 
     /// # 引数
+    /// * `index` - インデックス（パッケージ別のモジュールページの目次に使用）
     /// * `src_dir` - ソースディレクトリ
     /// * `toc` - 目次セクションのリスト
     /// 
     /// # 戻り値
     /// * `Result<()>` - 成功、またはエラー
-    fn generate_summary(&self, src_dir: &Path, toc: &[String]) -> Result<()> {
-        let mut summary = String::from("# Summary\n\n");
-
-        for section in toc {
-            let section_name = self.get_section_name(section);
-            let file_name = format!("{}.md", section);
-            summary.push_str(&format!("- [{}]({})\n", section_name, file_name));
-        }
-
+    fn generate_summary(&self, index: &Index, src_dir: &Path, toc: &[String]) -> Result<()> {
+        let summary = self.summary_markdown(index, toc);
         let summary_path = src_dir.join("SUMMARY.md");
         fs::write(&summary_path, summary)
             .with_context(|| format!("SUMMARY.mdの書き込みに失敗しました: {:?}", summary_path))?;
@@ -244,17 +546,279 @@ optional = true
         Ok(())
     }
 
-    /// セクション名を取得
+    /// SUMMARY.mdの内容（パッケージ別のモジュールページはモジュールの下にパッケージ → モジュールの入れ子で掲載）
+    fn summary_markdown(&self, index: &Index, toc: &[String]) -> String {
+        let layout = &self.config.site.layout;
+        let mut summary = String::from("# Summary\n\n");
+        for section in toc {
+            let section_name = self.get_section_name(section);
+            let file_name = layout.section_file(section);
+            summary.push_str(&format!("- [{}]({})\n", section_name, file_name));
+            if section == "modules" && groups_by_package(index, &self.config) {
+                let modules = index.modules_by_importance(self.config.site.max_modules);
+                for (package, modules) in group_by_package(index, modules) {
+                    let package_file = package_page_file(&file_name, &package);
+                    summary.push_str(&format!("  - [{}]({})\n", package, package_file));
+                    let package_base = package_file.strip_suffix("index.md").unwrap_or(&package_file);
+                    for module in modules {
+                        summary.push_str(&format!(
+                            "    - [{}]({}{})\n",
+                            module.id,
+                            package_base,
+                            module_page_name(index, &package, module)
+                        ));
+                    }
+                }
+            }
+        }
+        summary
+    }
+
+    /// セクション名を取得（`project.language` の言語、不明なセクションはそのまま）
     fn get_section_name<'a>(&self, section: &'a str) -> &'a str {
-        match section {
-            "overview" => "概要",
-            "architecture" => "アーキテクチャ",
-            "modules" => "モジュール",
-            "flows" => "フロー",
-            "deploy" => "デプロイ",
-            "faq" => "FAQ",
-            _ => section,
+        Locale::of(&self.config).text(section)
+    }
+
+    /// モジュールページを生成（各モジュールをワーカープールで並列に生成して1ページにまとめる）
+    /// 
+    /// `site.layout.package-dirs` が有効な場合、またはワークスペースのパッケージが複数ある場合は、
+    /// モジュールセクションのページをパッケージごとの一覧のみとし、パッケージごとの目次ページ
+    /// （`<モジュールのファイル名>/<パッケージ>/index.md`、要約・モジュールグラフ・モジュールへのリンク）と、
+    /// その下にモジュールごとのページ（`<モジュールのファイル名>/<パッケージ>/<パッケージ内のパス>.md`）を生成する。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `config` - 設定
+    /// * `with_diagrams` - パッケージの目次ページにモジュールグラフを含めるか
+    /// * `templates` - 各モジュールの解説のテンプレート
+    /// * `diagnostics` - 生成に失敗したモジュールの記録先
+    /// * `executor` - 各モジュールの生成タスクを実行するワーカープール
+    /// * `progress` - 生成済みのモジュールの解説の記録（ディスクに書き込まない場合はNone）
+    /// 
+    /// # 戻り値
+    /// * `ModulePages` - モジュールセクションとパッケージごとのページ
+    async fn generate_module_pages(
+        index: &Index,
+        config: &Config,
+        with_diagrams: bool,
+        templates: &Templates,
+        diagnostics: &Diagnostics,
+        executor: &Executor,
+        progress: Option<&Progress>,
+    ) -> ModulePages {
+        // 重要度順に詳細ページの対象モジュールを選択
+        let selected_modules = index.modules_by_importance(config.site.max_modules);
+        if selected_modules.len() < index.modules.len() {
+            diagnostics.warn(
+                "wiki",
+                format!(
+                    "全{}モジュールのうち重要度の高い{}モジュールのみ解説しました（site.max-modules）",
+                    index.modules.len(),
+                    selected_modules.len()
+                ),
+            );
+        }
+        let layout = &config.site.layout;
+        let modules_file = layout.section_file("modules");
+        let locale = Locale::of(config);
+
+        // まず、モジュール一覧を生成
+        let mut modules_content = format!("# {}\n\n{}\n\n", locale.text("modules"), locale.text("modules.intro"));
+        if selected_modules.len() < index.modules.len() {
+            modules_content.push_str(&format!(
+                "{}\n\n",
+                locale.format("modules.selected", &[&index.modules.len(), &selected_modules.len()])
+            ));
+        }
+        modules_content.push_str(&format!("## {}\n\n", locale.text("modules.list")));
+
+        let term_links = module_term_links(index, config, &selected_modules);
+        let (contents, resumed) =
+            Self::generate_module_contents(index, config, &selected_modules, templates, &term_links, diagnostics, executor, progress)
+                .await;
+
+        if !groups_by_package(index, config) {
+            for module in &selected_modules {
+                modules_content.push_str(&format!("- [{}](#{})\n", module.id, heading_anchor(&module.id)));
+            }
+            modules_content.push_str("\n\n---\n\n");
+            for content in selected_modules.iter().filter_map(|m| contents.get(&m.path)) {
+                modules_content.push_str(content);
+                modules_content.push_str("\n\n---\n\n");
+            }
+            return ModulePages { index: with_footer(modules_content, config), packages: Vec::new(), resumed };
+        }
+
+        // パッケージごとのページへのリンクは、モジュールセクションのページからの相対パス
+        let stem = Path::new(&modules_file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "modules".to_string());
+        let summarizer = Summarizer::new(config.clone()).with_diagnostics(diagnostics.clone());
+        let diagrammer = Diagrammer::new(config.clone()).with_diagnostics(diagnostics.clone());
+        let mut packages = Vec::new();
+        for (package, modules) in group_by_package(index, selected_modules) {
+            let package_file = package_page_file(&modules_file, &package);
+            let package_base = package_file.strip_suffix("index.md").unwrap_or(&package_file).to_string();
+            modules_content.push_str(&format!("### [{}]({}/{}/index.md)\n\n", package, stem, package));
+
+            let mut page = format!("# {}\n\n", package);
+            page.push_str(&Self::package_overview(index, &package, with_diagrams, &summarizer, &diagrammer, locale).await);
+            page.push_str(&format!("## {}\n\n", locale.text("package.modules")));
+            for module in &modules {
+                let module_file = module_page_name(index, &package, module);
+                modules_content.push_str(&format!("- [{}]({}/{}/{})\n", module.id, stem, package, module_file));
+                page.push_str(&format!("- [{}]({})\n", module.id, module_file));
+                if let Some(content) = contents.get(&module.path) {
+                    packages.push((format!("{}{}", package_base, module_file), with_footer(content.clone(), config)));
+                }
+            }
+            modules_content.push('\n');
+            packages.push((package_file, with_footer(page, config)));
+        }
+
+        ModulePages { index: with_footer(modules_content, config), packages, resumed }
+    }
+
+    /// パッケージの目次ページの冒頭（パッケージの要約とモジュールグラフ）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `package` - パッケージのディレクトリ（リポジトリルートからの相対パス、直下のファイルは `_root`）
+    /// * `with_diagrams` - モジュールグラフを含めるか
+    /// * `summarizer` - パッケージの要約に使用
+    /// * `diagrammer` - モジュールグラフの生成に使用
+    /// * `locale` - 見出しと要約の言語
+    /// 
+    /// # 戻り値
+    /// * `String` - 要約とモジュールグラフのMarkdown（リポジトリ直下のファイルは空文字列）
+    async fn package_overview(
+        index: &Index,
+        package: &str,
+        with_diagrams: bool,
+        summarizer: &Summarizer,
+        diagrammer: &Diagrammer,
+        locale: Locale,
+    ) -> String {
+        // リポジトリ直下のファイルの要約・図はリポジトリ全体と同じになるため省く
+        if package == "_root" {
+            return String::new();
+        }
+        let package_path = index.repo_path.join(package);
+        let mut content = String::new();
+        match summarizer
+            .summarize(index, "package", &package_path.to_string_lossy(), &locale.style("concise"))
+            .await
+        {
+            Ok(summary) => {
+                // 要約のタイトルはページの見出しと重複するため除く
+                let body = summary.content_md.trim_start();
+                let body = match body.strip_prefix("# ") {
+                    Some(rest) => rest.split_once('\n').map(|(_, rest)| rest).unwrap_or_default(),
+                    None => body,
+                };
+                content.push_str(body.trim());
+                content.push_str("\n\n");
+            }
+            Err(e) => summarizer.diagnostics().warn("wiki", format!("パッケージの要約を生成できませんでした: {}: {}", package, e)),
+        }
+        if with_diagrams {
+            if let Ok(subset) = index.subset(&package_path) {
+                content.push_str(&diagram_markdown(diagrammer, &subset, "module-graph"));
+            }
+        }
+        content
+    }
+
+    /// 各モジュールの解説を50並列で生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `config` - 設定
+    /// * `modules` - 対象のモジュール（掲載順）
+    /// * `templates` - 解説のテンプレート
+    /// * `term_links` - モジュールのパス → 用語集へのリンク（Markdown）
+    /// * `diagnostics` - 生成に失敗したモジュールの記録先
+    /// * `executor` - 各モジュールの生成タスクを実行するワーカープール
+    /// * `progress` - 生成済みのモジュールの解説の記録（再利用し、新たに生成した解説を追記する）
+    /// 
+    /// # 戻り値
+    /// * `(HashMap<PathBuf, String>, usize)` - モジュールのパス → 解説（生成に失敗したモジュールは含まない）と、記録から再利用した数
+    #[allow(clippy::too_many_arguments)]
+    async fn generate_module_contents(
+        index: &Index,
+        config: &Config,
+        modules: &[&analyzer_core::ModuleInfo],
+        templates: &Templates,
+        term_links: &HashMap<PathBuf, String>,
+        diagnostics: &Diagnostics,
+        executor: &Executor,
+        progress: Option<&Progress>,
+    ) -> (HashMap<PathBuf, String>, usize) {
+        let max_methods = config.site.max_methods_per_module;
+        let locale = Locale::of(config);
+
+        // 各モジュールをワーカープールで並列に処理
+        let mut module_handles = Vec::new();
+        let mut rendered_readmes = std::collections::HashSet::new();
+        let mut contents = HashMap::new();
+        let mut resumed = 0;
+        
+        for module in modules {
+            // パッケージのREADMEは、そのパッケージで最初に掲載するモジュールにのみ含める
+            let readme = index
+                .package_readme(&module.path)
+                .filter(|r| rendered_readmes.insert(r.dir.clone()))
+                .cloned();
+            if let Some(content) = progress.and_then(|p| p.module(&module.path)) {
+                contents.insert(module.path.clone(), content);
+                resumed += 1;
+                continue;
+            }
+            let progress = progress.cloned();
+            let terms = term_links.get(&module.path).cloned().unwrap_or_default();
+            let module = (*module).clone();
+            let index_for_module = index.clone();
+            let config_for_module = config.clone();
+            let diagnostics = diagnostics.clone();
+            let templates = templates.clone();
+            
+            let handle = executor.spawn(Priority::Module, async move {
+                let summarizer =
+                    Summarizer::new(config_for_module.clone()).with_diagnostics(diagnostics.clone()).with_templates(templates);
+                
+                let content = Self::generate_module_content_detailed(
+                    &index_for_module,
+                    &module,
+                    readme.as_ref(),
+                    &terms,
+                    &summarizer,
+                    max_methods,
+                    locale,
+                ).await;
+                // 中断しても生成済みの解説を再利用できるよう、1つずつ記録する
+                if let (Some(progress), Ok(content)) = (&progress, &content) {
+                    if let Err(e) = progress.record_module(&module.path, content) {
+                        diagnostics.warn("wiki", format!("生成の途中経過を記録できませんでした: {}", e));
+                    }
+                }
+                (module.path, content)
+            });
+            module_handles.push(handle);
         }
+        
+        // すべてのモジュールページを並列実行して結果を収集
+        for handle in module_handles {
+            match handle.await {
+                Ok((path, Ok(module_content))) => {
+                    contents.insert(path, module_content);
+                }
+                Ok((path, Err(e))) => diagnostics.warn_at("wiki", &path, format!("モジュールの解説を生成できませんでした: {}", e)),
+                Err(e) => diagnostics.warn("wiki", format!("モジュールの解説の生成タスクが異常終了しました: {}", e)),
+            }
+        }
+
+        (contents, resumed)
     }
 
     /// モジュールコンテンツを詳細に生成（50並列対応、文字列を返す）
@@ -262,38 +826,175 @@ optional = true
     /// # 引数
     /// * `index` - インデックス
     /// * `module` - モジュール情報
+    /// * `readme` - 先頭に掲載するパッケージのREADME
+    /// * `terms` - モジュールに現れる用語の用語集へのリンク（Markdown、ない場合は空）
     /// * `summarizer` - サマライザー
+    /// * `max_methods` - 解説する関数・メソッド数の上限（0で無制限）
+    /// * `locale` - 見出し・定型文の言語
     /// 
     /// # 戻り値
     /// * `Result<String>` - モジュールコンテンツ、またはエラー
     async fn generate_module_content_detailed(
         index: &Index,
         module: &analyzer_core::ModuleInfo,
+        readme: Option<&analyzer_core::PackageReadme>,
+        terms: &str,
         summarizer: &Summarizer,
+        max_methods: usize,
+        locale: Locale,
     ) -> Result<String> {
+        let max_methods = if max_methods == 0 { usize::MAX } else { max_methods };
+        // 手書きのREADMEを優先して掲載し、その後に生成した解説を続ける
+        let readme = readme.map(|readme| {
+            let source = readme.dir.join("README.md").strip_prefix(&index.repo_path).unwrap_or(&readme.dir).display().to_string();
+            serde_json::json!({
+                "source": locale.format("module.readme-source", &[&source]),
+                "content": demote_headings(&readme.content, 3),
+            })
+        });
+
+        let mut type_table = String::new();
+        let mut types = Vec::new();
+        let mut methods = Vec::new();
+        let mut footnotes = String::new();
+
+        // ファイル情報を取得してメソッドを抽出
+        if let Some(file_info) = index.files.iter().find(|f| f.path == module.path) {
+            type_table = render_type_table(file_info);
+
+            if let Some(file_content) = index.file_content(file_info) {
+                let mut citations = Vec::new();
+
+                // 型ごとの責務・フィールド・主要なメソッド（責務の後に型の行範囲を脚注で引用）
+                for ty in summarizer.extract_types_detailed(&file_content, &file_info.language).iter().take(max_methods) {
+                    let citation = summarizer.cite(index, &file_info.path, ty.start_line, ty.end_line);
+                    let marker = citation.marker();
+                    citations.push(citation);
+                    let summary = summarizer.summarize_type(ty, locale).await;
+                    types.push(serde_json::json!({
+                        "name": ty.name,
+                        "summary": summary.replacen("\n\n", &format!("{}\n\n", marker), 1),
+                    }));
+                }
+
+                // 各メソッドごとに詳細な解説を生成
+                for method in summarizer.extract_methods_detailed(&file_content, &file_info.language).iter().take(max_methods) {
+                    // 説明の根拠としてメソッドの行範囲を脚注で引用
+                    let citation = summarizer.cite(index, &file_info.path, method.start_line, method.end_line);
+                    let marker = citation.marker();
+                    citations.push(citation);
+
+                    // 日本語では英語コメントを翻訳し、英語ではドキュメントコメントをそのまま使う
+                    let doc = match (locale, method.documentation.is_empty()) {
+                        (Locale::Ja, false) => summarizer.translate_doc_to_japanese(&method.documentation).await,
+                        (Locale::Ja, true) => summarizer.infer_function_purpose_simple(&method.name),
+                        (Locale::En, false) => method.documentation.trim().to_string(),
+                        (Locale::En, true) => locale.format("module.no-doc", &[&method.name]),
+                    };
+
+                    // コードブロック（必ず表示、長いコードは重要な部分だけ表示）
+                    let code_lines: Vec<&str> = method.code_snippet.lines().collect();
+                    let code = if code_lines.len() <= 40 {
+                        method.code_snippet.clone()
+                    } else {
+                        let mut shown: Vec<&str> = code_lines.iter().take(20).copied().collect();
+                        shown.push(locale.text("module.omitted"));
+                        shown.extend(code_lines.iter().skip(code_lines.len().saturating_sub(5)));
+                        shown.join("\n")
+                    };
+
+                    // コードの説明（ドキュメントコメントがある場合は推定しない、推定は日本語のみ）
+                    let behavior = match locale {
+                        Locale::Ja => summarizer.infer_code_behavior(method),
+                        Locale::En => None,
+                    };
+                    methods.push(serde_json::json!({
+                        "name": method.qualified_name(),
+                        "short_name": method.name,
+                        "description": doc,
+                        "marker": marker,
+                        "api": render_method_api(method, locale),
+                        "language": method.language,
+                        "code": code,
+                        "behavior": behavior,
+                    }));
+                }
+                footnotes = summarizer.render_footnotes(&citations);
+            }
+        }
+
+        // mdBookのアンカーリンクは見出しから自動生成される（リンク側は `heading_anchor`）
+        summarizer.templates().render(
+            "wiki/module.md",
+            &serde_json::json!({
+                "module": {
+                    "id": module.id,
+                    "path": module.path.display().to_string(),
+                    "language": module.language,
+                    "component": module.component.as_str(),
+                    "icon": module.component.icon(),
+                },
+                "labels": {
+                    "file": locale.text("module.file"),
+                    "language": locale.text("module.language"),
+                    "terms": locale.text("module.terms"),
+                    "readme": locale.text("module.readme"),
+                    "types": locale.text("module.types"),
+                    "methods": locale.text("module.methods"),
+                    "methods_intro": locale.text("module.methods-intro"),
+                    "behavior": locale.text("module.behavior"),
+                    "behavior_intro": locale.text("module.behavior-intro"),
+                    "behavior_list": locale.text("module.behavior-list"),
+                },
+                "readme": readme,
+                "terms": terms,
+                "type_table": type_table,
+                "types": types,
+                "methods": methods,
+                "footnotes": footnotes,
+            }),
+        )
+    }
+
+    /// 個別のモジュールページを詳細に生成（50並列対応）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `modules_dir` - モジュールディレクトリ
+    /// * `module` - モジュール情報
+    /// * `summarizer` - サマライザー
+    /// 
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_module_page_detailed(
+        index: &Index,
+        modules_dir: &Path,
+        module: &analyzer_core::ModuleInfo,
+        summarizer: &Summarizer,
+    ) -> Result<()> {
         let mut content = String::new();
         
-        // mdBookのアンカーリンクは見出しから自動生成されるため、見出しをそのまま使用
-        content.push_str(&format!("## {}\n\n", module.name));
+        content.push_str(&format!("# {}\n\n", module.id));
         content.push_str(&format!("**ファイル**: `{}`  \n", module.path.display()));
         content.push_str(&format!("**言語**: {}\n\n", module.language));
         
         // ファイル情報を取得してメソッドを抽出
         if let Some(file_info) = index.files.iter().find(|f| f.path == module.path) {
-            if let Some(file_content) = &file_info.content {
-                let methods = summarizer.extract_methods_detailed(file_content, &file_info.language);
+            if let Some(file_content) = index.file_content(file_info) {
+                let methods = summarizer.extract_methods_detailed(&file_content, &file_info.language);
                 
                 if !methods.is_empty() {
-                    content.push_str("### 主要な関数・メソッド\n\n");
+                    content.push_str("## 主要な関数・メソッド\n\n");
                     content.push_str("このモジュールには以下の関数やメソッドが含まれています。各メソッドについて、日本語で詳しく解説します。\n\n");
                     
                     // 各メソッドごとに詳細な解説を生成
                     for method in methods.iter().take(30) {
-                        content.push_str(&format!("#### {}\n\n", method.name));
+                        content.push_str(&format!("### {}\n\n", method.name));
                         
                         // 日本語の説明を生成（英語コメントを翻訳）
                         let doc_ja = if !method.documentation.is_empty() {
-                            summarizer.translate_doc_to_japanese(&method.documentation)
+                            summarizer.translate_doc_to_japanese(&method.documentation).await
                         } else {
                             summarizer.infer_function_purpose_simple(&method.name)
                         };
@@ -301,7 +1002,7 @@ optional = true
                         content.push_str(&format!("{}\n\n", doc_ja));
                         
                         // コードの動作を詳しく説明
-                        content.push_str("##### コードの動作\n\n");
+                        content.push_str("#### コードの動作\n\n");
                         content.push_str("この関数の実装を見てみましょう。\n\n");
                         
                         // コードブロック（必ず表示）
@@ -309,170 +1010,61 @@ optional = true
                         if code_lines.len() <= 40 {
                             content.push_str("```");
                             content.push_str(&method.language);
-                            content.push_str("\n");
+                            content.push('\n');
                             content.push_str(&method.code_snippet);
                             content.push_str("\n```\n\n");
                         } else {
                             // 重要な部分だけ表示
                             content.push_str("```");
                             content.push_str(&method.language);
-                            content.push_str("\n");
+                            content.push('\n');
                             for line in code_lines.iter().take(20) {
                                 content.push_str(line);
-                                content.push_str("\n");
+                                content.push('\n');
                             }
                             content.push_str("// ... (省略) ...\n");
                             for line in code_lines.iter().skip(code_lines.len().saturating_sub(5)) {
                                 content.push_str(line);
-                                content.push_str("\n");
+                                content.push('\n');
                             }
                             content.push_str("```\n\n");
                         }
                         
-                        // コードの説明を追加
-                        content.push_str("このコードは以下の処理を行います：\n\n");
-                        content.push_str(&format!("- `{}`関数は、", method.name));
-                        // コードから処理内容を推測
-                        let code_lower = method.code_snippet.to_lowercase();
-                        if code_lower.contains("return") {
-                            content.push_str("値を返します。");
-                        } else if code_lower.contains("mut") || code_lower.contains("let") {
-                            content.push_str("変数を操作します。");
-                        } else if code_lower.contains("if") || code_lower.contains("match") {
-                            content.push_str("条件分岐を行います。");
-                        } else if code_lower.contains("loop") || code_lower.contains("for") || code_lower.contains("while") {
-                            content.push_str("繰り返し処理を行います。");
-                        } else {
-                            content.push_str("何らかの処理を実行します。");
+                        // コードの説明を追加（ドキュメントコメントがある場合は推定しない）
+                        if let Some(behavior) = summarizer.infer_code_behavior(method) {
+                            content.push_str("このコードは以下の処理を行います：\n\n");
+                            content.push_str(&format!("- `{}`関数は、{}\n\n", method.name, behavior));
                         }
-                        content.push_str("\n\n");
                     }
                 }
             }
         }
         
-        Ok(content)
+        // ファイル名を安全な形に変換
+        let safe_name = module.id.replace([':', '/', '\\'], "_");
+        let file_path = modules_dir.join(format!("{}.md", safe_name));
+        fs::write(&file_path, content)
+            .with_context(|| format!("モジュールページの書き込みに失敗しました: {:?}", file_path))?;
+        
+        Ok(())
     }
-
-    /// 個別のモジュールページを詳細に生成（50並列対応）
     /// 
     /// # 引数
     /// * `index` - インデックス
-    /// * `modules_dir` - モジュールディレクトリ
-    /// * `module` - モジュール情報
+    /// * `file_path` - 書き込むファイルのパス
+    /// * `section` - セクション名
+    /// * `with_diagrams` - 図を含めるか
     /// * `summarizer` - サマライザー
+    /// * `diagrammer` - ダイアグラマー
     /// 
     /// # 戻り値
-    /// * `Result<()>` - 成功、またはエラー
-    async fn generate_module_page_detailed(
+    /// * `Result<usize>` - 生成されたページ数、またはエラー
+    async fn generate_section_parallel(
         index: &Index,
-        modules_dir: &Path,
-        module: &analyzer_core::ModuleInfo,
-        summarizer: &Summarizer,
-    ) -> Result<()> {
-        let mut content = String::new();
-        
-        content.push_str(&format!("# {}\n\n", module.name));
-        content.push_str(&format!("**ファイル**: `{}`  \n", module.path.display()));
-        content.push_str(&format!("**言語**: {}\n\n", module.language));
-        
-        // ファイル情報を取得してメソッドを抽出
-        if let Some(file_info) = index.files.iter().find(|f| f.path == module.path) {
-            if let Some(file_content) = &file_info.content {
-                let methods = summarizer.extract_methods_detailed(file_content, &file_info.language);
-                
-                if !methods.is_empty() {
-                    content.push_str("## 主要な関数・メソッド\n\n");
-                    content.push_str("このモジュールには以下の関数やメソッドが含まれています。各メソッドについて、日本語で詳しく解説します。\n\n");
-                    
-                    // 各メソッドごとに詳細な解説を生成
-                    for method in methods.iter().take(30) {
-                        content.push_str(&format!("### {}\n\n", method.name));
-                        
-                        // 日本語の説明を生成（英語コメントを翻訳）
-                        let doc_ja = if !method.documentation.is_empty() {
-                            summarizer.translate_doc_to_japanese(&method.documentation)
-                        } else {
-                            summarizer.infer_function_purpose_simple(&method.name)
-                        };
-                        
-                        content.push_str(&format!("{}\n\n", doc_ja));
-                        
-                        // コードの動作を詳しく説明
-                        content.push_str("#### コードの動作\n\n");
-                        content.push_str("この関数の実装を見てみましょう。\n\n");
-                        
-                        // コードブロック（必ず表示）
-                        let code_lines: Vec<&str> = method.code_snippet.lines().collect();
-                        if code_lines.len() <= 40 {
-                            content.push_str("```");
-                            content.push_str(&method.language);
-                            content.push_str("\n");
-                            content.push_str(&method.code_snippet);
-                            content.push_str("\n```\n\n");
-                        } else {
-                            // 重要な部分だけ表示
-                            content.push_str("```");
-                            content.push_str(&method.language);
-                            content.push_str("\n");
-                            for line in code_lines.iter().take(20) {
-                                content.push_str(line);
-                                content.push_str("\n");
-                            }
-                            content.push_str("// ... (省略) ...\n");
-                            for line in code_lines.iter().skip(code_lines.len().saturating_sub(5)) {
-                                content.push_str(line);
-                                content.push_str("\n");
-                            }
-                            content.push_str("```\n\n");
-                        }
-                        
-                        // コードの説明を追加
-                        content.push_str("このコードは以下の処理を行います：\n\n");
-                        content.push_str(&format!("- `{}`関数は、", method.name));
-                        // コードから処理内容を推測
-                        let code_lower = method.code_snippet.to_lowercase();
-                        if code_lower.contains("return") {
-                            content.push_str("値を返します。");
-                        } else if code_lower.contains("mut") || code_lower.contains("let") {
-                            content.push_str("変数を操作します。");
-                        } else if code_lower.contains("if") || code_lower.contains("match") {
-                            content.push_str("条件分岐を行います。");
-                        } else if code_lower.contains("loop") || code_lower.contains("for") || code_lower.contains("while") {
-                            content.push_str("繰り返し処理を行います。");
-                        } else {
-                            content.push_str("何らかの処理を実行します。");
-                        }
-                        content.push_str("\n\n");
-                    }
-                }
-            }
-        }
-        
-        // ファイル名を安全な形に変換
-        let safe_name = module.name.replace("::", "_").replace("/", "_").replace("\\", "_");
-        let file_path = modules_dir.join(format!("{}.md", safe_name));
-        fs::write(&file_path, content)
-            .with_context(|| format!("モジュールページの書き込みに失敗しました: {:?}", file_path))?;
-        
-        Ok(())
-    }
-    /// 
-    /// # 引数
-    /// * `index` - インデックス
-    /// * `src_dir` - ソースディレクトリ
-    /// * `section` - セクション名
-    /// * `with_diagrams` - 図を含めるか
-    /// * `summarizer` - サマライザー
-    /// * `diagrammer` - ダイアグラマー
-    /// 
-    /// # 戻り値
-    /// * `Result<usize>` - 生成されたページ数、またはエラー
-    async fn generate_section_parallel(
-        index: &Index,
-        src_dir: &Path,
+        file_path: &Path,
         section: &str,
         with_diagrams: bool,
+        config: &Config,
         summarizer: &Summarizer,
         diagrammer: &Diagrammer,
     ) -> Result<usize> {
@@ -481,22 +1073,49 @@ optional = true
             return Ok(1);
         }
         
-        let content = match section {
-            "overview" => Self::generate_overview_parallel(index, summarizer).await?,
-            "architecture" => Self::generate_architecture_parallel(index, with_diagrams, diagrammer).await?,
-            "flows" => Self::generate_flows_parallel(index, with_diagrams, diagrammer).await?,
-            "deploy" => Self::generate_deploy_parallel(index, diagrammer).await?,
-            "faq" => Self::generate_faq_parallel(index).await?,
-            _ => format!("# {}\n\nセクションの内容\n", section),
+        let content = if SECTIONS.contains(&section) {
+            Self::generate_section_content(index, section, with_diagrams, config, summarizer, diagrammer).await?
+        } else {
+            placeholder_page(section, Locale::of(config))
         };
 
-        let page_count = 1;
+        write_page(file_path, &content)?;
 
-        let file_path = src_dir.join(format!("{}.md", section));
-        fs::write(&file_path, content)
-            .with_context(|| format!("セクションファイルの書き込みに失敗しました: {:?}", file_path))?;
+        Ok(1)
+    }
 
-        Ok(page_count)
+    /// セクションの内容を生成（modulesを除く、`project.language` の見出しと `[branding]` のフッターを付ける）
+    async fn generate_section_content(
+        index: &Index,
+        section: &str,
+        with_diagrams: bool,
+        config: &Config,
+        summarizer: &Summarizer,
+        diagrammer: &Diagrammer,
+    ) -> Result<String> {
+        let locale = Locale::of(config);
+        let body = match section {
+            "overview" => Self::generate_overview_parallel(index, summarizer, locale).await?,
+            "architecture" => Self::generate_architecture_parallel(index, with_diagrams, summarizer, diagrammer, locale).await?,
+            "packages" => Self::generate_packages_parallel(index, summarizer, locale).await?,
+            "metrics" => Self::generate_metrics(index),
+            "hotspots" => Self::generate_hotspots(index, summarizer),
+            "timeline" => Self::generate_timeline(index, with_diagrams),
+            "tests" => Self::generate_tests(index),
+            "unused" => Self::generate_unused(index, summarizer),
+            "debt" => Self::generate_debt(index, summarizer),
+            "api" => Self::generate_api(index, summarizer),
+            "flows" => Self::generate_flows_parallel(index, with_diagrams, diagrammer, locale).await?,
+            "deploy" => Self::generate_deploy_parallel(index, diagrammer, locale).await?,
+            "config" => Self::generate_config_files(index, summarizer),
+            "glossary" => Self::generate_glossary(index, summarizer, locale).await,
+            "faq" => Self::generate_faq_parallel(index, config, summarizer, locale).await?,
+            "licenses" => Self::generate_licenses(index),
+            "third-party" => Self::generate_third_party(index),
+            _ => return Err(anyhow::anyhow!("不明なセクション: {}", section)),
+        };
+        let content = format!("# {}\n\n{}", locale.text(section), body);
+        Ok(with_footer(content, config))
     }
 
     /// セクションを生成（非並列実行用、後方互換性のため保持）
@@ -539,322 +1158,3108 @@ optional = true
         Ok(page_count)
     }
 
-    /// 概要セクションを並列実行用に生成（図のみ）
-    async fn generate_overview_parallel(index: &Index, _summarizer: &Summarizer) -> Result<String> {
-        let mut content = String::from("# 概要\n\n");
-        content.push_str("このページでは、リポジトリ全体の構成を図で示します。\n\n");
-        
+    /// 概要セクションを並列実行用に生成（統計・READMEの冒頭・プロジェクトのドキュメント・全体構成図）
+    async fn generate_overview_parallel(index: &Index, summarizer: &Summarizer, locale: Locale) -> Result<String> {
         // 統計情報を簡潔に表示
-        content.push_str("## 統計情報\n\n");
-        content.push_str(&format!("- **ファイル数**: {}個\n", index.stats.files));
-        content.push_str(&format!("- **使用言語**: {}\n", index.stats.languages.join(", ")));
-        content.push_str(&format!("- **モジュール数**: {}個\n\n", index.stats.modules));
-        
+        let mut stats = vec![
+            locale.format("overview.files", &[&index.stats.files]),
+            locale.format("overview.languages", &[&index.stats.languages.join(", ")]),
+            locale.format("overview.modules", &[&index.stats.modules]),
+        ];
+        if let Some(license) = &index.project_docs.license {
+            stats.push(locale.format("overview.license", &[license]));
+        }
+
         // 全体構成図のみ
-        content.push_str("## 全体構成図\n\n");
-        content.push_str("```mermaid\n");
-        content.push_str("graph TD\n");
-        content.push_str("    A[リポジトリ全体] --> B[ファイル]\n");
-        content.push_str("    A --> C[モジュール]\n");
-        content.push_str("    A --> D[依存関係]\n");
-        content.push_str(&format!("    B --> E[{}ファイル]\n", index.stats.files));
-        content.push_str(&format!("    C --> F[{}モジュール]\n", index.stats.modules));
-        content.push_str("```\n\n");
+        let mut mermaid = String::from("graph TD\n");
+        mermaid.push_str(&format!(
+            "    A[{}] --> B[{}]\n",
+            locale.text("overview.diagram-repo"),
+            locale.text("overview.diagram-files")
+        ));
+        mermaid.push_str(&format!("    A --> C[{}]\n", locale.text("overview.diagram-modules")));
+        mermaid.push_str(&format!("    A --> D[{}]\n", locale.text("overview.diagram-dependencies")));
+        mermaid.push_str(&format!("    B --> E[{}]\n", locale.format("overview.diagram-file-count", &[&index.stats.files])));
+        mermaid.push_str(&format!("    C --> F[{}]\n", locale.format("overview.diagram-module-count", &[&index.stats.modules])));
+        let diagram = Diagram {
+            diagram_type: "overview".to_string(),
+            format: "mermaid".to_string(),
+            content: mermaid,
+            scope: locale.text("overview.diagram-repo").to_string(),
+        };
 
-        Ok(content)
+        summarizer.templates().render(
+            "wiki/overview.md",
+            &serde_json::json!({
+                "intro": locale.text("overview.intro"),
+                "labels": { "stats": locale.text("overview.stats"), "diagram": locale.text("overview.diagram") },
+                "stats": stats,
+                "files": index.stats.files,
+                "languages": index.stats.languages,
+                "modules": index.stats.modules,
+                "license": index.project_docs.license,
+                "project_docs": Self::render_project_docs(index, summarizer, locale),
+                "diagram": diagram.to_markdown(),
+            }),
+        )
+    }
+
+    /// リポジトリのREADMEの冒頭と、ライセンス・貢献ガイド・変更履歴へのリンクを生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// * `locale` - 見出し・定型文の言語
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（ドキュメントがない場合は空）
+    fn render_project_docs(index: &Index, summarizer: &Summarizer, locale: Locale) -> String {
+        let docs = &index.project_docs;
+        let mut content = String::new();
+        if let Some(readme) = &docs.readme {
+            content.push_str("## README\n\n");
+            if let Some(intro) = &docs.readme_intro {
+                content.push_str(&demote_headings(intro, 3));
+                content.push_str("\n\n");
+            }
+            content.push_str(&format!("{}\n\n", locale.format("docs.full-readme", &[&summarizer.file_link(index, readme)])));
+        }
+
+        let mut links = Vec::new();
+        if !docs.license_files.is_empty() {
+            let files: Vec<String> = docs.license_files.iter().map(|f| summarizer.file_link(index, f)).collect();
+            links.push(format!("- **{}**: {}\n", locale.text("docs.license"), files.join(", ")));
+        }
+        if let Some(contributing) = &docs.contributing {
+            links.push(format!("- **{}**: {}\n", locale.text("docs.contributing"), summarizer.file_link(index, contributing)));
+        }
+        if let Some(changelog) = &docs.changelog {
+            links.push(format!("- **{}**: {}\n", locale.text("docs.changelog"), summarizer.file_link(index, changelog)));
+        }
+        if !links.is_empty() {
+            content.push_str(&format!("## {}\n\n", locale.text("docs.title")));
+            content.push_str(&links.concat());
+            content.push('\n');
+        }
+        content
     }
 
-    /// アーキテクチャセクションを並列実行用に生成（図のみ）
+    /// アーキテクチャセクションを並列実行用に生成（層構成・図）
     async fn generate_architecture_parallel(
         index: &Index,
         with_diagrams: bool,
+        summarizer: &Summarizer,
         diagrammer: &Diagrammer,
+        locale: Locale,
     ) -> Result<String> {
-        let mut content = String::from("# アーキテクチャ\n\n");
+        let mut content = String::new();
+
+        let model = summarizer.infer_architecture(index).await;
+        content.push_str(&Self::render_layers(&model, with_diagrams, diagrammer, locale));
 
         if with_diagrams {
             content.push_str("## モジュールグラフ\n\n");
-            let diagram = diagrammer.generate_diagram(index, "module-graph")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
-            }
+            content.push_str(&diagram_markdown(diagrammer, index, "module-graph"));
         }
 
-        Ok(content)
-    }
-
-    /// モジュールセクションを並列実行用に生成（実際のコンテンツは別途50並列で生成）
-    async fn generate_modules_parallel(index: &Index, _summarizer: &Summarizer) -> Result<String> {
-        let mut content = String::from("# モジュール\n\n");
-        content.push_str("このセクションでは、各モジュールについて詳しく説明します。\n\n");
-        content.push_str("各モジュールの詳細は以下の通りです。\n\n");
-
-        // モジュール一覧を追加（アンカーリンク用）
-        content.push_str("## モジュール一覧\n\n");
-        for module in &index.modules {
-            let anchor = module.name.replace("::", "_").replace("/", "_").replace("\\", "_").replace(" ", "_");
-            content.push_str(&format!("- [{}](#{})\n", module.name, anchor));
-        }
-        content.push_str("\n");
+        content.push_str(&Self::render_dependency_cycles(index, with_diagrams, diagrammer));
+        content.push_str(&Self::render_build_conditions(index));
+        content.push_str(&Self::render_design_drift(index, summarizer));
+        content.push_str(&render_statistics(index));
+        content.push_str(&Self::render_type_relations(index, with_diagrams, diagrammer));
 
         Ok(content)
     }
 
-    /// フローセクションを並列実行用に生成（図のみ）
-    async fn generate_flows_parallel(
-        index: &Index,
+    /// モジュールの層ごとの一覧・層構成図・層の逆転を生成
+    ///
+    /// # 引数
+    /// * `model` - モジュールの層と層の間の依存
+    /// * `with_diagrams` - 層構成図を含めるか（Mermaid以外のレンダラでは省略）
+    /// * `diagrammer` - ダイアグラマー
+    /// * `locale` - 見出し・定型文の言語
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（モジュールがない場合は空）
+    fn render_layers(
+        model: &analyzer_core::ArchitectureModel,
         with_diagrams: bool,
         diagrammer: &Diagrammer,
-    ) -> Result<String> {
-        let mut content = String::from("# フロー\n\n");
+        locale: Locale,
+    ) -> String {
+        if model.components.is_empty() {
+            return String::new();
+        }
 
+        let mut content = format!("## {}\n\n{}\n\n", locale.text("layers.title"), locale.text("layers.intro"));
         if with_diagrams {
-            content.push_str("## シーケンス図\n\n");
-            let diagram = diagrammer.generate_diagram(index, "sequence")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+            match diagrammer.generate_layer_diagram(model) {
+                Ok(diagram) => content.push_str(&diagram.to_markdown()),
+                Err(e) => diagrammer.diagnostics().warn("diagrammer", format!("layersを生成できませんでした: {}", e)),
             }
+        }
 
-            content.push_str("## コールグラフ\n\n");
-            let diagram = diagrammer.generate_diagram(index, "call-graph")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+        for layer in analyzer_core::LAYERS.iter().copied().chain(["other"]) {
+            let components = model.layer(layer);
+            if components.is_empty() {
+                continue;
+            }
+            content.push_str(&format!("### {}\n\n", locale.text(&format!("layers.{}", layer))));
+            for component in components.iter().take(MAX_LAYER_ROWS) {
+                let basis = match component.basis {
+                    analyzer_core::LayerBasis::Endpoint => locale.text("layers.basis-endpoint").to_string(),
+                    analyzer_core::LayerBasis::Name => locale.format("layers.basis-name", &[&component.evidence]),
+                    analyzer_core::LayerBasis::Library => locale.format("layers.basis-library", &[&component.evidence]),
+                    analyzer_core::LayerBasis::Dependencies => locale.text("layers.basis-dependencies").to_string(),
+                    analyzer_core::LayerBasis::Llm => locale.text("layers.basis-llm").to_string(),
+                    analyzer_core::LayerBasis::None => String::new(),
+                };
+                match basis.is_empty() {
+                    true => content.push_str(&format!("- `{}`\n", component.id)),
+                    false => content.push_str(&format!("- `{}` — {}\n", component.id, basis)),
+                }
+            }
+            if components.len() > MAX_LAYER_ROWS {
+                content.push_str(&format!("- {}\n", locale.format("layers.more", &[&(components.len() - MAX_LAYER_ROWS)])));
             }
+            content.push('\n');
         }
 
-        Ok(content)
-    }
-
-    /// デプロイセクションを並列実行用に生成（図のみ）
-    async fn generate_deploy_parallel(index: &Index, diagrammer: &Diagrammer) -> Result<String> {
-        let mut content = String::from("# デプロイ\n\n");
-
-        content.push_str("## デプロイメント構成図\n\n");
-        let diagram = diagrammer.generate_diagram(index, "deployment")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+        if !model.violations.is_empty() {
+            let id = |path: &PathBuf| {
+                model.components.iter().find(|c| &c.path == path).map_or_else(|| path.display().to_string(), |c| c.id.clone())
+            };
+            content.push_str(&format!("### {}\n\n", locale.text("layers.violations")));
+            content.push_str(&format!("> ⚠️ {}\n\n", locale.format("layers.violations-intro", &[&model.violations.len()])));
+            for (from, to) in model.violations.iter().take(MAX_LAYER_ROWS) {
+                let layer = |path: &PathBuf| locale.text(&format!("layers.{}", model.layer_of(path).unwrap_or("other"))).to_string();
+                content.push_str(&format!("- `{}`（{}） → `{}`（{}）\n", id(from), layer(from), id(to), layer(to)));
+            }
+            if model.violations.len() > MAX_LAYER_ROWS {
+                content.push_str(&format!("- {}\n", locale.format("layers.more", &[&(model.violations.len() - MAX_LAYER_ROWS)])));
+            }
+            content.push('\n');
         }
-
-        Ok(content)
+        content
     }
 
-    /// FAQセクションを並列実行用に生成
-    async fn generate_faq_parallel(index: &Index) -> Result<String> {
-        let mut content = String::from("# FAQ\n\n");
+    /// 循環依存の警告と、循環を強調した図を生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `with_diagrams` - 循環依存の図を含めるか（Mermaid以外のレンダラでは省略）
+    /// * `diagrammer` - ダイアグラマー
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（循環依存がない場合は空）
+    fn render_dependency_cycles(index: &Index, with_diagrams: bool, diagrammer: &Diagrammer) -> String {
+        let cycles = index.dependency_cycles();
+        if cycles.is_empty() {
+            return String::new();
+        }
 
-        content.push_str("## よくある質問\n\n");
-        content.push_str("### このリポジトリは何ですか？\n\n");
+        let relative = |path: &PathBuf| format!("`{}`", path.strip_prefix(&index.repo_path).unwrap_or(path).display());
+        let mut content = String::from("## 循環依存\n\n");
         content.push_str(&format!(
-            "{}ファイル、{}言語、{}モジュールを含むリポジトリです。\n\n",
-            index.stats.files,
-            index.stats.languages.len(),
-            index.stats.modules
+            "> ⚠️ importをたどって互いに到達できるファイルの集まり（循環依存）が{}件あります。循環に含まれるファイルは個別に変更・テストしにくくなります。\n\n",
+            cycles.len()
         ));
-
-        content.push_str("### どのように始めますか？\n\n");
-        if !index.entrypoints.is_empty() {
-            content.push_str("エントリーポイント:\n");
-            for ep in &index.entrypoints {
-                content.push_str(&format!("- `{}`\n", ep.display()));
-            }
-        } else {
-            content.push_str("エントリーポイントが見つかりませんでした。\n");
+        for (i, cycle) in cycles.iter().take(MAX_CYCLE_WARNINGS).enumerate() {
+            let path: Vec<String> = cycle.path.iter().map(relative).collect();
+            content.push_str(&format!("- **循環{}**（{}ファイル）: {}\n", i + 1, cycle.files.len(), path.join(" → ")));
+        }
+        if cycles.len() > MAX_CYCLE_WARNINGS {
+            content.push_str(&format!("- ほか{}件\n", cycles.len() - MAX_CYCLE_WARNINGS));
         }
+        content.push('\n');
 
-        Ok(content)
+        if with_diagrams {
+            content.push_str(&diagram_markdown(diagrammer, index, "dependency-cycles"));
+        }
+        content
     }
 
-    /// 概要セクションを生成（非並列実行用、後方互換性のため保持）
-    #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_overview(&self, index: &Index) -> Result<String> {
-        let summary_result = self
-            .summarizer
-            .summarize(index, "repo", "", "concise-ja")
-            .await?;
+    /// 条件付きでビルド・読み込みされるモジュールの一覧を生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス（Cargoのフィーチャーの説明は `config_files` から引く）
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（条件付きのファイルがない場合は空）
+    fn render_build_conditions(index: &Index) -> String {
+        let files: Vec<&analyzer_core::FileInfo> =
+            index.files.iter().filter(|f| !f.is_test && !f.build_conditions.is_empty()).collect();
+        if files.is_empty() {
+            return String::new();
+        }
 
-        Ok(summary_result.content_md)
+        let relative = |path: &PathBuf| path.strip_prefix(&index.repo_path).unwrap_or(path).display().to_string();
+        let feature_description = |name: &str| {
+            let key = format!("features.{}", name);
+            index
+                .config_files
+                .iter()
+                .flat_map(|f| &f.settings)
+                .find(|s| s.key == key)
+                .and_then(|s| s.description.clone())
+        };
+        let mut content = String::from("## 条件付きでビルド・読み込まれるモジュール\n\n");
+        content.push_str("次のモジュールはフィーチャーフラグ・ビルド設定・環境変数の条件を満たす場合のみコンパイル・読み込みされます。");
+        content.push_str("ここに挙げていないモジュールは常に含まれます。\n\n");
+        for file in files.iter().take(MAX_CONDITIONAL_ROWS) {
+            let name = index.modules.iter().find(|m| m.path == file.path).map_or_else(|| relative(&file.path), |m| m.id.clone());
+            let conditions: Vec<String> = file
+                .build_conditions
+                .iter()
+                .map(|condition| {
+                    let label = match condition.feature().and_then(|f| feature_description(f).map(|d| (f, d))) {
+                        Some((feature, description)) => format!("feature `{}`（{}）有効時のみ", feature, description),
+                        None => condition.label(),
+                    };
+                    format!("{}（`{}` {}行目）", label, condition.declared_in.display(), condition.line)
+                })
+                .collect();
+            content.push_str(&format!("- `{}` — この機能は{}\n", name, conditions.join("、または")));
+        }
+        if files.len() > MAX_CONDITIONAL_ROWS {
+            content.push_str(&format!("- ほか{}件\n", files.len() - MAX_CONDITIONAL_ROWS));
+        }
+        content.push('\n');
+        content
     }
 
-    /// アーキテクチャセクションを生成（非並列実行用、後方互換性のため保持）
-    #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_architecture(&self, index: &Index, with_diagrams: bool) -> Result<String> {
-        let mut content = String::from("# アーキテクチャ\n\n");
-
-        content.push_str("## システム構成\n\n");
-        content.push_str(&format!(
-            "このリポジトリは{}ファイル、{}言語、{}モジュールで構成されています。\n\n",
-            index.stats.files,
-            index.stats.languages.len(),
-            index.stats.modules
-        ));
+    /// ARCHITECTURE.md・ADRに書かれた構成と実装の差分を生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ドキュメントへのリンクの生成に使用（`project.source-url`）
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（設計ドキュメントがない場合は空）
+    fn render_design_drift(index: &Index, summarizer: &Summarizer) -> String {
+        if index.design_docs.is_empty() {
+            return String::new();
+        }
+        let drift = index.design_drift();
+        let adrs = index.design_docs.iter().filter(|d| d.kind == "adr").count();
+        let mut sources: Vec<String> = index
+            .design_docs
+            .iter()
+            .filter(|d| d.kind == "architecture")
+            .map(|d| summarizer.file_link(index, &d.path))
+            .collect();
+        if let Some(dir) = index.design_docs.iter().find(|d| d.kind == "adr").and_then(|d| d.path.parent()) {
+            sources.push(format!("ADR（`{}/` の{}件）", dir.display(), adrs));
+        }
 
-        if with_diagrams {
-            content.push_str("## モジュールグラフ\n\n");
-            let diagram = self.diagrammer.generate_diagram(index, "module-graph")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+        let mut content = String::from("## ドキュメントと実装の差分\n\n");
+        content.push_str(&format!("{}に書かれた構成と、解析したコードを比べた結果です。", sources.join("・")));
+        content.push_str("名前の一致だけで判定しているため、改名・言い換えも差分として挙がります。\n\n");
+        if drift.missing_in_code.is_empty() && drift.missing_in_docs.is_empty() {
+            content.push_str("ドキュメントに書かれた構成とコードの構成に差分は見つかりませんでした。\n\n");
+            return content;
+        }
+        if !drift.missing_in_code.is_empty() {
+            content.push_str("### ドキュメントにあってコードにないもの\n\n");
+            content.push_str("削除・改名されたか、まだ実装されていない可能性があります。\n\n");
+            content.push_str("| 名前 | 記載箇所 |\n|---|---|\n");
+            for (doc, mention) in drift.missing_in_code.iter().take(MAX_DRIFT_ROWS) {
+                content.push_str(&format!("| `{}` | {} {}行目 |\n", mention.name, summarizer.file_link(index, doc), mention.line));
+            }
+            if drift.missing_in_code.len() > MAX_DRIFT_ROWS {
+                content.push_str(&format!("| ほか{}件 | |\n", drift.missing_in_code.len() - MAX_DRIFT_ROWS));
+            }
+            content.push('\n');
+        }
+        if !drift.missing_in_docs.is_empty() {
+            content.push_str("### コードにあってドキュメントにないもの\n\n");
+            content.push_str("設計ドキュメントのどこにも名前が現れない構成要素です。\n\n");
+            for component in drift.missing_in_docs.iter().take(MAX_DRIFT_ROWS) {
+                content.push_str(&format!("- `{}`\n", component));
+            }
+            if drift.missing_in_docs.len() > MAX_DRIFT_ROWS {
+                content.push_str(&format!("- ほか{}件\n", drift.missing_in_docs.len() - MAX_DRIFT_ROWS));
             }
+            content.push('\n');
         }
+        content
+    }
 
-        content.push_str("## 主要コンポーネント\n\n");
-        for module in &index.modules {
-            content.push_str(&format!("- **{}** (`{}`)\n", module.name, module.path.display()));
+    /// 型の実装・継承関係（トレイト・インターフェース・基底クラスごとの一覧とクラス図）を生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `with_diagrams` - クラス図を含めるか（Mermaid以外のレンダラでは省略）
+    /// * `diagrammer` - ダイアグラマー
+    ///
+    /// # 戻り値
+    /// * `String` - Markdown（関係がない場合は空）
+    fn render_type_relations(index: &Index, with_diagrams: bool, diagrammer: &Diagrammer) -> String {
+        let relations = index.type_relations();
+        if relations.is_empty() {
+            return String::new();
         }
 
-        Ok(content)
+        let mut targets: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (file, relation) in &relations {
+            let label = if relation.kind == "implements" { "実装" } else { "継承" };
+            let note = if relation.inferred { "、メソッドから推定" } else { "" };
+            let path = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+            targets
+                .entry(relation.target.as_str())
+                .or_default()
+                .push(format!("`{}`（{}{}、`{}`）", relation.name, label, note, path.display()));
+        }
+
+        let mut content = String::from("## 型の実装・継承関係\n\n");
+        content.push_str("トレイト・インターフェース・基底クラスごとに、実装・継承している型を示します。\n\n");
+        for (target, types) in targets.iter().take(MAX_RELATION_TARGETS) {
+            content.push_str(&format!("- **{}**: {}\n", target, types.join(", ")));
+        }
+        if targets.len() > MAX_RELATION_TARGETS {
+            content.push_str(&format!("- ほか{}件\n", targets.len() - MAX_RELATION_TARGETS));
+        }
+        content.push('\n');
+
+        if with_diagrams {
+            content.push_str(&diagram_markdown(diagrammer, index, "class-diagram"));
+        }
+        content
     }
 
-    /// モジュールセクションを生成（非並列実行用、後方互換性のため保持）
+    /// モジュールセクションを並列実行用に生成（実際のコンテンツは別途50並列で生成）
     #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_modules(&self, index: &Index) -> Result<String> {
+    async fn generate_modules_parallel(index: &Index, _summarizer: &Summarizer) -> Result<String> {
         let mut content = String::from("# モジュール\n\n");
+        content.push_str("このセクションでは、各モジュールについて詳しく説明します。\n\n");
+        content.push_str("各モジュールの詳細は以下の通りです。\n\n");
 
+        // モジュール一覧を追加（アンカーリンク用）
+        content.push_str("## モジュール一覧\n\n");
         for module in &index.modules {
-            content.push_str(&format!("## {}\n\n", module.name));
-            content.push_str(&format!("パス: `{}`\n\n", module.path.display()));
-            content.push_str(&format!("言語: {}\n\n", module.language));
+            content.push_str(&format!("- [{}](#{})\n", module.id, heading_anchor(&module.id)));
+        }
+        content.push('\n');
 
-            if !module.dependencies.is_empty() {
-                content.push_str("### 依存関係\n\n");
-                for dep in &module.dependencies {
-                    content.push_str(&format!("- `{}`\n", dep));
-                }
-                content.push_str("\n");
-            }
+        Ok(content)
+    }
 
-            // モジュールの要約を生成
-            let summary_result = self
-                .summarizer
-                .summarize(
-                    index,
-                    "module",
-                    &module.path.to_string_lossy(),
-                    "concise-ja",
-                )
-                .await?;
-            content.push_str(&summary_result.content_md);
-            content.push_str("\n\n");
+    /// メトリクスセクションを生成（全体の行数・コメント率と、複雑度の高いファイル・関数の表）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `String` - メトリクスのMarkdown
+    fn generate_metrics(index: &Index) -> String {
+        let relative = |path: &Path| path.strip_prefix(&index.repo_path).unwrap_or(path).display().to_string();
+        let mut content = String::new();
+
+        let lines: usize = index.files.iter().map(|f| f.metrics.lines).sum();
+        let code_lines: usize = index.files.iter().map(|f| f.metrics.code_lines).sum();
+        let comment_lines: usize = index.files.iter().map(|f| f.metrics.comment_lines).sum();
+        let functions: usize = index.files.iter().map(|f| f.metrics.functions).sum();
+        let complexity: usize = index.files.iter().flat_map(|f| &f.metrics.function_metrics).map(|f| f.complexity).sum();
+        content.push_str("## 全体\n\n");
+        content.push_str(&format!("- **総行数**: {}行（コード{}行、コメント{}行）\n", lines, code_lines, comment_lines));
+        if code_lines + comment_lines > 0 {
+            content.push_str(&format!(
+                "- **コメント率**: {:.1}%\n",
+                comment_lines as f64 * 100.0 / (code_lines + comment_lines) as f64
+            ));
+        }
+        content.push_str(&format!("- **関数・メソッド数**: {}個\n", functions));
+        if functions > 0 {
+            content.push_str(&format!("- **平均サイクロマティック複雑度**: {:.1}\n", complexity as f64 / functions as f64));
+        }
+        content.push('\n');
+
+        let mut files: Vec<&analyzer_core::FileInfo> = index.files.iter().filter(|f| f.metrics.lines > 0).collect();
+        files.sort_by(|a, b| b.metrics.complexity.cmp(&a.metrics.complexity).then(b.metrics.lines.cmp(&a.metrics.lines)));
+        if !files.is_empty() {
+            content.push_str("## ファイル別（複雑度の高い順）\n\n");
+            content.push_str("| ファイル | 行数 | コメント率 | 関数数 | 複雑度 | 最大複雑度 |\n|---|---|---|---|---|---|\n");
+            for file in files.iter().take(MAX_METRICS_ROWS) {
+                let metrics = &file.metrics;
+                content.push_str(&format!(
+                    "| `{}` | {} | {:.0}% | {} | {} | {} |\n",
+                    relative(&file.path),
+                    metrics.lines,
+                    metrics.comment_ratio() * 100.0,
+                    metrics.functions,
+                    metrics.complexity,
+                    metrics.max_complexity()
+                ));
+            }
+            content.push('\n');
+        }
+
+        let mut functions: Vec<(&analyzer_core::FileInfo, &analyzer_core::FunctionMetrics)> = index
+            .files
+            .iter()
+            .flat_map(|file| file.metrics.function_metrics.iter().map(move |f| (file, f)))
+            .collect();
+        functions.sort_by(|a, b| b.1.complexity.cmp(&a.1.complexity).then(b.1.lines().cmp(&a.1.lines())));
+        if !functions.is_empty() {
+            content.push_str("## 複雑な関数\n\n");
+            content.push_str("| 関数 | ファイル | 行 | 行数 | 複雑度 |\n|---|---|---|---|---|\n");
+            for (file, function) in functions.iter().take(MAX_METRICS_ROWS) {
+                let name = match &function.parent {
+                    Some(parent) => format!("{}::{}", parent, function.name),
+                    None => function.name.clone(),
+                };
+                content.push_str(&format!(
+                    "| `{}` | `{}` | {}-{} | {} | {} |\n",
+                    name,
+                    relative(&file.path),
+                    function.start_line,
+                    function.end_line,
+                    function.lines(),
+                    function.complexity
+                ));
+            }
+            content.push('\n');
+        }
+
+        content
+    }
+
+    /// パッケージセクションを並列実行用に生成（ワークスペースのパッケージの一覧と、パッケージごとの要約）
+    async fn generate_packages_parallel(index: &Index, summarizer: &Summarizer, locale: Locale) -> Result<String> {
+        let mut content = String::new();
+        if index.packages.is_empty() {
+            content.push_str("ワークスペース（モノレポ）の定義が見つからないため、パッケージの一覧はありません。\n");
+            return Ok(content);
+        }
+
+        content.push_str("| パッケージ | パス | エコシステム | ファイル数 | 依存先 |\n|---|---|---|---:|---|\n");
+        for package in &index.packages {
+            let path = package.path.to_string_lossy().replace('\\', "/");
+            content.push_str(&format!(
+                "| {} | `{}` | {} | {} | {} |\n",
+                package.name,
+                if path.is_empty() { "." } else { &path },
+                package.ecosystem,
+                index.package_files(package).len(),
+                package.dependencies.join(", ")
+            ));
+        }
+        content.push('\n');
+
+        for package in &index.packages {
+            if index.package_files(package).is_empty() {
+                continue;
+            }
+            let summary = summarizer.summarize(index, "package", &package.name, &locale.style("concise")).await?;
+            content.push_str(&demote_headings(&summary.content_md, 2));
+            content.push('\n');
         }
 
         Ok(content)
     }
 
-    /// フローセクションを生成（非並列実行用、後方互換性のため保持）
-    #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_flows(&self, index: &Index, with_diagrams: bool) -> Result<String> {
-        let mut content = String::from("# フロー\n\n");
+    /// ホットスポットセクションを生成（変更回数 × 複雑度の高いファイルの順位表）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - ホットスポットのMarkdown
+    fn generate_hotspots(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::new();
+
+        let mut files: Vec<(&analyzer_core::FileInfo, &analyzer_core::FileHistory)> =
+            index.files.iter().filter_map(|f| f.history.as_ref().map(|h| (f, h))).collect();
+        if files.is_empty() {
+            content.push_str("Gitの履歴がないため、ホットスポットを算出できません。\n");
+            return content;
+        }
+
+        let score = |file: &analyzer_core::FileInfo, history: &analyzer_core::FileHistory| {
+            history.commits * file.metrics.complexity.max(1)
+        };
+        files.sort_by(|a, b| score(b.0, b.1).cmp(&score(a.0, a.1)).then_with(|| a.0.path.cmp(&b.0.path)));
+
+        content.push_str("変更回数と複雑度の積（スコア）が大きいファイルほど、変更時に不具合が入りやすい箇所です。");
+        content.push_str("上位のファイルはリファクタリングやテストの追加を優先する候補になります。\n\n");
+        content.push_str("推移は最新のコミットの月までの月ごとの変更回数です（左が古い月）。\n\n");
+        content.push_str(
+            "| 順位 | ファイル | スコア | 変更回数 | 複雑度 | 推移 | 行数 | 最終更新 | 主な作成者 |\n\
+             |---:|---|---:|---:|---:|---|---:|---|---|\n",
+        );
+        for (rank, (file, history)) in files.iter().take(MAX_METRICS_ROWS).enumerate() {
+            let authors: Vec<String> =
+                history.authors.iter().map(|a| format!("{}（{}）", a.name, a.commits)).collect();
+            content.push_str(&format!(
+                "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                rank + 1,
+                summarizer.file_link(index, &file.path),
+                score(file, history),
+                history.commits,
+                file.metrics.complexity,
+                history.sparkline(),
+                file.metrics.lines,
+                history.last_modified_date(),
+                authors.join(", ")
+            ));
+        }
+        content.push('\n');
+        content
+    }
+
+    /// プロジェクトの歩みの章を生成
+    /// 
+    /// Gitのタグ・大きなコミット・ドキュメントの再生成を年ごとのMermaidのタイムラインと、新しい順の表にする
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `with_diagrams` - タイムラインの図を含めるか
+    /// 
+    /// # 戻り値
+    /// * `String` - 章のMarkdown
+    fn generate_timeline(index: &Index, with_diagrams: bool) -> String {
+        let kind_label = |kind: TimelineKind| match kind {
+            TimelineKind::Release => "リリース",
+            TimelineKind::Refactor => "大規模な変更",
+            TimelineKind::Docs => "ドキュメントの再生成",
+        };
+        let mut content = String::new();
+        if index.timeline.is_empty() {
+            content.push_str("Gitの履歴にタグ・大規模な変更・ドキュメントの再生成が見つからないため、プロジェクトの歩みを示せません。\n");
+            return content;
+        }
+        content.push_str("Gitの履歴から、リリース（タグ）・大規模な変更（多くのファイル・行を変更したコミット）・");
+        content.push_str("ドキュメントの再生成（ドキュメントの出力先のみを変更したコミット）を時系列で挙げます。");
+        content.push_str("現在の構成に至った経緯を知る手がかりになります。\n\n");
 
         if with_diagrams {
-            content.push_str("## シーケンス図\n\n");
-            let diagram = self.diagrammer.generate_diagram(index, "sequence")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+            // Mermaidのtimelineでは `:` が区切り、`;`・`#` がコメント・エスケープになるため置き換える
+            let label = |text: &str| text.replace([':', ';', '#'], " ").split_whitespace().collect::<Vec<_>>().join(" ");
+            let mut mermaid = String::from("timeline\n");
+            let mut year = String::new();
+            for event in &index.timeline {
+                let date = event.date();
+                if date.get(..4) != Some(year.as_str()) {
+                    year = date.get(..4).unwrap_or_default().to_string();
+                    mermaid.push_str(&format!("    section {}\n", year));
+                }
+                mermaid.push_str(&format!("        {} : {} {}\n", date, kind_label(event.kind), label(&event.title)));
             }
+            let diagram = Diagram {
+                diagram_type: "timeline".to_string(),
+                format: "mermaid".to_string(),
+                content: mermaid,
+                scope: format!("{}件の出来事", index.timeline.len()),
+            };
+            content.push_str(&diagram.to_markdown());
+        }
 
-            content.push_str("## コールグラフ\n\n");
-            let diagram = self.diagrammer.generate_diagram(index, "call-graph")?;
-            if diagram.format == "mermaid" {
-                content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+        content.push_str("| 日付 | 種類 | 内容 | コミット | 変更ファイル数 |\n|---|---|---|---|---:|\n");
+        for event in index.timeline.iter().rev() {
+            content.push_str(&format!(
+                "| {} | {} | {} | `{}` | {} |\n",
+                event.date(),
+                kind_label(event.kind),
+                event.title.replace('|', "\\|"),
+                event.commit,
+                if event.kind == TimelineKind::Release { "-".to_string() } else { event.files.to_string() }
+            ));
+        }
+        content.push('\n');
+        content
+    }
+
+    /// テストセクションを生成（モジュールごとのテストの有無とテスト数、テストのないモジュールの一覧）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `String` - テストのMarkdown
+    fn generate_tests(index: &Index) -> String {
+        /// 1モジュールあたりに列挙するテストファイルの最大数
+        const MAX_TEST_FILES: usize = 3;
+
+        let mut content = String::new();
+        let relative = |path: &Path| path.strip_prefix(&index.repo_path).unwrap_or(path).display().to_string();
+
+        let test_files = index.files.iter().filter(|f| f.is_test).count();
+        let inline = index.files.iter().filter(|f| !f.is_test && f.tests > 0).count();
+        if test_files == 0 && inline == 0 {
+            content.push_str("テストファイル・テストを含むファイルが見つかりません。\n");
+            return content;
+        }
+        content.push_str(&format!("- **テストファイル**: {}\n", test_files));
+        content.push_str(&format!("- **本体にテストを含むファイル**: {}\n", inline));
+        content.push_str(&format!("- **テスト関数**: {}\n\n", index.files.iter().map(|f| f.tests).sum::<usize>()));
+
+        let mut tested = Vec::new();
+        let mut untested = Vec::new();
+        for module in index.modules_by_importance(0) {
+            let Some(file) = index.files.iter().find(|f| f.path == module.path) else {
+                continue;
+            };
+            if file.is_test {
+                continue;
             }
+            let tests = index.tests_of(&file.path);
+            if file.tests > 0 || !tests.is_empty() {
+                tested.push((module, file, tests));
+            } else {
+                untested.push((module, file));
+            }
+        }
+        let modules = tested.len() + untested.len();
+        if modules > 0 {
+            content.push_str(&format!(
+                "モジュールのうち{}/{}（{:.0}%）にテストがあります。\n\n",
+                tested.len(),
+                modules,
+                tested.len() as f64 * 100.0 / modules as f64
+            ));
         }
 
-        Ok(content)
+        if !tested.is_empty() {
+            content.push_str("## テストのあるモジュール\n\n");
+            content.push_str("| モジュール | テスト数 | テスト |\n|---|---:|---|\n");
+            for (module, file, tests) in tested.iter().take(MAX_METRICS_ROWS) {
+                let count = file.tests + tests.iter().map(|t| t.tests).sum::<usize>();
+                let mut sources: Vec<String> = Vec::new();
+                if file.tests > 0 {
+                    sources.push("同じファイル".to_string());
+                }
+                sources.extend(tests.iter().take(MAX_TEST_FILES).map(|t| format!("`{}`", relative(&t.path))));
+                if tests.len() > MAX_TEST_FILES {
+                    sources.push(format!("ほか{}件", tests.len() - MAX_TEST_FILES));
+                }
+                content.push_str(&format!("| `{}` | {} | {} |\n", relative(&module.path), count, sources.join(", ")));
+            }
+            content.push('\n');
+        }
+
+        if !untested.is_empty() {
+            content.push_str("## テストのないモジュール\n\n");
+            content.push_str("重要度の高いモジュールほど、変更時の不具合を検知できないリスクが大きい箇所です。\n\n");
+            content.push_str("| モジュール | 重要度 | 複雑度 | 行数 |\n|---|---:|---:|---:|\n");
+            for (module, file) in untested.iter().take(MAX_METRICS_ROWS) {
+                content.push_str(&format!(
+                    "| `{}` | {:.2} | {} | {} |\n",
+                    relative(&module.path),
+                    module.importance,
+                    file.metrics.complexity,
+                    file.metrics.lines
+                ));
+            }
+            if untested.len() > MAX_METRICS_ROWS {
+                content.push_str(&format!("\nほか{}モジュール\n", untested.len() - MAX_METRICS_ROWS));
+            }
+            content.push('\n');
+        }
+
+        content
     }
 
-    /// デプロイセクションを生成（非並列実行用、後方互換性のため保持）
-    #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_deploy(&self, index: &Index) -> Result<String> {
-        let mut content = String::from("# デプロイ\n\n");
+    /// APIセクションを生成（REST・GraphQLのエンドポイントの一覧）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - 定義したファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - APIのMarkdown
+    fn generate_api(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::new();
+        if index.endpoints.is_empty() {
+            content.push_str("ルーティングの定義（Express・axum・actix-web・FastAPI・Flask・Springなど）やGraphQLのスキーマが見つかりません。\n");
+            return content;
+        }
 
-        content.push_str("## デプロイメント構成\n\n");
+        let is_graphql = |e: &&analyzer_core::ApiEndpoint| e.framework == "graphql";
+        let mut rest: Vec<&analyzer_core::ApiEndpoint> = index.endpoints.iter().filter(|e| !is_graphql(e)).collect();
+        let graphql: Vec<&analyzer_core::ApiEndpoint> = index.endpoints.iter().filter(is_graphql).collect();
+        let mut frameworks: Vec<&str> = rest.iter().map(|e| e.framework.as_str()).collect();
+        frameworks.sort();
+        frameworks.dedup();
 
-        // デプロイメント図を生成
-        let diagram = self.diagrammer.generate_diagram(index, "deployment")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n\n", diagram.content));
+        content.push_str(&format!("- **RESTエンドポイント**: {}\n", rest.len()));
+        if !frameworks.is_empty() {
+            content.push_str(&format!("- **フレームワーク**: {}\n", frameworks.join(", ")));
         }
+        content.push_str(&format!("- **GraphQLのフィールド**: {}\n\n", graphql.len()));
 
-        content.push_str("## エントリーポイント\n\n");
-        for ep in &index.entrypoints {
-            content.push_str(&format!("- `{}`\n", ep.display()));
+        let render = |content: &mut String, endpoints: &[&analyzer_core::ApiEndpoint]| {
+            for endpoint in endpoints.iter().take(MAX_API_ROWS) {
+                content.push_str(&format!(
+                    "| {} | `{}` | {} | {}（{}行目） |\n",
+                    endpoint.method,
+                    endpoint.path,
+                    endpoint.handler.as_deref().map_or_else(|| "-".to_string(), |h| format!("`{}`", h)),
+                    summarizer.file_link(index, &endpoint.file),
+                    endpoint.line
+                ));
+            }
+            if endpoints.len() > MAX_API_ROWS {
+                content.push_str(&format!("\nほか{}件\n", endpoints.len() - MAX_API_ROWS));
+            }
+            content.push('\n');
+        };
+
+        if !rest.is_empty() {
+            // 同じパスのメソッドをまとめて並べる
+            rest.sort_by(|a, b| a.path.cmp(&b.path));
+            content.push_str("## REST\n\n");
+            content.push_str("| メソッド | パス | ハンドラー | 定義 |\n|---|---|---|---|\n");
+            render(&mut content, &rest);
+        }
+        if !graphql.is_empty() {
+            content.push_str("## GraphQL\n\n");
+            content.push_str("| 操作 | フィールド | リゾルバー | 定義 |\n|---|---|---|---|\n");
+            render(&mut content, &graphql);
+        }
+
+        content
+    }
+
+    /// フローセクションを並列実行用に生成（図のみ）
+    async fn generate_flows_parallel(
+        index: &Index,
+        with_diagrams: bool,
+        diagrammer: &Diagrammer,
+        locale: Locale,
+    ) -> Result<String> {
+        let mut content = String::new();
+
+        if with_diagrams {
+            content.push_str(&format!("## {}\n\n", locale.text("flows.sequence")));
+            content.push_str(&diagram_markdown(diagrammer, index, "sequence"));
+
+            content.push_str(&format!("## {}\n\n", locale.text("flows.call-graph")));
+            content.push_str(&diagram_markdown(diagrammer, index, "call-graph"));
         }
 
         Ok(content)
     }
 
-    /// FAQセクションを生成（非並列実行用、後方互換性のため保持）
-    #[allow(dead_code)] // 後方互換性のため保持
-    async fn generate_faq(&self, index: &Index) -> Result<String> {
-        let mut content = String::from("# FAQ\n\n");
+    /// デプロイセクションを並列実行用に生成（図のみ）
+    async fn generate_deploy_parallel(index: &Index, diagrammer: &Diagrammer, locale: Locale) -> Result<String> {
+        let mut content = format!("## {}\n\n", locale.text("deploy.diagram"));
 
-        content.push_str("## よくある質問\n\n");
-        content.push_str("### このリポジトリは何ですか？\n\n");
+        content.push_str(&diagram_markdown(diagrammer, index, "deployment"));
+
+        content.push_str(&render_startup(index));
+
+        Ok(content)
+    }
+
+    /// FAQセクションを並列実行用に生成
+    async fn generate_faq_parallel(index: &Index, config: &Config, summarizer: &Summarizer, locale: Locale) -> Result<String> {
+        let mut content = format!("## {}\n\n", locale.text("faq.title"));
+        content.push_str(&format!("### {}\n\n", locale.text("faq.what")));
         content.push_str(&format!(
-            "{}ファイル、{}言語、{}モジュールを含むリポジトリです。\n\n",
-            index.stats.files,
-            index.stats.languages.len(),
-            index.stats.modules
+            "{}\n\n",
+            locale.format("faq.what-answer", &[&index.stats.files, &index.stats.languages.len(), &index.stats.modules])
         ));
 
-        content.push_str("### どのように始めますか？\n\n");
-        if !index.entrypoints.is_empty() {
-            content.push_str("エントリーポイント:\n");
-            for ep in &index.entrypoints {
-                content.push_str(&format!("- `{}`\n", ep.display()));
+        content.push_str(&Self::render_build_faq(index));
+        content.push_str(&Self::render_test_location_faq(index, summarizer));
+        content.push_str(&Self::render_config_faq(index, summarizer));
+        content.push_str(&Self::render_communication_faq(index, summarizer));
+        content.push_str(&Self::render_services_faq(index, summarizer));
+        content.push_str(&Self::render_project_docs_faq(index, summarizer));
+        content.push_str(&Self::render_debt_faq(index, summarizer));
+        content.push_str(&render_contact_faq(config));
+
+        Ok(content)
+    }
+
+    /// 設定ファイルの章を生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - 章のMarkdown
+    fn generate_config_files(index: &Index, summarizer: &Summarizer) -> String {
+        /// 表に掲載する値の最大文字数
+        const MAX_VALUE_CHARS: usize = 80;
+
+        let mut content = String::new();
+        if index.config_files.is_empty() {
+            content.push_str("設定ファイル（tsconfig・Cargo.tomlのフィーチャー・.env.example・webpack）は見つかりませんでした。\n");
+            return content;
+        }
+        content.push_str("リポジトリの動作やビルドを切り替える設定ファイルと、各項目が制御するものです。");
+        content.push_str("説明は設定ファイルのコメント、またはよく使う項目の一般的な意味です。\n\n");
+
+        for file in &index.config_files {
+            content.push_str(&format!("## {}\n\n", file.path.display()));
+            content.push_str(&format!("{}（{}）\n\n", file.summary, summarizer.file_link(index, &file.path)));
+            content.push_str("| 項目 | 値 | 説明 |\n|---|---|---|\n");
+            for setting in &file.settings {
+                let value = match setting.value.char_indices().nth(MAX_VALUE_CHARS) {
+                    Some((end, _)) => format!("{}…", &setting.value[..end]),
+                    None => setting.value.clone(),
+                };
+                let value = match value.is_empty() {
+                    true => "（空）".to_string(),
+                    false => format!("`{}`", value.replace('|', "\\|")),
+                };
+                content.push_str(&format!(
+                    "| `{}` | {} | {} |\n",
+                    setting.key,
+                    value,
+                    setting.description.as_deref().unwrap_or("").replace('|', "\\|")
+                ));
+            }
+            content.push('\n');
+        }
+        content
+    }
+
+    /// 用語集の章を生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - 用語の定義とファイルへのリンクの生成に使用
+    /// * `locale` - 定義の言語
+    ///
+    /// # 戻り値
+    /// * `String` - 章のMarkdown
+    async fn generate_glossary(index: &Index, summarizer: &Summarizer, locale: Locale) -> String {
+        /// 用語ごとに挙げるモジュールの最大数
+        const MAX_MODULES: usize = 5;
+
+        let terms = index.glossary_terms();
+        let mut content = String::new();
+        if terms.is_empty() {
+            content.push_str("複数のファイルの名前に繰り返し現れる用語は見つかりませんでした。\n");
+            return content;
+        }
+        content.push_str("型・関数・ファイルの名前に繰り返し現れる、このリポジトリのドメインの用語です。");
+        match summarizer.mode_label() {
+            "llm-local" => content.push_str("定義はローカルLLMが名前とドキュメントコメントから書いたもので、内容を確認してから参照してください。\n\n"),
+            _ => content.push_str("定義は同じ名前の型のドキュメントコメント、なければ用語を含む名前から書いています。\n\n"),
+        }
+
+        let definitions = summarizer.define_terms(&terms, locale).await;
+        for (term, definition) in terms.iter().zip(definitions) {
+            content.push_str(&format!("## {}\n\n{}\n\n", term.term, definition));
+            let identifiers: Vec<String> = term.identifiers.iter().map(|n| format!("`{}`", n)).collect();
+            content.push_str(&format!("- 関連する名前: {}\n", identifiers.join("・")));
+            let mut modules: Vec<String> = term
+                .files
+                .iter()
+                .take(MAX_MODULES)
+                .map(|path| match index.modules.iter().find(|m| &m.path == path) {
+                    Some(module) => format!("`{}`", module.id),
+                    None => format!("`{}`", path.strip_prefix(&index.repo_path).unwrap_or(path).display()),
+                })
+                .collect();
+            if term.files.len() > MAX_MODULES {
+                modules.push(format!("ほか{}件", term.files.len() - MAX_MODULES));
+            }
+            content.push_str(&format!("- 使われているモジュール: {}\n", modules.join("・")));
+            if let Some((path, line)) = &term.defined_in {
+                content.push_str(&format!("- 定義: {} {}行目\n", summarizer.file_link(index, path), line));
+            }
+            content.push('\n');
+        }
+        content
+    }
+
+    /// 未使用コードの章を生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - 章のMarkdown
+    fn generate_unused(index: &Index, summarizer: &Summarizer) -> String {
+        /// 1つの表・一覧に並べる最大数
+        const MAX_ROWS: usize = 200;
+
+        let unused = index.unused_code();
+        let mut content = String::new();
+        content.push_str("内部依存グラフとシンボルの参照から、使われていない可能性のあるコードを挙げます。");
+        content.push_str("名前の一致だけで判定しているため、動的な呼び出し・外部のリポジトリからの利用がないことを確認してから削除してください。\n\n");
+        if unused.is_empty() {
+            content.push_str("未使用コードの候補は見つかりませんでした。\n");
+            return content;
+        }
+
+        content.push_str("## importされていないモジュール\n\n");
+        if unused.orphan_modules.is_empty() {
+            content.push_str("すべてのモジュールがいずれかのファイルからimportされています。\n\n");
+        } else {
+            content.push_str(&format!(
+                "エントリーポイント・テスト以外で、リポジトリ内のどのファイルからもimportされていないモジュールが{}件あります。\n\n",
+                unused.orphan_modules.len()
+            ));
+            for path in unused.orphan_modules.iter().take(MAX_ROWS) {
+                content.push_str(&format!("- {}\n", summarizer.file_link(index, path)));
             }
+            if unused.orphan_modules.len() > MAX_ROWS {
+                content.push_str(&format!("- ほか{}件\n", unused.orphan_modules.len() - MAX_ROWS));
+            }
+            content.push('\n');
+        }
+
+        content.push_str("## 参照されていない公開関数・メソッド\n\n");
+        if unused.unreferenced_symbols.is_empty() {
+            content.push_str("定義以外で参照されていない公開関数・メソッドはありません。\n");
         } else {
-            content.push_str("エントリーポイントが見つかりませんでした。\n");
+            content.push_str("| 関数・メソッド | 種類 | 定義 |\n|---|---|---|\n");
+            for symbol in unused.unreferenced_symbols.iter().take(MAX_ROWS) {
+                content.push_str(&format!(
+                    "| `{}` | {} | {}（{}行目） |\n",
+                    symbol.qualified_name(),
+                    symbol.kind,
+                    summarizer.file_link(index, &symbol.path),
+                    symbol.line
+                ));
+            }
+            if unused.unreferenced_symbols.len() > MAX_ROWS {
+                content.push_str(&format!("\nほか{}件\n", unused.unreferenced_symbols.len() - MAX_ROWS));
+            }
         }
 
-        Ok(content)
+        content
     }
 
-    /// mdBookをビルド
+    /// 技術的負債の章を生成
+    /// 
+    /// コメントに残されたTODO・FIXME・HACK・XXXを、目印・作成者ごとの件数と、深刻な目印から順の一覧にする
     /// 
     /// # 引数
-    /// * `out_dir` - 出力ディレクトリ
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
     /// 
     /// # 戻り値
-    /// * `Result<()>` - 成功、またはエラー
-    fn build_mdbook(&self, out_dir: &Path) -> Result<()> {
-        info!("mdBookをビルド中...");
+    /// * `String` - 章のMarkdown
+    fn generate_debt(index: &Index, summarizer: &Summarizer) -> String {
+        /// 一覧に並べる最大数
+        const MAX_ROWS: usize = 200;
+        /// 作成者ごとの件数の表に載せる最大人数
+        const MAX_AUTHORS: usize = 10;
+
+        let todos = index.todo_comments();
+        let mut content = String::new();
+        content.push_str("コメントに残されたTODO・FIXME・HACK・XXXから、後回しにされた作業と暫定的な実装を挙げます。");
+        content.push_str("作成者はGitの履歴でその行を最後に変更した人です。\n\n");
+        if todos.is_empty() {
+            content.push_str("TODO・FIXME・HACK・XXXのコメントは見つかりませんでした。\n");
+            return content;
+        }
 
-        let output = Command::new("mdbook")
-            .arg("build")
-            .current_dir(out_dir)
-            .output()
-            .with_context(|| {
-                "mdBookコマンドが見つかりません。インストールしてください: cargo install mdbook"
-            })?;
+        content.push_str("## 目印ごとの件数\n\n| 目印 | 意味 | 件数 |\n|---|---|---|\n");
+        for marker in TODO_MARKERS {
+            let count = todos.iter().filter(|(_, t)| t.marker == *marker).count();
+            if count > 0 {
+                content.push_str(&format!("| {} | {} | {} |\n", marker, marker_label(marker), count));
+            }
+        }
+        content.push('\n');
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("mdBookビルドエラー: {}", stderr));
+        let mut authors: HashMap<&str, usize> = HashMap::new();
+        for (_, todo) in &todos {
+            if let Some(author) = &todo.author {
+                *authors.entry(author.as_str()).or_default() += 1;
+            }
+        }
+        if !authors.is_empty() {
+            let mut authors: Vec<(&str, usize)> = authors.into_iter().collect();
+            authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            content.push_str("## 作成者ごとの件数\n\n| 作成者 | 件数 |\n|---|---|\n");
+            for (author, count) in authors.iter().take(MAX_AUTHORS) {
+                content.push_str(&format!("| {} | {} |\n", author, count));
+            }
+            content.push('\n');
         }
 
-        info!("mdBookビルド完了");
-        Ok(())
+        content.push_str("## 一覧\n\n| 目印 | 内容 | 場所 | 作成者 |\n|---|---|---|---|\n");
+        for (file, todo) in todos.iter().take(MAX_ROWS) {
+            content.push_str(&format!(
+                "| {} | {} | {}（{}行目） | {} |\n",
+                todo.marker,
+                todo.text.replace('|', "\\|"),
+                summarizer.file_link(index, &file.path),
+                todo.line,
+                todo.author.as_deref().unwrap_or("-")
+            ));
+        }
+        if todos.len() > MAX_ROWS {
+            content.push_str(&format!("\nほか{}件\n", todos.len() - MAX_ROWS));
+        }
+
+        content
     }
-}
 
-/// Wikiビルド結果
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WikiResult {
-    pub ok: bool,
-    pub site_dir: PathBuf,
-    pub pages: usize,
-}
+    /// 依存ライセンス付録を生成
+    /// 
+    /// エコシステムごとに外部依存の要求・解決したバージョン・ライセンスを表にし、
+    /// コピーレフトのライセンスの依存は冒頭にまとめて示す
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `String` - 付録のMarkdown
+    fn generate_licenses(index: &Index) -> String {
+        let dependencies = &index.external_dependencies;
+        let mut content = String::new();
+        if dependencies.is_empty() {
+            content.push_str("マニフェストに外部依存が宣言されていません。\n");
+            return content;
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let unknown = dependencies.iter().filter(|d| d.license.is_none()).count();
+        content.push_str(&format!(
+            "マニフェストに宣言された外部依存{}件のバージョン（lockfileで解決したもの）とライセンスです。",
+            dependencies.len()
+        ));
+        if unknown > 0 {
+            content.push_str(&format!(
+                "ライセンスを検出できなかった{}件はパッケージの配布元で確認してください。",
+                unknown
+            ));
+        }
+        content.push_str("\n\n");
 
-    #[test]
-    fn test_get_section_name() {
-        let config = Config::default();
-        let builder = MdBookBuilder::new(config);
-        assert_eq!(builder.get_section_name("overview"), "概要");
-        assert_eq!(builder.get_section_name("architecture"), "アーキテクチャ");
+        let copyleft: Vec<&analyzer_core::ExternalDependency> = dependencies.iter().filter(|d| d.copyleft).collect();
+        if !copyleft.is_empty() {
+            content.push_str(&format!("> ⚠️ コピーレフトのライセンスの依存が{}件あります。\n", copyleft.len()));
+            content.push_str(">\n");
+            for dependency in &copyleft {
+                content.push_str(&format!(
+                    "> - `{}`（{}）: {}\n",
+                    dependency.name,
+                    dependency.ecosystem,
+                    dependency.license.as_deref().unwrap_or_default()
+                ));
+            }
+            content.push('\n');
+        }
+
+        let mut ecosystems: BTreeMap<&str, Vec<&analyzer_core::ExternalDependency>> = BTreeMap::new();
+        for dependency in dependencies {
+            ecosystems.entry(dependency.ecosystem.as_str()).or_default().push(dependency);
+        }
+        for (ecosystem, dependencies) in ecosystems {
+            content.push_str(&format!("## {}\n\n", ecosystem));
+            content.push_str("| パッケージ | 要求 | 解決したバージョン | ライセンス | 種類 | 宣言したマニフェスト |\n");
+            content.push_str("|---|---|---|---|---|---|\n");
+            for dependency in dependencies {
+                let license = match (&dependency.license, dependency.copyleft) {
+                    (Some(license), true) => format!("⚠️ {}", license),
+                    (Some(license), false) => license.clone(),
+                    (None, _) => "不明".to_string(),
+                };
+                let manifests: Vec<String> = dependency.manifests.iter().map(|m| format!("`{}`", m.display())).collect();
+                content.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} |\n",
+                    dependency.name,
+                    dependency.requested.as_deref().map_or("-".to_string(), |v| format!("`{}`", v)),
+                    dependency.version.as_deref().map_or("-".to_string(), |v| format!("`{}`", v)),
+                    license.replace('|', "\\|"),
+                    dependency.kind,
+                    manifests.join(", ")
+                ));
+            }
+            content.push('\n');
+        }
+
+        content
     }
-}
 
+    /// サードパーティ付録を生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `String` - 付録のMarkdown
+    fn generate_third_party(index: &Index) -> String {
+        /// 1ディレクトリあたりに列挙するパッケージ数の上限
+        const MAX_PACKAGES: usize = 50;
+
+        let mut content = String::new();
+        content.push_str("以下のディレクトリはリポジトリに取り込まれた外部コードと判定したため、");
+        content.push_str("モジュール一覧・統計・図から除外しています。\n\n");
+
+        for dir in &index.vendored {
+            content.push_str(&format!("## `{}`\n\n", dir.path.display()));
+            content.push_str(&format!("{}（{}件）\n\n", dir.reason, dir.packages.len()));
+            for package in dir.packages.iter().take(MAX_PACKAGES) {
+                content.push_str(&format!("- {}\n", package));
+            }
+            if dir.packages.len() > MAX_PACKAGES {
+                content.push_str(&format!("- ほか{}件\n", dir.packages.len() - MAX_PACKAGES));
+            }
+            content.push('\n');
+        }
+
+        content
+    }
+
+    /// ライセンス・貢献方法・変更履歴のFAQを生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_project_docs_faq(index: &Index, summarizer: &Summarizer) -> String {
+        let docs = &index.project_docs;
+        let mut content = String::from("### ライセンスは何ですか？\n\n");
+        let files: Vec<String> = docs.license_files.iter().map(|f| summarizer.file_link(index, f)).collect();
+        match (&docs.license, files.is_empty()) {
+            (Some(license), false) => content.push_str(&format!("{}です（{}）。\n\n", license, files.join(", "))),
+            (Some(license), true) => {
+                content.push_str(&format!("マニフェストに{}と宣言されています（ライセンスファイルはありません）。\n\n", license))
+            }
+            (None, false) => content.push_str(&format!("ライセンスの種類を判定できませんでした。{}を確認してください。\n\n", files.join(", "))),
+            (None, true) => content.push_str("ライセンスファイルが見つかりませんでした。\n\n"),
+        }
+
+        if let Some(contributing) = &docs.contributing {
+            content.push_str("### 開発に参加するには？\n\n");
+            content.push_str(&format!("貢献ガイド（{}）を参照してください。\n\n", summarizer.file_link(index, contributing)));
+        }
+        if let Some(changelog) = &docs.changelog {
+            content.push_str("### 変更履歴はどこにありますか？\n\n");
+            content.push_str(&format!("{}に記載されています。\n\n", summarizer.file_link(index, changelog)));
+        }
+        content
+    }
+
+    /// テストの置き場所のFAQを生成（テストファイルの多いディレクトリと、本体にテストを含むファイル）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_test_location_faq(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::from("### テストはどこにありますか？\n\n");
+        let mut dirs: BTreeMap<String, usize> = BTreeMap::new();
+        for file in index.files.iter().filter(|f| f.is_test) {
+            let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+            let dir = relative.parent().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+            *dirs.entry(if dir.is_empty() { ".".to_string() } else { dir }).or_default() += 1;
+        }
+        let inline: Vec<&analyzer_core::FileInfo> = index.files.iter().filter(|f| !f.is_test && f.tests > 0).collect();
+        if dirs.is_empty() && inline.is_empty() {
+            content.push_str("テストファイル・テストを含むファイルは見つかりませんでした。\n\n");
+            return content;
+        }
+        if !dirs.is_empty() {
+            let mut dirs: Vec<(String, usize)> = dirs.into_iter().collect();
+            dirs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            content.push_str("テストファイルは主に次のディレクトリにあります。\n\n");
+            for (dir, count) in dirs.iter().take(MAX_FAQ_ITEMS) {
+                content.push_str(&format!("- `{}/`（{}ファイル）\n", dir, count));
+            }
+            if dirs.len() > MAX_FAQ_ITEMS {
+                content.push_str(&format!("- ほか{}ディレクトリ\n", dirs.len() - MAX_FAQ_ITEMS));
+            }
+            content.push('\n');
+        }
+        if let Some(file) = inline.iter().max_by(|a, b| a.tests.cmp(&b.tests).then_with(|| b.path.cmp(&a.path))) {
+            content.push_str(&format!(
+                "本体のファイルにもテストが含まれます（{}ファイル、最も多いのは{}の{}件）。\n\n",
+                inline.len(),
+                summarizer.file_link(index, &file.path),
+                file.tests
+            ));
+        }
+        content
+    }
+
+    /// 設定の置き場所のFAQを生成（設定ファイルとマニフェスト）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_config_faq(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::from("### 設定はどこにありますか？\n\n");
+        if index.config_files.is_empty() && index.manifests.is_empty() {
+            content.push_str("設定ファイル・パッケージマニフェストは見つかりませんでした。\n\n");
+            return content;
+        }
+        if !index.config_files.is_empty() {
+            content.push_str("動作やビルドを切り替える設定ファイルです。項目の説明は「設定ファイル」の章にまとめています。\n\n");
+            for file in index.config_files.iter().take(MAX_FAQ_ITEMS) {
+                content.push_str(&format!("- {} — {}\n", summarizer.file_link(index, &file.path), file.summary));
+            }
+            if index.config_files.len() > MAX_FAQ_ITEMS {
+                content.push_str(&format!("- ほか{}件\n", index.config_files.len() - MAX_FAQ_ITEMS));
+            }
+            content.push('\n');
+        }
+        if !index.manifests.is_empty() {
+            let manifests: Vec<String> =
+                index.manifests.iter().take(MAX_FAQ_ITEMS).map(|m| summarizer.file_link(index, &m.path)).collect();
+            let more = match index.manifests.len() > MAX_FAQ_ITEMS {
+                true => format!("ほか{}件", index.manifests.len() - MAX_FAQ_ITEMS),
+                false => String::new(),
+            };
+            content.push_str(&format!("依存関係とパッケージの設定は{}{}にあります。\n\n", manifests.join("・"), more));
+        }
+        content
+    }
+
+    /// モジュールの連携のFAQを生成（内部依存グラフのimportと多く使われるモジュール、APIエンドポイント）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_communication_faq(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::from("### モジュールはどのように連携していますか？\n\n");
+        let imports: usize = index.internal_dependencies.values().map(|deps| deps.len()).sum();
+        if imports == 0 && index.endpoints.is_empty() {
+            content.push_str("モジュール間のimport・APIエンドポイントは検出できませんでした。\n\n");
+            return content;
+        }
+        if imports > 0 {
+            let mut importers: BTreeMap<&PathBuf, usize> = BTreeMap::new();
+            for dep in index.internal_dependencies.values().flatten() {
+                *importers.entry(dep).or_default() += 1;
+            }
+            let mut hubs: Vec<(&PathBuf, usize)> = importers.into_iter().collect();
+            hubs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            content.push_str(&format!(
+                "モジュールは直接のimportで連携しています（リポジトリ内のimportは{}件）。多くのモジュールから使われているのは次のファイルです。\n\n",
+                imports
+            ));
+            for (path, count) in hubs.iter().take(MAX_FAQ_ITEMS) {
+                let path = match path.is_absolute() {
+                    true => (*path).clone(),
+                    false => index.repo_path.join(path),
+                };
+                content.push_str(&format!("- {}（{}ファイルからimport）\n", summarizer.file_link(index, &path), count));
+            }
+            content.push_str("\n依存の全体像は「アーキテクチャ」の章のモジュールグラフを参照してください。\n\n");
+        }
+        if !index.endpoints.is_empty() {
+            let mut files: Vec<&PathBuf> = index.endpoints.iter().map(|e| &e.file).collect();
+            files.sort();
+            files.dedup();
+            let links: Vec<String> = files.iter().take(MAX_FAQ_ITEMS).map(|f| summarizer.file_link(index, f)).collect();
+            content.push_str(&format!(
+                "外部からは{}件のAPIエンドポイントで呼び出されます（定義: {}）。一覧は「API」の章にまとめています。\n\n",
+                index.endpoints.len(),
+                links.join("・")
+            ));
+        }
+        content
+    }
+
+    /// 外部サービスのFAQを生成（クライアントライブラリから推定したサービスと、接続先の環境変数）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_services_faq(index: &Index, summarizer: &Summarizer) -> String {
+        /// サービスごとに挙げるファイルの最大数
+        const MAX_SERVICE_FILES: usize = 3;
+
+        let mut content = String::from("### どの外部サービスを呼び出していますか？\n\n");
+        let services = index.external_services();
+        let variables = index.endpoint_variables();
+        if services.is_empty() && variables.is_empty() {
+            content.push_str("外部サービスのクライアントライブラリ・接続先の設定は見つかりませんでした。\n\n");
+            return content;
+        }
+        if !services.is_empty() {
+            content.push_str("importしているクライアントライブラリから推定した外部サービスです。\n\n");
+            for service in &services {
+                let libraries: Vec<String> = service.libraries.iter().map(|l| format!("`{}`", l)).collect();
+                let mut files: Vec<String> =
+                    service.files.iter().take(MAX_SERVICE_FILES).map(|f| summarizer.file_link(index, f)).collect();
+                if service.files.len() > MAX_SERVICE_FILES {
+                    files.push(format!("ほか{}件", service.files.len() - MAX_SERVICE_FILES));
+                }
+                content.push_str(&format!(
+                    "- **{}**（{}、{}）: {}\n",
+                    service.name,
+                    service.kind_label(),
+                    libraries.join("・"),
+                    files.join("・")
+                ));
+            }
+            content.push('\n');
+        }
+        if !variables.is_empty() {
+            let names: Vec<String> = variables.iter().map(|(_, key)| format!("`{}`", key)).collect();
+            let mut sources: Vec<&PathBuf> = variables.iter().map(|(path, _)| path).collect();
+            sources.dedup();
+            let sources: Vec<String> = sources.iter().map(|path| summarizer.file_link(index, path)).collect();
+            content.push_str(&format!("接続先は環境変数{}で設定します（{}）。\n\n", names.join("・"), sources.join("・")));
+        }
+        content
+    }
+
+    /// 技術的負債のFAQを生成（TODO・FIXMEなどの件数と、最も多いファイル）
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `summarizer` - ファイルへのリンクの生成に使用（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_debt_faq(index: &Index, summarizer: &Summarizer) -> String {
+        let mut content = String::from("### 技術的負債はどこにありますか？\n\n");
+        let todos = index.todo_comments();
+        if todos.is_empty() {
+            content.push_str("TODO・FIXME・HACK・XXXのコメントは見つかりませんでした。\n\n");
+            return content;
+        }
+        let counts: Vec<String> = TODO_MARKERS
+            .iter()
+            .map(|marker| (marker, todos.iter().filter(|(_, t)| t.marker == *marker).count()))
+            .filter(|(_, count)| *count > 0)
+            .map(|(marker, count)| format!("{} {}件", marker, count))
+            .collect();
+        content.push_str(&format!("コメントに作業メモが{}件（{}）残っています。", todos.len(), counts.join("、")));
+        if let Some(file) = index.files.iter().max_by(|a, b| a.todos.len().cmp(&b.todos.len()).then_with(|| b.path.cmp(&a.path))) {
+            content.push_str(&format!("最も多いのは{}（{}件）です。", summarizer.file_link(index, &file.path), file.todos.len()));
+        }
+        content.push_str("一覧は「技術的負債」の章にまとめています。\n\n");
+        content
+    }
+
+    /// ビルド・テスト・起動方法のFAQを生成
+    /// 
+    /// インデックスに記録されたビルドコマンド（Cargo.toml, package.json, Makefileなどから検出）を
+    /// コードブロックで示す。起動方法にはエントリーポイントも併記する。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// 
+    /// # 戻り値
+    /// * `String` - FAQのMarkdown
+    fn render_build_faq(index: &Index) -> String {
+        let mut content = String::new();
+
+        let commands_of = |kind: &str| -> Vec<&analyzer_core::BuildCommand> {
+            index.build_commands.iter().filter(|c| c.kind == kind).collect()
+        };
+        let render_commands = |content: &mut String, commands: &[&analyzer_core::BuildCommand]| {
+            let mut sources: Vec<&str> = commands.iter().map(|c| c.source.as_str()).collect();
+            sources.dedup();
+            content.push_str("```sh\n");
+            for command in commands {
+                content.push_str(&command.command);
+                content.push('\n');
+            }
+            content.push_str("```\n\n");
+            content.push_str(&format!(
+                "（検出元: {}）\n\n",
+                sources.iter().map(|s| format!("`{}`", s)).collect::<Vec<_>>().join(", ")
+            ));
+        };
+
+        content.push_str("### どうやってビルドしますか？\n\n");
+        let build = commands_of("build");
+        if build.is_empty() {
+            content.push_str("ビルドファイル（Cargo.toml, package.json, Makefile, build.gradleなど）が見つかりませんでした。\n\n");
+        } else {
+            content.push_str("以下のコマンドでビルドできます。\n\n");
+            render_commands(&mut content, &build);
+        }
+
+        content.push_str("### テストはどう実行しますか？\n\n");
+        let test = commands_of("test");
+        if test.is_empty() {
+            content.push_str("テストの実行方法は検出できませんでした。\n\n");
+        } else {
+            content.push_str("以下のコマンドでテストを実行できます。\n\n");
+            render_commands(&mut content, &test);
+        }
+
+        content.push_str("### どのように始めますか？\n\n");
+        let run = commands_of("run");
+        if !run.is_empty() {
+            content.push_str("以下のコマンドで起動できます。\n\n");
+            render_commands(&mut content, &run);
+        }
+        let entrypoints = index.all_entrypoints();
+        if !entrypoints.is_empty() {
+            content.push_str("エントリーポイント:\n");
+            for ep in &entrypoints {
+                content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
+            }
+        } else if run.is_empty() {
+            content.push_str("エントリーポイントが見つかりませんでした。\n");
+        }
+
+        content
+    }
+
+    /// 概要セクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_overview(&self, index: &Index) -> Result<String> {
+        let summary_result = self
+            .summarizer
+            .summarize(index, "repo", "", &Locale::of(&self.config).style("concise"))
+            .await?;
+
+        Ok(summary_result.content_md)
+    }
+
+    /// アーキテクチャセクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_architecture(&self, index: &Index, with_diagrams: bool) -> Result<String> {
+        let mut content = String::from("# アーキテクチャ\n\n");
+
+        content.push_str("## システム構成\n\n");
+        content.push_str(&format!(
+            "このリポジトリは{}ファイル、{}言語、{}モジュールで構成されています。\n\n",
+            index.stats.files,
+            index.stats.languages.len(),
+            index.stats.modules
+        ));
+
+        if with_diagrams {
+            content.push_str("## モジュールグラフ\n\n");
+            content.push_str(&diagram_markdown(&self.diagrammer, index, "module-graph"));
+        }
+
+        content.push_str("## 主要コンポーネント\n\n");
+        for module in &index.modules {
+            content.push_str(&format!("- **{}** (`{}`)\n", module.id, module.path.display()));
+        }
+
+        Ok(content)
+    }
+
+    /// モジュールセクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_modules(&self, index: &Index) -> Result<String> {
+        let mut content = String::from("# モジュール\n\n");
+
+        for module in &index.modules {
+            content.push_str(&format!("## {}\n\n", module.id));
+            content.push_str(&format!("パス: `{}`\n\n", module.path.display()));
+            content.push_str(&format!("言語: {}\n\n", module.language));
+
+            if !module.dependencies.is_empty() {
+                content.push_str("### 依存関係\n\n");
+                for dep in &module.dependencies {
+                    content.push_str(&format!("- `{}`\n", dep));
+                }
+                content.push('\n');
+            }
+
+            // モジュールの要約を生成
+            let summary_result = self
+                .summarizer
+                .summarize(
+                    index,
+                    "module",
+                    &module.path.to_string_lossy(),
+                    &Locale::of(&self.config).style("concise"),
+                )
+                .await?;
+            content.push_str(&summary_result.content_md);
+            content.push_str("\n\n");
+        }
+
+        Ok(content)
+    }
+
+    /// フローセクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_flows(&self, index: &Index, with_diagrams: bool) -> Result<String> {
+        let mut content = String::from("# フロー\n\n");
+
+        if with_diagrams {
+            content.push_str("## シーケンス図\n\n");
+            content.push_str(&diagram_markdown(&self.diagrammer, index, "sequence"));
+
+            content.push_str("## コールグラフ\n\n");
+            content.push_str(&diagram_markdown(&self.diagrammer, index, "call-graph"));
+        }
+
+        Ok(content)
+    }
+
+    /// デプロイセクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_deploy(&self, index: &Index) -> Result<String> {
+        let mut content = String::from("# デプロイ\n\n");
+
+        content.push_str("## デプロイメント構成\n\n");
+
+        // デプロイメント図を生成
+        content.push_str(&diagram_markdown(&self.diagrammer, index, "deployment"));
+
+        content.push_str("## エントリーポイント\n\n");
+        for ep in &index.all_entrypoints() {
+            content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
+        }
+
+        Ok(content)
+    }
+
+    /// FAQセクションを生成（非並列実行用、後方互換性のため保持）
+    #[allow(dead_code)] // 後方互換性のため保持
+    async fn generate_faq(&self, index: &Index) -> Result<String> {
+        let mut content = String::from("# FAQ\n\n");
+
+        content.push_str("## よくある質問\n\n");
+        content.push_str("### このリポジトリは何ですか？\n\n");
+        content.push_str(&format!(
+            "{}ファイル、{}言語、{}モジュールを含むリポジトリです。\n\n",
+            index.stats.files,
+            index.stats.languages.len(),
+            index.stats.modules
+        ));
+
+        content.push_str(&Self::render_build_faq(index));
+        content.push_str(&render_contact_faq(&self.config));
+
+        Ok(content)
+    }
+
+    /// mdBookをビルド
+    /// 
+    /// # 引数
+    /// * `out_dir` - 出力ディレクトリ
+    /// * `site_dir` - ビルド結果のディレクトリ（`index.html` がなければ失敗とする）
+    /// 
+    /// # 戻り値
+    /// * `Result<(), ToolFailure>` - 成功、または失敗した理由
+    fn build_mdbook(&self, out_dir: &Path, site_dir: &Path) -> Result<(), ToolFailure> {
+        info!("mdBookをビルド中...");
+        self.tools.run(&ToolCommand::new("mdbook").arg("build").current_dir(out_dir), &site_dir.join("index.html"))?;
+        info!("mdBookビルド完了");
+        Ok(())
+    }
+}
+
+/// Wikiビルド結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WikiResult {
+    pub ok: bool,
+    pub site_dir: PathBuf,
+    pub pages: usize,
+    /// 前回の途中経過から再利用したセクションのページの数（`with_resume`、`pages` に含む）
+    #[serde(default)]
+    pub resumed: usize,
+    /// 前回の途中経過から再利用したモジュールの解説の数（`with_resume`）
+    #[serde(default)]
+    pub resumed_modules: usize,
+    /// セクションごとの品質スコア
+    #[serde(default)]
+    pub quality: Vec<PageQuality>,
+    /// 出力したバッジのSVG（ソースディレクトリの `badges/`、`site.badges = false` の場合は空）
+    #[serde(default)]
+    pub badges: Vec<PathBuf>,
+    /// 外部ツールがない・失敗したために生成しなかった成果物（mdBookのビルド結果）
+    #[serde(default)]
+    pub skipped: Vec<SkippedArtifact>,
+    /// ビルド中に記録した警告（共有した記録先の場合は解析などの警告を含む）
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// モジュールセクションのページ
+struct ModulePages {
+    /// モジュールセクションのページ
+    index: String,
+    /// パッケージごとのページ（ソースディレクトリからの相対パス、内容）
+    packages: Vec<(String, String)>,
+    /// 途中経過から再利用したモジュールの解説の数
+    resumed: usize,
+}
+
+/// モジュールをパッケージごとにまとめる（パッケージのパス順、各パッケージ内は掲載順）
+fn group_by_package<'a>(
+    index: &Index,
+    modules: Vec<&'a analyzer_core::ModuleInfo>,
+) -> Vec<(String, Vec<&'a analyzer_core::ModuleInfo>)> {
+    let mut packages: BTreeMap<String, Vec<&analyzer_core::ModuleInfo>> = BTreeMap::new();
+    for module in modules {
+        packages.entry(package_dir(index, &module.path)).or_default().push(module);
+    }
+    packages.into_iter().collect()
+}
+
+/// 言語・パッケージごとの集計
+#[derive(Default)]
+struct GroupStats<'a> {
+    files: usize,
+    lines: usize,
+    functions: usize,
+    test_files: usize,
+    /// リポジトリ内のファイルに解決できなかったimport
+    external: BTreeSet<&'a str>,
+}
+
+/// 言語別・パッケージ別の統計表を作成（行数の多い順）
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `String` - Markdownの表（ファイルがない場合は空）
+fn render_statistics(index: &Index) -> String {
+    if index.files.is_empty() {
+        return String::new();
+    }
+
+    let mut by_language: BTreeMap<String, GroupStats> = BTreeMap::new();
+    let mut by_package: BTreeMap<String, GroupStats> = BTreeMap::new();
+    for file in &index.files {
+        for stats in [
+            by_language.entry(file.language.clone()).or_default(),
+            by_package.entry(package_dir(index, &file.path)).or_default(),
+        ] {
+            stats.files += 1;
+            stats.lines += file.metrics.lines;
+            stats.functions += file.metrics.functions;
+            if is_test_file(file) {
+                stats.test_files += 1;
+            }
+            for dep in &file.dependencies {
+                if index.dependencies.get(dep).is_some_and(|paths| paths.is_empty()) {
+                    stats.external.insert(dep.as_str());
+                }
+            }
+        }
+    }
+
+    let mut content = String::from("## 規模\n\n");
+    content.push_str("テスト比率はテストファイル（またはテストを含むファイル）の割合で、カバレッジの目安です。\n\n");
+    for (title, label, groups) in [("言語別", "言語", by_language), ("パッケージ別", "パッケージ", by_package)] {
+        let mut rows: Vec<(String, GroupStats)> = groups.into_iter().collect();
+        rows.sort_by(|a, b| b.1.lines.cmp(&a.1.lines).then(a.0.cmp(&b.0)));
+        content.push_str(&format!("### {}\n\n", title));
+        content.push_str(&format!(
+            "| {} | ファイル | 行数 | 関数 | 外部依存 | テスト比率 |\n|---|---:|---:|---:|---:|---:|\n",
+            label
+        ));
+        for (name, stats) in rows {
+            content.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {:.0}% |\n",
+                name,
+                stats.files,
+                stats.lines,
+                stats.functions,
+                stats.external.len(),
+                stats.test_files as f64 * 100.0 / stats.files as f64
+            ));
+        }
+        content.push('\n');
+    }
+    content
+}
+
+/// 起動方法（コンテナの起動コマンドと、フレームワーク・実行ファイルのエントリーポイント）を記述
+///
+/// # 引数
+/// * `index` - インデックス
+///
+/// # 戻り値
+/// * `String` - 「## 起動方法」のMarkdown（エントリーポイントがない場合は空）
+fn render_startup(index: &Index) -> String {
+    let entrypoints = index.all_entrypoints();
+    if entrypoints.is_empty() {
+        return String::new();
+    }
+    let (containers, sources): (Vec<_>, Vec<_>) = entrypoints.iter().partition(|e| e.kind == "docker");
+
+    let mut content = String::from("## 起動方法\n\n");
+    if !containers.is_empty() {
+        content.push_str("コンテナはDockerfileの次のコマンドで起動します。\n\n");
+        for ep in &containers {
+            content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
+        }
+        content.push('\n');
+    }
+    if !sources.is_empty() {
+        let servers = sources.iter().filter(|e| e.kind != "main" && e.kind != "config").count();
+        if servers > 0 {
+            content.push_str(&format!("{}個のアプリケーション（Webフレームワーク）の起動箇所を検出しました。\n\n", servers));
+        }
+        for ep in &sources {
+            content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
+        }
+        content.push('\n');
+    }
+    content
+}
+
+/// テストファイル（またはRustのようにテストを同じファイルに含むファイル）か
+fn is_test_file(file: &analyzer_core::FileInfo) -> bool {
+    file.is_test || file.tests > 0
+}
+
+/// モジュールで宣言された型（構造体・クラス・トレイト・インターフェースなど）の表を作成
+///
+/// # 引数
+/// * `file_info` - モジュールのファイル
+///
+/// # 戻り値
+/// * `String` - Markdownの表（型がない場合は空）
+fn render_type_table(file_info: &analyzer_core::FileInfo) -> String {
+    let types: Vec<&analyzer_core::Symbol> = file_info.symbols.iter().filter(|s| s.is_type()).collect();
+    if types.is_empty() {
+        return String::new();
+    }
+
+    let mut table = String::from("### 型定義\n\n| 名前 | 種類 | 可視性 | 行 | メソッド数 |\n|---|---|---|---|---|\n");
+    for symbol in types {
+        let methods = file_info
+            .symbols
+            .iter()
+            .filter(|s| s.kind == "method" && s.parent.as_deref() == Some(symbol.name.as_str()))
+            .count();
+        let visibility = if symbol.visibility.is_empty() { "-" } else { symbol.visibility.as_str() };
+        table.push_str(&format!(
+            "| `{}` | {} | {} | {}-{} | {} |\n",
+            symbol.name, symbol.kind, visibility, symbol.start_line, symbol.end_line, methods
+        ));
+    }
+    table.push('\n');
+    table
+}
+
+/// 関数・メソッドの引数の表と戻り値の型・async・可視性（モジュールのページ用）
+///
+/// # 引数
+/// * `method` - メソッド情報
+/// * `locale` - 見出し・定型文の言語
+///
+/// # 戻り値
+/// * `String` - Markdown（表示する情報がない場合は空）
+fn render_method_api(method: &summarizer::MethodInfo, locale: Locale) -> String {
+    let mut api = String::new();
+    if !method.parameters.is_empty() {
+        api.push_str(&format!(
+            "| {} | {} |\n|---|---|\n",
+            locale.text("module.api-parameter"),
+            locale.text("module.api-type")
+        ));
+        for parameter in &method.parameters {
+            let ty = match parameter.type_name.as_str() {
+                "" => "-".to_string(),
+                ty => format!("`{}`", ty.replace('|', "\\|")),
+            };
+            api.push_str(&format!("| `{}` | {} |\n", parameter.name, ty));
+        }
+        api.push('\n');
+    }
+
+    let mut facts = Vec::new();
+    if !method.return_type.is_empty() {
+        facts.push(format!("**{}**: `{}`", locale.text("module.api-returns"), method.return_type));
+    }
+    if method.is_async {
+        facts.push(format!("**{}**", locale.text("module.api-async")));
+    }
+    if !method.visibility.is_empty() {
+        facts.push(format!("**{}**: {}", locale.text("module.api-visibility"), method.visibility));
+    }
+    if !facts.is_empty() {
+        api.push_str(&facts.join(" ・ "));
+        api.push_str("\n\n");
+    }
+    api
+}
+
+/// モジュールのページをパッケージごとに分けるか（`site.layout.package-dirs`、またはワークスペースのパッケージが複数）
+fn groups_by_package(index: &Index, config: &Config) -> bool {
+    config.site.layout.package_dirs || index.packages.len() > 1
+}
+
+/// ファイルのパッケージ（ワークスペースのパッケージ、READMEのある最も近いディレクトリ、最上位のディレクトリの順）
+fn package_dir(index: &Index, path: &Path) -> String {
+    let relative = |path: &Path| path.strip_prefix(&index.repo_path).unwrap_or(path).to_path_buf();
+    let dir = match (index.package_of(path), index.package_readme(path)) {
+        (Some(package), _) if !package.path.as_os_str().is_empty() => package.path.clone(),
+        (_, Some(readme)) => relative(&readme.dir),
+        _ => relative(path)
+            .parent()
+            .and_then(|p| p.components().next())
+            .map(|c| PathBuf::from(c.as_os_str()))
+            .unwrap_or_default(),
+    };
+    let dir = dir.to_string_lossy().replace('\\', "/");
+    if dir.is_empty() { "_root".to_string() } else { dir }
+}
+
+/// パッケージのページのパス（ソースディレクトリからの相対パス）
+fn package_page_file(modules_file: &str, package: &str) -> String {
+    let base = modules_file.strip_suffix(".md").unwrap_or(modules_file);
+    format!("{}/{}/index.md", base, package)
+}
+
+/// モジュールのページのファイル名（パッケージのディレクトリからの相対パス）
+///
+/// パッケージ内のパスの区切り・記号を `-` にする（例: `src/lib.rs` → `src-lib-rs.md`）
+fn module_page_name(index: &Index, package: &str, module: &analyzer_core::ModuleInfo) -> String {
+    let relative = module.path.strip_prefix(&index.repo_path).unwrap_or(&module.path);
+    let relative = match package {
+        "_root" => relative,
+        package => relative.strip_prefix(package).unwrap_or(relative),
+    };
+    let slug: String = relative
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' })
+        .collect();
+    format!("{}.md", slug.trim_matches('-'))
+}
+
+/// モジュールのページから、そのモジュールに現れる用語への用語集のリンク
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `config` - ページの配置（`site.layout`）と見出しの言語
+/// * `modules` - 詳細ページを生成するモジュール
+///
+/// # 戻り値
+/// * `HashMap<PathBuf, String>` - モジュールのパス → 用語へのリンクを並べたMarkdown（用語のないモジュールは含まない）
+fn module_term_links(index: &Index, config: &Config, modules: &[&analyzer_core::ModuleInfo]) -> HashMap<PathBuf, String> {
+    let terms = index.glossary_terms();
+    if terms.is_empty() {
+        return HashMap::new();
+    }
+    let layout = &config.site.layout;
+    let modules_file = layout.section_file("modules");
+    let glossary_file = layout.section_file("glossary");
+    let separator = match Locale::of(config) {
+        Locale::Ja => "・",
+        Locale::En => ", ",
+    };
+    let grouped = groups_by_package(index, config);
+
+    let mut links = HashMap::new();
+    for module in modules {
+        let page_file = match grouped {
+            true => {
+                let package = package_dir(index, &module.path);
+                let package_file = package_page_file(&modules_file, &package);
+                format!("{}{}", package_file.strip_suffix("index.md").unwrap_or(&package_file), module_page_name(index, &package, module))
+            }
+            false => modules_file.clone(),
+        };
+        let depth = Path::new(&page_file).parent().map_or(0, |p| p.components().count());
+        let glossary = format!("{}{}", "../".repeat(depth), glossary_file);
+        let module_terms: Vec<String> = terms
+            .iter()
+            .filter(|t| t.files.contains(&module.path))
+            .map(|t| format!("[{}]({}#{})", t.term, glossary, heading_anchor(&t.term)))
+            .collect();
+        if !module_terms.is_empty() {
+            links.insert(module.path.clone(), module_terms.join(separator));
+        }
+    }
+    links
+}
+
+/// 見出しからmdBookが生成するアンカーID（mdBookの `normalize_id` と同じ規則）
+///
+/// 英数字・`_`・`-` は小文字にして残し、空白は `-` に、それ以外の記号は除く
+fn heading_anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                Some(c.to_ascii_lowercase())
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// ページに埋め込む図のMarkdown
+/// 
+/// 生成できない図・Mermaid以外の形式（Graphvizなど、mdBookで表示できない）の図は警告を記録して省略する。
+/// 
+/// # 引数
+/// * `diagrammer` - ダイアグラマー（警告はその記録先に残す）
+/// * `index` - インデックス
+/// * `diagram_type` - 図のタイプ
+/// 
+/// # 戻り値
+/// * `String` - 図のMarkdown（省略した場合は空文字列）
+fn diagram_markdown(diagrammer: &Diagrammer, index: &Index, diagram_type: &str) -> String {
+    match diagrammer.try_generate_diagram(index, diagram_type) {
+        Some(diagram) if diagram.format == "mermaid" => diagram.to_markdown(),
+        Some(diagram) => {
+            diagrammer.diagnostics().warn(
+                "wiki",
+                format!("{}形式の{}はページに埋め込めないため省略しました", diagram.format, diagram_type),
+            );
+            String::new()
+        }
+        None => String::new(),
+    }
+}
+
+/// セクションの生成タスクの優先度（付録はモジュールの解説より後）
+fn section_priority(section: &str) -> Priority {
+    if APPENDIX_SECTIONS.contains(&section) {
+        Priority::Appendix
+    } else {
+        Priority::Section
+    }
+}
+
+/// 内容を生成できないセクションのページ
+fn placeholder_page(section: &str, locale: Locale) -> String {
+    format!("# {}\n\n{}\n", locale.text(section), locale.text("section.placeholder"))
+}
+
+/// ページを書き込む（親ディレクトリがなければ作成）
+fn write_page(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content).with_context(|| format!("ページの書き込みに失敗しました: {:?}", path))
+}
+
+/// `[branding]` のフッターをページの末尾に付ける（フッターがない場合はそのまま）
+fn with_footer(mut content: String, config: &Config) -> String {
+    if let Some(footer) = config.branding.footer(&config.project.name) {
+        content.truncate(content.trim_end().len());
+        content.push_str(&format!("\n\n---\n\n{}\n", footer));
+    }
+    content
+}
+
+/// `[branding]` の問い合わせ先・関連リンクのFAQ（どちらも未設定の場合は空）
+fn render_contact_faq(config: &Config) -> String {
+    let branding = &config.branding;
+    if branding.contact.is_none() && branding.links.is_empty() {
+        return String::new();
+    }
+    let mut content = String::from("\n### 誰に問い合わせればよいですか？\n\n");
+    let product = branding.product(&config.project.name);
+    match (&branding.team, &branding.contact) {
+        (Some(team), Some(contact)) => {
+            content.push_str(&format!("{}は{}が担当しています。問い合わせ先: {}\n\n", product, team, contact))
+        }
+        (None, Some(contact)) => content.push_str(&format!("{}の問い合わせ先: {}\n\n", product, contact)),
+        _ => {}
+    }
+    if !branding.links.is_empty() {
+        content.push_str("関連リンク:\n");
+        for (name, url) in &branding.links {
+            content.push_str(&format!("- [{}]({})\n", name, url));
+        }
+    }
+    content
+}
+
+/// book.tomlに書くパス（区切りは `/`）
+fn toml_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// 実際に生成する目次
+///
+/// ワークスペースに複数のパッケージがあればアーキテクチャの次に「パッケージ」を、
+/// APIエンドポイントがあればフローの前に「API」を、
+/// 外部依存があれば依存ライセンス付録を、
+/// 解析から除外したベンダリングディレクトリがあればサードパーティ付録を末尾に追加する
+fn effective_toc(index: &Index, toc: &[String]) -> Vec<String> {
+    let mut toc = toc.to_vec();
+    if index.packages.len() > 1 && !toc.iter().any(|s| s == "packages") {
+        let position = toc.iter().position(|s| s == "architecture").map_or(toc.len().min(1), |i| i + 1);
+        toc.insert(position, "packages".to_string());
+    }
+    if !index.endpoints.is_empty() && !toc.iter().any(|s| s == "api") {
+        let position = toc.iter().position(|s| s == "flows").unwrap_or(toc.len());
+        toc.insert(position, "api".to_string());
+    }
+    if !index.config_files.is_empty() && !toc.iter().any(|s| s == "config") {
+        let position = toc.iter().position(|s| s == "faq").unwrap_or(toc.len());
+        toc.insert(position, "config".to_string());
+    }
+    if !toc.iter().any(|s| s == "glossary") && !index.glossary_terms().is_empty() {
+        let position = toc.iter().position(|s| s == "faq").unwrap_or(toc.len());
+        toc.insert(position, "glossary".to_string());
+    }
+    if !index.external_dependencies.is_empty() && !toc.iter().any(|s| s == "licenses") {
+        let position = toc.iter().position(|s| s == "third-party").unwrap_or(toc.len());
+        toc.insert(position, "licenses".to_string());
+    }
+    if !index.vendored.is_empty() && !toc.iter().any(|s| s == "third-party") {
+        toc.push("third-party".to_string());
+    }
+    toc
+}
+
+/// Markdownの見出しを指定レベル以下に下げる（コードブロック内は変更しない）
+/// 
+/// # 引数
+/// * `markdown` - Markdown文字列
+/// * `min_level` - 最上位の見出しを何レベルにするか（`#` の数）
+/// 
+/// # 戻り値
+/// * `String` - 見出しを調整したMarkdown
+fn demote_headings(markdown: &str, min_level: usize) -> String {
+    let top = markdown
+        .lines()
+        .filter_map(heading_level)
+        .min()
+        .unwrap_or(min_level);
+    let shift = min_level.saturating_sub(top);
+
+    let mut in_code = false;
+    let mut result = Vec::new();
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        match heading_level(line) {
+            Some(_) if !in_code && shift > 0 => result.push(format!("{}{}", "#".repeat(shift), line)),
+            _ => result.push(line.to_string()),
+        }
+    }
+    result.join("\n")
+}
+
+/// 見出し行のレベルを取得
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level > 0 && line[level..].starts_with(' ') {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_section_name() {
+        let config = Config::default();
+        let builder = MdBookBuilder::new(config);
+        assert_eq!(builder.get_section_name("overview"), "概要");
+        assert_eq!(builder.get_section_name("architecture"), "アーキテクチャ");
+    }
+
+    #[tokio::test]
+    async fn test_english_wiki_headings() {
+        let mut config = Config::default();
+        config.project.language = "en".to_string();
+        let builder = MdBookBuilder::new(config);
+        assert_eq!(builder.get_section_name("licenses"), "Dependency licenses");
+        assert!(builder.book_toml().contains("language = \"en\""));
+
+        let toc = vec!["overview".to_string(), "faq".to_string()];
+        assert!(builder.summary_markdown(&Index::default(), &toc).contains("- [Overview](overview.md)\n- [FAQ](faq.md)\n"));
+
+        let overview = builder.render_page(&Index::default(), "overview", false).await.unwrap();
+        assert!(overview.starts_with("# Overview\n\nThis page shows the overall structure"));
+        assert!(overview.contains("## Statistics\n\n- **Files**: 0\n"));
+    }
+
+    #[tokio::test]
+    async fn test_module_content_starts_with_package_readme() {
+        let module_path = PathBuf::from("/repo/crates/foo/src/lib.rs");
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![analyzer_core::FileInfo {
+                path: module_path.clone(),
+                name: "lib".to_string(),
+                language: "rs".to_string(),
+                content: Some("pub fn run() {\n}\n".to_string()),
+                ..Default::default()
+            }],
+            readmes: vec![analyzer_core::PackageReadme {
+                dir: PathBuf::from("/repo/crates/foo"),
+                content: "# foo\n\n手書きの説明です。\n".to_string(),
+            }],
+            ..Default::default()
+        };
+        let module = analyzer_core::ModuleInfo {
+            path: module_path.clone(),
+            name: "lib".to_string(),
+            language: "rs".to_string(),
+            ..Default::default()
+        };
+
+        let readme = index.package_readme(&module_path);
+        assert!(readme.is_some());
+
+        let summarizer = Summarizer::new(Config::default());
+        let content = MdBookBuilder::generate_module_content_detailed(&index, &module, readme, "", &summarizer, 30, Locale::Ja)
+            .await
+            .unwrap();
+
+        let readme_pos = content.find("手書きの説明です。").unwrap();
+        assert!(content.contains("### foo"));
+        assert!(readme_pos < content.find("run").unwrap_or(usize::MAX));
+    }
+
+    #[tokio::test]
+    async fn test_module_content_summarizes_types_with_methods() {
+        let module_path = PathBuf::from("/repo/src/store.rs");
+        let source = "/// Key-value store\npub struct Store {\n    entries: Vec<String>,\n}\n\nimpl Store {\n    /// Gets an entry\n    pub async fn get(&self, key: &str, limit: usize) -> Option<String> { None }\n}\n";
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![analyzer_core::FileInfo {
+                path: module_path.clone(),
+                name: "store".to_string(),
+                language: "rs".to_string(),
+                content: Some(source.to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let module = analyzer_core::ModuleInfo {
+            path: module_path,
+            name: "store".to_string(),
+            language: "rs".to_string(),
+            ..Default::default()
+        };
+
+        let summarizer = Summarizer::new(Config::default());
+        let content = MdBookBuilder::generate_module_content_detailed(&index, &module, None, "", &summarizer, 30, Locale::En)
+            .await
+            .unwrap();
+
+        assert!(content.contains("### Types\n\n#### Store\n\n**Responsibility**: Key-value store[^"), "{}", content);
+        assert!(content.contains("**Fields**: `entries`\n\n**Key methods**:\n\n- `get`: Gets an entry\n"), "{}", content);
+        assert!(content.contains("#### Store::get\n\n"), "{}", content);
+        assert!(
+            content.contains("| Parameter | Type |\n|---|---|\n| `key` | `&str` |\n| `limit` | `usize` |\n\n"),
+            "{}",
+            content
+        );
+        assert!(
+            content.contains("**Returns**: `Option<String>` ・ **Asynchronous (async)** ・ **Visibility**: public\n\n##### How the code works"),
+            "{}",
+            content
+        );
+
+        // 上限0は無制限
+        let unlimited = MdBookBuilder::generate_module_content_detailed(&index, &module, None, "", &summarizer, 0, Locale::En)
+            .await
+            .unwrap();
+        assert!(unlimited.contains("#### Store::get\n\n"), "{}", unlimited);
+    }
+
+    #[test]
+    fn test_render_type_table() {
+        let source = "pub struct Store;\nimpl Store {\n    pub fn get(&self) {}\n}\ntrait Backend {}\n";
+        let file_info = analyzer_core::FileInfo {
+            symbols: analyzer_core::parse_source(source, "rs").unwrap().symbols,
+            ..Default::default()
+        };
+
+        let table = render_type_table(&file_info);
+        assert!(table.starts_with("### 型定義\n\n| 名前 | 種類 | 可視性 | 行 | メソッド数 |\n"));
+        assert!(table.contains("| `Store` | struct | public | 1-1 | 1 |\n"));
+        assert!(table.contains("| `Backend` | trait | private | 5-5 | 0 |\n"));
+        assert!(render_type_table(&analyzer_core::FileInfo::default()).is_empty());
+    }
+
+    #[test]
+    fn test_generate_metrics_tables() {
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![analyzer_core::FileInfo {
+                path: PathBuf::from("/repo/src/main.rs"),
+                metrics: analyzer_core::FileMetrics {
+                    lines: 4,
+                    code_lines: 3,
+                    comment_lines: 1,
+                    functions: 1,
+                    complexity: 3,
+                    function_metrics: vec![analyzer_core::FunctionMetrics {
+                        name: "main".to_string(),
+                        start_line: 2,
+                        end_line: 4,
+                        complexity: 3,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let content = MdBookBuilder::generate_metrics(&index);
+        assert!(content.contains("- **コメント率**: 25.0%\n"));
+        assert!(content.contains("| `src/main.rs` | 4 | 25% | 1 | 3 | 3 |\n"));
+        assert!(content.contains("| `main` | `src/main.rs` | 2-4 | 3 | 3 |\n"));
+    }
+
+    #[test]
+    fn test_generate_hotspots_ranks_churn_by_complexity() {
+        let file = |name: &str, commits: usize, complexity: usize| analyzer_core::FileInfo {
+            path: PathBuf::from(format!("/repo/src/{}.rs", name)),
+            metrics: analyzer_core::FileMetrics { lines: 50, complexity, ..Default::default() },
+            history: Some(analyzer_core::FileHistory {
+                commits,
+                authors: vec![analyzer_core::AuthorCommits { name: "alice".to_string(), commits }],
+                last_modified: 1_700_000_000,
+                monthly: vec![0, 1, commits],
+            }),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("stable", 2, 30), file("busy", 10, 8)],
+            ..Default::default()
+        };
+
+        let mut config = Config::default();
+        config.project.source_url = Some("https://github.com/owner/repo/blob/main".to_string());
+        let summarizer = Summarizer::new(config);
+        let content = MdBookBuilder::generate_hotspots(&index, &summarizer);
+        assert!(content.contains(
+            "| 1 | [`src/busy.rs`](https://github.com/owner/repo/blob/main/src/busy.rs) | 80 | 10 | 8 | ▁▂█ | 50 | 2023-11-14 | alice（10） |"
+        ));
+        assert!(content.contains("| 2 | [`src/stable.rs`]"));
+        assert!(MdBookBuilder::generate_hotspots(&Index::default(), &summarizer).contains("Gitの履歴がない"));
+    }
+
+    #[test]
+    fn test_generate_tests_lists_untested_modules() {
+        let file = |path: &str, is_test: bool, tests: usize| analyzer_core::FileInfo {
+            path: PathBuf::from("/repo").join(path),
+            language: "go".to_string(),
+            is_module: !is_test,
+            is_test,
+            tests,
+            metrics: analyzer_core::FileMetrics { lines: 40, complexity: 5, ..Default::default() },
+            ..Default::default()
+        };
+        let module = |path: &str, importance: f64| analyzer_core::ModuleInfo {
+            path: PathBuf::from("/repo").join(path),
+            importance,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("pkg/store.go", false, 0), file("pkg/store_test.go", true, 4), file("pkg/server.go", false, 0)],
+            modules: vec![module("pkg/store.go", 0.4), module("pkg/server.go", 0.9)],
+            ..Default::default()
+        };
+
+        let content = MdBookBuilder::generate_tests(&index);
+        assert!(content.contains("- **テストファイル**: 1\n"));
+        assert!(content.contains("モジュールのうち1/2（50%）にテストがあります。"));
+        assert!(content.contains("| `pkg/store.go` | 4 | `pkg/store_test.go` |\n"));
+        assert!(content.contains("| `pkg/server.go` | 0.90 | 5 | 40 |\n"));
+        assert!(MdBookBuilder::generate_tests(&Index::default()).contains("見つかりません"));
+    }
+
+    #[test]
+    fn test_generate_api_lists_endpoints() {
+        let endpoint = |method: &str, path: &str, handler: Option<&str>, framework: &str| analyzer_core::ApiEndpoint {
+            method: method.to_string(),
+            path: path.to_string(),
+            handler: handler.map(str::to_string),
+            file: PathBuf::from("/repo/src/routes.ts"),
+            line: 3,
+            framework: framework.to_string(),
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            endpoints: vec![
+                endpoint("POST", "/users", None, "express"),
+                endpoint("GET", "/health", Some("health"), "express"),
+                endpoint("QUERY", "me", None, "graphql"),
+            ],
+            ..Default::default()
+        };
+
+        let summarizer = Summarizer::new(Config::default());
+        let content = MdBookBuilder::generate_api(&index, &summarizer);
+        assert!(content.contains("- **RESTエンドポイント**: 2\n- **フレームワーク**: express\n"));
+        assert!(content.contains("| GET | `/health` | `health` | `src/routes.ts`（3行目） |\n| POST | `/users` | - |"));
+        assert!(content.contains("## GraphQL\n\n| 操作 | フィールド | リゾルバー | 定義 |\n|---|---|---|---|\n| QUERY | `me` |"));
+        assert!(MdBookBuilder::generate_api(&Index::default(), &summarizer).contains("見つかりません"));
+
+        let toc: Vec<String> = ["overview", "flows", "faq"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(effective_toc(&index, &toc), ["overview", "api", "flows", "faq"]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_packages_for_workspace() {
+        let package = |name: &str, path: &str, dependencies: &[&str]| analyzer_core::PackageInfo {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            manifest: PathBuf::from(path).join("Cargo.toml"),
+            ecosystem: "cargo".to_string(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+        };
+        let file = |path: &str| analyzer_core::FileInfo {
+            path: PathBuf::from("/repo").join(path),
+            language: "rs".to_string(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("crates/api/src/lib.rs"), file("crates/core/src/lib.rs")],
+            packages: vec![package("api", "crates/api", &["core"]), package("core", "crates/core", &[])],
+            ..Default::default()
+        };
+
+        let summarizer = Summarizer::new(Config::default());
+        let content = MdBookBuilder::generate_packages_parallel(&index, &summarizer, Locale::Ja).await.unwrap();
+        assert!(content.contains("| api | `crates/api` | cargo | 1 | core |\n"));
+        assert!(content.contains("\n## api\n"));
+        assert!(content.contains("- 依存元: `api`"));
+
+        let toc: Vec<String> = ["overview", "architecture", "modules"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(effective_toc(&index, &toc), ["overview", "architecture", "packages", "modules"]);
+        assert_eq!(effective_toc(&Index::default(), &toc), toc);
+    }
+
+    #[test]
+    fn test_render_statistics_by_language_and_package() {
+        let file = |path: &str, language: &str, lines: usize, deps: &[&str]| analyzer_core::FileInfo {
+            path: PathBuf::from("/repo").join(path),
+            language: language.to_string(),
+            dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            metrics: analyzer_core::FileMetrics { lines, functions: 2, ..Default::default() },
+            is_test: path.contains(".test."),
+            ..Default::default()
+        };
+        let mut index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![
+                file("web/app.ts", "ts", 100, &["react", "./api"]),
+                file("web/app.test.ts", "ts", 20, &["vitest"]),
+                file("server/main.py", "py", 300, &["flask"]),
+            ],
+            ..Default::default()
+        };
+        for (dep, paths) in [("react", vec![]), ("vitest", vec![]), ("flask", vec![]), ("./api", vec!["web/api.ts".to_string()])] {
+            index.dependencies.insert(dep.to_string(), paths);
+        }
+
+        let content = render_statistics(&index);
+        assert!(content.contains("| `py` | 1 | 300 | 2 | 1 | 0% |\n| `ts` | 2 | 120 | 4 | 2 | 50% |\n"));
+        assert!(content.contains("| `server` | 1 | 300 | 2 | 1 | 0% |\n| `web` | 2 | 120 | 4 | 2 | 50% |\n"));
+        assert!(render_statistics(&Index::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_render_page_by_section_and_module_path() {
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![analyzer_core::FileInfo {
+                path: PathBuf::from("/repo/src/api.rs"),
+                language: "rs".to_string(),
+                content: Some("pub fn route() {\n}\n".to_string()),
+                ..Default::default()
+            }],
+            modules: vec![analyzer_core::ModuleInfo {
+                path: PathBuf::from("/repo/src/api.rs"),
+                id: "src/api.rs".to_string(),
+                name: "api".to_string(),
+                language: "rs".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let builder = MdBookBuilder::new(Config::default());
+
+        let faq = builder.render_page(&index, "faq", false).await.unwrap();
+        assert!(faq.starts_with("# "));
+
+        let module = builder.render_page(&index, "src/api.rs", false).await.unwrap();
+        assert!(module.starts_with("## src/api.rs\n"));
+        assert!(module.contains("#### route"));
+
+        assert!(builder.render_page(&index, "src/missing.rs", false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_render_page_uses_overridden_templates() {
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            modules: vec![analyzer_core::ModuleInfo {
+                path: PathBuf::from("/repo/src/api.rs"),
+                id: "src/api.rs".to_string(),
+                language: "rs".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("wiki")).unwrap();
+        fs::write(dir.path().join("wiki/module.md"), "## {{ module.icon }} {{ module.id }} ({{ module.language }})\n").unwrap();
+        let mut config = Config::default();
+        config.templates.dir = Some(dir.path().to_path_buf());
+        let builder = MdBookBuilder::new(config.clone());
+
+        assert_eq!(builder.render_page(&index, "src/api.rs", false).await.unwrap(), "## 📄 src/api.rs (rs)\n");
+        // 上書きしていない概要は組み込みのテンプレート
+        assert!(builder.render_page(&index, "overview", false).await.unwrap().contains("- **ファイル数**: 0個\n"));
+
+        fs::write(dir.path().join("wiki/module.md"), "{{ module.missing }}").unwrap();
+        let error = builder.render_page(&index, "src/api.rs", false).await.unwrap_err();
+        assert!(error.to_string().contains("wiki/module.md"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_branding_in_book_toml_faq_and_footer() {
+        let mut config = Config::default();
+        config.branding.product_name = Some("Acme \"Pay\"".to_string());
+        config.branding.team = Some("決済チーム".to_string());
+        config.branding.contact = Some("payments@example.com".to_string());
+        config.branding.links.insert("Runbook".to_string(), "https://wiki.example.com/runbook".to_string());
+        let builder = MdBookBuilder::new(config);
+
+        let book_toml = builder.book_toml();
+        assert!(book_toml.contains("title = \"Acme \\\"Pay\\\"\"\nauthors = [\"決済チーム\"]\n"));
+
+        let faq = builder.render_page(&Index::default(), "faq", false).await.unwrap();
+        assert!(faq.contains("### 誰に問い合わせればよいですか？\n\nAcme \"Pay\"は決済チームが担当しています。問い合わせ先: payments@example.com\n"));
+        assert!(faq.contains("- [Runbook](https://wiki.example.com/runbook)\n"));
+        assert!(faq.ends_with("\n\n---\n\nAcme \"Pay\" — 決済チーム ・ 問い合わせ: payments@example.com ・ [Runbook](https://wiki.example.com/runbook)\n"));
+
+        let plain = MdBookBuilder::new(Config::default());
+        assert!(plain.book_toml().contains("authors = [\"Unnamed Project\"]"));
+        assert!(!plain.render_page(&Index::default(), "faq", false).await.unwrap().contains("問い合わせ"));
+    }
+
+    #[tokio::test]
+    async fn test_overview_and_faq_link_project_docs() {
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            project_docs: analyzer_core::ProjectDocs {
+                readme: Some(PathBuf::from("README.md")),
+                readme_intro: Some("決済APIのサーバーです。\n\n# 特徴".to_string()),
+                license_files: vec![PathBuf::from("LICENSE")],
+                license: Some("Apache-2.0".to_string()),
+                contributing: Some(PathBuf::from(".github/CONTRIBUTING.md")),
+                changelog: None,
+            },
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        config.project.source_url = Some("https://github.com/owner/repo/blob/main".to_string());
+        let builder = MdBookBuilder::new(config);
+
+        let overview = builder.render_page(&index, "overview", false).await.unwrap();
+        assert!(overview.contains("- **ライセンス**: Apache-2.0\n"));
+        assert!(overview.contains("## README\n\n決済APIのサーバーです。\n\n### 特徴\n\n（全文: [`README.md`](https://github.com/owner/repo/blob/main/README.md)）\n"));
+        assert!(overview.contains("- **貢献ガイド**: [`.github/CONTRIBUTING.md`](https://github.com/owner/repo/blob/main/.github/CONTRIBUTING.md)\n"));
+
+        let faq = builder.render_page(&index, "faq", false).await.unwrap();
+        assert!(faq.contains("### ライセンスは何ですか？\n\nApache-2.0です（[`LICENSE`](https://github.com/owner/repo/blob/main/LICENSE)）。\n"));
+        assert!(faq.contains("### 開発に参加するには？\n"));
+        assert!(!faq.contains("### 変更履歴はどこにありますか？"));
+
+        let plain = MdBookBuilder::new(Config::default()).render_page(&Index::default(), "faq", false).await.unwrap();
+        assert!(plain.contains("ライセンスファイルが見つかりませんでした。"));
+    }
+
+    #[tokio::test]
+    async fn test_faq_answers_from_code() {
+        let repo = PathBuf::from("/repo");
+        let file = |path: &str, imports: &[&str], is_test: bool, tests: usize| analyzer_core::FileInfo {
+            path: repo.join(path),
+            dependencies: imports.iter().map(|s| s.to_string()).collect(),
+            is_test,
+            tests,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                file("app/storage.py", &["boto3"], false, 0),
+                file("app/api.py", &["requests"], false, 2),
+                file("app/db.py", &[], false, 0),
+                file("tests/test_api.py", &[], true, 4),
+                file("tests/test_db.py", &[], true, 1),
+            ],
+            internal_dependencies: [
+                (repo.join("app/api.py"), vec![repo.join("app/db.py"), repo.join("app/storage.py")]),
+                (repo.join("app/storage.py"), vec![repo.join("app/db.py")]),
+            ]
+            .into(),
+            endpoints: vec![analyzer_core::ApiEndpoint {
+                method: "GET".to_string(),
+                path: "/items".to_string(),
+                file: repo.join("app/api.py"),
+                ..Default::default()
+            }],
+            config_files: vec![analyzer_core::ConfigFile {
+                path: PathBuf::from(".env.example"),
+                summary: "環境変数".to_string(),
+                settings: vec![analyzer_core::ConfigSetting { key: "DATABASE_URL".to_string(), ..Default::default() }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let faq = MdBookBuilder::new(Config::default()).render_page(&index, "faq", false).await.unwrap();
+        assert!(faq.contains("### テストはどこにありますか？\n\nテストファイルは主に次のディレクトリにあります。\n\n- `tests/`（2ファイル）\n\n"));
+        assert!(faq.contains("本体のファイルにもテストが含まれます（1ファイル、最も多いのは`app/api.py`の2件）。\n"));
+        assert!(faq.contains("### 設定はどこにありますか？\n\n動作やビルドを切り替える設定ファイルです。"));
+        assert!(faq.contains("- `.env.example` — 環境変数\n"));
+        assert!(faq.contains("リポジトリ内のimportは3件"));
+        assert!(faq.contains("- `app/db.py`（2ファイルからimport）\n- `app/storage.py`（1ファイルからimport）\n"));
+        assert!(faq.contains("外部からは1件のAPIエンドポイントで呼び出されます（定義: `app/api.py`）。"));
+        assert!(faq.contains("- **AWS**（クラウド、`boto3`）: `app/storage.py`\n- **HTTP API**（HTTP、`requests`）: `app/api.py`\n"));
+        assert!(faq.contains("接続先は環境変数`DATABASE_URL`で設定します（`.env.example`）。\n"));
+    }
+
+    #[tokio::test]
+    async fn test_licenses_appendix_flags_copyleft() {
+        let dependency = |name: &str, ecosystem: &str, license: Option<&str>| analyzer_core::ExternalDependency {
+            name: name.to_string(),
+            ecosystem: ecosystem.to_string(),
+            requested: Some("^1".to_string()),
+            version: Some("1.2.0".to_string()),
+            license: license.map(str::to_string),
+            copyleft: license.is_some_and(analyzer_core::is_copyleft),
+            kind: "normal".to_string(),
+            manifests: vec![PathBuf::from("web/package.json")],
+        };
+        let index = Index {
+            external_dependencies: vec![
+                dependency("serde", "cargo", Some("MIT OR Apache-2.0")),
+                dependency("gpl-lib", "npm", Some("GPL-3.0-only")),
+                dependency("left-pad", "npm", None),
+            ],
+            vendored: vec![analyzer_core::VendoredDir { path: PathBuf::from("vendor"), ..Default::default() }],
+            ..Default::default()
+        };
+        let toc = vec!["overview".to_string(), "third-party".to_string()];
+        assert_eq!(effective_toc(&index, &toc), vec!["overview", "licenses", "third-party"]);
+
+        let page = MdBookBuilder::new(Config::default()).render_page(&index, "licenses", false).await.unwrap();
+        assert!(page.contains("外部依存3件"));
+        assert!(page.contains("ライセンスを検出できなかった1件"));
+        assert!(page.contains("> - `gpl-lib`（npm）: GPL-3.0-only\n"));
+        assert!(page.contains("| `gpl-lib` | `^1` | `1.2.0` | ⚠️ GPL-3.0-only | normal | `web/package.json` |\n"));
+        assert!(page.contains("| `serde` | `^1` | `1.2.0` | MIT OR Apache-2.0 | normal |"));
+        assert!(page.contains("| `left-pad` | `^1` | `1.2.0` | 不明 |"));
+    }
+
+    #[tokio::test]
+    async fn test_architecture_warns_dependency_cycles() {
+        let repo = PathBuf::from("/repo");
+        let mut index = Index { repo_path: repo.clone(), ..Default::default() };
+        index.internal_dependencies.insert(repo.join("src/a.rs"), vec![repo.join("src/b.rs")]);
+        index.internal_dependencies.insert(repo.join("src/b.rs"), vec![repo.join("src/a.rs")]);
+        let builder = MdBookBuilder::new(Config::default());
+
+        let page = builder.render_page(&index, "architecture", true).await.unwrap();
+        assert!(page.contains("## 循環依存\n\n> ⚠️ importをたどって互いに到達できるファイルの集まり（循環依存）が1件あります。"));
+        assert!(page.contains("- **循環1**（2ファイル）: `src/a.rs` → `src/b.rs` → `src/a.rs`\n"));
+        assert!(page.contains("subgraph C0[\"循環1（2ファイル）\"]"));
+
+        let page = builder.render_page(&Index::default(), "architecture", true).await.unwrap();
+        assert!(!page.contains("循環依存"));
+    }
+
+    #[tokio::test]
+    async fn test_architecture_notes_conditional_modules() {
+        let repo = PathBuf::from("/repo");
+        let condition = |kind: &str, expr: &str| analyzer_core::BuildCondition {
+            kind: kind.to_string(),
+            expr: expr.to_string(),
+            declared_in: PathBuf::from("src/lib.rs"),
+            line: 3,
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                analyzer_core::FileInfo {
+                    path: repo.join("src/search.rs"),
+                    build_conditions: vec![condition("cargo-feature", "feature = \"search\"")],
+                    ..Default::default()
+                },
+                analyzer_core::FileInfo {
+                    path: repo.join("src/unix.rs"),
+                    build_conditions: vec![condition("cfg", "unix")],
+                    ..Default::default()
+                },
+                analyzer_core::FileInfo { path: repo.join("src/main.rs"), ..Default::default() },
+            ],
+            modules: vec![analyzer_core::ModuleInfo { path: repo.join("src/search.rs"), id: "search".to_string(), ..Default::default() }],
+            config_files: vec![analyzer_core::ConfigFile {
+                settings: vec![analyzer_core::ConfigSetting {
+                    key: "features.search".to_string(),
+                    value: "[]".to_string(),
+                    description: Some("全文検索".to_string()),
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let page = MdBookBuilder::new(Config::default()).render_page(&index, "architecture", false).await.unwrap();
+        assert!(page.contains("## 条件付きでビルド・読み込まれるモジュール\n\n"));
+        assert!(page.contains("- `search` — この機能はfeature `search`（全文検索）有効時のみ（`src/lib.rs` 3行目）\n"));
+        assert!(page.contains("- `src/unix.rs` — この機能は`cfg(unix)` の場合のみ（`src/lib.rs` 3行目）\n"));
+        assert!(!page.contains("src/main.rs` —"));
+    }
+
+    #[tokio::test]
+    async fn test_architecture_compares_design_docs_with_code() {
+        let repo = PathBuf::from("/repo");
+        let mention = |name: &str, line: usize, is_path: bool| analyzer_core::DocMention { name: name.to_string(), line, is_path, exists: false };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                analyzer_core::FileInfo { path: repo.join("src/orders/mod.rs"), name: "mod".to_string(), ..Default::default() },
+                analyzer_core::FileInfo { path: repo.join("src/payments/mod.rs"), name: "mod".to_string(), ..Default::default() },
+            ],
+            design_docs: vec![
+                analyzer_core::DesignDoc {
+                    path: PathBuf::from("ARCHITECTURE.md"),
+                    kind: "architecture".to_string(),
+                    title: "Architecture".to_string(),
+                    mentions: vec![mention("src/legacy", 4, true)],
+                    words: ["orders".to_string(), "src".to_string()].into(),
+                },
+                analyzer_core::DesignDoc {
+                    path: PathBuf::from("docs/adr/0001-use-postgres.md"),
+                    kind: "adr".to_string(),
+                    title: "Use Postgres".to_string(),
+                    mentions: vec![mention("order_repository", 3, false)],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let page = MdBookBuilder::new(Config::default()).render_page(&index, "architecture", false).await.unwrap();
+        assert!(page.contains("## ドキュメントと実装の差分\n\n`ARCHITECTURE.md`・ADR（`docs/adr/` の1件）に書かれた構成"));
+        assert!(page.contains("| `src/legacy` | `ARCHITECTURE.md` 4行目 |\n"));
+        assert!(page.contains("| `order_repository` | `docs/adr/0001-use-postgres.md` 3行目 |\n"));
+        assert!(page.contains("### コードにあってドキュメントにないもの\n\n設計ドキュメントのどこにも名前が現れない構成要素です。\n\n- `src/payments`\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_glossary_page_and_module_term_links() {
+        let repo = PathBuf::from("/repo");
+        let symbol = |kind: &str, name: &str, documentation: &str| analyzer_core::Symbol {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            start_line: 3,
+            documentation: documentation.to_string(),
+            ..Default::default()
+        };
+        let file = |path: &str, symbols: Vec<analyzer_core::Symbol>| analyzer_core::FileInfo {
+            path: repo.join(path),
+            name: Path::new(path).file_stem().unwrap().to_string_lossy().to_string(),
+            symbols,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                file("src/order.rs", vec![symbol("struct", "Order", "顧客の注文。")]),
+                file("src/billing.rs", vec![symbol("function", "bill_order", "")]),
+            ],
+            modules: vec![
+                analyzer_core::ModuleInfo { path: repo.join("src/order.rs"), id: "order".to_string(), ..Default::default() },
+                analyzer_core::ModuleInfo { path: repo.join("src/billing.rs"), id: "billing".to_string(), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        assert!(effective_toc(&index, &["overview".to_string(), "faq".to_string()]).ends_with(&["glossary".to_string(), "faq".to_string()]));
+
+        let builder = MdBookBuilder::new(Config::default());
+        let page = builder.render_page(&index, "glossary", false).await.unwrap();
+        assert!(page.starts_with("# 用語集\n\n"));
+        assert!(page.contains("## Order\n\n顧客の注文。\n\n"));
+        assert!(page.contains("- 関連する名前: `Order`・`bill_order`・`order`\n"));
+        assert!(page.contains("- 使われているモジュール: `billing`・`order`\n"));
+        assert!(page.contains("- 定義: `src/order.rs` 3行目\n"));
+
+        let page = builder.render_page(&index, "src/billing.rs", false).await.unwrap();
+        assert!(page.contains("  \n**用語**: [Order](glossary.md#order)\n"));
+    }
+
+    #[tokio::test]
+    async fn test_architecture_lists_modules_by_layer() {
+        let repo = PathBuf::from("/repo");
+        let mut index = Index { repo_path: repo.clone(), ..Default::default() };
+        for path in ["src/routes.ts", "src/store.ts", "src/main.ts"] {
+            index.modules.push(analyzer_core::ModuleInfo { path: repo.join(path), id: path.to_string(), ..Default::default() });
+        }
+        index.internal_dependencies.insert(repo.join("src/store.ts"), vec![repo.join("src/routes.ts")]);
+        let mut config = Config::default();
+        config.project.language = "en".to_string();
+
+        let page = MdBookBuilder::new(config).render_page(&index, "architecture", true).await.unwrap();
+        assert!(page.contains("## Layers\n\nModules are classified"), "{}", page);
+        assert!(page.contains("subgraph L1[\"API・ハンドラー（1）\"]"), "{}", page);
+        assert!(page.contains("### API and handlers\n\n- `src/routes.ts` — name `routes`\n"), "{}", page);
+        assert!(page.contains("### Persistence\n\n- `src/store.ts` — name `store`\n"), "{}", page);
+        assert!(page.contains("### Other\n\n- `src/main.ts`\n"), "{}", page);
+        assert!(page.contains("### Layer violations\n\n> ⚠️ There are 1 imports"), "{}", page);
+        assert!(page.contains("- `src/store.ts`（Persistence） → `src/routes.ts`（API and handlers）\n"), "{}", page);
+    }
+
+    #[tokio::test]
+    async fn test_config_page_explains_settings() {
+        let setting = |key: &str, value: &str, description: Option<&str>| analyzer_core::ConfigSetting {
+            key: key.to_string(),
+            value: value.to_string(),
+            description: description.map(str::to_string),
+        };
+        let index = Index {
+            config_files: vec![analyzer_core::ConfigFile {
+                path: PathBuf::from("web/tsconfig.json"),
+                kind: "tsconfig".to_string(),
+                summary: "TypeScriptのコンパイラーの設定です。".to_string(),
+                settings: vec![
+                    setting("compilerOptions.strict", "true", Some("厳格な型チェックをまとめて有効にする")),
+                    setting("compilerOptions.plugins", "", None),
+                ],
+            }],
+            ..Default::default()
+        };
+        let toc = vec!["overview".to_string(), "faq".to_string()];
+        assert_eq!(effective_toc(&index, &toc), vec!["overview", "config", "faq"]);
+
+        let page = MdBookBuilder::new(Config::default()).render_page(&index, "config", false).await.unwrap();
+        assert!(page.contains("## web/tsconfig.json\n\nTypeScriptのコンパイラーの設定です。（`web/tsconfig.json`）\n"));
+        assert!(page.contains("| `compilerOptions.strict` | `true` | 厳格な型チェックをまとめて有効にする |\n"));
+        assert!(page.contains("| `compilerOptions.plugins` | （空） |  |\n"));
+    }
+
+    #[tokio::test]
+    async fn test_unused_page_lists_orphans_and_symbols() {
+        let repo = PathBuf::from("/repo");
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                analyzer_core::FileInfo {
+                    path: repo.join("src/store.rs"),
+                    language: "rs".to_string(),
+                    content: Some("pub fn save() {\n}\n".to_string()),
+                    symbols: vec![analyzer_core::Symbol {
+                        kind: "function".to_string(),
+                        name: "save".to_string(),
+                        start_line: 1,
+                        end_line: 2,
+                        visibility: "public".to_string(),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        config.project.source_url = Some("https://github.com/acme/app/blob/main".to_string());
+
+        let page = MdBookBuilder::new(config).render_page(&index, "unused", false).await.unwrap();
+        assert!(page.starts_with("# 未使用コード\n"));
+        assert!(page.contains("importされていないモジュールが1件"));
+        assert!(page.contains("- [`src/store.rs`](https://github.com/acme/app/blob/main/src/store.rs)\n"));
+        assert!(page.contains("| `save` | function | [`src/store.rs`](https://github.com/acme/app/blob/main/src/store.rs)（1行目） |\n"));
+
+        let page = MdBookBuilder::new(Config::default()).render_page(&Index::default(), "unused", false).await.unwrap();
+        assert!(page.contains("未使用コードの候補は見つかりませんでした。"));
+    }
+
+    #[tokio::test]
+    async fn test_debt_page_and_faq_list_todo_comments() {
+        let repo = PathBuf::from("/repo");
+        let todo = |line: usize, marker: &str, text: &str, author: Option<&str>| analyzer_core::TodoComment {
+            line,
+            marker: marker.to_string(),
+            text: text.to_string(),
+            author: author.map(str::to_string),
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                analyzer_core::FileInfo {
+                    path: repo.join("src/api.rs"),
+                    todos: vec![todo(3, "TODO", "paginate | sort", Some("alice")), todo(9, "TODO", "", Some("bob"))],
+                    ..Default::default()
+                },
+                analyzer_core::FileInfo {
+                    path: repo.join("src/db.rs"),
+                    todos: vec![todo(12, "FIXME", "leaks connections", Some("alice"))],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let builder = MdBookBuilder::new(Config::default());
+        let page = builder.render_page(&index, "debt", false).await.unwrap();
+        assert!(page.starts_with("# 技術的負債\n"));
+        assert!(page.contains("| FIXME | 不具合・要修正 | 1 |\n| TODO | 未実装・今後の作業 | 2 |\n"));
+        assert!(page.contains("| alice | 2 |\n| bob | 1 |\n"));
+        let rows: Vec<&str> = page.lines().filter(|l| l.starts_with("| FIXME | leaks") || l.starts_with("| TODO | pag")).collect();
+        assert_eq!(rows, vec!["| FIXME | leaks connections | `src/db.rs`（12行目） | alice |", "| TODO | paginate \\| sort | `src/api.rs`（3行目） | alice |"]);
+
+        let faq = builder.render_page(&index, "faq", false).await.unwrap();
+        assert!(faq.contains("コメントに作業メモが3件（FIXME 1件、TODO 2件）残っています。最も多いのは`src/api.rs`（2件）です。"));
+        let page = builder.render_page(&Index::default(), "debt", false).await.unwrap();
+        assert!(page.contains("TODO・FIXME・HACK・XXXのコメントは見つかりませんでした。"));
+    }
+
+    #[tokio::test]
+    async fn test_timeline_page_renders_events() {
+        let event = |time: i64, kind: TimelineKind, title: &str, files: usize| analyzer_core::TimelineEvent {
+            time,
+            kind,
+            title: title.to_string(),
+            commit: "abc1234".to_string(),
+            files,
+        };
+        let index = Index {
+            timeline: vec![
+                event(1_700_000_000, TimelineKind::Release, "v1.0.0", 0),
+                event(1_710_000_000, TimelineKind::Refactor, "refactor: split | merge #12", 42),
+            ],
+            ..Default::default()
+        };
+
+        let builder = MdBookBuilder::new(Config::default());
+        let page = builder.render_page(&index, "timeline", true).await.unwrap();
+        assert!(page.starts_with("# プロジェクトの歩み\n"));
+        assert!(page.contains("timeline\n    section 2023\n        2023-11-14 : リリース v1.0.0\n    section 2024\n        2024-03-09 : 大規模な変更 refactor split | merge 12\n"));
+        // 表は新しい順
+        let rows: Vec<&str> = page.lines().filter(|l| l.starts_with("| 20")).collect();
+        assert_eq!(
+            rows,
+            vec![
+                "| 2024-03-09 | 大規模な変更 | refactor: split \\| merge #12 | `abc1234` | 42 |",
+                "| 2023-11-14 | リリース | v1.0.0 | `abc1234` | - |",
+            ]
+        );
+        assert!(!builder.render_page(&index, "timeline", false).await.unwrap().contains("```mermaid"));
+        let page = builder.render_page(&Index::default(), "timeline", false).await.unwrap();
+        assert!(page.contains("プロジェクトの歩みを示せません"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_wiki_lists_pages_without_writing() {
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            vendored: vec![analyzer_core::VendoredDir {
+                path: PathBuf::from("vendor"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let builder = MdBookBuilder::new(Config::default());
+        let toc = vec!["overview".to_string(), "modules".to_string()];
+
+        let files = builder.plan_wiki(&index, true, &toc).await.unwrap();
+        let paths: Vec<String> = files.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "book.toml",
+                "src/SUMMARY.md",
+                "src/overview.md",
+                "src/modules.md",
+                "src/third-party.md",
+                "src/badges/docs-coverage.svg",
+                "src/badges/modules-documented.svg",
+                "src/badges/docs-generated.svg",
+                "book/"
+            ]
+        );
+        assert_eq!(files[1].estimated_bytes, Some(builder.summary_markdown(&index, &effective_toc(&index, &toc)).len()));
+        assert_eq!(files[3].generator, "heuristic");
+        assert_eq!(files[5].generator, "static");
+        assert_eq!(files[8].estimated_bytes, None);
+    }
+
+    #[tokio::test]
+    async fn test_plan_wiki_follows_layout() {
+        let module = |path: &str, id: &str| analyzer_core::ModuleInfo {
+            path: PathBuf::from(path),
+            id: id.to_string(),
+            name: "lib".to_string(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            modules: vec![
+                module("/repo/crates/config/src/lib.rs", "config:src/lib.rs"),
+                module("/repo/crates/slides/src/lib.rs", "slides:src/lib.rs"),
+                module("/repo/main.rs", "main.rs"),
+            ],
+            readmes: vec![analyzer_core::PackageReadme {
+                dir: PathBuf::from("/repo/crates/config"),
+                content: "# config".to_string(),
+            }],
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        config.site.layout.src_dir = PathBuf::from("pages");
+        config.site.layout.build_dir = PathBuf::from("public");
+        config.site.layout.files.insert("overview".to_string(), "index.md".to_string());
+        config.site.layout.files.insert("modules".to_string(), "reference/modules.md".to_string());
+        config.site.layout.package_dirs = true;
+        config.site.badges = false;
+        let builder = MdBookBuilder::new(config);
+        let toc = vec!["overview".to_string(), "modules".to_string()];
+
+        let files = builder.plan_wiki(&index, false, &toc).await.unwrap();
+        let paths: Vec<String> = files.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "book.toml",
+                "pages/SUMMARY.md",
+                "pages/index.md",
+                "pages/reference/modules/_root/main-rs.md",
+                "pages/reference/modules/_root/index.md",
+                "pages/reference/modules/crates/slides-src-lib-rs.md",
+                "pages/reference/modules/crates/index.md",
+                "pages/reference/modules/crates/config/src-lib-rs.md",
+                "pages/reference/modules/crates/config/index.md",
+                "pages/reference/modules.md",
+                "public/",
+            ]
+        );
+        let book_toml = builder.book_toml();
+        assert!(book_toml.contains("src = \"pages\""));
+        assert!(book_toml.contains("build-dir = \"public\""));
+
+        let summary = builder.summary_markdown(&index, &toc);
+        assert!(summary.contains("- [概要](index.md)\n"));
+        assert!(summary.contains("  - [crates/config](reference/modules/crates/config/index.md)\n"));
+        assert!(summary.contains("    - [config:src/lib.rs](reference/modules/crates/config/src-lib-rs.md)\n"));
+
+        let modules =
+            MdBookBuilder::generate_module_pages(&index, &builder.config, false, &Templates::builtin(), &builder.diagnostics, &builder.executor, None)
+                .await;
+        assert!(modules.index.contains("### [crates/config](modules/crates/config/index.md)"));
+        assert!(modules.index.contains("- [config:src/lib.rs](modules/crates/config/src-lib-rs.md)"));
+        assert!(modules.index.contains("- [slides:src/lib.rs](modules/crates/slides-src-lib-rs.md)"));
+        let (_, package_page) = modules.packages.iter().find(|(file, _)| file == "reference/modules/crates/config/index.md").unwrap();
+        assert!(package_page.starts_with("# crates/config\n\n"));
+        assert!(package_page.contains("- [config:src/lib.rs](src-lib-rs.md)"));
+    }
+
+    #[tokio::test]
+    async fn test_build_wiki_resumes_from_progress() {
+        let repo = tempfile::tempdir().unwrap();
+        let module_path = repo.path().join("src/store.rs");
+        let index = Index {
+            repo_path: repo.path().to_path_buf(),
+            files: vec![analyzer_core::FileInfo {
+                path: module_path.clone(),
+                language: "rs".to_string(),
+                content: Some("pub fn get() {}\n".to_string()),
+                ..Default::default()
+            }],
+            modules: vec![analyzer_core::ModuleInfo {
+                path: module_path,
+                id: "src/store.rs".to_string(),
+                language: "rs".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let out_dir = repo.path().join("wiki");
+        let out_dir = out_dir.to_string_lossy();
+        let toc = vec!["overview".to_string(), "modules".to_string(), "faq".to_string()];
+        let build = |resume: bool, toc: Vec<String>| {
+            let index = index.clone();
+            let out_dir = out_dir.to_string();
+            async move {
+                MdBookBuilder::new(Config::default())
+                    .with_tools(Tools::new(summarizer::FakeTools::new()))
+                    .with_resume(resume)
+                    .build_wiki(&index, &out_dir, false, &toc)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let first = build(false, toc.clone()).await;
+        assert_eq!(first.resumed, 0);
+        let src_dir = repo.path().join("wiki/src");
+        let faq = fs::read_to_string(src_dir.join("faq.md")).unwrap();
+
+        // 途中で終了したページ（消えたページ）だけを作り直し、残りは再利用する
+        fs::remove_file(src_dir.join("faq.md")).unwrap();
+        let resumed = build(true, toc.clone()).await;
+        assert_eq!((resumed.resumed, resumed.resumed_modules), (1, 1), "概要のページとモジュールの解説を再利用");
+        assert_eq!(resumed.pages, first.pages);
+        assert_eq!(fs::read_to_string(src_dir.join("faq.md")).unwrap(), faq);
+
+        // 入力（目次）が変わった場合は再利用しない
+        let changed = build(true, vec!["overview".to_string(), "faq".to_string()]).await;
+        assert_eq!((changed.resumed, changed.resumed_modules), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_module_pages_grouped_by_workspace_package() {
+        let module = |path: &str, id: &str| analyzer_core::ModuleInfo {
+            path: PathBuf::from(path),
+            id: id.to_string(),
+            name: "lib".to_string(),
+            ..Default::default()
+        };
+        let file = |path: &str| analyzer_core::FileInfo {
+            path: PathBuf::from(path),
+            language: "rust".to_string(),
+            ..Default::default()
+        };
+        let package = |name: &str, path: &str| analyzer_core::PackageInfo {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            ecosystem: "cargo".to_string(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("/repo/crates/config/src/lib.rs"), file("/repo/apps/cli/src/main.rs")],
+            modules: vec![
+                module("/repo/crates/config/src/lib.rs", "config:src/lib.rs"),
+                module("/repo/apps/cli/src/main.rs", "cli:src/main.rs"),
+            ],
+            packages: vec![package("config", "crates/config"), package("cli", "apps/cli")],
+            ..Default::default()
+        };
+        let mut config = Config::default();
+        config.site.badges = false;
+        let builder = MdBookBuilder::new(config);
+
+        // ワークスペースのパッケージが複数あれば、package-dirsなしでもパッケージごとに分ける
+        let summary = builder.summary_markdown(&index, &["modules".to_string()]);
+        assert!(summary.contains("  - [apps/cli](modules/apps/cli/index.md)\n    - [cli:src/main.rs](modules/apps/cli/src-main-rs.md)\n"));
+
+        let modules =
+            MdBookBuilder::generate_module_pages(&index, &builder.config, true, &Templates::builtin(), &builder.diagnostics, &builder.executor, None)
+                .await;
+        let (_, package_page) = modules.packages.iter().find(|(file, _)| file == "modules/crates/config/index.md").unwrap();
+        assert!(package_page.contains("```mermaid"));
+        assert!(package_page.contains("- [config:src/lib.rs](src-lib-rs.md)"));
+        assert!(modules.packages.iter().any(|(file, _)| file == "modules/crates/config/src-lib-rs.md"));
+    }
+}
@@ -0,0 +1,143 @@
+/*!
+ * Wikiの言語
+ *
+ * `project.language` に合わせて、Wikiの章の名前・ページの見出しと定型文を言語ごとのカタログから引く
+ *
+ * 主な仕様:
+ * - 言語（`Locale`）は要約・スライドと共有し、このモジュールはWikiの文言のカタログのみを持つ
+ * - 章の名前はセクション名（overviewなど）をキーとして引く
+ * - 文言はキーで引き、`{}` は引数で順に置き換える（カタログにないキーはキーをそのまま返す）
+ *
+ * 制限事項:
+ * - メトリクス・テスト・依存ライセンスなどの章の表と説明文は日本語のまま
+ */
+
+use std::fmt::Display;
+
+use summarizer::{Catalog, Locale};
+
+/// 文言のカタログ（キー、日本語、英語）
+const CATALOG: &Catalog = &[
+    ("overview", "概要", "Overview"),
+    ("architecture", "アーキテクチャ", "Architecture"),
+    ("packages", "パッケージ", "Packages"),
+    ("metrics", "メトリクス", "Metrics"),
+    ("hotspots", "ホットスポット", "Hotspots"),
+    ("timeline", "プロジェクトの歩み", "Project timeline"),
+    ("tests", "テスト", "Tests"),
+    ("unused", "未使用コード", "Unused code"),
+    ("debt", "技術的負債", "Technical debt"),
+    ("api", "API", "API"),
+    ("modules", "モジュール", "Modules"),
+    ("flows", "フロー", "Flows"),
+    ("deploy", "デプロイ", "Deployment"),
+    ("config", "設定ファイル", "Configuration files"),
+    ("glossary", "用語集", "Glossary"),
+    ("faq", "FAQ", "FAQ"),
+    ("licenses", "依存ライセンス", "Dependency licenses"),
+    ("third-party", "サードパーティ", "Third-party code"),
+    ("section.placeholder", "セクションの内容", "Section content"),
+    ("overview.intro", "このページでは、リポジトリ全体の構成を図で示します。", "This page shows the overall structure of the repository."),
+    ("overview.stats", "統計情報", "Statistics"),
+    ("overview.files", "- **ファイル数**: {}個", "- **Files**: {}"),
+    ("overview.languages", "- **使用言語**: {}", "- **Languages**: {}"),
+    ("overview.modules", "- **モジュール数**: {}個", "- **Modules**: {}"),
+    ("overview.license", "- **ライセンス**: {}", "- **License**: {}"),
+    ("overview.diagram", "全体構成図", "System overview"),
+    ("overview.diagram-repo", "リポジトリ全体", "Repository"),
+    ("overview.diagram-files", "ファイル", "Files"),
+    ("overview.diagram-modules", "モジュール", "Modules"),
+    ("overview.diagram-dependencies", "依存関係", "Dependencies"),
+    ("overview.diagram-file-count", "{}ファイル", "{} files"),
+    ("overview.diagram-module-count", "{}モジュール", "{} modules"),
+    ("layers.title", "レイヤー構成", "Layers"),
+    (
+        "layers.intro",
+        "モジュールを名前・import・内部依存から層に分類しました（推定のため、実際の設計と異なる場合があります）。",
+        "Modules are classified into layers from their names, imports and internal dependencies (an estimate that may differ from the actual design).",
+    ),
+    ("layers.ui", "UI", "UI"),
+    ("layers.api", "API・ハンドラー", "API and handlers"),
+    ("layers.domain", "ドメイン・サービス", "Domain and services"),
+    ("layers.persistence", "永続化", "Persistence"),
+    ("layers.utils", "ユーティリティ", "Utilities"),
+    ("layers.other", "その他", "Other"),
+    ("layers.basis-endpoint", "APIエンドポイントを定義", "defines API endpoints"),
+    ("layers.basis-name", "名前 `{}`", "name `{}`"),
+    ("layers.basis-library", "`{}` をimport", "imports `{}`"),
+    ("layers.basis-dependencies", "内部依存から推定", "inferred from internal dependencies"),
+    ("layers.basis-llm", "ローカルLLMで分類", "classified by the local LLM"),
+    ("layers.more", "ほか{}件", "{} more"),
+    ("layers.violations", "層の逆転", "Layer violations"),
+    ("layers.violations-intro", "下位の層から上位の層へのimportが{}件あります。", "There are {} imports from a lower layer to a higher layer."),
+    ("docs.full-readme", "（全文: {}）", "(Full text: {})"),
+    ("docs.license", "ライセンス", "License"),
+    ("docs.contributing", "貢献ガイド", "Contributing guide"),
+    ("docs.changelog", "変更履歴", "Changelog"),
+    ("docs.title", "プロジェクトのドキュメント", "Project documents"),
+    ("modules.intro", "このセクションでは、各モジュールについて詳しく説明します。", "This section describes each module in detail."),
+    ("modules.selected", "全{}モジュールのうち、重要度の高い{}モジュールを掲載しています。", "Showing the {1} most important of {0} modules."),
+    ("modules.list", "モジュール一覧", "Module list"),
+    ("module.file", "ファイル", "File"),
+    ("module.language", "言語", "Language"),
+    ("module.terms", "用語", "Terms"),
+    ("module.readme", "パッケージのREADME", "Package README"),
+    ("module.readme-source", "`{}` より", "From `{}`"),
+    ("module.types", "型", "Types"),
+    ("module.methods", "主要な関数・メソッド", "Key functions and methods"),
+    (
+        "module.methods-intro",
+        "このモジュールには以下の関数やメソッドが含まれています。各メソッドについて、日本語で詳しく解説します。",
+        "This module contains the following functions and methods, each explained in detail below.",
+    ),
+    ("module.no-doc", "`{}`関数の実装です。", "Implementation of `{}`."),
+    ("module.api-parameter", "引数", "Parameter"),
+    ("module.api-type", "型", "Type"),
+    ("module.api-returns", "戻り値", "Returns"),
+    ("module.api-async", "非同期（async）", "Asynchronous (async)"),
+    ("module.api-visibility", "可視性", "Visibility"),
+    ("module.behavior", "コードの動作", "How the code works"),
+    ("module.behavior-intro", "この関数の実装を見てみましょう。", "Here is the implementation of this function."),
+    ("module.omitted", "// ... (省略) ...", "// ... (omitted) ..."),
+    ("module.behavior-list", "このコードは以下の処理を行います：", "This code does the following:"),
+    ("flows.sequence", "シーケンス図", "Sequence diagram"),
+    ("flows.call-graph", "コールグラフ", "Call graph"),
+    ("deploy.diagram", "デプロイメント構成図", "Deployment diagram"),
+    ("faq.title", "よくある質問", "Frequently asked questions"),
+    ("faq.what", "このリポジトリは何ですか？", "What is this repository?"),
+    ("faq.what-answer", "{}ファイル、{}言語、{}モジュールを含むリポジトリです。", "A repository with {} files, {} languages and {} modules."),
+];
+
+/// Wikiの文言
+pub(crate) trait WikiText {
+    /// 文言を取得（カタログにない場合はキー）
+    fn text(self, key: &str) -> &str;
+    /// 引数を `{}` に順に埋め込んだ文言を取得
+    fn format(self, key: &str, args: &[&dyn Display]) -> String;
+}
+
+impl WikiText for Locale {
+    fn text(self, key: &str) -> &str {
+        self.lookup(CATALOG, key)
+    }
+
+    fn format(self, key: &str, args: &[&dyn Display]) -> String {
+        self.lookup_format(CATALOG, key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_covers_sections() {
+        for section in crate::SECTIONS.iter().chain(["modules"].iter()) {
+            assert_ne!(Locale::En.text(section), *section, "{}", section);
+        }
+        for (key, ja, en) in CATALOG {
+            assert!(!ja.is_empty() && !en.is_empty(), "{}", key);
+        }
+        assert_eq!(Locale::En.format("modules.selected", &[&40, &20]), "Showing the 20 most important of 40 modules.");
+    }
+}
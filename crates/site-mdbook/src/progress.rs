@@ -0,0 +1,250 @@
+/*!
+ * Wikiの生成の途中経過
+ *
+ * 生成を終えたページを出力ディレクトリに記録し、中断したビルドを `wiki --resume` で続きから再開する
+ * - LLMの停止・メモリ不足・Ctrl-Cなどで途中で終了しても、生成済みのページ・モジュールの解説を作り直さない
+ *
+ * 主な仕様:
+ * - 記録は `<出力ディレクトリ>/.wiki-progress/`（`state.json` と、モジュールごとの解説 `modules/<ハッシュ>.md`）
+ * - セクションのページ・モジュールの解説を書き込むたびに記録を更新する（一時ファイルから置き換え）
+ * - インデックス（IDと警告を除く）・設定・図の有無・目次のハッシュが前回と同じ場合のみ再利用する
+ * - セクションのページは、記録した内容のハッシュとファイルが一致する場合のみ生成済みとみなす（編集・削除したページは作り直す）
+ * - 再開しない通常のビルドは記録を消して最初から記録し直す
+ *
+ * 制限事項:
+ * - `templates.dir` のテンプレートの内容の変更は検出しない（設定のパスのみ比較）
+ * - モジュール一覧・パッケージの目次ページ・バッジ・mdBookのビルドは毎回作り直す
+ */
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use analyzer_core::Index;
+use config::Config;
+
+/// 途中経過を記録するディレクトリ（出力ディレクトリからの相対パス）
+const PROGRESS_DIR: &str = ".wiki-progress";
+
+/// 記録した途中経過
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    /// 生成の入力のハッシュ（`fingerprint`）
+    fingerprint: String,
+    /// 生成を終えたセクションのページ（ソースディレクトリからの相対パス → 内容のハッシュ）
+    #[serde(default)]
+    pages: BTreeMap<String, String>,
+    /// 解説を生成したモジュールのパス → 解説のファイル名（`modules/` 配下）
+    #[serde(default)]
+    modules: BTreeMap<PathBuf, String>,
+}
+
+/// Wikiの生成の途中経過（並列に生成するセクション・モジュールと共有）
+#[derive(Debug, Clone)]
+pub(crate) struct Progress {
+    dir: PathBuf,
+    state: Arc<Mutex<State>>,
+}
+
+impl Progress {
+    /// 途中経過の記録を始める
+    ///
+    /// # 引数
+    /// * `out_path` - Wikiの出力ディレクトリ
+    /// * `fingerprint` - 生成の入力のハッシュ
+    /// * `resume` - 前回の記録を再利用するか（falseの場合・入力が変わった場合は記録を消す）
+    ///
+    /// # 戻り値
+    /// * `Result<Self>` - 途中経過、またはエラー
+    pub fn start(out_path: &Path, fingerprint: &str, resume: bool) -> Result<Self> {
+        let dir = out_path.join(PROGRESS_DIR);
+        let previous = fs::read_to_string(dir.join("state.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<State>(&json).ok())
+            .filter(|state| resume && state.fingerprint == fingerprint);
+        let state = match previous {
+            Some(state) => state,
+            None => {
+                if dir.exists() {
+                    fs::remove_dir_all(&dir).with_context(|| format!("途中経過を削除できません: {:?}", dir))?;
+                }
+                State { fingerprint: fingerprint.to_string(), ..Default::default() }
+            }
+        };
+        let progress = Self { dir, state: Arc::new(Mutex::new(state)) };
+        progress.save(&progress.state.lock().unwrap())?;
+        Ok(progress)
+    }
+
+    /// セクションのページが生成済みか（記録した内容とファイルが一致する場合のみ）
+    ///
+    /// # 引数
+    /// * `src_dir` - Wikiのソースディレクトリ
+    /// * `file` - ページのファイル（ソースディレクトリからの相対パス）
+    ///
+    /// # 戻り値
+    /// * `bool` - 生成済みの場合はtrue
+    pub fn page_done(&self, src_dir: &Path, file: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        let Some(hash) = state.pages.get(file) else {
+            return false;
+        };
+        fs::read(src_dir.join(file)).is_ok_and(|content| content_hash(&content) == *hash)
+    }
+
+    /// セクションのページを生成済みとして記録
+    ///
+    /// # 引数
+    /// * `src_dir` - Wikiのソースディレクトリ
+    /// * `file` - 書き込んだページのファイル（ソースディレクトリからの相対パス）
+    ///
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn record_page(&self, src_dir: &Path, file: &str) -> Result<()> {
+        let path = src_dir.join(file);
+        let content = fs::read(&path).with_context(|| format!("ページを読み込めません: {:?}", path))?;
+        let mut state = self.state.lock().unwrap();
+        state.pages.insert(file.to_string(), content_hash(&content));
+        self.save(&state)
+    }
+
+    /// 生成済みのモジュールの解説
+    ///
+    /// # 引数
+    /// * `module` - モジュールのパス
+    ///
+    /// # 戻り値
+    /// * `Option<String>` - 解説（生成していない・読み込めない場合はNone）
+    pub fn module(&self, module: &Path) -> Option<String> {
+        let file = self.state.lock().unwrap().modules.get(module).cloned()?;
+        fs::read_to_string(self.dir.join("modules").join(file)).ok()
+    }
+
+    /// モジュールの解説を記録
+    ///
+    /// # 引数
+    /// * `module` - モジュールのパス
+    /// * `content` - 生成した解説
+    ///
+    /// # 戻り値
+    /// * `Result<()>` - 成功、またはエラー
+    pub fn record_module(&self, module: &Path, content: &str) -> Result<()> {
+        let file = format!("{}.md", &content_hash(module.to_string_lossy().as_bytes())[..16]);
+        write_atomic(&self.dir.join("modules").join(&file), content.as_bytes())?;
+        let mut state = self.state.lock().unwrap();
+        state.modules.insert(module.to_path_buf(), file);
+        self.save(&state)
+    }
+
+    /// 記録を書き込む
+    fn save(&self, state: &State) -> Result<()> {
+        write_atomic(&self.dir.join("state.json"), serde_json::to_string_pretty(state)?.as_bytes())
+    }
+}
+
+/// 生成の入力のハッシュ（同じ入力から生成したページのみ再利用する）
+///
+/// # 引数
+/// * `index` - インデックス（解析ごとに変わるIDと警告は含めない）
+/// * `config` - 設定
+/// * `with_diagrams` - 図を含めるか
+/// * `toc` - 目次セクションのリスト
+///
+/// # 戻り値
+/// * `String` - SHA-256の16進文字列
+pub(crate) fn fingerprint(index: &Index, config: &Config, with_diagrams: bool, toc: &[String]) -> String {
+    // serde_jsonのValueはキーの順に並ぶため、HashMapを含んでも同じ入力は同じ文字列になる
+    let mut index = serde_json::to_value(index).unwrap_or_default();
+    if let Some(fields) = index.as_object_mut() {
+        fields.remove("id");
+        fields.remove("diagnostics");
+    }
+    let inputs = serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "index": index,
+        "config": serde_json::to_value(config).unwrap_or_default(),
+        "with_diagrams": with_diagrams,
+        "toc": toc,
+    });
+    content_hash(inputs.to_string().as_bytes())
+}
+
+/// 内容のハッシュ（SHA-256の16進文字列）
+fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 一時ファイルに書き込んでから置き換える（書き込み中に終了しても壊れた記録を残さない）
+fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content).with_context(|| format!("途中経過を書き込めません: {:?}", tmp))?;
+    fs::rename(&tmp, path).with_context(|| format!("途中経過を書き込めません: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_resumes_only_same_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let src_dir = dir.path().join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("overview.md"), "# 概要\n").unwrap();
+
+        let progress = Progress::start(dir.path(), "a", false).unwrap();
+        progress.record_page(&src_dir, "overview.md").unwrap();
+        progress.record_module(Path::new("src/store.rs"), "## store\n").unwrap();
+
+        // 同じ入力で再開すると、生成済みのページ・モジュールの解説を再利用する
+        let resumed = Progress::start(dir.path(), "a", true).unwrap();
+        assert!(resumed.page_done(&src_dir, "overview.md"));
+        assert!(!resumed.page_done(&src_dir, "faq.md"));
+        assert_eq!(resumed.module(Path::new("src/store.rs")).as_deref(), Some("## store\n"));
+
+        // 編集したページは作り直す
+        fs::write(src_dir.join("overview.md"), "# 編集\n").unwrap();
+        assert!(!resumed.page_done(&src_dir, "overview.md"));
+
+        // 入力が変わった場合・再開しない場合は記録を消す
+        let changed = Progress::start(dir.path(), "b", true).unwrap();
+        assert!(changed.module(Path::new("src/store.rs")).is_none());
+        changed.record_module(Path::new("src/store.rs"), "## store\n").unwrap();
+        let fresh = Progress::start(dir.path(), "b", false).unwrap();
+        assert!(fresh.module(Path::new("src/store.rs")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fingerprint_stable_across_analyses() {
+        let repo = tempfile::tempdir().unwrap();
+        for (file, content) in [
+            ("src/lib.rs", "pub fn run() {}\n"),
+            ("scripts/tool.py", "def main():\n    pass\n"),
+            ("web/app.ts", "export function start() {}\n"),
+            ("cmd/main.go", "package main\n\nfunc main() {}\n"),
+        ] {
+            let path = repo.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let config = Config::default();
+        let toc = vec!["overview".to_string()];
+
+        // 複数の言語を含むリポジトリでも、同じ内容を解析し直したインデックスは同じハッシュになる
+        let mut fingerprints = Vec::new();
+        for _ in 0..4 {
+            let index = analyzer_core::Analyzer::new(config.clone()).analyze_repo(repo.path(), &config).await.unwrap();
+            assert_eq!(index.languages.len(), 4);
+            fingerprints.push(fingerprint(&index, &config, false, &toc));
+        }
+        assert!(fingerprints.windows(2).all(|w| w[0] == w[1]), "{:?}", fingerprints);
+    }
+}
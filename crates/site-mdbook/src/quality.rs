@@ -0,0 +1,176 @@
+/*!
+ * 生成ページの品質スコア
+ *
+ * 生成したWikiページを静的な指標で採点する
+ * - 情報密度: 見出し・記法・定型文を除いた実質的な内容の割合
+ * - 定型文率: 定型文または重複した行の割合
+ * - 図の有無: Mermaid図の数
+ * - 引用数: 脚注参照（`[^label]`）の数
+ *
+ * 主な仕様:
+ * - 定型文はWiki・要約のカタログの文言から日本語・英語の両方を集める（`{}` を含む文言は最も長い固定部分で照合）
+ * - スコアは0〜100（情報密度40点、定型文の少なさ30点、図15点、引用15点）
+ * - LLMモードの有効化などで出力が改善したかを比較する用途を想定
+ *
+ * 制限事項:
+ * - 内容の正しさは評価しない（あくまで形式的な指標）
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use summarizer::Locale;
+
+use crate::locale::WikiText;
+
+/// 定型文とみなすWikiの文言（カタログのキー）
+const WIKI_BOILERPLATE_KEYS: &[&str] =
+    &["section.placeholder", "module.methods-intro", "module.no-doc", "module.behavior-intro", "module.behavior-list"];
+/// 定型文とみなす要約の文言（カタログのキー）
+const SUMMARY_BOILERPLATE_KEYS: &[&str] = &["notes.none", "purpose.unknown"];
+/// カタログにない定型文（日本語のみで推測する文言）
+const EXTRA_BOILERPLATE_PHRASES: &[&str] = &["何らかの処理を実行します。"];
+
+/// 定型文とみなすフレーズ（日本語・英語）
+static BOILERPLATE_PHRASES: LazyLock<Vec<&'static str>> = LazyLock::new(|| {
+    let mut phrases: Vec<&'static str> = EXTRA_BOILERPLATE_PHRASES.to_vec();
+    for locale in [Locale::Ja, Locale::En] {
+        phrases.extend(WIKI_BOILERPLATE_KEYS.iter().map(|key| fixed_part(locale.text(key))));
+        phrases.extend(SUMMARY_BOILERPLATE_KEYS.iter().map(|key| fixed_part(locale.summary_text(key))));
+    }
+    phrases
+});
+
+/// 引用数の満点とする件数
+const FULL_CITATIONS: usize = 5;
+
+/// ページの品質スコア
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PageQuality {
+    /// セクション名
+    pub section: String,
+    /// 総合スコア（0〜100）
+    pub score: f64,
+    /// 情報密度（0〜1）
+    pub information_density: f64,
+    /// 定型文率（0〜1）
+    pub boilerplate_ratio: f64,
+    /// Mermaid図の数
+    pub diagrams: usize,
+    /// 脚注参照の数
+    pub citations: usize,
+}
+
+/// ページを採点
+///
+/// # 引数
+/// * `section` - セクション名
+/// * `markdown` - ページのMarkdown
+///
+/// # 戻り値
+/// * `PageQuality` - 品質スコア
+pub fn score_page(section: &str, markdown: &str) -> PageQuality {
+    let mut seen = HashSet::new();
+    let mut in_code = false;
+    let mut text_lines = 0;
+    let mut boilerplate_lines = 0;
+    let mut informative_chars = 0;
+    let mut total_chars = 0;
+    let mut diagrams = 0;
+    let mut citations = 0;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        total_chars += trimmed.chars().count();
+
+        if trimmed.starts_with("```") {
+            if !in_code && trimmed.trim_start_matches('`').trim() == "mermaid" {
+                diagrams += 1;
+            }
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            // コードや図は実質的な内容として数える
+            informative_chars += trimmed.chars().count();
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+            continue;
+        }
+        if trimmed.starts_with("[^") && trimmed.contains("]:") {
+            // 脚注定義は引用数で評価する
+            continue;
+        }
+
+        citations += trimmed.matches("[^").count();
+        text_lines += 1;
+        let is_boilerplate = BOILERPLATE_PHRASES.iter().any(|p| trimmed.contains(p)) || !seen.insert(trimmed);
+        if is_boilerplate {
+            boilerplate_lines += 1;
+        } else {
+            informative_chars += trimmed.chars().count();
+        }
+    }
+
+    let information_density = ratio(informative_chars, total_chars);
+    let boilerplate_ratio = ratio(boilerplate_lines, text_lines);
+    let score = 40.0 * information_density
+        + 30.0 * (1.0 - boilerplate_ratio)
+        + if diagrams > 0 { 15.0 } else { 0.0 }
+        + 15.0 * ratio(citations.min(FULL_CITATIONS), FULL_CITATIONS);
+
+    PageQuality {
+        section: section.to_string(),
+        score: (score * 10.0).round() / 10.0,
+        information_density,
+        boilerplate_ratio,
+        diagrams,
+        citations,
+    }
+}
+
+/// 文言のうち `{}` を除いた最も長い固定部分（`` `{}`関数の実装です。`` → `` `関数の実装です。 ``）
+fn fixed_part(text: &'static str) -> &'static str {
+    text.split("{}").max_by_key(|part| part.chars().count()).unwrap_or(text)
+}
+
+/// 割合（分母が0の場合は0）
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_page_prefers_substantive_content() {
+        let boilerplate = "# モジュール\n\n## a\n\n何らかの処理を実行します。\n\n## b\n\n何らかの処理を実行します。\n";
+        let rich = "# アーキテクチャ\n\n設定を読み込み、解析結果をキャッシュします。[^src/lib.rs-L1-L40]\n\n```mermaid\ngraph TD\n    A --> B\n```\n\n[^src/lib.rs-L1-L40]: `src/lib.rs#L1-L40`\n";
+
+        let low = score_page("modules", boilerplate);
+        assert_eq!(low.boilerplate_ratio, 1.0);
+        assert_eq!((low.diagrams, low.citations), (0, 0));
+
+        let high = score_page("architecture", rich);
+        assert_eq!(high.boilerplate_ratio, 0.0);
+        assert_eq!((high.diagrams, high.citations), (1, 1));
+        assert!(high.score > low.score);
+    }
+
+    #[test]
+    fn test_score_page_detects_english_boilerplate() {
+        let boilerplate = "# Modules\n\n## a\n\nHere is the implementation of this function.\n\nThis code does the following:\n\nImplementation of `run`.\n\nNo particular concerns were found.\n";
+        let page = score_page("modules", boilerplate);
+        assert_eq!(page.boilerplate_ratio, 1.0);
+
+        let rich = "# Modules\n\n## a\n\nLoads the configuration and caches parsed results.\n";
+        assert!(score_page("modules", rich).score > page.score);
+    }
+}
@@ -0,0 +1,53 @@
+//! mdBookの失敗・タイムアウト・出力の欠けでWikiのビルドがMarkdownのみの出力に切り替わることの確認
+
+use std::path::Path;
+
+use analyzer_core::Index;
+use config::Config;
+use site_mdbook::{MdBookBuilder, WikiResult};
+use summarizer::{FakeRun, FakeTools, Tools};
+
+async fn build_wiki(tools: &FakeTools, repo: &Path) -> WikiResult {
+    let index = Index { repo_path: repo.to_path_buf(), ..Default::default() };
+    let out_dir = repo.join("wiki");
+    let toc = vec!["overview".to_string(), "faq".to_string()];
+    MdBookBuilder::new(Config::default())
+        .with_tools(Tools::new(tools.clone()))
+        .build_wiki(&index, &out_dir.to_string_lossy(), false, &toc)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_wiki_builds_site_with_mdbook() {
+    let repo = tempfile::tempdir().unwrap();
+    let tools = FakeTools::new().with("mdbook", FakeRun::success().writing("book/index.html"));
+    let result = build_wiki(&tools, repo.path()).await;
+    assert!(result.skipped.is_empty(), "{:?}", result.skipped);
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+    let calls = tools.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!((calls[0].args.as_slice(), calls[0].current_dir.as_deref()), (["build".to_string()].as_slice(), Some(repo.path().join("wiki").as_path())));
+}
+
+#[tokio::test]
+async fn test_wiki_keeps_markdown_when_mdbook_fails() {
+    for run in [FakeRun::failure("error: Couldn't open SUMMARY.md"), FakeRun::timeout(), FakeRun::success()] {
+        let repo = tempfile::tempdir().unwrap();
+        let result = build_wiki(&FakeTools::new().with("mdbook", run.clone()), repo.path()).await;
+        assert!(result.ok);
+        assert!(repo.path().join("wiki/src/overview.md").exists());
+        assert_eq!(result.skipped.len(), 1, "{:?}", run);
+        assert_eq!((result.skipped[0].path.as_path(), result.skipped[0].tool.as_str()), (repo.path().join("wiki/book").as_path(), "mdbook"));
+        assert!(result.diagnostics.iter().any(|d| d.message.starts_with("mdBookのビルドに失敗したため")), "{:?}", result.diagnostics);
+    }
+}
+
+#[tokio::test]
+async fn test_wiki_skips_site_without_mdbook() {
+    let repo = tempfile::tempdir().unwrap();
+    let tools = FakeTools::new();
+    let result = build_wiki(&tools, repo.path()).await;
+    assert_eq!(result.skipped[0].reason, "mdbookが見つかりません（インストール: cargo install mdbook）");
+    assert!(tools.calls().is_empty());
+}
@@ -0,0 +1,275 @@
+/*!
+ * 生成済みWikiからのスライド生成
+ *
+ * Wikiの各ページを見出し＋要点の箇条書きに凝縮してスライドにする
+ * - Wikiとスライドの内容が食い違わない
+ * - 要約を再実行しないため高速に再生成できる
+ *
+ * 主な仕様:
+ * - ページの順序はWikiのSUMMARY.mdに従う
+ * - プリセット `summary`: 1ページ1スライド（リード文＋要点）
+ * - プリセット `detailed`: `##` 見出しごとに1スライド
+ * - 要点は箇条書きを優先し、足りない場合は小見出しで補う
+ *
+ * 制限事項:
+ * - コードブロック・Mermaid図・表はスライドに含めない
+ */
+
+use std::fs;
+use std::path::Path;
+use anyhow::{Context, Result};
+
+/// 1スライドあたりの要点の上限
+const MAX_BULLETS: usize = 5;
+
+/// スライドにするWikiページ
+#[derive(Debug, Clone)]
+pub(crate) struct WikiPage {
+    /// SUMMARY.mdでのタイトル
+    pub title: String,
+    /// ファイル名（拡張子付き）
+    pub file: String,
+    pub markdown: String,
+}
+
+/// 凝縮したスライド
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CondensedSlide {
+    pub title: String,
+    pub headline: String,
+    pub bullets: Vec<String>,
+}
+
+/// WikiのページをSUMMARY.mdの順に読み込む
+///
+/// # 引数
+/// * `src_dir` - Wikiのソースディレクトリ（`SUMMARY.md` を含む、`site.layout.src-dir`）
+///
+/// # 戻り値
+/// * `Result<Vec<WikiPage>>` - ページのリスト、またはエラー
+pub(crate) fn read_wiki_pages(src_dir: &Path) -> Result<Vec<WikiPage>> {
+    let summary_path = src_dir.join("SUMMARY.md");
+    let summary = fs::read_to_string(&summary_path)
+        .with_context(|| format!("SUMMARY.mdが見つかりません。先にWikiを生成してください: {:?}", summary_path))?;
+
+    let mut pages = Vec::new();
+    for line in summary.lines() {
+        let Some((title, file)) = line.trim_start().strip_prefix("- ").and_then(parse_link) else {
+            continue;
+        };
+        let path = src_dir.join(&file);
+        let markdown = fs::read_to_string(&path)
+            .with_context(|| format!("Wikiページの読み込みに失敗しました: {:?}", path))?;
+        pages.push(WikiPage { title, file, markdown });
+    }
+
+    Ok(pages)
+}
+
+/// ページをスライドに凝縮
+///
+/// # 引数
+/// * `page` - Wikiページ
+/// * `preset` - プリセット（summary|detailed）
+///
+/// # 戻り値
+/// * `Vec<CondensedSlide>` - スライドのリスト
+pub(crate) fn condense_page(page: &WikiPage, preset: &str) -> Vec<CondensedSlide> {
+    let sections = split_sections(&page.markdown);
+
+    match preset {
+        "detailed" => {
+            let mut slides = Vec::new();
+            for (i, (heading, body)) in sections.iter().enumerate() {
+                let title = match heading {
+                    Some(h) => h.clone(),
+                    None => page.title.clone(),
+                };
+                let slide = condense_body(&title, body);
+                // 見出しだけの導入部は省略
+                if i == 0 && heading.is_none() && slide.headline.is_empty() && slide.bullets.is_empty() {
+                    continue;
+                }
+                slides.push(slide);
+            }
+            slides
+        }
+        _ => {
+            let body: String = sections.iter().map(|(_, b)| b.as_str()).collect::<Vec<_>>().join("\n");
+            let mut slide = condense_body(&page.title, &body);
+            if slide.bullets.len() < MAX_BULLETS {
+                // 箇条書きが少ない場合は `##` 見出しを要点として補う
+                let headings = sections.iter().filter_map(|(h, _)| h.clone());
+                for heading in headings {
+                    if slide.bullets.len() >= MAX_BULLETS {
+                        break;
+                    }
+                    if !slide.bullets.contains(&heading) {
+                        slide.bullets.push(heading);
+                    }
+                }
+            }
+            vec![slide]
+        }
+    }
+}
+
+/// スライドをMarkdownに変換（mdbook-reveal/Marp共通の `---` 区切り）
+///
+/// # 引数
+/// * `slides` - スライドのリスト
+///
+/// # 戻り値
+/// * `String` - スライドのMarkdown
+pub(crate) fn render_slides(slides: &[CondensedSlide]) -> String {
+    let mut content = String::new();
+    for slide in slides {
+        content.push_str("---\n");
+        content.push_str(&format!("## {}\n\n", slide.title));
+        if !slide.headline.is_empty() {
+            content.push_str(&format!("{}\n\n", slide.headline));
+        }
+        for bullet in &slide.bullets {
+            content.push_str(&format!("- {}\n", bullet));
+        }
+        content.push_str("---\n\n");
+    }
+    content
+}
+
+/// 本文からリード文と要点を抽出
+fn condense_body(title: &str, body: &str) -> CondensedSlide {
+    let mut headline = String::new();
+    let mut bullets = Vec::new();
+    let mut subheadings = Vec::new();
+    let mut in_code = false;
+
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || trimmed.is_empty() || trimmed.starts_with('|') || trimmed.starts_with('>') || trimmed == "---" {
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix("### ").or_else(|| trimmed.strip_prefix("#### ")) {
+            subheadings.push(plain_text(heading));
+        } else if trimmed.starts_with('#') {
+            continue;
+        } else if let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) {
+            // 入れ子の箇条書きは要点に含めない
+            if line.starts_with(' ') {
+                continue;
+            }
+            let item = plain_text(item);
+            if !item.is_empty() && bullets.len() < MAX_BULLETS {
+                bullets.push(item);
+            }
+        } else if headline.is_empty() {
+            headline = first_sentence(&plain_text(trimmed));
+        }
+    }
+
+    for heading in subheadings {
+        if bullets.len() >= MAX_BULLETS {
+            break;
+        }
+        bullets.push(heading);
+    }
+
+    CondensedSlide {
+        title: title.to_string(),
+        headline,
+        bullets,
+    }
+}
+
+/// `## ` 見出しで本文を分割（最初の要素は見出し前の導入部）
+fn split_sections(markdown: &str) -> Vec<(Option<String>, String)> {
+    let mut sections: Vec<(Option<String>, String)> = vec![(None, String::new())];
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code {
+            if let Some(heading) = line.strip_prefix("## ") {
+                sections.push((Some(plain_text(heading)), String::new()));
+                continue;
+            }
+            if line.starts_with("# ") {
+                continue;
+            }
+        }
+        let body = &mut sections.last_mut().expect("先頭要素は常に存在する").1;
+        body.push_str(line);
+        body.push('\n');
+    }
+
+    sections
+}
+
+/// 最初の文を取得（句点まで）
+fn first_sentence(text: &str) -> String {
+    match text.find('。') {
+        Some(pos) => text[..pos + '。'.len_utf8()].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// インライン記法（リンク・強調・コード）を除去
+fn plain_text(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find("](").and_then(|mid| after[mid..].find(')').map(|end| (mid, mid + end))) {
+            Some((mid, end)) => {
+                result.push_str(&after[..mid]);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('[');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result.replace("**", "").replace('`', "").trim().to_string()
+}
+
+/// `[タイトル](ファイル)` 形式のリンクを解析
+fn parse_link(s: &str) -> Option<(String, String)> {
+    let s = s.strip_prefix('[')?;
+    let (title, rest) = s.split_once("](")?;
+    let (file, _) = rest.split_once(')')?;
+    Some((title.to_string(), file.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condense_page_presets() {
+        let page = WikiPage {
+            title: "アーキテクチャ".to_string(),
+            file: "architecture.md".to_string(),
+            markdown: "# アーキテクチャ\n\n本システムは3層で構成されます。詳細は以下のとおりです。\n\n## レイヤー\n\n- **API**: [routes](#routes)\n- `core`\n\n```rust\nfn main() {}\n```\n\n## デプロイ\n\nDockerで配布します。\n".to_string(),
+        };
+
+        let summary = condense_page(&page, "summary");
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].headline, "本システムは3層で構成されます。");
+        assert_eq!(summary[0].bullets, vec!["API: routes", "core", "レイヤー", "デプロイ"]);
+
+        let detailed = condense_page(&page, "detailed");
+        let titles: Vec<&str> = detailed.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["アーキテクチャ", "レイヤー", "デプロイ"]);
+        assert_eq!(detailed[2].headline, "Dockerで配布します。");
+    }
+}
@@ -1,39 +1,72 @@
-/**
+/*!
  * スライド生成実装
  * 
  * コードベースからスライドを生成する
  * - mdbook-revealプラグインを使用
  * - Marp CLIを使用（オプション）
  * - HTML/PDF/PPTX形式でエクスポート
+ * - 生成済みWikiを凝縮してスライド化（from_wikiモジュール）
+ * - ドライラン（plan_slides・plan_slides_from_wiki）は書き込まずに生成予定のファイルとサイズを返す
  * 
  * 主な仕様:
  * - mdbook-revealをデフォルトとして使用
  * - Marpは外部コマンド（Node.js依存）
  * - タイトル、全体構成、モジュール、シーケンス、運用、リスクのセクション
+ * - 概要に複雑度の高いファイル（ホットスポット）の表を掲載
+ * - APIのセクションにREST・GraphQLのエンドポイント（メソッド・パス・ハンドラー）の表を掲載
+ * - モジュールの見出し・主要モジュールの一覧にコンポーネントの種類のアイコンを付ける（`slides.icons`、図と共通の `COMPONENT_STYLES`）
+ * - `[branding]` のタイトル・チーム・問い合わせ先をタイトルスライドとbook.tomlに、タイトル・フッターをMarpのheader・footerに使用
+ * - モジュールのスライドに主要な関数（importしているファイルからの呼び出しの多い関数）のコードを `slides.excerpt-lines` 行まで載せ、
+ *   シグネチャと `return` の行を強調する（mdbook-revealは reveal.js の `[1,3]`、Marpは `{1,3}` の記法）
+ * - タイトル・関数・モジュールのスライドはテンプレート（`slides/title.md`・`slides/method.md`・`slides/module.md`、`templates.dir` で上書き可能）で組み立てる
+ * - 出力ファイルの配置は `slides.layout`（mdbook-reveal）と `slides.marp-file`（Marp）で変更可能
+ * - 生成できなかった図、省略したモジュール、エクスポートできなかった形式は警告として結果に含める
+ * - セクション・モジュールはワーカープール（`summarization.workers`、`with_executor` でWikiと共有）で並列に生成
+ * - `security.read-only-repo` の場合、出力先が解析対象のリポジトリ内なら作業ディレクトリ（`security.workspace-dir`）配下に出力
+ * - スライドの言語（ja|en）はビルドごとに指定でき、固定の文言は言語のカタログ（localeモジュール）から引く
+ *   - 要約は言語に合わせたスタイル（`concise-en` など）で生成し、残った日本語はローカルLLMで翻訳、関数の説明はドキュメントコメントをそのまま使う
  * 
  * 制限事項:
  * - mdbook-revealはmdBookプロジェクトから生成
+ * - mdbook-revealではスライドごとのヘッダー・フッターは表示しない（タイトルスライドのみ）
  * - Marpは別途インストールが必要
+ * - Marpの標準のエンジンはコードの行強調の記法に対応していないため、強調は行の強調に対応したプラグイン・テーマでのみ表示される
+ * - ローカルLLMがない場合、英語のスライドでもコメント・READMEから抜き出した説明は日本語のまま掲載する
+ * - Wikiから生成するスライドはWikiの言語（`project.language`）のまま
+ * - mdbook・Marp CLIがない場合・失敗した場合（時間切れ・出力の欠けを含む）はMarkdownのみを生成し、HTMLなどをスキップした成果物として返す（Marpは形式ごと）
  */
 
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::process::Command;
 use anyhow::{Context, Result};
-use tracing::{info, warn};
+use tracing::info;
 
 use config::Config;
-use analyzer_core::Index;
-use summarizer::Summarizer;
+use analyzer_core::{Diagnostic, Diagnostics, Executor, Index, ModuleInfo, Priority};
+use summarizer::{MethodInfo, PlannedFile, SkippedArtifact, Summarizer, Templates, ToolCommand, ToolStatus, Tools};
 use diagrammer::Diagrammer;
 
+mod from_wiki;
+mod locale;
+
+pub use locale::Locale;
+use locale::SlideText;
+
+/// Marp CLIでエクスポートできる形式
+const MARP_FORMATS: &[&str] = &["html", "pdf", "pptx"];
+
 /// スライドビルダー
 pub struct SlideBuilder {
     config: Config,
-    #[allow(dead_code)]
     summarizer: Summarizer,
     diagrammer: Diagrammer,
+    /// 警告の記録先（並列に生成するセクション・モジュールと共有）
+    diagnostics: Diagnostics,
+    /// セクション・モジュールの生成タスクを実行するワーカープール（Wikiと共有可能）
+    executor: Executor,
+    /// mdbook・Marp CLIの実行方法（テストでは失敗を再現する実行方法に差し替える）
+    tools: Tools,
 }
 
 impl SlideBuilder {
@@ -49,9 +82,50 @@ impl SlideBuilder {
             config: config.clone(),
             summarizer: Summarizer::new(config.clone()),
             diagrammer: Diagrammer::new(config.clone()),
+            diagnostics: Diagnostics::new(),
+            executor: Executor::new(config.summarization.workers),
+            tools: Tools::system(),
+        }
+    }
+
+    /// 警告の記録先を指定（解析・Wikiと共有して最後にまとめて表示する場合）
+    /// 
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    /// 
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたスライドビルダー
+    pub fn with_diagnostics(self, diagnostics: Diagnostics) -> Self {
+        Self {
+            summarizer: self.summarizer.with_diagnostics(diagnostics.clone()),
+            diagrammer: self.diagrammer.with_diagnostics(diagnostics.clone()),
+            diagnostics,
+            ..self
         }
     }
 
+    /// ワーカープールを指定（Wikiと同時実行数の上限を共有する場合）
+    /// 
+    /// # 引数
+    /// * `executor` - ワーカープール
+    /// 
+    /// # 戻り値
+    /// * `Self` - ワーカープールを差し替えたスライドビルダー
+    pub fn with_executor(self, executor: Executor) -> Self {
+        Self { executor, ..self }
+    }
+
+    /// 外部ツールの実行方法を指定（テストでmdbook・Marp CLIの失敗・タイムアウトを再現する場合）
+    /// 
+    /// # 引数
+    /// * `tools` - 外部ツールの実行方法
+    /// 
+    /// # 戻り値
+    /// * `Self` - 実行方法を差し替えたスライドビルダー
+    pub fn with_tools(self, tools: Tools) -> Self {
+        Self { tools, ..self }
+    }
+
     /// スライドをビルド
     /// 
     /// # 引数
@@ -60,6 +134,7 @@ impl SlideBuilder {
     /// * `out_dir` - 出力ディレクトリ
     /// * `sections` - セクションのリスト
     /// * `export` - エクスポート形式のリスト（html|pdf|pptx）
+    /// * `language` - スライドの言語（ja|en、通常は `slides.language`）
     /// 
     /// # 戻り値
     /// * `Result<SlideResult>` - ビルド結果、またはエラー
@@ -70,39 +145,259 @@ impl SlideBuilder {
         out_dir: &str,
         sections: &[String],
         export: &[String],
+        language: &str,
+    ) -> Result<SlideResult> {
+        info!("スライドビルド開始: flavor={}, out_dir={}, language={}", flavor, out_dir, language);
+        let locale = Locale::parse(language)?;
+        if locale != Locale::Ja && self.summarizer.is_extractive() {
+            self.diagnostics.warn("slides", "ローカルLLMがないため、英語のスライドでもコメント・READMEから抜き出した説明は日本語のまま掲載しました");
+        }
+
+        // security.read-only-repoの場合、リポジトリ内の出力先は作業ディレクトリ配下に置き換える
+        let out_path = self.config.security.output_path(&index.repo_path, Path::new(out_dir));
+        fs::create_dir_all(&out_path)?;
+
+        // 生成後に外部ツールがないことで失敗しないよう、先に確認してMarkdownのみの出力に切り替える
+        let tool = required_tool(&self.tools, flavor, export, &self.diagnostics);
+        // templates.dirの誤りは生成を始める前にエラーにする
+        let templates = Templates::load(&self.config)?;
+
+        let mut result = match flavor {
+            "mdbook-reveal" => self.build_mdbook_reveal(index, &out_path, sections, &templates, locale, tool.as_ref()).await?,
+            "marp" => self.build_marp(index, &out_path, sections, export, &templates, locale, tool.as_ref()).await?,
+            _ => return Err(anyhow::anyhow!("不明なフレーバー: {}", flavor)),
+        };
+        result.diagnostics = self.diagnostics.entries();
+        Ok(result)
+    }
+
+    /// 生成済みWikiからスライドをビルド
+    /// 
+    /// Wikiの各ページを見出しと要点に凝縮するため、要約を再実行せずに再生成できる。
+    /// 
+    /// # 引数
+    /// * `wiki_dir` - Wikiの出力ディレクトリ（`src/SUMMARY.md` を含む）
+    /// * `preset` - プリセット（summary: 1ページ1スライド、detailed: 見出しごとに1スライド）
+    /// * `flavor` - フレーバー（mdbook-reveal|marp）
+    /// * `out_dir` - 出力ディレクトリ
+    /// * `export` - エクスポート形式のリスト（html|pdf|pptx、marpの場合のみ）
+    /// 
+    /// # 戻り値
+    /// * `Result<SlideResult>` - ビルド結果、またはエラー
+    pub async fn build_slides_from_wiki(
+        &self,
+        wiki_dir: &str,
+        preset: &str,
+        flavor: &str,
+        out_dir: &str,
+        export: &[String],
     ) -> Result<SlideResult> {
-        info!("スライドビルド開始: flavor={}, out_dir={}", flavor, out_dir);
+        info!("Wikiからスライドをビルド: wiki_dir={}, preset={}", wiki_dir, preset);
+
+        if !["summary", "detailed"].contains(&preset) {
+            return Err(anyhow::anyhow!("不明なプリセット: {}（summary|detailed）", preset));
+        }
 
-        let out_path = PathBuf::from(out_dir);
+        let pages = from_wiki::read_wiki_pages(&self.config.output_path(wiki_dir).join(&self.config.site.layout.src_dir))?;
+        let out_path = self.config.output_path(out_dir);
         fs::create_dir_all(&out_path)?;
+        let tool = required_tool(&self.tools, flavor, export, &self.diagnostics);
+
+        let title_slide = self.wiki_title_slide(pages.len());
+
+        let mut result = match flavor {
+            "mdbook-reveal" => {
+                let src_dir = out_path.join(&self.config.slides.layout.src_dir);
+                fs::create_dir_all(&src_dir)?;
+                self.generate_reveal_book_toml(&out_path, Locale::Ja)?;
+
+                let mut summary = String::from("# Summary\n\n");
+                for (i, page) in pages.iter().enumerate() {
+                    let mut content = from_wiki::render_slides(&from_wiki::condense_page(page, preset));
+                    if i == 0 {
+                        content.insert_str(0, &title_slide);
+                    }
+                    write_slide(&src_dir.join(&page.file), &content)?;
+                    summary.push_str(&format!("- [{}]({})\n", page.title, page.file));
+                }
+                fs::write(src_dir.join("SUMMARY.md"), summary)
+                    .context("SUMMARY.mdの書き込みに失敗しました")?;
+
+                self.run_mdbook_build(&out_path, tool.as_ref())?
+            }
+            "marp" => {
+                let mut marp_content = self.marp_front_matter(Locale::Ja);
+                marp_content.push_str(&title_slide);
+                for page in &pages {
+                    marp_content.push_str(&from_wiki::render_slides(&from_wiki::condense_page(page, preset)));
+                }
+
+                let marp_file = out_path.join(&self.config.slides.marp_file);
+                write_slide(&marp_file, &marp_content)?;
+
+                self.export_marp(&marp_file, export, tool.as_ref())?
+            }
+            _ => return Err(anyhow::anyhow!("不明なフレーバー: {}", flavor)),
+        };
+        result.diagnostics = self.diagnostics.entries();
+        Ok(result)
+    }
+
+    /// スライドのビルドで生成されるファイルを列挙（ディスクには書き込まない）
+    /// 
+    /// 各セクションをメモリ上で生成してサイズを求める。LLMは呼び出さず、LLMを使うセクションは
+    /// ヒューリスティックの要約で生成したサイズを推定値とする。外部コマンドの出力はサイズ不明とする。
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `flavor` - フレーバー（mdbook-reveal|marp）
+    /// * `sections` - セクションのリスト
+    /// * `export` - エクスポート形式のリスト（html|pdf|pptx、marpの場合のみ）
+    /// * `language` - スライドの言語（ja|en）
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<PlannedFile>>` - 出力ディレクトリからの相対パスで表した生成予定のファイル、またはエラー
+    pub async fn plan_slides(
+        &self,
+        index: &Index,
+        flavor: &str,
+        sections: &[String],
+        export: &[String],
+        language: &str,
+    ) -> Result<Vec<PlannedFile>> {
+        if !["mdbook-reveal", "marp"].contains(&flavor) {
+            return Err(anyhow::anyhow!("不明なフレーバー: {}", flavor));
+        }
+        let locale = Locale::parse(language)?;
+
+        let mut offline_config = self.config.clone();
+        offline_config.summarization.mode = "none".to_string();
+        let offline_summarizer = Summarizer::new(offline_config.clone()).with_templates(Templates::load(&self.config)?);
+
+        let mut sections_planned = Vec::new();
+        for section in sections {
+            let content =
+                Self::slide_section_content(index, section, flavor, &offline_config, &offline_summarizer, &self.diagrammer, &self.executor, locale)
+                    .await?;
+            // 概要とアーキテクチャのみリポジトリの要約を使う
+            let generator = match section.as_str() {
+                "overview" | "architecture" => self.summarizer.mode_label(),
+                _ => "static",
+            };
+            sections_planned.push((section, content.len(), generator));
+        }
+
+        if flavor == "marp" {
+            let header = self.marp_front_matter(locale).len();
+            let body: usize = sections_planned.iter().map(|(_, len, _)| len + 1).sum();
+            let generator = if sections_planned.iter().any(|(_, _, g)| *g == "llm-local") { "llm-local" } else { "static" };
+            let marp_file = &self.config.slides.marp_file;
+            let mut files = vec![PlannedFile::new(marp_file, Some(header + body), generator)];
+            files.extend(marp_exports(marp_file, export));
+            return Ok(files);
+        }
+
+        let layout = &self.config.slides.layout;
+        let mut files = vec![
+            PlannedFile::new("book.toml", Some(self.reveal_book_toml(locale).len()), "static"),
+            PlannedFile::new(layout.src_dir.join("SUMMARY.md"), Some(self.reveal_summary(sections, locale).len()), "static"),
+        ];
+        for (section, len, generator) in sections_planned {
+            files.push(PlannedFile::new(layout.src_dir.join(layout.section_file(section)), Some(len), generator));
+        }
+        files.push(PlannedFile::new(format!("{}/", layout.build_dir.display()), None, "mdbook"));
+        Ok(files)
+    }
+
+    /// 生成済みWikiからのスライドのビルドで生成されるファイルを列挙（ディスクには書き込まない）
+    /// 
+    /// # 引数
+    /// * `wiki_dir` - Wikiの出力ディレクトリ（`src/SUMMARY.md` を含む）
+    /// * `preset` - プリセット（summary|detailed）
+    /// * `flavor` - フレーバー（mdbook-reveal|marp）
+    /// * `export` - エクスポート形式のリスト（html|pdf|pptx、marpの場合のみ）
+    /// 
+    /// # 戻り値
+    /// * `Result<Vec<PlannedFile>>` - 出力ディレクトリからの相対パスで表した生成予定のファイル、またはエラー
+    pub fn plan_slides_from_wiki(
+        &self,
+        wiki_dir: &str,
+        preset: &str,
+        flavor: &str,
+        export: &[String],
+    ) -> Result<Vec<PlannedFile>> {
+        if !["summary", "detailed"].contains(&preset) {
+            return Err(anyhow::anyhow!("不明なプリセット: {}（summary|detailed）", preset));
+        }
 
+        let pages = from_wiki::read_wiki_pages(&Path::new(wiki_dir).join(&self.config.site.layout.src_dir))?;
+        let title_slide = self.wiki_title_slide(pages.len());
+        let rendered: Vec<(String, usize)> = pages
+            .iter()
+            .map(|page| (page.file.clone(), from_wiki::render_slides(&from_wiki::condense_page(page, preset)).len()))
+            .collect();
+
+        let layout = &self.config.slides.layout;
         match flavor {
-            "mdbook-reveal" => self.build_mdbook_reveal(index, &out_path, sections, export).await,
-            "marp" => self.build_marp(index, &out_path, sections, export).await,
+            "mdbook-reveal" => {
+                let summary: usize = "# Summary\n\n".len()
+                    + pages.iter().map(|p| format!("- [{}]({})\n", p.title, p.file).len()).sum::<usize>();
+                let mut files = vec![
+                    PlannedFile::new("book.toml", Some(self.reveal_book_toml(Locale::Ja).len()), "static"),
+                    PlannedFile::new(layout.src_dir.join("SUMMARY.md"), Some(summary), "static"),
+                ];
+                for (i, (file, len)) in rendered.into_iter().enumerate() {
+                    let len = if i == 0 { len + title_slide.len() } else { len };
+                    files.push(PlannedFile::new(layout.src_dir.join(file), Some(len), "static"));
+                }
+                files.push(PlannedFile::new(format!("{}/", layout.build_dir.display()), None, "mdbook"));
+                Ok(files)
+            }
+            "marp" => {
+                let len = self.marp_front_matter(Locale::Ja).len()
+                    + title_slide.len()
+                    + rendered.iter().map(|(_, len)| len).sum::<usize>();
+                let marp_file = &self.config.slides.marp_file;
+                let mut files = vec![PlannedFile::new(marp_file, Some(len), "static")];
+                files.extend(marp_exports(marp_file, export));
+                Ok(files)
+            }
             _ => Err(anyhow::anyhow!("不明なフレーバー: {}", flavor)),
         }
     }
 
+    /// Wikiから生成するスライドのタイトルスライド
+    fn wiki_title_slide(&self, pages: usize) -> String {
+        let mut title_slide = format!("---\n# {}\n\n", self.config.branding.title(&self.config.project.name));
+        title_slide.push_str(&branding_lines(&self.config, Locale::Ja));
+        title_slide.push_str(&format!("{}ページのWikiから生成\n", pages));
+        title_slide.push_str("---\n\n");
+        title_slide
+    }
+
     /// mdbook-revealでスライドをビルド
     async fn build_mdbook_reveal(
         &self,
         index: &Index,
         out_dir: &Path,
         sections: &[String],
-        _export: &[String],
+        templates: &Templates,
+        locale: Locale,
+        mdbook: Option<&ToolStatus>,
     ) -> Result<SlideResult> {
         info!("mdbook-revealでスライドをビルド中...");
 
-        let src_dir = out_dir.join("src");
+        let layout = &self.config.slides.layout;
+        let src_dir = out_dir.join(&layout.src_dir);
         fs::create_dir_all(&src_dir)?;
 
         // book.tomlを生成（revealプラグイン設定付き）
-        self.generate_reveal_book_toml(out_dir)?;
+        self.generate_reveal_book_toml(out_dir, locale)?;
 
         // SUMMARY.mdを生成
-        self.generate_reveal_summary(&src_dir, sections)?;
+        self.generate_reveal_summary(&src_dir, sections, locale)?;
 
-        // スライドコンテンツを並列生成（16並列対応）
+        // スライドコンテンツをワーカープールで並列生成
         // インデックスと設定をクローンして各セクションで使用可能にする
         let index_clone = index.clone();
         let config_clone = self.config.clone();
@@ -111,23 +406,32 @@ impl SlideBuilder {
         let mut section_handles = Vec::new();
         for section in sections {
             let section = section.clone();
-            let src_dir_clone = src_dir.clone();
+            let file_path = src_dir.join(layout.section_file(&section));
             let index_for_section = index_clone.clone();
             let config_for_section = config_clone.clone();
+            let diagnostics = self.diagnostics.clone();
+            let executor = self.executor.clone();
+            let templates = templates.clone();
+            let priority = section_priority(&section);
             
-            let handle = tokio::spawn(async move {
+            let task = async move {
                 // 各セクション用に新しいインスタンスを作成
-                let diagrammer = Diagrammer::new(config_for_section.clone());
+                let summarizer =
+                    Summarizer::new(config_for_section.clone()).with_diagnostics(diagnostics.clone()).with_templates(templates);
+                let diagrammer = Diagrammer::new(config_for_section.clone()).with_diagnostics(diagnostics);
                 
                 Self::generate_reveal_section_parallel(
                     &index_for_section,
-                    &src_dir_clone,
+                    &file_path,
                     &section,
                     &config_for_section,
+                    &summarizer,
                     &diagrammer,
+                    &executor,
+                    locale,
                 ).await
-            });
-            section_handles.push(handle);
+            };
+            section_handles.push(spawn_section(&self.executor, priority, task));
         }
         
         // すべてのセクションを並列実行して結果を収集
@@ -136,45 +440,65 @@ impl SlideBuilder {
         }
 
         // mdbook buildを実行
-        let output = Command::new("mdbook")
-            .arg("build")
-            .current_dir(out_dir)
-            .output()
-            .with_context(|| {
-                "mdBookコマンドが見つかりません。インストールしてください: cargo install mdbook"
-            })?;
+        self.run_mdbook_build(out_dir, mdbook)
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("mdBookビルドエラー: {}", stderr));
+    /// mdbook buildを実行してHTMLスライドを生成
+    /// 
+    /// # 引数
+    /// * `out_dir` - 出力ディレクトリ
+    /// * `mdbook` - mdbookの状態（見つからない場合はビルドせずスキップした成果物として返す）
+    /// 
+    /// # 戻り値
+    /// * `Result<SlideResult>` - ビルド結果、またはエラー
+    fn run_mdbook_build(&self, out_dir: &Path, mdbook: Option<&ToolStatus>) -> Result<SlideResult> {
+        let build_dir = out_dir.join(&self.config.slides.layout.build_dir);
+        if let Some(mdbook) = mdbook.filter(|t| !t.available) {
+            return Ok(SlideResult {
+                ok: true,
+                files: Vec::new(),
+                skipped: vec![SkippedArtifact::missing_tool(build_dir, mdbook)],
+                diagnostics: Vec::new(),
+            });
         }
 
-        let mut files = Vec::new();
-        let html_path = out_dir.join("book").join("index.html");
-        if html_path.exists() {
-            files.push(SlideFile {
-                format: "html".to_string(),
-                path: html_path,
+        let html_path = build_dir.join("index.html");
+        if let Err(failure) = self.tools.run(&ToolCommand::new("mdbook").arg("build").current_dir(out_dir), &html_path) {
+            self.diagnostics.warn("slides", format!("mdbookのビルドに失敗したため、Markdownのみを生成しました: {}", failure));
+            return Ok(SlideResult {
+                ok: true,
+                files: Vec::new(),
+                skipped: vec![SkippedArtifact::failed_tool(build_dir, &failure)],
+                diagnostics: Vec::new(),
             });
         }
 
         Ok(SlideResult {
             ok: true,
-            files,
+            files: vec![SlideFile {
+                format: "html".to_string(),
+                path: html_path,
+            }],
+            skipped: Vec::new(),
+            diagnostics: Vec::new(),
         })
     }
 
     /// Marpでスライドをビルド
+    #[allow(clippy::too_many_arguments)]
     async fn build_marp(
         &self,
         index: &Index,
         out_dir: &Path,
         sections: &[String],
         export: &[String],
+        templates: &Templates,
+        locale: Locale,
+        marp: Option<&ToolStatus>,
     ) -> Result<SlideResult> {
         info!("Marpでスライドをビルド中...");
 
-        // Marpコンテンツを並列生成（16並列対応）
+        // Marpコンテンツをワーカープールで並列生成
         let index_clone = index.clone();
         let config_clone = self.config.clone();
         
@@ -184,107 +508,123 @@ impl SlideBuilder {
             let section = section.clone();
             let index_for_section = index_clone.clone();
             let config_for_section = config_clone.clone();
+            let diagnostics = self.diagnostics.clone();
+            let executor = self.executor.clone();
+            let templates = templates.clone();
+            let priority = section_priority(&section);
             
-            let handle = tokio::spawn(async move {
-                let summarizer = Summarizer::new(config_for_section.clone());
-                let diagrammer = Diagrammer::new(config_for_section.clone());
-                
-                match section.as_str() {
-                    "overview" => Self::generate_overview_slide_parallel(&index_for_section, &summarizer, &diagrammer).await,
-                    "architecture" => Self::generate_architecture_slide_parallel(&index_for_section, &summarizer, &diagrammer).await,
-                    "modules" => Self::generate_modules_slide_parallel(&index_for_section, &config_for_section).await,
-                    "flows" => Self::generate_flows_slide_parallel(&index_for_section, &diagrammer).await,
-                    "deploy" => Self::generate_deploy_slide_parallel(&index_for_section, &diagrammer).await,
-                    _ => Ok(format!("# {}\n\nセクションの内容\n", section)),
-                }
-            });
-            section_handles.push(handle);
+            let task = async move {
+                let summarizer =
+                    Summarizer::new(config_for_section.clone()).with_diagnostics(diagnostics.clone()).with_templates(templates);
+                let diagrammer = Diagrammer::new(config_for_section.clone()).with_diagnostics(diagnostics);
+
+                Self::slide_section_content(&index_for_section, &section, "marp", &config_for_section, &summarizer, &diagrammer, &executor, locale)
+                    .await
+            };
+            section_handles.push(spawn_section(&self.executor, priority, task));
         }
         
         // すべてのセクションを並列実行して結果を収集
-        let mut marp_content = String::from("---\nmarp: true\ntheme: default\n---\n\n");
+        let mut marp_content = self.marp_front_matter(locale);
         for handle in section_handles {
             let section_content = handle.await??;
             marp_content.push_str(&section_content);
-            marp_content.push_str("\n");
+            marp_content.push('\n');
         }
         
-        let marp_file = out_dir.join("slides.md");
-        fs::write(&marp_file, marp_content)?;
+        let marp_file = out_dir.join(&self.config.slides.marp_file);
+        write_slide(&marp_file, &marp_content)?;
 
         // Marp CLIでビルド
+        self.export_marp(&marp_file, export, marp)
+    }
+
+    /// Marp CLIで各形式にエクスポート（スライドのファイルと同じ場所に拡張子を置き換えて出力）
+    /// 
+    /// # 引数
+    /// * `marp_file` - スライドのMarkdown
+    /// * `export` - エクスポート形式のリスト（html|pdf|pptx）
+    /// * `marp` - Marp CLIの状態（見つからない場合はエクスポートせずスキップした成果物として返す）
+    /// 
+    /// # 戻り値
+    /// * `Result<SlideResult>` - ビルド結果、またはエラー
+    fn export_marp(&self, marp_file: &Path, export: &[String], marp: Option<&ToolStatus>) -> Result<SlideResult> {
         let mut files = Vec::new();
+        let mut skipped = Vec::new();
         for format in export {
-            let output_file = match format.as_str() {
-                "html" => out_dir.join("slides.html"),
-                "pdf" => out_dir.join("slides.pdf"),
-                "pptx" => out_dir.join("slides.pptx"),
-                _ => {
-                    warn!("サポートされていない形式: {}", format);
-                    continue;
-                }
-            };
-
-            let mut cmd = Command::new("marp");
-            cmd.arg(&marp_file)
-                .arg("--output")
-                .arg(&output_file);
-
-            match format.as_str() {
-                "html" => {
-                    cmd.arg("--html");
-                }
-                "pdf" => {
-                    cmd.arg("--pdf");
-                }
-                "pptx" => {
-                    cmd.arg("--pptx");
-                    // .pptx形式の生成を確実にするため、エラーハンドリングを改善
-                    cmd.arg("--allow-local-files");
-                }
-                _ => {}
+            if !MARP_FORMATS.contains(&format.as_str()) {
+                self.diagnostics.warn("slides", format!("サポートされていない形式のためエクスポートしませんでした: {}", format));
+                continue;
             }
-
-            let output = cmd.output().with_context(|| {
-                format!("Marp CLIが見つかりません。インストールしてください: npm install -g @marp-team/marp-cli")
-            })?;
-
-            if !output.status.success() {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                warn!("Marp CLIエラー (形式: {}): {}", format, stderr);
-                // .pptx形式の場合は、エラーがあっても続行
-                if format != "pptx" {
-                    return Err(anyhow::anyhow!("Marp CLIビルドエラー (形式: {}): {}", format, stderr));
-                }
+            let output_file = marp_file.with_extension(format);
+            if let Some(marp) = marp.filter(|t| !t.available) {
+                skipped.push(SkippedArtifact::missing_tool(output_file, marp));
+                continue;
             }
 
-            if output_file.exists() {
-                files.push(SlideFile {
+            let mut cmd = ToolCommand::new("marp").arg(marp_file).arg("--output").arg(&output_file);
+            cmd = match format.as_str() {
+                "html" => cmd.arg("--html"),
+                "pdf" => cmd.arg("--pdf"),
+                // 画像などのローカルファイルを参照するスライドも.pptxに含める
+                "pptx" => cmd.arg("--pptx").arg("--allow-local-files"),
+                _ => cmd,
+            };
+
+            // 失敗した形式だけをスキップし、ほかの形式のエクスポートは続ける
+            match self.tools.run(&cmd, &output_file) {
+                Ok(_) => files.push(SlideFile {
                     format: format.clone(),
                     path: output_file,
-                });
-            } else if format == "pptx" {
-                // .pptx形式の生成に失敗した場合の警告
-                warn!("スライドファイルが生成されませんでした: {:?}", output_file);
+                }),
+                Err(failure) => {
+                    self.diagnostics.warn_at("slides", &output_file, format!("Marp CLIのエクスポートに失敗しました（形式: {}）: {}", format, failure));
+                    skipped.push(SkippedArtifact::failed_tool(output_file, &failure));
+                }
             }
         }
 
-        Ok(SlideResult {
-            ok: true,
-            files,
-        })
+        Ok(SlideResult { ok: true, files, skipped, diagnostics: Vec::new() })
     }
 
     /// reveal用のbook.tomlを生成
-    fn generate_reveal_book_toml(&self, out_dir: &Path) -> Result<()> {
-        let book_toml = format!(
+    fn generate_reveal_book_toml(&self, out_dir: &Path, locale: Locale) -> Result<()> {
+        let book_toml = self.reveal_book_toml(locale);
+        let book_toml_path = out_dir.join("book.toml");
+        fs::write(&book_toml_path, book_toml)
+            .with_context(|| format!("book.tomlの書き込みに失敗しました: {:?}", book_toml_path))?;
+
+        Ok(())
+    }
+
+    /// Marpのフロントマター（`[branding]` のタイトル・フッターをheader・footerディレクティブに、言語をlangに設定）
+    fn marp_front_matter(&self, locale: Locale) -> String {
+        let branding = &self.config.branding;
+        let mut front_matter = format!("---\nmarp: true\ntheme: default\nlang: {}\n", locale.code());
+        // YAMLの文字列として書く（JSONの文字列リテラルはYAMLでも有効）
+        if branding.product_name.is_some() || branding.title.is_some() {
+            let title = branding.title(&self.config.project.name);
+            front_matter.push_str(&format!("header: {}\n", serde_json::Value::from(title)));
+        }
+        if let Some(footer) = branding.footer(&self.config.project.name) {
+            front_matter.push_str(&format!("footer: {}\n", serde_json::Value::from(footer)));
+        }
+        front_matter.push_str("---\n\n");
+        front_matter
+    }
+
+    /// reveal用のbook.tomlの内容
+    fn reveal_book_toml(&self, locale: Locale) -> String {
+        let layout = &self.config.slides.layout;
+        format!(
             r#"[book]
-title = "{}"
-authors = ["DeepRepoSlides"]
-language = "ja"
+title = {}
+authors = [{}]
+language = "{}"
+src = "{}"
 
 [build]
-build-dir = "book"
+build-dir = "{}"
 
 [output.html]
 default-theme = "black"
@@ -292,56 +632,77 @@ default-theme = "black"
 [output.reveal]
 optional = true
 "#,
-            self.config.project.name
-        );
+            // TOMLの基本文字列として書く（JSONの文字列リテラルと同じエスケープ）
+            serde_json::Value::from(self.config.branding.title(&self.config.project.name)),
+            serde_json::Value::from(self.config.branding.author(&self.config.project.name)),
+            locale.code(),
+            toml_path(&layout.src_dir),
+            toml_path(&layout.build_dir)
+        )
+    }
 
-        let book_toml_path = out_dir.join("book.toml");
-        fs::write(&book_toml_path, book_toml)
-            .with_context(|| format!("book.tomlの書き込みに失敗しました: {:?}", book_toml_path))?;
+    /// reveal用のSUMMARY.mdを生成
+    fn generate_reveal_summary(&self, src_dir: &Path, sections: &[String], locale: Locale) -> Result<()> {
+        let summary = self.reveal_summary(sections, locale);
+        let summary_path = src_dir.join("SUMMARY.md");
+        fs::write(&summary_path, summary)
+            .with_context(|| format!("SUMMARY.mdの書き込みに失敗しました: {:?}", summary_path))?;
 
         Ok(())
     }
 
-    /// reveal用のSUMMARY.mdを生成
-    fn generate_reveal_summary(&self, src_dir: &Path, sections: &[String]) -> Result<()> {
+    /// reveal用のSUMMARY.mdの内容
+    fn reveal_summary(&self, sections: &[String], locale: Locale) -> String {
         let mut summary = String::from("# Summary\n\n");
-
         for section in sections {
-            let section_name = self.get_section_name(section);
-            let file_name = format!("{}.md", section);
+            let section_name = self.get_section_name(section, locale);
+            let file_name = self.config.slides.layout.section_file(section);
             summary.push_str(&format!("- [{}]({})\n", section_name, file_name));
         }
-
-        let summary_path = src_dir.join("SUMMARY.md");
-        fs::write(&summary_path, summary)
-            .with_context(|| format!("SUMMARY.mdの書き込みに失敗しました: {:?}", summary_path))?;
-
-        Ok(())
+        summary
     }
 
     /// reveal用のセクションを並列実行用に生成（静的メソッド）
+    #[allow(clippy::too_many_arguments)]
     async fn generate_reveal_section_parallel(
         index: &Index,
-        src_dir: &Path,
+        file_path: &Path,
         section: &str,
         config: &Config,
+        summarizer: &Summarizer,
         diagrammer: &Diagrammer,
+        executor: &Executor,
+        locale: Locale,
     ) -> Result<()> {
-        let summarizer = Summarizer::new(config.clone());
-        let content = match section {
-            "overview" => Self::generate_overview_slide_parallel(index, &summarizer, diagrammer).await?,
-            "architecture" => Self::generate_architecture_slide_parallel(index, &summarizer, diagrammer).await?,
-            "modules" => Self::generate_modules_slide_parallel(index, config).await?,
-            "flows" => Self::generate_flows_slide_parallel(index, diagrammer).await?,
-            "deploy" => Self::generate_deploy_slide_parallel(index, diagrammer).await?,
-            _ => format!("# {}\n\nセクションの内容\n", section),
-        };
-
-        let file_path = src_dir.join(format!("{}.md", section));
-        fs::write(&file_path, content)
-            .with_context(|| format!("セクションファイルの書き込みに失敗しました: {:?}", file_path))?;
+        let content =
+            Self::slide_section_content(index, section, "mdbook-reveal", config, summarizer, diagrammer, executor, locale).await?;
+        write_slide(file_path, &content)
+    }
 
-        Ok(())
+    /// セクションのスライド内容を生成（静的メソッド、`flavor` はコードの強調の記法に使用）
+    #[allow(clippy::too_many_arguments)]
+    async fn slide_section_content(
+        index: &Index,
+        section: &str,
+        flavor: &str,
+        config: &Config,
+        summarizer: &Summarizer,
+        diagrammer: &Diagrammer,
+        executor: &Executor,
+        locale: Locale,
+    ) -> Result<String> {
+        Ok(match section {
+            "overview" => Self::generate_overview_slide_parallel(index, config, summarizer, diagrammer, locale).await?,
+            "architecture" => Self::generate_architecture_slide_parallel(index, config, summarizer, diagrammer, locale).await?,
+            "modules" => {
+                Self::generate_modules_slide_parallel(index, flavor, config, summarizer.diagnostics(), summarizer.templates(), executor, locale)
+                    .await?
+            }
+            "api" => endpoint_slide(index, locale),
+            "flows" => Self::generate_flows_slide_parallel(index, diagrammer, locale).await?,
+            "deploy" => Self::generate_deploy_slide_parallel(index, diagrammer, locale).await?,
+            _ => format!("# {}\n\n{}\n", section, locale.text("section.placeholder")),
+        })
     }
 
     /// reveal用のセクションを生成（非並列実行用、後方互換性のため保持）
@@ -371,43 +732,44 @@ optional = true
     /// 概要スライドを並列実行用に生成（静的メソッド）
     async fn generate_overview_slide_parallel(
         index: &Index,
+        config: &Config,
         summarizer: &Summarizer,
         diagrammer: &Diagrammer,
+        locale: Locale,
     ) -> Result<String> {
-        let mut content = String::new();
-        
-        // タイトルスライド
-        content.push_str("---\n");
-        content.push_str(&format!("# {}\n\n", index.repo_path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("プロジェクト")));
-        
-        // リポジトリ要約を取得
-        let summary_result = summarizer.summarize(index, "repo", "", "concise-ja").await?;
-        let summary_lines: Vec<&str> = summary_result.content_md.lines().take(5).collect();
-        for line in summary_lines {
-            if !line.trim().is_empty() {
-                content.push_str(line);
-                content.push_str("\n");
-            }
+        // タイトルスライド（リポジトリ要約と統計）
+        let summary_result = summarizer.summarize(index, "repo", "", &locale.style("concise")).await?;
+        let summary = localize(summarizer, locale, non_empty_lines(&summary_result.content_md, 5)).await;
+        let stats: [&dyn std::fmt::Display; 3] = [&index.stats.files, &index.stats.languages.len(), &index.stats.modules];
+        let branding = &config.branding;
+        let mut content = summarizer.templates().render(
+            "slides/title.md",
+            &serde_json::json!({
+                "title": deck_title(index, config, locale),
+                "team": branding.team,
+                "contact": branding.contact,
+                "contact_line": branding.contact.as_ref().map(|contact| locale.format("title.contact", &[contact])),
+                "summary": summary,
+                "stats": locale.format("overview.stats", &stats),
+                "files": index.stats.files,
+                "languages": index.stats.languages,
+                "modules": index.stats.modules,
+            }),
+        )?;
+
+        // 複雑度の高いファイル
+        let hotspots = hotspot_table(index, locale);
+        if !hotspots.is_empty() {
+            content.push_str("---\n");
+            content.push_str(&format!("## {}\n\n", locale.text("overview.hotspots")));
+            content.push_str(&hotspots);
+            content.push_str("---\n\n");
         }
-        content.push_str("\n");
-        
-        content.push_str(&format!(
-            "📊 **統計**: {}ファイル、{}言語、{}モジュール\n",
-            index.stats.files,
-            index.stats.languages.len(),
-            index.stats.modules
-        ));
-        content.push_str("---\n\n");
         
         // 全体構成図
         content.push_str("---\n");
-        content.push_str("## 全体構成\n\n");
-        let diagram = diagrammer.generate_diagram(index, "module-graph")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&format!("## {}\n\n", locale.text("overview.structure")));
+        content.push_str(&diagram_markdown(diagrammer, index, "module-graph"));
         content.push_str("---\n\n");
 
         Ok(content)
@@ -416,46 +778,40 @@ optional = true
     /// アーキテクチャスライドを並列実行用に生成（静的メソッド）
     async fn generate_architecture_slide_parallel(
         index: &Index,
+        config: &Config,
         summarizer: &Summarizer,
         diagrammer: &Diagrammer,
+        locale: Locale,
     ) -> Result<String> {
         let mut content = String::new();
 
         content.push_str("---\n");
-        content.push_str("## アーキテクチャ概要\n");
+        content.push_str(&format!("## {}\n", locale.text("architecture.title")));
         content.push_str("---\n\n");
         
         // アーキテクチャ要約を取得
-        let summary_result = summarizer.summarize(index, "repo", "", "concise-ja").await?;
-        let summary_lines: Vec<&str> = summary_result.content_md.lines().take(10).collect();
-        for line in summary_lines {
-            if !line.trim().is_empty() {
-                content.push_str(line);
-                content.push_str("\n");
-            }
-        }
+        let summary_result = summarizer.summarize(index, "repo", "", &locale.style("concise")).await?;
+        let summary = non_empty_lines(&summary_result.content_md, 10);
+        content.push_str(&localize(summarizer, locale, summary).await);
         content.push_str("\n---\n\n");
 
         // モジュールグラフ図
         content.push_str("---\n");
-        content.push_str("### モジュール構成図\n\n");
-        let diagram = diagrammer.generate_diagram(index, "module-graph")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&format!("### {}\n\n", locale.text("architecture.diagram")));
+        content.push_str(&diagram_markdown(diagrammer, index, "module-graph"));
         content.push_str("---\n\n");
 
         // 主要モジュール一覧
         content.push_str("---\n");
-        content.push_str("### 主要モジュール\n\n");
-        for (i, module) in index.modules.iter().take(10).enumerate() {
-            content.push_str(&format!("{}. **{}**\n", i + 1, module.name));
-            content.push_str(&format!("   - パス: `{}`\n", module.path.display()));
-            content.push_str(&format!("   - 言語: {}\n", module.language));
+        content.push_str(&format!("### {}\n\n", locale.text("architecture.key-modules")));
+        for (i, module) in index.modules_by_importance(10).into_iter().enumerate() {
+            content.push_str(&format!("{}. **{}**\n", i + 1, module_heading(module, config)));
+            content.push_str(&format!("   - {}: `{}`\n", locale.text("module.path"), module.path.display()));
+            content.push_str(&format!("   - {}: {}\n", locale.text("module.language"), module.language));
             if !module.dependencies.is_empty() {
-                content.push_str(&format!("   - 依存: {}\n", module.dependencies.join(", ")));
+                content.push_str(&format!("   - {}: {}\n", locale.text("module.dependencies"), module.dependencies.join(", ")));
             }
-            content.push_str("\n");
+            content.push('\n');
         }
         content.push_str("---\n\n");
 
@@ -463,92 +819,111 @@ optional = true
     }
 
     /// モジュールスライドを並列実行用に生成（静的メソッド、1ページ1センテンス形式）
-    /// tech-book-readerの50並列翻訳を参考に、16並列で日本語化処理
+    /// 各モジュールの日本語化はワーカープールで並列に処理
     async fn generate_modules_slide_parallel(
         index: &Index,
+        flavor: &str,
         config: &Config,
+        diagnostics: &Diagnostics,
+        templates: &Templates,
+        executor: &Executor,
+        locale: Locale,
     ) -> Result<String> {
         let mut content = String::new();
 
-        // 各モジュールごとに16並列で処理（tech-book-readerの50並列翻訳を参考）
+        // 各モジュールをワーカープールで並列に処理
         let mut module_handles = Vec::new();
-        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(16));
         let index_clone = index.clone();
         let config_clone = config.clone();
         
-        for module in index.modules.iter() {
+        // 重要度順に対象モジュールを選択
+        let selected_modules = index.modules_by_importance(config.slides.max_modules);
+        if selected_modules.len() < index.modules.len() {
+            diagnostics.warn(
+                "slides",
+                format!(
+                    "全{}モジュールのうち重要度の高い{}モジュールのみスライドにしました（slides.max-modules）",
+                    index.modules.len(),
+                    selected_modules.len()
+                ),
+            );
+        }
+        for module in selected_modules {
             let module = module.clone();
             let index_for_module = index_clone.clone();
             let config_for_module = config_clone.clone();
-            let permit = semaphore.clone();
+            let diagnostics = diagnostics.clone();
+            let templates = templates.clone();
+            let flavor = flavor.to_string();
             
-            let handle = tokio::spawn(async move {
-                let _permit = permit.acquire().await.unwrap();
+            let handle = executor.spawn(Priority::Module, async move {
                 let mut module_content = String::new();
+                let mut excerpt = None;
                 
                 // 各タスクで新しいSummarizerインスタンスを作成
-                let summarizer_for_module = Summarizer::new(config_for_module.clone());
+                let summarizer_for_module =
+                    Summarizer::new(config_for_module.clone()).with_diagnostics(diagnostics).with_templates(templates);
                 
                 // モジュールの要約を取得
                 let summary_result = summarizer_for_module
-                    .summarize(&index_for_module, "module", &module.path.to_string_lossy(), "detailed-ja")
+                    .summarize(&index_for_module, "module", &module.path.to_string_lossy(), &locale.style("detailed"))
                     .await?;
                 
                 // ファイル情報を取得してメソッドを抽出
                 if let Some(file_info) = index_for_module.files.iter().find(|f| f.path == module.path) {
-                    if let Some(file_content) = &file_info.content {
-                        let methods = summarizer_for_module.extract_methods_detailed(file_content, &file_info.language);
+                    if let Some(file_content) = index_for_module.file_content(file_info) {
+                        let methods = summarizer_for_module.extract_methods_detailed(&file_content, &file_info.language);
+                        let max_lines = config_for_module.slides.excerpt_lines;
+                        if max_lines > 0 {
+                            excerpt = key_method(&index_for_module, &module, &file_content, &methods).map(|method| {
+                                let (code, fence) = code_excerpt(method, max_lines, &flavor, locale.text("module.omitted"));
+                                serde_json::json!({ "name": method.qualified_name(), "code": code, "fence": fence })
+                            });
+                        }
                         
                         // 各メソッドごとに1ページ1センテンス形式でスライドを作成
                         for method in methods.iter() {
-                            // 日本語の説明を生成（英語コメントを翻訳）
-                            let doc_ja = if !method.documentation.is_empty() {
-                                summarizer_for_module.translate_doc_to_japanese(&method.documentation)
-                            } else {
-                                summarizer_for_module.infer_function_purpose_simple(&method.name)
-                            };
-                            
                             // 1ページ1センテンス形式：説明を1つのセンテンスにまとめる
-                            let description = format!("**{}**は、{}", method.name, doc_ja.trim_start_matches("この関数は").trim_start_matches("このメソッドは").trim());
-                            
-                            // スライドの開始
-                            module_content.push_str("---\n");
-                            module_content.push_str(&format!("## {}\n\n", method.name));
-                            
-                            // 1センテンスの説明
-                            module_content.push_str(&format!("{}\n\n", description));
-                            
-                            // コードブロック
-                            let code_lines: Vec<&str> = method.code_snippet.lines().collect();
-                            if code_lines.len() <= 20 {
-                                // 短いコードは全文表示
-                                module_content.push_str("```");
-                                module_content.push_str(&method.language);
-                                module_content.push_str("\n");
-                                module_content.push_str(&method.code_snippet);
-                                module_content.push_str("\n```\n\n");
-                            } else {
-                                // 長いコードは重要な部分だけ表示
-                                module_content.push_str("```");
-                                module_content.push_str(&method.language);
-                                module_content.push_str("\n");
-                                // 関数の定義部分（最初の5行）
-                                for line in code_lines.iter().take(5) {
-                                    module_content.push_str(line);
-                                    module_content.push_str("\n");
+                            let description = match locale {
+                                // 日本語の説明を生成（英語コメントを翻訳）
+                                Locale::Ja => {
+                                    let doc_ja = if !method.documentation.is_empty() {
+                                        summarizer_for_module.translate_doc_to_japanese(&method.documentation).await
+                                    } else {
+                                        summarizer_for_module.infer_function_purpose_simple(&method.name)
+                                    };
+                                    format!("**{}**は、{}", method.name, doc_ja.trim_start_matches("この関数は").trim_start_matches("このメソッドは").trim())
                                 }
-                                module_content.push_str("// ... (省略) ...\n");
-                                // 関数の終了部分（最後の3行）
-                                if code_lines.len() > 8 {
-                                    for line in code_lines.iter().skip(code_lines.len().saturating_sub(3)) {
-                                        module_content.push_str(line);
-                                        module_content.push_str("\n");
-                                    }
+                                // 英語ではドキュメントコメントを使う（日本語のコメントは翻訳、コメントがなければ説明を省く）
+                                Locale::En if method.documentation.trim().is_empty() => String::new(),
+                                Locale::En => {
+                                    let doc = localize(&summarizer_for_module, locale, method.documentation.trim().to_string()).await;
+                                    format!("**{}**: {}", method.name, doc.trim())
                                 }
-                                module_content.push_str("```\n\n");
-                            }
+                            };
                             
-                            module_content.push_str("---\n\n");
+                            // コードブロック（短いコードは全文、長いコードは定義部分の5行と終了部分の3行）
+                            let code_lines: Vec<&str> = method.code_snippet.lines().collect();
+                            let code = if code_lines.len() <= 20 {
+                                method.code_snippet.clone()
+                            } else {
+                                let mut shown: Vec<&str> = code_lines.iter().take(5).copied().collect();
+                                shown.push(locale.text("module.omitted"));
+                                shown.extend(code_lines.iter().skip(code_lines.len().saturating_sub(3)));
+                                shown.join("\n")
+                            };
+
+                            // 1ページに関数名・1センテンスの説明・コードブロック
+                            module_content.push_str(&summarizer_for_module.templates().render(
+                                "slides/method.md",
+                                &serde_json::json!({
+                                    "name": method.name,
+                                    "description": description,
+                                    "language": method.language,
+                                    "code": code,
+                                    "module": module.id,
+                                }),
+                            )?);
                         }
                     }
                 }
@@ -557,9 +932,6 @@ optional = true
                 if module_content.is_empty() || !module_content.ends_with("---\n\n") {
                     module_content.push_str("---\n");
                 }
-                module_content.push_str(&format!("## モジュール: {}\n\n", module.name));
-                module_content.push_str(&format!("**パス**: `{}`\n\n", module.path.display()));
-                module_content.push_str(&format!("**言語**: {}\n\n", module.language));
                 
                 // 要約を日本語で表示（1センテンスにまとめる）
                 let summary_lines: Vec<&str> = summary_result.content_md.lines().take(5).collect();
@@ -567,13 +939,33 @@ optional = true
                 for line in summary_lines {
                     if !line.trim().is_empty() && !line.starts_with('#') {
                         summary_text.push_str(line.trim());
-                        summary_text.push_str(" ");
+                        summary_text.push(' ');
                     }
                 }
-                if !summary_text.is_empty() {
-                    module_content.push_str(&format!("{}\n\n", summary_text.trim()));
-                }
-                module_content.push_str("\n---\n\n");
+                let summary = match summary_text.is_empty() {
+                    true => None,
+                    false => Some(localize(&summarizer_for_module, locale, summary_text.trim().to_string()).await.trim().to_string()),
+                };
+                module_content.push_str(&summarizer_for_module.templates().render(
+                    "slides/module.md",
+                    &serde_json::json!({
+                        "heading": locale.format("module.title", &[&module_heading(&module, &config_for_module)]),
+                        "module": {
+                            "id": module.id,
+                            "path": module.path.display().to_string(),
+                            "language": module.language,
+                            "component": module.component.as_str(),
+                            "icon": module.component.icon(),
+                        },
+                        "labels": {
+                            "path": locale.text("module.path"),
+                            "language": locale.text("module.language"),
+                            "excerpt": locale.text("module.excerpt"),
+                        },
+                        "summary": summary,
+                        "excerpt": excerpt,
+                    }),
+                )?);
                 
                 Ok::<String, anyhow::Error>(module_content)
             });
@@ -582,8 +974,10 @@ optional = true
         
         // すべてのモジュールスライドを16並列実行して結果を収集
         for handle in module_handles {
-            if let Ok(Ok(module_content)) = handle.await {
-                content.push_str(&module_content);
+            match handle.await {
+                Ok(Ok(module_content)) => content.push_str(&module_content),
+                Ok(Err(e)) => diagnostics.warn("slides", format!("モジュールのスライドを生成できませんでした: {}", e)),
+                Err(_) => {}
             }
         }
 
@@ -594,29 +988,24 @@ optional = true
     async fn generate_flows_slide_parallel(
         index: &Index,
         diagrammer: &Diagrammer,
+        locale: Locale,
     ) -> Result<String> {
         let mut content = String::new();
 
         content.push_str("---\n");
-        content.push_str("## システムフロー\n");
+        content.push_str(&format!("## {}\n", locale.text("flows.title")));
         content.push_str("---\n\n");
 
         // シーケンス図
         content.push_str("---\n");
-        content.push_str("### シーケンス図\n\n");
-        let diagram = diagrammer.generate_diagram(index, "sequence")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&format!("### {}\n\n", locale.text("flows.sequence")));
+        content.push_str(&diagram_markdown(diagrammer, index, "sequence"));
         content.push_str("---\n\n");
 
         // コールグラフ
         content.push_str("---\n");
-        content.push_str("### コールグラフ\n\n");
-        let diagram = diagrammer.generate_diagram(index, "call-graph")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&format!("### {}\n\n", locale.text("flows.call-graph")));
+        content.push_str(&diagram_markdown(diagrammer, index, "call-graph"));
         content.push_str("---\n\n");
 
         Ok(content)
@@ -626,29 +1015,29 @@ optional = true
     async fn generate_deploy_slide_parallel(
         index: &Index,
         diagrammer: &Diagrammer,
+        locale: Locale,
     ) -> Result<String> {
         let mut content = String::new();
 
         content.push_str("---\n");
-        content.push_str("## デプロイメント構成\n");
+        content.push_str(&format!("## {}\n", locale.text("deploy.title")));
         content.push_str("---\n\n");
 
         // デプロイメント図
-        let diagram = diagrammer.generate_diagram(index, "deployment")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&diagram_markdown(diagrammer, index, "deployment"));
         content.push_str("\n---\n\n");
 
         // エントリーポイント
         content.push_str("---\n");
-        content.push_str("### エントリーポイント\n\n");
-        if !index.entrypoints.is_empty() {
-            for ep in &index.entrypoints {
-                content.push_str(&format!("- `{}`\n", ep.display()));
+        content.push_str(&format!("### {}\n\n", locale.text("deploy.entrypoints")));
+        let entrypoints = index.all_entrypoints();
+        if !entrypoints.is_empty() {
+            for ep in &entrypoints {
+                content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
             }
         } else {
-            content.push_str("エントリーポイントが見つかりませんでした。\n");
+            content.push_str(locale.text("deploy.no-entrypoints"));
+            content.push('\n');
         }
         content.push_str("\n---\n\n");
 
@@ -660,7 +1049,8 @@ optional = true
         let mut content = String::new();
 
         content.push_str("---\n");
-        content.push_str(&format!("# {}\n\n", self.config.project.name));
+        content.push_str(&format!("# {}\n\n", deck_title(index, &self.config, Locale::Ja)));
+        content.push_str(&branding_lines(&self.config, Locale::Ja));
         content.push_str(&format!(
             "{}ファイル、{}言語、{}モジュール\n",
             index.stats.files,
@@ -670,10 +1060,7 @@ optional = true
         content.push_str("---\n\n");
 
         content.push_str("## 全体構成\n\n");
-        let diagram = self.diagrammer.generate_diagram(index, "module-graph")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&diagram_markdown(&self.diagrammer, index, "module-graph"));
 
         Ok(content)
     }
@@ -688,7 +1075,7 @@ optional = true
 
         content.push_str("### 主要モジュール\n\n");
         for module in &index.modules {
-            content.push_str(&format!("- **{}**\n", module.name));
+            content.push_str(&format!("- **{}**\n", module_heading(module, &self.config)));
         }
 
         Ok(content)
@@ -703,7 +1090,7 @@ optional = true
         content.push_str("---\n\n");
 
         for module in &index.modules {
-            content.push_str(&format!("### {}\n\n", module.name));
+            content.push_str(&format!("### {}\n\n", module_heading(module, &self.config)));
             content.push_str(&format!("パス: `{}`\n\n", module.path.display()));
             if !module.dependencies.is_empty() {
                 content.push_str("依存関係:\n");
@@ -726,10 +1113,7 @@ optional = true
         content.push_str("---\n\n");
 
         content.push_str("### シーケンス図\n\n");
-        let diagram = self.diagrammer.generate_diagram(index, "sequence")?;
-        if diagram.format == "mermaid" {
-            content.push_str(&format!("```mermaid\n{}\n```\n", diagram.content));
-        }
+        content.push_str(&diagram_markdown(&self.diagrammer, index, "sequence"));
 
         Ok(content)
     }
@@ -743,8 +1127,8 @@ optional = true
         content.push_str("---\n\n");
 
         content.push_str("### エントリーポイント\n\n");
-        for ep in &index.entrypoints {
-            content.push_str(&format!("- `{}`\n", ep.display()));
+        for ep in &index.all_entrypoints() {
+            content.push_str(&format!("- {}\n", ep.describe(&index.repo_path)));
         }
 
         Ok(content)
@@ -753,7 +1137,7 @@ optional = true
     /// Marpコンテンツを生成（非並列実行用、後方互換性のため保持）
     #[allow(dead_code)] // 後方互換性のため保持
     async fn generate_marp_content(&self, index: &Index, sections: &[String]) -> Result<String> {
-        let mut content = String::from("---\nmarp: true\ntheme: default\n---\n\n");
+        let mut content = self.marp_front_matter(Locale::Ja);
 
         for section in sections {
             match section.as_str() {
@@ -779,15 +1163,12 @@ optional = true
         Ok(content)
     }
 
-    /// セクション名を取得
-    fn get_section_name<'a>(&self, section: &'a str) -> &'a str {
-        match section {
-            "overview" => "概要",
-            "architecture" => "アーキテクチャ",
-            "modules" => "モジュール",
-            "flows" => "フロー",
-            "deploy" => "デプロイ",
-            _ => section,
+    /// セクション名を取得（カタログにないセクションはそのまま）
+    fn get_section_name(&self, section: &str, locale: Locale) -> String {
+        let key = format!("section.{}", section);
+        match locale.text(&key) {
+            name if name == key => section.to_string(),
+            name => name.to_string(),
         }
     }
 }
@@ -797,6 +1178,318 @@ optional = true
 pub struct SlideResult {
     pub ok: bool,
     pub files: Vec<SlideFile>,
+    /// 外部ツールがない・失敗したために生成しなかった成果物
+    #[serde(default)]
+    pub skipped: Vec<SkippedArtifact>,
+    /// ビルド中に記録した警告（共有した記録先の場合は解析などの警告を含む）
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 概要スライドのホットスポットの表に載せるファイル数
+const MAX_HOTSPOTS: usize = 5;
+
+/// スライドのタイトル（`[branding]` の製品名・タイトルを優先し、未指定の場合はリポジトリのディレクトリ名）
+fn deck_title(index: &Index, config: &Config, locale: Locale) -> String {
+    let branding = &config.branding;
+    if branding.product_name.is_some() || branding.title.is_some() {
+        return branding.title(&config.project.name);
+    }
+    index.repo_path.file_name().and_then(|n| n.to_str()).unwrap_or(locale.text("title.fallback")).to_string()
+}
+
+/// モジュールの見出し（`slides.icons` の場合はコンポーネントの種類のアイコンを前に付ける）
+fn module_heading(module: &ModuleInfo, config: &Config) -> String {
+    if config.slides.icons {
+        format!("{} {}", module.component.icon(), module.id)
+    } else {
+        module.id.clone()
+    }
+}
+
+/// タイトルスライドに載せるチーム・問い合わせ先（どちらも未設定の場合は空）
+fn branding_lines(config: &Config, locale: Locale) -> String {
+    let branding = &config.branding;
+    let mut lines = String::new();
+    if let Some(team) = &branding.team {
+        lines.push_str(&format!("**{}**\n\n", team));
+    }
+    if let Some(contact) = &branding.contact {
+        lines.push_str(&format!("{}\n\n", locale.format("title.contact", &[contact])));
+    }
+    lines
+}
+
+/// APIスライドの表に載せるエンドポイント数
+const MAX_ENDPOINTS: usize = 12;
+
+/// APIエンドポイントのスライド（REST・GraphQLを1つの表にまとめる）
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `locale` - スライドの言語
+///
+/// # 戻り値
+/// * `String` - スライドのMarkdown
+fn endpoint_slide(index: &Index, locale: Locale) -> String {
+    let mut content = format!("---\n## {}\n---\n\n", locale.text("api.title"));
+    if index.endpoints.is_empty() {
+        content.push_str(&format!("{}\n\n---\n\n", locale.text("api.none")));
+        return content;
+    }
+
+    content.push_str(&format!("{}\n|---|---|---|---|\n", locale.text("api.header")));
+    for endpoint in index.endpoints.iter().take(MAX_ENDPOINTS) {
+        let path = endpoint.file.strip_prefix(&index.repo_path).unwrap_or(&endpoint.file);
+        content.push_str(&format!(
+            "| {} | `{}` | {} | `{}` |\n",
+            endpoint.method,
+            endpoint.path,
+            endpoint.handler.as_deref().map_or_else(|| "-".to_string(), |h| format!("`{}`", h)),
+            path.display()
+        ));
+    }
+    if index.endpoints.len() > MAX_ENDPOINTS {
+        content.push_str(&format!("\n{}\n", locale.format("api.more", &[&(index.endpoints.len() - MAX_ENDPOINTS)])));
+    }
+    content.push_str("\n---\n\n");
+    content
+}
+
+/// 複雑度の高いファイルの表（複雑度が同じ場合は行数の多い順）
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `locale` - スライドの言語
+///
+/// # 戻り値
+/// * `String` - Markdownの表（関数を含むファイルがない場合は空）
+fn hotspot_table(index: &Index, locale: Locale) -> String {
+    let mut files: Vec<&analyzer_core::FileInfo> = index.files.iter().filter(|f| f.metrics.functions > 0).collect();
+    if files.is_empty() {
+        return String::new();
+    }
+    files.sort_by(|a, b| b.metrics.complexity.cmp(&a.metrics.complexity).then(b.metrics.lines.cmp(&a.metrics.lines)));
+
+    let mut table = format!("{}\n|---|---|---|---|\n", locale.text("hotspots.header"));
+    for file in files.into_iter().take(MAX_HOTSPOTS) {
+        let path = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+        table.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            path.display(),
+            file.metrics.lines,
+            file.metrics.functions,
+            file.metrics.complexity
+        ));
+    }
+    table
+}
+
+/// 空行を除いた先頭の行（要約の冒頭をスライドに載せる）
+fn non_empty_lines(markdown: &str, lines: usize) -> String {
+    markdown
+        .lines()
+        .take(lines)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// モジュールの主要な関数（他のファイル・モジュール内からの呼び出しの多い関数）
+///
+/// 重要度は、importしているファイルでの呼び出し数×3、モジュール内の呼び出し数、公開されていれば2、
+/// ドキュメントコメントがあれば1の和。同点の場合は先に定義された関数
+///
+/// # 引数
+/// * `index` - インデックス（importしているファイルの内容を読む）
+/// * `module` - モジュール
+/// * `content` - モジュールのファイル内容
+/// * `methods` - モジュールの関数・メソッド
+///
+/// # 戻り値
+/// * `Option<&MethodInfo>` - 主要な関数（関数がない場合はNone）
+fn key_method<'a>(index: &Index, module: &ModuleInfo, content: &str, methods: &'a [MethodInfo]) -> Option<&'a MethodInfo> {
+    let dependents: Vec<String> = index
+        .dependents_of(&module.path)
+        .into_iter()
+        .filter_map(|path| index.files.iter().find(|f| f.path == path))
+        .filter_map(|file| index.file_content(file).map(|c| c.into_owned()))
+        .collect();
+    let calls = |text: &str, name: &str| text.matches(&format!("{}(", name)).count();
+    methods
+        .iter()
+        .map(|method| {
+            let external: usize = dependents.iter().map(|text| calls(text, &method.name)).sum();
+            let internal = calls(content, &method.name).saturating_sub(1);
+            let score = external * 3
+                + internal
+                + if method.visibility == "public" { 2 } else { 0 }
+                + usize::from(!method.documentation.trim().is_empty());
+            (method, score)
+        })
+        .fold(None, |best: Option<(&MethodInfo, usize)>, (method, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((method, score)),
+        })
+        .map(|(method, _)| method)
+}
+
+/// 関数のコードの抜粋と、強調する行を付けたコードブロックの言語指定
+///
+/// シグネチャの行と `return` の行を強調する（mdbook-revealは `rs [1,4]`、Marpは `rs {1,4}`）。
+/// `max_lines` を超える場合は先頭から `max_lines - 1` 行と省略の行にする
+///
+/// # 引数
+/// * `method` - 関数
+/// * `max_lines` - 抜粋の最大行数（1以上）
+/// * `flavor` - フレーバー（mdbook-reveal|marp）
+/// * `omitted` - 省略した部分に置く行
+///
+/// # 戻り値
+/// * `(String, String)` - 抜粋したコードと、コードブロックの言語指定
+fn code_excerpt(method: &MethodInfo, max_lines: usize, flavor: &str, omitted: &str) -> (String, String) {
+    let lines: Vec<&str> = method.code_snippet.lines().collect();
+    let mut shown: Vec<&str> = match lines.len() > max_lines {
+        true => lines.iter().take(max_lines.saturating_sub(1)).copied().collect(),
+        false => lines.clone(),
+    };
+
+    // シグネチャ（`{`・`:`・`=>` で終わる行まで、最大3行）と戻り値の行
+    let signature_end = shown
+        .iter()
+        .take(3)
+        .position(|line| {
+            let line = line.trim_end();
+            line.ends_with('{') || line.ends_with(':') || line.contains("=>")
+        })
+        .unwrap_or(0);
+    let mut highlighted: Vec<usize> = (1..=signature_end + 1).filter(|n| *n <= shown.len()).collect();
+    for (i, line) in shown.iter().enumerate().skip(signature_end + 1) {
+        let line = line.trim_start();
+        if line == "return" || line.starts_with("return ") || line.starts_with("return;") {
+            highlighted.push(i + 1);
+        }
+    }
+    if shown.len() < lines.len() {
+        shown.push(omitted);
+    }
+
+    let mut ranges: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < highlighted.len() {
+        let start = highlighted[i];
+        while i + 1 < highlighted.len() && highlighted[i + 1] == highlighted[i] + 1 {
+            i += 1;
+        }
+        ranges.push(match highlighted[i] == start {
+            true => start.to_string(),
+            false => format!("{}-{}", start, highlighted[i]),
+        });
+        i += 1;
+    }
+    let fence = match (ranges.is_empty(), flavor) {
+        (true, _) => method.language.clone(),
+        (false, "marp") => format!("{} {{{}}}", method.language, ranges.join(",")),
+        (false, _) => format!("{} [{}]", method.language, ranges.join(",")),
+    };
+    (shown.join("\n"), fence)
+}
+
+/// 要約に残った日本語の文章をスライドの言語にする（英語に翻訳できない場合は日本語のまま）
+async fn localize(summarizer: &Summarizer, locale: Locale, text: String) -> String {
+    match locale {
+        Locale::Ja => text,
+        Locale::En => summarizer.translate_to_english(&text).await.unwrap_or(text),
+    }
+}
+
+/// スライドに埋め込む図のMarkdown
+/// 
+/// 生成できない図・Mermaid以外の形式の図は警告を記録して省略する。
+/// 
+/// # 引数
+/// * `diagrammer` - ダイアグラマー（警告はその記録先に残す）
+/// * `index` - インデックス
+/// * `diagram_type` - 図のタイプ
+/// 
+/// # 戻り値
+/// * `String` - 図のMarkdown（省略した場合は空文字列）
+fn diagram_markdown(diagrammer: &Diagrammer, index: &Index, diagram_type: &str) -> String {
+    match diagrammer.try_generate_diagram(index, diagram_type) {
+        Some(diagram) if diagram.format == "mermaid" => diagram.to_markdown(),
+        Some(diagram) => {
+            diagrammer.diagnostics().warn(
+                "slides",
+                format!("{}形式の{}はスライドに埋め込めないため省略しました", diagram.format, diagram_type),
+            );
+            String::new()
+        }
+        None => String::new(),
+    }
+}
+
+/// フレーバーとエクスポート形式に必要な外部ツールを確認
+/// 
+/// # 引数
+/// * `tools` - 外部ツールの実行方法
+/// * `flavor` - フレーバー（mdbook-reveal|marp）
+/// * `export` - エクスポート形式のリスト
+/// * `diagnostics` - ツールが見つからない場合の記録先
+/// 
+/// # 戻り値
+/// * `Option<ToolStatus>` - 必要なツールの状態（Markdownのみで完結する場合はNone）
+fn required_tool(tools: &Tools, flavor: &str, export: &[String], diagnostics: &Diagnostics) -> Option<ToolStatus> {
+    let name = match flavor {
+        "mdbook-reveal" => "mdbook",
+        "marp" if export.iter().any(|f| MARP_FORMATS.contains(&f.as_str())) => "marp",
+        _ => return None,
+    };
+    let tool = tools.check(name);
+    if !tool.available {
+        diagnostics.warn("slides", format!("{}が見つからないため、Markdownのみを生成しました", name));
+    }
+    Some(tool)
+}
+
+/// Marp CLIでエクスポートされるファイル（サイズ不明）
+fn marp_exports(marp_file: &str, export: &[String]) -> Vec<PlannedFile> {
+    export
+        .iter()
+        .filter(|format| MARP_FORMATS.contains(&format.as_str()))
+        .map(|format| PlannedFile::new(Path::new(marp_file).with_extension(format), None, "marp"))
+        .collect()
+}
+
+/// セクションの生成タスクの優先度
+///
+/// モジュールのセクションはモジュールごとのタスクを待つだけのため、ワーカープールの枠を使わない（None）
+fn section_priority(section: &str) -> Option<Priority> {
+    (section != "modules").then_some(Priority::Section)
+}
+
+/// セクションの生成タスクを起動（優先度がNoneの場合はワーカープールの外で実行）
+fn spawn_section<F>(executor: &Executor, priority: Option<Priority>, task: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    match priority {
+        Some(priority) => executor.spawn(priority, task),
+        None => tokio::spawn(task),
+    }
+}
+
+/// スライドのファイルを書き込む（親ディレクトリがなければ作成）
+fn write_slide(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content).with_context(|| format!("スライドファイルの書き込みに失敗しました: {:?}", path))
+}
+
+/// book.tomlに書くパス（区切りは `/`）
+fn toml_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
 }
 
 /// スライドファイル
@@ -814,7 +1507,222 @@ mod tests {
     fn test_get_section_name() {
         let config = Config::default();
         let builder = SlideBuilder::new(config);
-        assert_eq!(builder.get_section_name("overview"), "概要");
+        assert_eq!(builder.get_section_name("overview", Locale::Ja), "概要");
+        assert_eq!(builder.get_section_name("deploy", Locale::En), "Deployment");
+        assert_eq!(builder.get_section_name("custom", Locale::En), "custom");
     }
-}
 
+    #[test]
+    fn test_endpoint_slide_truncates_table() {
+        let endpoints = (0..MAX_ENDPOINTS + 2)
+            .map(|i| analyzer_core::ApiEndpoint {
+                method: "GET".to_string(),
+                path: format!("/items/{}", i),
+                handler: Some(format!("item{}", i)),
+                file: PathBuf::from("/repo/app/api.py"),
+                line: i + 1,
+                framework: "fastapi".to_string(),
+            })
+            .collect();
+        let index = Index { repo_path: PathBuf::from("/repo"), endpoints, ..Default::default() };
+
+        let content = endpoint_slide(&index, Locale::Ja);
+        assert!(content.contains("| GET | `/items/0` | `item0` | `app/api.py` |\n"));
+        assert!(content.contains("ほか2件"));
+        assert!(endpoint_slide(&Index::default(), Locale::Ja).contains("見つかりませんでした"));
+
+        let content = endpoint_slide(&index, Locale::En);
+        assert!(content.starts_with("---\n## API endpoints\n"));
+        assert!(content.contains("| Method | Path | Handler | File |\n"));
+        assert!(content.contains("and 2 more"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_slides_reports_generators() {
+        let mut config = Config::default();
+        config.summarization.mode = "local".to_string();
+        let builder = SlideBuilder::new(config);
+        let index = Index::default();
+        let sections = vec!["overview".to_string(), "flows".to_string()];
+        let export = vec!["pdf".to_string(), "docx".to_string()];
+
+        // LLMは呼び出さずに推定する
+        let files = builder.plan_slides(&index, "marp", &sections, &export, "ja").await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].generator, "llm-local");
+        assert!(files[0].estimated_bytes.unwrap() > 0);
+        assert_eq!(files[1].path, PathBuf::from("slides.pdf"));
+
+        let files = builder.plan_slides(&index, "mdbook-reveal", &sections, &export, "ja").await.unwrap();
+        let generators: Vec<&str> = files.iter().map(|f| f.generator.as_str()).collect();
+        assert_eq!(generators, vec!["static", "static", "llm-local", "static", "mdbook"]);
+        assert!(builder.plan_slides(&index, "marp", &sections, &export, "fr").await.is_err());
+
+        // 出力ディレクトリの構成を変更した場合
+        let mut config = Config::default();
+        config.slides.marp_file = "deck/talk.md".to_string();
+        config.slides.layout.src_dir = PathBuf::from("slides-src");
+        config.slides.layout.build_dir = PathBuf::from("site");
+        config.slides.layout.files.insert("flows".to_string(), "03-flows.md".to_string());
+        let builder = SlideBuilder::new(config);
+
+        let files = builder.plan_slides(&index, "marp", &sections, &export, "ja").await.unwrap();
+        assert_eq!(files[0].path, PathBuf::from("deck/talk.md"));
+        assert_eq!(files[1].path, PathBuf::from("deck/talk.pdf"));
+
+        let files = builder.plan_slides(&index, "mdbook-reveal", &sections, &export, "ja").await.unwrap();
+        let paths: Vec<String> = files.iter().map(|f| f.path.to_string_lossy().to_string()).collect();
+        assert_eq!(
+            paths,
+            vec!["book.toml", "slides-src/SUMMARY.md", "slides-src/overview.md", "slides-src/03-flows.md", "site/"]
+        );
+    }
+
+    #[test]
+    fn test_branding_in_title_and_marp_directives() {
+        let builder = SlideBuilder::new(Config::default());
+        assert_eq!(builder.marp_front_matter(Locale::Ja), "---\nmarp: true\ntheme: default\nlang: ja\n---\n\n");
+        let index = Index { repo_path: PathBuf::from("/work/acme-pay"), ..Default::default() };
+        assert_eq!(deck_title(&index, &Config::default(), Locale::Ja), "acme-pay");
+
+        let mut config = Config::default();
+        config.branding.product_name = Some("Acme Pay".to_string());
+        config.branding.team = Some("決済チーム".to_string());
+        config.branding.title = Some("{{product}} アーキテクチャ".to_string());
+        assert_eq!(deck_title(&index, &config, Locale::Ja), "Acme Pay アーキテクチャ");
+        assert_eq!(branding_lines(&config, Locale::Ja), "**決済チーム**\n\n");
+
+        let builder = SlideBuilder::new(config);
+        assert_eq!(
+            builder.marp_front_matter(Locale::En),
+            "---\nmarp: true\ntheme: default\nlang: en\nheader: \"Acme Pay アーキテクチャ\"\nfooter: \"Acme Pay — 決済チーム\"\n---\n\n"
+        );
+        assert!(builder.reveal_book_toml(Locale::Ja).contains("authors = [\"決済チーム\"]"));
+        assert!(builder.reveal_book_toml(Locale::En).contains("language = \"en\""));
+    }
+
+    #[tokio::test]
+    async fn test_module_headings_have_component_icons() {
+        let index = Index {
+            modules: vec![analyzer_core::ModuleInfo {
+                id: "src/jobs/mailer.py".to_string(),
+                component: analyzer_core::ComponentKind::Worker,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let content = SlideBuilder::new(Config::default()).generate_architecture_slide(&index).await.unwrap();
+        assert!(content.contains("- **⚙️ src/jobs/mailer.py**\n"), "{}", content);
+
+        let mut config = Config::default();
+        config.slides.icons = false;
+        let content = SlideBuilder::new(config).generate_architecture_slide(&index).await.unwrap();
+        assert!(content.contains("- **src/jobs/mailer.py**\n"), "{}", content);
+    }
+
+    #[test]
+    fn test_code_excerpt_highlights_signature_and_returns() {
+        let method = MethodInfo {
+            name: "total".to_string(),
+            language: "rs".to_string(),
+            code_snippet: "pub fn total(items: &[Item]) -> u64 {\n    if items.is_empty() {\n        return 0;\n    }\n    items.iter().map(|i| i.price).sum()\n}".to_string(),
+            ..Default::default()
+        };
+        let (code, fence) = code_excerpt(&method, 12, "mdbook-reveal", "// ...");
+        assert_eq!(code, method.code_snippet);
+        assert_eq!(fence, "rs [1,3]");
+        assert_eq!(code_excerpt(&method, 12, "marp", "// ...").1, "rs {1,3}");
+
+        let (code, fence) = code_excerpt(&method, 3, "marp", "// ...");
+        assert_eq!(code, "pub fn total(items: &[Item]) -> u64 {\n    if items.is_empty() {\n// ...");
+        assert_eq!(fence, "rs {1}");
+    }
+
+    #[tokio::test]
+    async fn test_module_slide_shows_key_function_excerpt() {
+        let repo = PathBuf::from("/repo");
+        let file = |path: &str, content: &str| analyzer_core::FileInfo {
+            path: repo.join(path),
+            language: "rs".to_string(),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: repo.clone(),
+            files: vec![
+                file("src/cart.rs", "fn helper() -> u64 {\n    1\n}\n\npub fn total() -> u64 {\n    helper()\n}\n"),
+                file("src/main.rs", "fn main() {\n    cart::total();\n    cart::total();\n}\n"),
+            ],
+            modules: vec![analyzer_core::ModuleInfo {
+                path: repo.join("src/cart.rs"),
+                id: "cart".to_string(),
+                language: "rs".to_string(),
+                ..Default::default()
+            }],
+            internal_dependencies: [(repo.join("src/main.rs"), vec![repo.join("src/cart.rs")])].into(),
+            ..Default::default()
+        };
+        let config = Config::default();
+        let render = |config: Config| {
+            let index = index.clone();
+            async move {
+                SlideBuilder::generate_modules_slide_parallel(
+                    &index,
+                    "marp",
+                    &config,
+                    &Diagnostics::new(),
+                    &Templates::builtin(),
+                    &Executor::new(1),
+                    Locale::Ja,
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        let content = render(config.clone()).await;
+        assert!(content.contains("**主要な関数**: `total`\n\n```rs {1}\npub fn total() -> u64 {\n    helper()\n}\n```\n"), "{}", content);
+
+        let mut config = config;
+        config.slides.excerpt_lines = 0;
+        assert!(!render(config).await.contains("主要な関数"));
+    }
+
+    #[test]
+    fn test_hotspot_table_orders_by_complexity() {
+        let file = |name: &str, complexity: usize| analyzer_core::FileInfo {
+            path: PathBuf::from(format!("/repo/src/{}.rs", name)),
+            metrics: analyzer_core::FileMetrics { lines: 10, functions: 1, complexity, ..Default::default() },
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("simple", 1), file("parser", 12), analyzer_core::FileInfo::default()],
+            ..Default::default()
+        };
+
+        let table = hotspot_table(&index, Locale::Ja);
+        assert_eq!(table.lines().nth(2), Some("| `src/parser.rs` | 10 | 1 | 12 |"));
+        assert_eq!(table.lines().count(), 4);
+        assert!(hotspot_table(&Index::default(), Locale::Ja).is_empty());
+    }
+
+    #[test]
+    fn test_missing_marp_skips_exports() {
+        let builder = SlideBuilder::new(Config::default());
+        let marp = ToolStatus {
+            name: "marp".to_string(),
+            available: false,
+            version: None,
+            install_hint: "npm install -g @marp-team/marp-cli".to_string(),
+        };
+        let export = vec!["html".to_string(), "pdf".to_string(), "docx".to_string()];
+
+        let result = builder.export_marp(Path::new("out/slides.md"), &export, Some(&marp)).unwrap();
+        assert!(result.ok);
+        assert!(result.files.is_empty());
+        let skipped: Vec<&Path> = result.skipped.iter().map(|a| a.path.as_path()).collect();
+        assert_eq!(skipped, vec![Path::new("out/slides.html"), Path::new("out/slides.pdf")]);
+        assert_eq!(required_tool(&Tools::system(), "marp", &["docx".to_string()], &Diagnostics::new()), None);
+    }
+}
@@ -0,0 +1,94 @@
+/*!
+ * スライドの言語
+ *
+ * 同じインデックスから日本語（社内レビュー用）と英語（社外向け）のスライドを作れるよう、
+ * スライドに書く固定の文言を言語ごとのカタログから引く
+ *
+ * 主な仕様:
+ * - 対応する言語は `config::SLIDE_LANGUAGES`（ja|en）、言語（`Locale`）は要約・Wikiと共有
+ * - 文言はキーで引き、`{}` は引数で順に置き換える
+ * - カタログにないキーはキーをそのまま返す
+ *
+ * 制限事項:
+ * - 要約は言語に合わせたスタイル（`concise-en` など）で生成し、残った日本語（抜き出したコメントなど）はSummarizer::translate_to_englishで翻訳する
+ * - 図のラベル（シーケンス図のメッセージなど）は翻訳しない
+ */
+
+use std::fmt::Display;
+
+use summarizer::Catalog;
+pub use summarizer::Locale;
+
+/// 文言のカタログ（キー、日本語、英語）
+const CATALOG: &Catalog = &[
+    ("section.overview", "概要", "Overview"),
+    ("section.architecture", "アーキテクチャ", "Architecture"),
+    ("section.modules", "モジュール", "Modules"),
+    ("section.api", "API", "API"),
+    ("section.flows", "フロー", "Flows"),
+    ("section.deploy", "デプロイ", "Deployment"),
+    ("section.placeholder", "セクションの内容", "Section content"),
+    ("title.fallback", "プロジェクト", "Project"),
+    ("title.contact", "問い合わせ: {}", "Contact: {}"),
+    ("overview.stats", "📊 **統計**: {}ファイル、{}言語、{}モジュール", "📊 **Stats**: {} files, {} languages, {} modules"),
+    ("overview.hotspots", "ホットスポット", "Hotspots"),
+    ("overview.structure", "全体構成", "System overview"),
+    ("hotspots.header", "| ファイル | 行数 | 関数数 | 複雑度 |", "| File | Lines | Functions | Complexity |"),
+    ("architecture.title", "アーキテクチャ概要", "Architecture overview"),
+    ("architecture.diagram", "モジュール構成図", "Module diagram"),
+    ("architecture.key-modules", "主要モジュール", "Key modules"),
+    ("module.title", "モジュール: {}", "Module: {}"),
+    ("module.path", "パス", "Path"),
+    ("module.language", "言語", "Language"),
+    ("module.dependencies", "依存", "Dependencies"),
+    ("module.omitted", "// ... (省略) ...", "// ... (omitted) ..."),
+    ("module.excerpt", "主要な関数", "Key function"),
+    ("flows.title", "システムフロー", "System flow"),
+    ("flows.sequence", "シーケンス図", "Sequence diagram"),
+    ("flows.call-graph", "コールグラフ", "Call graph"),
+    ("deploy.title", "デプロイメント構成", "Deployment"),
+    ("deploy.entrypoints", "エントリーポイント", "Entry points"),
+    ("deploy.no-entrypoints", "エントリーポイントが見つかりませんでした。", "No entry points were found."),
+    ("api.title", "APIエンドポイント", "API endpoints"),
+    ("api.none", "APIエンドポイントが見つかりませんでした。", "No API endpoints were found."),
+    ("api.header", "| メソッド | パス | ハンドラー | ファイル |", "| Method | Path | Handler | File |"),
+    ("api.more", "ほか{}件（Wikiの「API」を参照）", "and {} more (see the API page of the wiki)"),
+];
+
+/// スライドの文言
+pub(crate) trait SlideText {
+    /// 文言を取得（カタログにない場合はキー）
+    fn text(self, key: &str) -> &str;
+    /// 引数を `{}` に順に埋め込んだ文言を取得
+    fn format(self, key: &str, args: &[&dyn Display]) -> String;
+}
+
+impl SlideText for Locale {
+    fn text(self, key: &str) -> &str {
+        self.lookup(CATALOG, key)
+    }
+
+    fn format(self, key: &str, args: &[&dyn Display]) -> String {
+        self.lookup_format(CATALOG, key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_covers_languages() {
+        assert_eq!(Locale::parse("en").unwrap(), Locale::En);
+        assert!(Locale::parse("fr").is_err());
+        assert_eq!(config::SLIDE_LANGUAGES, [Locale::Ja.code(), Locale::En.code()]);
+
+        for (key, ja, en) in CATALOG {
+            assert!(!ja.is_empty() && !en.is_empty(), "{}", key);
+            assert_eq!(ja.matches("{}").count(), en.matches("{}").count(), "{}", key);
+        }
+        assert_eq!(Locale::En.format("overview.stats", &[&3, &2, &1]), "📊 **Stats**: 3 files, 2 languages, 1 modules");
+        assert_eq!(Locale::Ja.format("api.more", &[&4]), "ほか4件（Wikiの「API」を参照）");
+        assert_eq!(Locale::En.text("unknown.key"), "unknown.key");
+    }
+}
@@ -0,0 +1,73 @@
+//! mdbook・Marp CLIの失敗・タイムアウト・出力の欠けでスライドのビルドがMarkdownのみの出力に切り替わることの確認
+
+use std::path::Path;
+
+use analyzer_core::Index;
+use config::Config;
+use slides::{SlideBuilder, SlideResult};
+use summarizer::{FakeRun, FakeTools, Tools};
+
+async fn build_slides(tools: &FakeTools, repo: &Path, flavor: &str, export: &[&str]) -> SlideResult {
+    let index = Index { repo_path: repo.to_path_buf(), ..Default::default() };
+    let out_dir = repo.join("slides");
+    let export: Vec<String> = export.iter().map(|f| f.to_string()).collect();
+    SlideBuilder::new(Config::default())
+        .with_tools(Tools::new(tools.clone()))
+        .build_slides(&index, flavor, &out_dir.to_string_lossy(), &["overview".to_string()], &export, "ja")
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_marp_skips_only_failed_formats() {
+    let repo = tempfile::tempdir().unwrap();
+    let out = repo.path().join("slides");
+    let tools = FakeTools::new().with("marp", FakeRun::success().writing(out.join("slides.html")));
+    let result = build_slides(&tools, repo.path(), "marp", &["html"]).await;
+    assert_eq!(result.files.len(), 1);
+    assert!(result.skipped.is_empty());
+
+    // 失敗した場合は書き出したファイルがあってもスキップする
+    let tools = FakeTools::new().with("marp", FakeRun::failure("[ERROR] Failed converting Markdown.").writing(out.join("slides.html")));
+    let result = build_slides(&tools, repo.path(), "marp", &["html", "pdf"]).await;
+    assert!(result.ok);
+    assert!(result.files.is_empty());
+    let skipped: Vec<&str> = result.skipped.iter().map(|a| a.reason.as_str()).collect();
+    assert_eq!(skipped, vec!["marpが失敗しました: [ERROR] Failed converting Markdown."; 2]);
+    assert_eq!(tools.calls().len(), 2);
+    assert!(out.join("slides.md").exists());
+
+    let tools = FakeTools::new().with("marp", FakeRun::timeout());
+    let result = build_slides(&tools, repo.path(), "marp", &["pptx"]).await;
+    assert!(result.skipped[0].reason.starts_with("marpが時間内に終了しませんでした"), "{:?}", result.skipped);
+    assert_eq!(result.diagnostics.iter().filter(|d| d.message.starts_with("Marp CLIのエクスポートに失敗しました（形式: pptx）")).count(), 1);
+}
+
+#[tokio::test]
+async fn test_marp_partial_output_is_skipped() {
+    let repo = tempfile::tempdir().unwrap();
+    let out = repo.path().join("slides");
+    let tools = FakeTools::new().with("marp", FakeRun::success().writing(out.join("slides.html")));
+    let result = build_slides(&tools, repo.path(), "marp", &["html", "pdf"]).await;
+    let files: Vec<&str> = result.files.iter().map(|f| f.format.as_str()).collect();
+    assert_eq!(files, vec!["html"]);
+    assert_eq!(result.skipped.len(), 1);
+    assert_eq!(result.skipped[0].path, out.join("slides.pdf"));
+    assert!(result.skipped[0].reason.starts_with("marpの出力が見つかりません"));
+}
+
+#[tokio::test]
+async fn test_reveal_keeps_markdown_when_mdbook_fails() {
+    let repo = tempfile::tempdir().unwrap();
+    let tools = FakeTools::new().with("mdbook", FakeRun::failure("error: preprocessor reveal not found"));
+    let result = build_slides(&tools, repo.path(), "mdbook-reveal", &[]).await;
+    assert!(result.ok);
+    assert!(result.files.is_empty());
+    assert_eq!(result.skipped[0].tool, "mdbook");
+    assert!(repo.path().join("slides/src/SUMMARY.md").exists());
+
+    let tools = FakeTools::new().with("mdbook", FakeRun::success().writing("book/index.html"));
+    let result = build_slides(&tools, repo.path(), "mdbook-reveal", &[]).await;
+    assert_eq!(result.files.len(), 1);
+    assert!(result.skipped.is_empty());
+}
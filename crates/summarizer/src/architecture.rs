@@ -0,0 +1,130 @@
+/*!
+ * アーキテクチャの推定（静的な分類とローカルLLMの併用）
+ *
+ * モジュールの層をインデックスの名前・import・内部依存から推定し（`Index::architecture`）、
+ * 静的に分類できなかったモジュールをローカルLLMで分類し直す
+ *
+ * 主な仕様:
+ * - LLMにはモジュールID・importの先頭・宣言されたシンボル名の先頭を渡し、`<モジュールID>: <層>` の行で答えさせる
+ * - LLMに渡すモジュールは最大 `MAX_LLM_MODULES` 件（重要度の高い順）
+ * - 応答のうち、一覧にないモジュール・層の名前でない行は無視する
+ *
+ * 制限事項:
+ * - LLMがない場合・失敗した場合は静的な分類のみ（分類できなかったモジュールは `other` のまま）
+ * - 静的に分類できたモジュールはLLMで見直さない
+ */
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use analyzer_core::{ArchitectureModel, Index, LayerBasis, LAYERS};
+
+use crate::Summarizer;
+
+/// LLMで分類するモジュールの最大数
+const MAX_LLM_MODULES: usize = 40;
+/// LLMに渡すimport・シンボル名の最大数（それぞれ）
+const MAX_HINTS: usize = 8;
+
+impl Summarizer {
+    /// モジュールを層に分類したアーキテクチャを推定
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    ///
+    /// # 戻り値
+    /// * `ArchitectureModel` - モジュールの層と層の間の依存（ローカルLLMがある場合は分類できなかったモジュールを補う）
+    pub async fn infer_architecture(&self, index: &Index) -> ArchitectureModel {
+        let mut model = index.architecture();
+        let Some(llm) = self.llm.as_ref() else {
+            return model;
+        };
+        let mut unclassified: Vec<&analyzer_core::ModuleInfo> = index
+            .modules
+            .iter()
+            .filter(|m| model.layer_of(&m.path) == Some("other"))
+            .collect();
+        if unclassified.is_empty() {
+            return model;
+        }
+        unclassified.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+        unclassified.truncate(MAX_LLM_MODULES);
+
+        let mut listing = String::new();
+        for module in &unclassified {
+            let file = index.files.iter().find(|f| f.path == module.path);
+            let imports: Vec<&str> = file.map(|f| f.dependencies.iter().take(MAX_HINTS).map(String::as_str).collect()).unwrap_or_default();
+            let symbols: Vec<&str> = file.map(|f| f.symbols.iter().take(MAX_HINTS).map(|s| s.name.as_str()).collect()).unwrap_or_default();
+            listing.push_str(&format!("- {} (imports: {}; symbols: {})\n", module.id, imports.join(", "), symbols.join(", ")));
+        }
+        let prompt = format!(
+            "Classify each module of a software repository into one architectural layer.\n\
+             Layers: ui (user interface), api (API handlers and routing), domain (business logic and services), \
+             persistence (database and storage access), utils (shared helpers), other.\n\
+             Answer with one line per module in the form `<module id>: <layer>` and nothing else.\n\n{}",
+            listing
+        );
+        match llm.generate(&prompt).await {
+            Ok(response) => {
+                let ids: HashMap<&str, PathBuf> = unclassified.iter().map(|m| (m.id.as_str(), m.path.clone())).collect();
+                let changes = parse_layers(&response, &ids);
+                if changes.is_empty() {
+                    self.diagnostics.warn("summarizer", "ローカルLLMの応答からモジュールの層を読み取れませんでした");
+                }
+                model.reclassify(index, &changes, LayerBasis::Llm);
+            }
+            Err(e) => self.diagnostics.warn("summarizer", format!("ローカルLLMでのモジュールの層の分類に失敗しました: {}", e)),
+        }
+        model
+    }
+}
+
+/// LLMの応答から `<モジュールID>: <層>` の行を読み取る
+///
+/// # 引数
+/// * `response` - LLMの応答
+/// * `ids` - 分類を依頼したモジュールID → パス
+///
+/// # 戻り値
+/// * `Vec<(PathBuf, String)>` - モジュールのパスと層（一覧にないモジュール・不明な層の行は除く）
+fn parse_layers(response: &str, ids: &HashMap<&str, PathBuf>) -> Vec<(PathBuf, String)> {
+    response
+        .lines()
+        .filter_map(|line| {
+            // モジュールIDに `:` を含む場合（`web:src/index.ts`）があるため最後の `:` で分ける
+            let (id, layer) = line.trim().trim_start_matches(['-', '*', ' ']).rsplit_once(':')?;
+            let id = id.trim().trim_matches('`');
+            let layer = layer.trim().trim_matches(['`', '.']).to_lowercase();
+            let path = ids.get(id)?;
+            (LAYERS.contains(&layer.as_str()) || layer == "other").then(|| (path.clone(), layer))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_layers() {
+        let ids: HashMap<&str, PathBuf> = [("web:src/index.ts", PathBuf::from("/r/web/src/index.ts")), ("src/jobs.py", PathBuf::from("/r/src/jobs.py"))]
+            .into_iter()
+            .collect();
+        let response = "- `web:src/index.ts`: UI\nsrc/jobs.py: domain.\nsrc/unknown.rs: api\nsrc/jobs.py: backend\n";
+        assert_eq!(
+            parse_layers(response, &ids),
+            vec![(PathBuf::from("/r/web/src/index.ts"), "ui".to_string()), (PathBuf::from("/r/src/jobs.py"), "domain".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_infer_architecture_without_llm_is_static() {
+        let index = Index {
+            modules: vec![analyzer_core::ModuleInfo { path: PathBuf::from("src/utils.rs"), id: "src/utils.rs".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        let model = Summarizer::new(config::Config::default()).infer_architecture(&index).await;
+        assert_eq!(model, index.architecture());
+        assert_eq!(model.layer("utils").len(), 1);
+    }
+}
@@ -0,0 +1,489 @@
+/*!
+ * インデックスに対する質問応答（`ask`）
+ *
+ * 「認証はどこで行われていますか？」のような質問から関連するコードを検索し、引用付きの回答を作る
+ * - ローカルLLM（`summarization.mode = "local"`）が使える場合は、検索した抜粋をもとにLLMが回答を書く
+ * - それ以外（オフライン）は、検索した抜粋を関連度順に並べて回答とする
+ *
+ * 主な仕様:
+ * - 質問から英数字の単語と漢字・カタカナの語を取り出し、よく使う日本語の用語は英語のキーワードにも展開する（例: 認証 → auth, login）
+ * - ファイルパス・シンボル名・ファイル内容での一致からファイルの関連度を計算する
+ * - インデックスに埋め込みベクトル（`index.embeddings`）がある場合は意味検索のチャンクも取り出し、
+ *   キーワードの順位と合わせて（Reciprocal Rank Fusion）抜粋を選ぶ
+ * - 抜粋はキーワードを最も多く含む行を囲む関数（`MAX_PASSAGE_LINES` 行以内）、なければ前後数行
+ * - 回答の根拠は要約と同じ脚注形式の引用（`project.source-url` があればリンク）
+ *
+ * 制限事項:
+ * - 埋め込みベクトルのないインデックス（`index.embeddings = "none"`、古いインデックス）ではキーワードの一致のみで検索する
+ * - LLMの回答が抜粋に基づいているかは検証しない（根拠として抜粋の一覧を併記する）
+ */
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use analyzer_core::{Diagnostic, FileInfo, Index, SearchHit};
+
+use crate::{Citation, Summarizer};
+
+/// 回答に使う抜粋の最大数
+const MAX_PASSAGES: usize = 5;
+/// 関数全体を抜粋とする最大行数（これより長い場合は一致した行の前後のみ）
+const MAX_PASSAGE_LINES: usize = 30;
+/// 関数に含まれない場合に抜粋する一致した行の前後の行数
+const CONTEXT_LINES: usize = 3;
+/// 1つのキーワードについて数えるファイル内容での一致の上限
+const MAX_CONTENT_MATCHES: usize = 5;
+/// Reciprocal Rank Fusionの定数（上位の順位の差を緩やかにする）
+const RRF_K: f64 = 60.0;
+
+/// 日本語の用語と、コードで使われる英語のキーワード
+const QUERY_SYNONYMS: &[(&str, &[&str])] = &[
+    ("認証", &["auth", "login", "token", "session", "credential"]),
+    ("認可", &["authorize", "permission", "role"]),
+    ("権限", &["permission", "role", "acl"]),
+    ("設定", &["config", "setting", "env"]),
+    ("データベース", &["database", "db", "sql"]),
+    ("保存", &["save", "store", "persist", "write"]),
+    ("読み込", &["load", "read", "parse"]),
+    ("検索", &["search", "query", "index"]),
+    ("ルーティング", &["route", "router", "handler"]),
+    ("リクエスト", &["request", "http", "handler"]),
+    ("エラー", &["error", "err", "exception"]),
+    ("ログ", &["log", "logger", "tracing"]),
+    ("キャッシュ", &["cache"]),
+    ("テスト", &["test"]),
+    ("通知", &["notify", "notification", "webhook"]),
+    ("公開", &["publish", "deploy"]),
+    ("起動", &["main", "start", "run"]),
+    ("エントリーポイント", &["main", "entry"]),
+    ("ビルド", &["build"]),
+    ("解析", &["analyze", "parse"]),
+    ("要約", &["summar"]),
+    ("図", &["diagram", "mermaid"]),
+    ("スライド", &["slide"]),
+    ("暗号", &["crypt", "encrypt", "hash"]),
+    ("ユーザー", &["user"]),
+];
+
+/// 質問から除く英単語
+const STOP_WORDS: &[&str] = &[
+    "the", "is", "are", "was", "where", "what", "how", "why", "when", "which", "who", "does", "do", "did", "in", "of",
+    "to", "for", "and", "or", "on", "at", "by", "with", "this", "that", "it", "be", "an", "can", "done", "used",
+];
+
+/// 質問応答の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AskResult {
+    pub ok: bool,
+    pub answer_md: String,
+    /// 回答の根拠となったソースコードの範囲（answer_mdの脚注と対応）
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    /// 回答の作り方（llm-local|excerpt）
+    pub mode: String,
+    /// 回答中に記録した警告（LLMの失敗など）
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 検索した抜粋
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Passage {
+    pub path: PathBuf,
+    pub language: String,
+    /// 抜粋を含む関数・型（`kind name`）
+    pub symbol: Option<String>,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり、この行を含む）
+    pub end_line: usize,
+    pub text: String,
+    pub score: usize,
+}
+
+impl Summarizer {
+    /// インデックスに対する質問に回答
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `question` - 質問（日本語・英語）
+    ///
+    /// # 戻り値
+    /// * `Result<AskResult>` - 引用付きの回答、またはエラー
+    pub async fn ask(&self, index: &Index, question: &str) -> Result<AskResult> {
+        let terms = query_terms(question);
+        let mut passages = retrieve(index, &terms, MAX_PASSAGES);
+        if index.vectors.is_some() {
+            match index.semantic_search(question, MAX_PASSAGES).await {
+                Ok(hits) => {
+                    let semantic = hits
+                        .iter()
+                        .filter_map(|hit| passage_of_chunk(index, hit, hit.start_line?, hit.end_line?))
+                        .collect();
+                    passages = fuse(passages, semantic, MAX_PASSAGES);
+                }
+                Err(e) => self.diagnostics.warn("summarizer", format!("意味検索に失敗したためキーワードの一致のみで回答しました: {:#}", e)),
+            }
+        }
+        if passages.is_empty() {
+            return Ok(AskResult {
+                ok: false,
+                answer_md: format!(
+                    "「{}」に関連する箇所は見つかりませんでした。関数名・ファイル名などのキーワードを含めて質問してください。\n",
+                    question.trim()
+                ),
+                citations: Vec::new(),
+                mode: "excerpt".to_string(),
+                diagnostics: self.diagnostics.entries(),
+            });
+        }
+
+        let citations: Vec<Citation> =
+            passages.iter().map(|p| self.cite(index, &p.path, p.start_line, p.end_line)).collect();
+
+        if let Some(llm) = &self.llm {
+            let prompt = build_ask_prompt(question, index, &passages);
+            match llm.generate(&prompt).await {
+                Ok(answer) if !answer.is_empty() => {
+                    let mut answer_md = format!("{}\n\n## 根拠\n\n", answer);
+                    for (i, (passage, citation)) in passages.iter().zip(&citations).enumerate() {
+                        answer_md.push_str(&format!("{}. {}{}\n", i + 1, describe_passage(index, passage), citation.marker()));
+                    }
+                    answer_md.push_str(&self.render_footnotes(&citations));
+                    return Ok(AskResult {
+                        ok: true,
+                        answer_md,
+                        citations,
+                        mode: "llm-local".to_string(),
+                        diagnostics: self.diagnostics.entries(),
+                    });
+                }
+                Ok(_) => self.diagnostics.warn("summarizer", "ローカルLLMの応答が空のため抜粋で回答しました"),
+                Err(e) => self
+                    .diagnostics
+                    .warn("summarizer", format!("ローカルLLMでの回答に失敗したため抜粋で回答しました: {}", e)),
+            }
+        }
+
+        let mut answer_md = format!(
+            "「{}」に最も関連するのは{}です（キーワードの一致による抜粋）。\n\n",
+            question.trim(),
+            describe_passage(index, &passages[0])
+        );
+        for (i, (passage, citation)) in passages.iter().zip(&citations).enumerate() {
+            answer_md.push_str(&format!("### {}. {}{}\n\n", i + 1, describe_passage(index, passage), citation.marker()));
+            answer_md.push_str(&format!("```{}\n{}\n```\n\n", passage.language, passage.text.trim_end()));
+        }
+        answer_md.push_str(self.render_footnotes(&citations).trim_start());
+        Ok(AskResult {
+            ok: true,
+            answer_md,
+            citations,
+            mode: "excerpt".to_string(),
+            diagnostics: self.diagnostics.entries(),
+        })
+    }
+}
+
+/// 質問から検索キーワードを取り出す（小文字、重複なし）
+///
+/// # 引数
+/// * `question` - 質問
+///
+/// # 戻り値
+/// * `Vec<String>` - キーワード（英単語、漢字・カタカナの語、日本語の用語を展開した英語）
+pub(crate) fn query_terms(question: &str) -> Vec<String> {
+    let lower = question.to_lowercase();
+    let mut terms: Vec<String> = Vec::new();
+    let mut push = |term: &str| {
+        if !terms.iter().any(|t| t == term) {
+            terms.push(term.to_string());
+        }
+    };
+
+    for word in lower.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+        if word.len() >= 2 && !STOP_WORDS.contains(&word) {
+            push(word);
+        }
+    }
+
+    // 漢字・カタカナの連続（ひらがなは助詞・語尾として区切りに使う）
+    let mut word = String::new();
+    for c in lower.chars().chain(std::iter::once(' ')) {
+        if is_kanji(c) || is_katakana(c) {
+            word.push(c);
+        } else {
+            if word.chars().count() >= 2 {
+                push(&word);
+            }
+            word.clear();
+        }
+    }
+
+    for (japanese, english) in QUERY_SYNONYMS {
+        if lower.contains(japanese) {
+            for term in *english {
+                push(term);
+            }
+        }
+    }
+    terms
+}
+
+/// キーワードに関連する抜粋を検索
+///
+/// # 引数
+/// * `index` - インデックス
+/// * `terms` - キーワード（小文字）
+/// * `k` - 返す抜粋の最大数
+///
+/// # 戻り値
+/// * `Vec<Passage>` - 関連度の高い順の抜粋（1ファイル1件）
+pub(crate) fn retrieve(index: &Index, terms: &[String], k: usize) -> Vec<Passage> {
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    let mut passages: Vec<Passage> = index
+        .files
+        .iter()
+        .filter_map(|file| {
+            let content = index.file_content(file)?;
+            let score = file_score(index, file, &content, terms);
+            (score > 0).then(|| passage_of(file, &content, terms, score))
+        })
+        .collect();
+    passages.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+    passages.truncate(k);
+    passages
+}
+
+/// 意味検索でヒットしたチャンクを抜粋にする
+///
+/// チャンクに収まる最小の関数・型があればその名前を付け、長いチャンクは先頭の `MAX_PASSAGE_LINES` 行に切り詰める
+fn passage_of_chunk(index: &Index, hit: &SearchHit, start_line: usize, end_line: usize) -> Option<Passage> {
+    let file = index.files.iter().find(|f| f.path.to_string_lossy() == hit.path)?;
+    let content = index.file_content(file)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let start_line = start_line.max(1);
+    let end_line = end_line.min(lines.len()).min(start_line + MAX_PASSAGE_LINES - 1);
+    if start_line > end_line {
+        return None;
+    }
+    let symbol = file
+        .symbols
+        .iter()
+        .filter(|s| s.kind != "module" && s.kind != "impl")
+        .filter(|s| start_line <= s.start_line && s.end_line <= end_line)
+        .max_by_key(|s| s.end_line - s.start_line);
+    Some(Passage {
+        path: file.path.clone(),
+        language: file.language.clone(),
+        symbol: symbol.map(|s| format!("{} {}", s.kind, s.name)),
+        start_line,
+        end_line,
+        text: lines[start_line - 1..end_line].join("\n"),
+        score: 0,
+    })
+}
+
+/// キーワード検索と意味検索の抜粋を順位で合わせる（Reciprocal Rank Fusion、1ファイル1件）
+///
+/// # 引数
+/// * `keyword` - キーワードの一致による抜粋（関連度順）
+/// * `semantic` - 意味検索による抜粋（類似度順）
+/// * `k` - 返す抜粋の最大数
+///
+/// # 戻り値
+/// * `Vec<Passage>` - 合計スコアの高い順の抜粋（同じファイルはキーワード検索の抜粋を優先）
+pub(crate) fn fuse(keyword: Vec<Passage>, semantic: Vec<Passage>, k: usize) -> Vec<Passage> {
+    let mut fused: Vec<(f64, Passage)> = Vec::new();
+    for list in [keyword, semantic] {
+        for (rank, passage) in list.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            match fused.iter_mut().find(|(_, p)| p.path == passage.path) {
+                Some((total, _)) => *total += score,
+                None => fused.push((score, passage)),
+            }
+        }
+    }
+    // 同点は先に追加した（キーワード検索の）抜粋を優先する
+    fused.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    fused.into_iter().take(k).map(|(_, passage)| passage).collect()
+}
+
+/// ファイルの関連度（パス > シンボル名 > 内容の順に重み付け、テストファイルは半分）
+fn file_score(index: &Index, file: &FileInfo, content: &str, terms: &[String]) -> usize {
+    let path = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path).to_string_lossy().to_lowercase();
+    let content = content.to_lowercase();
+    let score: usize = terms
+        .iter()
+        .map(|term| {
+            let in_path = if path.contains(term.as_str()) { 6 } else { 0 };
+            let in_symbols =
+                file.symbols.iter().filter(|s| s.name.to_lowercase().contains(term.as_str())).count().min(3) * 3;
+            let in_content = content.matches(term.as_str()).count().min(MAX_CONTENT_MATCHES);
+            in_path + in_symbols + in_content
+        })
+        .sum();
+    if file.is_test { score / 2 } else { score }
+}
+
+/// キーワードを最も多く含む行の周辺を抜粋
+fn passage_of(file: &FileInfo, content: &str, terms: &[String], score: usize) -> Passage {
+    let lines: Vec<&str> = content.lines().collect();
+    let matches = |line: &str| {
+        let line = line.to_lowercase();
+        terms.iter().filter(|t| line.contains(t.as_str())).count()
+    };
+    // 一致数が同じ場合は先頭に近い行
+    let best = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (matches(line), i))
+        .max_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)))
+        .map_or(0, |(_, i)| i);
+    let line_number = best + 1;
+
+    let symbol = file
+        .symbols
+        .iter()
+        .filter(|s| s.kind != "module" && s.kind != "impl")
+        .filter(|s| s.start_line <= line_number && line_number <= s.end_line)
+        .filter(|s| s.end_line - s.start_line < MAX_PASSAGE_LINES)
+        .min_by_key(|s| s.end_line - s.start_line);
+    let (start_line, end_line) = match symbol {
+        Some(symbol) => (symbol.start_line, symbol.end_line),
+        None => (
+            line_number.saturating_sub(CONTEXT_LINES).max(1),
+            (line_number + CONTEXT_LINES).min(lines.len().max(1)),
+        ),
+    };
+
+    Passage {
+        path: file.path.clone(),
+        language: file.language.clone(),
+        symbol: symbol.map(|s| format!("{} {}", s.kind, s.name)),
+        start_line,
+        end_line,
+        text: lines.get(start_line - 1..end_line.min(lines.len())).unwrap_or_default().join("\n"),
+        score,
+    }
+}
+
+/// 抜粋の説明（`path` の `kind name`）
+fn describe_passage(index: &Index, passage: &Passage) -> String {
+    let path = relative(index, &passage.path);
+    match &passage.symbol {
+        Some(symbol) => format!("`{}` の `{}`", path, symbol),
+        None => format!("`{}`（{}〜{}行目）", path, passage.start_line, passage.end_line),
+    }
+}
+
+/// 質問応答用のプロンプトを作成
+fn build_ask_prompt(question: &str, index: &Index, passages: &[Passage]) -> String {
+    let mut prompt = format!(
+        "あなたはこのリポジトリに詳しいソフトウェアエンジニアです。\n\
+         以下のコードの抜粋だけをもとに、質問に日本語で2〜5文で回答してください。\n\
+         根拠にした抜粋は [1] のように番号で示してください。\n\
+         抜粋から読み取れないことは推測で書かず、分からないと答えてください。\n\n\
+         ## 質問\n\n{}\n\n## コードの抜粋\n",
+        question.trim()
+    );
+    for (i, passage) in passages.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n[{}] {}（{}〜{}行目）\n```{}\n{}\n```\n",
+            i + 1,
+            relative(index, &passage.path),
+            passage.start_line,
+            passage.end_line,
+            passage.language,
+            passage.text
+        ));
+    }
+    prompt
+}
+
+/// リポジトリルートからの相対パス
+fn relative(index: &Index, path: &Path) -> String {
+    path.strip_prefix(&index.repo_path).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+fn is_kanji(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c)
+}
+
+fn is_katakana(c: char) -> bool {
+    ('\u{30A0}'..='\u{30FF}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::Config;
+
+    #[test]
+    fn test_query_terms_expands_japanese() {
+        let terms = query_terms("認証はどこで行われていますか？ JWT handler");
+        assert_eq!(terms[..3], ["jwt", "handler", "認証"]);
+        assert!(terms.contains(&"auth".to_string()));
+        assert!(!terms.iter().any(|t| t == "行"));
+    }
+
+    #[test]
+    fn test_ask_answers_with_excerpts() {
+        let file = |path: &str, content: &str, symbols: Vec<analyzer_core::Symbol>| FileInfo {
+            path: Path::new("/repo").join(path),
+            language: "rs".to_string(),
+            symbols,
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let verify = analyzer_core::Symbol {
+            kind: "function".to_string(),
+            name: "verify_token".to_string(),
+            start_line: 3,
+            end_line: 5,
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![
+                file("src/main.rs", "fn main() {\n    run();\n}\n", Vec::new()),
+                file("src/auth.rs", "use jwt;\n\npub fn verify_token(token: &str) -> bool {\n    jwt::decode(token).is_ok()\n}\n", vec![verify]),
+            ],
+            ..Default::default()
+        };
+
+        let summarizer = Summarizer::new(Config::default());
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(summarizer.ask(&index, "認証はどこで行われていますか？")).unwrap();
+        assert_eq!(result.mode, "excerpt");
+        assert!(result.answer_md.starts_with("「認証はどこで行われていますか？」に最も関連するのは`src/auth.rs` の `function verify_token`です"));
+        assert_eq!(result.citations.len(), 1);
+        assert_eq!((result.citations[0].start_line, result.citations[0].end_line), (3, 5));
+        assert!(result.answer_md.contains("[^src/auth.rs-L3-L5]: `src/auth.rs#L3-L5`"));
+
+        let missing = rt.block_on(summarizer.ask(&index, "決済")).unwrap();
+        assert!(!missing.ok);
+    }
+
+    #[test]
+    fn test_fuse_ranks_passages_found_by_both() {
+        let passage = |path: &str| Passage {
+            path: PathBuf::from(path),
+            language: "rs".to_string(),
+            symbol: None,
+            start_line: 1,
+            end_line: 1,
+            text: String::new(),
+            score: 0,
+        };
+        let keyword = vec![passage("a.rs"), passage("b.rs"), passage("c.rs")];
+        let semantic = vec![passage("c.rs"), passage("d.rs")];
+
+        let fused: Vec<PathBuf> = fuse(keyword, semantic, 3).into_iter().map(|p| p.path).collect();
+        assert_eq!(fused, vec![PathBuf::from("c.rs"), PathBuf::from("a.rs"), PathBuf::from("b.rs")]);
+    }
+}
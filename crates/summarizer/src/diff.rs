@@ -0,0 +1,419 @@
+/*!
+ * インデックスの差分の要約（`summarize_diff`）
+ *
+ * 2つのインデックス（コミットごと・スナップショットごと）を比べ、何が変わったかをMarkdownの変更履歴にする
+ * - WikiやリリースのスライドにそのままMarkdownの節として貼れる形式（`## 変更点` から始まる）
+ *
+ * 主な仕様:
+ * - ファイルはリポジトリルートからの相対パスで照合する（別のディレクトリに展開したスナップショットどうしも比較できる）
+ * - 追加したモジュール・削除したファイル（モジュールかどうかを併記）・変更の多いファイル・外部依存の追加・削除・更新を挙げる
+ * - 変更したファイルはサイズ・行数・最終更新日時のいずれかが変わったもの
+ * - 変更の多さ（churn）は、両方のインデックスにGit履歴がある場合は間のコミット数、ない場合は行数の増減の大きさで並べる
+ * - 外部依存はエコシステムと名前で照合し、lockfileで解決したバージョン（なければ要求バージョン）の違いを更新とする
+ * - 新しいインデックスの置き換えの規則（`[security.redaction]`）を本文に適用する
+ *
+ * 制限事項:
+ * - ローカルLLMは使わず、インデックスの内容から決まった形式で書く（日本語のみ）
+ * - ファイルのリネームは削除と追加として扱う
+ * - 各一覧は `MAX_LISTED` 件まで
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use analyzer_core::{Diagnostic, FileInfo, Index};
+
+use crate::Summarizer;
+
+/// 各一覧に並べる最大数
+const MAX_LISTED: usize = 20;
+
+/// 2つのインデックスの差分
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexDiff {
+    /// 古いインデックスの解析時のコミット
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_commit: Option<String>,
+    /// 新しいインデックスの解析時のコミット
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_commit: Option<String>,
+    /// 追加したモジュール（相対パス、パス順）
+    pub added_modules: Vec<PathBuf>,
+    /// 削除したモジュール（相対パス、パス順）
+    pub removed_modules: Vec<PathBuf>,
+    /// 追加したファイル（モジュールを含む、相対パス、パス順）
+    pub added_files: Vec<PathBuf>,
+    /// 削除したファイル（モジュールを含む、相対パス、パス順）
+    pub removed_files: Vec<PathBuf>,
+    /// 変更したファイル（変更の多い順）
+    pub changed_files: Vec<FileChange>,
+    /// 外部依存の変更（追加・削除・更新、エコシステムと名前の順）
+    pub dependencies: Vec<DependencyChange>,
+    /// ファイル数・モジュール数・行数（古い、新しい）
+    pub files: (usize, usize),
+    pub modules: (usize, usize),
+    pub lines: (usize, usize),
+}
+
+/// 変更したファイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    /// リポジトリルートからの相対パス
+    pub path: PathBuf,
+    pub lines_before: usize,
+    pub lines_after: usize,
+    /// 間のコミット数（どちらかのインデックスにGit履歴がない場合はNone）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commits: Option<usize>,
+}
+
+impl FileChange {
+    /// 変更の多さ（間のコミット数、なければ行数の増減の大きさ）
+    fn churn(&self) -> usize {
+        self.commits.unwrap_or_else(|| self.lines_after.abs_diff(self.lines_before))
+    }
+}
+
+/// 外部依存の変更
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DependencyChange {
+    pub name: String,
+    /// エコシステム（cargo|npm|python|go）
+    pub ecosystem: String,
+    /// 変更の種類（added|removed|updated）
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+/// 差分の要約の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffResult {
+    pub ok: bool,
+    /// 変更履歴の節のMarkdown
+    pub content_md: String,
+    pub diff: IndexDiff,
+    /// 要約中に記録した警告
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl IndexDiff {
+    /// 2つのインデックスを比べる
+    ///
+    /// # 引数
+    /// * `old` - 古いインデックス
+    /// * `new` - 新しいインデックス
+    ///
+    /// # 戻り値
+    /// * `IndexDiff` - 差分
+    pub fn between(old: &Index, new: &Index) -> Self {
+        let old_files = files_by_path(old);
+        let new_files = files_by_path(new);
+        let old_modules = module_paths(old);
+        let new_modules = module_paths(new);
+
+        let mut changed_files: Vec<FileChange> = new_files
+            .iter()
+            .filter_map(|(path, after)| {
+                let before = old_files.get(path)?;
+                let modified = |f: &FileInfo| f.history.as_ref().map(|h| h.last_modified);
+                if before.size == after.size && before.metrics.lines == after.metrics.lines && modified(before) == modified(after) {
+                    return None;
+                }
+                let commits = match (&before.history, &after.history) {
+                    (Some(b), Some(a)) => Some(a.commits.saturating_sub(b.commits)),
+                    _ => None,
+                };
+                Some(FileChange { path: path.clone(), lines_before: before.metrics.lines, lines_after: after.metrics.lines, commits })
+            })
+            .collect();
+        changed_files.sort_by(|a, b| b.churn().cmp(&a.churn()).then_with(|| a.path.cmp(&b.path)));
+
+        Self {
+            old_commit: old.commit.clone(),
+            new_commit: new.commit.clone(),
+            added_modules: new_modules.difference(&old_modules).cloned().collect(),
+            removed_modules: old_modules.difference(&new_modules).cloned().collect(),
+            added_files: new_files.keys().filter(|p| !old_files.contains_key(*p)).cloned().collect(),
+            removed_files: old_files.keys().filter(|p| !new_files.contains_key(*p)).cloned().collect(),
+            changed_files,
+            dependencies: dependency_changes(old, new),
+            files: (old.files.len(), new.files.len()),
+            modules: (old.modules.len(), new.modules.len()),
+            lines: (total_lines(old), total_lines(new)),
+        }
+    }
+
+    /// 変更がないか
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty() && self.removed_files.is_empty() && self.changed_files.is_empty() && self.dependencies.is_empty()
+    }
+}
+
+impl Summarizer {
+    /// 2つのインデックスの差分をMarkdownの変更履歴にする
+    ///
+    /// # 引数
+    /// * `old` - 古いインデックス（以前のコミット・スナップショット）
+    /// * `new` - 新しいインデックス
+    ///
+    /// # 戻り値
+    /// * `DiffResult` - `## 変更点` から始まるMarkdownと差分
+    pub fn summarize_diff(&self, old: &Index, new: &Index) -> DiffResult {
+        let diff = IndexDiff::between(old, new);
+        let content_md = new.redaction.redact_text(&self.render_diff(new, &diff));
+        DiffResult { ok: true, content_md, diff, diagnostics: self.diagnostics.entries() }
+    }
+
+    /// 差分をMarkdownにする
+    fn render_diff(&self, new: &Index, diff: &IndexDiff) -> String {
+        let label = |commit: &Option<String>, fallback: &str| match commit {
+            Some(commit) => format!("`{}`", commit.chars().take(7).collect::<String>()),
+            None => fallback.to_string(),
+        };
+        let link = |path: &Path| self.file_link(new, &new.repo_path.join(path));
+        let more = |content: &mut String, count: usize| {
+            if count > MAX_LISTED {
+                content.push_str(&format!("- ほか{}件\n", count - MAX_LISTED));
+            }
+        };
+
+        let mut content = format!(
+            "## 変更点（{} → {}）\n\n",
+            label(&diff.old_commit, "以前の版"),
+            label(&diff.new_commit, "現在の版")
+        );
+        if diff.is_empty() {
+            content.push_str("ファイル・外部依存に変更はありません。\n");
+            return content;
+        }
+        let mut lead = Vec::new();
+        for (count, what) in [
+            (diff.added_modules.len(), "新しいモジュール"),
+            (diff.removed_files.len(), "削除したファイル"),
+            (diff.changed_files.len(), "変更したファイル"),
+            (diff.dependencies.len(), "外部依存の変更"),
+        ] {
+            if count > 0 {
+                lead.push(format!("{}が{}件", what, count));
+            }
+        }
+        if !lead.is_empty() {
+            content.push_str(&format!("{}あります。\n\n", lead.join("、")));
+        }
+        content.push_str(&format!(
+            "- **ファイル数**: {} → {}（追加 {}、削除 {}、変更 {}）\n",
+            diff.files.0,
+            diff.files.1,
+            diff.added_files.len(),
+            diff.removed_files.len(),
+            diff.changed_files.len()
+        ));
+        content.push_str(&format!("- **モジュール数**: {} → {}\n", diff.modules.0, diff.modules.1));
+        content.push_str(&format!(
+            "- **行数**: {} → {}（{:+}）\n\n",
+            diff.lines.0,
+            diff.lines.1,
+            diff.lines.1 as i64 - diff.lines.0 as i64
+        ));
+
+        if !diff.added_modules.is_empty() {
+            content.push_str("### 新しいモジュール\n\n");
+            for path in diff.added_modules.iter().take(MAX_LISTED) {
+                let file = new.files.iter().find(|f| f.path.strip_prefix(&new.repo_path).unwrap_or(&f.path) == path);
+                match file {
+                    Some(file) => content.push_str(&format!("- {}（{}、{}行）\n", link(path), file.language, file.metrics.lines)),
+                    None => content.push_str(&format!("- {}\n", link(path))),
+                }
+            }
+            more(&mut content, diff.added_modules.len());
+            content.push('\n');
+        }
+
+        if !diff.removed_files.is_empty() {
+            content.push_str("### 削除したファイル\n\n");
+            for path in diff.removed_files.iter().take(MAX_LISTED) {
+                let module = if diff.removed_modules.contains(path) { "（モジュール）" } else { "" };
+                content.push_str(&format!("- `{}`{}\n", path.display(), module));
+            }
+            more(&mut content, diff.removed_files.len());
+            content.push('\n');
+        }
+
+        if !diff.changed_files.is_empty() {
+            let commits = diff.changed_files.iter().any(|c| c.commits.is_some());
+            content.push_str("### 変更の多いファイル\n\n");
+            content.push_str(if commits { "| ファイル | コミット数 | 行数 |\n|---|---:|---|\n" } else { "| ファイル | 行数 |\n|---|---|\n" });
+            for change in diff.changed_files.iter().take(MAX_LISTED) {
+                let lines = format!(
+                    "{} → {}（{:+}）",
+                    change.lines_before,
+                    change.lines_after,
+                    change.lines_after as i64 - change.lines_before as i64
+                );
+                match commits {
+                    true => content.push_str(&format!(
+                        "| {} | {} | {} |\n",
+                        link(&change.path),
+                        change.commits.map_or("-".to_string(), |c| c.to_string()),
+                        lines
+                    )),
+                    false => content.push_str(&format!("| {} | {} |\n", link(&change.path), lines)),
+                }
+            }
+            if diff.changed_files.len() > MAX_LISTED {
+                content.push_str(&format!("\nほか{}件\n", diff.changed_files.len() - MAX_LISTED));
+            }
+            content.push('\n');
+        }
+
+        if !diff.dependencies.is_empty() {
+            content.push_str("### 外部依存の変更\n\n");
+            for change in diff.dependencies.iter().take(MAX_LISTED) {
+                let version = |v: &Option<String>| v.as_deref().map(|v| format!(" {}", v)).unwrap_or_default();
+                let line = match change.kind.as_str() {
+                    "added" => format!("追加: `{}`{}", change.name, version(&change.after)),
+                    "removed" => format!("削除: `{}`{}", change.name, version(&change.before)),
+                    _ => format!(
+                        "更新: `{}` {} → {}",
+                        change.name,
+                        change.before.as_deref().unwrap_or("-"),
+                        change.after.as_deref().unwrap_or("-")
+                    ),
+                };
+                content.push_str(&format!("- {}（{}）\n", line, change.ecosystem));
+            }
+            more(&mut content, diff.dependencies.len());
+            content.push('\n');
+        }
+
+        content
+    }
+}
+
+/// リポジトリルートからの相対パス → ファイル
+fn files_by_path(index: &Index) -> BTreeMap<PathBuf, &FileInfo> {
+    index
+        .files
+        .iter()
+        .map(|f| (f.path.strip_prefix(&index.repo_path).unwrap_or(&f.path).to_path_buf(), f))
+        .collect()
+}
+
+/// モジュールの相対パス
+fn module_paths(index: &Index) -> BTreeSet<PathBuf> {
+    index
+        .modules
+        .iter()
+        .map(|m| m.path.strip_prefix(&index.repo_path).unwrap_or(&m.path).to_path_buf())
+        .collect()
+}
+
+/// ファイルの行数の合計
+fn total_lines(index: &Index) -> usize {
+    index.files.iter().map(|f| f.metrics.lines).sum()
+}
+
+/// 外部依存の追加・削除・更新
+fn dependency_changes(old: &Index, new: &Index) -> Vec<DependencyChange> {
+    let versions = |index: &Index| -> BTreeMap<(String, String), Option<String>> {
+        index
+            .external_dependencies
+            .iter()
+            .map(|d| ((d.ecosystem.clone(), d.name.clone()), d.version.clone().or_else(|| d.requested.clone())))
+            .collect()
+    };
+    let before = versions(old);
+    let after = versions(new);
+    let keys: BTreeSet<&(String, String)> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let (kind, b, a) = match (before.get(key), after.get(key)) {
+                (None, Some(a)) => ("added", None, a.clone()),
+                (Some(b), None) => ("removed", b.clone(), None),
+                (Some(b), Some(a)) if b != a => ("updated", b.clone(), a.clone()),
+                _ => return None,
+            };
+            Some(DependencyChange { name: key.1.clone(), ecosystem: key.0.clone(), kind: kind.to_string(), before: b, after: a })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analyzer_core::{ExternalDependency, FileHistory, FileMetrics, ModuleInfo};
+    use config::Config;
+
+    fn file(repo: &Path, path: &str, lines: usize, commits: usize) -> FileInfo {
+        FileInfo {
+            path: repo.join(path),
+            language: "rust".to_string(),
+            size: lines * 10,
+            metrics: FileMetrics { lines, ..Default::default() },
+            history: Some(FileHistory { commits, last_modified: commits as i64, ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    fn module(repo: &Path, path: &str) -> ModuleInfo {
+        ModuleInfo { path: repo.join(path), ..Default::default() }
+    }
+
+    fn dependency(name: &str, version: &str) -> ExternalDependency {
+        ExternalDependency {
+            name: name.to_string(),
+            ecosystem: "cargo".to_string(),
+            version: Some(version.to_string()),
+            kind: "normal".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_diff_between_snapshots() {
+        // 別のディレクトリに展開したスナップショットも相対パスで照合する
+        let (old_repo, new_repo) = (PathBuf::from("/old"), PathBuf::from("/new"));
+        let old = Index {
+            repo_path: old_repo.clone(),
+            commit: Some("aaaaaaaaaaaa".to_string()),
+            files: vec![file(&old_repo, "src/main.rs", 100, 5), file(&old_repo, "src/legacy.rs", 40, 2), file(&old_repo, "README.md", 10, 1)],
+            modules: vec![module(&old_repo, "src/main.rs"), module(&old_repo, "src/legacy.rs")],
+            external_dependencies: vec![dependency("serde", "1.0.1"), dependency("rand", "0.8.0")],
+            ..Default::default()
+        };
+        let new = Index {
+            repo_path: new_repo.clone(),
+            commit: Some("bbbbbbbbbbbb".to_string()),
+            files: vec![file(&new_repo, "src/main.rs", 130, 9), file(&new_repo, "src/api.rs", 60, 1), file(&new_repo, "README.md", 10, 1)],
+            modules: vec![module(&new_repo, "src/main.rs"), module(&new_repo, "src/api.rs")],
+            external_dependencies: vec![dependency("serde", "1.0.2"), dependency("tokio", "1.40.0")],
+            ..Default::default()
+        };
+
+        let result = Summarizer::new(Config::default()).summarize_diff(&old, &new);
+        let diff = &result.diff;
+        assert_eq!(diff.added_modules, vec![PathBuf::from("src/api.rs")]);
+        assert_eq!(diff.removed_files, vec![PathBuf::from("src/legacy.rs")]);
+        assert_eq!(diff.changed_files, vec![FileChange { path: PathBuf::from("src/main.rs"), lines_before: 100, lines_after: 130, commits: Some(4) }]);
+        let kinds: Vec<(&str, &str)> = diff.dependencies.iter().map(|d| (d.kind.as_str(), d.name.as_str())).collect();
+        assert_eq!(kinds, vec![("removed", "rand"), ("updated", "serde"), ("added", "tokio")]);
+
+        let md = &result.content_md;
+        assert!(md.starts_with("## 変更点（`aaaaaaa` → `bbbbbbb`）\n\n新しいモジュールが1件、削除したファイルが1件、変更したファイルが1件、外部依存の変更が3件あります。\n"), "{}", md);
+        assert!(md.contains("- **行数**: 150 → 200（+50）\n"));
+        assert!(md.contains("### 新しいモジュール\n\n- `src/api.rs`（rust、60行）\n"));
+        assert!(md.contains("### 削除したファイル\n\n- `src/legacy.rs`（モジュール）\n"));
+        assert!(md.contains("| `src/main.rs` | 4 | 100 → 130（+30） |\n"));
+        assert!(md.contains("- 更新: `serde` 1.0.1 → 1.0.2（cargo）\n- 追加: `tokio` 1.40.0（cargo）\n"));
+
+        let unchanged = Summarizer::new(Config::default()).summarize_diff(&new, &new);
+        assert!(unchanged.diff.is_empty());
+        assert!(unchanged.content_md.ends_with("ファイル・外部依存に変更はありません。\n"));
+    }
+}
@@ -0,0 +1,256 @@
+/*!
+ * 抽出型の要約（`summarization.mode = "extractive"`）
+ *
+ * 名前からの推測ではなく、ファイル自身の説明文を抜き出して要約に使う
+ * - ファイル冒頭のコメント（`//!`・`/*! */`・`/** */`・`//`・`#`）とPythonのモジュールdocstring
+ * - パッケージ・リポジトリのREADMEの最初の段落
+ *
+ * 主な仕様:
+ * - shebang・エンコーディング宣言・空行は読み飛ばす
+ * - ライセンス表記（Copyright・SPDX）だけのコメントは除き、その次のコメントを使う
+ * - 最初の段落から2文までを1行にまとめ、リストの記号・Markdownのリンク・強調を除く
+ * - 日本語以外の文は「（ファイル冒頭のコメントより）」を付けてそのまま掲載（翻訳しない）
+ *
+ * 制限事項:
+ * - 関数・クラスの途中にあるコメントは対象外
+ * - READMEの段落は見出し・バッジ・HTML・コードブロック以外の最初の段落のみ
+ */
+
+use crate::is_japanese;
+
+/// 抜き出す文の最大数
+const MAX_SENTENCES: usize = 2;
+
+/// 抜き出した説明文
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Excerpt {
+    pub text: String,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり、この行を含む）
+    pub end_line: usize,
+}
+
+/// ファイル冒頭のコメントを抜き出す
+///
+/// # 引数
+/// * `content` - ファイル内容
+///
+/// # 戻り値
+/// * `Option<Excerpt>` - コメントの本文と行範囲（コメントがない場合はNone）
+pub(crate) fn header_comment(content: &str) -> Option<Excerpt> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if line.is_empty() || (i == 0 && line.starts_with("#!")) || line.contains("-*- coding") {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let body: Vec<&str> = if let Some(quote) = ["\"\"\"", "'''"].iter().find(|q| line.starts_with(**q)) {
+            // Pythonのモジュールdocstring
+            let rest = &line[3..];
+            if let Some(end) = rest.find(quote) {
+                vec![&rest[..end]]
+            } else {
+                let mut body = vec![rest];
+                i += 1;
+                while i < lines.len() && !lines[i].contains(quote) {
+                    body.push(lines[i]);
+                    i += 1;
+                }
+                if let Some(last) = lines.get(i) {
+                    body.push(last.split(quote).next().unwrap_or(""));
+                }
+                body
+            }
+        } else if line.starts_with("/*") {
+            let mut body = Vec::new();
+            loop {
+                let current = lines[i];
+                body.push(current);
+                if current.contains("*/") || i + 1 >= lines.len() {
+                    break;
+                }
+                i += 1;
+            }
+            body
+        } else if line.starts_with("//") || line.starts_with('#') {
+            let marker = if line.starts_with("//") { "//" } else { "#" };
+            let mut body = vec![lines[i]];
+            while i + 1 < lines.len() && lines[i + 1].trim().starts_with(marker) {
+                i += 1;
+                body.push(lines[i]);
+            }
+            body
+        } else {
+            // コメントより先にコードがある
+            return None;
+        };
+        let end = i;
+        i += 1;
+
+        let text = clean_comment_lines(&body);
+        if text.is_empty() || is_license(&text) {
+            continue;
+        }
+        return Some(Excerpt { text, start_line: start + 1, end_line: end + 1 });
+    }
+    None
+}
+
+/// READMEの最初の段落を抜き出す（見出し・バッジ・HTML・コードブロックは除く）
+///
+/// # 引数
+/// * `markdown` - READMEの内容
+///
+/// # 戻り値
+/// * `Option<Excerpt>` - 段落と行範囲（段落がない場合はNone）
+pub(crate) fn readme_excerpt(markdown: &str) -> Option<Excerpt> {
+    let mut in_code = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut start = 0;
+    for (i, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        let skip = in_code
+            || trimmed.starts_with('#')
+            || trimmed.starts_with('<')
+            || trimmed.starts_with("[![")
+            || trimmed.starts_with("![")
+            || trimmed.starts_with('|')
+            || trimmed.starts_with("---");
+        if trimmed.is_empty() || skip {
+            if !paragraph.is_empty() {
+                return Some(Excerpt { text: paragraph.join("\n"), start_line: start + 1, end_line: i });
+            }
+            continue;
+        }
+        if paragraph.is_empty() {
+            start = i;
+        }
+        paragraph.push(trimmed);
+    }
+    let end = start + paragraph.len();
+    (!paragraph.is_empty()).then(|| Excerpt { text: paragraph.join("\n"), start_line: start + 1, end_line: end })
+}
+
+/// 抜き出した文を要約の1文にまとめる
+///
+/// # 引数
+/// * `text` - コメントまたは段落の本文
+/// * `source` - 出典の説明（日本語以外の文に付ける。例: 「ファイル冒頭のコメント」）
+///
+/// # 戻り値
+/// * `String` - 最初の段落の2文まで（空の場合は空文字列）
+pub(crate) fn rewrite(text: &str, source: &str) -> String {
+    let paragraph: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .take_while(|l| !l.is_empty())
+        .map(|l| l.trim_start_matches(['-', '*', '+']).trim().to_string())
+        .collect();
+    let japanese = is_japanese(text);
+    let joined = paragraph.join(if japanese { "" } else { " " });
+    let joined = strip_markdown(&joined);
+
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in joined.chars() {
+        current.push(c);
+        let end_of_sentence = c == '。' || (c == '.' && !japanese);
+        if end_of_sentence {
+            sentences.push(current.trim().to_string());
+            current.clear();
+            if sentences.len() == MAX_SENTENCES {
+                break;
+            }
+        }
+    }
+    if sentences.len() < MAX_SENTENCES && !current.trim().is_empty() {
+        sentences.push(current.trim().to_string());
+    }
+    let mut summary = sentences.join(if japanese { "" } else { " " });
+    if summary.is_empty() {
+        return summary;
+    }
+
+    if japanese {
+        if !summary.ends_with('。') {
+            summary.push('。');
+        }
+        summary
+    } else {
+        if !summary.ends_with('.') {
+            summary.push('.');
+        }
+        format!("{}（{}より）", summary, source)
+    }
+}
+
+/// コメント記号を除いて本文を取り出す
+fn clean_comment_lines(lines: &[&str]) -> String {
+    let cleaned: Vec<String> = lines
+        .iter()
+        .map(|line| {
+            let line = line.trim();
+            let line = ["/*!", "/**", "/*", "//!", "///", "//", "#"]
+                .iter()
+                .find_map(|prefix| line.strip_prefix(prefix))
+                .unwrap_or(line);
+            let line = line.strip_suffix("*/").unwrap_or(line).trim();
+            line.strip_prefix('*').unwrap_or(line).trim().to_string()
+        })
+        .collect();
+    cleaned.join("\n").trim().to_string()
+}
+
+/// ライセンス表記のみのコメントか
+fn is_license(text: &str) -> bool {
+    let lower = text.to_lowercase();
+    lower.contains("copyright") || lower.contains("spdx-license") || lower.contains("licensed under")
+}
+
+/// Markdownのリンク・強調・コードの記号を除く
+fn strip_markdown(text: &str) -> String {
+    let link = regex::Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap();
+    let text = link.replace_all(text, "$1");
+    text.replace("**", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_comment_skips_license() {
+        let content = "#!/usr/bin/env node\n// Copyright 2024 Acme\n\n/**\n * Loads the config file. Falls back to defaults.\n * Then validates it.\n */\nexport function load() {}\n";
+        let excerpt = header_comment(content).unwrap();
+        assert_eq!((excerpt.start_line, excerpt.end_line), (4, 7));
+        assert_eq!(
+            rewrite(&excerpt.text, "ファイル冒頭のコメント"),
+            "Loads the config file. Falls back to defaults.（ファイル冒頭のコメントより）"
+        );
+
+        let python = "\"\"\"設定ファイルを読み込む\n\n詳細\n\"\"\"\nimport os\n";
+        let excerpt = header_comment(python).unwrap();
+        assert_eq!(rewrite(&excerpt.text, "ファイル冒頭のコメント"), "設定ファイルを読み込む。");
+        assert_eq!(header_comment("fn main() {}\n// 後ろのコメント\n"), None);
+    }
+
+    #[test]
+    fn test_readme_excerpt_skips_title_and_badges() {
+        let readme = "# store\n\n[![ci](https://ci)](https://ci)\n\nBlob storage for the **index**,\nbacked by [zstd](https://zstd).\n\n## Usage\n";
+        let excerpt = readme_excerpt(readme).unwrap();
+        assert_eq!((excerpt.start_line, excerpt.end_line), (5, 6));
+        assert_eq!(
+            rewrite(&excerpt.text, "README"),
+            "Blob storage for the index, backed by zstd.（READMEより）"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+ * 外部ツールのテスト用の実行方法
+ *
+ * mdBook・Marp CLI・Chromiumをインストール・アンインストールせずに、ビルダーが外部ツールの失敗で
+ * Markdownのみの出力に切り替わることを確かめる（`fake-tools` フィーチャー）
+ *
+ * 主な仕様:
+ * - 登録したツールのみ `--version` に応答し（`check` で見つかる）、登録していないツールは見つからない
+ * - ツールごとに成功・失敗（標準エラーの内容）・タイムアウトを指定し、終了前に書き出すファイルで出力の欠けを再現する
+ * - 実行した呼び出しは `calls` で確認できる（`--version` を除く）
+ *
+ * 制限事項:
+ * - 実際の待ち時間は発生しない（タイムアウトはすぐに `TimedOut` のエラーを返す）
+ * - 書き出すファイルの内容は固定の文字列
+ */
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::tools::{ToolCommand, ToolOutput, ToolRunner};
+
+/// 登録したツールの振る舞い
+#[derive(Debug, Clone)]
+pub struct FakeRun {
+    outcome: FakeOutcome,
+    /// 終了前に書き出すファイル（相対パスは実行ディレクトリから）
+    writes: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+enum FakeOutcome {
+    Success,
+    Failure(String),
+    Timeout,
+}
+
+impl FakeRun {
+    /// 終了コード0で終了する
+    pub fn success() -> Self {
+        Self { outcome: FakeOutcome::Success, writes: Vec::new() }
+    }
+
+    /// 標準エラーに書いて終了コード1で終了する
+    pub fn failure(stderr: impl Into<String>) -> Self {
+        Self { outcome: FakeOutcome::Failure(stderr.into()), writes: Vec::new() }
+    }
+
+    /// 時間内に終了しない
+    pub fn timeout() -> Self {
+        Self { outcome: FakeOutcome::Timeout, writes: Vec::new() }
+    }
+
+    /// 終了前にファイルを書き出す（一部の形式だけ出力された状態の再現）
+    pub fn writing(mut self, path: impl Into<PathBuf>) -> Self {
+        self.writes.push(path.into());
+        self
+    }
+}
+
+/// 外部ツールのテスト用の実行方法（`Tools::new(fake.clone())` で渡し、元の値で呼び出しを確認する）
+#[derive(Debug, Clone, Default)]
+pub struct FakeTools {
+    runs: HashMap<String, FakeRun>,
+    calls: Arc<Mutex<Vec<ToolCommand>>>,
+}
+
+impl FakeTools {
+    /// どのツールも見つからない状態で作成
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ツールを登録
+    ///
+    /// # 引数
+    /// * `name` - コマンド名
+    /// * `run` - 実行したときの振る舞い
+    ///
+    /// # 戻り値
+    /// * `Self` - ツールを登録した実行方法
+    pub fn with(mut self, name: &str, run: FakeRun) -> Self {
+        self.runs.insert(name.to_string(), run);
+        self
+    }
+
+    /// 実行した呼び出し（実行した順、`--version` を除く）
+    pub fn calls(&self) -> Vec<ToolCommand> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl ToolRunner for FakeTools {
+    fn run(&self, command: &ToolCommand) -> io::Result<ToolOutput> {
+        let Some(run) = self.runs.get(&command.program) else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{}が見つかりません", command.program)));
+        };
+        if command.args == ["--version"] {
+            return Ok(ToolOutput { success: true, stdout: format!("{} 0.0.0-fake\n", command.program), stderr: String::new() });
+        }
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).push(command.clone());
+
+        for path in &run.writes {
+            let path = match &command.current_dir {
+                Some(dir) if path.is_relative() => dir.join(path),
+                _ => path.clone(),
+            };
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, "fake output")?;
+        }
+        match &run.outcome {
+            FakeOutcome::Success => Ok(ToolOutput { success: true, ..Default::default() }),
+            FakeOutcome::Failure(stderr) => Ok(ToolOutput { success: false, stdout: String::new(), stderr: stderr.clone() }),
+            FakeOutcome::Timeout => Err(io::Error::new(io::ErrorKind::TimedOut, "テスト用のタイムアウト")),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+/*!
+ * 用語の定義（静的な定義とローカルLLMの併用）
+ *
+ * インデックスから抽出した用語（`Index::glossary_terms`）に1文の定義を付ける
+ *
+ * 主な仕様:
+ * - 静的な定義: 用語と同じ名前の型のドキュメントコメントの最初の文、なければ用語を含む名前と現れるファイル数から書く
+ *   - 日本語では英語のドキュメントコメントを翻訳する（`translate_doc_to_japanese`）
+ * - ローカルLLMがある場合は、用語・関連する識別子・ドキュメントコメントを渡し、`<用語>: <定義>` の行で答えさせる
+ * - LLMに渡す用語は最大 `MAX_LLM_TERMS` 件、応答にない用語は静的な定義を使う
+ *
+ * 制限事項:
+ * - LLMがない場合・失敗した場合は静的な定義のみ
+ * - LLMの定義はキャッシュしない（Wikiの生成ごとに問い合わせる）
+ */
+
+use std::collections::HashMap;
+
+use analyzer_core::GlossaryTerm;
+
+use crate::types::first_sentence;
+use crate::{Locale, Summarizer};
+
+/// LLMで定義する用語の最大数
+const MAX_LLM_TERMS: usize = 40;
+/// 静的な定義・LLMに渡す識別子の最大数
+const MAX_HINTS: usize = 3;
+
+impl Summarizer {
+    /// 用語の定義を作成
+    ///
+    /// # 引数
+    /// * `terms` - 用語（`Index::glossary_terms`）
+    /// * `locale` - 定義の言語
+    ///
+    /// # 戻り値
+    /// * `Vec<String>` - 用語と同じ順の定義（1文）
+    pub async fn define_terms(&self, terms: &[GlossaryTerm], locale: Locale) -> Vec<String> {
+        let mut definitions = Vec::with_capacity(terms.len());
+        for term in terms {
+            definitions.push(self.define_term_statically(term, locale).await);
+        }
+        let Some(llm) = self.llm.as_ref() else {
+            return definitions;
+        };
+        if terms.is_empty() {
+            return definitions;
+        }
+
+        let mut listing = String::new();
+        for term in terms.iter().take(MAX_LLM_TERMS) {
+            let doc = first_sentence(&term.documentation);
+            listing.push_str(&format!("- {} (names: {}", term.term, term.identifiers.iter().take(MAX_HINTS).cloned().collect::<Vec<_>>().join(", ")));
+            if !doc.is_empty() {
+                listing.push_str(&format!("; doc: {}", doc));
+            }
+            listing.push_str(")\n");
+        }
+        let prompt = format!(
+            "Write a one-sentence definition in {} of each domain term used in the source code of a software repository.\n\
+             Base the definitions only on the given names and documentation.\n\
+             Answer with one line per term in the form `<term>: <definition>` and nothing else.\n\n{}",
+            match locale {
+                Locale::Ja => "Japanese",
+                Locale::En => "English",
+            },
+            listing
+        );
+        match llm.generate(&prompt).await {
+            Ok(response) => {
+                let parsed = parse_definitions(&response, terms);
+                if parsed.is_empty() {
+                    self.diagnostics.warn("summarizer", "ローカルLLMの応答から用語の定義を読み取れませんでした");
+                }
+                for (i, definition) in parsed {
+                    definitions[i] = definition;
+                }
+            }
+            Err(e) => self.diagnostics.warn("summarizer", format!("ローカルLLMでの用語の定義に失敗しました: {}", e)),
+        }
+        definitions
+    }
+
+    /// 型のドキュメントコメント、または用語を含む名前から定義を書く
+    async fn define_term_statically(&self, term: &GlossaryTerm, locale: Locale) -> String {
+        if !term.documentation.is_empty() {
+            let doc = match locale {
+                Locale::Ja => self.translate_doc_to_japanese(&term.documentation).await,
+                Locale::En => term.documentation.clone(),
+            };
+            let sentence = first_sentence(&doc);
+            if !sentence.is_empty() {
+                return sentence.to_string();
+            }
+        }
+        let names: Vec<String> = term.identifiers.iter().take(MAX_HINTS).map(|n| format!("`{}`", n)).collect();
+        let separator = match locale {
+            Locale::Ja => "・",
+            Locale::En => ", ",
+        };
+        locale.summary_format("glossary.heuristic", &[&names.join(separator), &term.files.len()])
+    }
+}
+
+/// LLMの応答から `<用語>: <定義>` の行を読み取る
+///
+/// # 引数
+/// * `response` - LLMの応答
+/// * `terms` - 定義を依頼した用語
+///
+/// # 戻り値
+/// * `HashMap<usize, String>` - 用語の位置 → 定義（一覧にない用語・空の定義の行は除く）
+fn parse_definitions(response: &str, terms: &[GlossaryTerm]) -> HashMap<usize, String> {
+    response
+        .lines()
+        .filter_map(|line| {
+            let (term, definition) = line.trim().trim_start_matches(['-', '*', ' ']).split_once([':', '：'])?;
+            let term = term.trim().trim_matches(['`', '*']);
+            let definition = definition.trim();
+            let position = terms.iter().position(|t| t.term.eq_ignore_ascii_case(term))?;
+            (!definition.is_empty()).then(|| (position, definition.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn term(name: &str, documentation: &str) -> GlossaryTerm {
+        GlossaryTerm {
+            term: name.to_string(),
+            identifiers: vec!["Order".to_string(), "create_order".to_string(), "list_orders".to_string(), "bill_order".to_string()],
+            files: vec![PathBuf::from("src/a.rs"), PathBuf::from("src/b.rs")],
+            documentation: documentation.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_definitions() {
+        let terms = [term("Order", ""), term("invoice", "")];
+        let response = "- **Order**: A purchase made by a customer.\ninvoice： 請求書。\nshipment: unknown\nOrder:\n";
+        let parsed = parse_definitions(response, &terms);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[&0], "A purchase made by a customer.");
+        assert_eq!(parsed[&1], "請求書。");
+    }
+
+    #[tokio::test]
+    async fn test_define_terms_without_llm() {
+        let summarizer = Summarizer::new(config::Config::default());
+        let terms = [term("Order", "顧客の注文。明細を持つ。"), term("invoice", "")];
+        let definitions = summarizer.define_terms(&terms, Locale::Ja).await;
+        assert_eq!(definitions[0], "顧客の注文。");
+        assert_eq!(definitions[1], "`Order`・`create_order`・`list_orders` などの名前に現れる語で、2ファイルで使われています。");
+
+        let definitions = summarizer.define_terms(&terms[1..], Locale::En).await;
+        assert_eq!(definitions[0], "Appears in names such as `Order`, `create_order`, `list_orders` in 2 files.");
+    }
+}
@@ -0,0 +1,398 @@
+/*!
+ * 段階的な要約（map-reduce）
+ *
+ * ローカルLLMのコンテキストに収まらない大きなファイル・リポジトリを、
+ * 小さな単位の要約を積み上げて要約する
+ * - ファイル: 行単位のチャンクに分けてチャンクごとに要約し（map）、ファイルの要約に統合する（reduce）
+ * - パッケージ・リポジトリ: ファイルの要約をパッケージ（ワークスペース外はトップレベルのディレクトリ）ごとに統合し、さらに全体に統合する
+ *
+ * 主な仕様:
+ * - 1回の入力の上限は `summarization.context-tokens` からプロンプトの定型文と応答の分（`RESERVED_TOKENS`）を引いたトークン数
+ * - トークン数は英数字4文字で1トークン、それ以外（日本語など）は1文字1トークンとして見積もる
+ * - 統合する要約が上限を超える場合は、上限に収まるまとまりごとに統合してから、さらに統合する
+ * - 個別に要約するファイルは重要度の高い順に `summarization.max-map-files` 件まで
+ * - 1回の入力に収まらない大きさのファイルは全体をメモリに展開せず、チャンクごとに読みながら要約する（`Index::content_windows`）
+ *
+ * 制限事項:
+ * - トークン数は見積もりのため、モデルのトークナイザーによっては上限を超えることがある
+ * - 1行が上限を超える場合も行の途中では分割しない
+ * - 要約の呼び出しは順に行う（LLMの呼び出し回数はファイル数・チャンク数に比例する）
+ */
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use analyzer_core::{ContentWindow, ContentWindows, FileInfo, Index};
+
+use crate::llm::OllamaClient;
+
+/// プロンプトの定型文と応答のために確保するトークン数
+const RESERVED_TOKENS: usize = 1024;
+
+/// テキストのトークン数を見積もる
+///
+/// # 引数
+/// * `text` - テキスト
+///
+/// # 戻り値
+/// * `usize` - 見積もったトークン数
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(|c| c.is_ascii()).count();
+    let other = text.chars().count() - ascii;
+    ascii.div_ceil(4) + other
+}
+
+/// 内容を行単位で上限のトークン数以内のチャンクに分割
+///
+/// # 引数
+/// * `content` - 内容
+/// * `max_tokens` - 1チャンクのトークン数の上限
+///
+/// # 戻り値
+/// * `Vec<ContentWindow>` - チャンク（行番号は1始まり）
+pub(crate) fn split_into_chunks(content: &str, max_tokens: usize) -> Vec<ContentWindow> {
+    ContentWindows::new(Box::new(std::io::Cursor::new(content.as_bytes())), max_tokens, estimate_tokens).collect()
+}
+
+/// 段階的な要約
+pub(crate) struct Hierarchy<'a> {
+    llm: &'a OllamaClient,
+    /// 1回の入力（ソースコード・統合する要約）のトークン数の上限
+    budget: usize,
+}
+
+impl<'a> Hierarchy<'a> {
+    /// LLMのコンテキスト長から入力の上限を決める
+    ///
+    /// # 引数
+    /// * `llm` - ローカルLLM
+    ///
+    /// # 戻り値
+    /// * `Self` - 段階的な要約
+    pub(crate) fn new(llm: &'a OllamaClient) -> Self {
+        Self::with_budget(llm, llm.context_tokens().saturating_sub(RESERVED_TOKENS))
+    }
+
+    /// 入力の上限を指定して作成
+    pub(crate) fn with_budget(llm: &'a OllamaClient, budget: usize) -> Self {
+        Self { llm, budget: budget.max(1) }
+    }
+
+    /// ソースコードがそのまま1回の入力に収まるか
+    pub(crate) fn fits(&self, source: &str) -> bool {
+        estimate_tokens(source) <= self.budget
+    }
+
+    /// ファイルを要約（上限を超える場合はチャンクごとに要約してから統合）
+    ///
+    /// # 引数
+    /// * `label` - ファイルの表示名（リポジトリルートからの相対パス）
+    /// * `content` - ファイルの内容
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - ファイルの要約、またはエラー
+    pub(crate) async fn summarize_file(&self, label: &str, content: &str) -> Result<String> {
+        if self.fits(content) {
+            return self.generate(&file_prompt(label, content)).await;
+        }
+        self.summarize_chunks(label, split_into_chunks(content, self.budget)).await
+    }
+
+    /// インデックスのファイルを要約（1回の入力に収まらないファイルは全体を展開せず、チャンクごとに読みながら要約）
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `file` - ファイル
+    /// * `label` - ファイルの表示名（リポジトリルートからの相対パス）
+    ///
+    /// # 戻り値
+    /// * `Result<Option<String>>` - ファイルの要約（内容を読めない場合はNone）、またはエラー
+    pub(crate) async fn summarize_indexed_file(&self, index: &Index, file: &FileInfo, label: &str) -> Result<Option<String>> {
+        if self.may_fit(file) {
+            let Some(content) = index.file_content(file) else {
+                return Ok(None);
+            };
+            return self.summarize_file(label, &content).await.map(Some);
+        }
+        let Some(chunks) = index.content_windows(file, self.budget, estimate_tokens) else {
+            return Ok(None);
+        };
+        self.summarize_chunks(label, chunks).await.map(Some)
+    }
+
+    /// ファイルが1回の入力に収まる可能性があるか（1トークンは4バイト以下のため、超える場合は読まずに判定できる）
+    pub(crate) fn may_fit(&self, file: &FileInfo) -> bool {
+        file.size <= self.budget.saturating_mul(4)
+    }
+
+    /// チャンクごとに要約してから統合（map-reduce）
+    async fn summarize_chunks(&self, label: &str, chunks: impl IntoIterator<Item = ContentWindow>) -> Result<String> {
+        let mut summaries = Vec::new();
+        for chunk in chunks {
+            let summary = self.generate(&chunk_prompt(label, &chunk)).await?;
+            summaries.push(format!("{}行目〜{}行目: {}", chunk.start_line, chunk.end_line, summary));
+        }
+        self.combine(label, "ファイル", summaries).await
+    }
+
+    /// 要約を1つに統合（上限を超える場合は収まるまとまりごとに統合してから、さらに統合）
+    ///
+    /// # 引数
+    /// * `label` - 統合する対象の表示名
+    /// * `level` - 統合する対象の単位（ファイル・パッケージ・リポジトリ）
+    /// * `parts` - 統合する要約
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - 統合した要約、またはエラー
+    pub(crate) async fn combine(&self, label: &str, level: &str, mut parts: Vec<String>) -> Result<String> {
+        loop {
+            match parts.len() {
+                0 => return Ok(String::new()),
+                1 if self.fits(&parts[0]) => return Ok(parts.remove(0)),
+                _ => {}
+            }
+            let mut batches = self.batches(&parts);
+            if batches.len() == parts.len() && parts.len() > 1 {
+                // どの要約も単独で上限に近い場合は、2つずつ統合して数を減らす
+                batches = parts.chunks(2).collect();
+            }
+            if batches.len() == 1 {
+                return self.generate(&combine_prompt(label, level, &parts)).await;
+            }
+            let mut combined = Vec::new();
+            for batch in batches {
+                combined.push(self.generate(&combine_prompt(label, level, batch)).await?);
+            }
+            parts = combined;
+        }
+    }
+
+    /// リポジトリ（またはその一部）のファイルを要約し、パッケージごと・全体の順に統合
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `files` - 要約するファイル（重要度の高い順、`summarization.max-map-files` 件までに絞ったもの）
+    /// * `label` - 全体の表示名
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - パッケージごとの要約と全体の要約を並べたMarkdown、またはエラー
+    pub(crate) async fn summarize_files(&self, index: &Index, files: &[&FileInfo], label: &str) -> Result<String> {
+        // パッケージ（ワークスペース外はトップレベルのディレクトリ）ごとにファイルの要約をまとめる
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for file in files {
+            let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let Some(summary) = self.summarize_indexed_file(index, file, &relative).await? else {
+                continue;
+            };
+            groups.entry(group_name(index, &file.path)).or_default().push(format!("`{}`: {}", relative, summary));
+        }
+
+        let mut sections = Vec::new();
+        let mut group_summaries = Vec::new();
+        for (name, summaries) in groups {
+            let summary = self.combine(&name, "パッケージ", summaries).await?;
+            sections.push(format!("### {}\n\n{}\n", name, summary));
+            group_summaries.push(format!("{}: {}", name, summary));
+        }
+        if group_summaries.len() > 1 {
+            let summary = self.combine(label, "リポジトリ", group_summaries).await?;
+            sections.push(format!("### 全体\n\n{}\n", summary));
+        }
+        Ok(sections.join("\n"))
+    }
+
+    /// 要約を上限のトークン数以内のまとまりに分ける（1つで上限を超える要約は単独のまとまりにする）
+    fn batches<'p>(&self, parts: &'p [String]) -> Vec<&'p [String]> {
+        let mut batches = Vec::new();
+        let mut start = 0;
+        let mut tokens = 0;
+        for (i, part) in parts.iter().enumerate() {
+            let part_tokens = estimate_tokens(part);
+            if i > start && tokens + part_tokens > self.budget {
+                batches.push(&parts[start..i]);
+                start = i;
+                tokens = 0;
+            }
+            tokens += part_tokens;
+        }
+        batches.push(&parts[start..]);
+        batches
+    }
+
+    /// LLMで生成し、空の応答はエラーにする
+    async fn generate(&self, prompt: &str) -> Result<String> {
+        let text = self.llm.generate(prompt).await?;
+        if text.is_empty() {
+            return Err(anyhow::anyhow!("ローカルLLMの応答が空です"));
+        }
+        Ok(text)
+    }
+}
+
+/// ファイルのまとまりの名前（ワークスペースのパッケージ名、なければトップレベルのディレクトリ）
+fn group_name(index: &Index, path: &Path) -> String {
+    if let Some(package) = index.package_of(path) {
+        return package.name.clone();
+    }
+    let relative = path.strip_prefix(&index.repo_path).unwrap_or(path);
+    let mut components = relative.components().filter_map(|c| match c {
+        Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+        _ => None,
+    });
+    match (components.next(), components.next()) {
+        (Some(dir), Some(_)) => dir,
+        _ => "（ルート）".to_string(),
+    }
+}
+
+/// ファイル全体を要約するプロンプト
+fn file_prompt(label: &str, content: &str) -> String {
+    format!(
+        "あなたはソフトウェアの技術文書を書くエンジニアです。\n\
+         以下のファイル `{}` の役割と主な処理を日本語で2〜3文で説明してください。\n\
+         見出しやコードブロックは使わず、本文のみを出力してください。\n\n\
+         ```\n{}\n```\n",
+        label, content
+    )
+}
+
+/// ファイルの一部（チャンク）を要約するプロンプト
+fn chunk_prompt(label: &str, chunk: &ContentWindow) -> String {
+    format!(
+        "あなたはソフトウェアの技術文書を書くエンジニアです。\n\
+         以下はファイル `{}` の{}行目〜{}行目です。この部分で定義・実装されている内容を日本語で1〜2文で説明してください。\n\
+         見出しやコードブロックは使わず、本文のみを出力してください。\n\n\
+         ```\n{}\n```\n",
+        label, chunk.start_line, chunk.end_line, chunk.text
+    )
+}
+
+/// 要約を統合するプロンプト
+fn combine_prompt(label: &str, level: &str, parts: &[String]) -> String {
+    format!(
+        "あなたはソフトウェアの技術文書を書くエンジニアです。\n\
+         以下は{} `{}` の各部分の要約です。これらを統合し、{}全体の目的と役割を日本語で3〜5文で説明してください。\n\
+         見出しやコードブロックは使わず、本文のみを出力してください。\n\
+         要約から読み取れないことは推測で書かないでください。\n\n\
+         {}\n",
+        level,
+        label,
+        level,
+        parts.iter().map(|p| format!("- {}", p)).collect::<Vec<_>>().join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::Config;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// 同じ応答を返し続けるモックのOllamaを起動し、受け取ったリクエスト数を数える
+    fn spawn_mock_ollama(response: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let body = format!(r#"{{"response":"{}","done":true}}"#, response);
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[test]
+    fn test_split_into_chunks_respects_budget() {
+        assert_eq!(estimate_tokens("abcdefgh"), 2);
+        assert_eq!(estimate_tokens("設定を読む"), 5);
+
+        let content = (1..=10).map(|i| format!("let value_{} = {};", i, i)).collect::<Vec<_>>().join("\n");
+        let chunks = split_into_chunks(&content, 20);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0].start_line, 1);
+        assert_eq!(chunks.last().unwrap().end_line, 10);
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end_line + 1, pair[1].start_line);
+        }
+        assert!(chunks.iter().all(|c| estimate_tokens(&c.text) <= 20));
+    }
+
+    #[tokio::test]
+    async fn test_large_file_is_summarized_by_chunks() {
+        let (url, requests) = spawn_mock_ollama("設定を読み込む処理です。");
+        let mut config = Config::default();
+        config.summarization.ollama_url = url;
+        let llm = OllamaClient::new(&config);
+
+        // 収まるファイルは1回で要約
+        let hierarchy = Hierarchy::with_budget(&llm, 100);
+        let summary = hierarchy.summarize_file("src/config.rs", "fn load() {}").await.unwrap();
+        assert_eq!(summary, "設定を読み込む処理です。");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // 上限を超えるファイルはチャンクごとに要約してから統合
+        let content = (1..=30).map(|i| format!("fn handler_{}() {{ work({}); }}", i, i)).collect::<Vec<_>>().join("\n");
+        let chunks = split_into_chunks(&content, 100).len();
+        assert!(chunks > 1);
+        hierarchy.summarize_file("src/handlers.rs", &content).await.unwrap();
+        let calls = requests.load(Ordering::SeqCst) - 1;
+        assert!(calls > chunks, "map {}回 + reduce 1回以上: {}", chunks, calls);
+    }
+
+    #[tokio::test]
+    async fn test_large_indexed_file_is_streamed_by_chunks() {
+        let (url, requests) = spawn_mock_ollama("ハンドラーを定義しています。");
+        let mut config = Config::default();
+        config.summarization.ollama_url = url;
+        let llm = OllamaClient::new(&config);
+        let hierarchy = Hierarchy::with_budget(&llm, 50);
+
+        let content = (1..=40).map(|i| format!("fn handler_{}() {{ work({}); }}", i, i)).collect::<Vec<_>>().join("\n");
+        let file = FileInfo {
+            path: std::path::PathBuf::from("/repo/src/handlers.rs"),
+            size: content.len(),
+            content: Some(content.clone()),
+            ..Default::default()
+        };
+        assert!(!hierarchy.may_fit(&file));
+        let index = Index { repo_path: std::path::PathBuf::from("/repo"), files: vec![file], ..Default::default() };
+
+        let summary = hierarchy.summarize_indexed_file(&index, &index.files[0], "src/handlers.rs").await.unwrap();
+        assert_eq!(summary.as_deref(), Some("ハンドラーを定義しています。"));
+        let chunks = split_into_chunks(&content, 50).len();
+        assert!(requests.load(Ordering::SeqCst) > chunks);
+    }
+}
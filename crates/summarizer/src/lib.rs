@@ -1,34 +1,94 @@
-/**
+/*!
  * サマライザー実装
  * 
  * コードの要約を生成する
  * - 静的ヒューリスティックによる要約（LLMなし）
- * - LLMを使用した要約（オプション）
+ * - LLMを使用した要約（オプション、`mode = "local"` でOllama）
+ *   - コンテキストに収まらないファイルはチャンクごと、リポジトリ・パッケージはファイル → パッケージ → 全体の順に段階的に要約（hierarchyモジュール）
+ * - ファイル冒頭のコメント・READMEを抜き出す要約（`mode = "extractive"`、extractiveモジュール）
+ * - インデックスに対する質問への引用付きの回答（askモジュール）
+ * - 2つのインデックス（コミット・スナップショット）の差分の変更履歴（diffモジュール）
+ * - READMEのないリポジトリ向けのREADME.mdの下書き（`readme` スコープ、readmeモジュール）
+ * - モジュールの層の推定（静的に分類できないモジュールをローカルLLMで補う、architectureモジュール）
+ * - 用語集の用語の定義（型のドキュメントコメント・名前から、ローカルLLMがあればLLMで書く、glossaryモジュール）
  * - 日本語フォーカスのプロンプト
  * 
  * 主な仕様:
  * - リポジトリ/パッケージ/モジュール/ファイル単位での要約
- * - concise-ja（簡潔）とdetailed-ja（詳細）の2スタイル
+ * - ワークスペースのパッケージは名前でも指定でき、パッケージ間の依存とマニフェストの外部依存を記載
+ * - concise（簡潔）とdetailed（詳細）の2スタイルを日本語（`-ja`）・英語（`-en`）で出力（localeモジュール）
+ * - detailedのリポジトリの要約には、importされないモジュールと参照されない公開関数を「未使用コードの可能性」として記載
+ * - ファイル・detailedのモジュールの要約には、型ごとの責務・フィールド・主要なメソッドを記載（typesモジュール）
  * - アーティファクト（Mermaid図など）の生成
+ * - 各記述に根拠となるファイルと行範囲の引用（脚注）を付与
+ * - 要約の本文には `[security.redaction]` の独自の規則を適用（`Index::redaction`、件数は `Index::redaction_counts`）
+ * - 日本語で書かれたコメントは翻訳せずそのまま使用（文字種で判定）
+ * - 英語のドキュメントコメントはローカルLLM（なければ文型の辞書）で日本語に翻訳し、LLMの訳はキャッシュ（translateモジュール）
+ * - 英語のスライド用に、日本語の要約をローカルLLMで英語に翻訳（LLMがない場合は翻訳しない）
+ * - Wiki・スライド・ポスターで共通の外部ツール（mdBook・Marp CLI・Chromium）の検出と実行（toolsモジュール、テスト用の実行方法はfake_toolsモジュール）
+ * - Wiki・スライドで共通の出力のテンプレート（templatesモジュール、`templates.dir` で上書き）
  * 
  * 制限事項:
- * - LLM統合は環境変数による設定が必要
- * - オフラインモードでは静的ヒューリスティックのみ使用
+ * - リモートLLM（`mode = "remote"`）は未対応のため静的ヒューリスティックを使用
+ * - ローカルLLMに接続できない場合は静的ヒューリスティックにフォールバック
  */
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use anyhow::Result;
 use tracing::info;
 
 use config::Config;
-use analyzer_core::{Index, FileInfo};
+use analyzer_core::{parse_source, ComponentKind, Diagnostic, Diagnostics, Index, FileInfo};
+
+mod architecture;
+mod ask;
+mod diff;
+mod extractive;
+#[cfg(any(test, feature = "fake-tools"))]
+mod fake_tools;
+mod glossary;
+mod hierarchy;
+mod llm;
+mod locale;
+mod readme;
+mod templates;
+mod tools;
+mod translate;
+mod types;
+
+pub use ask::AskResult;
+pub use diff::{DependencyChange, DiffResult, FileChange, IndexDiff};
+pub use llm::OllamaClient;
+pub use locale::{Catalog, Locale};
+pub use templates::{template_names, Templates};
+pub use tools::{
+    check_tool, check_tools, SkippedArtifact, SystemTools, ToolCommand, ToolFailure, ToolOutput, ToolRunner, ToolStatus, Tools,
+    EXTERNAL_TOOLS, TOOL_TIMEOUT,
+};
+#[cfg(any(test, feature = "fake-tools"))]
+pub use fake_tools::{FakeRun, FakeTools};
+pub use types::TypeInfo;
+
+use hierarchy::Hierarchy;
+
+/// 外部依存の節に並べるマニフェストに宣言されていないimportの最大数
+const MAX_UNDECLARED_IMPORTS: usize = 20;
+/// 型の節で要約する型の最大数
+const MAX_TYPES: usize = 10;
+/// 未使用コードの可能性の節に並べるモジュール・関数の最大数（それぞれ）
+const MAX_UNUSED_ITEMS: usize = 10;
 
 /// サマライザー
 #[derive(Clone)]
 pub struct Summarizer {
-    #[allow(dead_code)]
     config: Config,
+    /// ローカルLLM（`summarization.mode = "local"` の場合のみ）
+    llm: Option<OllamaClient>,
+    /// LLMの失敗・省略した要約などの警告の記録先
+    diagnostics: Diagnostics,
+    /// Wiki・スライドの組み立てに使うテンプレート
+    templates: Templates,
 }
 
 impl Summarizer {
@@ -40,16 +100,54 @@ impl Summarizer {
     /// # 戻り値
     /// * `Self` - サマライザーインスタンス
     pub fn new(config: Config) -> Self {
-        Self { config }
+        let llm = match config.summarization.mode.as_str() {
+            "local" => Some(OllamaClient::new(&config)),
+            _ => None,
+        };
+        Self { config, llm, diagnostics: Diagnostics::new(), templates: Templates::builtin() }
+    }
+
+    /// テンプレートを指定（`templates.dir` で上書きしたテンプレートをビルドごとに一度だけ読み込んで共有する場合）
+    /// 
+    /// # 引数
+    /// * `templates` - テンプレート集
+    /// 
+    /// # 戻り値
+    /// * `Self` - テンプレートを差し替えたサマライザー
+    pub fn with_templates(mut self, templates: Templates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Wiki・スライドの組み立てに使うテンプレート
+    pub fn templates(&self) -> &Templates {
+        &self.templates
+    }
+
+    /// 警告の記録先を指定（解析・Wiki・スライドと共有して最後にまとめて表示する場合）
+    /// 
+    /// # 引数
+    /// * `diagnostics` - 警告の記録先
+    /// 
+    /// # 戻り値
+    /// * `Self` - 記録先を差し替えたサマライザー
+    pub fn with_diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// 警告の記録先
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
     }
 
     /// 要約を生成
     /// 
     /// # 引数
     /// * `index` - インデックス
-    /// * `scope` - スコープ（repo|package|module|file）
-    /// * `target` - 対象（パスまたはモジュールID）
-    /// * `style` - スタイル（concise-ja|detailed-ja）
+    /// * `scope` - スコープ（repo|package|module|file|readme）
+    /// * `target` - 対象（パスまたはモジュールID、repo・readmeの場合は無視）
+    /// * `style` - スタイル（concise-ja|detailed-ja|concise-en|detailed-en）
     /// 
     /// # 戻り値
     /// * `Result<SummarizeResult>` - 要約結果、またはエラー
@@ -62,23 +160,254 @@ impl Summarizer {
     ) -> Result<SummarizeResult> {
         info!("要約生成開始: scope={}, target={}, style={}", scope, target, style);
 
-        let content_md = match scope {
-            "repo" => self.summarize_repo(index, style).await?,
-            "package" => self.summarize_package(index, target, style).await?,
-            "module" => self.summarize_module(index, target, style).await?,
-            "file" => self.summarize_file(index, target, style).await?,
+        let mut citations = Vec::new();
+        let mut content_md = match scope {
+            "repo" => self.summarize_repo(index, style, &mut citations).await?,
+            "package" => self.summarize_package(index, target, style, &mut citations).await?,
+            "module" => self.summarize_module(index, target, style, &mut citations).await?,
+            "file" => self.summarize_file(index, target, style, &mut citations).await?,
+            "readme" => self.summarize_readme(index, style, &mut citations).await?,
             _ => return Err(anyhow::anyhow!("不明なスコープ: {}", scope)),
         };
 
+        if let Some(llm) = &self.llm {
+            match self.explain_with_llm(llm, index, scope, target, style, &content_md, &mut citations).await {
+                Ok(explanation) => content_md = insert_after_title(&content_md, &explanation),
+                Err(e) => self
+                    .diagnostics
+                    .warn("summarizer", format!("ローカルLLMでの要約に失敗したため静的解析の結果を使用しました: {}", e)),
+            }
+        }
+        // LLMの解説・READMEの抜粋に残った独自の規則の値を置き換える
+        let mut content_md = index.redaction.redact_text(&content_md);
+        content_md.push_str(&self.render_footnotes(&citations));
+
         let artifacts = self.generate_artifacts(index, scope, target).await?;
 
         Ok(SummarizeResult {
             ok: true,
             content_md,
             artifacts,
+            citations,
+            diagnostics: self.diagnostics.entries(),
         })
     }
 
+    /// 要約に使う方式
+    /// 
+    /// # 戻り値
+    /// * `&'static str` - llm-local（Ollama、接続できない場合はヒューリスティック）またはheuristic
+    pub fn mode_label(&self) -> &'static str {
+        if self.llm.is_some() {
+            "llm-local"
+        } else if self.is_extractive() {
+            "extractive"
+        } else {
+            "heuristic"
+        }
+    }
+
+    /// ファイル自身のコメント・READMEを抜き出して説明するか（`summarization.mode = "extractive"`）
+    pub fn is_extractive(&self) -> bool {
+        self.config.summarization.mode == "extractive"
+    }
+
+    /// ファイル冒頭のコメント、なければパッケージのREADMEの最初の段落からモジュールの説明を作成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `file` - 説明するファイル
+    /// * `citations` - 引用の追加先（抜き出した行範囲）
+    /// 
+    /// # 戻り値
+    /// * `Option<String>` - 脚注参照付きの説明（抜き出せる説明文がない場合はNone）
+    pub fn extractive_description(&self, index: &Index, file: &FileInfo, citations: &mut Vec<Citation>) -> Option<String> {
+        if let Some(excerpt) = index.file_content(file).and_then(|c| extractive::header_comment(&c)) {
+            let text = extractive::rewrite(&excerpt.text, "ファイル冒頭のコメント");
+            if !text.is_empty() {
+                let citation = self.cite(index, &file.path, excerpt.start_line, excerpt.end_line);
+                let marker = citation.marker();
+                citations.push(citation);
+                return Some(format!("{}{}", text, marker));
+            }
+        }
+
+        let readme = index.package_readme(&file.path)?;
+        let excerpt = extractive::readme_excerpt(&readme.content)?;
+        let text = extractive::rewrite(&excerpt.text, "README");
+        if text.is_empty() {
+            return None;
+        }
+        let citation = self.cite(index, &readme.dir.join("README.md"), excerpt.start_line, excerpt.end_line);
+        let marker = citation.marker();
+        citations.push(citation);
+        Some(format!("{}（パッケージの説明）{}", text, marker))
+    }
+
+    /// 引用を作成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `path` - 根拠となるファイルのパス
+    /// * `start_line` - 開始行（1始まり）
+    /// * `end_line` - 終了行（1始まり、開始行を含む）
+    /// 
+    /// # 戻り値
+    /// * `Citation` - リポジトリルートからの相対パスで表した引用
+    pub fn cite(&self, index: &Index, path: &Path, start_line: usize, end_line: usize) -> Citation {
+        Citation {
+            path: path.strip_prefix(&index.repo_path).unwrap_or(path).to_path_buf(),
+            start_line: start_line.max(1),
+            end_line: end_line.max(start_line.max(1)),
+        }
+    }
+
+    /// ファイルへのリンクをMarkdownで生成
+    /// 
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `path` - ファイルパス
+    /// 
+    /// # 戻り値
+    /// * `String` - `project.source-url` 配下へのリンク（設定されていない場合はコード表記のパス）
+    pub fn file_link(&self, index: &Index, path: &Path) -> String {
+        let relative = path.strip_prefix(&index.repo_path).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        match self.config.project.source_url.as_deref() {
+            Some(base) => format!("[`{}`]({}/{})", relative, base.trim_end_matches('/'), relative),
+            None => format!("`{}`", relative),
+        }
+    }
+
+    /// 引用の脚注定義をMarkdownで生成
+    /// 
+    /// `project.source-url` が設定されている場合は行番号付きのリンクにする。
+    /// 
+    /// # 引数
+    /// * `citations` - 引用のリスト
+    /// 
+    /// # 戻り値
+    /// * `String` - 脚注定義（引用がない場合は空文字列）
+    pub fn render_footnotes(&self, citations: &[Citation]) -> String {
+        let source_url = self.config.project.source_url.as_deref();
+        let mut labels = std::collections::HashSet::new();
+        let mut footnotes = String::new();
+
+        for citation in citations {
+            if !labels.insert(citation.label()) {
+                continue;
+            }
+            let location = citation.location();
+            let text = match citation.url(source_url) {
+                Some(url) => format!("[{}]({})", location, url),
+                None => format!("`{}`", location),
+            };
+            footnotes.push_str(&format!("[^{}]: {}\n", citation.label(), text));
+        }
+
+        if footnotes.is_empty() {
+            footnotes
+        } else {
+            format!("\n{}", footnotes)
+        }
+    }
+
+    /// ローカルLLMで解説を生成
+    /// 
+    /// # 引数
+    /// * `llm` - Ollamaクライアント
+    /// * `index` - インデックス
+    /// * `scope` - スコープ
+    /// * `target` - 対象
+    /// * `style` - スタイル
+    /// * `draft` - 静的ヒューリスティックによる要約
+    /// * `citations` - 引用の追加先
+    /// 
+    /// # 戻り値
+    /// * `Result<String>` - 「## 解説」セクションのMarkdown、またはエラー
+    #[allow(clippy::too_many_arguments)]
+    async fn explain_with_llm(
+        &self,
+        llm: &OllamaClient,
+        index: &Index,
+        scope: &str,
+        target: &str,
+        style: &str,
+        draft: &str,
+        citations: &mut Vec<Citation>,
+    ) -> Result<String> {
+        // module/fileスコープではソースコードを渡し、その範囲を引用とする
+        let file = match scope {
+            "module" | "file" => index.files.iter().find(|f| f.path == Path::new(target)),
+            _ => None,
+        };
+        // コンテキストに収まらないファイルと、リポジトリ・パッケージは段階的に要約してから渡す
+        // （明らかに収まらない大きさのファイルは全体を読み込まず、チャンクごとに読む）
+        let hierarchy = Hierarchy::new(llm);
+        let source = file.filter(|f| hierarchy.may_fit(f)).and_then(|f| index.file_content(f));
+        let path = Path::new(target);
+        let label = path.strip_prefix(&index.repo_path).unwrap_or(path).to_string_lossy().replace('\\', "/");
+        let (source, digest) = match (scope, file, source) {
+            (_, _, Some(source)) if hierarchy.fits(&source) => (Some(source), None),
+            (_, Some(_), Some(source)) => (None, Some(hierarchy.summarize_file(&label, &source).await?)),
+            (_, Some(file), None) => (None, hierarchy.summarize_indexed_file(index, file, &label).await?),
+            ("repo" | "readme", ..) => {
+                let label = index.repo_path.file_name().and_then(|n| n.to_str()).unwrap_or("リポジトリ");
+                let files = self.map_files(index, index.files.iter().collect());
+                (None, Some(hierarchy.summarize_files(index, &files, label).await?))
+            }
+            ("package", ..) => {
+                let files = match index.find_package(target) {
+                    Some(package) => index.package_files(package),
+                    None => index.files.iter().filter(|f| f.path.starts_with(target)).collect(),
+                };
+                let files = self.map_files(index, files);
+                (None, Some(hierarchy.summarize_files(index, &files, target).await?))
+            }
+            _ => (None, None),
+        };
+
+        let prompt = llm::build_prompt(scope, style, draft, source.as_deref(), digest.as_deref());
+        let explanation = llm.generate(&prompt).await?;
+        if explanation.is_empty() {
+            return Err(anyhow::anyhow!("ローカルLLMの応答が空です"));
+        }
+
+        let marker = match file {
+            Some(f) => self.cite_file(index, f, citations),
+            None => String::new(),
+        };
+        Ok(format!("## 解説\n\n{}{}\n\n", explanation, marker))
+    }
+
+    /// 段階的な要約で個別に要約するファイルを重要度の高い順に `summarization.max-map-files` 件まで選ぶ
+    fn map_files<'a>(&self, index: &'a Index, mut files: Vec<&'a FileInfo>) -> Vec<&'a FileInfo> {
+        let importance = |file: &FileInfo| index.module_at(&file.path).map(|m| m.importance).unwrap_or_default();
+        files.sort_by(|a, b| {
+            importance(b)
+                .partial_cmp(&importance(a))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        let limit = self.config.summarization.max_map_files;
+        if files.len() > limit {
+            self.diagnostics.warn(
+                "summarizer",
+                format!("段階的な要約は重要度の高い{}ファイルのみを対象にしました（全{}ファイル）", limit, files.len()),
+            );
+            files.truncate(limit);
+        }
+        files
+    }
+
+    /// ファイル全体を根拠とする引用を追加し、脚注参照を返す
+    fn cite_file(&self, index: &Index, file: &FileInfo, citations: &mut Vec<Citation>) -> String {
+        let lines = index.content_lines(file).map(|lines| lines.count()).unwrap_or(1);
+        let citation = self.cite(index, &file.path, 1, lines);
+        let marker = citation.marker();
+        citations.push(citation);
+        marker
+    }
+
     /// リポジトリ全体の要約を生成
     /// 
     /// # 引数
@@ -87,44 +416,84 @@ impl Summarizer {
     /// 
     /// # 戻り値
     /// * `Result<String>` - Markdown形式の要約、またはエラー
-    async fn summarize_repo(&self, index: &Index, style: &str) -> Result<String> {
+    async fn summarize_repo(&self, index: &Index, style: &str, citations: &mut Vec<Citation>) -> Result<String> {
+        let locale = Locale::from_style(style);
         let mut sections = Vec::new();
 
         // 概要
         sections.push(format!(
-            "# {}\n\n{}ファイル、{}言語、{}モジュールを含むリポジトリです。\n",
+            "# {}\n\n{}\n",
             index.repo_path.file_name()
                 .and_then(|n| n.to_str())
-                .unwrap_or("リポジトリ"),
-            index.stats.files,
-            index.stats.languages.len(),
-            index.stats.modules
+                .unwrap_or(locale.summary_text("repo.fallback")),
+            locale.summary_format("repo.stats", &[&index.stats.files, &index.stats.languages.len(), &index.stats.modules])
         ));
 
+        // リポジトリ直下のREADMEの最初の段落
+        if self.is_extractive() {
+            let readme = std::fs::read_to_string(index.repo_path.join("README.md")).ok();
+            if let Some(excerpt) = readme.as_deref().and_then(extractive::readme_excerpt) {
+                let text = extractive::rewrite(&excerpt.text, "README");
+                if !text.is_empty() {
+                    let citation = self.cite(index, &index.repo_path.join("README.md"), excerpt.start_line, excerpt.end_line);
+                    sections.push(format!("\n{}{}\n", text, citation.marker()));
+                    citations.push(citation);
+                }
+            }
+        }
+
         // 目的・コンポーネント
-        if style == "detailed-ja" {
-            sections.push("## 目的・コンポーネント\n\n".to_string());
-            sections.push(self.infer_purpose(index).await);
+        if is_detailed(style) {
+            sections.push(format!("## {}\n\n", locale.summary_text("repo.purpose")));
+            sections.push(self.infer_purpose(index, locale).await);
             sections.push("\n".to_string());
         }
 
         // 主要データ・ユーザーフロー
-        sections.push("## 主要コンポーネント\n\n".to_string());
-        sections.push(self.describe_components(index).await);
+        sections.push(format!("## {}\n\n", locale.summary_text("repo.components")));
+        sections.push(self.describe_components(index, locale, citations).await);
         sections.push("\n".to_string());
 
         // 外部依存
-        if !index.dependencies.is_empty() {
-            sections.push("## 外部依存\n\n".to_string());
-            sections.push(self.describe_dependencies(index).await);
+        if !index.manifests.is_empty() || index.dependencies.values().any(|paths| paths.is_empty()) {
+            sections.push(format!("## {}\n\n", locale.summary_text("repo.dependencies")));
+            sections.push(self.describe_dependencies(index, locale).await);
+            sections.push("\n".to_string());
+        }
+
+        // 内部の結合度
+        if !index.internal_dependencies.is_empty() {
+            sections.push(format!("## {}\n\n", locale.summary_text("repo.coupling")));
+            sections.push(self.describe_coupling(index, locale));
             sections.push("\n".to_string());
         }
 
+        // 未使用コードの可能性
+        if is_detailed(style) {
+            let unused = index.unused_code();
+            if !unused.is_empty() {
+                sections.push(format!("## {}\n\n", locale.summary_text("repo.unused")));
+                sections.push(self.describe_unused(index, &unused, locale, citations));
+                sections.push("\n".to_string());
+            }
+        }
+
         // エントリーポイント
-        if !index.entrypoints.is_empty() {
-            sections.push("## エントリーポイント\n\n".to_string());
-            for ep in &index.entrypoints {
-                sections.push(format!("- `{}`\n", ep.display()));
+        let entrypoints = index.all_entrypoints();
+        if !entrypoints.is_empty() {
+            sections.push(format!("## {}\n\n", locale.summary_text("repo.entrypoints")));
+            for ep in &entrypoints {
+                let marker = match (index.files.iter().find(|f| f.path == ep.path), ep.line) {
+                    (Some(_), Some(line)) => {
+                        let citation = self.cite(index, &ep.path, line, line);
+                        let marker = citation.marker();
+                        citations.push(citation);
+                        marker
+                    }
+                    (Some(file), None) => self.cite_file(index, file, citations),
+                    (None, _) => String::new(),
+                };
+                sections.push(format!("- {}{}\n", ep.describe(&index.repo_path), marker));
             }
             sections.push("\n".to_string());
         }
@@ -141,30 +510,69 @@ impl Summarizer {
     /// 
     /// # 戻り値
     /// * `Result<String>` - Markdown形式の要約、またはエラー
-    async fn summarize_package(&self, index: &Index, target: &str, _style: &str) -> Result<String> {
-        let target_path = Path::new(target);
-        let package_files: Vec<&FileInfo> = index
-            .files
-            .iter()
-            .filter(|f| f.path.starts_with(target_path))
-            .collect();
+    async fn summarize_package(
+        &self,
+        index: &Index,
+        target: &str,
+        style: &str,
+        citations: &mut Vec<Citation>,
+    ) -> Result<String> {
+        let locale = Locale::from_style(style);
+        // ワークスペースのパッケージ（モノレポでない場合はパスの前方一致）
+        let package = index.find_package(target);
+        let target_path = match package {
+            Some(package) => index.repo_path.join(&package.path),
+            None => PathBuf::from(target),
+        };
+        let package_files: Vec<&FileInfo> = match package {
+            Some(package) => index.package_files(package),
+            None => index.files.iter().filter(|f| f.path.starts_with(&target_path)).collect(),
+        };
 
         if package_files.is_empty() {
             return Err(anyhow::anyhow!("パッケージが見つかりません: {}", target));
         }
 
         let mut sections = Vec::new();
-        sections.push(format!("# {}\n\n", target_path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("パッケージ")));
+        let title = match package {
+            Some(package) => package.name.as_str(),
+            None => target_path.file_name().and_then(|n| n.to_str()).unwrap_or(locale.summary_text("package.fallback")),
+        };
+        sections.push(format!("# {}\n\n", title));
+
+        match package {
+            Some(package) => sections.push(format!(
+                "{}\n\n",
+                locale.summary_format(
+                    "package.manifest",
+                    &[&package.ecosystem, &package.manifest.display(), &package_files.len()]
+                )
+            )),
+            None => sections.push(format!("{}\n\n", locale.summary_format("package.files", &[&package_files.len()]))),
+        }
 
-        sections.push(format!("{}ファイルを含むパッケージです。\n\n", package_files.len()));
+        if self.is_extractive() {
+            let readme = index.readmes.iter().find(|r| r.dir == target_path);
+            if let Some((readme, excerpt)) = readme.and_then(|r| Some((r, extractive::readme_excerpt(&r.content)?))) {
+                let text = extractive::rewrite(&excerpt.text, "README");
+                if !text.is_empty() {
+                    let citation = self.cite(index, &readme.dir.join("README.md"), excerpt.start_line, excerpt.end_line);
+                    sections.push(format!("{}{}\n\n", text, citation.marker()));
+                    citations.push(citation);
+                }
+            }
+        }
+
+        if let Some(package) = package {
+            sections.push(self.describe_package_relations(index, package, locale));
+        }
 
         // モジュール一覧
-        sections.push("## モジュール\n\n".to_string());
+        sections.push(format!("## {}\n\n", locale.summary_text("package.modules")));
         for file in &package_files {
             if file.is_module {
-                sections.push(format!("- `{}`\n", file.path.display()));
+                let marker = self.cite_file(index, file, citations);
+                sections.push(format!("- `{}`{}\n", file.path.display(), marker));
             }
         }
 
@@ -180,36 +588,62 @@ impl Summarizer {
     /// 
     /// # 戻り値
     /// * `Result<String>` - Markdown形式の要約、またはエラー
-    async fn summarize_module(&self, index: &Index, target: &str, style: &str) -> Result<String> {
+    async fn summarize_module(
+        &self,
+        index: &Index,
+        target: &str,
+        style: &str,
+        citations: &mut Vec<Citation>,
+    ) -> Result<String> {
         let target_path = Path::new(target);
         let file_info = index
             .files
             .iter()
             .find(|f| f.path == target_path)
             .ok_or_else(|| anyhow::anyhow!("モジュールが見つかりません: {}", target))?;
+        let locale = Locale::from_style(style);
 
         let mut sections = Vec::new();
         sections.push(format!("# {}\n\n", file_info.name));
 
-        // 役割
-        sections.push("## 役割\n\n".to_string());
-        sections.push(self.infer_role(file_info).await);
-        sections.push("\n".to_string());
+        // 役割（抽出型ではファイル自身の説明、なければファイル全体から推定）
+        sections.push(format!("## {}\n\n", locale.summary_text("module.role")));
+        let extracted = if self.is_extractive() {
+            self.extractive_description(index, file_info, citations)
+        } else {
+            None
+        };
+        let file_marker = self.cite_file(index, file_info, citations);
+        match extracted {
+            Some(description) => sections.push(format!("{}\n", description)),
+            None => {
+                sections.push(self.infer_role(file_info, locale).await);
+                sections.push(format!("{}\n", file_marker));
+            }
+        }
 
         // 公開API（簡易的な推定）
         if !file_info.dependencies.is_empty() {
-            sections.push("## 依存関係\n\n".to_string());
+            sections.push(format!("## {}\n\n", locale.summary_text("module.dependencies")));
             for dep in &file_info.dependencies {
                 sections.push(format!("- `{}`\n", dep));
             }
             sections.push("\n".to_string());
         }
 
+        // 型ごとの責務・フィールド・主要なメソッド
+        if is_detailed(style) {
+            if let Some(content) = index.file_content(file_info) {
+                let types = self.extract_types_detailed(&content, &file_info.language);
+                sections.push(self.summarize_types(&types, locale, "##").await);
+            }
+        }
+
         // 注意点（静的ヒューリスティック）
-        if style == "detailed-ja" {
-            sections.push("## 注意点\n\n".to_string());
-            sections.push(self.infer_notes(file_info).await);
-            sections.push("\n".to_string());
+        if is_detailed(style) {
+            sections.push(format!("## {}\n\n", locale.summary_text("module.notes")));
+            sections.push(self.infer_notes(file_info, locale).await);
+            sections.push(format!("{}\n", file_marker));
         }
 
         Ok(sections.join(""))
@@ -224,28 +658,41 @@ impl Summarizer {
     /// 
     /// # 戻り値
     /// * `Result<String>` - Markdown形式の要約、またはエラー
-    async fn summarize_file(&self, index: &Index, target: &str, _style: &str) -> Result<String> {
+    async fn summarize_file(
+        &self,
+        index: &Index,
+        target: &str,
+        style: &str,
+        citations: &mut Vec<Citation>,
+    ) -> Result<String> {
         let target_path = Path::new(target);
         let file_info = index
             .files
             .iter()
             .find(|f| f.path == target_path)
             .ok_or_else(|| anyhow::anyhow!("ファイルが見つかりません: {}", target))?;
+        let locale = Locale::from_style(style);
 
         let mut sections = Vec::new();
         sections.push(format!("# {}\n\n", file_info.name));
 
-        if let Some(content) = &file_info.content {
-            sections.push("## 概要\n\n".to_string());
-            sections.push(self.summarize_content(content, &file_info.language).await);
-            sections.push("\n".to_string());
+        if let Some(content) = index.file_content(file_info) {
+            if self.is_extractive() {
+                if let Some(description) = self.extractive_description(index, file_info, citations) {
+                    sections.push(format!("{}\n\n", description));
+                }
+            }
+            let marker = self.cite_file(index, file_info, citations);
+            sections.push(format!("## {}\n\n", locale.summary_text("file.overview")));
+            sections.push(self.summarize_content(&content, &file_info.language, locale).await);
+            sections.push(format!("{}\n", marker));
         }
 
         Ok(sections.join(""))
     }
 
     /// 目的を推定
-    async fn infer_purpose(&self, index: &Index) -> String {
+    async fn infer_purpose(&self, index: &Index, locale: Locale) -> String {
         let mut purposes = Vec::new();
 
         // ファイル名から推測
@@ -256,91 +703,237 @@ impl Summarizer {
             .collect();
 
         if !main_files.is_empty() {
-            purposes.push("アプリケーションまたはサーバーとして動作する可能性があります。".to_string());
+            purposes.push(locale.summary_text("purpose.app").to_string());
         }
 
         // 依存関係から推測
-        for (dep, _) in &index.dependencies {
+        for dep in index.dependencies.keys() {
             if dep.contains("express") || dep.contains("fastapi") || dep.contains("flask") {
-                purposes.push("WebアプリケーションまたはAPIサーバーです。".to_string());
+                purposes.push(locale.summary_text("purpose.web").to_string());
                 break;
             }
             if dep.contains("react") || dep.contains("vue") || dep.contains("angular") {
-                purposes.push("フロントエンドアプリケーションです。".to_string());
+                purposes.push(locale.summary_text("purpose.frontend").to_string());
                 break;
             }
         }
 
         if purposes.is_empty() {
-            purposes.push("コードベースの目的を特定するには追加の分析が必要です。".to_string());
+            purposes.push(locale.summary_text("purpose.unknown").to_string());
         }
 
         purposes.join("\n")
     }
 
     /// コンポーネントを記述
-    async fn describe_components(&self, index: &Index) -> String {
+    async fn describe_components(&self, index: &Index, locale: Locale, citations: &mut Vec<Citation>) -> String {
         let mut descriptions = Vec::new();
 
         // モジュールごとに説明
         for module in &index.modules {
-            descriptions.push(format!(
-                "- **{}** (`{}`): {}言語で記述されたモジュール",
-                module.name,
-                module.path.display(),
-                module.language
-            ));
+            let marker = match index.files.iter().find(|f| f.path == module.path) {
+                Some(file) => self.cite_file(index, file, citations),
+                None => String::new(),
+            };
+            let extracted = match index.files.iter().find(|f| f.path == module.path) {
+                Some(file) if self.is_extractive() => self.extractive_description(index, file, citations),
+                _ => None,
+            };
+            let description = match extracted {
+                Some(description) => description,
+                None => locale.summary_format("components.module", &[&module.language, &marker]),
+            };
+            descriptions.push(format!("- **{}** (`{}`): {}", module.id, module.path.display(), description));
         }
 
         if descriptions.is_empty() {
-            descriptions.push("コンポーネント情報がありません。".to_string());
+            descriptions.push(locale.summary_text("components.none").to_string());
         }
 
         descriptions.join("\n")
     }
 
-    /// 依存関係を記述
-    async fn describe_dependencies(&self, index: &Index) -> String {
-        let mut deps_list = Vec::new();
-        for (dep, _) in &index.dependencies {
-            deps_list.push(format!("- `{}`", dep));
+    /// 依存関係を記述（リポジトリ内のファイルに解決したimportを除く）
+    async fn describe_dependencies(&self, index: &Index, locale: Locale) -> String {
+        let mut deps: Vec<&String> = index
+            .dependencies
+            .iter()
+            .filter(|(_, paths)| paths.is_empty())
+            .map(|(dep, _)| dep)
+            .collect();
+        deps.sort();
+        if index.manifests.is_empty() {
+            let deps_list: Vec<String> = deps.iter().map(|dep| format!("- `{}`", dep)).collect();
+            return deps_list.join("\n");
+        }
+
+        // マニフェストに宣言された依存（バージョン付き）
+        let packages = index.external_packages();
+        let mut lines = Vec::new();
+        if !packages.is_empty() {
+            lines.push(locale.summary_text("dependencies.header").to_string());
+            lines.push("|---|---|---|---|".to_string());
+            for (manifest, dep) in &packages {
+                let kind = match dep.kind.as_str() {
+                    "dev" => locale.summary_text("dependencies.dev"),
+                    "build" => locale.summary_text("dependencies.build"),
+                    _ => locale.summary_text("dependencies.normal"),
+                };
+                lines.push(format!(
+                    "| `{}` | {} | {} | `{}` |",
+                    dep.name,
+                    dep.version.as_deref().unwrap_or("-"),
+                    kind,
+                    manifest.path.display()
+                ));
+            }
+        }
+
+        let mut internal: Vec<&str> = index
+            .manifests
+            .iter()
+            .flat_map(|m| &m.dependencies)
+            .filter(|dep| dep.internal)
+            .map(|dep| dep.name.as_str())
+            .collect();
+        internal.sort();
+        internal.dedup();
+        if !internal.is_empty() {
+            let names: Vec<String> = internal.iter().map(|name| format!("`{}`", name)).collect();
+            lines.push(format!("\n{}", locale.summary_format("dependencies.internal", &[&names.join(", ")])));
+        }
+
+        // マニフェストで説明できないimport（標準ライブラリ・未宣言の依存）
+        let declared: Vec<&str> = index.manifests.iter().flat_map(|m| &m.dependencies).map(|d| d.name.as_str()).collect();
+        let undeclared: Vec<String> = deps
+            .iter()
+            .filter(|dep| !declared.iter().any(|name| import_matches_package(dep, name)))
+            .take(MAX_UNDECLARED_IMPORTS)
+            .map(|dep| format!("`{}`", dep))
+            .collect();
+        if !undeclared.is_empty() {
+            lines.push(format!("\n{}", locale.summary_format("dependencies.undeclared", &[&undeclared.join(", ")])));
+        }
+        lines.join("\n")
+    }
+
+    /// パッケージ間の依存と、マニフェストに宣言された外部依存を記述
+    fn describe_package_relations(&self, index: &Index, package: &analyzer_core::PackageInfo, locale: Locale) -> String {
+        let names = |names: Vec<&str>| names.iter().map(|n| format!("`{}`", n)).collect::<Vec<_>>().join(", ");
+        let mut content = String::new();
+
+        let dependents: Vec<&str> = index
+            .packages
+            .iter()
+            .filter(|p| p.dependencies.contains(&package.name))
+            .map(|p| p.name.as_str())
+            .collect();
+        if !package.dependencies.is_empty() || !dependents.is_empty() {
+            content.push_str(&format!("## {}\n\n", locale.summary_text("package.relations")));
+            if !package.dependencies.is_empty() {
+                let depends_on = names(package.dependencies.iter().map(String::as_str).collect());
+                content.push_str(&format!("- {}: {}\n", locale.summary_text("package.depends-on"), depends_on));
+            }
+            if !dependents.is_empty() {
+                content.push_str(&format!("- {}: {}\n", locale.summary_text("package.dependents"), names(dependents)));
+            }
+            content.push('\n');
+        }
+
+        let manifest = index.manifests.iter().find(|m| m.path == package.manifest);
+        let external: Vec<String> = manifest
+            .into_iter()
+            .flat_map(|m| &m.dependencies)
+            .filter(|dep| !dep.internal && dep.kind == "normal")
+            .map(|dep| match &dep.version {
+                Some(version) => format!("- `{}` {}", dep.name, version),
+                None => format!("- `{}`", dep.name),
+            })
+            .collect();
+        if !external.is_empty() {
+            content.push_str(&format!("## {}\n\n", locale.summary_text("repo.dependencies")));
+            content.push_str(&external.join("\n"));
+            content.push_str("\n\n");
         }
-        deps_list.join("\n")
+        content
     }
 
-    /// 役割を推定
-    async fn infer_role(&self, file_info: &FileInfo) -> String {
-        let name_lower = file_info.name.to_lowercase();
+    /// 多くのファイルからimportされているファイルを記述
+    fn describe_coupling(&self, index: &Index, locale: Locale) -> String {
+        let mut dependents: std::collections::BTreeMap<&PathBuf, usize> = std::collections::BTreeMap::new();
+        for targets in index.internal_dependencies.values() {
+            for target in targets {
+                *dependents.entry(target).or_default() += 1;
+            }
+        }
+        let mut ranked: Vec<(&PathBuf, usize)> = dependents.into_iter().collect();
+        ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let edges: usize = index.internal_dependencies.values().map(|t| t.len()).sum();
+        let mut lines = vec![locale.summary_format("coupling.intro", &[&index.internal_dependencies.len(), &edges])];
+        for (path, count) in ranked.into_iter().take(5) {
+            let relative = path.strip_prefix(&index.repo_path).unwrap_or(path);
+            lines.push(locale.summary_format("coupling.item", &[&relative.display(), &count]));
+        }
+        lines.join("\n")
+    }
 
-        if name_lower.contains("config") || name_lower.contains("setting") {
-            "設定管理を行うモジュールです。".to_string()
-        } else if name_lower.contains("api") || name_lower.contains("route") {
-            "APIエンドポイントまたはルーティングを定義するモジュールです。".to_string()
-        } else if name_lower.contains("util") || name_lower.contains("helper") {
-            "ユーティリティ関数を提供するモジュールです。".to_string()
-        } else if name_lower.contains("model") || name_lower.contains("schema") {
-            "データモデルまたはスキーマを定義するモジュールです。".to_string()
-        } else if name_lower.contains("service") || name_lower.contains("business") {
-            "ビジネスロジックを実装するモジュールです。".to_string()
-        } else {
-            format!("{}で記述されたモジュールです。", file_info.language)
+    /// 未使用コードの候補を説明（モジュール・関数はそれぞれ `MAX_UNUSED_ITEMS` 件まで）
+    fn describe_unused(
+        &self,
+        index: &Index,
+        unused: &analyzer_core::UnusedCode,
+        locale: Locale,
+        citations: &mut Vec<Citation>,
+    ) -> String {
+        let mut content = String::new();
+        if !unused.orphan_modules.is_empty() {
+            content.push_str(&format!("{}\n\n", locale.summary_format("unused.orphans", &[&unused.orphan_modules.len()])));
+            for path in unused.orphan_modules.iter().take(MAX_UNUSED_ITEMS) {
+                content.push_str(&format!("- {}\n", self.file_link(index, path)));
+            }
+            content.push('\n');
+        }
+        if !unused.unreferenced_symbols.is_empty() {
+            content.push_str(&format!(
+                "{}\n\n",
+                locale.summary_format("unused.symbols", &[&unused.unreferenced_symbols.len()])
+            ));
+            for symbol in unused.unreferenced_symbols.iter().take(MAX_UNUSED_ITEMS) {
+                let citation = self.cite(index, &symbol.path, symbol.line, symbol.line);
+                content.push_str(&format!("- `{}`{}\n", symbol.qualified_name(), citation.marker()));
+                citations.push(citation);
+            }
+            content.push('\n');
         }
+        content.push_str(locale.summary_text("unused.caution"));
+        content.push('\n');
+        content
+    }
+
+    /// 役割を推定（モジュールのコンポーネントの種類と同じ分類）
+    async fn infer_role(&self, file_info: &FileInfo, locale: Locale) -> String {
+        let key = match ComponentKind::classify(&file_info.path) {
+            ComponentKind::Other => return locale.summary_format("role.other", &[&file_info.language]),
+            kind => format!("role.{}", kind.as_str()),
+        };
+        locale.summary_text(&key).to_string()
     }
 
     /// 注意点を推定
-    async fn infer_notes(&self, file_info: &FileInfo) -> String {
+    async fn infer_notes(&self, file_info: &FileInfo, locale: Locale) -> String {
         let mut notes = Vec::new();
 
         if file_info.size > 10000 {
-            notes.push("ファイルサイズが大きいため、リファクタリングを検討してください。".to_string());
+            notes.push(locale.summary_text("notes.large"));
         }
 
         if file_info.dependencies.len() > 20 {
-            notes.push("依存関係が多く、結合度が高い可能性があります。".to_string());
+            notes.push(locale.summary_text("notes.coupled"));
         }
 
         if notes.is_empty() {
-            notes.push("特に注意すべき点は見つかりませんでした。".to_string());
+            notes.push(locale.summary_text("notes.none"));
         }
 
         notes.join("\n")
@@ -348,6 +941,9 @@ impl Summarizer {
 
     /// メソッド単位での詳細な解説を生成
     /// 
+    /// tree-sitterで抽出した関数・メソッド（implブロック・クラスのメソッドを含む）について、
+    /// 直前のコメントとコード範囲を返す。
+    /// 
     /// # 引数
     /// * `content` - ファイル内容
     /// * `language` - 言語
@@ -355,238 +951,153 @@ impl Summarizer {
     /// # 戻り値
     /// * `Vec<MethodInfo>` - メソッド情報のリスト
     pub fn extract_methods_detailed(&self, content: &str, language: &str) -> Vec<MethodInfo> {
-        let mut methods = Vec::new();
-
-        match language {
-            "rs" => {
-                // Rust関数を詳細に抽出
-                let func_re = regex::Regex::new(r"(?:pub\s+)?(?:async\s+)?fn\s+(\w+)\s*\([^)]*\)\s*(?:->\s*[^{]+)?\s*\{").unwrap();
-                for cap in func_re.captures_iter(content) {
-                    if let Some(name) = cap.get(1) {
-                        let func_name = name.as_str();
-                        // 関数の前のコメントを探す
-                        let lines: Vec<&str> = content.lines().collect();
-                        let mut doc = String::new();
-                        for (i, line) in lines.iter().enumerate() {
-                            if line.contains(&format!("fn {}", func_name)) {
-                                // 前の行のコメントを収集
-                                let mut j = i.saturating_sub(1);
-                                while j > 0 && (lines[j].trim_start().starts_with("///") || lines[j].trim().is_empty()) {
-                                    if lines[j].trim_start().starts_with("///") {
-                                        doc.push_str(&lines[j].trim_start().trim_start_matches("///").trim());
-                                        doc.push_str("\n");
-                                    }
-                                    j = j.saturating_sub(1);
-                                }
-                                break;
-                            }
-                        }
-                        
-                        methods.push(MethodInfo {
-                            name: func_name.to_string(),
-                            language: language.to_string(),
-                            documentation: doc.trim().to_string(),
-                            code_snippet: self.extract_method_code(content, func_name, language),
-                        });
-                    }
-                }
-            }
-            "ts" | "js" => {
-                // JavaScript/TypeScript関数を詳細に抽出
-                let func_re = regex::Regex::new(r"(?:export\s+)?(?:async\s+)?function\s+(\w+)\s*\([^)]*\)\s*(?::\s*[^{]+)?\s*\{").unwrap();
-                for cap in func_re.captures_iter(content) {
-                    if let Some(name) = cap.get(1) {
-                        let func_name = name.as_str();
-                        let doc = self.infer_function_purpose_simple(func_name);
-                        methods.push(MethodInfo {
-                            name: func_name.to_string(),
-                            language: language.to_string(),
-                            documentation: doc,
-                            code_snippet: self.extract_method_code(content, func_name, language),
-                        });
-                    }
-                }
-            }
-            "py" => {
-                // Python関数を詳細に抽出
-                let func_re = regex::Regex::new(r"def\s+(\w+)\s*\([^)]*\)\s*:").unwrap();
-                for cap in func_re.captures_iter(content) {
-                    if let Some(name) = cap.get(1) {
-                        let func_name = name.as_str();
-                        let doc = self.infer_function_purpose_simple(func_name);
-                        methods.push(MethodInfo {
-                            name: func_name.to_string(),
-                            language: language.to_string(),
-                            documentation: doc,
-                            code_snippet: self.extract_method_code(content, func_name, language),
-                        });
-                    }
-                }
-            }
-            _ => {}
+        match parse_source(content, language) {
+            Some(parsed) => methods_of(&parsed.symbols, content, language),
+            None => Vec::new(),
         }
+    }
 
-        methods
+    /// 型単位の情報を抽出（メソッドを所属する型ごとにまとめる）
+    /// 
+    /// # 引数
+    /// * `content` - ファイル内容
+    /// * `language` - 言語
+    /// 
+    /// # 戻り値
+    /// * `Vec<TypeInfo>` - 型の情報（フィールド・メソッドを含む、宣言順）
+    pub fn extract_types_detailed(&self, content: &str, language: &str) -> Vec<TypeInfo> {
+        match parse_source(content, language) {
+            Some(parsed) => {
+                let methods = methods_of(&parsed.symbols, content, language);
+                types::group_types(&parsed.symbols, &parsed.fields, &methods, language)
+            }
+            None => Vec::new(),
+        }
     }
 
-    /// メソッドのコードスニペットを抽出
-    fn extract_method_code(&self, content: &str, method_name: &str, language: &str) -> String {
-        let lines: Vec<&str> = content.lines().collect();
-        let mut in_method = false;
-        let mut brace_count = 0;
-        let mut method_lines = Vec::new();
-        let search_pattern = match language {
-            "rs" => format!("fn {}", method_name),
-            "ts" | "js" => format!("function {}", method_name),
-            "py" => format!("def {}", method_name),
-            _ => method_name.to_string(),
-        };
+    /// 型の要約（責務・フィールド・主要なメソッド）を生成
+    /// 
+    /// 日本語では型・メソッドの英語のドキュメントコメントを翻訳する。
+    /// 
+    /// # 引数
+    /// * `ty` - 型の情報
+    /// * `locale` - 見出し・定型文の言語
+    /// 
+    /// # 戻り値
+    /// * `String` - Markdown（見出しを含まない）
+    pub async fn summarize_type(&self, ty: &TypeInfo, locale: Locale) -> String {
+        let mut docs = Vec::new();
+        for doc in std::iter::once(&ty.documentation).chain(ty.key_methods().into_iter().map(|m| &m.documentation)) {
+            docs.push(match locale {
+                Locale::Ja if !doc.trim().is_empty() => self.translate_doc_to_japanese(doc).await,
+                _ => doc.trim().to_string(),
+            });
+        }
+        types::render_type(ty, &docs[0], &docs[1..], locale)
+    }
 
-        for line in &lines {
-            if line.contains(&search_pattern) {
-                in_method = true;
-                brace_count = 0;
+    /// 日本語のMarkdownを英語に翻訳（ローカルLLMがある場合のみ）
+    /// 
+    /// 英語のスライドなどで、日本語で生成した要約を英語にするために使う。
+    /// 見出し・箇条書き・コードブロックなどのMarkdownの構造は維持するよう指示する。
+    /// 
+    /// # 引数
+    /// * `markdown` - 翻訳するMarkdown
+    /// 
+    /// # 戻り値
+    /// * `Option<String>` - 英語のMarkdown（日本語でない場合はそのまま、翻訳できない場合はNone）
+    pub async fn translate_to_english(&self, markdown: &str) -> Option<String> {
+        if !is_japanese(markdown) {
+            return Some(markdown.to_string());
+        }
+        let llm = self.llm.as_ref()?;
+        let prompt = format!(
+            "Translate the following Japanese Markdown into natural English.\n\
+             Keep the Markdown structure (headings, lists, tables, links, code blocks) and identifiers unchanged.\n\
+             Output only the translated Markdown.\n\n{}",
+            markdown
+        );
+        match llm.generate(&prompt).await {
+            Ok(translated) if !translated.trim().is_empty() => Some(translated),
+            Ok(_) => {
+                self.diagnostics.warn("summarizer", "ローカルLLMの翻訳結果が空でした");
+                None
             }
-            
-            if in_method {
-                method_lines.push(*line);
-                
-                // ブレースのカウント（Rust/JS/TS）
-                if language == "rs" || language == "ts" || language == "js" {
-                    brace_count += line.matches('{').count();
-                    brace_count -= line.matches('}').count();
-                    if brace_count == 0 && method_lines.len() > 1 {
-                        break;
-                    }
-                } else if language == "py" {
-                    // Pythonの場合はインデントで判定
-                    if method_lines.len() > 1 {
-                        let first_indent = method_lines[0].len() - method_lines[0].trim_start().len();
-                        let current_indent = line.len() - line.trim_start().len();
-                        if current_indent <= first_indent && !line.trim().is_empty() {
-                            method_lines.pop();
-                            break;
-                        }
-                    }
-                }
+            Err(e) => {
+                self.diagnostics.warn("summarizer", format!("ローカルLLMでの英語への翻訳に失敗しました: {}", e));
+                None
             }
         }
-
-        method_lines.join("\n")
     }
 
-    /// 英語のコメントを日本語に翻訳（簡易版）
+    /// 英語のドキュメントコメントを日本語に翻訳
+    /// 
+    /// コメントがすでに日本語の場合は翻訳せずそのまま返す。
+    /// ローカルLLMがある場合はLLMで翻訳し（モデルと原文ごとにキャッシュ）、
+    /// ない場合・失敗した場合はよく使われる文型を辞書で訳す（translateモジュール）。
     /// 
     /// # 引数
     /// * `doc` - 英語のドキュメント文字列
     /// 
     /// # 戻り値
     /// * `String` - 日本語に翻訳された説明
-    pub fn translate_doc_to_japanese(&self, doc: &str) -> String {
-        // 簡易的な翻訳ルール（実際の実装ではLLM APIを使用可能）
-        // よくある英語のパターンを日本語に変換
-        let mut translated = doc.to_string();
-        
-        // パターンマッチングによる簡易翻訳
-        let replacements = vec![
-            ("returns", "返します"),
-            ("returns the", "を返します"),
-            ("returns a", "を返します"),
-            ("returns an", "を返します"),
-            ("return", "返す"),
-            ("gets", "取得します"),
-            ("get", "取得する"),
-            ("sets", "設定します"),
-            ("set", "設定する"),
-            ("creates", "作成します"),
-            ("create", "作成する"),
-            ("checks", "チェックします"),
-            ("check", "チェックする"),
-            ("validates", "検証します"),
-            ("validate", "検証する"),
-            ("parses", "解析します"),
-            ("parse", "解析する"),
-            ("handles", "処理します"),
-            ("handle", "処理する"),
-            ("processes", "処理します"),
-            ("process", "処理する"),
-            ("if", "もし"),
-            ("when", "時に"),
-            ("when the", "時に"),
-            ("this function", "この関数は"),
-            ("this method", "このメソッドは"),
-            ("this", "これ"),
-            ("the", ""),
-            ("a", ""),
-            ("an", ""),
-            ("is", "は"),
-            ("are", "は"),
-            ("does", "します"),
-            ("will", "します"),
-            ("should", "すべきです"),
-            ("must", "しなければなりません"),
-            ("can", "できます"),
-            ("may", "かもしれません"),
-            ("or", "または"),
-            ("and", "と"),
-            ("with", "と共に"),
-            ("for", "のために"),
-            ("from", "から"),
-            ("to", "へ"),
-            ("by", "によって"),
-            ("in", "で"),
-            ("at", "で"),
-            ("on", "で"),
-            ("of", "の"),
-            ("parameter", "パラメータ"),
-            ("parameters", "パラメータ"),
-            ("argument", "引数"),
-            ("arguments", "引数"),
-            ("value", "値"),
-            ("values", "値"),
-            ("data", "データ"),
-            ("error", "エラー"),
-            ("errors", "エラー"),
-            ("result", "結果"),
-            ("results", "結果"),
-            ("function", "関数"),
-            ("method", "メソッド"),
-            ("class", "クラス"),
-            ("object", "オブジェクト"),
-            ("string", "文字列"),
-            ("number", "数値"),
-            ("boolean", "真偽値"),
-            ("array", "配列"),
-            ("list", "リスト"),
-            ("map", "マップ"),
-            ("hash", "ハッシュ"),
-            ("file", "ファイル"),
-            ("path", "パス"),
-            ("directory", "ディレクトリ"),
-            ("directory exists", "ディレクトリが存在する場合"),
-            ("does not exist", "存在しない場合"),
-            ("exists", "存在する"),
-            ("not set", "設定されていない"),
-            ("is set", "設定されている"),
-            ("will be", "になります"),
-            ("canonicalized", "正規化されます"),
-            ("will err", "エラーになります"),
-            ("err if", "エラーになります"),
-        ];
-        
-        for (en, ja) in replacements.iter() {
-            let pattern = format!(r"\b{}\b", regex::escape(en));
-            if let Ok(re) = regex::Regex::new(&pattern) {
-                translated = re.replace_all(&translated, *ja).to_string();
+    pub async fn translate_doc_to_japanese(&self, doc: &str) -> String {
+        let doc = doc.trim();
+        if is_japanese(doc) {
+            return doc.to_string();
+        }
+        let Some(llm) = self.llm.as_ref() else {
+            return translate::translate_offline(doc);
+        };
+        if let Some(translated) = translate::cached(llm.model(), doc) {
+            return translated;
+        }
+
+        match llm.generate(&translate::prompt(doc)).await {
+            Ok(translated) if !translated.trim().is_empty() => {
+                translate::store(llm.model(), doc, &translated);
+                translated
+            }
+            Ok(_) => {
+                self.diagnostics.warn("summarizer", "ローカルLLMの翻訳結果が空でした");
+                translate::translate_offline(doc)
+            }
+            Err(e) => {
+                self.diagnostics.warn("summarizer", format!("ローカルLLMでの日本語への翻訳に失敗しました: {}", e));
+                translate::translate_offline(doc)
             }
         }
-        
-        // 空でない場合は翻訳結果を返す、空の場合は関数名から推測
-        if translated.trim().is_empty() {
-            "この関数の実装です。".to_string()
-        } else {
-            format!("この関数は{}。", translated.trim())
+    }
+
+    /// コードの動作を戻り値の型・キーワードから推定
+    /// 
+    /// ドキュメントコメントがある場合は、その説明の方が正確なため推定しない。
+    /// 戻り値の型が書かれている場合はその型を返すと説明する。
+    /// 
+    /// # 引数
+    /// * `method` - メソッド情報
+    /// 
+    /// # 戻り値
+    /// * `Option<String>` - 動作の説明（ドキュメントコメントがある場合はNone）
+    pub fn infer_code_behavior(&self, method: &MethodInfo) -> Option<String> {
+        if !method.documentation.trim().is_empty() {
+            return None;
+        }
+
+        if method.returns_value() {
+            return Some(format!("`{}` を返します。", method.return_type));
         }
+        let code_lower = method.code_snippet.to_lowercase();
+        let behavior = if code_lower.contains("mut") || code_lower.contains("let") {
+            "変数を操作します。"
+        } else if code_lower.contains("if") || code_lower.contains("match") {
+            "条件分岐を行います。"
+        } else if code_lower.contains("loop") || code_lower.contains("for") || code_lower.contains("while") {
+            "繰り返し処理を行います。"
+        } else {
+            "何らかの処理を実行します。"
+        };
+        Some(behavior.to_string())
     }
 
     /// 関数の目的を推定（簡易版、公開メソッド）
@@ -613,20 +1124,54 @@ impl Summarizer {
         }
     }
 
+    /// 型ごとの要約の節を生成
+    /// 
+    /// # 引数
+    /// * `types` - 型の情報
+    /// * `locale` - 見出し・定型文の言語
+    /// * `level` - 節の見出しのレベル（`##` など、型の見出しは1つ下）
+    /// 
+    /// # 戻り値
+    /// * `String` - Markdown（型がない場合は空）
+    async fn summarize_types(&self, types: &[TypeInfo], locale: Locale, level: &str) -> String {
+        if types.is_empty() {
+            return String::new();
+        }
+        let mut section = format!("{} {}\n\n", level, locale.summary_text("file.types"));
+        if types.len() > MAX_TYPES {
+            self.diagnostics.warn("summarizer", format!("型{}件のうち先頭の{}件のみ要約しました", types.len(), MAX_TYPES));
+        }
+        for ty in types.iter().take(MAX_TYPES) {
+            section.push_str(&format!("{}# {}\n\n", level, ty.name));
+            section.push_str(&self.summarize_type(ty, locale).await);
+        }
+        section
+    }
+
     /// コンテンツを要約（メソッド単位での詳細な解説を含む）
-    async fn summarize_content(&self, content: &str, language: &str) -> String {
+    async fn summarize_content(&self, content: &str, language: &str, locale: Locale) -> String {
         let mut summary = String::new();
         
+        // 型単位の要約（責務・フィールド・主要なメソッド）
+        let types = self.extract_types_detailed(content, language);
+        summary.push_str(&self.summarize_types(&types, locale, "##").await);
+
         // メソッド単位での詳細な解説を生成
         let methods = self.extract_methods_detailed(content, language);
         
         if !methods.is_empty() {
-            summary.push_str("## 主要な関数・メソッド\n\n");
+            summary.push_str(&format!("## {}\n\n", locale.summary_text("file.methods")));
+            if methods.len() > 10 {
+                self.diagnostics.warn(
+                    "summarizer",
+                    format!("関数・メソッド{}件のうち先頭の10件のみ解説しました", methods.len()),
+                );
+            }
             for method in methods.iter().take(10) {
-                summary.push_str(&format!("### {}\n\n", method.name));
+                summary.push_str(&format!("### {}\n\n", method.qualified_name()));
                 
                 if !method.documentation.is_empty() {
-                    summary.push_str(&format!("**説明**: {}\n\n", method.documentation));
+                    summary.push_str(&format!("**{}**: {}\n\n", locale.summary_text("file.description"), method.documentation));
                 }
                 
                 // コードスニペットを追加（短い場合のみ）
@@ -634,22 +1179,23 @@ impl Summarizer {
                 if code_lines.len() <= 20 {
                     summary.push_str("```");
                     summary.push_str(&method.language);
-                    summary.push_str("\n");
+                    summary.push('\n');
                     summary.push_str(&method.code_snippet);
                     summary.push_str("\n```\n\n");
                 } else {
                     summary.push_str("```");
                     summary.push_str(&method.language);
-                    summary.push_str("\n");
+                    summary.push('\n');
                     // 最初の10行と最後の5行を表示
                     for line in code_lines.iter().take(10) {
                         summary.push_str(line);
-                        summary.push_str("\n");
+                        summary.push('\n');
                     }
-                    summary.push_str("// ... (省略) ...\n");
+                    summary.push_str(locale.summary_text("file.omitted"));
+                    summary.push('\n');
                     for line in code_lines.iter().skip(code_lines.len().saturating_sub(5)) {
                         summary.push_str(line);
-                        summary.push_str("\n");
+                        summary.push('\n');
                     }
                     summary.push_str("```\n\n");
                 }
@@ -665,7 +1211,8 @@ impl Summarizer {
                         .map(|m| m.as_str())
                         .collect();
                     if !funcs.is_empty() {
-                        summary.push_str("主要な関数:\n");
+                        summary.push_str(locale.summary_text("file.functions"));
+                        summary.push('\n');
                         for func in funcs {
                             summary.push_str(&format!("- `{}`\n", func));
                         }
@@ -679,7 +1226,8 @@ impl Summarizer {
                         .map(|m| m.as_str())
                         .collect();
                     if !funcs.is_empty() {
-                        summary.push_str("主要な関数:\n");
+                        summary.push_str(locale.summary_text("file.functions"));
+                        summary.push('\n');
                         for func in funcs {
                             summary.push_str(&format!("- `{}`\n", func));
                         }
@@ -693,20 +1241,21 @@ impl Summarizer {
                         .map(|m| m.as_str())
                         .collect();
                     if !funcs.is_empty() {
-                        summary.push_str("主要な関数:\n");
+                        summary.push_str(locale.summary_text("file.functions"));
+                        summary.push('\n');
                         for func in funcs {
                             summary.push_str(&format!("- `{}`\n", func));
                         }
                     }
                 }
                 _ => {
-                    summary.push_str("コードの要約を生成しました。");
+                    summary.push_str(locale.summary_text("file.generic"));
                 }
             }
         }
 
         if summary.is_empty() {
-            summary.push_str(&format!("{}行のコードを含むファイルです。", content.lines().count()));
+            summary.push_str(&locale.summary_format("file.lines", &[&content.lines().count()]));
         }
 
         summary
@@ -745,26 +1294,139 @@ impl Summarizer {
     /// モジュールグラフのMermaid DSLを生成
     async fn generate_module_graph_mermaid(&self, index: &Index) -> Result<String> {
         let mut mermaid = String::from("graph TD\n");
-        let mut node_count = 0;
 
-        for module in &index.modules {
+        for (node_count, module) in index.modules.iter().enumerate() {
             let node_id = format!("M{}", node_count);
-            let label = module.name.clone();
+            let label = module.id.clone();
             mermaid.push_str(&format!("    {}[\"{}\"]\n", node_id, label));
-            node_count += 1;
         }
 
         Ok(mermaid)
     }
 }
 
+/// 詳細なスタイル（detailed-ja|detailed-en）か
+fn is_detailed(style: &str) -> bool {
+    style.starts_with("detailed")
+}
+
+/// 先頭の `# ` 見出しの直後にセクションを挿入（見出しがない場合は先頭に挿入）
+fn insert_after_title(markdown: &str, section: &str) -> String {
+    if markdown.starts_with("# ") {
+        if let Some(pos) = markdown.find("\n\n") {
+            let (title, rest) = markdown.split_at(pos + 2);
+            return format!("{}{}{}", title, section, rest);
+        }
+    }
+    format!("{}{}", section, markdown)
+}
+
+/// importがマニフェストのパッケージを指しているか（`serde_json::Value` → `serde-json`、`@scope/pkg/sub` → `@scope/pkg`）
+fn import_matches_package(import: &str, package: &str) -> bool {
+    let normalize = |s: &str| s.to_lowercase().replace('-', "_");
+    let (import, package) = (normalize(import), normalize(package));
+    import == package
+        || ["/", "::", "."]
+            .iter()
+            .any(|sep| import.starts_with(&format!("{}{}", package, sep)))
+}
+
+/// テキストが日本語で書かれているかを文字種で判定
+/// 
+/// ひらがな・カタカナを含み、かつ日本語の文字（かな・漢字）が英字に対して一定以上の割合を占める場合に日本語とみなす。
+/// コード片や識別子が混ざったコメントも日本語として扱える。
+/// 
+/// # 引数
+/// * `text` - 判定するテキスト
+/// 
+/// # 戻り値
+/// * `bool` - 日本語の場合はtrue
+pub fn is_japanese(text: &str) -> bool {
+    let mut kana = 0;
+    let mut kanji = 0;
+    let mut latin = 0;
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30ff}' | '\u{ff66}'..='\u{ff9f}' => kana += 1,
+            '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => kanji += 1,
+            c if c.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+    // 日本語は1文字あたりの情報量が多いため、英字の4分の1以上あれば日本語とする
+    kana > 0 && (kana + kanji) * 4 >= latin
+}
+
+/// シンボルから関数・メソッドを抽出（構文木の範囲をそのままコードスニペットとする）
+fn methods_of(symbols: &[analyzer_core::Symbol], content: &str, language: &str) -> Vec<MethodInfo> {
+    let lines: Vec<&str> = content.lines().collect();
+    symbols
+        .iter()
+        .filter(|s| s.kind == "function" || s.kind == "method")
+        .map(|symbol| {
+            let start = symbol.start_line.saturating_sub(1).min(lines.len());
+            let end = symbol.end_line.min(lines.len()).max(start);
+            MethodInfo {
+                name: symbol.name.clone(),
+                parent: symbol.parent.clone(),
+                visibility: symbol.visibility.clone(),
+                language: language.to_string(),
+                documentation: symbol.documentation.clone(),
+                code_snippet: lines[start..end].join("\n"),
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+                parameters: symbol.parameters.clone(),
+                return_type: symbol.return_type.clone(),
+                is_async: symbol.is_async,
+            }
+        })
+        .collect()
+}
+
 /// メソッド情報
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MethodInfo {
     pub name: String,
+    /// 所属する型（メソッドの場合）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 可視性（public|private|protected|internal、判定できない言語は空）
+    #[serde(default)]
+    pub visibility: String,
     pub language: String,
     pub documentation: String,
     pub code_snippet: String,
+    /// 開始行（1始まり）
+    #[serde(default)]
+    pub start_line: usize,
+    /// 終了行（1始まり）
+    #[serde(default)]
+    pub end_line: usize,
+    /// 引数（レシーバーを除く宣言順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<analyzer_core::Parameter>,
+    /// 戻り値の型（書かれていない場合は空）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub return_type: String,
+    /// 非同期関数か
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_async: bool,
+}
+
+impl MethodInfo {
+    /// 所属する型を付けた名前（`Stack::push`、Rust・C/C++以外は `Stack.push`）
+    pub fn qualified_name(&self) -> String {
+        match self.parent.as_deref().map(types::base_name) {
+            Some(parent) if matches!(self.language.as_str(), "rs" | "c" | "cpp") => format!("{}::{}", parent, self.name),
+            Some(parent) => format!("{}.{}", parent, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// 値を返すか（戻り値の型が書かれていて、`()`・`void`・`None`・`Unit` 以外）
+    pub fn returns_value(&self) -> bool {
+        !matches!(self.return_type.as_str(), "" | "()" | "void" | "None" | "Unit" | "Promise<void>")
+    }
 }
 
 /// 要約結果
@@ -773,6 +1435,81 @@ pub struct SummarizeResult {
     pub ok: bool,
     pub content_md: String,
     pub artifacts: Vec<Artifact>,
+    /// 要約の根拠となったソースコードの範囲（content_mdの脚注と対応）
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    /// 要約中に記録した警告
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 引用（要約の根拠となったファイルと行範囲）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// リポジトリルートからの相対パス
+    pub path: PathBuf,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり、開始行を含む）
+    pub end_line: usize,
+}
+
+impl Citation {
+    /// 脚注ラベル（同じ範囲の引用は同じラベルになる）
+    pub fn label(&self) -> String {
+        let path: String = self
+            .path
+            .to_string_lossy()
+            .chars()
+            .map(|c| if c.is_whitespace() || c == ']' || c == '[' { '_' } else { c })
+            .collect();
+        format!("{}-L{}-L{}", path, self.start_line, self.end_line)
+    }
+
+    /// 脚注参照（`[^label]`）
+    pub fn marker(&self) -> String {
+        format!("[^{}]", self.label())
+    }
+
+    /// 表示用の位置（`path#L1-L20`）
+    pub fn location(&self) -> String {
+        format!("{}#L{}-L{}", self.path.display(), self.start_line, self.end_line)
+    }
+
+    /// ソースコード閲覧URL
+    /// 
+    /// # 引数
+    /// * `source_url` - URLのベース（`project.source-url`）
+    /// 
+    /// # 戻り値
+    /// * `Option<String>` - 行番号付きURL（ベースがない場合はNone）
+    pub fn url(&self, source_url: Option<&str>) -> Option<String> {
+        let base = source_url?.trim_end_matches('/');
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        Some(format!("{}/{}#L{}-L{}", base, path, self.start_line, self.end_line))
+    }
+}
+
+/// ドライランで生成予定のファイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedFile {
+    /// 出力ディレクトリからの相対パス
+    pub path: PathBuf,
+    /// 推定サイズ（バイト、外部コマンドの出力など推定できない場合はNone）
+    pub estimated_bytes: Option<usize>,
+    /// 内容の生成方式（llm-local|extractive|heuristic|static|mdbook|marp）
+    pub generator: String,
+}
+
+impl PlannedFile {
+    /// 生成予定のファイルを作成
+    pub fn new(path: impl Into<PathBuf>, estimated_bytes: Option<usize>, generator: &str) -> Self {
+        Self {
+            path: path.into(),
+            estimated_bytes,
+            generator: generator.to_string(),
+        }
+    }
 }
 
 /// アーティファクト
@@ -798,12 +1535,254 @@ mod tests {
             size: 1000,
             dependencies: vec![],
             is_module: true,
+            symbols: vec![],
+            relations: vec![],
+            metrics: Default::default(),
+            is_test: false,
+            tests: 0,
+            history: None,
             content: None,
+            encoding: None,
+            redactions: vec![],
+            todos: vec![],
+            build_conditions: vec![],
         };
 
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let role = rt.block_on(summarizer.infer_role(&file_info));
+        let role = rt.block_on(summarizer.infer_role(&file_info, Locale::Ja));
         assert!(role.contains("設定"));
     }
-}
 
+    #[test]
+    fn test_summarize_module_cites_source_lines() {
+        let mut config = Config::default();
+        config.project.source_url = Some("https://github.com/owner/repo/blob/main/".to_string());
+        let summarizer = Summarizer::new(config);
+        let index = Index {
+            repo_path: Path::new("/repo").to_path_buf(),
+            files: vec![analyzer_core::FileInfo {
+                path: Path::new("/repo/src/api.ts").to_path_buf(),
+                name: "api".to_string(),
+                language: "ts".to_string(),
+                content: Some("import x from 'x';\nexport const a = 1;\nexport const b = 2;\n".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt
+            .block_on(summarizer.summarize(&index, "module", "/repo/src/api.ts", "concise-ja"))
+            .unwrap();
+        assert_eq!(
+            result.citations,
+            vec![Citation { path: Path::new("src/api.ts").to_path_buf(), start_line: 1, end_line: 3 }]
+        );
+        assert!(result.content_md.contains("ルーティングを定義するモジュールです。[^src/api.ts-L1-L3]"));
+        assert!(result
+            .content_md
+            .contains("[^src/api.ts-L1-L3]: [src/api.ts#L1-L3](https://github.com/owner/repo/blob/main/src/api.ts#L1-L3)"));
+    }
+
+    #[test]
+    fn test_describe_coupling_ranks_imported_files() {
+        let summarizer = Summarizer::new(Config::default());
+        let mut index = Index {
+            repo_path: Path::new("/repo").to_path_buf(),
+            ..Default::default()
+        };
+        let path = |name: &str| Path::new("/repo/src").join(name);
+        index.internal_dependencies.insert(path("a.ts"), vec![path("core.ts"), path("util.ts")]);
+        index.internal_dependencies.insert(path("b.ts"), vec![path("core.ts")]);
+
+        let coupling = summarizer.describe_coupling(&index, Locale::Ja);
+        assert!(coupling.starts_with("2ファイルの間に3件のimportがあります。"));
+        assert!(coupling.contains("- `src/core.ts`: 2ファイルからimport\n- `src/util.ts`: 1ファイルからimport"));
+
+        let coupling = summarizer.describe_coupling(&index, Locale::En);
+        assert!(coupling.starts_with("There are 3 imports between 2 files."));
+        assert!(coupling.contains("- `src/core.ts`: imported by 2 files"));
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let summary = rt.block_on(summarizer.summarize(&index, "repo", "", "detailed-en")).unwrap();
+        assert!(summary.content_md.contains("## Key components") && summary.content_md.contains("## Internal coupling"));
+        assert!(!summary.content_md.contains("主要コンポーネント"));
+    }
+
+    #[test]
+    fn test_describe_unused_lists_orphans_and_symbols() {
+        let summarizer = Summarizer::new(Config::default());
+        let index = Index { repo_path: Path::new("/repo").to_path_buf(), ..Default::default() };
+        let unused = analyzer_core::UnusedCode {
+            orphan_modules: vec![Path::new("/repo/src/legacy.ts").to_path_buf()],
+            unreferenced_symbols: vec![analyzer_core::UnreferencedSymbol {
+                path: Path::new("/repo/src/util.ts").to_path_buf(),
+                name: "pad".to_string(),
+                kind: "function".to_string(),
+                parent: None,
+                line: 12,
+            }],
+        };
+
+        let mut citations = Vec::new();
+        let content = summarizer.describe_unused(&index, &unused, Locale::Ja, &mut citations);
+        assert!(content.starts_with("リポジトリ内のどのファイルからもimportされていないモジュールが1件あります。\n\n- `src/legacy.ts`\n"));
+        assert!(content.contains("- `pad`[^src/util.ts-L12-L12]\n"));
+        assert_eq!(citations.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_japanese_doc_passthrough() {
+        let summarizer = Summarizer::new(Config::default());
+        let doc = "設定ファイルを読み込み、`Config` を返す";
+        assert!(is_japanese(doc));
+        assert_eq!(summarizer.translate_doc_to_japanese(doc).await, doc);
+        assert!(!is_japanese("Returns the parsed config (例外なし)"));
+        assert_eq!(summarizer.translate_doc_to_japanese("Returns the value").await, "valueを返します。");
+
+        let mut method = MethodInfo {
+            name: "load".to_string(),
+            parent: None,
+            visibility: String::new(),
+            language: "rs".to_string(),
+            documentation: doc.to_string(),
+            code_snippet: "fn load() -> Config { return Config::default(); }".to_string(),
+            start_line: 1,
+            end_line: 1,
+            return_type: "Config".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(summarizer.infer_code_behavior(&method), None);
+        method.documentation.clear();
+        assert_eq!(summarizer.infer_code_behavior(&method).as_deref(), Some("`Config` を返します。"));
+
+        // 戻り値の型がない場合は本文の `return` からは推定しない
+        method.return_type.clear();
+        method.code_snippet = "fn load() { return; }".to_string();
+        assert_eq!(summarizer.infer_code_behavior(&method).as_deref(), Some("何らかの処理を実行します。"));
+    }
+
+    #[test]
+    fn test_local_mode_falls_back_when_ollama_unreachable() {
+        let mut config = Config::default();
+        config.summarization.mode = "local".to_string();
+        config.summarization.ollama_url = "http://127.0.0.1:1".to_string();
+        let summarizer = Summarizer::new(config);
+        let index = Index {
+            files: vec![analyzer_core::FileInfo {
+                path: Path::new("src/config.rs").to_path_buf(),
+                name: "config".to_string(),
+                language: "rs".to_string(),
+                content: Some("pub struct Config;\n".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt
+            .block_on(summarizer.summarize(&index, "module", "src/config.rs", "concise-ja"))
+            .unwrap();
+        assert!(result.content_md.contains("## 役割"));
+        assert!(!result.content_md.contains("## 解説"));
+    }
+    #[test]
+    fn test_extractive_mode_uses_header_comment_and_readme() {
+        let mut config = Config::default();
+        config.summarization.mode = "extractive".to_string();
+        let summarizer = Summarizer::new(config);
+        assert_eq!(summarizer.mode_label(), "extractive");
+        let file = |name: &str, content: &str| analyzer_core::FileInfo {
+            path: Path::new("/repo/store").join(name),
+            name: name.trim_end_matches(".rs").to_string(),
+            language: "rs".to_string(),
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: Path::new("/repo").to_path_buf(),
+            files: vec![
+                file("blob.rs", "//! 圧縮したBLOBを保存する\n\npub struct Blob;\n"),
+                file("util.rs", "pub fn helper() {}\n"),
+            ],
+            readmes: vec![analyzer_core::PackageReadme {
+                dir: Path::new("/repo/store").to_path_buf(),
+                content: "# store\n\nBlob storage for the index.\n".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let mut citations = Vec::new();
+        let blob = summarizer.extractive_description(&index, &index.files[0], &mut citations);
+        assert_eq!(blob.as_deref(), Some("圧縮したBLOBを保存する。[^store/blob.rs-L1-L1]"));
+        let util = summarizer.extractive_description(&index, &index.files[1], &mut citations);
+        assert_eq!(
+            util.as_deref(),
+            Some("Blob storage for the index.（READMEより）（パッケージの説明）[^store/README.md-L3-L3]")
+        );
+        assert_eq!(citations[1].path, Path::new("store/README.md"));
+    }
+    #[test]
+    fn test_summarize_package_by_workspace_name() {
+        let summarizer = Summarizer::new(Config::default());
+        let file = |path: &str| FileInfo {
+            path: Path::new("/repo").join(path),
+            language: "ts".to_string(),
+            ..Default::default()
+        };
+        let index = Index {
+            repo_path: PathBuf::from("/repo"),
+            files: vec![file("packages/ui/src/button.ts"), file("packages/ui/src/nested/icon.ts"), file("apps/web/main.ts")],
+            packages: vec![analyzer_core::PackageInfo {
+                name: "@app/ui".to_string(),
+                path: PathBuf::from("packages/ui"),
+                manifest: PathBuf::from("packages/ui/package.json"),
+                ecosystem: "npm".to_string(),
+                dependencies: Vec::new(),
+            }],
+            ..Default::default()
+        };
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let mut citations = Vec::new();
+        let by_name = rt.block_on(summarizer.summarize_package(&index, "@app/ui", "concise-ja", &mut citations)).unwrap();
+        assert!(by_name.starts_with("# @app/ui\n\nnpmのパッケージ（`packages/ui/package.json`）で、2ファイルを含みます。"));
+        let by_path = rt.block_on(summarizer.summarize_package(&index, "packages/ui", "concise-ja", &mut citations)).unwrap();
+        assert_eq!(by_name, by_path);
+    }
+
+    #[test]
+    fn test_describe_dependencies_uses_manifests() {
+        let summarizer = Summarizer::new(Config::default());
+        let dep = |name: &str, version: &str, kind: &str, internal: bool| analyzer_core::DeclaredDependency {
+            name: name.to_string(),
+            version: Some(version.to_string()),
+            kind: kind.to_string(),
+            internal,
+        };
+        let mut index = Index {
+            manifests: vec![analyzer_core::Manifest {
+                path: Path::new("crates/api/Cargo.toml").to_path_buf(),
+                ecosystem: "cargo".to_string(),
+                name: Some("api".to_string()),
+                dependencies: vec![
+                    dep("serde-json", "1.0", "normal", false),
+                    dep("core", "0.1", "normal", true),
+                    dep("tempfile", "3", "dev", false),
+                ],
+                workspace_members: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        for import in ["serde_json::Value", "std::fs", "core::Store"] {
+            index.dependencies.insert(import.to_string(), vec![]);
+        }
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let described = rt.block_on(summarizer.describe_dependencies(&index, Locale::Ja));
+        assert!(described.contains("| `serde-json` | 1.0 | 実行時 | `crates/api/Cargo.toml` |"));
+        assert!(described.contains("| `tempfile` | 3 | 開発 | `crates/api/Cargo.toml` |"));
+        assert!(described.contains("リポジトリ内のパッケージへの依存: `core`"));
+        assert!(described.ends_with("マニフェストに宣言されていないimport（標準ライブラリなど）: `std::fs`"));
+    }
+}
@@ -0,0 +1,288 @@
+/*!
+ * ローカルLLMバックエンド（Ollama）
+ *
+ * `summarization.mode = "local"` のときに、localhostのOllamaで日本語・英語の解説を生成する
+ * - コードを外部APIに送信せずに高品質な要約を得られる
+ * - `security.offline = true` でも利用可能（接続先はループバックに限定）
+ *
+ * 主な仕様:
+ * - `POST {ollama-url}/api/generate` を `stream: false` で呼び出す
+ * - モデルは `summarization.model`（未指定時は既定モデル）
+ * - 静的ヒューリスティックの下書きとソースコードの抜粋、または段階的な要約（hierarchyモジュール）をプロンプトに含める
+ * - コンテキスト長は `summarization.context-tokens`（`num_ctx` として渡す）
+ * - スタイルが `-en` の場合は英語で解説するよう指示する
+ *
+ * 制限事項:
+ * - ソースコードは先頭 `MAX_SOURCE_CHARS` 文字までしか渡さない
+ * - 接続に失敗した場合は呼び出し側で静的ヒューリスティックにフォールバックする
+ */
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use anyhow::{Context, Result};
+
+use config::Config;
+
+use crate::locale::Locale;
+
+/// `summarization.model` 未指定時のモデル
+const DEFAULT_MODEL: &str = "qwen2.5:7b";
+/// プロンプトに含めるソースコードの最大文字数
+const MAX_SOURCE_CHARS: usize = 12_000;
+
+/// Ollamaクライアント
+#[derive(Clone)]
+pub struct OllamaClient {
+    base_url: String,
+    model: String,
+    temperature: f64,
+    context_tokens: usize,
+    client: reqwest::Client,
+}
+
+/// `/api/generate` のリクエスト
+#[derive(Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    stream: bool,
+    options: GenerateOptions,
+}
+
+#[derive(Serialize)]
+struct GenerateOptions {
+    temperature: f64,
+    num_ctx: usize,
+}
+
+/// `/api/generate` のレスポンス
+#[derive(Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+impl OllamaClient {
+    /// 設定からクライアントを作成
+    ///
+    /// # 引数
+    /// * `config` - 設定
+    ///
+    /// # 戻り値
+    /// * `Self` - クライアント
+    pub fn new(config: &Config) -> Self {
+        let summarization = &config.summarization;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(summarization.timeout_secs))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            base_url: summarization.ollama_url.trim_end_matches('/').to_string(),
+            model: summarization.model.clone().unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            temperature: summarization.temperature,
+            context_tokens: summarization.context_tokens,
+            client,
+        }
+    }
+
+    /// コンテキスト長（トークン）
+    pub fn context_tokens(&self) -> usize {
+        self.context_tokens
+    }
+
+    /// 使用するモデル
+    pub(crate) fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// テキストを生成
+    ///
+    /// # 引数
+    /// * `prompt` - プロンプト
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - 生成されたテキスト、またはエラー
+    pub async fn generate(&self, prompt: &str) -> Result<String> {
+        let url = format!("{}/api/generate", self.base_url);
+        let request = GenerateRequest {
+            model: &self.model,
+            prompt,
+            stream: false,
+            options: GenerateOptions {
+                temperature: self.temperature,
+                num_ctx: self.context_tokens,
+            },
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&request)
+            .send()
+            .await
+            .with_context(|| format!("Ollamaに接続できませんでした: {}", url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("Ollamaがエラーを返しました（{}）: {}", status, body));
+        }
+
+        let body: GenerateResponse = response
+            .json()
+            .await
+            .context("Ollamaのレスポンスの解析に失敗しました")?;
+        Ok(body.response.trim().to_string())
+    }
+}
+
+/// 要約用のプロンプトを作成
+///
+/// # 引数
+/// * `scope` - スコープ（repo|package|module|file）
+/// * `style` - スタイル（concise-ja|detailed-ja|concise-en|detailed-en）
+/// * `draft` - 静的ヒューリスティックによる下書き
+/// * `source` - ソースコード（module/fileスコープのみ）
+/// * `digest` - ファイル・パッケージごとの要約を統合した要約（ソースコードの代わりに渡す）
+///
+/// # 戻り値
+/// * `String` - プロンプト
+pub(crate) fn build_prompt(scope: &str, style: &str, draft: &str, source: Option<&str>, digest: Option<&str>) -> String {
+    let detailed = style.starts_with("detailed");
+    let locale = Locale::from_style(style);
+    let mut prompt = match locale {
+        Locale::Ja => format!(
+            "あなたはソフトウェアの技術文書を書くエンジニアです。\n\
+             以下の情報をもとに、{}の目的と役割を日本語で{}説明してください。\n\
+             見出しやコードブロックは使わず、本文のみを出力してください。\n\
+             情報から読み取れないことは推測で書かないでください。\n\n\
+             ## 静的解析による下書き\n\n{}\n",
+            match scope {
+                "repo" | "readme" => "リポジトリ全体",
+                "package" => "パッケージ",
+                _ => "このコード",
+            },
+            if detailed { "5〜10文で詳しく" } else { "2〜3文で簡潔に" },
+            draft
+        ),
+        Locale::En => format!(
+            "You are an engineer writing technical documentation for software.\n\
+             Based on the information below, explain the purpose and role of {} in English, {}.\n\
+             Output only the body text without headings or code blocks.\n\
+             Do not guess anything that cannot be read from the information.\n\n\
+             ## Draft from static analysis\n\n{}\n",
+            match scope {
+                "repo" | "readme" => "the whole repository",
+                "package" => "this package",
+                _ => "this code",
+            },
+            if detailed { "in detail in 5 to 10 sentences" } else { "concisely in 2 to 3 sentences" },
+            draft
+        ),
+    };
+
+    let (digest_heading, source_heading) = match locale {
+        Locale::Ja => ("コードの段階的な要約", "ソースコード"),
+        Locale::En => ("Hierarchical summary of the code", "Source code"),
+    };
+    if let Some(digest) = digest {
+        prompt.push_str(&format!("\n## {}\n\n{}\n", digest_heading, digest));
+    }
+    if let Some(source) = source {
+        let excerpt: String = source.chars().take(MAX_SOURCE_CHARS).collect();
+        prompt.push_str(&format!("\n## {}\n\n```\n{}\n```\n", source_heading, excerpt));
+    }
+
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// 1リクエストだけ応答するモックHTTPサーバーを起動し、受け取ったリクエストボディを返す
+    fn spawn_mock_server(response_body: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response_body.len(),
+                response_body
+            )
+            .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_generate_with_mock_server() {
+        let (url, handle) = spawn_mock_server(r#"{"model":"test","response":" APIのルーティングを定義します。\n","done":true}"#);
+        let mut config = Config::default();
+        config.summarization.ollama_url = url;
+        config.summarization.model = Some("test-model".to_string());
+
+        let client = OllamaClient::new(&config);
+        let text = client.generate("要約してください").await.unwrap();
+        assert_eq!(text, "APIのルーティングを定義します。");
+
+        let request: serde_json::Value = serde_json::from_str(&handle.join().unwrap()).unwrap();
+        assert_eq!(request["model"], "test-model");
+        assert_eq!(request["stream"], false);
+        assert_eq!(request["prompt"], "要約してください");
+        assert_eq!(request["options"]["num_ctx"], 8192);
+    }
+
+    #[tokio::test]
+    async fn test_translate_doc_uses_llm_and_cache() {
+        let (url, handle) = spawn_mock_server(r#"{"model":"test","response":"設定ファイルを読み込みます。","done":true}"#);
+        let mut config = Config::default();
+        config.summarization.mode = "local".to_string();
+        config.summarization.ollama_url = url;
+        config.summarization.model = Some("translate-test-model".to_string());
+
+        let summarizer = crate::Summarizer::new(config);
+        let doc = "Loads the settings file.";
+        assert_eq!(summarizer.translate_doc_to_japanese(doc).await, "設定ファイルを読み込みます。");
+        let request: serde_json::Value = serde_json::from_str(&handle.join().unwrap()).unwrap();
+        assert!(request["prompt"].as_str().unwrap().ends_with(doc));
+
+        // モックサーバーは1回しか応答しないため、2回目はキャッシュから返る
+        assert_eq!(summarizer.translate_doc_to_japanese(doc).await, "設定ファイルを読み込みます。");
+        assert!(summarizer.diagnostics().entries().is_empty());
+    }
+
+    #[test]
+    fn test_build_prompt_follows_style_language() {
+        let ja = build_prompt("module", "detailed-ja", "下書き", Some("fn main() {}"), None);
+        assert!(ja.contains("日本語で5〜10文で詳しく") && ja.contains("## ソースコード"));
+
+        let en = build_prompt("repo", "concise-en", "draft", None, Some("digest"));
+        assert!(en.contains("the whole repository in English, concisely in 2 to 3 sentences"));
+        assert!(en.contains("## Hierarchical summary of the code\n\ndigest"));
+        assert!(!en.contains("日本語"));
+    }
+}
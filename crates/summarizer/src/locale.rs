@@ -0,0 +1,262 @@
+/*!
+ * 出力の言語
+ *
+ * 海外のチームでも使えるよう、要約・Wiki・スライドを日本語と英語で出力する
+ * - 言語（`Locale`）はWiki・スライドと共有し、固定の文言のカタログはクレートごとに持つ
+ * - このモジュールのカタログは静的ヒューリスティックによる要約の見出し・定型文
+ *
+ * 主な仕様:
+ * - 対応する言語は `config::LANGUAGES`（ja|en）
+ * - 要約スタイルは `<concise|detailed>-<言語>`（例: `concise-en`）で、言語の付かないスタイルは `project.language` を使う
+ * - 文言はキーで引き、`{}` は引数で順に置き換える（カタログにないキーはキーをそのまま返す）
+ *
+ * 制限事項:
+ * - ファイル・READMEから抜き出したコメント、関数の説明の推定（`infer_function_purpose_simple` など）は翻訳しない
+ */
+
+use std::fmt::Display;
+
+use anyhow::Result;
+
+use config::Config;
+
+/// 文言のカタログ（キー、日本語、英語）
+pub type Catalog = [(&'static str, &'static str, &'static str)];
+
+/// 出力の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+/// 要約の見出し・定型文のカタログ
+const CATALOG: &Catalog = &[
+    ("repo.fallback", "リポジトリ", "Repository"),
+    ("repo.stats", "{}ファイル、{}言語、{}モジュールを含むリポジトリです。", "A repository with {} files, {} languages and {} modules."),
+    ("repo.purpose", "目的・コンポーネント", "Purpose and components"),
+    ("repo.components", "主要コンポーネント", "Key components"),
+    ("repo.dependencies", "外部依存", "External dependencies"),
+    ("repo.coupling", "内部の結合度", "Internal coupling"),
+    ("repo.unused", "未使用コードの可能性", "Possibly unused code"),
+    ("repo.entrypoints", "エントリーポイント", "Entry points"),
+    ("glossary.heuristic", "{} などの名前に現れる語で、{}ファイルで使われています。", "Appears in names such as {} in {} files."),
+    ("readme.badges", "バッジ（CI・バージョン・ライセンスなど）をここに追加してください", "Add badges (CI, version, license etc.) here"),
+    ("readme.about", "概要", "About"),
+    ("readme.architecture", "アーキテクチャ", "Architecture"),
+    ("readme.architecture-intro", "パッケージ・ディレクトリの間のimportの関係です（矢印はimportする側からされる側）。", "Imports between packages and directories (arrows point from the importer to the imported)."),
+    ("readme.getting-started", "はじめに", "Getting started"),
+    ("readme.commands", "ビルドファイルから検出したコマンドです。", "Commands detected from the build files."),
+    ("readme.build", "ビルド", "Build"),
+    ("readme.test", "テスト", "Test"),
+    ("readme.run", "実行", "Run"),
+    ("readme.entrypoints", "エントリーポイント:", "Entry points:"),
+    ("readme.no-commands", "ビルド・実行の手順を記載してください。", "Describe how to build and run the project."),
+    ("readme.layout", "ディレクトリ構成", "Directory layout"),
+    ("readme.files", "{}ファイル", "{} files"),
+    ("readme.license", "ライセンス", "License"),
+    ("readme.license-file", "{}（`{}` を参照）", "{} (see `{}`)"),
+    ("readme.license-unknown", "ライセンスを記載してください。", "Describe the license."),
+    ("purpose.app", "アプリケーションまたはサーバーとして動作する可能性があります。", "It may run as an application or a server."),
+    ("purpose.web", "WebアプリケーションまたはAPIサーバーです。", "It is a web application or an API server."),
+    ("purpose.frontend", "フロントエンドアプリケーションです。", "It is a frontend application."),
+    ("purpose.unknown", "コードベースの目的を特定するには追加の分析が必要です。", "Further analysis is needed to identify the purpose of the codebase."),
+    ("components.module", "{}言語で記述されたモジュール{}", "Module written in {}{}"),
+    ("components.none", "コンポーネント情報がありません。", "No component information is available."),
+    ("dependencies.header", "| パッケージ | バージョン | 種類 | 宣言 |", "| Package | Version | Kind | Declared in |"),
+    ("dependencies.dev", "開発", "dev"),
+    ("dependencies.build", "ビルド", "build"),
+    ("dependencies.normal", "実行時", "runtime"),
+    ("dependencies.internal", "リポジトリ内のパッケージへの依存: {}", "Dependencies on packages in this repository: {}"),
+    ("dependencies.undeclared", "マニフェストに宣言されていないimport（標準ライブラリなど）: {}", "Imports not declared in a manifest (standard library etc.): {}"),
+    ("coupling.intro", "{}ファイルの間に{}件のimportがあります。多くのファイルから参照されているファイル:\n", "There are {1} imports between {0} files. Files referenced by many files:\n"),
+    ("coupling.item", "- `{}`: {}ファイルからimport", "- `{}`: imported by {} files"),
+    ("unused.orphans", "リポジトリ内のどのファイルからもimportされていないモジュールが{}件あります。", "{} modules are not imported by any file in the repository."),
+    ("unused.symbols", "定義以外で参照されていない公開関数・メソッドが{}件あります。", "{} public functions and methods are not referenced outside their definition."),
+    ("unused.caution", "名前の一致だけで判定しているため、動的な呼び出し・外部のリポジトリからの利用がないことを確認してから削除してください。", "Detection is based on name matching only, so make sure there are no dynamic calls or uses from other repositories before removing them."),
+    ("package.fallback", "パッケージ", "Package"),
+    ("package.manifest", "{}のパッケージ（`{}`）で、{}ファイルを含みます。", "A {} package (`{}`) with {} files."),
+    ("package.files", "{}ファイルを含むパッケージです。", "A package with {} files."),
+    ("package.modules", "モジュール", "Modules"),
+    ("package.relations", "パッケージ間の依存", "Dependencies between packages"),
+    ("package.depends-on", "依存先", "Depends on"),
+    ("package.dependents", "依存元", "Used by"),
+    ("module.role", "役割", "Role"),
+    ("module.dependencies", "依存関係", "Dependencies"),
+    ("module.notes", "注意点", "Notes"),
+    ("role.config", "設定管理を行うモジュールです。", "A module that manages configuration."),
+    ("role.api", "APIエンドポイントまたはルーティングを定義するモジュールです。", "A module that defines API endpoints or routing."),
+    ("role.util", "ユーティリティ関数を提供するモジュールです。", "A module that provides utility functions."),
+    ("role.model", "データモデルまたはスキーマを定義するモジュールです。", "A module that defines data models or schemas."),
+    ("role.service", "ビジネスロジックを実装するモジュールです。", "A module that implements business logic."),
+    ("role.db", "データベース・ストレージへのアクセスを担うモジュールです。", "A module that accesses databases or storage."),
+    ("role.ui", "画面・UIコンポーネントを定義するモジュールです。", "A module that defines screens or UI components."),
+    ("role.worker", "バックグラウンドのジョブ・タスクを処理するモジュールです。", "A module that processes background jobs or tasks."),
+    ("role.infra", "デプロイ・インフラ構成を定義するモジュールです。", "A module that defines deployment or infrastructure."),
+    ("role.other", "{}で記述されたモジュールです。", "A module written in {}."),
+    ("notes.large", "ファイルサイズが大きいため、リファクタリングを検討してください。", "The file is large; consider refactoring it."),
+    ("notes.coupled", "依存関係が多く、結合度が高い可能性があります。", "It has many dependencies and may be tightly coupled."),
+    ("notes.none", "特に注意すべき点は見つかりませんでした。", "No particular concerns were found."),
+    ("file.overview", "概要", "Overview"),
+    ("file.methods", "主要な関数・メソッド", "Key functions and methods"),
+    ("file.description", "説明", "Description"),
+    ("file.omitted", "// ... (省略) ...", "// ... (omitted) ..."),
+    ("file.functions", "主要な関数:", "Key functions:"),
+    ("file.generic", "コードの要約を生成しました。", "Generated a summary of the code."),
+    ("file.lines", "{}行のコードを含むファイルです。", "A file with {} lines of code."),
+    ("file.types", "型", "Types"),
+    ("type.responsibility", "責務", "Responsibility"),
+    ("type.fields", "フィールド", "Fields"),
+    ("type.methods", "主要なメソッド", "Key methods"),
+    ("type.more", "ほか{}件", "{} more"),
+    ("type.generic", "{}個のフィールドと{}個のメソッドを持つ{}です。", "A {2} with {0} fields and {1} methods."),
+    ("kind.struct", "構造体", "struct"),
+    ("kind.enum", "列挙型", "enum"),
+    ("kind.trait", "トレイト", "trait"),
+    ("kind.class", "クラス", "class"),
+    ("kind.interface", "インターフェース", "interface"),
+    ("kind.impl", "実装", "implementation"),
+];
+
+impl Locale {
+    /// 言語コードから言語を決める
+    ///
+    /// # 引数
+    /// * `code` - 言語コード（ja|en）
+    ///
+    /// # 戻り値
+    /// * `Result<Self>` - 言語、または対応していない場合はエラー
+    pub fn parse(code: &str) -> Result<Self> {
+        match code {
+            "ja" => Ok(Self::Ja),
+            "en" => Ok(Self::En),
+            _ => Err(anyhow::anyhow!(
+                "言語は {} のいずれかである必要があります: {}",
+                config::LANGUAGES.join(", "),
+                code
+            )),
+        }
+    }
+
+    /// 設定の出力言語（`project.language`、検証済みのため不正な値は日本語とする）
+    pub fn of(config: &Config) -> Self {
+        Self::parse(&config.project.language).unwrap_or_default()
+    }
+
+    /// 要約スタイルの言語（`-en` で終わる場合は英語、それ以外は日本語）
+    pub fn from_style(style: &str) -> Self {
+        match style.rsplit_once('-') {
+            Some((_, "en")) => Self::En,
+            _ => Self::Ja,
+        }
+    }
+
+    /// 言語コード
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::Ja => "ja",
+            Self::En => "en",
+        }
+    }
+
+    /// この言語の要約スタイル
+    ///
+    /// # 引数
+    /// * `base` - 言語を除いたスタイル（concise|detailed）
+    ///
+    /// # 戻り値
+    /// * `String` - スタイル（例: `concise-en`）
+    pub fn style(self, base: &str) -> String {
+        format!("{}-{}", base, self.code())
+    }
+
+    /// カタログから文言を取得
+    ///
+    /// # 引数
+    /// * `catalog` - カタログ
+    /// * `key` - カタログのキー
+    ///
+    /// # 戻り値
+    /// * `&str` - 文言（カタログにない場合はキー）
+    pub fn lookup<'a>(self, catalog: &'a Catalog, key: &'a str) -> &'a str {
+        match catalog.iter().find(|(k, _, _)| *k == key) {
+            Some((_, ja, en)) => match self {
+                Self::Ja => ja,
+                Self::En => en,
+            },
+            None => key,
+        }
+    }
+
+    /// カタログから引数を埋め込んだ文言を取得
+    ///
+    /// # 引数
+    /// * `catalog` - カタログ
+    /// * `key` - カタログのキー
+    /// * `args` - `{}` に順に埋め込む値（`{0}` のように位置を指定した場合はその引数）
+    ///
+    /// # 戻り値
+    /// * `String` - 文言
+    pub fn lookup_format(self, catalog: &Catalog, key: &str, args: &[&dyn Display]) -> String {
+        let template = self.lookup(catalog, key);
+        let mut text = String::new();
+        let mut next = 0;
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|e| start + e) else {
+                break;
+            };
+            text.push_str(&rest[..start]);
+            let position = match &rest[start + 1..end] {
+                "" => {
+                    next += 1;
+                    Some(next - 1)
+                }
+                digits => digits.parse::<usize>().ok(),
+            };
+            match position.and_then(|i| args.get(i)) {
+                Some(arg) => text.push_str(&arg.to_string()),
+                None if position.is_none() => text.push_str(&rest[start..=end]),
+                None => {}
+            }
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        text
+    }
+
+    /// 要約の文言を取得（Wikiの品質スコアの定型文の判定にも使う）
+    pub fn summary_text(self, key: &str) -> &str {
+        self.lookup(CATALOG, key)
+    }
+
+    /// 引数を埋め込んだ要約の文言を取得
+    pub(crate) fn summary_format(self, key: &str, args: &[&dyn Display]) -> String {
+        self.lookup_format(CATALOG, key, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_styles_and_catalog() {
+        assert_eq!(Locale::from_style("detailed-en"), Locale::En);
+        assert_eq!(Locale::from_style("concise-ja"), Locale::Ja);
+        assert_eq!(Locale::En.style("concise"), "concise-en");
+        assert!(Locale::parse("fr").is_err());
+        assert_eq!(config::LANGUAGES, [Locale::Ja.code(), Locale::En.code()]);
+
+        for (key, ja, en) in CATALOG {
+            assert!(!ja.is_empty() && !en.is_empty(), "{}", key);
+        }
+        assert_eq!(
+            Locale::En.summary_format("coupling.intro", &[&3, &5]),
+            "There are 5 imports between 3 files. Files referenced by many files:\n"
+        );
+        assert_eq!(Locale::Ja.summary_format("coupling.item", &[&"a.rs", &2]), "- `a.rs`: 2ファイルからimport");
+        assert_eq!(Locale::En.summary_text("unknown.key"), "unknown.key");
+    }
+}
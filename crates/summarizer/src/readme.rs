@@ -0,0 +1,271 @@
+/*!
+ * README.mdの下書き（`readme` スコープ）
+ *
+ * READMEのないリポジトリ向けに、インデックスの内容からREADME.mdとしてそのまま置ける下書きを組み立てる
+ * - タイトル・バッジの置き場所・概要・アーキテクチャの図・はじめに（ビルド・テスト・実行のコマンドとエントリーポイント）・ディレクトリ構成・ライセンスの順
+ *
+ * 主な仕様:
+ * - タイトルはリポジトリ直下のマニフェストのパッケージ名、なければリポジトリのディレクトリ名
+ * - バッジはコメントで置き場所を示し、ライセンスが分かる場合はshields.ioのライセンスのバッジを置く
+ * - アーキテクチャの図はワークスペースのパッケージ（なければ2階層目までのディレクトリ）の間のimportをMermaidで描く
+ * - ディレクトリ構成は2階層目までのディレクトリとファイル数の木
+ * - detailedスタイルでは主要コンポーネントの一覧も載せる
+ * - 見出し・定型文は要約と同じく言語（`-ja`・`-en`）に合わせる
+ *
+ * 制限事項:
+ * - インストール方法・使い方の例は推定しない（検出したビルドコマンドとエントリーポイントのみ）
+ * - 図のノードは `MAX_GRAPH_NODES`、ディレクトリ構成は1階層あたり `MAX_LAYOUT_ENTRIES` 件まで
+ */
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::Result;
+
+use analyzer_core::Index;
+
+use crate::{is_detailed, Citation, Locale, Summarizer};
+
+/// アーキテクチャの図に描くノード（パッケージ・ディレクトリ）の最大数
+const MAX_GRAPH_NODES: usize = 15;
+/// ディレクトリ構成の1階層に並べる最大数
+const MAX_LAYOUT_ENTRIES: usize = 12;
+
+impl Summarizer {
+    /// README.mdの下書きを生成
+    ///
+    /// # 引数
+    /// * `index` - インデックス
+    /// * `style` - スタイル（見出し・定型文の言語と、主要コンポーネントを載せるか）
+    /// * `citations` - 引用の追加先（エントリーポイントの位置）
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - README.mdのMarkdown、またはエラー
+    pub(crate) async fn summarize_readme(&self, index: &Index, style: &str, citations: &mut Vec<Citation>) -> Result<String> {
+        let locale = Locale::from_style(style);
+        let mut sections = Vec::new();
+
+        // タイトル・バッジ
+        let root_manifest = index.manifests.iter().find(|m| m.path.parent() == Some(Path::new("")) && m.name.is_some());
+        let title = root_manifest
+            .and_then(|m| m.name.as_deref())
+            .or_else(|| index.repo_path.file_name().and_then(|n| n.to_str()))
+            .unwrap_or(locale.summary_text("repo.fallback"));
+        sections.push(format!("# {}\n\n<!-- {} -->\n", title, locale.summary_text("readme.badges")));
+        if let Some(license) = &index.project_docs.license {
+            let badge = license.replace('-', "--").replace('_', "__").replace(' ', "%20");
+            sections.push(format!("![License](https://img.shields.io/badge/license-{}-blue)\n", badge));
+        }
+        sections.push("\n".to_string());
+
+        // 概要
+        sections.push(format!("## {}\n\n", locale.summary_text("readme.about")));
+        sections.push(format!(
+            "{}\n{}\n\n",
+            self.infer_purpose(index, locale).await,
+            locale.summary_format("repo.stats", &[&index.stats.files, &index.stats.languages.len(), &index.stats.modules])
+        ));
+
+        // アーキテクチャ
+        if let Some(graph) = dependency_graph(index) {
+            sections.push(format!("## {}\n\n", locale.summary_text("readme.architecture")));
+            sections.push(format!("{}\n\n```mermaid\n{}```\n\n", locale.summary_text("readme.architecture-intro"), graph));
+        }
+        if is_detailed(style) {
+            sections.push(format!("## {}\n\n", locale.summary_text("repo.components")));
+            sections.push(self.describe_components(index, locale, citations).await);
+            sections.push("\n".to_string());
+        }
+
+        // はじめに
+        sections.push(format!("## {}\n\n", locale.summary_text("readme.getting-started")));
+        let entrypoints = index.all_entrypoints();
+        if index.build_commands.is_empty() && entrypoints.is_empty() {
+            sections.push(format!("{}\n\n", locale.summary_text("readme.no-commands")));
+        }
+        if !index.build_commands.is_empty() {
+            let mut block = String::from("```sh\n");
+            for kind in ["build", "test", "run"] {
+                let commands: Vec<&str> =
+                    index.build_commands.iter().filter(|c| c.kind == kind).map(|c| c.command.as_str()).collect();
+                if commands.is_empty() {
+                    continue;
+                }
+                if block.len() > "```sh\n".len() {
+                    block.push('\n');
+                }
+                block.push_str(&format!("# {}\n", locale.summary_text(&format!("readme.{}", kind))));
+                for command in commands {
+                    block.push_str(&format!("{}\n", command));
+                }
+            }
+            block.push_str("```\n\n");
+            sections.push(format!("{}\n\n{}", locale.summary_text("readme.commands"), block));
+        }
+        if !entrypoints.is_empty() {
+            sections.push(format!("{}\n\n", locale.summary_text("readme.entrypoints")));
+            for ep in &entrypoints {
+                let marker = match (index.files.iter().find(|f| f.path == ep.path), ep.line) {
+                    (Some(_), Some(line)) => {
+                        let citation = self.cite(index, &ep.path, line, line);
+                        let marker = citation.marker();
+                        citations.push(citation);
+                        marker
+                    }
+                    _ => String::new(),
+                };
+                sections.push(format!("- {}{}\n", ep.describe(&index.repo_path), marker));
+            }
+            sections.push("\n".to_string());
+        }
+
+        // ディレクトリ構成
+        if let Some(layout) = directory_layout(index, locale) {
+            sections.push(format!("## {}\n\n```text\n{}```\n\n", locale.summary_text("readme.layout"), layout));
+        }
+
+        // ライセンス
+        sections.push(format!("## {}\n\n", locale.summary_text("readme.license")));
+        let license = match (&index.project_docs.license, index.project_docs.license_files.first()) {
+            (Some(license), Some(file)) => locale.summary_format("readme.license-file", &[license, &file.display()]),
+            (Some(license), None) => license.clone(),
+            (None, _) => locale.summary_text("readme.license-unknown").to_string(),
+        };
+        sections.push(format!("{}\n", license));
+
+        Ok(sections.join(""))
+    }
+}
+
+/// ファイルをまとめる単位（ワークスペースのパッケージ、なければ2階層目までのディレクトリ、リポジトリ直下のファイルはNone）
+fn group_of(index: &Index, path: &Path) -> Option<String> {
+    if let Some(package) = index.package_of(path).filter(|p| !p.path.as_os_str().is_empty()) {
+        return Some(package.name.clone());
+    }
+    let relative = path.strip_prefix(&index.repo_path).unwrap_or(path);
+    let dirs: Vec<String> = relative
+        .parent()
+        .map(|p| p.components().take(2).map(|c| c.as_os_str().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    (!dirs.is_empty()).then(|| dirs.join("/"))
+}
+
+/// パッケージ・ディレクトリの間のimportのMermaidの図（まとめる単位が2つ未満の場合はNone）
+fn dependency_graph(index: &Index) -> Option<String> {
+    let mut sizes: BTreeMap<String, usize> = BTreeMap::new();
+    for file in index.files.iter().filter(|f| !f.is_test) {
+        if let Some(group) = group_of(index, &file.path) {
+            *sizes.entry(group).or_default() += 1;
+        }
+    }
+    let mut groups: Vec<(&String, &usize)> = sizes.iter().collect();
+    groups.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    let nodes: Vec<&String> = groups.into_iter().take(MAX_GRAPH_NODES).map(|(name, _)| name).collect();
+    if nodes.len() < 2 {
+        return None;
+    }
+
+    let id = |path: &Path| group_of(index, path).and_then(|group| nodes.iter().position(|n| **n == group));
+    let mut edges = BTreeSet::new();
+    for (from, targets) in &index.internal_dependencies {
+        let Some(from) = id(from) else {
+            continue;
+        };
+        for target in targets {
+            if let Some(to) = id(target).filter(|to| *to != from) {
+                edges.insert((from, to));
+            }
+        }
+    }
+
+    let mut mermaid = String::from("graph LR\n");
+    for (i, name) in nodes.iter().enumerate() {
+        mermaid.push_str(&format!("    N{}[\"{}\"]\n", i, name.replace('"', "'")));
+    }
+    for (from, to) in edges {
+        mermaid.push_str(&format!("    N{} --> N{}\n", from, to));
+    }
+    Some(mermaid)
+}
+
+/// 2階層目までのディレクトリとファイル数の木（ディレクトリがない場合はNone）
+fn directory_layout(index: &Index, locale: Locale) -> Option<String> {
+    let mut tree: BTreeMap<String, (usize, BTreeMap<String, usize>)> = BTreeMap::new();
+    for file in &index.files {
+        let relative = file.path.strip_prefix(&index.repo_path).unwrap_or(&file.path);
+        let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        let entry = tree.entry(parts[0].clone()).or_default();
+        entry.0 += 1;
+        if parts.len() >= 3 {
+            *entry.1.entry(parts[1].clone()).or_default() += 1;
+        }
+    }
+    if tree.is_empty() {
+        return None;
+    }
+
+    let files = |count: &usize| locale.summary_format("readme.files", &[count]);
+    let mut layout = String::from(".\n");
+    let shown = tree.len().min(MAX_LAYOUT_ENTRIES);
+    for (i, (dir, (count, children))) in tree.iter().take(MAX_LAYOUT_ENTRIES).enumerate() {
+        let last = i + 1 == shown && tree.len() <= MAX_LAYOUT_ENTRIES;
+        let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        layout.push_str(&format!("{}{}/  # {}\n", branch, dir, files(count)));
+        let child_shown = children.len().min(MAX_LAYOUT_ENTRIES);
+        for (j, (child, count)) in children.iter().take(MAX_LAYOUT_ENTRIES).enumerate() {
+            let last = j + 1 == child_shown && children.len() <= MAX_LAYOUT_ENTRIES;
+            layout.push_str(&format!("{}{}{}/  # {}\n", indent, if last { "└── " } else { "├── " }, child, files(count)));
+        }
+        if children.len() > MAX_LAYOUT_ENTRIES {
+            layout.push_str(&format!("{}└── …\n", indent));
+        }
+    }
+    if tree.len() > MAX_LAYOUT_ENTRIES {
+        layout.push_str("└── …\n");
+    }
+    Some(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use analyzer_core::{BuildCommand, FileInfo};
+    use config::Config;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summarize_readme_draft() {
+        let repo = PathBuf::from("/repo/shop");
+        let file = |path: &str| FileInfo { path: repo.join(path), language: "rs".to_string(), ..Default::default() };
+        let mut index = Index {
+            repo_path: repo.clone(),
+            files: vec![file("src/main.rs"), file("src/api/orders.rs"), file("src/db/store.rs"), file("build.rs")],
+            build_commands: vec![
+                BuildCommand { tool: "cargo".to_string(), kind: "build".to_string(), command: "cargo build".to_string(), source: "Cargo.toml".to_string() },
+                BuildCommand { tool: "cargo".to_string(), kind: "test".to_string(), command: "cargo test".to_string(), source: "Cargo.toml".to_string() },
+            ],
+            ..Default::default()
+        };
+        index.internal_dependencies.insert(repo.join("src/api/orders.rs"), vec![repo.join("src/db/store.rs")]);
+        index.project_docs.license = Some("MIT OR Apache-2.0".to_string());
+
+        let summarizer = Summarizer::new(Config::default());
+        let readme = summarizer.summarize(&index, "readme", "", "concise-ja").await.unwrap().content_md;
+        assert!(readme.starts_with("# shop\n\n<!-- バッジ"));
+        assert!(readme.contains("![License](https://img.shields.io/badge/license-MIT%20OR%20Apache--2.0-blue)\n"));
+        assert!(readme.contains("## 概要\n\n"));
+        assert!(readme.contains("    N0[\"src\"]\n    N1[\"src/api\"]\n    N2[\"src/db\"]\n    N1 --> N2\n"));
+        assert!(readme.contains("```sh\n# ビルド\ncargo build\n\n# テスト\ncargo test\n```"));
+        assert!(readme.contains("```text\n.\n└── src/  # 3ファイル\n    ├── api/  # 1ファイル\n    └── db/  # 1ファイル\n```"));
+        assert!(readme.ends_with("## ライセンス\n\nMIT OR Apache-2.0\n"));
+
+        let english = summarizer.summarize(&Index { repo_path: repo, ..Default::default() }, "readme", "", "concise-en").await.unwrap();
+        assert!(english.content_md.contains("## Getting started\n\nDescribe how to build and run the project.\n"));
+        assert!(english.content_md.ends_with("## License\n\nDescribe the license.\n"));
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+ * 出力のテンプレート
+ *
+ * Wikiの概要・モジュールの解説とスライドのタイトル・関数・モジュールのレイアウトをTeraのテンプレートで組み立てる
+ * - 組み込みのテンプレート（`templates/` 配下）は従来の出力と同じ内容を生成する
+ * - `templates.dir` に同じ名前のファイルを置くと組み込みのテンプレートより優先する（文言・ブランディング・構成の変更）
+ *
+ * 主な仕様:
+ * - テンプレートの名前は `wiki/overview.md`・`wiki/module.md`・`slides/title.md`・`slides/method.md`・`slides/module.md`
+ * - 変数は要約・コードブロックなどを組み立て済みのMarkdownと、元の値（ファイル数・パスなど）の両方を渡す
+ * - 固定の文言は `labels` にまとめ、言語（`project.language`・スライドの言語）に合わせた文言を渡す
+ * - Markdownのためエスケープはしない
+ * - 上書きしたテンプレートは読み込み時に構文を確認し、誤りがある場合はファイルと位置を含むエラーにする
+ *
+ * 制限事項:
+ * - 上書きできるのは上記の名前のみで、ほかの名前のファイルはエラー（`include` 用の部品も置けない）
+ * - 要約（`Summarizer::summarize`）の本文と、そのほかのセクションは従来どおりコードで組み立てる
+ */
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tera::Tera;
+
+use config::Config;
+
+/// 組み込みのテンプレート（名前、内容）
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("wiki/overview.md", include_str!("../templates/wiki/overview.md")),
+    ("wiki/module.md", include_str!("../templates/wiki/module.md")),
+    ("slides/title.md", include_str!("../templates/slides/title.md")),
+    ("slides/method.md", include_str!("../templates/slides/method.md")),
+    ("slides/module.md", include_str!("../templates/slides/module.md")),
+];
+
+/// 組み込みのテンプレートのみのテンプレート集（一度だけ解析する）
+static BUILTIN: LazyLock<Templates> = LazyLock::new(|| {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(BUILTIN_TEMPLATES.iter().copied()).expect("組み込みのテンプレートの構文が正しくありません");
+    Templates { tera: Arc::new(tera), overridden: Vec::new() }
+});
+
+/// テンプレート集
+#[derive(Debug, Clone)]
+pub struct Templates {
+    tera: Arc<Tera>,
+    /// `templates.dir` で上書きしたテンプレートの名前
+    overridden: Vec<String>,
+}
+
+impl Templates {
+    /// 組み込みのテンプレートのみのテンプレート集
+    pub fn builtin() -> Self {
+        BUILTIN.clone()
+    }
+
+    /// 設定のディレクトリのテンプレートで上書きしたテンプレート集を読み込む
+    ///
+    /// # 引数
+    /// * `config` - 設定（`templates.dir` が未指定の場合は組み込みのテンプレートのみ）
+    ///
+    /// # 戻り値
+    /// * `Result<Self>` - テンプレート集、または読み込み・構文のエラー
+    pub fn load(config: &Config) -> Result<Self> {
+        let Some(dir) = &config.templates.dir else {
+            return Ok(Self::builtin());
+        };
+        let mut templates = Self::builtin();
+        let mut tera = (*templates.tera).clone();
+        for name in template_files(dir, dir)? {
+            if !BUILTIN_TEMPLATES.iter().any(|(builtin, _)| *builtin == name) {
+                return Err(anyhow::anyhow!(
+                    "不明なテンプレートです: {}（上書きできるテンプレート: {}）",
+                    name,
+                    template_names().join(", ")
+                ));
+            }
+            let path = dir.join(&name);
+            let content = fs::read_to_string(&path).with_context(|| format!("テンプレートを読み込めません: {:?}", path))?;
+            tera.add_raw_template(&name, &content)
+                .map_err(|e| anyhow::anyhow!("テンプレートの構文に誤りがあります: {:?}: {}", path, describe(&e)))?;
+            templates.overridden.push(name);
+        }
+        templates.tera = Arc::new(tera);
+        Ok(templates)
+    }
+
+    /// 上書きしたテンプレートの名前
+    pub fn overridden(&self) -> &[String] {
+        &self.overridden
+    }
+
+    /// テンプレートに変数を埋め込む
+    ///
+    /// # 引数
+    /// * `name` - テンプレートの名前（`wiki/overview.md` など）
+    /// * `context` - 変数（オブジェクトにシリアライズできる値）
+    ///
+    /// # 戻り値
+    /// * `Result<String>` - 生成したMarkdown、または変数の不足などのエラー
+    pub fn render(&self, name: &str, context: &impl Serialize) -> Result<String> {
+        let context = tera::Context::from_serialize(context).map_err(|e| anyhow::anyhow!("テンプレートの変数が不正です: {}: {}", name, describe(&e)))?;
+        self.tera
+            .render(name, &context)
+            .map_err(|e| anyhow::anyhow!("テンプレートを展開できません: {}: {}", name, describe(&e)))
+    }
+}
+
+/// 上書きできるテンプレートの名前
+pub fn template_names() -> Vec<&'static str> {
+    BUILTIN_TEMPLATES.iter().map(|(name, _)| *name).collect()
+}
+
+/// ディレクトリ配下のファイルを `/` 区切りの相対パスで列挙（ソート済み）
+fn template_files(root: &Path, dir: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("テンプレートのディレクトリを読み込めません: {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(template_files(root, &path)?);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Teraのエラーを原因（構文の位置など）まで含めた文字列にする
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_builtin_templates_render() {
+        let templates = Templates::builtin();
+        let slide = templates
+            .render("slides/method.md", &json!({ "name": "run", "description": "", "language": "rust", "code": "fn run() {}" }))
+            .unwrap();
+        assert_eq!(slide, "---\n## run\n\n```rust\nfn run() {}\n```\n\n---\n\n");
+        assert!(templates.overridden().is_empty());
+        assert!(templates.render("slides/method.md", &json!({})).unwrap_err().to_string().contains("slides/method.md"));
+    }
+
+    #[test]
+    fn test_load_overrides_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("slides")).unwrap();
+        fs::write(dir.path().join("slides/method.md"), "# {{ name | upper }}\n").unwrap();
+        let mut config = Config::default();
+        config.templates.dir = Some(dir.path().to_path_buf());
+
+        let templates = Templates::load(&config).unwrap();
+        assert_eq!(templates.overridden(), ["slides/method.md"]);
+        assert_eq!(templates.render("slides/method.md", &json!({ "name": "run" })).unwrap(), "# RUN\n");
+        // 上書きしていないテンプレートは組み込みのまま
+        let title = json!({ "title": "T", "summary": "S", "stats": "N" });
+        assert!(templates.render("slides/title.md", &title).unwrap().starts_with("---\n# T\n\nS\nN\n"));
+
+        fs::write(dir.path().join("slides/method.md"), "{% if %}").unwrap();
+        assert!(Templates::load(&config).unwrap_err().to_string().contains("構文に誤り"));
+        fs::remove_file(dir.path().join("slides/method.md")).unwrap();
+        fs::write(dir.path().join("wiki.md"), "").unwrap();
+        assert!(Templates::load(&config).unwrap_err().to_string().contains("不明なテンプレート"));
+    }
+}
@@ -0,0 +1,416 @@
+/*!
+ * 外部ツールの検出と実行
+ *
+ * mdBook・Marp CLI・Chromiumがない環境で、生成に時間をかけた後に失敗しないよう事前に確認する
+ * - 見つからないツールで作る成果物はスキップし、Markdownのみを出力する
+ * - CLIの `doctor` コマンドでも同じ判定を使う
+ * - ビルダーは外部ツールを `Tools` 経由で実行し、テストでは `FakeTools`（`fake-tools` フィーチャー）に差し替えて
+ *   失敗・タイムアウト・出力の欠けを再現する
+ *
+ * 主な仕様:
+ * - `<ツール> --version` を実行できるかで判定し、出力の1行目をバージョンとして記録
+ * - スキップした成果物は理由（インストール方法、または失敗の内容）とともに結果に含める
+ * - 実行は `TOOL_TIMEOUT` を超えたら終了させ、失敗（`ToolFailure::Timeout`）として扱う
+ * - 終了コードが0でも、期待した出力のファイルがない場合は失敗（`ToolFailure::NoOutput`）として扱う
+ * - 失敗はツールごとに数え、クローンした `Tools` と共有する（MCPサーバーのメトリクスで公開）
+ *
+ * 制限事項:
+ * - ツールのバージョンの互換性は確認しない
+ * - mdbook-revealなどのmdBookプラグインは確認しない（book.tomlで `optional = true`）
+ * - Gitの履歴・公開はlibgit2で読み書きするため、外部コマンドとしては扱わない
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+/// 確認する外部ツールとインストール方法
+pub const EXTERNAL_TOOLS: &[(&str, &str)] = &[
+    ("mdbook", "cargo install mdbook"),
+    ("marp", "npm install -g @marp-team/marp-cli"),
+    ("chromium", "apt install chromium"),
+];
+
+/// 外部ツールの状態
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolStatus {
+    pub name: String,
+    pub available: bool,
+    /// `--version` の出力（1行目）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// インストール方法（不明なツールの場合は空）
+    pub install_hint: String,
+}
+
+/// 外部ツールの有無を確認
+///
+/// # 引数
+/// * `name` - コマンド名
+///
+/// # 戻り値
+/// * `ToolStatus` - ツールの状態
+pub fn check_tool(name: &str) -> ToolStatus {
+    Tools::system().check(name)
+}
+
+/// すべての外部ツールの有無を確認
+///
+/// # 戻り値
+/// * `Vec<ToolStatus>` - `EXTERNAL_TOOLS` の順の状態
+pub fn check_tools() -> Vec<ToolStatus> {
+    EXTERNAL_TOOLS.iter().map(|(name, _)| check_tool(name)).collect()
+}
+
+/// 外部ツールの実行時間の上限（超えた場合は終了させる）
+pub const TOOL_TIMEOUT: Duration = Duration::from_secs(600);
+/// 実行中のツールの終了を確認する間隔
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 外部ツールの呼び出し
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// 実行ディレクトリ（Noneの場合は現在のディレクトリ）
+    pub current_dir: Option<PathBuf>,
+}
+
+impl ToolCommand {
+    /// 引数なしの呼び出しを作成
+    pub fn new(program: impl Into<String>) -> Self {
+        Self { program: program.into(), args: Vec::new(), current_dir: None }
+    }
+
+    /// 引数を追加
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_string_lossy().into_owned());
+        self
+    }
+
+    /// 実行ディレクトリを指定
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+}
+
+/// 外部ツールの実行結果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 外部ツールの実行方法
+pub trait ToolRunner: Send + Sync + fmt::Debug {
+    /// 呼び出しを実行
+    ///
+    /// # 引数
+    /// * `command` - 外部ツールの呼び出し
+    ///
+    /// # 戻り値
+    /// * `io::Result<ToolOutput>` - 終了したツールの出力（見つからない場合は `NotFound`、時間切れの場合は `TimedOut` のエラー）
+    fn run(&self, command: &ToolCommand) -> io::Result<ToolOutput>;
+}
+
+/// 実際のコマンドを実行する（時間切れの場合はプロセスを終了させる）
+#[derive(Debug, Clone)]
+pub struct SystemTools {
+    timeout: Duration,
+}
+
+impl SystemTools {
+    /// 実行時間の上限を指定して作成
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl Default for SystemTools {
+    fn default() -> Self {
+        Self::new(TOOL_TIMEOUT)
+    }
+}
+
+impl ToolRunner for SystemTools {
+    fn run(&self, command: &ToolCommand) -> io::Result<ToolOutput> {
+        let mut cmd = Command::new(&command.program);
+        cmd.args(&command.args).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        if let Some(dir) = &command.current_dir {
+            cmd.current_dir(dir);
+        }
+        let mut child = cmd.spawn()?;
+        // 出力が多いとパイプが詰まって終了しないため、終了を待つ間も別スレッドで読む
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+        let deadline = Instant::now() + self.timeout;
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(io::Error::new(io::ErrorKind::TimedOut, format!("{}秒以内に終了しませんでした", self.timeout.as_secs())));
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+        Ok(ToolOutput {
+            success: status.success(),
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        })
+    }
+}
+
+/// パイプを最後まで読むスレッドを開始
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+/// 外部ツールの実行に失敗した理由
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ToolFailure {
+    #[error("{0}を実行できません: {1}")]
+    Spawn(String, String),
+    #[error("{0}が時間内に終了しませんでした: {1}")]
+    Timeout(String, String),
+    #[error("{0}が失敗しました: {1}")]
+    Exit(String, String),
+    #[error("{0}の出力が見つかりません: {1:?}")]
+    NoOutput(String, PathBuf),
+}
+
+impl ToolFailure {
+    /// 失敗したツールの名前
+    pub fn tool(&self) -> &str {
+        match self {
+            Self::Spawn(tool, _) | Self::Timeout(tool, _) | Self::Exit(tool, _) | Self::NoOutput(tool, _) => tool,
+        }
+    }
+}
+
+/// ビルダーが使う外部ツール（クローンしても同じ実行方法を共有する）
+#[derive(Debug, Clone)]
+pub struct Tools {
+    runner: Arc<dyn ToolRunner>,
+    /// ツール名 -> 失敗数
+    failures: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl Default for Tools {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+impl Tools {
+    /// 実際のコマンドを実行する（`TOOL_TIMEOUT` で打ち切る）
+    pub fn system() -> Self {
+        Self::new(SystemTools::default())
+    }
+
+    /// 実行方法を指定して作成（テストでは `FakeTools`）
+    pub fn new(runner: impl ToolRunner + 'static) -> Self {
+        Self { runner: Arc::new(runner), failures: Arc::default() }
+    }
+
+    /// 外部ツールの有無を確認
+    ///
+    /// # 引数
+    /// * `name` - コマンド名
+    ///
+    /// # 戻り値
+    /// * `ToolStatus` - ツールの状態
+    pub fn check(&self, name: &str) -> ToolStatus {
+        let install_hint = EXTERNAL_TOOLS
+            .iter()
+            .find(|(tool, _)| *tool == name)
+            .map(|(_, hint)| hint.to_string())
+            .unwrap_or_default();
+        let output = self.runner.run(&ToolCommand::new(name).arg("--version")).ok().filter(|o| o.success);
+        let version = output.as_ref().and_then(|o| {
+            o.stdout
+                .lines()
+                .next()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+        });
+
+        ToolStatus {
+            name: name.to_string(),
+            available: output.is_some(),
+            version,
+            install_hint,
+        }
+    }
+
+    /// 外部ツールを実行し、期待した出力ができたか確認
+    ///
+    /// # 引数
+    /// * `command` - 外部ツールの呼び出し
+    /// * `output` - 成功した場合にできているはずのファイル
+    ///
+    /// # 戻り値
+    /// * `Result<ToolOutput, ToolFailure>` - ツールの出力、または失敗した理由（終了コード・標準エラーの内容・時間切れ・出力の欠け）
+    pub fn run(&self, command: &ToolCommand, output: &Path) -> Result<ToolOutput, ToolFailure> {
+        let result = self.execute(command, output);
+        if let Err(failure) = &result {
+            *self.failures.lock().unwrap_or_else(|e| e.into_inner()).entry(failure.tool().to_string()).or_default() += 1;
+        }
+        result
+    }
+
+    /// ツールごとの失敗数（`run` が失敗した回数、`check` は含めない）
+    pub fn failures(&self) -> BTreeMap<String, u64> {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 外部ツールを実行し、失敗した理由に変換
+    fn execute(&self, command: &ToolCommand, output: &Path) -> Result<ToolOutput, ToolFailure> {
+        let name = command.program.clone();
+        let result = match self.runner.run(command) {
+            Ok(result) => result,
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => return Err(ToolFailure::Timeout(name, e.to_string())),
+            Err(e) => return Err(ToolFailure::Spawn(name, e.to_string())),
+        };
+        if !result.success {
+            let stderr = result.stderr.trim();
+            return Err(ToolFailure::Exit(name, if stderr.is_empty() { "終了コードが0以外".to_string() } else { stderr.to_string() }));
+        }
+        if !output.exists() {
+            return Err(ToolFailure::NoOutput(name, output.to_path_buf()));
+        }
+        Ok(result)
+    }
+}
+
+/// 外部ツールがない・失敗したためにスキップした成果物
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedArtifact {
+    /// 生成されなかった成果物のパス
+    pub path: PathBuf,
+    /// 必要な外部ツール
+    pub tool: String,
+    pub reason: String,
+}
+
+impl SkippedArtifact {
+    /// 見つからないツールで作る成果物として記録
+    ///
+    /// # 引数
+    /// * `path` - 生成されなかった成果物のパス
+    /// * `tool` - 見つからなかったツールの状態
+    ///
+    /// # 戻り値
+    /// * `Self` - スキップした成果物
+    pub fn missing_tool(path: impl Into<PathBuf>, tool: &ToolStatus) -> Self {
+        let reason = if tool.install_hint.is_empty() {
+            format!("{}が見つかりません", tool.name)
+        } else {
+            format!("{}が見つかりません（インストール: {}）", tool.name, tool.install_hint)
+        };
+        Self {
+            path: path.into(),
+            tool: tool.name.clone(),
+            reason,
+        }
+    }
+
+    /// 外部ツールが失敗したために生成されなかった成果物として記録
+    ///
+    /// # 引数
+    /// * `path` - 生成されなかった成果物のパス
+    /// * `failure` - 失敗した理由
+    ///
+    /// # 戻り値
+    /// * `Self` - スキップした成果物
+    pub fn failed_tool(path: impl Into<PathBuf>, failure: &ToolFailure) -> Self {
+        Self {
+            path: path.into(),
+            tool: failure.tool().to_string(),
+            reason: failure.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_tool_is_reported_with_hint() {
+        let missing = check_tool("deeprepo-no-such-tool");
+        assert!(!missing.available);
+        assert_eq!(missing.version, None);
+
+        let mdbook = ToolStatus {
+            name: "mdbook".to_string(),
+            available: false,
+            version: None,
+            install_hint: "cargo install mdbook".to_string(),
+        };
+        let skipped = SkippedArtifact::missing_tool("out/wiki/book", &mdbook);
+        assert_eq!(skipped.tool, "mdbook");
+        assert_eq!(skipped.reason, "mdbookが見つかりません（インストール: cargo install mdbook）");
+        assert_eq!(check_tools().len(), EXTERNAL_TOOLS.len());
+    }
+
+    #[test]
+    fn test_run_reports_failures() {
+        let dir = std::env::temp_dir().join(format!("deeprepo-tools-{}", std::process::id()));
+        let fake = crate::FakeTools::new()
+            .with("mdbook", crate::FakeRun::failure("error: SUMMARY.md not found\n"))
+            .with("marp", crate::FakeRun::timeout())
+            .with("chromium", crate::FakeRun::success().writing("poster.pdf"));
+        let tools = Tools::new(fake.clone());
+        assert_eq!(tools.check("marp").version.as_deref(), Some("marp 0.0.0-fake"));
+        assert!(!tools.check("mdbook-reveal").available);
+
+        let build = ToolCommand::new("mdbook").arg("build").current_dir(&dir);
+        let failure = tools.run(&build, &dir.join("book/index.html")).unwrap_err();
+        assert_eq!(failure, ToolFailure::Exit("mdbook".to_string(), "error: SUMMARY.md not found".to_string()));
+        assert!(matches!(tools.run(&ToolCommand::new("marp"), &dir), Err(ToolFailure::Timeout(..))));
+        assert!(matches!(tools.run(&ToolCommand::new("mdbook-reveal"), &dir), Err(ToolFailure::Spawn(..))));
+
+        let print = ToolCommand::new("chromium").current_dir(&dir);
+        assert!(tools.run(&print, &dir.join("poster.pdf")).is_ok());
+        let missing = tools.run(&print, &dir.join("poster.png")).unwrap_err();
+        let skipped = SkippedArtifact::failed_tool(dir.join("poster.png"), &missing);
+        assert_eq!((skipped.tool.as_str(), skipped.reason.starts_with("chromiumの出力が見つかりません")), ("chromium", true));
+        assert_eq!(fake.calls().len(), 4);
+
+        // 失敗はクローンと共有して数える（`check` は含めない）
+        let counts = tools.clone().failures();
+        let counts: Vec<(&str, u64)> = counts.iter().map(|(tool, count)| (tool.as_str(), *count)).collect();
+        assert_eq!(counts, vec![("chromium", 1), ("marp", 1), ("mdbook", 1), ("mdbook-reveal", 1)]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_system_tools_time_out() {
+        let tools = Tools::new(SystemTools::new(Duration::from_millis(100)));
+        let started = Instant::now();
+        let failure = tools.run(&ToolCommand::new("sleep").arg("5"), Path::new("/")).unwrap_err();
+        assert!(matches!(failure, ToolFailure::Timeout(..)), "{}", failure);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,314 @@
+/*!
+ * ドキュメントコメントの日本語訳
+ *
+ * Wiki・スライドのモジュールの解説で、英語のドキュメントコメントを読める日本語にする
+ * - ローカルLLM（`summarization.mode = "local"`）がある場合はLLMで翻訳する
+ * - LLMがない・失敗した場合は、よく使われる文型（`Returns the ...` など）を辞書で訳し、それ以外の文は原文のまま残す
+ *
+ * 主な仕様:
+ * - LLMの翻訳はモデルと原文ごとにプロセス内でキャッシュし、同じコメント（オーバーロード・トレイトの実装など）は再翻訳しない
+ * - 辞書による翻訳は行・文ごとに行い、コードブロック・識別子（バッククォート）は変えない
+ * - rustdocの見出し（`# Arguments` など）、JSDocの `@param`・`@returns`、Pythonの `:param:` を日本語の表記にする
+ *
+ * 制限事項:
+ * - 辞書による翻訳は動詞と前置詞の語順を入れ替えるだけで、目的語などの英単語はそのまま残る
+ * - キャッシュはプロセスの終了とともに消える（ディスクには保存しない）
+ */
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+use regex::Regex;
+
+/// LLMの翻訳のキャッシュ（モデルと原文 → 訳文）
+static CACHE: LazyLock<Mutex<HashMap<(String, String), String>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 動詞（三人称単数形、原形）と訳文の述語（`{}` は目的語）
+const VERBS: &[(&[&str], &str)] = &[
+    (&["returns", "return"], "{}を返します"),
+    (&["gets", "get", "retrieves", "retrieve", "fetches", "fetch"], "{}を取得します"),
+    (&["sets", "set"], "{}を設定します"),
+    (&["creates", "create", "constructs", "construct", "makes", "make"], "{}を作成します"),
+    (&["builds", "build", "generates", "generate"], "{}を生成します"),
+    (&["parses", "parse"], "{}を解析します"),
+    (&["validates", "validate", "verifies", "verify"], "{}を検証します"),
+    (&["checks", "check"], "{}を確認します"),
+    (&["loads", "load", "reads", "read"], "{}を読み込みます"),
+    (&["writes", "write"], "{}を書き込みます"),
+    (&["saves", "save", "stores", "store"], "{}を保存します"),
+    (&["converts", "convert"], "{}を変換します"),
+    (&["adds", "add", "inserts", "insert", "appends", "append"], "{}を追加します"),
+    (&["removes", "remove", "deletes", "delete"], "{}を削除します"),
+    (&["updates", "update"], "{}を更新します"),
+    (&["handles", "handle", "processes", "process"], "{}を処理します"),
+    (&["initializes", "initialize", "initialises", "initialise"], "{}を初期化します"),
+    (&["runs", "run", "executes", "execute"], "{}を実行します"),
+    (&["starts", "start"], "{}を開始します"),
+    (&["stops", "stop"], "{}を停止します"),
+    (&["sends", "send"], "{}を送信します"),
+    (&["computes", "compute", "calculates", "calculate"], "{}を計算します"),
+    (&["finds", "find", "searches", "search"], "{}を検索します"),
+    (&["registers", "register"], "{}を登録します"),
+    (&["renders", "render"], "{}を描画します"),
+    (&["formats", "format"], "{}を整形します"),
+    (&["resolves", "resolve"], "{}を解決します"),
+    (&["opens", "open"], "{}を開きます"),
+    (&["closes", "close"], "{}を閉じます"),
+    (&["clears", "clear", "resets", "reset"], "{}をリセットします"),
+    (&["merges", "merge"], "{}をマージします"),
+    (&["sorts", "sort"], "{}を並べ替えます"),
+    (&["filters", "filter"], "{}を絞り込みます"),
+];
+
+/// 目的語に続く前置詞と、目的語の前に置く訳（`{}` は前置詞の目的語）
+const PREPOSITIONS: &[(&str, &str)] = &[
+    (" from ", "{}から"),
+    (" into ", "{}に"),
+    (" to ", "{}に"),
+    (" for ", "{}の"),
+    (" of ", "{}の"),
+    (" with ", "{}で"),
+    (" in ", "{}内の"),
+];
+
+/// 見出し・タグの訳（rustdoc・JSDoc・Pythonのdocstring）
+const HEADINGS: &[(&str, &str)] = &[
+    ("arguments", "引数"),
+    ("parameters", "引数"),
+    ("params", "引数"),
+    ("returns", "戻り値"),
+    ("return", "戻り値"),
+    ("errors", "エラー"),
+    ("panics", "パニック"),
+    ("examples", "例"),
+    ("example", "例"),
+    ("safety", "安全性"),
+];
+
+/// `Returns <値> if <条件>`
+static RETURNS_IF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^returns?\s+(\S+)\s+(?:if|when)\s+(.+)$").unwrap());
+/// `Checks whether <条件>` / `Whether <条件>`
+static WHETHER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:(?:checks?|determines?|tests?)\s+)?(?:whether|if)\s+(.+)$").unwrap());
+/// `@param name 説明` / `:param name: 説明`
+static PARAM_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:@param\s+(?:\{[^}]*\}\s+)?|:param\s+)(\[?[\w.$]+\]?):?\s*(?:-\s*)?(.*)$").unwrap());
+/// `@returns 説明` / `:returns: 説明`
+static RETURNS_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:@returns?\s+(?:\{[^}]*\}\s+)?|:returns?:\s*)(.*)$").unwrap());
+
+/// キャッシュ済みのLLMの翻訳
+///
+/// # 引数
+/// * `model` - LLMのモデル
+/// * `doc` - 原文
+///
+/// # 戻り値
+/// * `Option<String>` - 訳文（翻訳していない場合はNone）
+pub(crate) fn cached(model: &str, doc: &str) -> Option<String> {
+    CACHE.lock().ok()?.get(&(model.to_string(), doc.to_string())).cloned()
+}
+
+/// LLMの翻訳をキャッシュに保存
+pub(crate) fn store(model: &str, doc: &str, translated: &str) {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert((model.to_string(), doc.to_string()), translated.to_string());
+    }
+}
+
+/// LLMに渡す翻訳のプロンプト
+pub(crate) fn prompt(doc: &str) -> String {
+    format!(
+        "次のソースコードのドキュメントコメントを、自然な日本語に翻訳してください。\n\
+         識別子・バッククォートで囲まれた部分・コードブロックは変更せず、Markdownの構造を維持してください。\n\
+         訳文のみを出力してください。\n\n{}",
+        doc
+    )
+}
+
+/// 辞書でドキュメントコメントを翻訳（LLMがない場合）
+///
+/// # 引数
+/// * `doc` - 英語のドキュメントコメント
+///
+/// # 戻り値
+/// * `String` - 訳せた文を日本語にしたコメント（訳せない文は原文のまま）
+pub(crate) fn translate_offline(doc: &str) -> String {
+    let mut in_code = false;
+    let lines: Vec<String> = doc
+        .trim()
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                return line.to_string();
+            }
+            if in_code {
+                return line.to_string();
+            }
+            translate_line(line)
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// 1行を翻訳（見出し・タグ・箇条書きの記号は残して説明のみ訳す）
+fn translate_line(line: &str) -> String {
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let text = line.trim();
+
+    if let Some(heading) = text.strip_prefix("# ") {
+        return match lookup_heading(heading) {
+            Some(ja) => format!("{}# {}", indent, ja),
+            None => line.to_string(),
+        };
+    }
+    if let Some(captures) = PARAM_TAG.captures(text) {
+        return format!("{}引数 `{}`: {}", indent, &captures[1], translate_text(&captures[2]));
+    }
+    if let Some(captures) = RETURNS_TAG.captures(text) {
+        return format!("{}戻り値: {}", indent, translate_text(&captures[1]));
+    }
+    // 箇条書き（`* `x` - 説明` の形式は説明のみ訳す）
+    for bullet in ["* ", "- "] {
+        if let Some(item) = text.strip_prefix(bullet) {
+            return match item.split_once(" - ") {
+                Some((name, description)) => format!("{}{}{} - {}", indent, bullet, name, translate_text(description)),
+                None => format!("{}{}{}", indent, bullet, translate_text(item)),
+            };
+        }
+    }
+    format!("{}{}", indent, translate_text(text))
+}
+
+/// 見出し・タグの訳
+fn lookup_heading(heading: &str) -> Option<&'static str> {
+    let heading = heading.trim().trim_end_matches(':').to_lowercase();
+    HEADINGS.iter().find(|(en, _)| *en == heading).map(|(_, ja)| *ja)
+}
+
+/// 文ごとに翻訳して連結
+fn translate_text(text: &str) -> String {
+    let mut translated = String::new();
+    for sentence in split_sentences(text) {
+        match translate_sentence(sentence) {
+            Some(ja) => translated.push_str(&ja),
+            None => {
+                if !translated.is_empty() && !translated.ends_with('。') {
+                    translated.push(' ');
+                }
+                translated.push_str(sentence);
+            }
+        }
+    }
+    translated
+}
+
+/// 文に分ける（`. ` の位置で区切り、識別子中の `.` では区切らない）
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut rest = text.trim();
+    while let Some(pos) = rest.find(". ") {
+        sentences.push(rest[..=pos].trim());
+        rest = rest[pos + 2..].trim_start();
+    }
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// 1文を文型の辞書で翻訳
+///
+/// # 引数
+/// * `sentence` - 英語の文
+///
+/// # 戻り値
+/// * `Option<String>` - 訳文（辞書の文型に当てはまらない場合はNone）
+fn translate_sentence(sentence: &str) -> Option<String> {
+    let sentence = sentence.trim().trim_end_matches('.');
+
+    if let Some(captures) = RETURNS_IF.captures(sentence) {
+        return Some(format!("{}の場合は{}を返します。", strip_article(&captures[2]), &captures[1]));
+    }
+    if let Some(captures) = WHETHER.captures(sentence) {
+        return Some(format!("{}かどうかを判定します。", strip_article(&captures[1])));
+    }
+
+    let (verb, object) = sentence.split_once(' ')?;
+    let verb = verb.to_lowercase();
+    let (_, predicate) = VERBS.iter().find(|(forms, _)| forms.contains(&verb.as_str()))?;
+    let object = object.trim();
+    if object.is_empty() {
+        return None;
+    }
+
+    // 前置詞句は目的語の前に置く（`Loads the config from a file` → `fileからconfigを読み込みます`）
+    let (object, modifier) = match PREPOSITIONS
+        .iter()
+        .filter_map(|(preposition, template)| object.find(preposition).map(|pos| (pos, *preposition, *template)))
+        .min_by_key(|(pos, _, _)| *pos)
+    {
+        Some((pos, preposition, template)) => (
+            &object[..pos],
+            template.replace("{}", &strip_article(&object[pos + preposition.len()..])),
+        ),
+        None => (object, String::new()),
+    };
+    Some(format!("{}{}。", modifier, predicate.replace("{}", &strip_article(object))))
+}
+
+/// 冠詞を除き、よく使う形容詞を訳す
+fn strip_article(phrase: &str) -> String {
+    let mut phrase = phrase.trim();
+    for article in ["the ", "a ", "an ", "The ", "A ", "An "] {
+        if let Some(rest) = phrase.strip_prefix(article) {
+            phrase = rest;
+            break;
+        }
+    }
+    for (en, ja) in [("new ", "新しい"), ("all ", "すべての"), ("given ", "指定した"), ("current ", "現在の")] {
+        if let Some(rest) = phrase.strip_prefix(en) {
+            return format!("{}{}", ja, rest);
+        }
+    }
+    phrase.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_offline_sentences() {
+        assert_eq!(translate_offline("Returns the value."), "valueを返します。");
+        assert_eq!(translate_offline("Loads the config from a file."), "fileからconfigを読み込みます。");
+        assert_eq!(translate_offline("Creates a new parser"), "新しいparserを作成します。");
+        assert_eq!(translate_offline("Returns `true` if the path exists."), "path existsの場合は`true`を返します。");
+        assert_eq!(translate_offline("Checks whether the cache is stale."), "cache is staleかどうかを判定します。");
+        assert_eq!(
+            translate_offline("Parses the header. Thread-safe wrapper."),
+            "headerを解析します。Thread-safe wrapper."
+        );
+        assert_eq!(translate_offline("Thread-safe wrapper around the pool."), "Thread-safe wrapper around the pool.");
+    }
+
+    #[test]
+    fn test_translate_offline_structure() {
+        let doc = "Reads a file.\n\n# Arguments\n* `path` - The path to read\n\n# Examples\n```\nlet s = read(\"a\");\n```";
+        assert_eq!(
+            translate_offline(doc),
+            "fileを読み込みます。\n\n# 引数\n* `path` - The path to read\n\n# 例\n```\nlet s = read(\"a\");\n```"
+        );
+        assert_eq!(translate_offline("@param {string} name Sets the name"), "引数 `name`: nameを設定します。");
+        assert_eq!(translate_offline(":returns: the parsed tree"), "戻り値: the parsed tree");
+    }
+
+    #[test]
+    fn test_cache_by_model() {
+        store("model-a", "Returns the id.", "IDを返します。");
+        assert_eq!(cached("model-a", "Returns the id.").as_deref(), Some("IDを返します。"));
+        assert_eq!(cached("model-b", "Returns the id."), None);
+    }
+}
@@ -0,0 +1,241 @@
+/*!
+ * 型単位の要約
+ *
+ * 構造体・クラスなどの型ごとに、責務・フィールド・主要なメソッドをまとめる
+ * - メソッドは所属する型（Rustはimplブロックの型）の下にまとめる
+ * - ファイル・モジュールの要約とWikiのモジュールページで共通に使う（`Summarizer::extract_types_detailed`）
+ *
+ * 主な仕様:
+ * - 型の名前はジェネリクス・パスを除いて照合する（`impl<T> Stack<T>` のメソッドは `Stack` に属する）
+ * - 同じファイルで宣言されていない型のメソッド（外部の型へのimplなど）は種類 `impl` の型としてまとめる
+ * - 責務は型のドキュメントコメント、ない場合は種類・フィールド数・メソッド数から記述
+ * - 主要なメソッドは公開メソッドを優先して最大 `MAX_KEY_METHODS` 件
+ *
+ * 制限事項:
+ * - フィールドを抽出しない言語（C/C++・Ruby・PHP・Kotlin）はフィールドの項目を省略する
+ * - 複数のファイルに分かれた同じ型（Rustの別ファイルのimplなど）はファイルごとに別の型として扱う
+ */
+
+use serde::{Deserialize, Serialize};
+
+use analyzer_core::{Symbol, TypeField};
+
+use crate::{Locale, MethodInfo};
+
+/// 型の要約に並べる主要なメソッドの最大数
+const MAX_KEY_METHODS: usize = 5;
+/// 型の要約に並べるフィールドの最大数
+const MAX_FIELDS: usize = 20;
+
+/// 型の情報（メソッドを所属する型ごとにまとめたもの）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeInfo {
+    pub name: String,
+    /// 種類（struct|enum|trait|class|interface|impl）
+    pub kind: String,
+    pub language: String,
+    pub documentation: String,
+    /// 可視性（判定できない言語・implは空）
+    #[serde(default)]
+    pub visibility: String,
+    /// フィールド名（宣言順）
+    #[serde(default)]
+    pub fields: Vec<String>,
+    /// メソッド（出現順）
+    #[serde(default)]
+    pub methods: Vec<MethodInfo>,
+    /// 開始行（1始まり）
+    pub start_line: usize,
+    /// 終了行（1始まり）
+    pub end_line: usize,
+}
+
+impl TypeInfo {
+    /// 主要なメソッド（公開メソッドを優先し、それぞれ出現順）
+    pub fn key_methods(&self) -> Vec<&MethodInfo> {
+        let (public, other): (Vec<&MethodInfo>, Vec<&MethodInfo>) =
+            self.methods.iter().partition(|m| m.visibility == "public");
+        public.into_iter().chain(other).take(MAX_KEY_METHODS).collect()
+    }
+}
+
+/// シンボル・フィールド・メソッドを型ごとにまとめる
+///
+/// # 引数
+/// * `symbols` - ファイルのシンボル（出現順）
+/// * `fields` - 型のフィールド
+/// * `methods` - 関数・メソッド（所属する型のあるものを型にまとめる）
+/// * `language` - 言語
+///
+/// # 戻り値
+/// * `Vec<TypeInfo>` - 型の情報（宣言順、宣言のない型は最初のメソッドの位置）
+pub(crate) fn group_types(symbols: &[Symbol], fields: &[TypeField], methods: &[MethodInfo], language: &str) -> Vec<TypeInfo> {
+    let mut types: Vec<TypeInfo> = symbols
+        .iter()
+        .filter(|s| s.is_type())
+        .map(|s| TypeInfo {
+            name: s.name.clone(),
+            kind: s.kind.clone(),
+            language: language.to_string(),
+            documentation: s.documentation.clone(),
+            visibility: s.visibility.clone(),
+            fields: Vec::new(),
+            methods: Vec::new(),
+            start_line: s.start_line,
+            end_line: s.end_line,
+        })
+        .collect();
+
+    for method in methods {
+        let Some(parent) = method.parent.as_deref().map(base_name) else {
+            continue;
+        };
+        let position = match types.iter().position(|t| t.name == parent) {
+            Some(position) => position,
+            None => {
+                types.push(TypeInfo {
+                    name: parent.to_string(),
+                    kind: "impl".to_string(),
+                    language: language.to_string(),
+                    documentation: String::new(),
+                    visibility: String::new(),
+                    fields: Vec::new(),
+                    methods: Vec::new(),
+                    start_line: method.start_line,
+                    end_line: method.end_line,
+                });
+                types.len() - 1
+            }
+        };
+        let ty = &mut types[position];
+        if ty.kind == "impl" {
+            ty.end_line = ty.end_line.max(method.end_line);
+        }
+        ty.methods.push(method.clone());
+    }
+
+    for field in fields {
+        if let Some(ty) = types.iter_mut().find(|t| t.name == field.parent) {
+            ty.fields.push(field.name.clone());
+        }
+    }
+    types.sort_by_key(|t| t.start_line);
+    types
+}
+
+/// 型の要約をMarkdownで生成（見出しは呼び出し側で付ける）
+///
+/// # 引数
+/// * `ty` - 型の情報
+/// * `responsibility` - 責務（ドキュメントコメント、翻訳済みのもの。空の場合は種類・数から記述）
+/// * `method_docs` - 主要なメソッドの説明（`key_methods` の順、説明のないものは空）
+/// * `locale` - 見出し・定型文の言語
+///
+/// # 戻り値
+/// * `String` - 責務・フィールド・主要なメソッドのMarkdown
+pub(crate) fn render_type(ty: &TypeInfo, responsibility: &str, method_docs: &[String], locale: Locale) -> String {
+    let mut text = String::new();
+    let responsibility = match responsibility.trim() {
+        "" => locale.summary_format(
+            "type.generic",
+            &[&ty.fields.len(), &ty.methods.len(), &locale.summary_text(&format!("kind.{}", ty.kind))],
+        ),
+        doc => doc.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" "),
+    };
+    text.push_str(&format!("**{}**: {}\n\n", locale.summary_text("type.responsibility"), responsibility));
+
+    if !ty.fields.is_empty() {
+        let mut fields: Vec<String> = ty.fields.iter().take(MAX_FIELDS).map(|f| format!("`{}`", f)).collect();
+        if ty.fields.len() > MAX_FIELDS {
+            fields.push(locale.summary_format("type.more", &[&(ty.fields.len() - MAX_FIELDS)]));
+        }
+        text.push_str(&format!("**{}**: {}\n\n", locale.summary_text("type.fields"), fields.join(", ")));
+    }
+
+    let key_methods = ty.key_methods();
+    if !key_methods.is_empty() {
+        text.push_str(&format!("**{}**:\n\n", locale.summary_text("type.methods")));
+        for (i, method) in key_methods.iter().enumerate() {
+            match method_docs.get(i).map(|d| first_sentence(d)).filter(|d| !d.is_empty()) {
+                Some(doc) => text.push_str(&format!("- `{}`: {}\n", method.name, doc)),
+                None => text.push_str(&format!("- `{}`\n", method.name)),
+            }
+        }
+        if ty.methods.len() > key_methods.len() {
+            text.push_str(&format!("- {}\n", locale.summary_format("type.more", &[&(ty.methods.len() - key_methods.len())])));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// 型名からジェネリクス・パス・参照を除いた名前（`crate::Stack<T>` → `Stack`）
+pub(crate) fn base_name(ty: &str) -> &str {
+    let ty = ty.split(['<', '[', '(']).next().unwrap_or(ty).trim();
+    ty.rsplit(['.', ':', '\\']).next().unwrap_or(ty).trim_start_matches(['&', '*']).trim()
+}
+
+/// 説明の最初の文（最初の段落の最初の行）
+pub(crate) fn first_sentence(doc: &str) -> &str {
+    let line = doc.trim().lines().next().unwrap_or_default().trim();
+    match line.find('。') {
+        Some(end) => &line[..end + '。'.len_utf8()],
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn method(name: &str, parent: Option<&str>, visibility: &str, line: usize) -> MethodInfo {
+        MethodInfo {
+            name: name.to_string(),
+            parent: parent.map(String::from),
+            visibility: visibility.to_string(),
+            language: "rs".to_string(),
+            documentation: String::new(),
+            code_snippet: String::new(),
+            start_line: line,
+            end_line: line + 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_group_types_and_render() {
+        let parsed = analyzer_core::parse_source(
+            "/// Stack of values\npub struct Stack<T> {\n    items: Vec<T>,\n}\n\nimpl<T> Stack<T> {\n    fn grow(&mut self) {}\n    pub fn push(&mut self, v: T) {}\n}\n\nimpl Display for Remote {\n    fn fmt(&self) {}\n}\n\nfn main() {}\n",
+            "rs",
+        )
+        .unwrap();
+        let methods = vec![
+            method("grow", Some("Stack<T>"), "private", 7),
+            method("push", Some("Stack<T>"), "public", 8),
+            method("fmt", Some("Remote"), "private", 12),
+            method("main", None, "private", 15),
+        ];
+        let types = group_types(&parsed.symbols, &parsed.fields, &methods, "rs");
+        let summary: Vec<(&str, &str, Vec<&str>, Vec<&str>)> = types
+            .iter()
+            .map(|t| {
+                let methods = t.methods.iter().map(|m| m.name.as_str()).collect();
+                (t.name.as_str(), t.kind.as_str(), t.fields.iter().map(String::as_str).collect(), methods)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("Stack", "struct", vec!["items"], vec!["grow", "push"]), ("Remote", "impl", vec![], vec!["fmt"])]
+        );
+        assert_eq!(types[0].key_methods().iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), vec!["push", "grow"]);
+
+        let text = render_type(&types[0], &types[0].documentation, &["Pushes a value.\nDetails".to_string()], Locale::En);
+        assert!(text.contains("**Responsibility**: Stack of values"), "{}", text);
+        assert!(text.contains("**Fields**: `items`"), "{}", text);
+        assert!(text.contains("- `push`: Pushes a value.\n- `grow`\n"), "{}", text);
+
+        let text = render_type(&types[1], "", &[], Locale::Ja);
+        assert!(text.contains("**責務**: 0個のフィールドと1個のメソッドを持つ実装です。"), "{}", text);
+        assert!(!text.contains("フィールド**"), "{}", text);
+    }
+}